@@ -12,8 +12,13 @@ use super::subcommand::{Subcommand, StringToSubcommandId, ParsedSubcommandId};
 use crate::util;
 use crate::util::prism::{Prism, PrismExt};
 use crate::errorlist::ErrorList;
+use crate::expr::Expr;
 use crate::expr::prisms;
+use crate::expr::simplifier::Simplifier;
+use crate::expr::var::Var;
 use crate::expr::vector::Vector;
+use crate::expr::vector::packed::PackedTensor;
+use crate::mode::calculation::CalculationMode;
 use crate::state::ApplicationState;
 use crate::stack::base::StackLike;
 use crate::stack::keepable::KeepableStack;
@@ -120,6 +125,37 @@ fn binary_subcommand_argument_schema() -> BinaryArgumentSchema<StringToSubcomman
   )
 }
 
+/// Evaluates `vmap` in packed form, if `input` is a vector of real
+/// numbers and the (unary) subcommand only does arithmetic on its
+/// argument. See [`PackedTensor::map_elementwise`].
+///
+/// The first element is also evaluated in the usual way, and the
+/// packed result is only used if the two agree. This guards against
+/// simplifiers which would have left the arithmetic alone, or which
+/// would have done more with it.
+fn map_packed(
+  subcommand: &Subcommand,
+  input: &Expr,
+  simplifier: &dyn Simplifier,
+  calculation_mode: &CalculationMode,
+) -> Option<Expr> {
+  if calculation_mode.precision().is_some() {
+    // The simplifier converts floats to arbitrary-precision numbers
+    // at each step, which packed arithmetic does not.
+    return None;
+  }
+  let tensor = PackedTensor::parse(input)?;
+  let var = Var::new("$vmap").unwrap(); // unwrap: This is a valid variable name.
+  let body = subcommand.apply_unsimplified(vec![Expr::from(var.clone())]);
+  let result = tensor.map_elementwise(&body, &var, calculation_mode)?;
+
+  let first = tensor.data().first()?;
+  let mut errors = ErrorList::new();
+  let expected = subcommand.call_or_panic(vec![Expr::from(first.clone())], simplifier, calculation_mode.clone(), &mut errors);
+  let agrees = errors.is_empty() && expected == Expr::from(result.data()[0].clone());
+  agrees.then(|| result.into_expr())
+}
+
 impl VectorApplyCommand {
   pub fn new() -> Self {
     Self { _priv: () }
@@ -183,18 +219,14 @@ impl Command for VectorApplyCommand {
     let vec = match prisms::ExprToVector.narrow_type(input_expr) {
       Ok(vec) => vec,
       Err(input_expr) => {
-        if !context.opts.keep_modifier {
-          stack.push(input_expr);
-        }
+        stack.restore_popped([input_expr]);
         anyhow::bail!("Expected vector");
       }
     };
     let expr = match subcommand.try_call(Vec::from(vec), simplifier, calculation_mode, &mut errors) {
       Ok(expr) => expr,
       Err(err) => {
-        if !context.opts.keep_modifier {
-          stack.push(prisms::ExprToVector.widen_type(Vector::from(err.args.clone())));
-        }
+        stack.restore_popped([prisms::ExprToVector.widen_type(Vector::from(err.args.clone()))]);
         return Err(err.into());
       }
     };
@@ -225,12 +257,14 @@ impl Command for VectorMapCommand {
     anyhow::ensure!(subcommand.arity() == 1, "Expected unary subcommand");
 
     let input_expr = stack.pop()?;
+    if let Some(output_expr) = map_packed(&subcommand, &input_expr, simplifier, &calculation_mode) {
+      stack.push(output_expr);
+      return Ok(CommandOutput::from_errors(errors));
+    }
     let vec = match prisms::ExprToVector.narrow_type(input_expr) {
       Ok(vec) => vec,
       Err(input_expr) => {
-        if !context.opts.keep_modifier {
-          stack.push(input_expr);
-        }
+        stack.restore_popped([input_expr]);
         anyhow::bail!("Expected vector");
       }
     };
@@ -268,16 +302,12 @@ impl Command for VectorReduceCommand {
     let vec = match prisms::ExprToVector.narrow_type(input_expr) {
       Ok(vec) => vec,
       Err(input_expr) => {
-        if !context.opts.keep_modifier {
-          stack.push(input_expr);
-        }
+        stack.restore_popped([input_expr]);
         anyhow::bail!("Expected vector");
       }
     };
     if vec.is_empty() {
-      if !context.opts.keep_modifier {
-        stack.push(prisms::ExprToVector.widen_type(vec));
-      }
+      stack.restore_popped([prisms::ExprToVector.widen_type(vec)]);
       anyhow::bail!("Expected non-empty vector");
     }
 
@@ -324,9 +354,7 @@ impl Command for VectorAccumCommand {
     let vec = match prisms::ExprToVector.narrow_type(input_expr) {
       Ok(vec) => vec,
       Err(input_expr) => {
-        if !context.opts.keep_modifier {
-          stack.push(input_expr);
-        }
+        stack.restore_popped([input_expr]);
         anyhow::bail!("Expected vector");
       }
     };
@@ -378,9 +406,7 @@ impl Command for OuterProductCommand {
     let (a_vec, b_vec) = match prism.narrow_type((a_vec, b_vec)) {
       Ok(values) => values,
       Err((a_vec, b_vec)) => {
-        if !context.opts.keep_modifier {
-          stack.push_several([a_vec, b_vec]);
-        }
+        stack.restore_popped([a_vec, b_vec]);
         anyhow::bail!("Expected two vectors");
       }
     };
@@ -423,22 +449,16 @@ impl Command for InnerProductCommand {
     let (a_vec, b_vec) = match prism.narrow_type((a_vec, b_vec)) {
       Ok(values) => values,
       Err((a_vec, b_vec)) => {
-        if !context.opts.keep_modifier {
-          stack.push_several([a_vec, b_vec]);
-        }
+        stack.restore_popped([a_vec, b_vec]);
         anyhow::bail!("Expected two vectors");
       }
     };
     if a_vec.len() != b_vec.len() {
-      if !context.opts.keep_modifier {
-        stack.push_several([prisms::ExprToVector.widen_type(a_vec), prisms::ExprToVector.widen_type(b_vec)]);
-      }
+      stack.restore_popped([prisms::ExprToVector.widen_type(a_vec), prisms::ExprToVector.widen_type(b_vec)]);
       anyhow::bail!("Vector length mismatch");
     }
     if a_vec.is_empty() {
-      if !context.opts.keep_modifier {
-        stack.push_several([prisms::ExprToVector.widen_type(a_vec), prisms::ExprToVector.widen_type(b_vec)]);
-      }
+      stack.restore_popped([prisms::ExprToVector.widen_type(a_vec), prisms::ExprToVector.widen_type(b_vec)]);
       anyhow::bail!("Expected non-empty vectors");
     }
 
@@ -467,7 +487,7 @@ mod tests {
   use crate::stack::test_utils::stack_of;
   use crate::stack::StackError;
   use crate::expr::Expr;
-  use crate::command::test_utils::act_on_stack;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
   use crate::command::options::CommandOptions;
   use crate::command::subcommand::{SubcommandArityError, GetSubcommandError};
   use crate::command::subcommand::SubcommandId;
@@ -484,6 +504,7 @@ mod tests {
     hash_map.insert("nop".to_string(), Box::new(NullaryCommand));
     hash_map.insert("test_func".to_string(), Box::new(UnaryFunctionCommand::named("test_func")));
     hash_map.insert("test_func2".to_string(), Box::new(BinaryFunctionCommand::named("test_func2")));
+    hash_map.insert("double".to_string(), Box::new(UnaryFunctionCommand::new(|arg| Expr::call("*", vec![arg, Expr::from(2)]))));
     hash_map.insert("+".to_string(), Box::new(BinaryFunctionCommand::named("+")));
     hash_map.insert("*".to_string(), Box::new(BinaryFunctionCommand::named("*")));
     CommandDispatchTable::from_hash_map(hash_map)
@@ -709,6 +730,45 @@ mod tests {
     ]));
   }
 
+  #[test]
+  fn test_map_command_on_packed_vector() {
+    let command = VectorMapCommand::new();
+    let arg = subcommand("double");
+    let input_stack = vec![
+      Expr::from(10),
+      Expr::call("vector", (1..=5i64).map(Expr::from).collect()),
+    ];
+    let output_stack = act_on_stack(&command, (setup_sample_dispatch_table, setup_default_simplifier, vec![arg]), input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("vector", vec![
+        Expr::from(2),
+        Expr::from(4),
+        Expr::from(6),
+        Expr::from(8),
+        Expr::from(10),
+      ]),
+    ]));
+  }
+
+  #[test]
+  fn test_map_command_on_packed_vector_without_simplifier() {
+    // The identity simplifier leaves the arithmetic alone, so the
+    // packed form must not be used here.
+    let command = VectorMapCommand::new();
+    let arg = subcommand("double");
+    let input_stack = vec![
+      Expr::call("vector", vec![Expr::from(1), Expr::from(2)]),
+    ];
+    let output_stack = act_on_stack(&command, (setup_sample_dispatch_table, vec![arg]), input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::call("vector", vec![
+        Expr::call("*", vec![Expr::from(1), Expr::from(2)]),
+        Expr::call("*", vec![Expr::from(2), Expr::from(2)]),
+      ]),
+    ]));
+  }
+
   #[test]
   fn test_map_command_on_non_vector() {
     let command = VectorMapCommand::new();
@@ -65,3 +65,259 @@ impl Command for FindRootCommand {
     None
   }
 }
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops two values `expr` and `interval` off the stack and pushes
+/// `global_min(expr, v, interval)`.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct GlobalMinCommand {
+  _priv: (),
+}
+
+impl GlobalMinCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for GlobalMinCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&GlobalMinCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [expr, interval] = stack.pop_several(2)?.try_into().unwrap();
+    let expr = Expr::call("global_min", vec![expr, Expr::Atom(Atom::Var(variable_name)), interval]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops an equation off the stack and pushes `isolate(eq, v)`, which
+/// rearranges the equation to solve for `v`.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct IsolateCommand {
+  _priv: (),
+}
+
+impl IsolateCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for IsolateCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&IsolateCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let equation = stack.pop()?;
+    let expr = Expr::call("isolate", vec![equation, Expr::Atom(Atom::Var(variable_name))]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops an inequality off the stack and pushes
+/// `solve_inequality(ineq, v)`, which is the set of real values of
+/// `v` satisfying the inequality.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct SolveInequalityCommand {
+  _priv: (),
+}
+
+impl SolveInequalityCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for SolveInequalityCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&SolveInequalityCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let inequality = stack.pop()?;
+    let expr = Expr::call("solve_inequality", vec![inequality, Expr::Atom(Atom::Var(variable_name))]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops an expression off the stack and pushes `domain(expr, v)`,
+/// which is the set of real values of `v` at which the expression is
+/// defined.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct DomainCommand {
+  _priv: (),
+}
+
+impl DomainCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for DomainCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&DomainCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let expr = stack.pop()?;
+    let expr = Expr::call("domain", vec![expr, Expr::Atom(Atom::Var(variable_name))]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops three values `expr`, `lower`, and `upper` off the stack and
+/// pushes `f(expr, v, lower, upper)`, where `f` is `sum` or `prod`.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug)]
+pub struct SeriesCommand {
+  function_name: &'static str,
+}
+
+impl SeriesCommand {
+  pub fn sum() -> Self {
+    Self { function_name: "sum" }
+  }
+
+  pub fn product() -> Self {
+    Self { function_name: "prod" }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for SeriesCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&SeriesCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [expr, lower, upper] = stack.pop_several(3)?.try_into().unwrap();
+    let expr = Expr::call(self.function_name, vec![expr, Expr::Atom(Atom::Var(variable_name)), lower, upper]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
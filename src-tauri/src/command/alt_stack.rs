@@ -0,0 +1,211 @@
+
+//! Commands for moving values between the main stack and the
+//! alternate stack.
+
+use super::arguments::{NullaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+use crate::stack::keepable::KeepableStack;
+use crate::stack::base::StackLike;
+
+/// This command takes no arguments. Moves values from the top of the
+/// main stack onto the alternate stack, preserving their order.
+///
+/// With a numerical argument N, moves N values. Otherwise, moves the
+/// top value. Fails if the numerical argument is not positive. With
+/// the "keep" modifier, the values are copied and left in place on
+/// the main stack.
+#[derive(Debug, Clone)]
+pub struct ToAltStackCommand;
+
+/// This command takes no arguments. Moves values from the top of the
+/// alternate stack back onto the main stack, preserving their order.
+///
+/// The numerical argument and the "keep" modifier behave as in
+/// [`ToAltStackCommand`], but with the roles of the two stacks
+/// reversed.
+#[derive(Debug, Clone)]
+pub struct FromAltStackCommand;
+
+/// This command takes no arguments. Exchanges the contents of the
+/// main stack and the alternate stack. Ignores the numerical argument
+/// and the "keep" modifier.
+///
+/// Pinned entries on the main stack are never consumed, so they stay
+/// on the main stack (beneath the values from the alternate stack),
+/// and copies of them are moved to the alternate stack.
+#[derive(Debug, Clone)]
+pub struct SwapStacksCommand;
+
+fn positive_argument(context: &CommandContext) -> anyhow::Result<usize> {
+  let arg = context.opts.argument.unwrap_or(1);
+  if arg <= 0 {
+    anyhow::bail!("Expected positive numerical argument");
+  }
+  Ok(arg as usize)
+}
+
+impl Command for ToAltStackCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let count = positive_argument(context)?;
+
+    state.undo_stack_mut().push_cut();
+    let values = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier).pop_several(count)?;
+    state.alt_stack_mut().push_several(values);
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for FromAltStackCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let count = positive_argument(context)?;
+
+    state.undo_stack_mut().push_cut();
+    let values = KeepableStack::new(state.alt_stack_mut(), context.opts.keep_modifier).pop_several(count)?;
+    state.main_stack_mut().push_several(values);
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SwapStacksCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+
+    state.undo_stack_mut().push_cut();
+    let main_values = state.main_stack_mut().pop_all();
+    let alt_values = state.alt_stack_mut().pop_all();
+    state.main_stack_mut().push_several(alt_values);
+    state.alt_stack_mut().push_several(main_values);
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::pins::PinStackElemCommand;
+  use crate::stack::{Stack, StackError};
+  use crate::stack::test_utils::stack_of;
+  use crate::state::test_utils::state_for_stack;
+
+  fn run(state: &mut ApplicationState, command: &dyn Command, opts: CommandOptions) -> anyhow::Result<CommandOutput> {
+    let context = CommandContext { opts, ..CommandContext::default() };
+    command.run_command(state, vec![], &context)
+  }
+
+  #[test]
+  fn test_to_alt_stack() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    run(&mut state, &ToAltStackCommand, CommandOptions::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![30]));
+    run(&mut state, &ToAltStackCommand, CommandOptions::numerical(2)).unwrap();
+    assert_eq!(state.main_stack(), &Stack::new());
+    assert_eq!(state.alt_stack(), &stack_of(vec![30, 10, 20]));
+  }
+
+  #[test]
+  fn test_to_alt_stack_with_keep_modifier() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    run(&mut state, &ToAltStackCommand, CommandOptions::numerical(2).with_keep_modifier()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![20, 30]));
+  }
+
+  #[test]
+  fn test_to_alt_stack_with_bad_argument() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    run(&mut state, &ToAltStackCommand, CommandOptions::numerical(0)).unwrap_err();
+    let err = run(&mut state, &ToAltStackCommand, CommandOptions::numerical(4)).unwrap_err();
+    assert_eq!(
+      err.downcast::<StackError>().unwrap(),
+      StackError::NotEnoughElements { expected: 4, actual: 3 },
+    );
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+    assert_eq!(state.alt_stack(), &Stack::new());
+  }
+
+  #[test]
+  fn test_from_alt_stack() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    run(&mut state, &ToAltStackCommand, CommandOptions::numerical(3)).unwrap();
+    run(&mut state, &FromAltStackCommand, CommandOptions::numerical(2)).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![20, 30]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![10]));
+    let err = run(&mut state, &FromAltStackCommand, CommandOptions::numerical(2)).unwrap_err();
+    assert_eq!(
+      err.downcast::<StackError>().unwrap(),
+      StackError::NotEnoughElements { expected: 2, actual: 1 },
+    );
+  }
+
+  #[test]
+  fn test_alt_stack_is_undoable() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    run(&mut state, &ToAltStackCommand, CommandOptions::numerical(2)).unwrap();
+    run(&mut state, &FromAltStackCommand, CommandOptions::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 30]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![20]));
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![20, 30]));
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+    assert_eq!(state.alt_stack(), &Stack::new());
+    state.redo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![20, 30]));
+  }
+
+  #[test]
+  fn test_swap_stacks() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    run(&mut state, &ToAltStackCommand, CommandOptions::default()).unwrap();
+    run(&mut state, &SwapStacksCommand, CommandOptions::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![30]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![10, 20]));
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![30]));
+  }
+
+  #[test]
+  fn test_swap_stacks_leaves_pinned_entries() {
+    let mut state = state_for_stack(vec![10, 20]);
+    run(&mut state, &PinStackElemCommand::pin(), CommandOptions::numerical(2)).unwrap();
+    run(&mut state, &SwapStacksCommand, CommandOptions::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    assert_eq!(state.alt_stack(), &stack_of(vec![10, 20]));
+  }
+}
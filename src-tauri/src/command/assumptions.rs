@@ -0,0 +1,172 @@
+
+//! Commands for declaring properties of variables.
+
+use super::arguments::{UnaryArgumentSchema, BinaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::expr::assumptions::{Assumption, StringToAssumption};
+use crate::expr::prisms::StringToVar;
+use crate::expr::var::Var;
+use crate::state::ApplicationState;
+use crate::state::undo::UpdateAssumptionsChange;
+
+use std::collections::BTreeSet;
+
+/// This command takes two arguments: a variable name and the name of
+/// an [`Assumption`]. Declares that the variable has the given
+/// property, in addition to any properties already declared for it.
+///
+/// The simplifier consults these declarations to perform
+/// simplifications that are only valid under them, such as rewriting
+/// `sqrt(x^2)` to `x` when `x` is nonnegative. Does not touch the
+/// stack.
+#[derive(Debug, Default)]
+pub struct DeclareCommand {
+  _priv: (),
+}
+
+/// This command takes one argument: a variable name. Removes every
+/// property declared for that variable. Does not touch the stack.
+#[derive(Debug, Default)]
+pub struct UndeclareCommand {
+  _priv: (),
+}
+
+impl DeclareCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> BinaryArgumentSchema<StringToVar, Var, StringToAssumption, Assumption> {
+    BinaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+      "assumption (real, integer, nonnegative, positive, or nonzero)".to_owned(),
+      StringToAssumption,
+    )
+  }
+}
+
+impl UndeclareCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for DeclareCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (var, assumption) = validate_schema(&DeclareCommand::argument_schema(), args)?;
+
+    state.undo_stack_mut().push_cut();
+    let mut assumptions = state.assumptions().get(&var);
+    assumptions.insert(assumption);
+    let old_assumptions = state.assumptions_mut().set(var.clone(), assumptions.clone());
+    state.undo_stack_mut().push_change(UpdateAssumptionsChange::new(var, old_assumptions, assumptions));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for UndeclareCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let var = validate_schema(&UndeclareCommand::argument_schema(), args)?;
+
+    state.undo_stack_mut().push_cut();
+    let old_assumptions = state.assumptions_mut().set(var.clone(), BTreeSet::new());
+    state.undo_stack_mut().push_change(UpdateAssumptionsChange::new(var, old_assumptions, BTreeSet::new()));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::errorlist::ErrorList;
+  use crate::expr::Expr;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::default_simplifier_with_assumptions;
+
+  fn run(state: &mut ApplicationState, command: &dyn Command, args: &[&str]) -> anyhow::Result<CommandOutput> {
+    let args = args.iter().map(|arg| (*arg).to_owned()).collect();
+    command.run_command(state, args, &CommandContext::default())
+  }
+
+  fn x() -> Var {
+    Var::new("x").unwrap()
+  }
+
+  #[test]
+  fn test_declare() {
+    let mut state = ApplicationState::new();
+    run(&mut state, &DeclareCommand::new(), &["x", "integer"]).unwrap();
+    run(&mut state, &DeclareCommand::new(), &["x", "positive"]).unwrap();
+    assert_eq!(state.assumptions().get(&x()), BTreeSet::from([Assumption::Integer, Assumption::Positive]));
+    state.undo().unwrap();
+    assert_eq!(state.assumptions().get(&x()), BTreeSet::from([Assumption::Integer]));
+    state.undo().unwrap();
+    assert!(state.assumptions().is_empty());
+  }
+
+  #[test]
+  fn test_undeclare() {
+    let mut state = ApplicationState::new();
+    run(&mut state, &DeclareCommand::new(), &["x", "real"]).unwrap();
+    run(&mut state, &UndeclareCommand::new(), &["x"]).unwrap();
+    assert!(state.assumptions().is_empty());
+    state.undo().unwrap();
+    assert_eq!(state.assumptions().get(&x()), BTreeSet::from([Assumption::Real]));
+  }
+
+  #[test]
+  fn test_declare_invalid() {
+    let mut state = ApplicationState::new();
+    run(&mut state, &DeclareCommand::new(), &["x", "complex"]).unwrap_err();
+    run(&mut state, &DeclareCommand::new(), &["3", "real"]).unwrap_err();
+    run(&mut state, &DeclareCommand::new(), &["x"]).unwrap_err();
+    assert!(state.assumptions().is_empty());
+  }
+
+  #[test]
+  fn test_simplifier_uses_declarations() {
+    let mut state = ApplicationState::new();
+    run(&mut state, &DeclareCommand::new(), &["x", "nonnegative"]).unwrap();
+
+    let function_table = build_function_table();
+    let context = CommandContext {
+      simplifier: default_simplifier_with_assumptions(&function_table, state.assumptions().clone()),
+      ..CommandContext::default()
+    };
+    let mut errors = ErrorList::new();
+    let square = Expr::call("^", vec![Expr::from(x()), Expr::from(2)]);
+    let expr = context.simplify_expr(Expr::call("sqrt", vec![square]), state.calculation_mode().clone(), &mut errors);
+    assert!(errors.is_empty());
+    assert_eq!(expr, Expr::from(x()));
+  }
+}
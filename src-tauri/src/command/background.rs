@@ -0,0 +1,60 @@
+
+//! Marker for commands which should run on the background job runner.
+//!
+//! Wrapping a command in [`RunInBackground`] does not change what the
+//! command does. It only overrides [`Command::runs_in_background`],
+//! so that the frontend's top-level invocations of the command are
+//! handed to [`crate::state::jobs`] instead of running while the
+//! application state is locked.
+
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+
+/// A command which runs on a worker thread when invoked by the user.
+#[derive(Debug, Clone)]
+pub struct RunInBackground<C> {
+  inner: C,
+}
+
+impl<C: Command> RunInBackground<C> {
+  pub fn new(inner: C) -> Self {
+    Self { inner }
+  }
+
+  pub fn inner(&self) -> &C {
+    &self.inner
+  }
+}
+
+impl<C: Command> Command for RunInBackground<C> {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    self.inner.run_command(state, args, ctx)
+  }
+
+  fn as_subcommand(&self, opts: &CommandOptions) -> Option<Subcommand> {
+    self.inner.as_subcommand(opts)
+  }
+
+  fn runs_in_background(&self) -> bool {
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::nullary::NullaryCommand;
+
+  #[test]
+  fn test_runs_in_background() {
+    assert!(!NullaryCommand.runs_in_background());
+    assert!(RunInBackground::new(NullaryCommand).runs_in_background());
+  }
+}
@@ -4,7 +4,9 @@ use crate::expr::Expr;
 use crate::expr::number::Number;
 use crate::expr::simplifier::{Simplifier, SimplifierContext};
 use crate::expr::simplifier::identity::IdentitySimplifier;
+use crate::expr::function::table::FunctionTable;
 use crate::expr::simplifier::error::SimplifierError;
+use crate::expr::units::unit_aware_simplifier;
 use crate::errorlist::ErrorList;
 use crate::units::parsing::{UnitParser, NullaryUnitParser};
 use crate::mode::calculation::CalculationMode;
@@ -15,6 +17,8 @@ use super::dispatch::CommandDispatchTable;
 
 use once_cell::sync::Lazy;
 
+use std::time::Duration;
+
 pub trait Command {
   /// Runs the command. If a fatal error prevents the command from
   /// executing at all, then an `Err` should be returned. If the
@@ -56,6 +60,17 @@ pub trait Command {
   /// corresponding subcommand will generally ignore the numerical
   /// argument.
   fn as_subcommand(&self, opts: &CommandOptions) -> Option<Subcommand>;
+
+  /// Whether the command is expensive enough that, when invoked
+  /// directly by the user, it should run on a worker thread rather
+  /// than while holding the application state. See
+  /// [`crate::state::jobs`]. The default is `false`.
+  ///
+  /// This only affects top-level invocations. A background command
+  /// run as part of a macro or program still runs in the foreground.
+  fn runs_in_background(&self) -> bool {
+    false
+  }
 }
 
 pub struct CommandContext<'a, 'b, 'c> {
@@ -63,6 +78,13 @@ pub struct CommandContext<'a, 'b, 'c> {
   pub simplifier: Box<dyn Simplifier + 'a>,
   pub units_parser: &'b dyn UnitParser<Number>,
   pub dispatch_table: &'c CommandDispatchTable,
+  /// The built-in functions known to the simplifier.
+  pub function_table: &'c FunctionTable,
+  /// The number of programs (including macros) which are running
+  /// this command as one of their steps, directly or indirectly. Zero
+  /// for a command invoked by the user. See
+  /// [`MAX_NESTING_DEPTH`](super::program::MAX_NESTING_DEPTH).
+  pub nesting_depth: usize,
 }
 
 /// The result of performing a command, including any non-fatal errors
@@ -71,6 +93,8 @@ pub struct CommandContext<'a, 'b, 'c> {
 pub struct CommandOutput {
   errors: Vec<String>,
   force_scroll_down: bool,
+  confirmation_request: Option<String>,
+  timer_request: Option<Duration>,
 }
 
 impl<'a, 'b, 'c> CommandContext<'a, 'b, 'c> {
@@ -91,8 +115,17 @@ impl<'a, 'b, 'c> CommandContext<'a, 'b, 'c> {
     custom_simplifier_fn: impl FnOnce(&'s (dyn Simplifier + 'a)) -> CowDyn<'s, dyn Simplifier + 'a>,
   ) -> Expr {
     let simplifier = custom_simplifier_fn(self.simplifier.as_ref());
+    // In units mode, every simplification also simplifies units.
+    let unit_aware;
+    let simplifier: &dyn Simplifier = if calculation_mode.has_units_flag() {
+      let prefer_derived_units = calculation_mode.has_derived_units_flag();
+      unit_aware = unit_aware_simplifier(simplifier.as_ref(), self.units_parser, prefer_derived_units);
+      unit_aware.as_ref()
+    } else {
+      simplifier.as_ref()
+    };
     let mut simplifier_context = SimplifierContext {
-      base_simplifier: simplifier.as_ref(),
+      base_simplifier: simplifier,
       errors,
       calculation_mode,
     };
@@ -105,6 +138,20 @@ impl CommandOutput {
     CommandOutput {
       errors: vec![],
       force_scroll_down: true,
+      confirmation_request: None,
+      timer_request: None,
+    }
+  }
+
+  /// An output indicating that the command did *not* run, because it
+  /// must first be confirmed by the user. The message describes what
+  /// the command would do.
+  pub fn needs_confirmation(message: impl Into<String>) -> CommandOutput {
+    CommandOutput {
+      errors: vec![],
+      force_scroll_down: false,
+      confirmation_request: Some(message.into()),
+      timer_request: None,
     }
   }
 
@@ -114,6 +161,8 @@ impl CommandOutput {
     CommandOutput {
       errors: errors.into_iter().map(|e| e.to_string()).collect(),
       force_scroll_down: true,
+      confirmation_request: None,
+      timer_request: None,
     }
   }
 
@@ -137,6 +186,25 @@ impl CommandOutput {
   pub fn force_scroll_down(&self) -> bool {
     self.force_scroll_down
   }
+
+  /// If the command is awaiting confirmation, returns the message to
+  /// show the user.
+  pub fn confirmation_request(&self) -> Option<&str> {
+    self.confirmation_request.as_deref()
+  }
+
+  /// Asks for a [`TimerExpiredPayload`] event to be sent to the
+  /// frontend once the given duration has passed.
+  ///
+  /// [`TimerExpiredPayload`]: crate::state::events::TimerExpiredPayload
+  pub fn with_timer(mut self, duration: Duration) -> Self {
+    self.timer_request = Some(duration);
+    self
+  }
+
+  pub fn timer_request(&self) -> Option<Duration> {
+    self.timer_request
+  }
 }
 
 /// An appropriate default context, with no special command options
@@ -146,12 +214,15 @@ impl CommandOutput {
 impl Default for CommandContext<'static, 'static, 'static> {
   fn default() -> Self {
     static EMPTY_DISPATCH_TABLE: Lazy<CommandDispatchTable> = Lazy::new(CommandDispatchTable::default);
+    static EMPTY_FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(FunctionTable::new);
 
     CommandContext {
       opts: CommandOptions::default(),
       simplifier: Box::new(IdentitySimplifier),
       units_parser: &NullaryUnitParser,
       dispatch_table: &EMPTY_DISPATCH_TABLE,
+      function_table: &EMPTY_FUNCTION_TABLE,
+      nesting_depth: 0,
     }
   }
 }
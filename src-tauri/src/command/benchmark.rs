@@ -0,0 +1,302 @@
+
+//! Commands for measuring how long computations take.
+
+use super::arguments::{NullaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::program::{Program, MAX_ITERATIONS};
+use super::subcommand::Subcommand;
+use crate::errorlist::ErrorList;
+use crate::expr::Expr;
+use crate::expr::vector::Vector;
+use crate::state::ApplicationState;
+use crate::stack::base::StackLike;
+use crate::stack::keepable::KeepableStack;
+
+use std::time::{Duration, Instant};
+
+/// The number of runs performed by [`BenchmarkCommand`] if no
+/// numerical argument is given.
+pub const DEFAULT_BENCHMARK_RUNS: usize = 10;
+
+/// Pops a candidate off the stack and runs it repeatedly, pushing a
+/// vector `[min, mean, max]` of the wall-clock times (in seconds) of
+/// the runs.
+///
+/// A candidate which is a vector is treated as a program (see
+/// [`super::program`]). Each run of a program starts from a fresh
+/// copy of the current state, so the program's effects on the stack
+/// and variables are discarded. Any other candidate is treated as an
+/// expression, and a run consists of simplifying the expression.
+///
+/// The numerical argument determines the number of runs, defaulting
+/// to [`DEFAULT_BENCHMARK_RUNS`]. With the hyperbolic modifier, two
+/// candidates are popped and each is benchmarked separately, pushing
+/// two result vectors in the same order as the candidates.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct BenchmarkCommand {
+  _priv: (),
+}
+
+/// Summary statistics for a set of timed runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+  pub min: Duration,
+  pub mean: Duration,
+  pub max: Duration,
+}
+
+enum Candidate {
+  Program(Program),
+  Expr(Expr),
+}
+
+impl BenchmarkCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl Timings {
+  /// Computes timing statistics from a nonempty list of durations.
+  /// Panics if `durations` is empty.
+  pub fn from_durations(durations: &[Duration]) -> Self {
+    assert!(!durations.is_empty(), "Timings::from_durations requires at least one duration");
+    let min = durations.iter().copied().min().unwrap();
+    let max = durations.iter().copied().max().unwrap();
+    let total: Duration = durations.iter().sum();
+    let mean = total / durations.len() as u32;
+    Timings { min, mean, max }
+  }
+
+  pub fn into_expr(self) -> Expr {
+    let elems: Vector = [self.min, self.mean, self.max].into_iter()
+      .map(|d| Expr::from(d.as_secs_f64()))
+      .collect();
+    elems.into_expr()
+  }
+}
+
+impl Candidate {
+  fn new(expr: Expr) -> Self {
+    match Program::parse(expr) {
+      Ok(program) => Candidate::Program(program),
+      Err((expr, _)) => Candidate::Expr(expr),
+    }
+  }
+
+  fn time_once(
+    &self,
+    state: &ApplicationState,
+    context: &CommandContext,
+    errors: &mut Vec<String>,
+  ) -> anyhow::Result<Duration> {
+    match self {
+      Candidate::Program(program) => {
+        let mut scratch_state = state.detached_copy();
+        let start = Instant::now();
+        let program_errors = program.execute(&mut scratch_state, context)?;
+        let elapsed = start.elapsed();
+        errors.extend(program_errors);
+        Ok(elapsed)
+      }
+      Candidate::Expr(expr) => {
+        let calculation_mode = state.calculation_mode().clone();
+        let mut simplifier_errors = ErrorList::new();
+        let start = Instant::now();
+        context.simplify_expr(expr.clone(), calculation_mode, &mut simplifier_errors);
+        let elapsed = start.elapsed();
+        errors.extend(simplifier_errors.into_iter().map(|e| e.to_string()));
+        Ok(elapsed)
+      }
+    }
+  }
+
+  fn benchmark(
+    &self,
+    state: &ApplicationState,
+    context: &CommandContext,
+    runs: usize,
+    errors: &mut Vec<String>,
+  ) -> anyhow::Result<Timings> {
+    let mut durations = Vec::with_capacity(runs);
+    for run in 0..runs {
+      // Only report errors from the first run, since subsequent runs
+      // will produce the same ones.
+      let mut run_errors = Vec::new();
+      durations.push(self.time_once(state, context, &mut run_errors)?);
+      if run == 0 {
+        errors.extend(run_errors);
+      }
+    }
+    Ok(Timings::from_durations(&durations))
+  }
+}
+
+impl Command for BenchmarkCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let runs = context.opts.argument.unwrap_or(DEFAULT_BENCHMARK_RUNS as i64);
+    anyhow::ensure!(
+      runs > 0 && runs as usize <= MAX_ITERATIONS,
+      "Expected number of runs between 1 and {MAX_ITERATIONS}, got {runs}",
+    );
+    let candidate_count = if context.opts.hyperbolic_modifier { 2 } else { 1 };
+
+    state.undo_stack_mut().push_cut();
+    let candidates = {
+      let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+      stack.pop_several(candidate_count)?
+    };
+
+    let mut errors = Vec::new();
+    let mut results = Vec::with_capacity(candidate_count);
+    for expr in &candidates {
+      let candidate = Candidate::new(expr.clone());
+      match candidate.benchmark(state, context, runs as usize, &mut errors) {
+        Ok(timings) => results.push(timings.into_expr()),
+        Err(err) => {
+          if !context.opts.keep_modifier {
+            state.main_stack_mut().push_several(candidates);
+          }
+          return Err(err);
+        }
+      }
+    }
+
+    state.main_stack_mut().push_several(results);
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
+  use crate::command::program::test_utils::setup_default_dispatch_table;
+  use crate::expr::prisms::{ExprToVector, expr_to_number};
+  use crate::util::prism::Prism;
+  use crate::stack::{Stack, StackError};
+
+  fn assert_is_timing_vector(expr: &Expr) {
+    let vector = ExprToVector.narrow_type(expr.clone()).unwrap();
+    assert_eq!(vector.len(), 3);
+    let values: Vec<f64> = vector.into_iter()
+      .map(|e| expr_to_number().narrow_type(e).unwrap().to_f64().unwrap())
+      .collect();
+    assert!(values[0] <= values[1] && values[1] <= values[2]);
+  }
+
+  fn into_vec(stack: Stack<Expr>) -> Vec<Expr> {
+    stack.into_iter().collect()
+  }
+
+  #[test]
+  fn test_timings_from_durations() {
+    let timings = Timings::from_durations(&[
+      Duration::from_millis(30),
+      Duration::from_millis(10),
+      Duration::from_millis(20),
+    ]);
+    assert_eq!(timings, Timings {
+      min: Duration::from_millis(10),
+      mean: Duration::from_millis(20),
+      max: Duration::from_millis(30),
+    });
+  }
+
+  #[test]
+  fn test_benchmark_expr() {
+    let output_stack = act_on_stack(
+      &BenchmarkCommand::new(),
+      (setup_default_simplifier, CommandOptions::numerical(3)),
+      vec![Expr::from(9), Expr::call("+", vec![Expr::from(1), Expr::from(2)])],
+    ).unwrap();
+    let output = into_vec(output_stack);
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0], Expr::from(9));
+    assert_is_timing_vector(&output[1]);
+  }
+
+  #[test]
+  fn test_benchmark_program_discards_effects() {
+    let program = Expr::call("vector", vec![Expr::from(2), Expr::from("*".to_owned())]);
+    let output_stack = act_on_stack(
+      &BenchmarkCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(9), program],
+    ).unwrap();
+    let output = into_vec(output_stack);
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0], Expr::from(9));
+    assert_is_timing_vector(&output[1]);
+  }
+
+  #[test]
+  fn test_benchmark_comparison() {
+    let output_stack = act_on_stack(
+      &BenchmarkCommand::new(),
+      (setup_default_simplifier, CommandOptions::numerical(2).with_hyperbolic_modifier()),
+      vec![Expr::from(1), Expr::from(2)],
+    ).unwrap();
+    let output = into_vec(output_stack);
+    assert_eq!(output.len(), 2);
+    assert_is_timing_vector(&output[0]);
+    assert_is_timing_vector(&output[1]);
+  }
+
+  #[test]
+  fn test_benchmark_with_keep_arg() {
+    let output_stack = act_on_stack(
+      &BenchmarkCommand::new(),
+      (setup_default_simplifier, CommandOptions::numerical(2).with_keep_modifier()),
+      vec![Expr::from(1)],
+    ).unwrap();
+    let output = into_vec(output_stack);
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0], Expr::from(1));
+    assert_is_timing_vector(&output[1]);
+  }
+
+  #[test]
+  fn test_benchmark_failing_program() {
+    let program = Expr::call("vector", vec![Expr::from("pop".to_owned())]);
+    let err = act_on_stack(
+      &BenchmarkCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![program],
+    ).unwrap_err();
+    assert!(err.to_string().contains("Program step 0"));
+  }
+
+  #[test]
+  fn test_benchmark_invalid_run_count() {
+    let err = act_on_stack(
+      &BenchmarkCommand::new(),
+      CommandOptions::numerical(0),
+      vec![Expr::from(1)],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Expected number of runs between 1 and 10000, got 0");
+  }
+
+  #[test]
+  fn test_benchmark_on_empty_stack() {
+    let err = act_on_stack(
+      &BenchmarkCommand::new(),
+      (),
+      Vec::<Expr>::new(),
+    ).unwrap_err();
+    assert!(err.is::<StackError>());
+  }
+}
@@ -0,0 +1,147 @@
+
+//! Commands for saving and recalling numbered bookmarks.
+
+use super::arguments::{UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::expr::prisms::{StringToUsize, ParsedUsize};
+use crate::state::ApplicationState;
+use crate::state::undo::UpdateBookmarkChange;
+use crate::stack::base::{StackLike, RandomAccessStackLike};
+
+/// This command takes one argument: a nonnegative bookmark number.
+/// Associates the top stack value with that bookmark, replacing any
+/// value previously stored there. The stack itself is not modified.
+///
+/// Fails if the stack is empty. Does not use the numerical argument
+/// or the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct BookmarkCommand {
+  _priv: (),
+}
+
+/// This command takes one argument: a nonnegative bookmark number.
+/// Pushes the value stored in that bookmark onto the stack. Fails if
+/// the bookmark has not been set.
+#[derive(Debug, Default)]
+pub struct RecallBookmarkCommand {
+  _priv: (),
+}
+
+fn argument_schema() -> UnaryArgumentSchema<StringToUsize, ParsedUsize> {
+  UnaryArgumentSchema::new(
+    "nonnegative integer".to_owned(),
+    StringToUsize,
+  )
+}
+
+impl BookmarkCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl RecallBookmarkCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Command for BookmarkCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let index = usize::from(validate_schema(&argument_schema(), args)?);
+    state.undo_stack_mut().push_cut();
+
+    let expr = state.main_stack().get(0)?.clone();
+    let old_value = state.bookmarks_mut().insert(index, expr.clone());
+    state.undo_stack_mut().push_change(UpdateBookmarkChange::new(index, old_value, Some(expr)));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for RecallBookmarkCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let index = usize::from(validate_schema(&argument_schema(), args)?);
+    state.undo_stack_mut().push_cut();
+
+    let expr = state.bookmarks().try_get(index)?.clone();
+    state.main_stack_mut().push(expr);
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::test_utils::act_on_stack;
+  use crate::expr::Expr;
+  use crate::stack::test_utils::stack_of;
+  use crate::stack::StackError;
+  use crate::state::test_utils::state_for_stack;
+  use crate::state::bookmarks::UnknownBookmarkError;
+
+  #[test]
+  fn test_bookmark_then_recall() {
+    let mut state = state_for_stack(vec![10, 20]);
+    let context = CommandContext::default();
+    BookmarkCommand::new().run_command(&mut state, vec!["3".to_owned()], &context).unwrap();
+    assert_eq!(state.bookmarks().get(3), Some(&Expr::from(20)));
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20]));
+
+    state.main_stack_mut().pop().unwrap();
+    state.main_stack_mut().pop().unwrap();
+    RecallBookmarkCommand::new().run_command(&mut state, vec!["3".to_owned()], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![20]));
+  }
+
+  #[test]
+  fn test_bookmark_is_undoable() {
+    let mut state = state_for_stack(vec![10]);
+    let context = CommandContext::default();
+    BookmarkCommand::new().run_command(&mut state, vec!["0".to_owned()], &context).unwrap();
+    state.undo().unwrap();
+    assert!(state.bookmarks().is_empty());
+    state.redo().unwrap();
+    assert_eq!(state.bookmarks().get(0), Some(&Expr::from(10)));
+  }
+
+  #[test]
+  fn test_bookmark_on_empty_stack() {
+    let err = act_on_stack(&BookmarkCommand::new(), vec!["1"], Vec::<Expr>::new()).unwrap_err();
+    let err = err.downcast::<StackError>().unwrap();
+    assert_eq!(err, StackError::NotEnoughElements { expected: 1, actual: 0 });
+  }
+
+  #[test]
+  fn test_recall_unknown_bookmark() {
+    let err = act_on_stack(&RecallBookmarkCommand::new(), vec!["7"], vec![10]).unwrap_err();
+    let err = err.downcast::<UnknownBookmarkError>().unwrap();
+    assert_eq!(err, UnknownBookmarkError { index: 7 });
+  }
+
+  #[test]
+  fn test_bookmark_invalid_argument() {
+    act_on_stack(&BookmarkCommand::new(), vec!["-1"], vec![10]).unwrap_err();
+  }
+}
@@ -67,3 +67,292 @@ impl Command for DerivativeCommand {
     None
   }
 }
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops two values `expr` and `point` off the stack and pushes
+/// `num_deriv(expr, v, point)`, the numerical derivative of `expr`
+/// at `point` together with an error estimate.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct NumericalDerivativeCommand {
+  _priv: (),
+}
+
+impl NumericalDerivativeCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for NumericalDerivativeCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&NumericalDerivativeCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [expr, point] = stack.pop_several(2)?.try_into().unwrap();
+    let expr = Expr::call("num_deriv", vec![expr, Expr::Atom(Atom::Var(variable_name)), point]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops two values `expr` and `range` off the stack and pushes
+/// `num_integrate(expr, v, range)`, the numerical integral of `expr`
+/// over the interval `range` together with an error estimate.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct NumericalIntegralCommand {
+  _priv: (),
+}
+
+impl NumericalIntegralCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for NumericalIntegralCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&NumericalIntegralCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [expr, range] = stack.pop_several(2)?.try_into().unwrap();
+    let expr = Expr::call("num_integrate", vec![expr, Expr::Atom(Atom::Var(variable_name)), range]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops two values `expr` and `point` off the stack and pushes
+/// `limit(expr, v, point)`, the limit of `expr` as `v` approaches
+/// `point`. The point may be one of the infinite constants.
+///
+/// The one-sided variants push `limit_left(expr, v, point)` or
+/// `limit_right(expr, v, point)` instead.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug)]
+pub struct LimitCommand {
+  function_name: &'static str,
+}
+
+impl LimitCommand {
+  pub fn new() -> Self {
+    Self { function_name: "limit" }
+  }
+
+  pub fn left() -> Self {
+    Self { function_name: "limit_left" }
+  }
+
+  pub fn right() -> Self {
+    Self { function_name: "limit_right" }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Default for LimitCommand {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Command for LimitCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&LimitCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [expr, point] = stack.pop_several(2)?.try_into().unwrap();
+    let expr = Expr::call(self.function_name, vec![expr, Expr::Atom(Atom::Var(variable_name)), point]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops one value `expr` off the stack and pushes `integrate(expr,
+/// v)`, which will attempt to calculate an antiderivative of the
+/// expression `expr` in terms of the variable `v`.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct IntegralCommand {
+  _priv: (),
+}
+
+impl IntegralCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for IntegralCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&IntegralCommand::argument_schema(), args)?;
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let expr = stack.pop()?;
+    let expr = Expr::call("integrate", vec![expr, Expr::Atom(Atom::Var(variable_name))]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. When executed,
+/// pops two values `expr` and `range` off the stack and pushes
+/// `f(expr, v, range, n)`, where `f` is a Monte Carlo function (such
+/// as `mc_integrate` or `mc_estimate`) and `n` is the numerical
+/// argument. If no numerical argument is given, `n` is omitted and
+/// the function's default sample count is used.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug)]
+pub struct MonteCarloCommand {
+  function_name: &'static str,
+}
+
+impl MonteCarloCommand {
+  /// Monte Carlo integration over an interval.
+  pub fn integrate() -> Self {
+    Self { function_name: "mc_integrate" }
+  }
+
+  /// Monte Carlo estimation of an expected value.
+  pub fn estimate() -> Self {
+    Self { function_name: "mc_estimate" }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for MonteCarloCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let variable_name = validate_schema(&MonteCarloCommand::argument_schema(), args)?;
+
+    let samples = context.opts.argument;
+    if let Some(samples) = samples {
+      anyhow::ensure!(samples > 1, "{}() requires at least two samples, got {samples}", self.function_name);
+    }
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [expr, range] = stack.pop_several(2)?.try_into().unwrap();
+    let mut function_args = vec![expr, Expr::Atom(Atom::Var(variable_name)), range];
+    if let Some(samples) = samples {
+      function_args.push(Expr::from(samples));
+    }
+    let expr = Expr::call(self.function_name, function_args);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
@@ -0,0 +1,125 @@
+
+//! Confirmation protocol for destructive commands.
+//!
+//! A command wrapped in [`RequireConfirmation`] first checks whether
+//! it is about to do something the user may regret (such as deleting
+//! a large part of the stack). If so, and if the user has not already
+//! confirmed it via [`CommandOptions::confirmed`], the command does
+//! nothing and instead returns a [`CommandOutput`] carrying a
+//! confirmation request. The frontend is then expected to ask the
+//! user and, if they agree, re-invoke the same command with the
+//! confirmation flag set.
+
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+use crate::util::Ellipsis;
+
+use std::fmt::{self, Debug, Formatter};
+
+/// The number of stack elements a command may discard before it
+/// requires confirmation.
+pub const DEFAULT_CONFIRMATION_THRESHOLD: usize = 10;
+
+/// A predicate deciding whether a command needs confirmation. Returns
+/// a message describing the consequences of the command if so, or
+/// `None` if the command may run without asking.
+pub type ConfirmationCheck =
+  dyn Fn(&ApplicationState, &[String], &CommandContext) -> Option<String> + Send + Sync;
+
+/// A command which refuses to run without confirmation whenever its
+/// check function produces a message.
+pub struct RequireConfirmation<C> {
+  inner: C,
+  check: Box<ConfirmationCheck>,
+}
+
+impl<C: Command> RequireConfirmation<C> {
+  pub fn new<F>(inner: C, check: F) -> Self
+  where F: Fn(&ApplicationState, &[String], &CommandContext) -> Option<String> + Send + Sync + 'static {
+    Self { inner, check: Box::new(check) }
+  }
+
+  pub fn inner(&self) -> &C {
+    &self.inner
+  }
+}
+
+impl<C: Debug> Debug for RequireConfirmation<C> {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.debug_struct("RequireConfirmation")
+      .field("inner", &self.inner)
+      .field("check", &Ellipsis)
+      .finish()
+  }
+}
+
+impl<C: Command> Command for RequireConfirmation<C> {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    if !ctx.opts.confirmed {
+      if let Some(message) = (self.check)(state, &args, ctx) {
+        return Ok(CommandOutput::needs_confirmation(message));
+      }
+    }
+    self.inner.run_command(state, args, ctx)
+  }
+
+  fn as_subcommand(&self, opts: &CommandOptions) -> Option<Subcommand> {
+    self.inner.as_subcommand(opts)
+  }
+}
+
+/// Builds a confirmation message for a command which will discard
+/// `count` stack elements, if `count` exceeds the threshold.
+pub fn confirm_discarding(count: usize, threshold: usize) -> Option<String> {
+  (count > threshold).then(|| format!("This will discard {count} stack elements. Continue?"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::shuffle::PopCommand;
+  use crate::state::test_utils::state_for_stack;
+  use crate::stack::test_utils::stack_of;
+  use crate::stack::base::StackLike;
+
+  fn guarded_pop() -> RequireConfirmation<PopCommand> {
+    RequireConfirmation::new(PopCommand, |state, _, _| {
+      confirm_discarding(state.main_stack().len(), 2)
+    })
+  }
+
+  #[test]
+  fn test_runs_without_confirmation_when_check_passes() {
+    let mut state = state_for_stack(vec![10, 20]);
+    let output = guarded_pop().run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert_eq!(output.confirmation_request(), None);
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+  }
+
+  #[test]
+  fn test_requests_confirmation() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    let output = guarded_pop().run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert_eq!(output.confirmation_request(), Some("This will discard 3 stack elements. Continue?"));
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+  }
+
+  #[test]
+  fn test_runs_when_confirmed() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    let context = CommandContext {
+      opts: CommandOptions::default().with_confirmation(),
+      ..CommandContext::default()
+    };
+    let output = guarded_pop().run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(output.confirmation_request(), None);
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20]));
+  }
+}
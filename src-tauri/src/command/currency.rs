@@ -0,0 +1,271 @@
+
+//! Commands for converting between currencies and for managing the
+//! table of exchange rates.
+
+use super::arguments::{UnaryArgumentSchema, BinaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use super::units::correct_unit_for_exactness;
+use crate::errorlist::ErrorList;
+use crate::expr::number::Number;
+use crate::expr::units::{UnitPrism, parse_composite_unit_expr, tagged_into_expr_lossy};
+use crate::mode::display::language::LanguageMode;
+use crate::state::ApplicationState;
+use crate::stack::base::StackLike;
+use crate::stack::keepable::KeepableStack;
+use crate::units::CompositeUnit;
+use crate::units::currency::{CurrencyRateTable, CurrencyUnitParser};
+use crate::units::dimension::{BaseDimension, Dimension};
+use crate::units::parsing::UnitParser;
+use crate::util::prism::{Prism, Identity};
+
+use anyhow::anyhow;
+use num::One;
+
+/// This command requires two arguments: the currency code to convert
+/// from and the currency code to convert to. Both are parsed as units
+/// with [`UnitPrism`], using the exchange rates stored in the
+/// application state.
+///
+/// Converts the value on top of the stack from the source currency to
+/// the target currency, and tags the result with the target currency.
+/// The value may be a plain number, which is taken to be in the
+/// source currency, or may already carry the source currency as its
+/// unit. A value carrying any other unit is an error. Unless
+/// fractional mode is on, the exchange rate is applied as a
+/// floating-point value.
+///
+/// This command always operates on the top value of the stack and
+/// does not use the numerical argument. However, this command does
+/// respect the "keep" modifier.
+#[derive(Debug, Clone, Default)]
+pub struct ConvertCurrencyCommand {
+  _priv: (),
+}
+
+/// This command requires one argument: a JSON exchange rate table, in
+/// the format accepted by [`CurrencyRateTable::from_json`].
+///
+/// Replaces the application's exchange rates with the given table.
+/// Exchange rates are not part of the undoable state, so this command
+/// cannot be undone.
+#[derive(Debug, Clone, Default)]
+pub struct SetCurrencyRatesCommand {
+  _priv: (),
+}
+
+impl ConvertCurrencyCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> BinaryArgumentSchema<Identity, String, Identity, String> {
+    BinaryArgumentSchema::new(
+      "currency code".to_owned(),
+      Identity,
+      "currency code".to_owned(),
+      Identity,
+    )
+  }
+
+  /// Parses the code as a unit, failing if it does not name a
+  /// currency.
+  fn parse_currency<P, L>(prism: &UnitPrism<P, L, Number>, code: String) -> anyhow::Result<CompositeUnit<Number>>
+  where P: UnitParser<Number>,
+        L: LanguageMode {
+    let unit = prism.narrow_type(code)
+      .map(CompositeUnit::from)
+      .map_err(|code| anyhow!("Unknown currency: {code}"))?;
+    if unit.dimension() != Dimension::singleton(BaseDimension::Currency) {
+      anyhow::bail!("Unknown currency: {unit}");
+    }
+    Ok(unit)
+  }
+}
+
+impl SetCurrencyRatesCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Command for ConvertCurrencyCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (source, target) = validate_schema(&Self::argument_schema(), args)?;
+    let calculation_mode = state.calculation_mode().clone();
+
+    let rates = state.currency_rates().clone();
+    let units_parser = CurrencyUnitParser::new(ctx.units_parser, &rates);
+    // The prism borrows the display settings, so it must be dropped
+    // before the state is modified.
+    let (source_unit, target_unit) = {
+      let prism = UnitPrism::new(&units_parser, state.display_settings().language_mode());
+      (Self::parse_currency(&prism, source)?, Self::parse_currency(&prism, target)?)
+    };
+
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), ctx.opts.keep_modifier);
+    let original_expr = stack.pop()?;
+    let mut tagged_term = parse_composite_unit_expr(&units_parser, original_expr.clone());
+    if tagged_term.unit.is_one() {
+      tagged_term.unit = source_unit.clone();
+    }
+    if tagged_term.unit != source_unit {
+      // Recover the stack, then bail.
+      stack.restore_popped([original_expr]);
+      anyhow::bail!("Expected a value in {source_unit}, got one in {}", tagged_term.unit);
+    }
+    tagged_term.unit = correct_unit_for_exactness(tagged_term.unit, &calculation_mode);
+
+    // convert_or_panic safety: Both units are currencies.
+    let tagged_term = tagged_term.convert_or_panic(correct_unit_for_exactness(target_unit, &calculation_mode));
+    let expr = tagged_into_expr_lossy(tagged_term);
+
+    let mut errors = ErrorList::new();
+    stack.push(ctx.simplify_expr(expr, calculation_mode, &mut errors));
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetCurrencyRatesCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let json = validate_schema(&UnaryArgumentSchema::any(), args)?;
+    *state.currency_rates_mut() = CurrencyRateTable::from_json(&json)?;
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
+  use crate::command::units::test_utils::setup_si_units;
+  use crate::mode::calculation::CalculationMode;
+  use crate::expr::Expr;
+  use crate::expr::atom::Atom;
+  use crate::units::currency::CurrencyRateProvider;
+  use crate::stack::test_utils::stack_of;
+  use crate::state::test_utils::state_for_stack;
+  use crate::units::currency::CurrencyRateError;
+  use crate::assert_strict_eq;
+
+  fn fractional_mode() -> CalculationMode {
+    let mut mode = CalculationMode::default();
+    mode.set_fractional_flag(true);
+    mode
+  }
+
+  fn quantity(n: i64, currency: &str) -> Expr {
+    Expr::call("*", vec![Expr::from(n), Expr::var(currency).unwrap()])
+  }
+
+  #[test]
+  fn test_convert_currency() {
+    let output_stack = act_on_stack(
+      &ConvertCurrencyCommand::new(),
+      (setup_default_simplifier, fractional_mode(), vec!["USD", "JPY"]),
+      vec![10, 20],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::from(10), quantity(3_000, "JPY")]));
+  }
+
+  #[test]
+  fn test_convert_currency_tagged_value() {
+    let output_stack = act_on_stack(
+      &ConvertCurrencyCommand::new(),
+      ((setup_si_units, setup_default_simplifier), fractional_mode(), vec!["USD", "JPY"]),
+      vec![quantity(10, "USD")],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![quantity(1_500, "JPY")]));
+  }
+
+  #[test]
+  fn test_convert_currency_wrong_unit() {
+    let err = act_on_stack(
+      &ConvertCurrencyCommand::new(),
+      ((setup_si_units, setup_default_simplifier), fractional_mode(), vec!["USD", "JPY"]),
+      vec![quantity(10, "m")],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Expected a value in USD, got one in m");
+    let err = act_on_stack(
+      &ConvertCurrencyCommand::new(),
+      ((setup_si_units, setup_default_simplifier), fractional_mode(), vec!["USD", "JPY"]),
+      vec![quantity(10, "EUR")],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Expected a value in USD, got one in EUR");
+  }
+
+  #[test]
+  fn test_convert_currency_is_inexact_by_default() {
+    let output_stack = act_on_stack(
+      &ConvertCurrencyCommand::new(),
+      (setup_default_simplifier, vec!["USD", "EUR"]),
+      vec![25],
+    ).unwrap();
+    let [Expr::Call(name, args)]: [Expr; 1] = output_stack.into_iter().collect::<Vec<_>>().try_into().unwrap() else {
+      panic!("Expected a single call");
+    };
+    assert_eq!(name, "*");
+    let [Expr::Atom(Atom::Number(amount)), currency] = <[Expr; 2]>::try_from(args).unwrap() else {
+      panic!("Expected a number times a currency");
+    };
+    assert_strict_eq!(amount, Number::from(23.0));
+    assert_eq!(currency, Expr::var("EUR").unwrap());
+  }
+
+  #[test]
+  fn test_convert_currency_unknown_code() {
+    let err = act_on_stack(
+      &ConvertCurrencyCommand::new(),
+      (setup_default_simplifier, vec!["USD", "XYZ"]),
+      vec![10],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown currency: XYZ");
+    let err = act_on_stack(
+      &ConvertCurrencyCommand::new(),
+      (setup_si_units, setup_default_simplifier, vec!["m", "USD"]),
+      vec![10],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown currency: m");
+  }
+
+  #[test]
+  fn test_set_currency_rates() {
+    let mut state = state_for_stack(vec![10]);
+    let context = CommandContext::default();
+    let json = r#"{"base": "EUR", "rates": {"USD": 2, "XYZ": 8}}"#;
+    SetCurrencyRatesCommand::new().run_command(&mut state, vec![json.to_owned()], &context).unwrap();
+    assert_eq!(state.currency_rates().rate("XYZ"), Some(Number::from(4.0)));
+    assert_eq!(state.currency_rates().rate("EUR"), Some(Number::from(0.5)));
+    assert_eq!(state.currency_rates().rate("JPY"), None);
+  }
+
+  #[test]
+  fn test_set_currency_rates_invalid() {
+    let mut state = state_for_stack(Vec::<Expr>::new());
+    let context = CommandContext::default();
+    let json = r#"{"base": "EUR", "rates": {"XYZ": 8}}"#;
+    let err = SetCurrencyRatesCommand::new().run_command(&mut state, vec![json.to_owned()], &context).unwrap_err();
+    assert!(matches!(err.downcast_ref(), Some(CurrencyRateError::MissingReferenceCurrency)));
+    assert_eq!(state.currency_rates(), &CurrencyRateTable::default());
+  }
+}
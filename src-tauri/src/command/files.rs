@@ -0,0 +1,316 @@
+
+//! Commands which read from or write to the filesystem.
+
+use super::arguments::{UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::errorlist::ErrorList;
+use crate::state::ApplicationState;
+use crate::stack::base::{StackLike, RandomAccessStackLike};
+use crate::util::prism::Identity;
+
+use anyhow::{Context, anyhow};
+use html_escape::decode_html_entities;
+
+use std::fs::{self, File};
+use std::io::{self, Write, BufWriter};
+
+/// Writes the session's command history, as a Markdown document, to
+/// the file path given as the sole argument. The file is overwritten
+/// if it exists. The stack is not modified.
+#[derive(Debug, Default)]
+pub struct ExportTranscriptCommand {
+  _priv: (),
+}
+
+impl ExportTranscriptCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+pub(super) fn file_path_argument_schema() -> UnaryArgumentSchema<Identity, String> {
+  UnaryArgumentSchema::new(
+    "file path".to_owned(),
+    Identity,
+  )
+}
+
+impl Command for ExportTranscriptCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let path = validate_schema(&file_path_argument_schema(), args)?;
+    let transcript = state.history().to_markdown();
+    fs::write(&path, transcript).with_context(|| format!("Could not write to {path}"))?;
+    Ok(CommandOutput::success().set_force_scroll_down(false))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// Writes the top stack element, in the reversible syntax of the
+/// current language mode, to the file path given as the sole
+/// argument. The file is overwritten if it exists. The stack is not
+/// modified.
+///
+/// The expression is written to the file as it is rendered, so this
+/// works even for expressions too large to display on the stack.
+#[derive(Debug, Default)]
+pub struct ExportExprFileCommand {
+  _priv: (),
+}
+
+/// Writer which decodes each chunk of HTML written to it into plain
+/// text. This relies on chunks being complete HTML fragments, as
+/// guaranteed by [`LanguageMode::write_incrementally`].
+///
+/// [`LanguageMode::write_incrementally`]: crate::mode::display::language::LanguageMode::write_incrementally
+struct HtmlDecodingWriter<W> {
+  inner: W,
+}
+
+impl ExportExprFileCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl<W: Write> Write for HtmlDecodingWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let html = std::str::from_utf8(buf).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    self.inner.write_all(decode_html_entities(html).as_bytes())?;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+impl Command for ExportExprFileCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let path = validate_schema(&file_path_argument_schema(), args)?;
+    let expr = state.main_stack().get(0)?;
+    let display_settings = state.display_settings();
+    let language_mode = display_settings.language_mode();
+    let language_mode = language_mode.to_reversible_language_mode();
+
+    let file = File::create(&path).with_context(|| format!("Could not write to {path}"))?;
+    let mut out = HtmlDecodingWriter { inner: BufWriter::new(file) };
+    language_mode.to_writer(expr, &display_settings.language_settings, &mut out)
+      .and_then(|_| out.write_all(b"\n"))
+      .and_then(|_| out.flush())
+      .with_context(|| format!("Could not write to {path}"))?;
+    Ok(CommandOutput::success().set_force_scroll_down(false))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// Reads expressions from the file path given as the sole argument
+/// and pushes them onto the stack, in order. The file contains one
+/// expression per line, written in the syntax of the current language
+/// mode's parser. This is the syntax produced by
+/// [`ExportExprFileCommand`]. Blank lines are ignored.
+///
+/// If any line fails to parse, the error reports its line number and
+/// the stack is not modified.
+#[derive(Debug, Default)]
+pub struct ImportExprFileCommand {
+  _priv: (),
+}
+
+impl ImportExprFileCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl Command for ImportExprFileCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let calculation_mode = state.calculation_mode().clone();
+    let path = validate_schema(&file_path_argument_schema(), args)?;
+    let contents = fs::read_to_string(&path).with_context(|| format!("Could not read {path}"))?;
+
+    let language_mode = state.display_settings().language_mode();
+    let exprs = contents.lines()
+      .enumerate()
+      .filter(|(_, line)| !line.trim().is_empty())
+      .map(|(index, line)| {
+        language_mode.parse(line).map_err(|err| anyhow!("Line {}: {}", index + 1, err))
+      })
+      .collect::<anyhow::Result<Vec<_>>>()?;
+    drop(language_mode);
+    if exprs.is_empty() {
+      anyhow::bail!("No expressions in {path}");
+    }
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+    let exprs = exprs.into_iter()
+      .map(|expr| context.simplify_expr(expr, calculation_mode.clone(), &mut errors))
+      .collect::<Vec<_>>();
+    state.main_stack_mut().push_several(exprs);
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::state::history::HistoryEntry;
+  use crate::command::test_utils::act_on_stack;
+  use crate::expr::Expr;
+  use crate::stack::Stack;
+  use crate::stack::test_utils::stack_of;
+
+  use std::env;
+  use std::process;
+
+  /// A path in the system temporary directory which is unique to
+  /// this test process and the given name.
+  fn temp_path(name: &str) -> String {
+    let path = env::temp_dir().join(format!("fifi-test-{}-{}", process::id(), name));
+    path.to_string_lossy().into_owned()
+  }
+
+  #[test]
+  fn test_export_transcript() {
+    let path = temp_path("transcript.md");
+    let command = ExportTranscriptCommand::new();
+    let mut state = ApplicationState::new();
+    state.history_mut().push(HistoryEntry {
+      command_name: String::from("push_number"),
+      args: vec![String::from("3")],
+      options: CommandOptions::default(),
+      result: Some(String::from("3")),
+    });
+    command.run_command(&mut state, vec![path.clone()], &CommandContext::default()).unwrap();
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(contents, "# Session transcript\n\n1. `push_number 3` → `3`\n");
+  }
+
+  #[test]
+  fn test_export_transcript_leaves_stack_alone() {
+    let path = temp_path("transcript2.md");
+    let output_stack = act_on_stack(&ExportTranscriptCommand::new(), vec![path.clone()], vec![1, 2]).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(output_stack, crate::stack::test_utils::stack_of(vec![1, 2]));
+  }
+
+  #[test]
+  fn test_export_transcript_to_bad_path() {
+    let path = env::temp_dir().join("fifi-nonexistent-dir").join("x").join("transcript.md");
+    let err = act_on_stack(
+      &ExportTranscriptCommand::new(),
+      vec![path.to_string_lossy().into_owned()],
+      vec![1],
+    ).unwrap_err();
+    assert!(err.to_string().starts_with("Could not write to"));
+  }
+
+  #[test]
+  fn test_export_transcript_without_path() {
+    act_on_stack(&ExportTranscriptCommand::new(), (), vec![1]).unwrap_err();
+  }
+
+  #[test]
+  fn test_export_expr_file() {
+    let path = temp_path("expr.txt");
+    let expr = Expr::call("<=", vec![
+      Expr::call("f", vec![Expr::from(1), Expr::var("x").unwrap()]),
+      Expr::call("vector", vec![Expr::from(2), Expr::from(3)]),
+    ]);
+    let input_stack = vec![Expr::from(0), expr];
+    let output_stack = act_on_stack(&ExportExprFileCommand::new(), vec![path.clone()], input_stack.clone()).unwrap();
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(contents, "f(1, x) <= [2, 3]\n");
+    assert_eq!(output_stack, Stack::from(input_stack));
+  }
+
+  #[test]
+  fn test_export_expr_file_on_empty_stack() {
+    let path = temp_path("expr2.txt");
+    act_on_stack(&ExportExprFileCommand::new(), vec![path], Vec::<i64>::new()).unwrap_err();
+  }
+
+  #[test]
+  fn test_import_expr_file() {
+    let path = temp_path("import.txt");
+    fs::write(&path, "1 + 1\n\n[x, 3]\n").unwrap();
+    let output_stack = act_on_stack(&ImportExprFileCommand::new(), vec![path.clone()], vec![10]).unwrap();
+    fs::remove_file(&path).unwrap();
+    let vector = Expr::call("vector", vec![Expr::var("x").unwrap(), Expr::from(3)]);
+    let sum = Expr::call("+", vec![Expr::from(1), Expr::from(1)]);
+    assert_eq!(output_stack, Stack::from(vec![Expr::from(10), sum, vector]));
+  }
+
+  #[test]
+  fn test_export_then_import_expr_file() {
+    let path = temp_path("round_trip.txt");
+    let expr = Expr::call("-", vec![
+      Expr::var("x").unwrap(),
+      Expr::call("f", vec![Expr::from(1.5), Expr::string("a<b")]),
+    ]);
+    act_on_stack(&ExportExprFileCommand::new(), vec![path.clone()], vec![expr.clone()]).unwrap();
+    let output_stack = act_on_stack(&ImportExprFileCommand::new(), vec![path.clone()], Vec::<Expr>::new()).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![expr]));
+  }
+
+  #[test]
+  fn test_import_expr_file_reports_line_number() {
+    let path = temp_path("import_bad.txt");
+    fs::write(&path, "1\n2\n\n(3 +\n").unwrap();
+    let err = act_on_stack(&ImportExprFileCommand::new(), vec![path.clone()], vec![10]).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert!(err.to_string().starts_with("Line 4: "), "Unexpected error: {err}");
+  }
+
+  #[test]
+  fn test_import_expr_file_failure_leaves_stack_alone() {
+    let path = temp_path("import_bad2.txt");
+    fs::write(&path, "1\n)\n").unwrap();
+    let mut state = ApplicationState::new();
+    state.main_stack_mut().push(Expr::from(10));
+    ImportExprFileCommand::new().run_command(&mut state, vec![path.clone()], &CommandContext::default()).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(state.into_main_stack(), stack_of(vec![10]));
+  }
+
+  #[test]
+  fn test_import_missing_expr_file() {
+    let path = env::temp_dir().join("fifi-nonexistent-dir").join("exprs.txt");
+    let err = act_on_stack(
+      &ImportExprFileCommand::new(),
+      vec![path.to_string_lossy().into_owned()],
+      vec![1],
+    ).unwrap_err();
+    assert!(err.to_string().starts_with("Could not read"));
+  }
+}
@@ -361,12 +361,16 @@ impl Command for BinaryFunctionCommand {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::command::test_utils::act_on_stack;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
   use crate::command::options::CommandOptions;
+  use crate::expr::number::{Number, ComplexNumber, Quaternion};
+  use crate::mode::calculation::{CalculationMode, AngleMode};
   use crate::command::subcommand::SubcommandArityError;
   use crate::command::subcommand::test_utils::{try_call as try_call_subcommand};
   use crate::stack::test_utils::stack_of;
   use crate::stack::{Stack, StackError};
+  use crate::expr::prisms::expr_to_number;
+  use crate::util::prism::Prism;
 
   fn push_constant_zero() -> PushConstantCommand {
     PushConstantCommand::new(Expr::zero())
@@ -787,6 +791,207 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_quaternion_multiplication_respects_order() {
+    let input_stack = vec![
+      Expr::from(Quaternion::jj()),
+      Expr::from(ComplexNumber::ii()),
+      Expr::from(ComplexNumber::ii()),
+      Expr::from(Quaternion::jj()),
+    ];
+    let multiply = BinaryFunctionCommand::named("*");
+    let output_stack = act_on_stack(&multiply, (setup_default_simplifier, CommandOptions::numerical(2)), input_stack).unwrap();
+    assert_eq!(
+      output_stack,
+      Stack::from(vec![
+        Expr::from(Quaternion::jj()),
+        Expr::from(ComplexNumber::ii()),
+        Expr::from(Quaternion::kk()),
+      ]),
+    );
+  }
+
+  #[test]
+  fn test_large_vector_arithmetic() {
+    let len: i64 = 10_000;
+    let input_stack = vec![
+      Expr::call("vector", (1..=len).map(Expr::from).collect()),
+      Expr::call("vector", (1..=len).map(|i| Expr::from(2 * i)).collect()),
+    ];
+    let add = BinaryFunctionCommand::named("+");
+    let output_stack = act_on_stack(&add, setup_default_simplifier, input_stack).unwrap();
+    assert_eq!(
+      output_stack,
+      Stack::from(vec![Expr::call("vector", (1..=len).map(|i| Expr::from(3 * i)).collect())]),
+    );
+  }
+
+  #[test]
+  fn test_polynomial_multiplication_in_modular_mode() {
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_modulus(Some(3));
+    let x = || Expr::var("x").unwrap();
+    let input_stack = vec![
+      Expr::call("+", vec![x(), Expr::from(1)]),
+      Expr::call("+", vec![x(), Expr::from(2)]),
+    ];
+    let poly_mul = BinaryFunctionCommand::new(move |p, q| Expr::call("poly_mul", vec![p, q, x()]));
+    let output_stack = act_on_stack(&poly_mul, (setup_default_simplifier, calculation_mode), input_stack).unwrap();
+    // (x + 1) (x + 2) = x^2 + 3x + 2 = x^2 + 2 mod 3
+    assert_eq!(
+      output_stack,
+      Stack::from(vec![
+        Expr::call("+", vec![Expr::from(2), Expr::call("^", vec![x(), Expr::from(2)])]),
+      ]),
+    );
+  }
+
+  fn matrix(rows: Vec<Vec<Expr>>) -> Expr {
+    Expr::call("vector", rows.into_iter().map(|row| Expr::call("vector", row)).collect())
+  }
+
+  #[test]
+  fn test_matrix_inverse_in_exact_matrix_mode() {
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_exact_matrix_flag(true);
+    let input_stack = vec![
+      matrix(vec![vec![Expr::from(1), Expr::from(2)], vec![Expr::from(3), Expr::from(4)]]),
+    ];
+    let recip = UnaryFunctionCommand::named("recip");
+    let output_stack = act_on_stack(&recip, (setup_default_simplifier, calculation_mode), input_stack).unwrap();
+    assert_eq!(
+      output_stack,
+      Stack::from(vec![
+        matrix(vec![
+          vec![Expr::from(-2), Expr::from(1)],
+          vec![Expr::from(Number::ratio(3, 2)), Expr::from(Number::ratio(-1, 2))],
+        ]),
+      ]),
+    );
+  }
+
+  #[test]
+  fn test_negative_matrix_power() {
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_exact_matrix_flag(true);
+    let input_stack = vec![
+      matrix(vec![vec![Expr::from(1), Expr::from(2)], vec![Expr::from(3), Expr::from(4)]]),
+      Expr::from(-1),
+    ];
+    let matpow = BinaryFunctionCommand::named("matpow");
+    let output_stack = act_on_stack(&matpow, (setup_default_simplifier, calculation_mode), input_stack).unwrap();
+    assert_eq!(
+      output_stack,
+      Stack::from(vec![
+        matrix(vec![
+          vec![Expr::from(-2), Expr::from(1)],
+          vec![Expr::from(Number::ratio(3, 2)), Expr::from(Number::ratio(-1, 2))],
+        ]),
+      ]),
+    );
+  }
+
+  #[test]
+  fn test_numerical_matrix_function_in_exact_matrix_mode() {
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_exact_matrix_flag(true);
+    let input_matrix = matrix(vec![vec![Expr::from(1), Expr::from(0)], vec![Expr::from(0), Expr::from(1)]]);
+    let matexp = UnaryFunctionCommand::named("matexp");
+    let output_stack = act_on_stack(&matexp, (setup_default_simplifier, calculation_mode), vec![input_matrix.clone()]).unwrap();
+    assert_eq!(
+      output_stack,
+      Stack::from(vec![Expr::call("matexp", vec![input_matrix])]),
+    );
+  }
+
+  #[test]
+  fn test_hash_functions() {
+    let input_stack = vec![Expr::from("abc")];
+    let md5 = UnaryFunctionCommand::named("md5");
+    let output_stack = act_on_stack(&md5, setup_default_simplifier, input_stack.clone()).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("900150983cd24fb0d6963f7d28e17f72")]));
+    let sha256 = UnaryFunctionCommand::named("sha256");
+    let output_stack = act_on_stack(&sha256, setup_default_simplifier, input_stack.clone()).unwrap();
+    assert_eq!(
+      output_stack,
+      Stack::from(vec![Expr::from("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")]),
+    );
+    let crc32 = UnaryFunctionCommand::named("crc32");
+    let output_stack = act_on_stack(&crc32, setup_default_simplifier, input_stack).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from(0x352441c2)]));
+  }
+
+  #[test]
+  fn test_hash_of_byte_vector() {
+    let input_stack = vec![Expr::call("vector", vec![Expr::from(97), Expr::from(98), Expr::from(99)])];
+    let md5 = UnaryFunctionCommand::named("md5");
+    let output_stack = act_on_stack(&md5, setup_default_simplifier, input_stack).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("900150983cd24fb0d6963f7d28e17f72")]));
+
+    let input_stack = vec![Expr::call("vector", vec![Expr::from(256)])];
+    let output_stack = act_on_stack(&md5, setup_default_simplifier, input_stack.clone()).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::call("md5", input_stack)]));
+  }
+
+  #[test]
+  fn test_base64_round_trip() {
+    let encode = UnaryFunctionCommand::named("base64_encode");
+    let decode = UnaryFunctionCommand::named("base64_decode");
+    let output_stack = act_on_stack(&encode, setup_default_simplifier, vec![Expr::from("hello!")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("aGVsbG8h")]));
+    let output_stack = act_on_stack(&decode, setup_default_simplifier, vec![Expr::from("aGVsbG8h")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("hello!")]));
+    // Not valid UTF-8, so decodes to bytes.
+    let output_stack = act_on_stack(&decode, setup_default_simplifier, vec![Expr::from("/w==")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::call("vector", vec![Expr::from(255)])]));
+  }
+
+  #[test]
+  fn test_bytes_of_string() {
+    let bytes = UnaryFunctionCommand::named("bytes");
+    let output_stack = act_on_stack(&bytes, setup_default_simplifier, vec![Expr::from("é")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::call("vector", vec![Expr::from(0xc3), Expr::from(0xa9)])]));
+    let bytes_to_string = UnaryFunctionCommand::named("bytes_to_string");
+    let output_stack = act_on_stack(&bytes_to_string, setup_default_simplifier, output_stack.into_iter().collect()).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("é")]));
+  }
+
+  #[test]
+  fn test_url_encoding() {
+    let encode = UnaryFunctionCommand::named("url_encode");
+    let decode = UnaryFunctionCommand::named("url_decode");
+    let output_stack = act_on_stack(&encode, setup_default_simplifier, vec![Expr::from("a b&c=é~")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("a%20b%26c%3D%C3%A9~")]));
+    let output_stack = act_on_stack(&decode, setup_default_simplifier, output_stack.into_iter().collect()).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("a b&c=é~")]));
+    // Not valid UTF-8, so left alone.
+    let output_stack = act_on_stack(&decode, setup_default_simplifier, vec![Expr::from("%FF")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::call("url_decode", vec![Expr::from("%FF")])]));
+  }
+
+  #[test]
+  fn test_moon_phase() {
+    let moon_phase = UnaryFunctionCommand::named("moon_phase");
+    let output_stack = act_on_stack(&moon_phase, setup_default_simplifier, vec![Expr::from("2000-01-21T04:40")]).unwrap();
+    let phase = output_stack.into_iter().next().unwrap();
+    let phase = expr_to_number().narrow_type(phase).unwrap().to_f64().unwrap();
+    assert!((phase - 0.5).abs() < 0.01, "Expected a full moon, got {phase}");
+
+    let input_stack = vec![Expr::from("2000-13-01")];
+    let output_stack = act_on_stack(&moon_phase, setup_default_simplifier, input_stack.clone()).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::call("moon_phase", input_stack)]));
+  }
+
+  #[test]
+  fn test_html_escaping() {
+    let escape = UnaryFunctionCommand::named("html_escape");
+    let unescape = UnaryFunctionCommand::named("html_unescape");
+    let output_stack = act_on_stack(&escape, setup_default_simplifier, vec![Expr::from("<a href=\"x\">&</a>")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("&lt;a href=&quot;x&quot;&gt;&amp;&lt;&#x2F;a&gt;")]));
+    let output_stack = act_on_stack(&unescape, setup_default_simplifier, vec![Expr::from("&lt;&eacute;&#233;&gt;")]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::from("<éé>")]));
+  }
+
   #[test]
   fn test_binary_function_command_explicitly_left_assoc() {
     let input_stack = vec![10, 20, 30, 40];
@@ -1427,4 +1632,119 @@ mod tests {
     let err = try_call_subcommand(&subcommand, vec![Expr::from(0)]).unwrap_err();
     assert!(matches!(err, SubcommandArityError { expected: 2, actual: 1, args: _ }));
   }
+
+  fn geo_point(lat: f64, lon: f64) -> Expr {
+    Expr::call("vector", vec![Expr::from(Number::from(lat)), Expr::from(Number::from(lon))])
+  }
+
+  fn degrees_mode() -> CalculationMode {
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_angle_mode(AngleMode::Degrees);
+    calculation_mode
+  }
+
+  #[test]
+  fn test_geo_distance() {
+    let geo_distance = BinaryFunctionCommand::named("geo_distance");
+    let input_stack = vec![geo_point(51.5074, -0.1278), geo_point(48.8566, 2.3522)];
+    let output_stack = act_on_stack(&geo_distance, (setup_default_simplifier, degrees_mode()), input_stack).unwrap();
+    let Some(Expr::Call(f, args)) = output_stack.into_iter().next() else {
+      panic!("Expected a product");
+    };
+    assert_eq!(f, "*");
+    assert_eq!(args[1], Expr::var("m").unwrap());
+    let distance = expr_to_number().narrow_type(args[0].clone()).unwrap().to_f64().unwrap();
+    assert!((distance - 343_560.0).abs() < 500.0, "Got {distance}");
+  }
+
+  #[test]
+  fn test_bearing() {
+    let bearing = BinaryFunctionCommand::named("bearing");
+    let input_stack = vec![geo_point(0.0, 0.0), geo_point(0.0, 1.0)];
+    let output_stack = act_on_stack(&bearing, (setup_default_simplifier, degrees_mode()), input_stack).unwrap();
+    let bearing = expr_to_number().narrow_type(output_stack.into_iter().next().unwrap()).unwrap().to_f64().unwrap();
+    assert!((bearing - 90.0).abs() < 1e-9, "Got {bearing}");
+  }
+
+  #[test]
+  fn test_solve_triangle() {
+    let solve_triangle = UnaryFunctionCommand::named("solve_triangle");
+    let unknown = || Expr::var("x").unwrap();
+    let input = Expr::call("vector", vec![Expr::from(3), Expr::from(4), unknown(), unknown(), unknown(), Expr::from(90)]);
+    let output_stack = act_on_stack(&solve_triangle, (setup_default_simplifier, degrees_mode()), vec![input]).unwrap();
+    let Some(Expr::Call(f, values)) = output_stack.into_iter().next() else {
+      panic!("Expected a vector");
+    };
+    assert_eq!(f, "vector");
+    let values: Vec<f64> = values.into_iter()
+      .map(|value| expr_to_number().narrow_type(value).unwrap().to_f64().unwrap())
+      .collect();
+    let expected = [3.0, 4.0, 5.0, 36.86989764584402, 53.13010235415598, 90.0, 6.0];
+    assert_eq!(values.len(), expected.len());
+    for (actual, expected) in values.into_iter().zip(expected) {
+      assert!((actual - expected).abs() < 1e-9, "Expected {expected}, got {actual}");
+    }
+  }
+
+  #[test]
+  fn test_solve_triangle_no_solution() {
+    let solve_triangle = UnaryFunctionCommand::named("solve_triangle");
+    let unknown = || Expr::var("x").unwrap();
+    let input = Expr::call("vector", vec![Expr::from(1), Expr::from(1), Expr::from(3), unknown(), unknown(), unknown()]);
+    let output_stack = act_on_stack(&solve_triangle, setup_default_simplifier, vec![input.clone()]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![Expr::call("solve_triangle", vec![input])]));
+  }
+
+  #[test]
+  fn test_prime_functions() {
+    let prime_pi = UnaryFunctionCommand::named("prime_pi");
+    let output_stack = act_on_stack(&prime_pi, setup_default_simplifier, vec![100]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![25]));
+
+    let nth_prime = UnaryFunctionCommand::named("nth_prime");
+    let output_stack = act_on_stack(&nth_prime, setup_default_simplifier, vec![25]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![97]));
+    let output_stack = act_on_stack(&nth_prime, setup_default_simplifier, vec![0]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::call("nth_prime", vec![Expr::from(0)])]));
+
+    let prime_range = BinaryFunctionCommand::named("prime_range");
+    let output_stack = act_on_stack(&prime_range, setup_default_simplifier, vec![10, 30]).unwrap();
+    let expected = Expr::call("vector", [11, 13, 17, 19, 23, 29].into_iter().map(Expr::from).collect());
+    assert_eq!(output_stack, stack_of(vec![expected]));
+  }
+
+  #[test]
+  fn test_modular_functions() {
+    let modinv = BinaryFunctionCommand::named("modinv");
+    let output_stack = act_on_stack(&modinv, setup_default_simplifier, vec![3, 11]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![4]));
+    let output_stack = act_on_stack(&modinv, setup_default_simplifier, vec![6, 9]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::call("modinv", vec![Expr::from(6), Expr::from(9)])]));
+
+    let crt = BinaryFunctionCommand::named("crt");
+    let residues = Expr::call("vector", vec![Expr::from(2), Expr::from(3), Expr::from(2)]);
+    let moduli = Expr::call("vector", vec![Expr::from(3), Expr::from(5), Expr::from(7)]);
+    let output_stack = act_on_stack(&crt, setup_default_simplifier, vec![residues, moduli]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![23]));
+  }
+
+  #[test]
+  fn test_guess_sequence() {
+    let guess_sequence = UnaryFunctionCommand::named("guess_sequence");
+    let squares = Expr::call("vector", [1, 4, 9, 16].into_iter().map(Expr::from).collect());
+    let output_stack = act_on_stack(&guess_sequence, setup_default_simplifier, vec![squares]).unwrap();
+    let n = || Expr::var("n").unwrap();
+    let expected = Expr::call("vector", vec![
+      Expr::call("=", vec![
+        Expr::call("a", vec![n()]),
+        Expr::call("^", vec![n(), Expr::from(2)]),
+      ]),
+      Expr::call("vector", [25, 36, 49, 64, 81].into_iter().map(Expr::from).collect()),
+    ]);
+    assert_eq!(output_stack, stack_of(vec![expected]));
+
+    let digits = Expr::call("vector", [3, 1, 4, 1, 5, 9].into_iter().map(Expr::from).collect());
+    let output_stack = act_on_stack(&guess_sequence, setup_default_simplifier, vec![digits.clone()]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::call("guess_sequence", vec![digits])]));
+  }
 }
@@ -12,6 +12,7 @@ use crate::expr::prisms::expr_to_typed_array;
 use crate::state::ApplicationState;
 use crate::stack::base::StackLike;
 use crate::stack::keepable::KeepableStack;
+use crate::expr::algebra::regression::FitModel;
 use crate::graphics::GRAPHICS_NAME;
 use crate::util::prism::{Prism, Identity, OnVec};
 
@@ -59,6 +60,61 @@ pub struct ContourPlotCommand {
   _priv: (),
 }
 
+/// This command pops one value off the stack: a vector of `[datetime,
+/// value]` pairs, with datetimes given as UTC strings in ISO 8601
+/// format. Produces a two-dimensional graphics value which plots the
+/// values against a date axis.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct TimePlotCommand {
+  _priv: (),
+}
+
+/// This command pops two values off the stack: a data matrix and a
+/// vector of labels. Produces a two-dimensional graphics value which
+/// draws a bar chart, with one bar per label for each row of the
+/// matrix. The value of each bar is shown on the bar.
+///
+/// The plain variant draws the rows side by side, while the stacked
+/// variant stacks them.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug)]
+pub struct BarChartCommand {
+  function_name: &'static str,
+}
+
+/// This command pops one value off the stack: a vector of
+/// non-negative values. Produces a two-dimensional graphics value
+/// which draws a pie chart of the values, with each wedge labeled by
+/// its percentage of the total.
+///
+/// If given a numerical argument, then two values are popped
+/// instead: the vector of values and a vector of labels for the
+/// wedges.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct PieChartCommand {
+  _priv: (),
+}
+
+/// This command pops one value off the stack: a matrix whose rows
+/// are `[x, y]` data points. Produces a two-dimensional graphics
+/// value which draws the points as markers.
+///
+/// If given a positive numerical argument N, then the least-squares
+/// polynomial of degree N is drawn through the points as well. In
+/// particular, a numerical argument of 1 overlays the regression
+/// line.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct ScatterPlotCommand {
+  _priv: (),
+}
+
 impl PlotCommand {
   pub fn new() -> Self {
     Default::default()
@@ -87,6 +143,56 @@ impl ContourPlotCommand {
   }
 }
 
+impl TimePlotCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> NullaryArgumentSchema {
+    NullaryArgumentSchema::new()
+  }
+}
+
+impl BarChartCommand {
+  pub fn new() -> Self {
+    Self { function_name: "barchart" }
+  }
+
+  pub fn stacked() -> Self {
+    Self { function_name: "stackedbar" }
+  }
+
+  fn argument_schema() -> NullaryArgumentSchema {
+    NullaryArgumentSchema::new()
+  }
+}
+
+impl Default for BarChartCommand {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl PieChartCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> NullaryArgumentSchema {
+    NullaryArgumentSchema::new()
+  }
+}
+
+impl ScatterPlotCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn argument_schema() -> NullaryArgumentSchema {
+    NullaryArgumentSchema::new()
+  }
+}
+
 impl Command for PlotCommand {
   fn run_command(
     &self,
@@ -125,9 +231,7 @@ impl Command for PlotCommand {
           match OnVec::new(expr_to_typed_array(Identity)).narrow_type(all_values) {
             Err(all_values) => {
               // Failure, restore the stack and report an error.
-              if !context.opts.keep_modifier {
-                stack.push_several(all_values);
-              }
+              stack.restore_popped(all_values);
               anyhow::bail!("Expecting 2-vectors of X and Y values");
             }
             Ok(xy_values) => xy_values,
@@ -145,9 +249,7 @@ impl Command for PlotCommand {
         match ExprToVector.narrow_type(y_values) {
           Err(y_values) => {
             // Failure, restore the stack and report an error.
-            if !context.opts.keep_modifier {
-              stack.push_several([x_values, y_values]);
-            }
+            stack.restore_popped([x_values, y_values]);
             anyhow::bail!("Expecting vector of Y values");
           }
           Ok(y_values_vec) => {
@@ -228,6 +330,128 @@ impl Command for ContourPlotCommand {
   }
 }
 
+impl Command for TimePlotCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&TimePlotCommand::argument_schema(), args)?;
+
+    let calculation_mode = state.calculation_mode().clone();
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+
+    let points = stack.pop()?;
+    let expr = Expr::call(GRAPHICS_NAME, vec![Expr::call("timeplot", vec![points])]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for BarChartCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&BarChartCommand::argument_schema(), args)?;
+
+    let calculation_mode = state.calculation_mode().clone();
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+
+    let [data, labels] = stack.pop_several(2)?.try_into().unwrap();
+    let expr = Expr::call(GRAPHICS_NAME, vec![Expr::call(self.function_name, vec![data, labels])]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for PieChartCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&PieChartCommand::argument_schema(), args)?;
+
+    let calculation_mode = state.calculation_mode().clone();
+
+    let has_labels = context.opts.argument.is_some();
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+
+    let chart_args = stack.pop_several(if has_labels { 2 } else { 1 })?;
+    let expr = Expr::call(GRAPHICS_NAME, vec![Expr::call("piechart", chart_args)]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for ScatterPlotCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&ScatterPlotCommand::argument_schema(), args)?;
+
+    let degree = match context.opts.argument {
+      None => None,
+      Some(degree) if degree > 0 => Some(degree as usize),
+      Some(_) => anyhow::bail!("Expected positive polynomial degree"),
+    };
+    let calculation_mode = state.calculation_mode().clone();
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+
+    let points = stack.pop()?;
+    let mut scatter_plot_args = vec![points];
+    if let Some(degree) = degree {
+      scatter_plot_args.push(Expr::from(FitModel::Polynomial(degree).to_string()));
+    }
+    let expr = Expr::call(GRAPHICS_NAME, vec![Expr::call("scatterplot", scatter_plot_args)]);
+    let expr = context.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -448,4 +672,108 @@ mod tests {
       ]),
     ]));
   }
+
+  #[test]
+  fn test_time_plot_command() {
+    let opts = CommandOptions::default();
+    let points = Expr::call("vector", vec![
+      Expr::call("vector", vec![Expr::from("2024-01-01"), Expr::from(3)]),
+    ]);
+    let output_stack = act_on_stack(&TimePlotCommand::new(), opts, vec![Expr::from(10), points.clone()]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("graphics", vec![Expr::call("timeplot", vec![points])]),
+    ]));
+  }
+
+  #[test]
+  fn test_time_plot_command_with_keep_arg() {
+    let opts = CommandOptions::default().with_keep_modifier();
+    let output_stack = act_on_stack(&TimePlotCommand::new(), opts, vec![10]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("graphics", vec![Expr::call("timeplot", vec![Expr::from(10)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_bar_chart_command() {
+    let opts = CommandOptions::default();
+    let input_stack = vec![10, 20, 30];
+    let output_stack = act_on_stack(&BarChartCommand::new(), opts, input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("graphics", vec![Expr::call("barchart", vec![Expr::from(20), Expr::from(30)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_stacked_bar_command_with_keep_arg() {
+    let opts = CommandOptions::default().with_keep_modifier();
+    let input_stack = vec![10, 20, 30];
+    let output_stack = act_on_stack(&BarChartCommand::stacked(), opts, input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::from(20),
+      Expr::from(30),
+      Expr::call("graphics", vec![Expr::call("stackedbar", vec![Expr::from(20), Expr::from(30)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_pie_chart_command() {
+    let opts = CommandOptions::default();
+    let output_stack = act_on_stack(&PieChartCommand::new(), opts, vec![10, 20]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("graphics", vec![Expr::call("piechart", vec![Expr::from(20)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_pie_chart_command_with_labels() {
+    let opts = CommandOptions::numerical(1);
+    let output_stack = act_on_stack(&PieChartCommand::new(), opts, vec![10, 20, 30]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("graphics", vec![Expr::call("piechart", vec![Expr::from(20), Expr::from(30)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_pie_chart_command_with_keep_arg() {
+    let opts = CommandOptions::default().with_keep_modifier();
+    let output_stack = act_on_stack(&PieChartCommand::new(), opts, vec![10, 20]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::from(20),
+      Expr::call("graphics", vec![Expr::call("piechart", vec![Expr::from(20)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_scatter_plot_command() {
+    let opts = CommandOptions::default();
+    let output_stack = act_on_stack(&ScatterPlotCommand::new(), opts, vec![10, 20]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("graphics", vec![Expr::call("scatterplot", vec![Expr::from(20)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_scatter_plot_command_with_regression() {
+    let opts = CommandOptions::numerical(1).with_keep_modifier();
+    let output_stack = act_on_stack(&ScatterPlotCommand::new(), opts, vec![10]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("graphics", vec![Expr::call("scatterplot", vec![Expr::from(10), Expr::from("poly 1")])]),
+    ]));
+  }
+
+  #[test]
+  fn test_scatter_plot_command_with_nonpositive_arg() {
+    let opts = CommandOptions::numerical(0);
+    act_on_stack(&ScatterPlotCommand::new(), opts, vec![10]).unwrap_err();
+  }
 }
@@ -0,0 +1,221 @@
+
+//! Commands for collapsing runs of stack entries into titled groups.
+
+use super::arguments::{NullaryArgumentSchema, UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+use crate::state::undo::UpdateGroupChange;
+use crate::stack::base::RandomAccessStackLike;
+
+/// This command takes one argument: the title of the group. Collects
+/// entries at the top of the stack into a single group, which the
+/// frontend displays as one collapsible row.
+///
+/// With a numerical argument N, groups the top N entries. Otherwise,
+/// groups the top entry alone. Entries which already belonged to a
+/// group are moved into the new one. The group stays together as
+/// other values are pushed and popped above it.
+///
+/// Fails if the title is empty, if the stack does not have enough
+/// elements, or if the numerical argument is not positive. Does not
+/// use the "keep" modifier, since the stack values are never
+/// modified.
+#[derive(Debug, Default)]
+pub struct GroupStackElemsCommand {
+  _priv: (),
+}
+
+/// This command takes no arguments. Dissolves the group containing a
+/// stack entry, leaving all of its entries in place.
+///
+/// With a numerical argument N, dissolves the group containing the
+/// Nth entry from the top of the stack. Otherwise, uses the top
+/// entry. Fails if that entry does not exist or is not in a group,
+/// or if the numerical argument is not positive.
+#[derive(Debug, Default)]
+pub struct UngroupStackElemsCommand {
+  _priv: (),
+}
+
+impl GroupStackElemsCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl UngroupStackElemsCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+fn positive_argument(context: &CommandContext) -> anyhow::Result<i64> {
+  let arg = context.opts.argument.unwrap_or(1);
+  if arg <= 0 {
+    anyhow::bail!("Expected positive numerical argument");
+  }
+  Ok(arg)
+}
+
+impl Command for GroupStackElemsCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let title = validate_schema(&UnaryArgumentSchema::any(), args)?;
+    let title = title.trim();
+    if title.is_empty() {
+      anyhow::bail!("Expected a group title");
+    }
+    let count = positive_argument(context)?;
+    // Make sure the entries exist before grouping them.
+    state.main_stack().get(count - 1)?;
+
+    state.undo_stack_mut().push_cut();
+    let group = state.stack_groups().new_group(title.to_owned());
+    for index in 0..count as usize {
+      let old_group = state.stack_groups_mut().set(index, Some(group.clone()));
+      state.undo_stack_mut().push_change(UpdateGroupChange::new(index, old_group, Some(group.clone())));
+    }
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for UngroupStackElemsCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let arg = positive_argument(context)?;
+    let index = arg - 1;
+    state.main_stack().get(index)?;
+    let Some(range) = state.stack_groups().group_range(index as usize) else {
+      anyhow::bail!("Stack entry {arg} is not in a group");
+    };
+
+    state.undo_stack_mut().push_cut();
+    for index in range {
+      let old_group = state.stack_groups_mut().set(index, None);
+      state.undo_stack_mut().push_change(UpdateGroupChange::new(index, old_group, None));
+    }
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stack::StackError;
+  use crate::stack::base::StackLike;
+  use crate::state::test_utils::state_for_stack;
+
+  fn group(state: &mut ApplicationState, title: &str, argument: Option<i64>) -> anyhow::Result<CommandOutput> {
+    let mut context = CommandContext::default();
+    context.opts.argument = argument;
+    GroupStackElemsCommand::new().run_command(state, vec![title.to_owned()], &context)
+  }
+
+  fn ungroup(state: &mut ApplicationState, argument: Option<i64>) -> anyhow::Result<CommandOutput> {
+    let mut context = CommandContext::default();
+    context.opts.argument = argument;
+    UngroupStackElemsCommand::new().run_command(state, vec![], &context)
+  }
+
+  fn titles(state: &ApplicationState) -> Vec<Option<String>> {
+    state.stack_groups().for_stack_len(state.main_stack().len())
+      .into_iter()
+      .map(|group| group.map(|group| group.title().to_owned()))
+      .collect()
+  }
+
+  #[test]
+  fn test_group_top_entries() {
+    let mut state = state_for_stack(vec![10, 20, 30, 40]);
+    group(&mut state, " raw data ", Some(3)).unwrap();
+    let data = Some("raw data".to_owned());
+    assert_eq!(titles(&state), vec![None, data.clone(), data.clone(), data]);
+    assert_eq!(state.stack_groups().group_range(1), Some(0..3));
+  }
+
+  #[test]
+  fn test_group_follows_entries() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    group(&mut state, "data", Some(2)).unwrap();
+    state.main_stack_mut().push(40.into());
+    assert_eq!(state.stack_groups().group_range(1), Some(1..3));
+    state.main_stack_mut().pop().unwrap();
+    state.main_stack_mut().pop().unwrap();
+    assert_eq!(state.stack_groups().group_range(0), Some(0..1));
+  }
+
+  #[test]
+  fn test_regroup_replaces_old_group() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    group(&mut state, "a", Some(3)).unwrap();
+    group(&mut state, "b", None).unwrap();
+    let a = Some("a".to_owned());
+    assert_eq!(titles(&state), vec![a.clone(), a, Some("b".to_owned())]);
+    assert_eq!(state.stack_groups().group_range(1), Some(1..3));
+  }
+
+  #[test]
+  fn test_ungroup() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    group(&mut state, "data", Some(2)).unwrap();
+    ungroup(&mut state, Some(2)).unwrap();
+    assert!(state.stack_groups().is_empty());
+    let err = ungroup(&mut state, None).unwrap_err();
+    assert_eq!(err.to_string(), "Stack entry 1 is not in a group");
+  }
+
+  #[test]
+  fn test_group_is_undoable() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    group(&mut state, "data", Some(2)).unwrap();
+    ungroup(&mut state, None).unwrap();
+    state.undo().unwrap();
+    assert_eq!(state.stack_groups().group_range(0), Some(0..2));
+    state.undo().unwrap();
+    assert!(state.stack_groups().is_empty());
+    state.redo().unwrap();
+    assert_eq!(state.stack_groups().group_range(0), Some(0..2));
+  }
+
+  #[test]
+  fn test_group_restored_when_pop_undone() {
+    let mut state = state_for_stack(vec![10, 20]);
+    group(&mut state, "data", Some(2)).unwrap();
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().pop().unwrap();
+    state.undo().unwrap();
+    assert_eq!(state.stack_groups().group_range(0), Some(0..2));
+  }
+
+  #[test]
+  fn test_group_with_bad_arguments() {
+    let mut state = state_for_stack(vec![10]);
+    let err = group(&mut state, "data", Some(2)).unwrap_err();
+    let err = err.downcast::<StackError>().unwrap();
+    assert_eq!(err, StackError::NotEnoughElements { expected: 2, actual: 1 });
+    group(&mut state, "data", Some(0)).unwrap_err();
+    group(&mut state, "  ", None).unwrap_err();
+    assert!(state.stack_groups().is_empty());
+  }
+}
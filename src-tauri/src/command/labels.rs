@@ -0,0 +1,139 @@
+
+//! Commands for annotating stack entries with text labels.
+
+use super::arguments::{UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+use crate::state::undo::UpdateLabelChange;
+use crate::stack::base::RandomAccessStackLike;
+
+/// This command takes one argument: the label text. Attaches that
+/// label to a stack entry, replacing any label it already had. An
+/// empty (or entirely whitespace) label removes the entry's label.
+///
+/// With a numerical argument N, labels the Nth entry from the top of
+/// the stack. Otherwise, labels the top entry. The label stays with
+/// the entry as other values are pushed and popped, and it is
+/// discarded once the entry itself is consumed.
+///
+/// Fails if the stack does not have enough elements or if the
+/// numerical argument is not positive. Does not use the "keep"
+/// modifier, since the stack values are never modified.
+#[derive(Debug, Default)]
+pub struct LabelStackElemCommand {
+  _priv: (),
+}
+
+impl LabelStackElemCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Command for LabelStackElemCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let label = validate_schema(&UnaryArgumentSchema::any(), args)?;
+    let label = label.trim();
+    let label = (!label.is_empty()).then(|| label.to_owned());
+
+    let arg = context.opts.argument.unwrap_or(1);
+    if arg <= 0 {
+      anyhow::bail!("Expected positive numerical argument");
+    }
+    let index = arg - 1;
+    // Make sure the entry exists before labeling it.
+    state.main_stack().get(index)?;
+
+    state.undo_stack_mut().push_cut();
+    let index = index as usize;
+    let old_label = state.stack_labels_mut().set(index, label.clone());
+    state.undo_stack_mut().push_change(UpdateLabelChange::new(index, old_label, label));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stack::StackError;
+  use crate::stack::base::StackLike;
+  use crate::state::test_utils::state_for_stack;
+
+  fn label_top(state: &mut ApplicationState, label: &str, argument: Option<i64>) -> anyhow::Result<CommandOutput> {
+    let mut context = CommandContext::default();
+    context.opts.argument = argument;
+    LabelStackElemCommand::new().run_command(state, vec![label.to_owned()], &context)
+  }
+
+  #[test]
+  fn test_label_top_of_stack() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    label_top(&mut state, "  total  ", None).unwrap();
+    assert_eq!(state.stack_labels().for_stack_len(3), vec![None, None, Some("total".to_owned())]);
+  }
+
+  #[test]
+  fn test_label_with_numerical_argument() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    label_top(&mut state, "first", Some(3)).unwrap();
+    assert_eq!(state.stack_labels().get(2), Some("first"));
+  }
+
+  #[test]
+  fn test_empty_label_clears() {
+    let mut state = state_for_stack(vec![10]);
+    label_top(&mut state, "x", None).unwrap();
+    label_top(&mut state, " ", None).unwrap();
+    assert!(state.stack_labels().is_empty());
+  }
+
+  #[test]
+  fn test_label_follows_entry() {
+    let mut state = state_for_stack(vec![10, 20]);
+    label_top(&mut state, "twenty", None).unwrap();
+    state.main_stack_mut().push(30.into());
+    assert_eq!(state.stack_labels().get(1), Some("twenty"));
+    state.main_stack_mut().pop().unwrap();
+    state.main_stack_mut().pop().unwrap();
+    assert!(state.stack_labels().is_empty());
+    state.main_stack_mut().push(40.into());
+    assert_eq!(state.stack_labels().get(0), None);
+  }
+
+  #[test]
+  fn test_label_restored_on_undo() {
+    let mut state = state_for_stack(vec![10, 20]);
+    label_top(&mut state, "twenty", None).unwrap();
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().pop().unwrap();
+    assert_eq!(state.stack_labels().get(0), None);
+    state.undo().unwrap();
+    assert_eq!(state.stack_labels().get(0), Some("twenty"));
+    state.undo().unwrap();
+    assert!(state.stack_labels().is_empty());
+    state.redo().unwrap();
+    assert_eq!(state.stack_labels().get(0), Some("twenty"));
+  }
+
+  #[test]
+  fn test_label_out_of_bounds() {
+    let mut state = state_for_stack(vec![10]);
+    let err = label_top(&mut state, "x", Some(2)).unwrap_err();
+    let err = err.downcast::<StackError>().unwrap();
+    assert_eq!(err, StackError::NotEnoughElements { expected: 2, actual: 1 });
+    label_top(&mut state, "x", Some(0)).unwrap_err();
+    assert!(state.stack_labels().is_empty());
+  }
+}
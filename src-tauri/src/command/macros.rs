@@ -0,0 +1,185 @@
+
+//! Commands for recording and replaying keyboard macros.
+//!
+//! The recording itself happens as commands are run from the
+//! frontend; see [`MacroRecorder`](crate::state::macros::MacroRecorder).
+
+use super::arguments::{NullaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::program::{Program, Instruction};
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+use crate::state::macros::MacroError;
+
+/// Begins recording a macro. Every math command run from the frontend
+/// until the next `end_macro_record` becomes part of the macro. Fails
+/// if a macro is already being recorded.
+#[derive(Debug, Default)]
+pub struct StartMacroRecordCommand {
+  _priv: (),
+}
+
+/// Finishes recording the current macro, which replaces any macro
+/// recorded previously. Fails if no macro is being recorded.
+#[derive(Debug, Default)]
+pub struct EndMacroRecordCommand {
+  _priv: (),
+}
+
+/// Replays the most recently recorded macro. The whole replay is a
+/// single undo action, and if any step fails then every step is
+/// rolled back.
+#[derive(Debug, Default)]
+pub struct PlayMacroCommand {
+  _priv: (),
+}
+
+impl StartMacroRecordCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl EndMacroRecordCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl PlayMacroCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Command for StartMacroRecordCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.macro_recorder_mut().start_recording()?;
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for EndMacroRecordCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.macro_recorder_mut().end_recording()?;
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for PlayMacroCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    if state.macro_recorder().is_recording() {
+      anyhow::bail!(MacroError::PlayWhileRecording);
+    }
+    let steps = state.macro_recorder().last_macro().ok_or(MacroError::NoMacro)?;
+    let program = Program::new(steps.iter().cloned().map(Instruction::Run).collect());
+
+    state.undo_stack_mut().push_cut();
+    let mark = state.undo_stack().mark();
+    match program.execute(state, context) {
+      Ok(errors) => {
+        state.undo_stack_mut().merge_since_mark(mark);
+        Ok(CommandOutput::from_errors(errors))
+      }
+      Err(err) => {
+        state.revert_to_undo_mark(mark);
+        Err(err.into())
+      }
+    }
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::program::ProgramStep;
+  use crate::command::program::test_utils::setup_default_dispatch_table;
+  use crate::command::test_utils::setup_default_simplifier;
+  use crate::stack::test_utils::stack_of;
+  use crate::state::test_utils::state_for_stack;
+
+  fn context() -> CommandContext<'static, 'static, 'static> {
+    let mut context = CommandContext::default();
+    setup_default_simplifier(&mut vec![], &mut ApplicationState::new(), &mut context);
+    setup_default_dispatch_table(&mut vec![], &mut ApplicationState::new(), &mut context);
+    context
+  }
+
+  fn record_macro(state: &mut ApplicationState, steps: Vec<ProgramStep>) {
+    StartMacroRecordCommand::new().run_command(state, vec![], &context()).unwrap();
+    for step in steps {
+      state.macro_recorder_mut().record(step);
+    }
+    EndMacroRecordCommand::new().run_command(state, vec![], &context()).unwrap();
+  }
+
+  #[test]
+  fn test_play_macro() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    record_macro(&mut state, vec![ProgramStep::new("+"), ProgramStep::new("dup")]);
+    PlayMacroCommand::new().run_command(&mut state, vec![], &context()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 50, 50]));
+    PlayMacroCommand::new().run_command(&mut state, vec![], &context()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 100, 100]));
+  }
+
+  #[test]
+  fn test_play_macro_is_one_undo_action() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    record_macro(&mut state, vec![ProgramStep::new("+"), ProgramStep::new("dup")]);
+    PlayMacroCommand::new().run_command(&mut state, vec![], &context()).unwrap();
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+    state.redo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 50, 50]));
+  }
+
+  #[test]
+  fn test_play_macro_rolls_back_on_failure() {
+    let mut state = state_for_stack(vec![10, 20]);
+    record_macro(&mut state, vec![ProgramStep::new("+"), ProgramStep::new("+")]);
+    PlayMacroCommand::new().run_command(&mut state, vec![], &context()).unwrap_err();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20]));
+  }
+
+  #[test]
+  fn test_play_macro_errors() {
+    let mut state = state_for_stack(vec![10]);
+    let err = PlayMacroCommand::new().run_command(&mut state, vec![], &context()).unwrap_err();
+    assert_eq!(err.downcast::<MacroError>().unwrap(), MacroError::NoMacro);
+    StartMacroRecordCommand::new().run_command(&mut state, vec![], &context()).unwrap();
+    let err = PlayMacroCommand::new().run_command(&mut state, vec![], &context()).unwrap_err();
+    assert_eq!(err.downcast::<MacroError>().unwrap(), MacroError::PlayWhileRecording);
+  }
+}
@@ -1,26 +1,46 @@
 
 pub mod accum;
+pub mod alt_stack;
 pub mod algebra;
 pub mod arguments;
+pub mod assumptions;
+pub mod background;
 mod base;
+pub mod benchmark;
+pub mod bookmarks;
 pub mod calculus;
+pub mod confirmation;
+pub mod currency;
 pub mod dispatch;
+pub mod files;
 pub mod flag_dispatch;
 pub mod functional;
 pub mod general;
 pub mod graphics;
+pub mod groups;
 pub mod input;
+pub mod labels;
+pub mod macros;
 pub mod modes;
 pub mod nullary;
 pub mod options;
+pub mod pins;
+pub mod program;
+pub mod random;
+pub mod rewrite;
+pub mod session;
 pub mod shuffle;
 pub mod statistics;
+pub mod stopwatch;
 pub mod subcommand;
+pub mod templates;
 pub mod units;
+pub mod user_functions;
 pub mod variables;
 pub mod vector;
 
 pub use base::{Command, CommandContext, CommandOutput};
+use background::RunInBackground;
 use functional::{PushConstantCommand, UnaryFunctionCommand, BinaryFunctionCommand};
 use statistics::DatasetDrivenCommand;
 use dispatch::CommandDispatchTable;
@@ -49,7 +69,10 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
   map.insert("*".to_string(), Box::new(BinaryFunctionCommand::named("*")));
   map.insert("/".to_string(), Box::new(BinaryFunctionCommand::named("/")));
   map.insert("%".to_string(), Box::new(BinaryFunctionCommand::named("%")));
-  map.insert("div".to_string(), Box::new(BinaryFunctionCommand::named("div")));
+  map.insert("div".to_string(), Box::new(dispatch_on_hyper_command(
+    BinaryFunctionCommand::named("div"),
+    BinaryFunctionCommand::named("divmod"),
+  )));
   map.insert("^".to_string(), Box::new(dispatch_on_inverse_command(
     BinaryFunctionCommand::named("^"),
     BinaryFunctionCommand::new(nroot),
@@ -105,8 +128,28 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
     inv_hyper_flag: UnaryFunctionCommand::named("atanh"),
   })));
 
+  // Bitwise operations
+  map.insert("band".to_string(), Box::new(BinaryFunctionCommand::named("band")));
+  map.insert("bor".to_string(), Box::new(BinaryFunctionCommand::named("bor")));
+  map.insert("bxor".to_string(), Box::new(BinaryFunctionCommand::named("bxor")));
+  map.insert("bnot".to_string(), Box::new(UnaryFunctionCommand::named("bnot")));
+  map.insert("lsh".to_string(), Box::new(dispatch_on_flags_command(FlagDispatchArgs {
+    no_flags: BinaryFunctionCommand::named("lsh"),
+    hyper_flag: UnaryFunctionCommand::named("lsh"),
+    inv_flag: BinaryFunctionCommand::named("rsh"),
+    inv_hyper_flag: UnaryFunctionCommand::named("rsh"),
+  })));
+  map.insert("rsh".to_string(), Box::new(dispatch_on_flags_command(FlagDispatchArgs {
+    no_flags: BinaryFunctionCommand::named("rsh"),
+    hyper_flag: UnaryFunctionCommand::named("rsh"),
+    inv_flag: BinaryFunctionCommand::named("lsh"),
+    inv_hyper_flag: UnaryFunctionCommand::named("lsh"),
+  })));
+
   // Stack shuffling (no arguments)
-  map.insert("pop".to_string(), Box::new(shuffle::PopCommand));
+  map.insert("pop".to_string(), Box::new(shuffle::pop_command()));
+  map.insert("clear_stack".to_string(), Box::new(shuffle::clear_stack_command()));
+  map.insert("reset_session".to_string(), Box::new(session::reset_session_command()));
   map.insert("swap".to_string(), Box::new(shuffle::SwapCommand));
   map.insert("dup".to_string(), Box::new(shuffle::DupCommand));
 
@@ -121,6 +164,10 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
   map.insert("incomplete(".to_string(), Box::new(PushConstantCommand::new(IncompleteObject::new(ObjectType::LeftParen))));
   map.insert("incomplete]".to_string(), Box::new(vector::VectorFromIncompleteObjectCommand::new()));
   map.insert("incomplete)".to_string(), Box::new(vector::ComplexFromIncompleteObjectCommand::new()));
+  map.insert("incomplete{".to_string(), Box::new(PushConstantCommand::new(IncompleteObject::new(ObjectType::LeftBrace))));
+  map.insert("incomplete}".to_string(), Box::new(vector::AggregateFromIncompleteObjectCommand::set()));
+  map.insert("incomplete<".to_string(), Box::new(PushConstantCommand::new(IncompleteObject::new(ObjectType::LeftAngle))));
+  map.insert("incomplete>".to_string(), Box::new(vector::AggregateFromIncompleteObjectCommand::tuple()));
 
   // Constants (no arguments)
   map.insert("infinity".to_string(), Box::new(PushConstantCommand::new(InfiniteConstant::PosInfinity)));
@@ -153,12 +200,29 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
   )));
   map.insert("abs".to_string(), Box::new(UnaryFunctionCommand::named("abs")));
   map.insert("signum".to_string(), Box::new(UnaryFunctionCommand::named("signum")));
+  map.insert("round".to_string(), Box::new(UnaryFunctionCommand::named("round")));
   map.insert("conj".to_string(), Box::new(UnaryFunctionCommand::named("conj")));
   map.insert("arg".to_string(), Box::new(UnaryFunctionCommand::named("arg")));
+  map.insert("arg_deg".to_string(), Box::new(UnaryFunctionCommand::named("arg_deg")));
   map.insert("re".to_string(), Box::new(UnaryFunctionCommand::named("re")));
   map.insert("im".to_string(), Box::new(UnaryFunctionCommand::named("im")));
   map.insert("lowercase".to_string(), Box::new(UnaryFunctionCommand::named("lowercase")));
   map.insert("uppercase".to_string(), Box::new(UnaryFunctionCommand::named("uppercase")));
+  map.insert("url_encode".to_string(), Box::new(UnaryFunctionCommand::named("url_encode")));
+  map.insert("url_decode".to_string(), Box::new(UnaryFunctionCommand::named("url_decode")));
+  map.insert("html_escape".to_string(), Box::new(UnaryFunctionCommand::named("html_escape")));
+  map.insert("html_unescape".to_string(), Box::new(UnaryFunctionCommand::named("html_unescape")));
+  map.insert("moon_phase".to_string(), Box::new(UnaryFunctionCommand::named("moon_phase")));
+  map.insert("geo_distance".to_string(), Box::new(BinaryFunctionCommand::named("geo_distance")));
+  map.insert("bearing".to_string(), Box::new(BinaryFunctionCommand::named("bearing")));
+  map.insert("solve_triangle".to_string(), Box::new(UnaryFunctionCommand::named("solve_triangle")));
+  map.insert("prime_pi".to_string(), Box::new(UnaryFunctionCommand::named("prime_pi")));
+  map.insert("nth_prime".to_string(), Box::new(UnaryFunctionCommand::named("nth_prime")));
+  map.insert("prime_range".to_string(), Box::new(BinaryFunctionCommand::named("prime_range")));
+  map.insert("modinv".to_string(), Box::new(BinaryFunctionCommand::named("modinv")));
+  map.insert("crt".to_string(), Box::new(BinaryFunctionCommand::named("crt")));
+  map.insert("rsa_keygen".to_string(), Box::new(UnaryFunctionCommand::named("rsa_keygen")));
+  map.insert("guess_sequence".to_string(), Box::new(UnaryFunctionCommand::named("guess_sequence")));
   map.insert("=".to_string(), Box::new(BinaryFunctionCommand::named("=")));
   map.insert("!=".to_string(), Box::new(BinaryFunctionCommand::named("!=")));
   map.insert("<".to_string(), Box::new(BinaryFunctionCommand::named("<")));
@@ -167,13 +231,28 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
   map.insert(">=".to_string(), Box::new(BinaryFunctionCommand::named(">=")));
   map.insert("plot".to_string(), Box::new(graphics::PlotCommand::new()));
   map.insert("contourplot".to_string(), Box::new(graphics::ContourPlotCommand::new()));
+  map.insert("timeplot".to_string(), Box::new(graphics::TimePlotCommand::new()));
+  map.insert("barchart".to_string(), Box::new(graphics::BarChartCommand::new()));
+  map.insert("stackedbar".to_string(), Box::new(graphics::BarChartCommand::stacked()));
+  map.insert("piechart".to_string(), Box::new(graphics::PieChartCommand::new()));
+  map.insert("scatterplot".to_string(), Box::new(graphics::ScatterPlotCommand::new()));
   map.insert("xy".to_string(), Box::new(BinaryFunctionCommand::named("xy")));
 
   // Mode commands
+  map.insert("reset_modes".to_string(), Box::new(modes::reset_modes_command()));
   map.insert("toggle_graphics".to_string(), Box::new(modes::toggle_graphics_command()));
   map.insert("toggle_unicode".to_string(), Box::new(modes::toggle_unicode_command()));
+  map.insert("toggle_twos_complement".to_string(), Box::new(modes::toggle_twos_complement_command()));
+  map.insert("toggle_always_parenthesize".to_string(), Box::new(modes::toggle_always_parenthesize_command()));
+  map.insert("toggle_spoken_text".to_string(), Box::new(modes::toggle_spoken_text_command()));
   map.insert("toggle_infinity".to_string(), Box::new(modes::toggle_infinity_command()));
   map.insert("toggle_fractional".to_string(), Box::new(modes::toggle_fractional_command()));
+  map.insert("toggle_exact_matrix".to_string(), Box::new(modes::toggle_exact_matrix_command()));
+  map.insert("toggle_units".to_string(), Box::new(modes::toggle_units_command()));
+  map.insert("toggle_derived_units".to_string(), Box::new(modes::toggle_derived_units_command()));
+  map.insert("toggle_branch_cut".to_string(), Box::new(modes::toggle_branch_cut_command()));
+  map.insert("toggle_degrees".to_string(), Box::new(modes::toggle_degrees_command()));
+  map.insert("toggle_polar_display".to_string(), Box::new(modes::toggle_polar_display_command()));
   map.insert("set_basic_language_mode".to_string(), Box::new(modes::SetLanguageModeCommand::basic_language_mode()));
   map.insert("set_fancy_language_mode".to_string(), Box::new(modes::SetLanguageModeCommand::fancy_language_mode()));
 
@@ -185,6 +264,10 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
   map.insert("convert_units_with_context".to_string(), Box::new(units::ContextualConvertUnitsCommand::new()));
   map.insert("convert_temp".to_string(), Box::new(units::ConvertTemperatureCommand::new()));
   map.insert("convert_temp_with_context".to_string(), Box::new(units::ContextualConvertTemperatureCommand::new()));
+  map.insert("define_conversion_context".to_string(), Box::new(units::DefineConversionContextCommand::new()));
+  map.insert("remove_conversion_context".to_string(), Box::new(units::RemoveConversionContextCommand::new()));
+  map.insert("convert_currency".to_string(), Box::new(currency::ConvertCurrencyCommand::new()));
+  map.insert("set_currency_rates".to_string(), Box::new(currency::SetCurrencyRatesCommand::new()));
 
   // Vector commands
   map.insert("subvector".to_string(), Box::new(dispatch_on_hyper_command(
@@ -222,6 +305,14 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
   map.insert("outerprod".to_string(), Box::new(accum::OuterProductCommand::new()));
   map.insert("innerprod".to_string(), Box::new(accum::InnerProductCommand::new()));
 
+  // Set commands
+  map.insert("union".to_string(), Box::new(BinaryFunctionCommand::named("union")));
+  map.insert("intersection".to_string(), Box::new(BinaryFunctionCommand::named("intersection")));
+  map.insert("difference".to_string(), Box::new(BinaryFunctionCommand::named("difference")));
+  map.insert("powerset".to_string(), Box::new(UnaryFunctionCommand::named("powerset")));
+  map.insert("cardinality".to_string(), Box::new(UnaryFunctionCommand::named("cardinality")));
+  map.insert("member".to_string(), Box::new(BinaryFunctionCommand::named("member")));
+
   // Vector statistics commands
   map.insert("mean".to_string(), Box::new(dispatch_on_flags_command(FlagDispatchArgs {
     no_flags: DatasetDrivenCommand::named("mean"),
@@ -243,6 +334,12 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
     inv_flag: statistics::pop_covar_command(),
     inv_hyper_flag: statistics::correlation_command(), // Note: We may make this one do something different later
   })));
+  map.insert("fit".to_string(), Box::new(statistics::FitCommand::new()));
+  map.insert("histogram".to_string(), Box::new(dispatch_on_hyper_command(
+    statistics::HistogramCommand::by_count(),
+    statistics::HistogramCommand::by_width(),
+  )));
+  map.insert("summary".to_string(), Box::new(DatasetDrivenCommand::named("summary")));
 
   // Matrix commands
   map.insert("identity_matrix".to_string(), Box::new(vector::IdentityMatrixCommand::new()));
@@ -258,10 +355,18 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
     UnaryFunctionCommand::named("transpose"),
     UnaryFunctionCommand::new(conj_transpose),
   )));
-  map.insert("det".to_string(), Box::new(UnaryFunctionCommand::named("det")));
+  map.insert("det".to_string(), Box::new(RunInBackground::new(UnaryFunctionCommand::named("det"))));
   map.insert("trace".to_string(), Box::new(UnaryFunctionCommand::named("trace")));
   map.insert("@".to_string(), Box::new(BinaryFunctionCommand::named("@")));
   map.insert("kron".to_string(), Box::new(BinaryFunctionCommand::named("kron")));
+  map.insert("matexp".to_string(), Box::new(RunInBackground::new(UnaryFunctionCommand::named("matexp"))));
+  map.insert("expm".to_string(), Box::new(RunInBackground::new(UnaryFunctionCommand::named("expm"))));
+  map.insert("matpow".to_string(), Box::new(RunInBackground::new(BinaryFunctionCommand::named("matpow"))));
+  map.insert("matlog".to_string(), Box::new(RunInBackground::new(UnaryFunctionCommand::named("matlog"))));
+  map.insert("lu".to_string(), Box::new(UnaryFunctionCommand::named("lu")));
+  map.insert("qr".to_string(), Box::new(UnaryFunctionCommand::named("qr")));
+  map.insert("cholesky".to_string(), Box::new(UnaryFunctionCommand::named("cholesky")));
+  map.insert("svd".to_string(), Box::new(UnaryFunctionCommand::named("svd")));
 
   // Commands which accept a single string.
   map.insert("push_number".to_string(), Box::new(input::push_number_command()));
@@ -272,14 +377,86 @@ pub fn default_dispatch_table() -> CommandDispatchTable {
   map.insert("manual_substitute".to_string(), Box::new(variables::SubstituteVarCommand::new()));
   map.insert("store_var".to_string(), Box::new(variables::StoreVarCommand::new()));
   map.insert("unbind_var".to_string(), Box::new(variables::UnbindVarCommand::new()));
+  map.insert("clear_vars".to_string(), Box::new(variables::ClearVarsCommand::new()));
+  map.insert("define_function".to_string(), Box::new(user_functions::DefineFunctionCommand::new()));
+  map.insert("define_derivative".to_string(), Box::new(user_functions::DefineDerivativeCommand::new()));
+  map.insert("add_rewrite_rule".to_string(), Box::new(rewrite::AddRewriteRuleCommand::new()));
+  map.insert("match_expr".to_string(), Box::new(rewrite::MatchExprCommand::new()));
+  map.insert("insert_template".to_string(), Box::new(templates::InsertTemplateCommand::new()));
+  map.insert("fill_slot".to_string(), Box::new(templates::FillSlotCommand::new()));
+  map.insert("declare".to_string(), Box::new(assumptions::DeclareCommand::new()));
+  map.insert("undeclare".to_string(), Box::new(assumptions::UndeclareCommand::new()));
+  map.insert("bookmark".to_string(), Box::new(bookmarks::BookmarkCommand::new()));
+  map.insert("recall_bookmark".to_string(), Box::new(bookmarks::RecallBookmarkCommand::new()));
+  map.insert("label_stack_elem".to_string(), Box::new(labels::LabelStackElemCommand::new()));
+  map.insert("pin_stack_elem".to_string(), Box::new(pins::PinStackElemCommand::pin()));
+  map.insert("unpin_stack_elem".to_string(), Box::new(pins::PinStackElemCommand::unpin()));
+  map.insert("group".to_string(), Box::new(groups::GroupStackElemsCommand::new()));
+  map.insert("ungroup".to_string(), Box::new(groups::UngroupStackElemsCommand::new()));
+  map.insert("to_alt_stack".to_string(), Box::new(alt_stack::ToAltStackCommand));
+  map.insert("from_alt_stack".to_string(), Box::new(alt_stack::FromAltStackCommand));
+  map.insert("swap_stacks".to_string(), Box::new(alt_stack::SwapStacksCommand));
   map.insert("deriv".to_string(), Box::new(calculus::DerivativeCommand::new()));
+  map.insert("num_deriv".to_string(), Box::new(calculus::NumericalDerivativeCommand::new()));
+  map.insert("integrate".to_string(), Box::new(RunInBackground::new(calculus::IntegralCommand::new())));
+  map.insert("num_integrate".to_string(), Box::new(RunInBackground::new(calculus::NumericalIntegralCommand::new())));
+  map.insert("limit".to_string(), Box::new(RunInBackground::new(calculus::LimitCommand::new())));
+  map.insert("limit_left".to_string(), Box::new(calculus::LimitCommand::left()));
+  map.insert("limit_right".to_string(), Box::new(calculus::LimitCommand::right()));
+  map.insert("mc_integrate".to_string(), Box::new(RunInBackground::new(calculus::MonteCarloCommand::integrate())));
+  map.insert("mc_estimate".to_string(), Box::new(RunInBackground::new(calculus::MonteCarloCommand::estimate())));
+  map.insert("sensitivity".to_string(), Box::new(BinaryFunctionCommand::named("sensitivity")));
   map.insert("find_root".to_string(), Box::new(algebra::FindRootCommand::new()));
+  map.insert("global_min".to_string(), Box::new(RunInBackground::new(algebra::GlobalMinCommand::new())));
+  map.insert("isolate".to_string(), Box::new(algebra::IsolateCommand::new()));
+  map.insert("solve_inequality".to_string(), Box::new(algebra::SolveInequalityCommand::new()));
+  map.insert("domain".to_string(), Box::new(algebra::DomainCommand::new()));
+  map.insert("sum".to_string(), Box::new(algebra::SeriesCommand::sum()));
+  map.insert("prod".to_string(), Box::new(algebra::SeriesCommand::product()));
 
   // Specialized commands
   map.insert("mouse_move_stack_elem".to_string(), Box::new(shuffle::MoveStackElemCommand));
   map.insert("mouse_replace_stack_elem".to_string(), Box::new(shuffle::ReplaceStackElemCommand { is_mouse_interaction: true }));
   map.insert("replace_stack_elem".to_string(), Box::new(shuffle::ReplaceStackElemCommand { is_mouse_interaction: false }));
+  map.insert("modify_stack_elem".to_string(), Box::new(shuffle::ModifyStackElemCommand));
+  map.insert("delete_range".to_string(), Box::new(shuffle::delete_range_command()));
+  map.insert("duplicate_range".to_string(), Box::new(shuffle::DuplicateRangeCommand));
+  map.insert("rotate_range".to_string(), Box::new(shuffle::RotateRangeCommand));
+  map.insert("collect_range_to_vector".to_string(), Box::new(shuffle::CollectRangeToVectorCommand));
   map.insert("set_display_radix".to_string(), Box::new(modes::SetDisplayRadixCommand::new()));
+  map.insert("set_locale".to_string(), Box::new(modes::SetLocaleCommand::new()));
+  map.insert("set_float_format".to_string(), Box::new(modes::SetFloatFormatCommand::new()));
+  map.insert("set_max_display_elements".to_string(), Box::new(modes::SetMaxDisplayElementsCommand::new()));
+  map.insert("set_modulus".to_string(), Box::new(modes::SetModulusCommand::new()));
+  map.insert("set_precision".to_string(), Box::new(modes::SetPrecisionCommand::new()));
+  map.insert("set_word_size".to_string(), Box::new(modes::SetWordSizeCommand::new()));
+  map.insert("set_rounding_mode".to_string(), Box::new(modes::SetRoundingModeCommand::new()));
+  map.insert("set_undo_limit".to_string(), Box::new(dispatch_on_hyper_command(
+    session::SetUndoLimitCommand::depth(),
+    session::SetUndoLimitCommand::memory(),
+  )));
+
+  // Files
+  map.insert("export_transcript".to_string(), Box::new(files::ExportTranscriptCommand::new()));
+  map.insert("export_expr_file".to_string(), Box::new(files::ExportExprFileCommand::new()));
+  map.insert("import_expr_file".to_string(), Box::new(files::ImportExprFileCommand::new()));
+
+  // Programs
+  map.insert("eval_program".to_string(), Box::new(program::EvalProgramCommand::new()));
+  map.insert("if_then".to_string(), Box::new(program::IfThenCommand::new()));
+  map.insert("repeat_program".to_string(), Box::new(program::RepeatProgramCommand::new()));
+  map.insert("while_program".to_string(), Box::new(program::WhileProgramCommand::new()));
+  map.insert("step_program".to_string(), Box::new(program::StepProgramCommand::new()));
+  map.insert("start_macro_record".to_string(), Box::new(macros::StartMacroRecordCommand::new()));
+  map.insert("end_macro_record".to_string(), Box::new(macros::EndMacroRecordCommand::new()));
+  map.insert("play_macro".to_string(), Box::new(macros::PlayMacroCommand::new()));
+  map.insert("stopwatch_start".to_string(), Box::new(stopwatch::StopwatchStartCommand::new()));
+  map.insert("stopwatch_lap".to_string(), Box::new(stopwatch::StopwatchLapCommand::new()));
+  map.insert("stopwatch_stop".to_string(), Box::new(stopwatch::StopwatchStopCommand::new()));
+  map.insert("timer".to_string(), Box::new(stopwatch::TimerCommand::new()));
+  map.insert("benchmark".to_string(), Box::new(benchmark::BenchmarkCommand::new()));
+  map.insert("random_expr".to_string(), Box::new(random::RandomExprCommand::new()));
+  map.insert("check_simplifier".to_string(), Box::new(random::CheckSimplifierCommand::new()));
 
   CommandDispatchTable::from_hash_map(map)
 }
@@ -14,9 +14,13 @@ use crate::undo::UndoableChange;
 use crate::state::{ApplicationState, UndoableState};
 use crate::state::undo::ToggleFlagChange;
 use crate::util::radix::{Radix, StringToRadix};
-use crate::mode::display::language::LanguageMode;
-use crate::mode::display::language::basic::BasicLanguageMode;
-use crate::mode::display::language::fancy::FancyLanguageMode;
+use crate::mode::display::language::{LanguageMode, LanguageSettings, StringToDisplayLimit};
+use crate::mode::display::export::named_language_mode;
+use crate::mode::display::DisplaySettings;
+use crate::mode::display::locale::{Locale, StringToLocale};
+use crate::mode::display::float_format::{FloatFormat, StringToFloatFormat};
+use crate::mode::calculation::{CalculationMode, BranchCut, AngleMode, WordSize, RoundingMode,
+                               StringToModulus, StringToPrecision, StringToWordSize, StringToRoundingMode};
 
 use std::sync::Arc;
 
@@ -28,6 +32,62 @@ pub struct SetDisplayRadixChange {
   pub new_value: Radix,
 }
 
+/// [`UndoableChange`] which changes the display settings' locale to
+/// a given value.
+#[derive(Clone, Debug)]
+pub struct SetLocaleChange {
+  pub old_value: Locale,
+  pub new_value: Locale,
+}
+
+/// [`UndoableChange`] which changes the display settings' float
+/// format to a given value.
+#[derive(Clone, Debug)]
+pub struct SetFloatFormatChange {
+  pub old_value: FloatFormat,
+  pub new_value: FloatFormat,
+}
+
+/// [`UndoableChange`] which sets or clears the maximum number of
+/// elements displayed in a vector or matrix.
+#[derive(Clone, Debug)]
+pub struct SetMaxDisplayElementsChange {
+  pub old_value: Option<usize>,
+  pub new_value: Option<usize>,
+}
+
+/// [`UndoableChange`] which sets or clears the modulus for modular
+/// arithmetic mode.
+#[derive(Clone, Debug)]
+pub struct SetModulusChange {
+  pub old_value: Option<u64>,
+  pub new_value: Option<u64>,
+}
+
+/// [`UndoableChange`] which sets or clears the precision for
+/// arbitrary-precision mode.
+#[derive(Clone, Debug)]
+pub struct SetPrecisionChange {
+  pub old_value: Option<u32>,
+  pub new_value: Option<u32>,
+}
+
+/// [`UndoableChange`] which sets or clears the word size for the
+/// bitwise functions.
+#[derive(Clone, Debug)]
+pub struct SetWordSizeChange {
+  pub old_value: Option<WordSize>,
+  pub new_value: Option<WordSize>,
+}
+
+/// [`UndoableChange`] which sets the rounding mode, for both
+/// calculation and display.
+#[derive(Clone, Debug)]
+pub struct SetRoundingModeChange {
+  pub old_value: RoundingMode,
+  pub new_value: RoundingMode,
+}
+
 /// [`UndoableChange`] which sets the engine's language mode to the
 /// given value.
 #[derive(Clone)]
@@ -36,6 +96,16 @@ pub struct SetLanguageModeChange {
   pub new_value: Arc<dyn LanguageMode + Send + Sync>,
 }
 
+/// [`UndoableChange`] which replaces the display settings and
+/// calculation mode wholesale.
+#[derive(Clone)]
+pub struct ResetModesChange {
+  pub old_display_settings: DisplaySettings,
+  pub old_calculation_mode: CalculationMode,
+  pub new_display_settings: DisplaySettings,
+  pub new_calculation_mode: CalculationMode,
+}
+
 /// Command which sets the display radix to the given value. Expects a
 /// single radix value (per [`StringToRadix`]) as argument. Does not
 /// use the keep modifier or numerical argument.
@@ -44,6 +114,70 @@ pub struct SetDisplayRadixCommand {
   _priv: (),
 }
 
+/// Command which sets the locale used for reading and writing
+/// numbers. Expects a single locale name (per [`StringToLocale`]) as
+/// argument. Does not use the keep modifier or numerical argument.
+#[derive(Debug, Clone, Default)]
+pub struct SetLocaleCommand {
+  _priv: (),
+}
+
+/// Command which sets the notation used to write inexact numbers.
+/// Expects a single float format (per [`StringToFloatFormat`]) as
+/// argument. Does not use the keep modifier or numerical argument.
+#[derive(Debug, Clone, Default)]
+pub struct SetFloatFormatCommand {
+  _priv: (),
+}
+
+/// Command which sets the maximum number of elements displayed in a
+/// vector, set, or matrix before the rest are elided. Expects a
+/// single argument (per [`StringToDisplayLimit`]): either a positive
+/// number of elements, or zero to always display values in full.
+/// Does not use the keep modifier or numerical argument.
+#[derive(Debug, Clone, Default)]
+pub struct SetMaxDisplayElementsCommand {
+  _priv: (),
+}
+
+/// Command which sets the modulus for modular arithmetic mode.
+/// Expects a single argument (per [`StringToModulus`]): either an
+/// integer of at least two, or zero to turn modular arithmetic mode
+/// off. Does not use the keep modifier or numerical argument.
+#[derive(Debug, Clone, Default)]
+pub struct SetModulusCommand {
+  _priv: (),
+}
+
+/// Command which sets the number of significant digits used for
+/// inexact computations. Expects a single argument (per
+/// [`StringToPrecision`]): either a positive number of digits, or
+/// zero to go back to ordinary floating-point values. Does not use
+/// the keep modifier or numerical argument.
+#[derive(Debug, Clone, Default)]
+pub struct SetPrecisionCommand {
+  _priv: (),
+}
+
+/// Command which sets the word size used by the bitwise functions.
+/// Expects a single argument (per [`StringToWordSize`]): a word size
+/// such as `u8` or `i32`, or zero to go back to unbounded integers.
+/// Does not use the keep modifier or numerical argument.
+#[derive(Debug, Clone, Default)]
+pub struct SetWordSizeCommand {
+  _priv: (),
+}
+
+/// Command which sets the rounding mode used by the rounding
+/// functions and by the float formats. Expects a single argument (per
+/// [`StringToRoundingMode`]): one of `half-up`, `half-even`,
+/// `toward-zero`, or `away-from-zero`. Does not use the keep modifier
+/// or numerical argument.
+#[derive(Debug, Clone, Default)]
+pub struct SetRoundingModeCommand {
+  _priv: (),
+}
+
 /// Command which sets the language mode to the given value. Does not
 /// use the keep modifier or numerical argument.
 #[derive(Clone)]
@@ -51,6 +185,26 @@ pub struct SetLanguageModeCommand {
   value: Arc<dyn LanguageMode + Send + Sync>,
 }
 
+/// Command which resets the display settings and calculation mode to
+/// their defaults. Does not affect the stack or variables. Does not
+/// use the keep modifier or numerical argument.
+pub fn reset_modes_command() -> impl Command + Send + Sync {
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let change = ResetModesChange {
+      old_display_settings: state.display_settings().clone(),
+      old_calculation_mode: state.calculation_mode().clone(),
+      new_display_settings: DisplaySettings::default(),
+      new_calculation_mode: CalculationMode::default(),
+    };
+    *state.display_settings_mut() = change.new_display_settings.clone();
+    *state.calculation_mode_mut() = change.new_calculation_mode.clone();
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(change);
+    Ok(CommandOutput::success())
+  })
+}
+
 impl SetDisplayRadixCommand {
   pub fn new() -> Self {
     Self { _priv: () }
@@ -64,21 +218,108 @@ impl SetDisplayRadixCommand {
   }
 }
 
+impl SetLocaleCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToLocale, Locale> {
+    UnaryArgumentSchema::new(
+      String::from("known locale name"),
+      StringToLocale,
+    )
+  }
+}
+
+impl SetFloatFormatCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToFloatFormat, FloatFormat> {
+    UnaryArgumentSchema::new(
+      String::from("float format (normal, fix N, sci [N], or eng [N])"),
+      StringToFloatFormat,
+    )
+  }
+}
+
+impl SetMaxDisplayElementsCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToDisplayLimit, Option<usize>> {
+    UnaryArgumentSchema::new(
+      String::from("number of elements, or 0 to disable"),
+      StringToDisplayLimit,
+    )
+  }
+}
+
+impl SetModulusCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToModulus, Option<u64>> {
+    UnaryArgumentSchema::new(
+      String::from("modulus of at least 2, or 0 to disable"),
+      StringToModulus,
+    )
+  }
+}
+
+impl SetPrecisionCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToPrecision, Option<u32>> {
+    UnaryArgumentSchema::new(
+      String::from("number of significant digits, or 0 to disable"),
+      StringToPrecision,
+    )
+  }
+}
+
+impl SetWordSizeCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToWordSize, Option<WordSize>> {
+    UnaryArgumentSchema::new(
+      String::from("word size (such as u8 or i32), or 0 to disable"),
+      StringToWordSize,
+    )
+  }
+}
+
+impl SetRoundingModeCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToRoundingMode, RoundingMode> {
+    UnaryArgumentSchema::new(
+      String::from("rounding mode (half-up, half-even, toward-zero, or away-from-zero)"),
+      StringToRoundingMode,
+    )
+  }
+}
+
 impl SetLanguageModeCommand {
   pub fn new(value: Arc<dyn LanguageMode + Send + Sync>) -> Self {
     Self { value }
   }
 
   pub fn basic_language_mode() -> Self {
-    let mode = Arc::new(BasicLanguageMode::from_common_operators());
-    Self::new(mode)
+    Self::new(named_language_mode("basic").expect("Basic language mode should exist"))
   }
 
   pub fn fancy_language_mode() -> Self {
-    let mode = Arc::new(FancyLanguageMode::from_common_unicode(
-      BasicLanguageMode::from_common_operators().with_fancy_parens(),
-    ));
-    Self::new(mode)
+    Self::new(named_language_mode("fancy").expect("Fancy language mode should exist"))
   }
 }
 
@@ -97,6 +338,21 @@ pub fn toggle_graphics_command() -> impl Command + Send + Sync {
   })
 }
 
+pub fn toggle_spoken_text_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_accessor("is_spoken_text_enabled", |state| &mut state.display_settings_mut().is_spoken_text_enabled)
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let settings = state.display_settings_mut();
+    settings.is_spoken_text_enabled = !settings.is_spoken_text_enabled;
+    Ok(CommandOutput::success())
+  })
+}
+
 pub fn toggle_unicode_command() -> impl Command + Send + Sync {
   fn toggle_flag_change() -> ToggleFlagChange {
     ToggleFlagChange::from_accessor("prefers_unicode_output", |state| {
@@ -114,6 +370,40 @@ pub fn toggle_unicode_command() -> impl Command + Send + Sync {
   })
 }
 
+pub fn toggle_twos_complement_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_accessor("twos_complement", |state| {
+      &mut state.display_settings_mut().language_settings.twos_complement
+    })
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let settings = &mut state.display_settings_mut().language_settings;
+    settings.twos_complement = !settings.twos_complement;
+    Ok(CommandOutput::success())
+  })
+}
+
+pub fn toggle_always_parenthesize_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_accessor("always_parenthesize", |state| {
+      &mut state.display_settings_mut().language_settings.always_parenthesize
+    })
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let settings = &mut state.display_settings_mut().language_settings;
+    settings.always_parenthesize = !settings.always_parenthesize;
+    Ok(CommandOutput::success())
+  })
+}
+
 pub fn toggle_infinity_command() -> impl Command + Send + Sync {
   fn toggle_flag_change() -> ToggleFlagChange {
     ToggleFlagChange::from_getter_setter(
@@ -152,6 +442,153 @@ pub fn toggle_fractional_command() -> impl Command + Send + Sync {
   })
 }
 
+pub fn toggle_exact_matrix_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_getter_setter(
+      "exact_matrix_flag",
+      |state| state.calculation_mode().has_exact_matrix_flag(),
+      |state, v| state.calculation_mode_mut().set_exact_matrix_flag(v),
+    )
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let calc = state.calculation_mode_mut();
+    calc.set_exact_matrix_flag(!calc.has_exact_matrix_flag());
+    Ok(CommandOutput::success())
+  })
+}
+
+pub fn toggle_units_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_getter_setter(
+      "units_flag",
+      |state| state.calculation_mode().has_units_flag(),
+      |state, v| state.calculation_mode_mut().set_units_flag(v),
+    )
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let calc = state.calculation_mode_mut();
+    calc.set_units_flag(!calc.has_units_flag());
+    Ok(CommandOutput::success())
+  })
+}
+
+pub fn toggle_derived_units_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_getter_setter(
+      "derived_units_flag",
+      |state| state.calculation_mode().has_derived_units_flag(),
+      |state, v| state.calculation_mode_mut().set_derived_units_flag(v),
+    )
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let calc = state.calculation_mode_mut();
+    calc.set_derived_units_flag(!calc.has_derived_units_flag());
+    Ok(CommandOutput::success())
+  })
+}
+
+/// Toggles the branch cut for complex `arg` and `ln` between the
+/// negative and the positive real axis. See [`BranchCut`].
+pub fn toggle_branch_cut_command() -> impl Command + Send + Sync {
+  fn is_positive(state: &UndoableState) -> bool {
+    state.calculation_mode().branch_cut() == BranchCut::PositiveReal
+  }
+
+  fn set_positive(state: &mut UndoableState, value: bool) {
+    let branch_cut = if value { BranchCut::PositiveReal } else { BranchCut::NegativeReal };
+    state.calculation_mode_mut().set_branch_cut(branch_cut);
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(ToggleFlagChange::from_getter_setter("branch_cut", is_positive, set_positive));
+    let calc = state.calculation_mode_mut();
+    let new_branch_cut = match calc.branch_cut() {
+      BranchCut::NegativeReal => BranchCut::PositiveReal,
+      BranchCut::PositiveReal => BranchCut::NegativeReal,
+    };
+    calc.set_branch_cut(new_branch_cut);
+    Ok(CommandOutput::success())
+  })
+}
+
+/// Toggles the angle mode between radians and degrees. If complex
+/// numbers are currently being displayed in polar form, the displayed
+/// angles switch to the new unit as well.
+pub fn toggle_degrees_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_getter_setter(
+      "degrees",
+      |state| state.calculation_mode().angle_mode() == AngleMode::Degrees,
+      |state, v| {
+        let angle_mode = if v { AngleMode::Degrees } else { AngleMode::Radians };
+        state.calculation_mode_mut().set_angle_mode(angle_mode);
+        sync_polar_angle_mode(&mut state.display_settings_mut().language_settings, angle_mode);
+      },
+    )
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let angle_mode = match state.calculation_mode().angle_mode() {
+      AngleMode::Radians => AngleMode::Degrees,
+      AngleMode::Degrees => AngleMode::Radians,
+    };
+    state.calculation_mode_mut().set_angle_mode(angle_mode);
+    sync_polar_angle_mode(&mut state.display_settings_mut().language_settings, angle_mode);
+    Ok(CommandOutput::success())
+  })
+}
+
+/// Toggles whether complex numbers are displayed in polar form,
+/// using the current angle mode.
+pub fn toggle_polar_display_command() -> impl Command + Send + Sync {
+  fn toggle_flag_change() -> ToggleFlagChange {
+    ToggleFlagChange::from_getter_setter(
+      "polar_display",
+      |state| state.display_settings().language_settings.polar_angle_mode.is_some(),
+      |state, v| {
+        let angle_mode = state.calculation_mode().angle_mode();
+        state.display_settings_mut().language_settings.polar_angle_mode = v.then_some(angle_mode);
+      },
+    )
+  }
+
+  GeneralCommand::new(|state, args, _| {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(toggle_flag_change());
+    let angle_mode = state.calculation_mode().angle_mode();
+    let settings = &mut state.display_settings_mut().language_settings;
+    settings.polar_angle_mode = match settings.polar_angle_mode {
+      None => Some(angle_mode),
+      Some(_) => None,
+    };
+    Ok(CommandOutput::success())
+  })
+}
+
+fn sync_polar_angle_mode(settings: &mut LanguageSettings, angle_mode: AngleMode) {
+  if settings.polar_angle_mode.is_some() {
+    settings.polar_angle_mode = Some(angle_mode);
+  }
+}
+
 impl Command for SetDisplayRadixCommand {
   fn run_command(
     &self,
@@ -178,6 +615,192 @@ impl Command for SetDisplayRadixCommand {
   }
 }
 
+impl Command for SetLocaleCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let old_locale = state.display_settings().language_settings.locale.clone();
+    let new_locale = validate_schema(&Self::argument_schema(), args)?;
+    if old_locale == new_locale {
+      // Nothing to change, so don't modify the undo stack.
+      return Ok(CommandOutput::success());
+    }
+
+    state.display_settings_mut().language_settings.locale = new_locale.clone();
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut()
+      .push_change(SetLocaleChange { old_value: old_locale, new_value: new_locale });
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetFloatFormatCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let old_format = state.display_settings().language_settings.float_format;
+    let new_format = validate_schema(&Self::argument_schema(), args)?;
+    if old_format == new_format {
+      // Nothing to change, so don't modify the undo stack.
+      return Ok(CommandOutput::success());
+    }
+
+    state.display_settings_mut().language_settings.float_format = new_format;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut()
+      .push_change(SetFloatFormatChange { old_value: old_format, new_value: new_format });
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetMaxDisplayElementsCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let old_limit = state.display_settings().language_settings.max_display_elements;
+    let new_limit = validate_schema(&Self::argument_schema(), args)?;
+    if old_limit == new_limit {
+      // Nothing to change, so don't modify the undo stack.
+      return Ok(CommandOutput::success());
+    }
+
+    state.display_settings_mut().language_settings.max_display_elements = new_limit;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut()
+      .push_change(SetMaxDisplayElementsChange { old_value: old_limit, new_value: new_limit });
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetModulusCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let old_modulus = state.calculation_mode().modulus();
+    let new_modulus = validate_schema(&Self::argument_schema(), args)?;
+    if old_modulus == new_modulus {
+      // Nothing to change, so don't modify the undo stack.
+      return Ok(CommandOutput::success());
+    }
+
+    state.calculation_mode_mut().set_modulus(new_modulus);
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut()
+      .push_change(SetModulusChange { old_value: old_modulus, new_value: new_modulus });
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetPrecisionCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let old_precision = state.calculation_mode().precision();
+    let new_precision = validate_schema(&Self::argument_schema(), args)?;
+    if old_precision == new_precision {
+      // Nothing to change, so don't modify the undo stack.
+      return Ok(CommandOutput::success());
+    }
+
+    state.calculation_mode_mut().set_precision(new_precision);
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut()
+      .push_change(SetPrecisionChange { old_value: old_precision, new_value: new_precision });
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetWordSizeCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let old_word_size = state.calculation_mode().word_size();
+    let new_word_size = validate_schema(&Self::argument_schema(), args)?;
+    if old_word_size == new_word_size {
+      // Nothing to change, so don't modify the undo stack.
+      return Ok(CommandOutput::success());
+    }
+
+    state.calculation_mode_mut().set_word_size(new_word_size);
+    state.display_settings_mut().language_settings.word_size = new_word_size;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut()
+      .push_change(SetWordSizeChange { old_value: old_word_size, new_value: new_word_size });
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetRoundingModeCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let old_rounding_mode = state.calculation_mode().rounding_mode();
+    let new_rounding_mode = validate_schema(&Self::argument_schema(), args)?;
+    if old_rounding_mode == new_rounding_mode {
+      // Nothing to change, so don't modify the undo stack.
+      return Ok(CommandOutput::success());
+    }
+
+    // The display settings carry their own copy of the rounding mode,
+    // so that displayed values agree with computed ones.
+    state.calculation_mode_mut().set_rounding_mode(new_rounding_mode);
+    state.display_settings_mut().language_settings.rounding_mode = new_rounding_mode;
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut()
+      .push_change(SetRoundingModeChange { old_value: old_rounding_mode, new_value: new_rounding_mode });
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
 impl Command for SetLanguageModeCommand {
   fn run_command(
     &self,
@@ -204,12 +827,12 @@ impl Command for SetLanguageModeCommand {
 }
 
 impl UndoableChange<UndoableState> for SetDisplayRadixChange {
-  fn play_forward(&self, state: &mut UndoableState) {
+  fn play_forward(&mut self, state: &mut UndoableState) {
     let settings = state.display_settings_mut();
     settings.language_settings.preferred_radix = self.new_value;
   }
 
-  fn play_backward(&self, state: &mut UndoableState) {
+  fn play_backward(&mut self, state: &mut UndoableState) {
     let settings = state.display_settings_mut();
     settings.language_settings.preferred_radix = self.old_value;
   }
@@ -219,13 +842,121 @@ impl UndoableChange<UndoableState> for SetDisplayRadixChange {
   }
 }
 
+impl UndoableChange<UndoableState> for SetFloatFormatChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    let settings = state.display_settings_mut();
+    settings.language_settings.float_format = self.new_value;
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let settings = state.display_settings_mut();
+    settings.language_settings.float_format = self.old_value;
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for SetLocaleChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    let settings = state.display_settings_mut();
+    settings.language_settings.locale = self.new_value.clone();
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let settings = state.display_settings_mut();
+    settings.language_settings.locale = self.old_value.clone();
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for SetMaxDisplayElementsChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    let settings = state.display_settings_mut();
+    settings.language_settings.max_display_elements = self.new_value;
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let settings = state.display_settings_mut();
+    settings.language_settings.max_display_elements = self.old_value;
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for SetModulusChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_modulus(self.new_value);
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_modulus(self.old_value);
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for SetPrecisionChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_precision(self.new_value);
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_precision(self.old_value);
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for SetWordSizeChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_word_size(self.new_value);
+    state.display_settings_mut().language_settings.word_size = self.new_value;
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_word_size(self.old_value);
+    state.display_settings_mut().language_settings.word_size = self.old_value;
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for SetRoundingModeChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_rounding_mode(self.new_value);
+    state.display_settings_mut().language_settings.rounding_mode = self.new_value;
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.calculation_mode_mut().set_rounding_mode(self.old_value);
+    state.display_settings_mut().language_settings.rounding_mode = self.old_value;
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
 impl UndoableChange<UndoableState> for SetLanguageModeChange {
-  fn play_forward(&self, state: &mut UndoableState) {
+  fn play_forward(&mut self, state: &mut UndoableState) {
     let settings = state.display_settings_mut();
     settings.base_language_mode = self.new_value.clone();
   }
 
-  fn play_backward(&self, state: &mut UndoableState) {
+  fn play_backward(&mut self, state: &mut UndoableState) {
     let settings = state.display_settings_mut();
     settings.base_language_mode = self.old_value.clone();
   }
@@ -234,3 +965,19 @@ impl UndoableChange<UndoableState> for SetLanguageModeChange {
     String::from("SetLanguageModeChange { ... }")
   }
 }
+
+impl UndoableChange<UndoableState> for ResetModesChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    *state.display_settings_mut() = self.new_display_settings.clone();
+    *state.calculation_mode_mut() = self.new_calculation_mode.clone();
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    *state.display_settings_mut() = self.old_display_settings.clone();
+    *state.calculation_mode_mut() = self.old_calculation_mode.clone();
+  }
+
+  fn undo_summary(&self) -> String {
+    String::from("ResetModesChange { ... }")
+  }
+}
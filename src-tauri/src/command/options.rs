@@ -3,7 +3,7 @@ use serde::{Serialize, Deserialize};
 
 /// Options passed in addition to a command.
 #[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct CommandOptions {
   /// The optional numerical argument for the command. This often
   /// indicates where on the stack to apply the command, or to how
@@ -19,6 +19,13 @@ pub struct CommandOptions {
   /// The "inverse" modifier, which indicates to many commands that
   /// the inverse operation to the usual should be performed.
   pub inverse_modifier: bool,
+  /// Whether the user has already confirmed that the command should
+  /// proceed. Destructive commands wrapped in
+  /// [`RequireConfirmation`](super::confirmation::RequireConfirmation)
+  /// refuse to run until this flag is set. Never serialized when
+  /// false, so that recorded programs don't mention it.
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub confirmed: bool,
 }
 
 impl CommandOptions {
@@ -49,4 +56,9 @@ impl CommandOptions {
     self.inverse_modifier = true;
     self
   }
+
+  pub fn with_confirmation(mut self) -> Self {
+    self.confirmed = true;
+    self
+  }
 }
@@ -0,0 +1,182 @@
+
+//! Commands for pinning stack entries in place.
+
+use super::arguments::{NullaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+use crate::state::undo::UpdatePinChange;
+use crate::stack::base::RandomAccessStackLike;
+
+/// This command takes no arguments. Pins or unpins a stack entry,
+/// depending on the `pin` flag.
+///
+/// A pinned entry can still be used as an argument by other commands,
+/// but it is never consumed by them. That is, commands read the
+/// value but leave it in place on the stack, as though the "keep"
+/// modifier had been applied to that entry alone.
+///
+/// With a numerical argument N, (un)pins the Nth entry from the top
+/// of the stack. Otherwise, (un)pins the top entry. Fails if the
+/// stack does not have enough elements or if the numerical argument
+/// is not positive. Does not use the "keep" modifier, since the stack
+/// values are never modified.
+#[derive(Debug)]
+pub struct PinStackElemCommand {
+  pin: bool,
+}
+
+impl PinStackElemCommand {
+  pub fn pin() -> Self {
+    Self { pin: true }
+  }
+
+  pub fn unpin() -> Self {
+    Self { pin: false }
+  }
+}
+
+impl Command for PinStackElemCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+
+    let arg = context.opts.argument.unwrap_or(1);
+    if arg <= 0 {
+      anyhow::bail!("Expected positive numerical argument");
+    }
+    let index = arg - 1;
+    // Make sure the entry exists before pinning it.
+    state.main_stack().get(index)?;
+
+    state.undo_stack_mut().push_cut();
+    let index = index as usize;
+    let old_pinned = state.stack_pins_mut().set(index, self.pin);
+    state.undo_stack_mut().push_change(UpdatePinChange::new(index, old_pinned, self.pin));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::functional::BinaryFunctionCommand;
+  use crate::command::shuffle::PopCommand;
+  use crate::command::vector::PackCommand;
+  use crate::expr::Expr;
+  use crate::stack::{Stack, StackError};
+  use crate::stack::test_utils::stack_of;
+  use crate::state::test_utils::state_for_stack;
+
+  fn pin(state: &mut ApplicationState, command: PinStackElemCommand, argument: Option<i64>) -> anyhow::Result<CommandOutput> {
+    let mut context = CommandContext::default();
+    context.opts.argument = argument;
+    command.run_command(state, vec![], &context)
+  }
+
+  #[test]
+  fn test_pin_and_unpin() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    pin(&mut state, PinStackElemCommand::pin(), Some(2)).unwrap();
+    assert_eq!(state.stack_pins().for_stack_len(3), vec![false, true, false]);
+    pin(&mut state, PinStackElemCommand::unpin(), Some(2)).unwrap();
+    assert!(state.stack_pins().is_empty());
+  }
+
+  #[test]
+  fn test_pinned_entry_is_not_consumed() {
+    let mut state = state_for_stack(vec![10, 20]);
+    pin(&mut state, PinStackElemCommand::pin(), Some(2)).unwrap();
+    BinaryFunctionCommand::named("+").run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![
+      Expr::from(10),
+      Expr::call("+", vec![Expr::from(10), Expr::from(20)]),
+    ]));
+    assert!(state.stack_pins().is_pinned(1));
+    assert!(!state.stack_pins().is_pinned(0));
+  }
+
+  #[test]
+  fn test_pinned_entry_on_top_is_read_once() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    pin(&mut state, PinStackElemCommand::pin(), None).unwrap();
+    BinaryFunctionCommand::named("-").run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![
+      Expr::from(10),
+      Expr::from(30),
+      Expr::call("-", vec![Expr::from(20), Expr::from(30)]),
+    ]));
+    assert!(state.stack_pins().is_pinned(1));
+    assert!(!state.stack_pins().is_pinned(0));
+
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+    assert!(state.stack_pins().is_pinned(0));
+  }
+
+  #[test]
+  fn test_pinned_entry_on_top_restored_on_error() {
+    let mut state = state_for_stack(vec![10, 5]);
+    pin(&mut state, PinStackElemCommand::pin(), None).unwrap();
+    // Pack reads the count off the top of the stack, then fails to
+    // find enough values beneath it.
+    PackCommand::new().run_command(&mut state, vec![], &CommandContext::default()).unwrap_err();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 5]));
+    assert!(state.stack_pins().is_pinned(0));
+    assert!(!state.stack_pins().is_pinned(1));
+  }
+
+  #[test]
+  fn test_pinned_entry_survives_pop() {
+    let mut state = state_for_stack(vec![10, 20, 30]);
+    pin(&mut state, PinStackElemCommand::pin(), None).unwrap();
+    let mut context = CommandContext::default();
+    context.opts.argument = Some(2);
+    PopCommand.run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 30]));
+  }
+
+  #[test]
+  fn test_pin_is_undoable() {
+    let mut state = state_for_stack(vec![10, 20]);
+    pin(&mut state, PinStackElemCommand::pin(), None).unwrap();
+    pin(&mut state, PinStackElemCommand::unpin(), None).unwrap();
+    assert!(state.stack_pins().is_empty());
+    state.undo().unwrap();
+    assert!(state.stack_pins().is_pinned(0));
+    state.undo().unwrap();
+    assert!(state.stack_pins().is_empty());
+  }
+
+  #[test]
+  fn test_pin_restored_when_pop_nth_undone() {
+    let mut state = state_for_stack(vec![10, 20]);
+    pin(&mut state, PinStackElemCommand::pin(), None).unwrap();
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().pop_nth(0).unwrap();
+    assert!(state.stack_pins().is_empty());
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &Stack::from(vec![Expr::from(10), Expr::from(20)]));
+    assert!(state.stack_pins().is_pinned(0));
+  }
+
+  #[test]
+  fn test_pin_out_of_bounds() {
+    let mut state = state_for_stack(vec![10]);
+    let err = pin(&mut state, PinStackElemCommand::pin(), Some(2)).unwrap_err();
+    let err = err.downcast::<StackError>().unwrap();
+    assert_eq!(err, StackError::NotEnoughElements { expected: 2, actual: 1 });
+    pin(&mut state, PinStackElemCommand::pin(), Some(0)).unwrap_err();
+    assert!(state.stack_pins().is_empty());
+  }
+}
@@ -0,0 +1,989 @@
+
+//! Commands for running simple stack-based programs.
+//!
+//! A program is a vector (in the expression language) of
+//! instructions. Each string element of the vector is a command to
+//! run, and every other element is a literal value which is pushed
+//! onto the stack when that instruction is reached. A command can be
+//! given either as a bare command name (such as `"+"` or `"dup"`) or
+//! as a JSON object with the fields `name`, `args`, and `options`,
+//! for commands that require arguments or modifiers.
+
+use super::arguments::{NullaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::errorlist::ErrorList;
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::prisms::{ExprToComplex, expr_to_usize};
+use crate::expr::vector::{Vector, ExprToVector};
+use crate::stack::base::{StackLike, RandomAccessStackLike};
+use crate::stack::keepable::KeepableStack;
+use crate::state::ApplicationState;
+use crate::util::prism::Prism;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+use num::Zero;
+
+/// A parsed program, ready to be executed against an
+/// [`ApplicationState`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+  instructions: Vec<Instruction>,
+  source: Expr,
+}
+
+/// A single instruction in a [`Program`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+  /// Push a literal value onto the stack.
+  Push(Expr),
+  /// Run the command indicated by the step.
+  Run(ProgramStep),
+}
+
+/// A command invocation within a program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramStep {
+  pub name: String,
+  #[serde(default)]
+  pub args: Vec<String>,
+  #[serde(default)]
+  pub options: CommandOptions,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum ParseProgramError {
+  #[error("Expected a program vector")]
+  NotAVector(Expr),
+  #[error("Invalid program step {0:?}")]
+  InvalidStep(String),
+}
+
+/// The maximum number of iterations that a looping program command
+/// will perform before giving up. This guards against runaway loops,
+/// since the UI is blocked while a command is running.
+pub const MAX_ITERATIONS: usize = 10_000;
+
+/// The maximum depth to which programs may run other programs. A
+/// program which (directly or indirectly) runs itself would otherwise
+/// overflow the native stack, which aborts the whole application
+/// rather than failing the command.
+pub const MAX_NESTING_DEPTH: usize = 64;
+
+/// A program which is being run one instruction at a time by
+/// [`StepProgramCommand`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramSession {
+  program: Program,
+  next_index: usize,
+}
+
+/// Error produced when a step of a program fails.
+#[derive(Debug, Error)]
+#[error("Program step {index} ({name}) failed: {error}")]
+pub struct ProgramStepError {
+  pub index: usize,
+  pub name: String,
+  pub error: anyhow::Error,
+}
+
+#[derive(Debug, Clone, Error)]
+#[error("Programs may not be nested more than {MAX_NESTING_DEPTH} levels deep")]
+pub struct NestingDepthError;
+
+/// Error produced when a step of a program asks for confirmation.
+/// There is nobody to ask in the middle of a program, so the program
+/// stops rather than skipping the step. A step can be confirmed in
+/// advance by setting the `confirmed` option on it.
+#[derive(Debug, Clone, Error)]
+#[error("Step requires confirmation: {message}")]
+pub struct UnconfirmedStepError {
+  pub message: String,
+}
+
+/// Pops a program off the stack and runs it.
+///
+/// Respects the "keep" modifier, in which case the program is left on
+/// the stack (underneath anything the program itself pushes).
+#[derive(Debug, Default)]
+pub struct EvalProgramCommand {
+  _priv: (),
+}
+
+/// Pops a condition and two programs off the stack. The top of the
+/// stack is the "else" program, below it is the "then" program, and
+/// below that is the condition. If the condition is nonzero, the
+/// "then" program is run. Otherwise, the "else" program is run.
+///
+/// The condition is simplified before being checked. It is an error
+/// for the condition to simplify to something other than a real or
+/// complex number. Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct IfThenCommand {
+  _priv: (),
+}
+
+/// Pops a program and an iteration count off the stack. The program
+/// is on top, and the nonnegative integer count is below it. Runs the
+/// program the given number of times. The count may not exceed
+/// [`MAX_ITERATIONS`].
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct RepeatProgramCommand {
+  _priv: (),
+}
+
+/// Pops a program and a condition off the stack. The program is on
+/// top, and the condition expression is below it. Runs the program
+/// repeatedly for as long as the condition is true.
+///
+/// Before each iteration, the condition has all bound variables
+/// substituted into it and is then simplified. It must simplify to a
+/// number, where any nonzero value is considered true. Typically,
+/// the program will update a variable that the condition depends on.
+/// If the loop runs for more than [`MAX_ITERATIONS`] iterations, the
+/// command fails.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct WhileProgramCommand {
+  _priv: (),
+}
+
+/// Steps through a program one instruction at a time, for debugging.
+///
+/// If no program is currently being stepped through (or the previous
+/// one has finished), this command pops a program off the stack and
+/// begins a new session, without running any instructions. Otherwise,
+/// it runs the next instruction of the current session. After each
+/// invocation, the frontend is informed of the upcoming instruction.
+///
+/// With the inverse modifier, this command abandons the current
+/// session instead.
+#[derive(Debug, Default)]
+pub struct StepProgramCommand {
+  _priv: (),
+}
+
+impl Program {
+  pub fn new(instructions: Vec<Instruction>) -> Self {
+    let source = instructions_to_expr(&instructions);
+    Self { instructions, source }
+  }
+
+  /// The vector expression this program was parsed from.
+  pub fn source(&self) -> &Expr {
+    &self.source
+  }
+
+  pub fn into_expr(self) -> Expr {
+    self.source
+  }
+
+  pub fn instructions(&self) -> &[Instruction] {
+    &self.instructions
+  }
+
+  pub fn len(&self) -> usize {
+    self.instructions.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.instructions.is_empty()
+  }
+
+  /// Parses a program from a vector expression. On failure, the
+  /// original expression is returned alongside the error.
+  pub fn parse(expr: Expr) -> Result<Program, (Expr, ParseProgramError)> {
+    let vector = match ExprToVector.narrow_type(expr) {
+      Ok(vector) => vector,
+      Err(expr) => return Err((expr.clone(), ParseProgramError::NotAVector(expr))),
+    };
+    let instructions: Result<Vec<_>, _> = vector.iter()
+      .map(|elem| Instruction::parse(elem.clone()))
+      .collect();
+    match instructions {
+      Ok(instructions) => Ok(Program { instructions, source: vector.into_expr() }),
+      Err(err) => Err((vector.into_expr(), err)),
+    }
+  }
+
+  /// Runs the program, returning the list of non-fatal errors
+  /// produced by its steps. Stops at the first fatal error.
+  ///
+  /// Each command in the program manages its own undo state, so an
+  /// undo after a program has run undoes the program's final step,
+  /// not the entire program.
+  pub fn execute(
+    &self,
+    state: &mut ApplicationState,
+    context: &CommandContext,
+  ) -> Result<Vec<String>, ProgramStepError> {
+    let mut errors = Vec::new();
+    for (index, instruction) in self.instructions.iter().enumerate() {
+      let output = instruction.execute(state, context).map_err(|error| {
+        match error.downcast::<ProgramStepError>() {
+          // Report the innermost step once, rather than wrapping it
+          // again at every one of the levels that led up to it.
+          Ok(inner) if inner.error.is::<NestingDepthError>() => inner,
+          Ok(inner) => ProgramStepError { index, name: instruction.name().to_owned(), error: inner.into() },
+          Err(error) => ProgramStepError { index, name: instruction.name().to_owned(), error },
+        }
+      })?;
+      errors.extend(output.errors().iter().cloned());
+    }
+    Ok(errors)
+  }
+}
+
+impl Instruction {
+  pub fn parse(expr: Expr) -> Result<Instruction, ParseProgramError> {
+    match expr {
+      Expr::Atom(Atom::String(s)) => {
+        ProgramStep::parse(s).map(Instruction::Run)
+      }
+      expr => {
+        Ok(Instruction::Push(expr))
+      }
+    }
+  }
+
+  /// A short user-facing name for the instruction, used in error
+  /// messages.
+  pub fn name(&self) -> &str {
+    match self {
+      Instruction::Push(_) => "push",
+      Instruction::Run(step) => &step.name,
+    }
+  }
+
+  pub fn execute(
+    &self,
+    state: &mut ApplicationState,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    match self {
+      Instruction::Push(expr) => {
+        state.undo_stack_mut().push_cut();
+        state.main_stack_mut().push(expr.clone());
+        Ok(CommandOutput::success())
+      }
+      Instruction::Run(step) => {
+        let nesting_depth = context.nesting_depth + 1;
+        if nesting_depth > MAX_NESTING_DEPTH {
+          anyhow::bail!(NestingDepthError);
+        }
+        let command = context.dispatch_table.get(&step.name)?;
+        let step_context = CommandContext {
+          opts: step.options.clone(),
+          simplifier: Box::new(context.simplifier.as_ref()),
+          units_parser: context.units_parser,
+          dispatch_table: context.dispatch_table,
+          function_table: context.function_table,
+          nesting_depth,
+        };
+        let output = command.run_command(state, step.args.clone(), &step_context)?;
+        if let Some(message) = output.confirmation_request() {
+          anyhow::bail!(UnconfirmedStepError { message: message.to_owned() });
+        }
+        Ok(output)
+      }
+    }
+  }
+}
+
+impl ProgramStep {
+  pub fn new(name: impl Into<String>) -> Self {
+    Self {
+      name: name.into(),
+      args: Vec::new(),
+      options: CommandOptions::default(),
+    }
+  }
+
+  /// Parses a program step from a string. Strings beginning with `{`
+  /// are parsed as JSON. Any other non-empty string is treated as a
+  /// bare command name with no arguments.
+  pub fn parse(s: String) -> Result<ProgramStep, ParseProgramError> {
+    let trimmed = s.trim();
+    if trimmed.starts_with('{') {
+      serde_json::from_str(trimmed).map_err(|_| ParseProgramError::InvalidStep(s))
+    } else if trimmed.is_empty() {
+      Err(ParseProgramError::InvalidStep(s))
+    } else {
+      Ok(ProgramStep::new(trimmed))
+    }
+  }
+}
+
+impl EvalProgramCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl IfThenCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl ProgramSession {
+  pub fn new(program: Program) -> Self {
+    Self { program, next_index: 0 }
+  }
+
+  pub fn program(&self) -> &Program {
+    &self.program
+  }
+
+  pub fn next_index(&self) -> usize {
+    self.next_index
+  }
+
+  /// The instruction that will run on the next step, or `None` if the
+  /// program has finished.
+  pub fn upcoming(&self) -> Option<&Instruction> {
+    self.program.instructions.get(self.next_index)
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.upcoming().is_none()
+  }
+}
+
+impl StepProgramCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl RepeatProgramCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl WhileProgramCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+/// Pops a program off the given stack, restoring the stack if the
+/// value is not a valid program.
+pub(super) fn pop_program<S>(stack: &mut KeepableStack<S>) -> anyhow::Result<Program>
+where S: RandomAccessStackLike<Elem = Expr> {
+  let expr = stack.pop()?;
+  match Program::parse(expr) {
+    Ok(program) => Ok(program),
+    Err((expr, err)) => {
+      stack.restore_popped([expr]);
+      Err(err.into())
+    }
+  }
+}
+
+/// Returns whether the (already simplified) expression is a nonzero
+/// number, or `None` if the expression is not a number.
+pub(super) fn is_truthy(expr: Expr) -> Option<bool> {
+  ExprToComplex.narrow_type(expr).ok().map(|value| !value.is_zero())
+}
+
+impl Command for EvalProgramCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    let program = {
+      let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+      pop_program(&mut stack)?
+    };
+    let errors = program.execute(state, context)?;
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for IfThenCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let calculation_mode = state.calculation_mode().clone();
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+
+    let program = {
+      let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+      stack.check_stack_size(3)?;
+      let [condition, then_expr, else_expr] = stack.pop_several(3)?.try_into().unwrap();
+      let (then_program, else_program) = match (Program::parse(then_expr), Program::parse(else_expr)) {
+        (Ok(then_program), Ok(else_program)) => (then_program, else_program),
+        (then_result, else_result) => {
+          let err = then_result.as_ref().err().or(else_result.as_ref().err()).unwrap().1.clone();
+          stack.restore_popped([
+            condition,
+            then_result.map_or_else(|(e, _)| e, Program::into_expr),
+            else_result.map_or_else(|(e, _)| e, Program::into_expr),
+          ]);
+          return Err(err.into());
+        }
+      };
+      let simplified_condition = context.simplify_expr(condition.clone(), calculation_mode, &mut errors);
+      match is_truthy(simplified_condition) {
+        Some(true) => then_program,
+        Some(false) => else_program,
+        None => {
+          stack.restore_popped([
+            condition,
+            then_program.into_expr(),
+            else_program.into_expr(),
+          ]);
+          anyhow::bail!("Expected numerical condition");
+        }
+      }
+    };
+
+    let mut output_errors: Vec<String> = errors.into_iter().map(|e| e.to_string()).collect();
+    output_errors.extend(program.execute(state, context)?);
+    Ok(CommandOutput::from_errors(output_errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for StepProgramCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+
+    if context.opts.inverse_modifier {
+      *state.program_session_mut() = None;
+      return Ok(CommandOutput::success());
+    }
+
+    let Some(session) = state.program_session().filter(|session| !session.is_finished()).cloned() else {
+      // Start a new session.
+      state.undo_stack_mut().push_cut();
+      let program = {
+        let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+        pop_program(&mut stack)?
+      };
+      *state.program_session_mut() = Some(ProgramSession::new(program));
+      return Ok(CommandOutput::success());
+    };
+
+    let instruction = session.upcoming().expect("Session should not be finished");
+    let result = instruction.execute(state, context);
+    // Advance even on failure, so that a broken instruction does not
+    // block the rest of the session.
+    if let Some(session) = state.program_session_mut() {
+      session.next_index += 1;
+    }
+    let output = result.map_err(|error| {
+      ProgramStepError { index: session.next_index, name: instruction.name().to_owned(), error }
+    })?;
+    Ok(output)
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for RepeatProgramCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+
+    let (count, program) = {
+      let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+      stack.check_stack_size(2)?;
+      let program = pop_program(&mut stack)?;
+      let count_expr = stack.pop()?;
+      match expr_to_usize().narrow_type(count_expr) {
+        Ok(count) if count <= MAX_ITERATIONS => (count, program),
+        result => {
+          stack.restore_popped([
+            result.map_or_else(|e| e, |count| expr_to_usize().widen_type(count)),
+            program.into_expr(),
+          ]);
+          anyhow::bail!("Expected iteration count between 0 and {MAX_ITERATIONS}");
+        }
+      }
+    };
+
+    let mut errors = Vec::new();
+    for _ in 0..count {
+      errors.extend(program.execute(state, context)?);
+    }
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for WhileProgramCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+
+    let (condition, program) = {
+      let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+      stack.check_stack_size(2)?;
+      let program = pop_program(&mut stack)?;
+      let condition = stack.pop()?;
+      (condition, program)
+    };
+
+    let mut output_errors = Vec::new();
+    for iteration in 0..=MAX_ITERATIONS {
+      let calculation_mode = state.calculation_mode().clone();
+      let mut errors = ErrorList::new();
+      let current_condition = condition.clone().substitute_vars(state.variable_table());
+      let current_condition = context.simplify_expr(current_condition, calculation_mode, &mut errors);
+      output_errors.extend(errors.into_iter().map(|e| e.to_string()));
+      match is_truthy(current_condition) {
+        Some(true) => {
+          output_errors.extend(program.execute(state, context)?);
+        }
+        Some(false) => {
+          return Ok(CommandOutput::from_errors(output_errors));
+        }
+        None => {
+          if iteration == 0 && !context.opts.keep_modifier {
+            // Nothing has run yet, so put the arguments back.
+            let mut stack = state.main_stack_mut();
+            stack.push(condition);
+            stack.push(program.into_expr());
+          }
+          anyhow::bail!("Expected numerical condition");
+        }
+      }
+    }
+    anyhow::bail!("Loop exceeded {MAX_ITERATIONS} iterations")
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+fn instructions_to_expr(instructions: &[Instruction]) -> Expr {
+  let elems: Vector = instructions.iter().map(|instruction| {
+    match instruction {
+      Instruction::Push(expr) => expr.clone(),
+      Instruction::Run(step) => {
+        if step.args.is_empty() && step.options == CommandOptions::default() {
+          Expr::from(step.name.clone())
+        } else {
+          Expr::from(serde_json::to_string(step).expect("ProgramStep serialization should not fail"))
+        }
+      }
+    }
+  }).collect();
+  elems.into_expr()
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+  use crate::command::base::CommandContext;
+  use crate::command::default_dispatch_table;
+  use crate::command::dispatch::CommandDispatchTable;
+  use crate::state::ApplicationState;
+
+  use once_cell::sync::Lazy;
+
+  /// An [`ActOnStackArg`](crate::command::test_utils::ActOnStackArg)
+  /// which installs the default dispatch table, so that programs can
+  /// look up commands by name.
+  pub fn setup_default_dispatch_table(_args: &mut Vec<String>, _state: &mut ApplicationState, context: &mut CommandContext) {
+    static DISPATCH_TABLE: Lazy<CommandDispatchTable> = Lazy::new(default_dispatch_table);
+    context.dispatch_table = &DISPATCH_TABLE;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::test_utils::setup_default_dispatch_table;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
+  use crate::stack::test_utils::stack_of;
+  use crate::stack::{Stack, StackError};
+  use crate::expr::var::Var;
+
+  fn program(elems: Vec<Expr>) -> Expr {
+    Expr::call("vector", elems)
+  }
+
+  fn cmd(name: &str) -> Expr {
+    Expr::from(name.to_owned())
+  }
+
+  #[test]
+  fn test_parse_program() {
+    let expr = program(vec![
+      Expr::from(2),
+      cmd("*"),
+      cmd(r#"{"name": "store_var", "args": ["x"]}"#),
+    ]);
+    let program = Program::parse(expr).unwrap();
+    assert_eq!(program.instructions(), &[
+      Instruction::Push(Expr::from(2)),
+      Instruction::Run(ProgramStep::new("*")),
+      Instruction::Run(ProgramStep { name: "store_var".to_owned(), args: vec!["x".to_owned()], options: CommandOptions::default() }),
+    ]);
+  }
+
+  #[test]
+  fn test_parse_program_failure() {
+    let (expr, err) = Program::parse(Expr::from(1)).unwrap_err();
+    assert_eq!(expr, Expr::from(1));
+    assert!(matches!(err, ParseProgramError::NotAVector(_)));
+
+    let (expr, err) = Program::parse(program(vec![cmd("{ not json")])).unwrap_err();
+    assert_eq!(expr, program(vec![cmd("{ not json")]));
+    assert!(matches!(err, ParseProgramError::InvalidStep(_)));
+  }
+
+  #[test]
+  fn test_program_roundtrip() {
+    let expr = program(vec![
+      Expr::from(2),
+      cmd("*"),
+      cmd(r#"{"name":"store_var","args":["x"],"options":{"argument":null,"keepModifier":false,"hyperbolicModifier":false,"inverseModifier":false}}"#),
+    ]);
+    let parsed = Program::parse(expr.clone()).unwrap();
+    assert_eq!(parsed.source(), &expr);
+    let rebuilt = Program::new(parsed.instructions().to_vec());
+    assert_eq!(rebuilt, parsed);
+  }
+
+  #[test]
+  fn test_eval_program() {
+    let output_stack = act_on_stack(
+      &EvalProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(10), program(vec![Expr::from(2), cmd("*"), Expr::from(1), cmd("+")])],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![21]));
+  }
+
+  #[test]
+  fn test_eval_program_with_keep_arg() {
+    let prog = program(vec![cmd("dup")]);
+    let output_stack = act_on_stack(
+      &EvalProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table, CommandOptions::default().with_keep_modifier()),
+      vec![Expr::from(10), prog.clone()],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::from(10), prog.clone(), prog]));
+  }
+
+  #[test]
+  fn test_eval_program_on_non_program() {
+    let err = act_on_stack(
+      &EvalProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![10],
+    ).unwrap_err();
+    assert!(err.is::<ParseProgramError>());
+  }
+
+  #[test]
+  fn test_eval_program_on_empty_stack() {
+    let err = act_on_stack(
+      &EvalProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      Vec::<Expr>::new(),
+    ).unwrap_err();
+    assert!(err.is::<StackError>());
+  }
+
+  #[test]
+  fn test_if_then_true() {
+    let output_stack = act_on_stack(
+      &IfThenCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![
+        Expr::from(5),
+        Expr::call("<", vec![Expr::from(1), Expr::from(2)]),
+        program(vec![cmd("negate")]),
+        program(vec![cmd("dup")]),
+      ],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![-5]));
+  }
+
+  #[test]
+  fn test_if_then_false() {
+    let output_stack = act_on_stack(
+      &IfThenCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![
+        Expr::from(5),
+        Expr::from(0),
+        program(vec![cmd("negate")]),
+        program(vec![cmd("dup")]),
+      ],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![5, 5]));
+  }
+
+  #[test]
+  fn test_if_then_with_non_numerical_condition() {
+    let err = act_on_stack(
+      &IfThenCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![
+        Expr::var("x").unwrap(),
+        program(vec![cmd("negate")]),
+        program(vec![cmd("dup")]),
+      ],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Expected numerical condition");
+  }
+
+  #[test]
+  fn test_if_then_with_invalid_program() {
+    let err = act_on_stack(
+      &IfThenCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(1), Expr::from(2), program(vec![cmd("dup")])],
+    ).unwrap_err();
+    assert!(err.is::<ParseProgramError>());
+  }
+
+  #[test]
+  fn test_if_then_with_too_few_args() {
+    let err = act_on_stack(
+      &IfThenCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(1), program(vec![])],
+    ).unwrap_err();
+    assert!(err.is::<StackError>());
+  }
+
+  #[test]
+  fn test_repeat_program() {
+    let output_stack = act_on_stack(
+      &RepeatProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(1), Expr::from(3), program(vec![Expr::from(2), cmd("*")])],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![8]));
+  }
+
+  #[test]
+  fn test_repeat_program_zero_times() {
+    let output_stack = act_on_stack(
+      &RepeatProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(1), Expr::from(0), program(vec![cmd("pop")])],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![1]));
+  }
+
+  #[test]
+  fn test_repeat_program_with_invalid_count() {
+    let err = act_on_stack(
+      &RepeatProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(1), Expr::from(-1), program(vec![cmd("pop")])],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Expected iteration count between 0 and 10000");
+    let err = act_on_stack(
+      &RepeatProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![Expr::from(1), Expr::from(10_001), program(vec![cmd("pop")])],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Expected iteration count between 0 and 10000");
+  }
+
+  #[test]
+  fn test_while_program_with_unbound_var() {
+    let err = act_on_stack(
+      &WhileProgramCommand::new(),
+      (setup_default_simplifier, setup_default_dispatch_table),
+      vec![
+        Expr::from(3),
+        Expr::call("<", vec![Expr::var("x").unwrap(), Expr::from(100)]),
+        program(vec![
+          Expr::from(2),
+          cmd("*"),
+          cmd(r#"{"name": "store_var", "args": ["x"], "options": {"keepModifier": true}}"#),
+        ]),
+      ],
+    ).unwrap_err();
+    // `x` is unbound on the first iteration, so the condition is not
+    // numerical.
+    assert_eq!(err.to_string(), "Expected numerical condition");
+  }
+
+  #[test]
+  fn test_while_program() {
+    // Doubles the stack top until it exceeds 100.
+    let output_stack = act_on_stack(
+      &WhileProgramCommand::new(),
+      (
+        setup_default_simplifier,
+        setup_default_dispatch_table,
+        |_: &mut Vec<String>, state: &mut ApplicationState, _: &mut CommandContext| {
+          state.variable_table_mut().insert(Var::new("x").unwrap(), Expr::from(3));
+        },
+      ),
+      vec![
+        Expr::from(3),
+        Expr::call("<", vec![Expr::var("x").unwrap(), Expr::from(100)]),
+        program(vec![
+          Expr::from(2),
+          cmd("*"),
+          cmd(r#"{"name": "store_var", "args": ["x"], "options": {"keepModifier": true}}"#),
+        ]),
+      ],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![192]));
+  }
+
+  #[test]
+  fn test_while_program_exceeding_cap() {
+    let mut state = crate::state::test_utils::state_for_stack(vec![
+      Expr::from(1),
+      program(vec![]),
+    ]);
+    let mut args = Vec::new();
+    let mut context = CommandContext::default();
+    setup_default_dispatch_table(&mut args, &mut state, &mut context);
+    let err = WhileProgramCommand::new().run_command(&mut state, args, &context).unwrap_err();
+    assert_eq!(err.to_string(), "Loop exceeded 10000 iterations");
+  }
+
+  #[test]
+  fn test_step_program() {
+    let mut state = crate::state::test_utils::state_for_stack(vec![
+      Expr::from(10),
+      program(vec![Expr::from(2), cmd("*")]),
+    ]);
+    let mut args = Vec::new();
+    let mut context = CommandContext::default();
+    setup_default_simplifier(&mut args, &mut state, &mut context);
+    setup_default_dispatch_table(&mut args, &mut state, &mut context);
+    let command = StepProgramCommand::new();
+
+    command.run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    let session = state.program_session().unwrap();
+    assert_eq!(session.next_index(), 0);
+    assert_eq!(session.upcoming(), Some(&Instruction::Push(Expr::from(2))));
+
+    command.run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 2]));
+    let session = state.program_session().unwrap();
+    assert_eq!(session.upcoming(), Some(&Instruction::Run(ProgramStep::new("*"))));
+
+    command.run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![20]));
+    assert!(state.program_session().unwrap().is_finished());
+  }
+
+  #[test]
+  fn test_step_program_abort() {
+    let mut state = crate::state::test_utils::state_for_stack(vec![
+      program(vec![Expr::from(2)]),
+    ]);
+    let mut context = CommandContext::default();
+    setup_default_dispatch_table(&mut vec![], &mut state, &mut context);
+    StepProgramCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    assert!(state.program_session().is_some());
+    context.opts = CommandOptions::default().with_inverse_modifier();
+    StepProgramCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    assert!(state.program_session().is_none());
+    assert_eq!(state.main_stack(), &Stack::new());
+  }
+
+  #[test]
+  fn test_self_applying_program_fails() {
+    let recursive = program(vec![cmd("dup"), cmd("eval_program")]);
+    let mut state = crate::state::test_utils::state_for_stack(vec![recursive]);
+    let mut context = CommandContext::default();
+    setup_default_dispatch_table(&mut vec![], &mut state, &mut context);
+    let err = EvalProgramCommand::new().run_command(&mut state, vec![], &context).unwrap_err();
+    let err = err.downcast::<ProgramStepError>().unwrap();
+    assert_eq!(err.name, "eval_program");
+    assert!(err.error.is::<NestingDepthError>());
+  }
+
+  #[test]
+  fn test_unconfirmed_step_fails() {
+    let mut elems: Vec<Expr> = (0..12_i64).map(Expr::from).collect();
+    elems.push(program(vec![cmd("clear_stack"), Expr::from(99)]));
+    let mut state = crate::state::test_utils::state_for_stack(elems);
+    let mut context = CommandContext::default();
+    setup_default_dispatch_table(&mut vec![], &mut state, &mut context);
+    let err = EvalProgramCommand::new().run_command(&mut state, vec![], &context).unwrap_err();
+    let err = err.downcast::<ProgramStepError>().unwrap();
+    assert_eq!(err.index, 0);
+    assert_eq!(err.name, "clear_stack");
+    assert!(err.error.is::<UnconfirmedStepError>());
+    // Neither the step nor anything after it ran.
+    assert_eq!(state.into_main_stack(), stack_of((0..12_i64).collect::<Vec<_>>()));
+  }
+
+  #[test]
+  fn test_confirmed_step_runs() {
+    let mut elems: Vec<Expr> = (0..12_i64).map(Expr::from).collect();
+    elems.push(program(vec![cmd(r#"{"name": "clear_stack", "options": {"confirmed": true}}"#), Expr::from(99)]));
+    let mut state = crate::state::test_utils::state_for_stack(elems);
+    let mut context = CommandContext::default();
+    setup_default_dispatch_table(&mut vec![], &mut state, &mut context);
+    EvalProgramCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.into_main_stack(), stack_of(vec![99]));
+  }
+
+  #[test]
+  fn test_failing_step_reports_index() {
+    let mut state = crate::state::test_utils::state_for_stack(vec![Expr::from(1)]);
+    let mut args = Vec::new();
+    let mut context = CommandContext::default();
+    setup_default_dispatch_table(&mut args, &mut state, &mut context);
+    let prog = Program::parse(program(vec![cmd("pop"), cmd("pop")])).unwrap();
+    let err = prog.execute(&mut state, &context).unwrap_err();
+    assert_eq!(err.index, 1);
+    assert_eq!(err.name, "pop");
+    assert_eq!(state.into_main_stack(), Stack::new());
+  }
+}
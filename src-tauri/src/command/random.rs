@@ -0,0 +1,291 @@
+
+//! Commands which produce random values, and which use random values
+//! to test the simplifier.
+
+use super::arguments::{BinaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::errorlist::ErrorList;
+use crate::expr::Expr;
+use crate::expr::algebra::ExprFunctionN;
+use crate::expr::number::{ComplexLike, ComplexNumber, Number};
+use crate::expr::random::RandomExprGrammar;
+use crate::expr::simplifier::Simplifier;
+use crate::expr::var::Var;
+use crate::util::prism::Identity;
+use crate::state::ApplicationState;
+use crate::stack::base::StackLike;
+
+use rand::Rng;
+
+/// The expression depth used by [`RandomExprCommand`] if no
+/// numerical argument is given.
+pub const DEFAULT_RANDOM_EXPR_DEPTH: usize = 3;
+
+/// The deepest expression [`RandomExprCommand`] will generate. Trees
+/// of binary operators grow exponentially with depth.
+pub const MAX_RANDOM_EXPR_DEPTH: usize = 10;
+
+/// The number of expressions checked by [`CheckSimplifierCommand`] if
+/// no numerical argument is given.
+pub const DEFAULT_SIMPLIFIER_CHECKS: usize = 20;
+
+/// The largest number of expressions [`CheckSimplifierCommand`] will
+/// check in one run.
+pub const MAX_SIMPLIFIER_CHECKS: usize = 1_000;
+
+/// The number of random points at which each expression is compared
+/// against its simplified form.
+const POINTS_PER_CHECK: usize = 5;
+
+/// Variables are sampled uniformly from `[-SAMPLE_RADIUS,
+/// SAMPLE_RADIUS]`.
+const SAMPLE_RADIUS: f64 = 5.0;
+
+/// The relative tolerance used when comparing values.
+const CHECK_TOLERANCE: f64 = 1e-6;
+
+/// This command takes two arguments: a whitespace-separated list of
+/// operators and a whitespace-separated list of variable names. It
+/// pushes a random expression built from those operators, the
+/// variables, and small positive integers. The operators must be
+/// among [`RANDOM_OPERATORS`](crate::expr::random::RANDOM_OPERATORS),
+/// and the variable list may be empty.
+///
+/// The numerical argument is the maximum depth of the expression,
+/// defaulting to [`DEFAULT_RANDOM_EXPR_DEPTH`]. The expression is
+/// pushed without simplification, so that it can serve as an
+/// exercise to be simplified.
+#[derive(Debug, Default)]
+pub struct RandomExprCommand {
+  _priv: (),
+}
+
+/// Audits the simplifier. This command takes the same two arguments
+/// as [`RandomExprCommand`] and generates random expressions of depth
+/// [`DEFAULT_RANDOM_EXPR_DEPTH`] from them. Each expression is
+/// simplified, and the original and simplified forms are evaluated
+/// numerically at several random points. Pushes a vector containing
+/// a pair `[original, simplified]` for every expression where the two
+/// disagree, so an empty vector means no problems were found.
+///
+/// Points where either form fails to evaluate to a finite number are
+/// skipped, since simplification may legitimately remove a
+/// singularity, as in `x / x = 1`.
+///
+/// The numerical argument is the number of expressions to check,
+/// defaulting to [`DEFAULT_SIMPLIFIER_CHECKS`].
+#[derive(Debug, Default)]
+pub struct CheckSimplifierCommand {
+  _priv: (),
+}
+
+impl RandomExprCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl CheckSimplifierCommand {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+fn grammar_argument_schema() -> BinaryArgumentSchema<Identity, String, Identity, String> {
+  BinaryArgumentSchema::new(
+    "operator list".to_owned(),
+    Identity,
+    "variable list".to_owned(),
+    Identity,
+  )
+}
+
+/// Parses the arguments shared by the commands in this module.
+fn parse_grammar(operators: &str, variables: &str, depth: usize) -> anyhow::Result<RandomExprGrammar> {
+  let operators: Vec<&str> = operators.split_whitespace().collect();
+  let variables = variables.split_whitespace()
+    .map(|name| Var::new(name).ok_or_else(|| anyhow::anyhow!("Invalid variable name: {name}")))
+    .collect::<anyhow::Result<Vec<_>>>()?;
+  Ok(RandomExprGrammar::new(depth, &operators, variables)?)
+}
+
+/// Compares two expressions in `vars` at each of the given points,
+/// returning the first point at which they disagree.
+fn find_mismatch(
+  original: &Expr,
+  simplified: &Expr,
+  vars: &[Var],
+  points: &[Vec<f64>],
+  simplifier: &dyn Simplifier,
+) -> Option<Vec<f64>> {
+  let original = ExprFunctionN::new(original.clone(), vars.to_vec(), simplifier);
+  let simplified = ExprFunctionN::new(simplified.clone(), vars.to_vec(), simplifier);
+  let eval = |function: &ExprFunctionN, point: &[f64]| {
+    let values = point.iter().map(|x| ComplexLike::Real(Number::from(*x))).collect();
+    let value = ComplexNumber::from(function.eval_at_complex(values).ok()?);
+    let value = (value.real().to_f64_or_nan(), value.imag().to_f64_or_nan());
+    (value.0.is_finite() && value.1.is_finite()).then_some(value)
+  };
+  points.iter()
+    .find(|point| {
+      let (Some(a), Some(b)) = (eval(&original, point), eval(&simplified, point)) else {
+        return false;
+      };
+      let difference = (a.0 - b.0).hypot(a.1 - b.1);
+      let scale = 1f64.max(a.0.hypot(a.1)).max(b.0.hypot(b.1));
+      difference > CHECK_TOLERANCE * scale
+    })
+    .cloned()
+}
+
+impl Command for RandomExprCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (operators, variables) = validate_schema(&grammar_argument_schema(), args)?;
+    let depth = context.opts.argument.unwrap_or(DEFAULT_RANDOM_EXPR_DEPTH as i64);
+    anyhow::ensure!(
+      (0..=MAX_RANDOM_EXPR_DEPTH as i64).contains(&depth),
+      "Expected depth between 0 and {MAX_RANDOM_EXPR_DEPTH}, got {depth}",
+    );
+    let grammar = parse_grammar(&operators, &variables, depth as usize)?;
+
+    state.undo_stack_mut().push_cut();
+    let expr = grammar.generate(&mut rand::thread_rng());
+    state.main_stack_mut().push(expr);
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for CheckSimplifierCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (operators, variables) = validate_schema(&grammar_argument_schema(), args)?;
+    let checks = context.opts.argument.unwrap_or(DEFAULT_SIMPLIFIER_CHECKS as i64);
+    anyhow::ensure!(
+      checks > 0 && checks as usize <= MAX_SIMPLIFIER_CHECKS,
+      "Expected number of checks between 1 and {MAX_SIMPLIFIER_CHECKS}, got {checks}",
+    );
+    let grammar = parse_grammar(&operators, &variables, DEFAULT_RANDOM_EXPR_DEPTH)?;
+    let calculation_mode = state.calculation_mode().clone();
+
+    let mut rng = rand::thread_rng();
+    let mut mismatches = Vec::new();
+    for _ in 0..checks {
+      let original = grammar.generate(&mut rng);
+      // Errors from the simplifier are expected here, since random
+      // expressions frequently divide by zero and the like.
+      let simplified = context.simplify_expr(original.clone(), calculation_mode.clone(), &mut ErrorList::new());
+      let points: Vec<Vec<f64>> = (0..POINTS_PER_CHECK)
+        .map(|_| grammar.variables.iter().map(|_| rng.gen_range(-SAMPLE_RADIUS..=SAMPLE_RADIUS)).collect())
+        .collect();
+      if find_mismatch(&original, &simplified, &grammar.variables, &points, context.simplifier.as_ref()).is_some() {
+        mismatches.push(Expr::call("vector", vec![original, simplified]));
+      }
+    }
+
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().push(Expr::call("vector", mismatches));
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
+  use crate::expr::simplifier::default_simplifier;
+  use crate::expr::function::library::build_function_table;
+
+  #[test]
+  fn test_random_expr() {
+    let opts = CommandOptions::numerical(2);
+    let output_stack = act_on_stack(&RandomExprCommand::new(), (vec!["+ *", "x y"], opts), vec![10]).unwrap();
+    let elems: Vec<Expr> = output_stack.into_iter().collect();
+    assert_eq!(elems.len(), 2);
+    assert_eq!(elems[0], Expr::from(10));
+    let Expr::Call(name, args) = &elems[1] else {
+      panic!("Expected a call, got {}", elems[1]);
+    };
+    assert!(name == "+" || name == "*");
+    assert_eq!(args.len(), 2);
+  }
+
+  #[test]
+  fn test_random_expr_depth_zero() {
+    let opts = CommandOptions::numerical(0);
+    let output_stack = act_on_stack(&RandomExprCommand::new(), (vec!["+", "x"], opts), Vec::<Expr>::new()).unwrap();
+    let elems: Vec<Expr> = output_stack.into_iter().collect();
+    assert!(matches!(elems.as_slice(), [Expr::Atom(_)]));
+  }
+
+  #[test]
+  fn test_random_expr_invalid_arguments() {
+    let command = RandomExprCommand::new();
+    act_on_stack(&command, vec!["+ frobnicate", "x"], Vec::<Expr>::new()).unwrap_err();
+    act_on_stack(&command, vec!["+", "x 1y"], Vec::<Expr>::new()).unwrap_err();
+    act_on_stack(&command, (vec!["+", "x"], CommandOptions::numerical(-1)), Vec::<Expr>::new()).unwrap_err();
+    let too_deep = CommandOptions::numerical(MAX_RANDOM_EXPR_DEPTH as i64 + 1);
+    act_on_stack(&command, (vec!["+", "x"], too_deep), vec![1]).unwrap_err();
+  }
+
+  #[test]
+  fn test_find_mismatch() {
+    let function_table = build_function_table();
+    let simplifier = default_simplifier(&function_table);
+    let x = || Expr::var("x").unwrap();
+    let vars = vec![Var::new("x").unwrap()];
+    let points = vec![vec![0.5], vec![-2.25]];
+    let original = Expr::call("+", vec![x(), x()]);
+    let correct = Expr::call("*", vec![Expr::from(2), x()]);
+    let wrong = Expr::call("*", vec![Expr::from(3), x()]);
+    assert_eq!(find_mismatch(&original, &correct, &vars, &points, simplifier.as_ref()), None);
+    assert_eq!(find_mismatch(&original, &wrong, &vars, &points, simplifier.as_ref()), Some(vec![0.5]));
+  }
+
+  #[test]
+  fn test_find_mismatch_skips_undefined_points() {
+    let function_table = build_function_table();
+    let simplifier = default_simplifier(&function_table);
+    let x = || Expr::var("x").unwrap();
+    let vars = vec![Var::new("x").unwrap()];
+    let original = Expr::call("/", vec![x(), x()]);
+    let points = vec![vec![0.0], vec![1.5]];
+    assert_eq!(find_mismatch(&original, &Expr::from(1), &vars, &points, simplifier.as_ref()), None);
+  }
+
+  #[test]
+  fn test_check_simplifier() {
+    let opts = CommandOptions::numerical(10);
+    let output_stack = act_on_stack(
+      &CheckSimplifierCommand::new(),
+      (setup_default_simplifier, vec!["+ - *", "x y"], opts),
+      vec![1],
+    ).unwrap();
+    let elems: Vec<Expr> = output_stack.into_iter().collect();
+    assert_eq!(elems, vec![Expr::from(1), Expr::call("vector", vec![])]);
+  }
+
+  #[test]
+  fn test_check_simplifier_invalid_count() {
+    let command = CheckSimplifierCommand::new();
+    act_on_stack(&command, (vec!["+", "x"], CommandOptions::numerical(0)), Vec::<Expr>::new()).unwrap_err();
+  }
+}
@@ -0,0 +1,226 @@
+
+//! Commands for managing user-defined rewrite rules and matching
+//! patterns.
+
+use super::arguments::{NullaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::expr::Expr;
+use crate::expr::rewrite::RewriteRule;
+use crate::expr::rewrite::matcher::match_pattern;
+use crate::expr::vector::Vector;
+use crate::state::ApplicationState;
+use crate::state::undo::AddRewriteRuleChange;
+use crate::stack::base::{StackLike, RandomAccessStackLike};
+use crate::stack::keepable::KeepableStack;
+
+/// This command takes no arguments. The top stack element must be a
+/// rewrite rule of the form `lhs := rhs`. Adds the rule to the
+/// state's rewrite rules, with lower priority than any existing rule.
+/// From then on, the simplifier rewrites any subexpression matching
+/// `lhs` to `rhs`.
+///
+/// Fails if the stack is empty or if the top element is not a valid
+/// rule. Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct AddRewriteRuleCommand {
+  _priv: (),
+}
+
+/// This command takes no arguments. Pops two values `pattern` and
+/// `target` off the stack and matches `target` against `pattern`, as
+/// per [`match_pattern`]. If the match succeeds, pushes a vector of
+/// equations `v = value`, one for each pattern variable `v` in the
+/// pattern, sorted by variable name. If the match fails, pushes 0.
+///
+/// Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct MatchExprCommand {
+  _priv: (),
+}
+
+impl AddRewriteRuleCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl MatchExprCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Command for AddRewriteRuleCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+
+    // Validate before touching the stack, so a bad rule leaves the
+    // stack as it was.
+    let top = state.main_stack().get(0)?.clone();
+    let rule = RewriteRule::from_expr(top)?;
+
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    stack.pop()?;
+    state.rewrite_rules_mut().push(rule.clone());
+    state.undo_stack_mut().push_change(AddRewriteRuleChange::new(rule));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for MatchExprCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [pattern, target] = stack.pop_several(2)?.try_into().unwrap();
+    let result = match match_pattern(&pattern, &target) {
+      None => Expr::zero(),
+      Some(bindings) => {
+        let mut vars: Vec<_> = bindings.keys().collect();
+        vars.sort();
+        let equations = vars.into_iter().map(|var| {
+          let value = bindings.get(var).unwrap().clone(); // unwrap: `var` is a key of `bindings`
+          Expr::call("=", vec![Expr::from(var.clone()), value])
+        });
+        equations.collect::<Vector>().into()
+      }
+    };
+    stack.push(result);
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::test_utils::act_on_stack;
+  use crate::errorlist::ErrorList;
+  use crate::expr::Expr;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::function::user::UserFunctionTable;
+  use crate::expr::rewrite::RewriteRuleError;
+  use crate::expr::simplifier::default_simplifier_with_rewrite_rules;
+  use crate::stack::test_utils::stack_of;
+  use crate::state::test_utils::state_for_stack;
+
+  fn var(name: &str) -> Expr {
+    Expr::var(name).unwrap()
+  }
+
+  /// The rule `f(x_, x_) := 2 * f(x_)`.
+  fn sample_rule() -> Expr {
+    let lhs = Expr::call("f", vec![var("x_"), var("x_")]);
+    let rhs = Expr::call("*", vec![Expr::from(2), Expr::call("f", vec![var("x_")])]);
+    Expr::call(":=", vec![lhs, rhs])
+  }
+
+  #[test]
+  fn test_add_rewrite_rule() {
+    let mut state = state_for_stack(vec![Expr::from(10), sample_rule()]);
+    AddRewriteRuleCommand::new().run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    assert_eq!(state.rewrite_rules().len(), 1);
+  }
+
+  #[test]
+  fn test_add_rewrite_rule_is_undoable() {
+    let mut state = state_for_stack(vec![sample_rule()]);
+    AddRewriteRuleCommand::new().run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    state.undo().unwrap();
+    assert!(state.rewrite_rules().is_empty());
+    assert_eq!(state.main_stack(), &stack_of(vec![sample_rule()]));
+    state.redo().unwrap();
+    assert_eq!(state.rewrite_rules().len(), 1);
+  }
+
+  #[test]
+  fn test_rewrite_rule_is_applied() {
+    let mut state = state_for_stack(vec![sample_rule()]);
+    AddRewriteRuleCommand::new().run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+
+    let function_table = build_function_table();
+    let context = CommandContext {
+      simplifier: default_simplifier_with_rewrite_rules(&function_table, UserFunctionTable::new(), state.rewrite_rules().clone()),
+      ..CommandContext::default()
+    };
+    let mut errors = ErrorList::new();
+    let expr = Expr::call("f", vec![Expr::from(3), Expr::call("+", vec![Expr::from(1), Expr::from(2)])]);
+    let expr = context.simplify_expr(expr, state.calculation_mode().clone(), &mut errors);
+    assert!(errors.is_empty());
+    assert_eq!(expr, Expr::call("*", vec![Expr::from(2), Expr::call("f", vec![Expr::from(3)])]));
+  }
+
+  #[test]
+  fn test_match_expr() {
+    let pattern = Expr::call("+", vec![var("a_"), Expr::call("^", vec![var("b_"), Expr::from(2)])]);
+    let target = Expr::call("+", vec![Expr::from(1), Expr::call("^", vec![var("x"), Expr::from(2)])]);
+    let output_stack = act_on_stack(&MatchExprCommand::new(), (), vec![Expr::from(10), pattern, target]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("vector", vec![
+        Expr::call("=", vec![var("a_"), Expr::from(1)]),
+        Expr::call("=", vec![var("b_"), var("x")]),
+      ]),
+    ]));
+  }
+
+  #[test]
+  fn test_match_expr_failure() {
+    let pattern = Expr::call("f", vec![var("x_"), var("x_")]);
+    let target = Expr::call("f", vec![Expr::from(1), Expr::from(2)]);
+    let output_stack = act_on_stack(&MatchExprCommand::new(), (), vec![pattern, target]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![0]));
+  }
+
+  #[test]
+  fn test_match_expr_without_pattern_vars() {
+    let output_stack = act_on_stack(&MatchExprCommand::new(), (), vec![var("x"), var("x")]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::call("vector", vec![])]));
+  }
+
+  #[test]
+  fn test_match_expr_with_keep_modifier() {
+    let opts = CommandOptions::default().with_keep_modifier();
+    let output_stack = act_on_stack(&MatchExprCommand::new(), opts, vec![var("y_"), Expr::from(3)]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      var("y_"),
+      Expr::from(3),
+      Expr::call("vector", vec![Expr::call("=", vec![var("y_"), Expr::from(3)])]),
+    ]));
+  }
+
+  #[test]
+  fn test_match_expr_empty_stack() {
+    act_on_stack(&MatchExprCommand::new(), (), vec![Expr::from(1)]).unwrap_err();
+  }
+
+  #[test]
+  fn test_add_rewrite_rule_invalid() {
+    let err = act_on_stack(&AddRewriteRuleCommand::new(), (), vec![Expr::from(10)]).unwrap_err();
+    assert_eq!(err.downcast::<RewriteRuleError>().unwrap(), RewriteRuleError::ExpectedRule(Expr::from(10)));
+  }
+}
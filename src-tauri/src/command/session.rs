@@ -0,0 +1,177 @@
+
+//! Commands for managing the persisted session and its history.
+
+use super::arguments::{NullaryArgumentSchema, UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::confirmation::RequireConfirmation;
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::state::ApplicationState;
+use crate::expr::prisms::{StringToUsize, ParsedUsize};
+
+/// Discards the entire session, returning the calculator to the
+/// state it is in on first launch: the stack, variables, bookmarks,
+/// user functions, and calculation modes are all reset, and the undo
+/// history is cleared. The saved session is overwritten accordingly
+/// once the command completes.
+///
+/// This command cannot be undone.
+#[derive(Debug, Default)]
+pub struct ResetSessionCommand {
+  _priv: (),
+}
+
+/// Sets one of the limits on the undo history. Expects a single
+/// argument: the new limit, or zero to remove the limit. The depth
+/// variant limits the number of undo actions, and the memory variant
+/// limits the approximate memory used by the history, in megabytes.
+/// If the history already exceeds the new limit, the oldest undo
+/// actions are discarded immediately.
+///
+/// This command does not itself create an undo action. Does not use
+/// the keep modifier or numerical argument.
+#[derive(Debug, Clone)]
+pub struct SetUndoLimitCommand {
+  limit: UndoLimitKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UndoLimitKind {
+  Depth,
+  Memory,
+}
+
+const BYTES_PER_MEGABYTE: usize = 1024 * 1024;
+
+impl ResetSessionCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+/// [`ResetSessionCommand`], guarded so that it always requires
+/// confirmation.
+pub fn reset_session_command() -> RequireConfirmation<ResetSessionCommand> {
+  RequireConfirmation::new(ResetSessionCommand::new(), |_, _, _| {
+    Some("Reset the session? This clears the stack, variables, bookmarks, and user functions, and cannot be undone.".to_owned())
+  })
+}
+
+impl SetUndoLimitCommand {
+  pub fn depth() -> Self {
+    Self { limit: UndoLimitKind::Depth }
+  }
+
+  pub fn memory() -> Self {
+    Self { limit: UndoLimitKind::Memory }
+  }
+
+  fn argument_schema(&self) -> UnaryArgumentSchema<StringToUsize, ParsedUsize> {
+    let description = match self.limit {
+      UndoLimitKind::Depth => "maximum number of undo actions, or 0 to disable",
+      UndoLimitKind::Memory => "maximum undo memory in megabytes, or 0 to disable",
+    };
+    UnaryArgumentSchema::new(description.to_owned(), StringToUsize)
+  }
+}
+
+impl Command for ResetSessionCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    *state = ApplicationState::with_constants();
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for SetUndoLimitCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let value = usize::from(validate_schema(&self.argument_schema(), args)?);
+    let value = (value > 0).then_some(value);
+    let mut limits = state.undo_stack().limits();
+    match self.limit {
+      UndoLimitKind::Depth => {
+        limits.max_depth = value;
+      }
+      UndoLimitKind::Memory => {
+        limits.max_bytes = value
+          .map(|megabytes| megabytes.checked_mul(BYTES_PER_MEGABYTE).ok_or_else(|| anyhow::anyhow!("Memory limit is too large")))
+          .transpose()?;
+      }
+    }
+    state.undo_stack_mut().set_limits(limits);
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::Expr;
+  use crate::expr::var::Var;
+  use crate::state::test_utils::state_for_stack;
+  use crate::stack::base::StackLike;
+
+  #[test]
+  fn test_reset_session() {
+    let mut state = state_for_stack(vec![1, 2, 3]);
+    state.variable_table_mut().insert(Var::new("x").unwrap(), Expr::from(10));
+    state.undo_stack_mut().push_cut();
+    ResetSessionCommand::new().run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert!(state.main_stack().is_empty());
+    assert!(state.variable_table().get(&Var::new("x").unwrap()).is_none());
+    assert!(state.variable_table().get(&Var::new("pi").unwrap()).is_some());
+    assert!(state.undo().is_err());
+  }
+
+  #[test]
+  fn test_reset_session_requires_confirmation() {
+    let mut state = state_for_stack(vec![1, 2, 3]);
+    let output = reset_session_command().run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert!(output.confirmation_request().is_some());
+    assert_eq!(state.main_stack().len(), 3);
+  }
+
+  #[test]
+  fn test_set_undo_depth_limit() {
+    let mut state = state_for_stack(vec![1, 2, 3]);
+    for _ in 0..3 {
+      state.undo_stack_mut().push_cut();
+      state.main_stack_mut().push(Expr::from(0));
+    }
+    assert_eq!(state.undo_stack().undo_depth(), 3);
+    SetUndoLimitCommand::depth().run_command(&mut state, vec!["2".to_owned()], &CommandContext::default()).unwrap();
+    assert_eq!(state.undo_stack().limits().max_depth, Some(2));
+    assert_eq!(state.undo_stack().undo_depth(), 2);
+
+    SetUndoLimitCommand::depth().run_command(&mut state, vec!["0".to_owned()], &CommandContext::default()).unwrap();
+    assert_eq!(state.undo_stack().limits().max_depth, None);
+  }
+
+  #[test]
+  fn test_set_undo_memory_limit() {
+    let mut state = ApplicationState::new();
+    SetUndoLimitCommand::memory().run_command(&mut state, vec!["3".to_owned()], &CommandContext::default()).unwrap();
+    assert_eq!(state.undo_stack().limits().max_bytes, Some(3 * 1024 * 1024));
+    let too_large = usize::MAX.to_string();
+    SetUndoLimitCommand::memory().run_command(&mut state, vec![too_large], &CommandContext::default()).unwrap_err();
+    SetUndoLimitCommand::memory().run_command(&mut state, vec!["-1".to_owned()], &CommandContext::default()).unwrap_err();
+  }
+}
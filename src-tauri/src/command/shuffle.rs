@@ -4,7 +4,8 @@
 use super::base::{Command, CommandContext, CommandOutput};
 use super::arguments::{NullaryArgumentSchema, BinaryArgumentSchema, validate_schema};
 use super::options::CommandOptions;
-use super::subcommand::Subcommand;
+use super::subcommand::{Subcommand, StringToSubcommandId, ParsedSubcommandId};
+use super::confirmation::{RequireConfirmation, confirm_discarding, DEFAULT_CONFIRMATION_THRESHOLD};
 use crate::state::ApplicationState;
 use crate::stack::keepable::KeepableStack;
 use crate::stack::base::{StackLike, RandomAccessStackLike};
@@ -22,6 +23,11 @@ use std::cmp::Ordering;
 #[derive(Debug, Clone)]
 pub struct PopCommand;
 
+/// Discards every value on the stack. Ignores the numerical argument
+/// and the "keep" modifier.
+#[derive(Debug, Clone)]
+pub struct ClearStackCommand;
+
 /// Swaps the top two stack values.
 #[derive(Debug, Clone)]
 pub struct SwapCommand;
@@ -58,6 +64,123 @@ pub struct ReplaceStackElemCommand {
   pub is_mouse_interaction: bool,
 }
 
+/// Applies a subcommand to a single stack element in place. Takes two
+/// arguments: `pos` and a subcommand identifier. The former must be a
+/// non-negative integer.
+///
+/// If the subcommand is unary, it is applied to the element at
+/// position `pos`, and the result replaces that element. If the
+/// subcommand is binary, the top of the stack is popped and used as
+/// the second argument, with the element at position `pos` as the
+/// first. In the binary case, `pos` is indexed relative to the stack
+/// _before_ the top is popped, so it must be at least 1.
+///
+/// This command does not use the numerical argument. With the "keep"
+/// modifier, the operands are left on the stack and the result is
+/// inserted at position `pos`, just above the original element.
+#[derive(Debug, Clone)]
+pub struct ModifyStackElemCommand;
+
+/// Deletes a contiguous range of stack elements. Takes two arguments:
+/// the stack positions (counting from zero at the top of the stack)
+/// of either end of the range, in either order. Both ends are
+/// included.
+///
+/// Does not use the numerical argument or the "keep" modifier.
+#[derive(Debug, Clone)]
+pub struct DeleteRangeCommand;
+
+/// Pushes copies of a contiguous range of stack elements onto the top
+/// of the stack, in their original order. Takes the same arguments as
+/// [`DeleteRangeCommand`].
+///
+/// Does not use the numerical argument or the "keep" modifier.
+#[derive(Debug, Clone)]
+pub struct DuplicateRangeCommand;
+
+/// Rotates a contiguous range of stack elements. Takes the same
+/// arguments as [`DeleteRangeCommand`].
+///
+/// With a positive numerical argument N (the default is 1), the
+/// element at the top of the range is buried N positions deeper, and
+/// the elements between shift up to fill the gap, wrapping around
+/// within the range. With a negative argument, the rotation goes the
+/// other way. Does not use the "keep" modifier, since no information
+/// is destroyed.
+#[derive(Debug, Clone)]
+pub struct RotateRangeCommand;
+
+/// Replaces a contiguous range of stack elements with a single vector
+/// containing them, with the deepest element first. Takes the same
+/// arguments as [`DeleteRangeCommand`].
+///
+/// With the "keep" modifier, the original elements are left in place
+/// and the vector is pushed onto the top of the stack instead. Does
+/// not use the numerical argument.
+#[derive(Debug, Clone)]
+pub struct CollectRangeToVectorCommand;
+
+fn range_argument_schema() -> BinaryArgumentSchema<StringToUsize, ParsedUsize, StringToUsize, ParsedUsize> {
+  BinaryArgumentSchema::new(
+    "nonnegative integer".to_owned(),
+    StringToUsize,
+    "nonnegative integer".to_owned(),
+    StringToUsize,
+  )
+}
+
+/// Parses the arguments to a range command, producing the stack
+/// positions of the top and bottom of the range, in that order.
+fn validate_range_arguments(args: Vec<String>) -> anyhow::Result<(usize, usize)> {
+  let (a, b) = validate_schema(&range_argument_schema(), args)?;
+  let (a, b) = (usize::from(a), usize::from(b));
+  Ok((a.min(b), a.max(b)))
+}
+
+/// Removes the elements at positions `top..=bottom`, returning them
+/// with the deepest element first. The stack must be large enough.
+fn pop_range<S>(stack: &mut S, top: usize, bottom: usize) -> Vec<S::Elem>
+where S: RandomAccessStackLike {
+  let mut elements: Vec<_> = (top..=bottom)
+    .map(|_| stack.pop_nth(top).expect("Stack underflow"))
+    .collect();
+  elements.reverse();
+  elements
+}
+
+/// Clones the elements at positions `top..=bottom`, with the deepest
+/// element first. The stack must be large enough.
+fn copy_range<S>(stack: &S, top: usize, bottom: usize) -> Vec<S::Elem>
+where S: RandomAccessStackLike,
+      S::Elem: Clone {
+  (top..=bottom).rev()
+    .map(|i| stack.get(i as i64).expect("Stack underflow").clone())
+    .collect()
+}
+
+/// Inverse of [`pop_range`]. Inserts `elements` (deepest first) so
+/// that the topmost of them ends up at position `top`.
+fn insert_range<S>(stack: &mut S, top: usize, elements: Vec<S::Elem>)
+where S: RandomAccessStackLike {
+  for element in elements {
+    stack.insert(top, element).expect("Stack insert out of bounds");
+  }
+}
+
+/// [`DeleteRangeCommand`], guarded so that deleting more than
+/// [`DEFAULT_CONFIRMATION_THRESHOLD`] elements at once requires
+/// confirmation.
+pub fn delete_range_command() -> RequireConfirmation<DeleteRangeCommand> {
+  RequireConfirmation::new(DeleteRangeCommand, |state, args, _| {
+    let (top, bottom) = validate_range_arguments(args.to_vec()).ok()?;
+    if bottom >= state.main_stack().len() {
+      // Out of bounds, which will fail anyway.
+      return None;
+    }
+    confirm_discarding(bottom - top + 1, DEFAULT_CONFIRMATION_THRESHOLD)
+  })
+}
+
 impl MoveStackElemCommand {
   fn argument_schema() -> BinaryArgumentSchema<StringToUsize, ParsedUsize, StringToUsize, ParsedUsize> {
     BinaryArgumentSchema::new(
@@ -84,6 +207,42 @@ impl ReplaceStackElemCommand {
   }
 }
 
+impl ModifyStackElemCommand {
+  fn argument_schema() -> BinaryArgumentSchema<StringToUsize, ParsedUsize, StringToSubcommandId, ParsedSubcommandId> {
+    BinaryArgumentSchema::new(
+      "nonnegative integer".to_owned(),
+      StringToUsize,
+      "subcommand identifier".to_owned(),
+      StringToSubcommandId,
+    )
+  }
+}
+
+/// [`PopCommand`], guarded so that discarding more than
+/// [`DEFAULT_CONFIRMATION_THRESHOLD`] elements at once requires
+/// confirmation.
+pub fn pop_command() -> RequireConfirmation<PopCommand> {
+  RequireConfirmation::new(PopCommand, |state, _, ctx| {
+    let stack_len = state.main_stack().len();
+    let count = match ctx.opts.argument.unwrap_or(1) {
+      0 => stack_len,
+      n if n > 0 && n as usize <= stack_len => n as usize,
+      // Out of bounds (which will fail anyway) or popping a single
+      // element.
+      _ => return None,
+    };
+    confirm_discarding(count, DEFAULT_CONFIRMATION_THRESHOLD)
+  })
+}
+
+/// Clears the entire stack. Requires confirmation if this would
+/// discard more than [`DEFAULT_CONFIRMATION_THRESHOLD`] elements.
+pub fn clear_stack_command() -> RequireConfirmation<ClearStackCommand> {
+  RequireConfirmation::new(ClearStackCommand, |state, _, _| {
+    confirm_discarding(state.main_stack().len(), DEFAULT_CONFIRMATION_THRESHOLD)
+  })
+}
+
 impl Command for PopCommand {
   fn run_command(
     &self,
@@ -120,6 +279,24 @@ impl Command for PopCommand {
   }
 }
 
+impl Command for ClearStackCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().pop_all();
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
 impl Command for SwapCommand {
   fn run_command(
     &self,
@@ -241,6 +418,109 @@ impl Command for MoveStackElemCommand {
     None
   }
 }
+impl Command for DeleteRangeCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (top, bottom) = validate_range_arguments(args)?;
+    state.undo_stack_mut().push_cut();
+    let mut stack = state.main_stack_mut();
+    stack.check_stack_size(bottom + 1)?;
+    pop_range(&mut stack, top, bottom);
+    Ok(
+      CommandOutput::success()
+        .set_force_scroll_down(false)
+    )
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for DuplicateRangeCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (top, bottom) = validate_range_arguments(args)?;
+    state.undo_stack_mut().push_cut();
+    let mut stack = state.main_stack_mut();
+    stack.check_stack_size(bottom + 1)?;
+    let elements = copy_range(&stack, top, bottom);
+    stack.push_several(elements);
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for RotateRangeCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (top, bottom) = validate_range_arguments(args)?;
+    state.undo_stack_mut().push_cut();
+    let mut stack = state.main_stack_mut();
+    stack.check_stack_size(bottom + 1)?;
+
+    let len = (bottom - top + 1) as i64;
+    let amount = ctx.opts.argument.unwrap_or(1).rem_euclid(len) as usize;
+    if amount == 0 {
+      // Nothing to do, so don't clutter the undo stack.
+      return Ok(CommandOutput::success().set_force_scroll_down(false));
+    }
+    let mut elements = pop_range(&mut stack, top, bottom);
+    elements.rotate_right(amount);
+    insert_range(&mut stack, top, elements);
+    Ok(
+      CommandOutput::success()
+        .set_force_scroll_down(false)
+    )
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for CollectRangeToVectorCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (top, bottom) = validate_range_arguments(args)?;
+    state.undo_stack_mut().push_cut();
+    let mut stack = state.main_stack_mut();
+    stack.check_stack_size(bottom + 1)?;
+
+    if ctx.opts.keep_modifier {
+      let elements = copy_range(&stack, top, bottom);
+      stack.push(Expr::call("vector", elements));
+    } else {
+      let elements = pop_range(&mut stack, top, bottom);
+      stack.insert(top, Expr::call("vector", elements)).expect("Stack insert out of bounds");
+    }
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
 impl Command for ReplaceStackElemCommand {
   fn run_command(
     &self,
@@ -285,10 +565,63 @@ impl Command for ReplaceStackElemCommand {
   }
 }
 
+impl Command for ModifyStackElemCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (index, subcommand_id) = validate_schema(&Self::argument_schema(), args)?;
+    let index = usize::from(index);
+    let calculation_mode = state.calculation_mode().clone();
+    let mut errors = ErrorList::new();
+    let subcommand = subcommand_id.as_ref().get_subcommand(ctx.dispatch_table)?;
+    state.undo_stack_mut().push_cut();
+
+    let mut stack = state.main_stack_mut();
+    let operands = match subcommand.arity() {
+      1 => vec![stack.get(index as i64)?.clone()],
+      2 => {
+        anyhow::ensure!(index > 0, "Cannot combine the top of the stack with itself");
+        vec![stack.get(index as i64)?.clone(), stack.get(0)?.clone()]
+      }
+      arity => anyhow::bail!("Expected unary or binary subcommand, got arity {arity}"),
+    };
+    // call_or_panic: We built the operand list to match the arity.
+    let expr = subcommand.call_or_panic(operands, ctx.simplifier.as_ref(), calculation_mode, &mut errors);
+
+    if ctx.opts.keep_modifier {
+      stack.insert(index, expr)?;
+    } else {
+      let index = if subcommand.arity() == 2 {
+        stack.pop()?;
+        index - 1
+      } else {
+        index
+      };
+      let mut stack_elem = stack.get_mut(index as i64)?;
+      *stack_elem = expr;
+    }
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::command::test_utils::act_on_stack;
+  use crate::command::test_utils::{act_on_stack, ActOnStackArg};
+  use crate::command::subcommand::SubcommandId;
+  use crate::command::functional::{UnaryFunctionCommand, BinaryFunctionCommand};
+  use crate::command::dispatch::CommandDispatchTable;
+
+  use once_cell::sync::Lazy;
+
+  use std::collections::HashMap;
   use crate::command::options::CommandOptions;
   use crate::stack::test_utils::stack_of;
   use crate::stack::{Stack, StackError};
@@ -317,6 +650,45 @@ mod tests {
     )
   }
 
+  #[test]
+  fn test_large_pop_requires_confirmation() {
+    let command = pop_command();
+    let input_stack: Vec<i64> = (0..20).collect();
+    let mut state = crate::state::test_utils::state_for_stack(input_stack.clone());
+    let mut context = CommandContext {
+      opts: CommandOptions::numerical(15),
+      ..CommandContext::default()
+    };
+    let output = command.run_command(&mut state, vec![], &context).unwrap();
+    assert!(output.confirmation_request().is_some());
+    assert_eq!(state.main_stack(), &stack_of(input_stack));
+
+    context.opts = CommandOptions::numerical(15).with_confirmation();
+    command.run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack().len(), 5);
+  }
+
+  #[test]
+  fn test_small_pop_does_not_require_confirmation() {
+    let output_stack = act_on_stack(&pop_command(), CommandOptions::numerical(3), vec![10, 20, 30, 40]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![10]));
+  }
+
+  #[test]
+  fn test_clear_stack() {
+    let output_stack = act_on_stack(&clear_stack_command(), (), vec![10, 20, 30]).unwrap();
+    assert_eq!(output_stack, Stack::new());
+  }
+
+  #[test]
+  fn test_clear_stack_is_undoable() {
+    let mut state = crate::state::test_utils::state_for_stack(vec![10, 20]);
+    ClearStackCommand.run_command(&mut state, vec![], &CommandContext::default()).unwrap();
+    assert!(state.main_stack().is_empty());
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20]));
+  }
+
   #[test]
   fn test_multiple_pop() {
     let output_stack = act_on_stack(
@@ -1086,4 +1458,171 @@ mod tests {
       ]),
     )
   }
+
+  #[test]
+  fn test_delete_range() {
+    let input_stack = vec![10, 20, 30, 40, 50, 60, 70];
+    let output_stack = act_on_stack(&DeleteRangeCommand, vec!["1", "3"], input_stack.clone()).unwrap();
+    assert_eq!(output_stack, stack_of(vec![10, 20, 30, 70]));
+    let output_stack = act_on_stack(&DeleteRangeCommand, vec!["3", "1"], input_stack.clone()).unwrap();
+    assert_eq!(output_stack, stack_of(vec![10, 20, 30, 70]));
+    let output_stack = act_on_stack(&DeleteRangeCommand, vec!["0", "0"], input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![10, 20, 30, 40, 50, 60]));
+  }
+
+  #[test]
+  fn test_delete_range_out_of_bounds() {
+    let err = act_on_stack(&DeleteRangeCommand, vec!["2", "7"], vec![10, 20, 30, 40, 50, 60, 70]).unwrap_err();
+    let err = err.downcast::<StackError>().unwrap();
+    assert_eq!(err, StackError::NotEnoughElements { expected: 8, actual: 7 });
+  }
+
+  #[test]
+  fn test_large_delete_range_requires_confirmation() {
+    let input_stack: Vec<i64> = (0..20).collect();
+    let mut state = crate::state::test_utils::state_for_stack(input_stack.clone());
+    let args = vec!["2".to_owned(), "15".to_owned()];
+    let output = delete_range_command().run_command(&mut state, args, &CommandContext::default()).unwrap();
+    assert!(output.confirmation_request().is_some());
+    assert_eq!(state.main_stack(), &stack_of(input_stack));
+  }
+
+  #[test]
+  fn test_duplicate_range() {
+    let output_stack = act_on_stack(&DuplicateRangeCommand, vec!["1", "2"], vec![10, 20, 30, 40]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![10, 20, 30, 40, 20, 30]));
+  }
+
+  #[test]
+  fn test_rotate_range() {
+    let input_stack = vec![10, 20, 30, 40, 50];
+    let output_stack = act_on_stack(&RotateRangeCommand, vec!["1", "3"], input_stack.clone()).unwrap();
+    assert_eq!(output_stack, stack_of(vec![10, 40, 20, 30, 50]));
+    let output_stack = act_on_stack(
+      &RotateRangeCommand,
+      (vec!["1", "3"], CommandOptions::numerical(-1)),
+      input_stack.clone(),
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![10, 30, 40, 20, 50]));
+    let output_stack = act_on_stack(
+      &RotateRangeCommand,
+      (vec!["1", "3"], CommandOptions::numerical(3)),
+      input_stack.clone(),
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(input_stack));
+  }
+
+  #[test]
+  fn test_rotate_range_is_undoable() {
+    let mut state = crate::state::test_utils::state_for_stack(vec![10, 20, 30, 40]);
+    let args = vec!["0".to_owned(), "3".to_owned()];
+    RotateRangeCommand.run_command(&mut state, args, &CommandContext::default()).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![40, 10, 20, 30]));
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30, 40]));
+  }
+
+  #[test]
+  fn test_collect_range_to_vector() {
+    let output_stack = act_on_stack(&CollectRangeToVectorCommand, vec!["2", "1"], vec![10, 20, 30, 40]).unwrap();
+    let vector = Expr::call("vector", vec![Expr::from(20), Expr::from(30)]);
+    assert_eq!(output_stack, stack_of(vec![Expr::from(10), vector, Expr::from(40)]));
+  }
+
+  #[test]
+  fn test_collect_range_to_vector_with_keep_arg() {
+    let output_stack = act_on_stack(
+      &CollectRangeToVectorCommand,
+      (vec!["1", "2"], CommandOptions::default().with_keep_modifier()),
+      vec![10, 20, 30, 40],
+    ).unwrap();
+    let vector = Expr::call("vector", vec![Expr::from(20), Expr::from(30)]);
+    assert_eq!(output_stack, stack_of(vec![Expr::from(10), Expr::from(20), Expr::from(30), Expr::from(40), vector]));
+  }
+
+  fn modify_stack_elem_args(index: &str, subcommand_name: &str) -> impl ActOnStackArg {
+    let subcommand_id = SubcommandId { name: subcommand_name.to_owned(), options: CommandOptions::default() };
+    let args = vec![index.to_owned(), serde_json::to_string(&subcommand_id).unwrap()];
+    (args, |_: &mut Vec<String>, _: &mut ApplicationState, context: &mut CommandContext| {
+      static TABLE: Lazy<CommandDispatchTable> = Lazy::new(|| {
+        let mut hash_map = HashMap::<String, Box<dyn Command + Send + Sync>>::new();
+        hash_map.insert("nop".to_string(), Box::new(crate::command::nullary::NullaryCommand));
+        hash_map.insert("test_func".to_string(), Box::new(UnaryFunctionCommand::named("test_func")));
+        hash_map.insert("test_func2".to_string(), Box::new(BinaryFunctionCommand::named("test_func2")));
+        CommandDispatchTable::from_hash_map(hash_map)
+      });
+      context.dispatch_table = Lazy::force(&TABLE);
+    })
+  }
+
+  #[test]
+  fn test_modify_stack_elem_unary() {
+    let output_stack = act_on_stack(
+      &ModifyStackElemCommand,
+      modify_stack_elem_args("2", "test_func"),
+      vec![10, 20, 30, 40],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("test_func", vec![Expr::from(20)]),
+      Expr::from(30),
+      Expr::from(40),
+    ]));
+  }
+
+  #[test]
+  fn test_modify_stack_elem_binary() {
+    let output_stack = act_on_stack(
+      &ModifyStackElemCommand,
+      modify_stack_elem_args("2", "test_func2"),
+      vec![10, 20, 30, 40],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::call("test_func2", vec![Expr::from(20), Expr::from(40)]),
+      Expr::from(30),
+    ]));
+  }
+
+  #[test]
+  fn test_modify_stack_elem_with_keep_arg() {
+    let output_stack = act_on_stack(
+      &ModifyStackElemCommand,
+      (modify_stack_elem_args("2", "test_func2"), CommandOptions::default().with_keep_modifier()),
+      vec![10, 20, 30, 40],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::from(10),
+      Expr::from(20),
+      Expr::call("test_func2", vec![Expr::from(20), Expr::from(40)]),
+      Expr::from(30),
+      Expr::from(40),
+    ]));
+  }
+
+  #[test]
+  fn test_modify_stack_elem_failures() {
+    // Out of bounds
+    let err = act_on_stack(&ModifyStackElemCommand, modify_stack_elem_args("4", "test_func"), vec![10, 20, 30, 40]).unwrap_err();
+    assert_eq!(err.downcast::<StackError>().unwrap(), StackError::NotEnoughElements { expected: 5, actual: 4 });
+    // Binary operation on the top of the stack
+    act_on_stack(&ModifyStackElemCommand, modify_stack_elem_args("0", "test_func2"), vec![10, 20]).unwrap_err();
+    // Nullary subcommand
+    act_on_stack(&ModifyStackElemCommand, modify_stack_elem_args("0", "nop"), vec![10, 20]).unwrap_err();
+  }
+
+  #[test]
+  fn test_modify_stack_elem_is_undoable() {
+    let mut state = crate::state::test_utils::state_for_stack(vec![10, 20, 30]);
+    let mut args = Vec::new();
+    let mut context = CommandContext::default();
+    modify_stack_elem_args("1", "test_func2").mutate_arg(&mut args, &mut state, &mut context);
+    ModifyStackElemCommand.run_command(&mut state, args, &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![
+      Expr::from(10),
+      Expr::call("test_func2", vec![Expr::from(20), Expr::from(30)]),
+    ]));
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+  }
 }
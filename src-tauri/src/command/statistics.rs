@@ -6,12 +6,13 @@ use crate::errorlist::ErrorList;
 use crate::expr::Expr;
 use crate::expr::vector::Vector;
 use crate::expr::prisms::expr_to_matrix;
+use crate::expr::algebra::regression::{FitModel, StringToFitModel};
 use crate::stack::base::{StackLike, RandomAccessStackLike};
 use crate::stack::keepable::KeepableStack;
 use crate::util::prism::Prism;
 use crate::state::ApplicationState;
 use super::base::{Command, CommandContext, CommandOutput};
-use super::arguments::{NullaryArgumentSchema, validate_schema};
+use super::arguments::{NullaryArgumentSchema, UnaryArgumentSchema, validate_schema};
 use super::options::CommandOptions;
 use super::subcommand::Subcommand;
 
@@ -52,6 +53,33 @@ pub struct CovarianceCommand {
   function_name: String,
 }
 
+/// This command takes one argument: a [`FitModel`], such as `linear`
+/// or `poly 2`. Pops one value off the stack, which should be a
+/// matrix whose rows are `[x, y]` data points, and fits the model to
+/// the data by least squares. Pushes the vector `[coefficients,
+/// r_squared]`.
+///
+/// Respects the keep modifier.
+#[derive(Debug, Default)]
+pub struct FitCommand {
+  _priv: (),
+}
+
+/// This command pops two values off the stack: a vector of real
+/// numbers and a bin specification. Bins the data into a histogram,
+/// producing a matrix with one `[lower_bound, count]` row per bin.
+///
+/// The plain variant takes the number of bins, while the width
+/// variant takes the width of each bin. If given a numerical
+/// argument, then only the data vector is popped, and the numerical
+/// argument is used as the bin specification.
+///
+/// Respects the keep modifier.
+#[derive(Debug)]
+pub struct HistogramCommand {
+  function_name: &'static str,
+}
+
 impl DatasetDrivenCommand {
   pub fn new<F>(function: F) -> Self
   where F: Fn(Expr) -> Expr + Send + Sync + 'static {
@@ -131,6 +159,35 @@ pub fn correlation_command() -> CovarianceCommand {
   CovarianceCommand::named("corr")
 }
 
+impl FitCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToFitModel, FitModel> {
+    UnaryArgumentSchema::new(
+      "fit model (linear, poly N, exponential, or logarithmic)".to_owned(),
+      StringToFitModel,
+    )
+  }
+}
+
+impl HistogramCommand {
+  pub fn by_count() -> Self {
+    Self { function_name: "histogram" }
+  }
+
+  pub fn by_width() -> Self {
+    Self { function_name: "histogramw" }
+  }
+}
+
+impl Default for HistogramCommand {
+  fn default() -> Self {
+    Self::by_count()
+  }
+}
+
 impl Command for DatasetDrivenCommand {
   fn run_command(
     &self,
@@ -185,16 +242,12 @@ impl Command for CovarianceCommand {
       let m = match expr_to_matrix().narrow_type(m) {
         Ok(m) => m,
         Err(original_m) => {
-          if !ctx.opts.keep_modifier {
-            stack.push(original_m);
-          }
+          stack.restore_popped([original_m]);
           anyhow::bail!("Expected matrix");
         }
       };
       if m.width() != 2 {
-        if !ctx.opts.keep_modifier {
-          stack.push(expr_to_matrix().widen_type(m));
-        }
+        stack.restore_popped([expr_to_matrix().widen_type(m)]);
         anyhow::bail!("Expected matrix of width 2");
       }
       let [a, b] = m.into_matrix().into_column_major().try_into().unwrap();
@@ -217,10 +270,68 @@ impl Command for CovarianceCommand {
   }
 }
 
+impl Command for FitCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let model = validate_schema(&FitCommand::argument_schema(), args)?;
+    state.undo_stack_mut().push_cut();
+    let calculation_mode = state.calculation_mode().clone();
+    let mut errors = ErrorList::new();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), ctx.opts.keep_modifier);
+
+    let data = stack.pop()?;
+    let expr = Expr::call("fit", vec![data, Expr::from(model.to_string())]);
+    let expr = ctx.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for HistogramCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+    let calculation_mode = state.calculation_mode().clone();
+    let mut errors = ErrorList::new();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), ctx.opts.keep_modifier);
+
+    let histogram_args = match ctx.opts.argument {
+      None => stack.pop_several(2)?,
+      Some(bins) => vec![stack.pop()?, Expr::from(bins)],
+    };
+    let expr = Expr::call(self.function_name, histogram_args);
+    let expr = ctx.simplify_expr(expr, calculation_mode, &mut errors);
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, opts: &CommandOptions) -> Option<Subcommand> {
+    match opts.argument {
+      None => Some(Subcommand::named(2, self.function_name)),
+      Some(_) => None,
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::command::test_utils::act_on_stack;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
   use crate::command::options::CommandOptions;
   use crate::command::subcommand::test_utils::{try_call as try_call_subcommand};
   use crate::stack::{Stack, StackError};
@@ -488,4 +599,97 @@ mod tests {
     assert!(errors.is_empty());
     assert_eq!(expr, Expr::call("test_func", vec![Expr::from(0), Expr::from(10)]));
   }
+
+  fn data_point(x: i64, y: i64) -> Expr {
+    Expr::call("vector", vec![Expr::from(x), Expr::from(y)])
+  }
+
+  #[test]
+  fn test_fit_command() {
+    let data = Expr::call("vector", vec![data_point(0, 1), data_point(1, 3), data_point(2, 5)]);
+    let output_stack = act_on_stack(
+      &FitCommand::new(),
+      (vec!["linear"], setup_default_simplifier),
+      vec![data],
+    ).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![
+      Expr::call("vector", vec![
+        Expr::call("vector", vec![Expr::from(1), Expr::from(2)]),
+        Expr::from(1),
+      ]),
+    ]));
+  }
+
+  #[test]
+  fn test_fit_command_with_keep_arg() {
+    let opts = CommandOptions::default().with_keep_modifier();
+    let output_stack = act_on_stack(&FitCommand::new(), (vec!["poly 2"], opts), vec![10]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![
+      Expr::from(10),
+      Expr::call("fit", vec![Expr::from(10), Expr::from("poly 2")]),
+    ]));
+  }
+
+  #[test]
+  fn test_fit_command_with_invalid_model() {
+    act_on_stack(&FitCommand::new(), vec!["quadratic"], vec![10]).unwrap_err();
+  }
+
+  #[test]
+  fn test_histogram_command() {
+    let output_stack = act_on_stack(&HistogramCommand::by_count(), (), vec![10, 20, 30]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![
+      Expr::from(10),
+      Expr::call("histogram", vec![Expr::from(20), Expr::from(30)]),
+    ]));
+  }
+
+  #[test]
+  fn test_histogram_command_with_numerical_arg() {
+    let opts = CommandOptions::numerical(2).with_keep_modifier();
+    let output_stack = act_on_stack(&HistogramCommand::by_width(), opts, vec![10, 20]).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![
+      Expr::from(10),
+      Expr::from(20),
+      Expr::call("histogramw", vec![Expr::from(20), Expr::from(2)]),
+    ]));
+  }
+
+  #[test]
+  fn test_histogram_command_with_simplifier() {
+    let data = Expr::call("vector", vec![Expr::from(0), Expr::from(1), Expr::from(1), Expr::from(4)]);
+    let output_stack = act_on_stack(
+      &HistogramCommand::by_count(),
+      (CommandOptions::numerical(2), setup_default_simplifier),
+      vec![data],
+    ).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![
+      Expr::call("vector", vec![
+        Expr::call("vector", vec![Expr::from(0), Expr::from(3)]),
+        Expr::call("vector", vec![Expr::from(2), Expr::from(1)]),
+      ]),
+    ]));
+  }
+
+  #[test]
+  fn test_summary_command() {
+    let data = Expr::call("vector", vec![Expr::from(3), Expr::from(1), Expr::from(2)]);
+    let output_stack = act_on_stack(
+      &DatasetDrivenCommand::named("summary"),
+      setup_default_simplifier,
+      vec![data],
+    ).unwrap();
+    let equation = |name: &str, value: Expr| Expr::call("=", vec![Expr::var(name).unwrap(), value]);
+    assert_eq!(output_stack, Stack::from(vec![
+      Expr::call("vector", vec![
+        equation("min", Expr::from(1)),
+        equation("q1", Expr::from(1.5)),
+        equation("median", Expr::from(2)),
+        equation("q3", Expr::from(2.5)),
+        equation("max", Expr::from(3)),
+        equation("mean", Expr::from(2)),
+        equation("stddev", Expr::from(1.0)),
+      ]),
+    ]));
+  }
 }
@@ -0,0 +1,228 @@
+
+//! Commands for timing real-world events with a stopwatch, and for
+//! setting timers.
+
+use super::arguments::{NullaryArgumentSchema, UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::prisms::{StringToUsize, ParsedUsize};
+use crate::state::ApplicationState;
+use crate::stack::base::StackLike;
+
+use std::time::{Duration, Instant};
+
+/// The longest timer which can be set with [`TimerCommand`], in
+/// seconds.
+pub const MAX_TIMER_SECONDS: usize = 24 * 60 * 60;
+
+/// Starts the stopwatch, restarting it if it is already running.
+/// Does not modify the stack.
+#[derive(Debug, Default)]
+pub struct StopwatchStartCommand {
+  _priv: (),
+}
+
+/// Pushes the time since the previous lap (or since the stopwatch
+/// was started, for the first lap) onto the stack, and begins a new
+/// lap. Fails if the stopwatch is not running.
+#[derive(Debug, Default)]
+pub struct StopwatchLapCommand {
+  _priv: (),
+}
+
+/// Stops the stopwatch and pushes the total time since it was
+/// started onto the stack. Fails if the stopwatch is not running.
+#[derive(Debug, Default)]
+pub struct StopwatchStopCommand {
+  _priv: (),
+}
+
+/// This command takes one argument: a positive number of seconds.
+/// Notifies the user once that many seconds have passed. Does not
+/// modify the stack.
+#[derive(Debug, Default)]
+pub struct TimerCommand {
+  _priv: (),
+}
+
+impl StopwatchStartCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl StopwatchLapCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl StopwatchStopCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl TimerCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToUsize, ParsedUsize> {
+    UnaryArgumentSchema::new(
+      "positive integer".to_owned(),
+      StringToUsize,
+    )
+  }
+}
+
+/// Converts a duration into an expression in seconds, with units.
+pub fn duration_to_expr(duration: Duration) -> Expr {
+  let seconds = Expr::var("s").expect("s should be a valid variable name");
+  Expr::call("*", vec![Expr::from(Number::from(duration.as_secs_f64())), seconds])
+}
+
+impl Command for StopwatchStartCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.stopwatch_mut().start(Instant::now());
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for StopwatchLapCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let elapsed = state.stopwatch_mut().lap(Instant::now())?;
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().push(duration_to_expr(elapsed));
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for StopwatchStopCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let elapsed = state.stopwatch_mut().stop(Instant::now())?;
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().push(duration_to_expr(elapsed));
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for TimerCommand {
+  fn run_command(
+    &self,
+    _state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let seconds = usize::from(validate_schema(&TimerCommand::argument_schema(), args)?);
+    anyhow::ensure!(
+      seconds > 0 && seconds <= MAX_TIMER_SECONDS,
+      "Expected a number of seconds between 1 and {MAX_TIMER_SECONDS}, got {seconds}",
+    );
+    let output = CommandOutput::success().set_force_scroll_down(false);
+    Ok(output.with_timer(Duration::from_secs(seconds as u64)))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::prisms::expr_to_number;
+  use crate::stack::base::RandomAccessStackLike;
+  use crate::state::stopwatch::StopwatchNotRunningError;
+  use crate::state::test_utils::state_for_stack;
+  use crate::util::prism::Prism;
+
+  fn elapsed_seconds(expr: &Expr) -> f64 {
+    let Expr::Call(f, args) = expr else {
+      panic!("Expected a product, got {expr:?}");
+    };
+    assert_eq!(f, "*");
+    assert_eq!(args[1], Expr::var("s").unwrap());
+    expr_to_number().narrow_type(args[0].clone()).unwrap().to_f64().unwrap()
+  }
+
+  #[test]
+  fn test_stopwatch() {
+    let mut state = state_for_stack(vec![10]);
+    let context = CommandContext::default();
+    StopwatchStartCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack().len(), 1);
+    StopwatchLapCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    StopwatchStopCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack().len(), 3);
+    let lap = elapsed_seconds(state.main_stack().get(1).unwrap());
+    let total = elapsed_seconds(state.main_stack().get(0).unwrap());
+    assert!(0.0 <= lap && lap <= total);
+    assert!(!state.stopwatch().is_running());
+  }
+
+  #[test]
+  fn test_stopwatch_not_running() {
+    let mut state = state_for_stack(Vec::<Expr>::new());
+    let context = CommandContext::default();
+    let err = StopwatchLapCommand::new().run_command(&mut state, vec![], &context).unwrap_err();
+    assert!(err.is::<StopwatchNotRunningError>());
+    let err = StopwatchStopCommand::new().run_command(&mut state, vec![], &context).unwrap_err();
+    assert!(err.is::<StopwatchNotRunningError>());
+    assert!(state.main_stack().is_empty());
+  }
+
+  #[test]
+  fn test_stopwatch_is_not_undone() {
+    let mut state = state_for_stack(Vec::<Expr>::new());
+    let context = CommandContext::default();
+    StopwatchStartCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    StopwatchLapCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    state.undo().unwrap();
+    assert!(state.main_stack().is_empty());
+    assert!(state.stopwatch().is_running());
+  }
+
+  #[test]
+  fn test_timer() {
+    let mut state = state_for_stack(vec![10]);
+    let context = CommandContext::default();
+    let output = TimerCommand::new().run_command(&mut state, vec!["5".to_owned()], &context).unwrap();
+    assert_eq!(output.timer_request(), Some(Duration::from_secs(5)));
+    TimerCommand::new().run_command(&mut state, vec!["0".to_owned()], &context).unwrap_err();
+    TimerCommand::new().run_command(&mut state, vec!["100000".to_owned()], &context).unwrap_err();
+  }
+}
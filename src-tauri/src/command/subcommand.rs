@@ -111,6 +111,14 @@ impl<'a> Subcommand<'a> {
     Ok(expr)
   }
 
+  /// Applies the function indicated by this subcommand to the
+  /// arguments given, without simplifying the result. Panics in case
+  /// of arity mismatch.
+  pub fn apply_unsimplified(&self, args: Vec<Expr>) -> Expr {
+    assert_eq!(args.len(), self.arity, "Subcommand arity mismatch");
+    (self.function)(args)
+  }
+
   /// Invokes the function indicated by this subcommand on the
   /// arguments given. Panics in case of arity mismatch.
   pub fn call_or_panic(
@@ -0,0 +1,168 @@
+
+//! Commands for entering expressions through templates with slots.
+
+use super::arguments::{NullaryArgumentSchema, UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::errorlist::ErrorList;
+use crate::expr::template::{Template, StringToTemplate, fill_first_slot, count_slots};
+use crate::state::ApplicationState;
+use crate::stack::base::{StackLike, RandomAccessStackLike};
+use crate::stack::keepable::KeepableStack;
+
+use anyhow::bail;
+
+/// This command takes one argument: the name of a [`Template`].
+/// Pushes the template's skeleton expression, with every argument an
+/// unfilled slot. The skeleton is not simplified.
+#[derive(Debug, Default)]
+pub struct InsertTemplateCommand {
+  _priv: (),
+}
+
+/// This command takes no arguments. Pops two values `template` and
+/// `value` off the stack, and pushes `template` with its leftmost
+/// unfilled slot replaced by `value`. Once the last slot is filled,
+/// the result is simplified; until then, it is left alone, so that
+/// the simplifier does not act on a half-built expression.
+///
+/// Fails if `template` has no unfilled slots. Respects the "keep"
+/// modifier.
+#[derive(Debug, Default)]
+pub struct FillSlotCommand {
+  _priv: (),
+}
+
+impl InsertTemplateCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToTemplate, Template> {
+    UnaryArgumentSchema::new(
+      "template name".to_owned(),
+      StringToTemplate,
+    )
+  }
+}
+
+impl FillSlotCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Command for InsertTemplateCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let template = validate_schema(&InsertTemplateCommand::argument_schema(), args)?;
+
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().push(template.to_expr());
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for FillSlotCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    let calculation_mode = state.calculation_mode().clone();
+
+    // Validate before touching the stack, so a template with no
+    // slots leaves the stack as it was.
+    if count_slots(state.main_stack().get(1)?) == 0 {
+      bail!("Expression has no unfilled slots");
+    }
+
+    let mut errors = ErrorList::new();
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let [template, value] = stack.pop_several(2)?.try_into().unwrap();
+    let expr = fill_first_slot(template, value)
+      .unwrap_or_else(|_| unreachable!("template was checked for slots"));
+    let expr = if count_slots(&expr) == 0 {
+      context.simplify_expr(expr, calculation_mode, &mut errors)
+    } else {
+      expr
+    };
+    stack.push(expr);
+
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
+  use crate::expr::Expr;
+  use crate::expr::template::slot;
+  use crate::stack::test_utils::stack_of;
+
+  fn x() -> Expr {
+    Expr::var("x").unwrap()
+  }
+
+  #[test]
+  fn test_insert_template() {
+    let output_stack = act_on_stack(&InsertTemplateCommand::new(), vec!["integral"], vec![Expr::from(1)]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::from(1), Expr::call("integrate", vec![slot(), slot()])]));
+  }
+
+  #[test]
+  fn test_insert_unknown_template() {
+    act_on_stack(&InsertTemplateCommand::new(), vec!["integrate"], Vec::<Expr>::new()).unwrap_err();
+  }
+
+  #[test]
+  fn test_fill_slot() {
+    let template = Expr::call("deriv", vec![slot(), slot()]);
+    let squared = Expr::call("^", vec![x(), Expr::from(2)]);
+    let output_stack = act_on_stack(
+      &FillSlotCommand::new(),
+      setup_default_simplifier,
+      vec![template, squared.clone()],
+    ).unwrap();
+    // Not simplified while a slot remains.
+    assert_eq!(output_stack, stack_of(vec![Expr::call("deriv", vec![squared.clone(), slot()])]));
+
+    let output_stack = act_on_stack(
+      &FillSlotCommand::new(),
+      setup_default_simplifier,
+      vec![Expr::call("deriv", vec![squared, slot()]), x()],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::call("*", vec![Expr::from(2), x()])]));
+  }
+
+  #[test]
+  fn test_fill_slot_without_slots() {
+    act_on_stack(&FillSlotCommand::new(), (), vec![Expr::from(1), Expr::from(2)]).unwrap_err();
+  }
+
+  #[test]
+  fn test_fill_slot_with_keep_modifier() {
+    let opts = CommandOptions::default().with_keep_modifier();
+    let template = Expr::call("f", vec![slot()]);
+    let output_stack = act_on_stack(&FillSlotCommand::new(), opts, vec![template.clone(), Expr::from(3)]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![template, Expr::from(3), Expr::call("f", vec![Expr::from(3)])]));
+  }
+}
@@ -14,20 +14,21 @@ use crate::mode::calculation::CalculationMode;
 use crate::mode::display::language::LanguageMode;
 use crate::expr::Expr;
 use crate::expr::number::Number;
-use crate::expr::simplifier::{Simplifier, SimplifierContext};
-use crate::expr::simplifier::chained::ChainedSimplifier;
+use crate::expr::simplifier::SimplifierContext;
 use crate::expr::algebra::term::Term;
 use crate::expr::units::{parse_composite_unit_expr, try_parse_unit,
                          unit_into_term, tagged_into_expr_lossy,
-                         UnitPrism, ParsedCompositeUnit,
-                         UnitTermSimplifier, UnitPolynomialSimplifier};
+                         UnitPrism, ParsedCompositeUnit, unit_aware_simplifier};
 use crate::units::CompositeUnit;
 use crate::units::parsing::UnitParser;
 use crate::units::tagged::{Tagged, TemperatureTagged, try_into_basic_temperature_unit};
 use crate::units::dimension::Dimension;
-use crate::util::prism::ErrorWithPayload;
+use crate::units::context::{ConversionContext, ConversionRelation};
+use crate::util::prism::{Prism, Identity, ErrorWithPayload};
+use crate::state::undo::UpdateConversionContextChange;
+use crate::expr::simplifier::error::SimplifierError;
 
-use anyhow::Context;
+use anyhow::{Context, anyhow};
 
 /// This command requires two arguments: the unit to convert from and
 /// the unit to convert to. Both arguments are parsed with
@@ -52,13 +53,21 @@ pub struct ConvertUnitsCommand {
 }
 
 /// This command requires one argument: the target unit. The target
-/// unit will be parsed via [`UnitPrism`].
+/// unit will be parsed via [`UnitPrism`]. Optionally, a second
+/// argument names a [`ConversionContext`] defined with
+/// [`DefineConversionContextCommand`].
 ///
 /// Pops the top value of the stack, interpreting it as an expression
 /// with units already present, and converts that expression into the
 /// given target unit. If the dimensions do not match, then remainder
 /// units will be inserted.
 ///
+/// If a conversion context is named, then the conversion instead goes
+/// through the first link of that context which relates the
+/// dimension of the stack value to that of the target unit, in either
+/// direction. No remainder units are inserted in this case, and it is
+/// an error if no link applies.
+///
 /// The new units will be present on the top stack element when this
 /// operation is done.
 ///
@@ -86,6 +95,34 @@ pub struct ContextualConvertTemperatureCommand {
   _priv: (),
 }
 
+/// This command requires two arguments: the name of a conversion
+/// context and a JSON description of that context, in the format
+/// accepted by [`ConversionContext::from_json`].
+///
+/// Defines (or redefines) the named context, for use with
+/// [`ContextualConvertUnitsCommand`]. Fails if any unit in the
+/// context cannot be parsed. Does not touch the stack.
+#[derive(Debug, Clone, Default)]
+pub struct DefineConversionContextCommand {
+  _priv: (),
+}
+
+/// This command requires one argument: the name of a conversion
+/// context. Removes that context. Fails if no such context exists.
+/// Does not touch the stack.
+#[derive(Debug, Clone, Default)]
+pub struct RemoveConversionContextCommand {
+  _priv: (),
+}
+
+/// A [`ConversionLink`](crate::units::context::ConversionLink) whose
+/// units have been parsed.
+struct ResolvedLink {
+  source: CompositeUnit<Number>,
+  target: CompositeUnit<Number>,
+  relation: ConversionRelation,
+}
+
 impl ConvertUnitsCommand {
   pub fn new() -> Self {
     Self { _priv: () }
@@ -118,6 +155,62 @@ impl ContextualConvertUnitsCommand {
       UnitPrism::new(context.units_parser, state.display_settings().language_mode()),
     )
   }
+
+  fn named_argument_schema<'p, 'm>(
+    state: &'m ApplicationState,
+    context: &CommandContext<'_, 'p, '_>,
+  ) -> BinaryArgumentSchema<ConcreteUnitPrism<'p, 'm>, ParsedCompositeUnit<Number>, Identity, String> {
+    BinaryArgumentSchema::new(
+      "valid unit expression".to_owned(),
+      UnitPrism::new(context.units_parser, state.display_settings().language_mode()),
+      "conversion context name".to_owned(),
+      Identity,
+    )
+  }
+
+  /// Parses the units of every link in the named context.
+  fn resolve_context(
+    state: &ApplicationState,
+    context: &CommandContext,
+    name: &str,
+  ) -> anyhow::Result<Vec<ResolvedLink>> {
+    let conversion_context = state.conversion_contexts().get(name)
+      .ok_or_else(|| anyhow!("Unknown conversion context: {name}"))?;
+    let prism = UnitPrism::new(context.units_parser, state.display_settings().language_mode());
+    let calculation_mode = state.calculation_mode();
+    conversion_context.links().iter()
+      .map(|link| {
+        let source = parse_link_unit(&prism, &link.source)?;
+        let target = parse_link_unit(&prism, &link.target)?;
+        Ok(ResolvedLink {
+          source: correct_unit_for_exactness(source, calculation_mode),
+          target: correct_unit_for_exactness(target, calculation_mode),
+          relation: link.relation.clone(),
+        })
+      })
+      .collect()
+  }
+}
+
+impl DefineConversionContextCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> BinaryArgumentSchema<Identity, String, Identity, String> {
+    BinaryArgumentSchema::new(
+      "conversion context name".to_owned(),
+      Identity,
+      "conversion context JSON".to_owned(),
+      Identity,
+    )
+  }
+}
+
+impl RemoveConversionContextCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
 }
 
 impl ConvertTemperatureCommand {
@@ -156,28 +249,59 @@ impl ContextualConvertTemperatureCommand {
 
 type ConcreteUnitPrism<'p, 'm> = UnitPrism<&'p dyn UnitParser<Number>, Box<dyn LanguageMode + 'm>, Number>;
 
+fn parse_link_unit(prism: &ConcreteUnitPrism, unit: &str) -> anyhow::Result<CompositeUnit<Number>> {
+  prism.narrow_type(unit.to_owned())
+    .map(CompositeUnit::from)
+    .map_err(|unit| anyhow!("Invalid unit in conversion context: {unit}"))
+}
+
+/// Converts the value through the first link which relates its
+/// dimension to that of the target unit.
+fn convert_within_context(
+  context_name: &str,
+  links: &[ResolvedLink],
+  tagged_term: Tagged<Term, Number>,
+  target_unit: CompositeUnit<Number>,
+  ctx: &CommandContext,
+  calculation_mode: &CalculationMode,
+  errors: &mut ErrorList<SimplifierError>,
+) -> anyhow::Result<Expr> {
+  let source_dim = tagged_term.unit.dimension();
+  let target_dim = target_unit.dimension();
+  for link in links {
+    let (from_unit, to_unit, is_forward) = if link.source.dimension() == source_dim && link.target.dimension() == target_dim {
+      (&link.source, &link.target, true)
+    } else if link.target.dimension() == source_dim && link.source.dimension() == target_dim {
+      (&link.target, &link.source, false)
+    } else {
+      continue;
+    };
+    // convert_or_panic safety: We just checked the dimensions.
+    let value = Expr::from(tagged_term.convert_or_panic(from_unit.clone()).value);
+    let value = ctx.simplify_expr(value, calculation_mode.clone(), errors);
+    let value = if is_forward {
+      link.relation.apply(value)?
+    } else {
+      link.relation.apply_inverse(value)?
+    };
+    let converted = Tagged::new(Term::parse(value), to_unit.clone()).convert_or_panic(target_unit);
+    return Ok(tagged_into_expr_lossy(converted));
+  }
+  anyhow::bail!("Conversion context '{context_name}' does not relate these units");
+}
+
 fn calculate_remainder_unit<P>(parser: &P, source_dim: &Dimension, target_dim: &Dimension) -> CompositeUnit<Number>
 where P: UnitParser<Number> + ?Sized {
   let remainder_dim = source_dim.to_owned() / target_dim.to_owned();
   parser.base_composite_unit(&remainder_dim)
 }
 
-/// Simplifier which runs a unit simplification step after the usual
-/// simplification step.
-fn unit_simplifier<'a>(ctx: &'a CommandContext) -> Box<dyn Simplifier + 'a> {
-  let simplifiers: [Box<dyn Simplifier + 'a>; 3] = [
-    Box::new(ctx.simplifier.as_ref()),
-    Box::new(UnitTermSimplifier::new(ctx.units_parser)),
-    Box::new(UnitPolynomialSimplifier::new(ctx.units_parser)),
-  ];
-  ChainedSimplifier::several(simplifiers)
-}
-
 /// Unary command which simplifies units on the targeted stack
 /// element(s).
 pub fn simplify_units_command() -> UnaryFunctionCommand {
   UnaryFunctionCommand::with_all(|arg, state, ctx, errors| {
-    let simplifier = unit_simplifier(ctx);
+    let prefer_derived_units = state.calculation_mode().has_derived_units_flag();
+    let simplifier = unit_aware_simplifier(ctx.simplifier.as_ref(), ctx.units_parser, prefer_derived_units);
     let mut simplifier_ctx = SimplifierContext {
       base_simplifier: simplifier.as_ref(),
       calculation_mode: state.calculation_mode().clone(),
@@ -262,17 +386,44 @@ impl Command for ContextualConvertUnitsCommand {
   ) -> anyhow::Result<CommandOutput> {
     let calculation_mode = state.calculation_mode().clone();
 
-    let target_unit = validate_schema(&Self::argument_schema(state, ctx), args)?;
+    let (target_unit, context_name) = if args.len() == 2 {
+      let (target_unit, context_name) = validate_schema(&Self::named_argument_schema(state, ctx), args)?;
+      (target_unit, Some(context_name))
+    } else {
+      (validate_schema(&Self::argument_schema(state, ctx), args)?, None)
+    };
     let target_unit = correct_unit_for_exactness(CompositeUnit::from(target_unit), &calculation_mode);
+    let links = context_name.as_deref()
+      .map(|name| Self::resolve_context(state, ctx, name))
+      .transpose()?;
 
     state.undo_stack_mut().push_cut();
     let mut stack = KeepableStack::new(state.main_stack_mut(), ctx.opts.keep_modifier);
+    let original_expr = stack.pop()?;
     let tagged_term = {
-      let mut tagged_term = parse_composite_unit_expr(ctx.units_parser, stack.pop()?);
+      let mut tagged_term = parse_composite_unit_expr(ctx.units_parser, original_expr.clone());
       tagged_term.unit = correct_unit_for_exactness(tagged_term.unit, &calculation_mode);
       tagged_term
     };
 
+    if let (Some(context_name), Some(links)) = (context_name, links) {
+      let mut errors = ErrorList::new();
+      let result = convert_within_context(
+        &context_name, &links, tagged_term, target_unit, ctx, &calculation_mode, &mut errors,
+      );
+      match result {
+        Ok(expr) => {
+          stack.push(ctx.simplify_expr(expr, calculation_mode, &mut errors));
+          return Ok(CommandOutput::from_errors(errors));
+        }
+        Err(err) => {
+          // Recover the stack, then bail.
+          stack.restore_popped([original_expr]);
+          return Err(err);
+        }
+      }
+    }
+
     let remainder_unit = calculate_remainder_unit(
       ctx.units_parser,
       &tagged_term.unit.dimension(),
@@ -358,9 +509,7 @@ impl Command for ContextualConvertTemperatureCommand {
       Err(err) => {
         // Recover the stack, then bail.
         let tagged_term = err.clone().recover_payload();
-        if !ctx.opts.keep_modifier {
-          stack.push(tagged_into_expr_lossy(tagged_term));
-        }
+        stack.restore_popped([tagged_into_expr_lossy(tagged_term)]);
         anyhow::bail!(err);
       }
     };
@@ -380,7 +529,61 @@ impl Command for ContextualConvertTemperatureCommand {
   }
 }
 
-fn correct_unit_for_exactness(unit: CompositeUnit<Number>, calc_mode: &CalculationMode) -> CompositeUnit<Number> {
+impl Command for DefineConversionContextCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let (name, json) = validate_schema(&Self::argument_schema(), args)?;
+    let name = name.trim().to_owned();
+    if name.is_empty() {
+      anyhow::bail!("Expected a conversion context name");
+    }
+    let conversion_context = ConversionContext::from_json(&json)?;
+    {
+      let prism = UnitPrism::new(ctx.units_parser, state.display_settings().language_mode());
+      for link in conversion_context.links() {
+        parse_link_unit(&prism, &link.source)?;
+        parse_link_unit(&prism, &link.target)?;
+      }
+    }
+
+    state.undo_stack_mut().push_cut();
+    let old_value = state.conversion_contexts_mut().insert(name.clone(), conversion_context.clone());
+    state.undo_stack_mut().push_change(UpdateConversionContextChange::new(name, old_value, Some(conversion_context)));
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+impl Command for RemoveConversionContextCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _ctx: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let name = validate_schema(&UnaryArgumentSchema::any(), args)?;
+    let Some(old_value) = state.conversion_contexts_mut().remove(&name) else {
+      anyhow::bail!("Unknown conversion context: {name}");
+    };
+
+    state.undo_stack_mut().push_cut();
+    state.undo_stack_mut().push_change(UpdateConversionContextChange::new(name, Some(old_value), None));
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+pub(crate) fn correct_unit_for_exactness(unit: CompositeUnit<Number>, calc_mode: &CalculationMode) -> CompositeUnit<Number> {
   if calc_mode.has_fractional_flag() {
     unit
   } else {
@@ -418,6 +621,7 @@ pub(crate) mod test_utils {
 mod tests {
   use super::*;
   use super::test_utils::setup_si_units;
+  use crate::command::functional::BinaryFunctionCommand;
   use crate::command::test_utils::{act_on_stack, setup_default_simplifier};
   use crate::command::options::CommandOptions;
   use crate::stack::test_utils::stack_of;
@@ -622,4 +826,174 @@ mod tests {
       ]),
     ]));
   }
+
+  const FUEL_CONTEXT: &str = r#"{"links": [{"from": "L/km", "to": "mi/gal", "reciprocal": 2}]}"#;
+  const GAUGE_CONTEXT: &str = r#"{"links": [{"from": "1", "to": "mm", "table": [[0, 8], [10, 2]]}]}"#;
+
+  fn setup_conversion_contexts(_args: &mut Vec<String>, state: &mut ApplicationState, _context: &mut CommandContext) {
+    let contexts = state.conversion_contexts_mut();
+    contexts.insert("fuel".to_owned(), ConversionContext::from_json(FUEL_CONTEXT).unwrap());
+    contexts.insert("gauge".to_owned(), ConversionContext::from_json(GAUGE_CONTEXT).unwrap());
+  }
+
+  #[test]
+  fn test_define_conversion_context() {
+    let mut state = ApplicationState::new();
+    let mut context = CommandContext::default();
+    setup_si_units(&mut Vec::new(), &mut state, &mut context);
+    let args = vec!["fuel".to_owned(), FUEL_CONTEXT.to_owned()];
+    DefineConversionContextCommand::new().run_command(&mut state, args, &context).unwrap();
+    assert_eq!(state.conversion_contexts().names().collect::<Vec<_>>(), vec!["fuel"]);
+    RemoveConversionContextCommand::new().run_command(&mut state, vec!["fuel".to_owned()], &context).unwrap();
+    assert!(state.conversion_contexts().is_empty());
+    state.undo().unwrap();
+    assert!(state.conversion_contexts().get("fuel").is_some());
+    state.undo().unwrap();
+    assert!(state.conversion_contexts().is_empty());
+  }
+
+  #[test]
+  fn test_define_conversion_context_invalid() {
+    let mut state = ApplicationState::new();
+    let mut context = CommandContext::default();
+    setup_si_units(&mut Vec::new(), &mut state, &mut context);
+    let json = r#"{"links": [{"from": "L/km", "to": "nonsense", "reciprocal": 2}]}"#;
+    let err = DefineConversionContextCommand::new()
+      .run_command(&mut state, vec!["fuel".to_owned(), json.to_owned()], &context)
+      .unwrap_err();
+    assert_eq!(err.to_string(), "Invalid unit in conversion context: nonsense");
+    let err = RemoveConversionContextCommand::new()
+      .run_command(&mut state, vec!["fuel".to_owned()], &context)
+      .unwrap_err();
+    assert_eq!(err.to_string(), "Unknown conversion context: fuel");
+    assert!(state.conversion_contexts().is_empty());
+  }
+
+  #[test]
+  fn test_reciprocal_context_conversion() {
+    let setup = (setup_si_units, setup_default_simplifier, setup_conversion_contexts);
+    let input_stack = vec![quantity(4, Expr::call("/", vec![var("L"), var("km")]))];
+    let output_stack = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["mi / gal", "fuel"]),
+      input_stack,
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::call("/", vec![Expr::call("*", vec![Expr::from(0.5), var("mi")]), var("gal")]),
+    ]));
+    let input_stack = vec![quantity(4, Expr::call("/", vec![var("mi"), var("gal")]))];
+    let output_stack = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["L / km", "fuel"]),
+      input_stack,
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::call("/", vec![Expr::call("*", vec![Expr::from(0.5), var("L")]), var("km")]),
+    ]));
+  }
+
+  #[test]
+  fn test_table_context_conversion() {
+    let setup = (setup_si_units, setup_default_simplifier, setup_conversion_contexts);
+    let output_stack = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["mm", "gauge"]),
+      vec![5],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::call("*", vec![Expr::from(5.0), var("mm")])]));
+    let output_stack = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["1", "gauge"]),
+      vec![quantity(5, var("mm"))],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::from(5.0)]));
+    let output_stack = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["1", "gauge"]),
+      vec![Expr::call("*", vec![Expr::from(0.5), var("cm")])],
+    ).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::from(5.0)]));
+  }
+
+  #[test]
+  fn test_context_conversion_failure() {
+    let setup = (setup_si_units, setup_default_simplifier, setup_conversion_contexts);
+    let err = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["mm", "nonexistent"]),
+      vec![5],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown conversion context: nonexistent");
+    let err = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["s", "gauge"]),
+      vec![5],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Conversion context 'gauge' does not relate these units");
+    let err = act_on_stack(
+      &ContextualConvertUnitsCommand::new(),
+      (setup, vec!["mm", "gauge"]),
+      vec![20],
+    ).unwrap_err();
+    assert_eq!(err.to_string(), "Value 20 is outside the range of the conversion table");
+  }
+
+  fn units_mode(prefer_derived_units: bool) -> CalculationMode {
+    let mut mode = CalculationMode::default();
+    mode.set_units_flag(true);
+    mode.set_derived_units_flag(prefer_derived_units);
+    mode
+  }
+
+  fn quantity(n: i64, unit: Expr) -> Expr {
+    Expr::call("*", vec![Expr::from(n), unit])
+  }
+
+  fn var(name: &str) -> Expr {
+    Expr::var(name).unwrap()
+  }
+
+  #[test]
+  fn test_units_mode_division() {
+    let divide = BinaryFunctionCommand::named("/");
+    let setup = (setup_si_units, setup_default_simplifier, units_mode(false));
+    let output_stack = act_on_stack(&divide, setup, vec![quantity(10, var("m")), quantity(2, var("s"))]).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::call("/", vec![quantity(5, var("m")), var("s")]),
+    ]));
+  }
+
+  #[test]
+  fn test_units_mode_addition() {
+    let add = BinaryFunctionCommand::named("+");
+    let input_stack = vec![quantity(3, var("m")), quantity(20, var("cm"))];
+    let output_stack = act_on_stack(&add, (setup_si_units, setup_default_simplifier, units_mode(false)), input_stack.clone()).unwrap();
+    assert_eq!(output_stack, stack_of(vec![quantity(320, var("cm"))]));
+    // Without units mode, the units are left alone.
+    let output_stack = act_on_stack(&add, (setup_si_units, setup_default_simplifier), input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![
+      Expr::call("+", vec![quantity(20, var("cm")), quantity(3, var("m"))]),
+    ]));
+  }
+
+  #[test]
+  fn test_units_mode_derived_units() {
+    let multiply = BinaryFunctionCommand::named("*");
+    let input_stack = vec![quantity(3, var("N")), quantity(2, var("m"))];
+    let output_stack = act_on_stack(&multiply, (setup_si_units, setup_default_simplifier, units_mode(false)), input_stack.clone()).unwrap();
+    assert_eq!(output_stack, stack_of(vec![Expr::call("*", vec![Expr::from(6), var("N"), var("m")])]));
+    let output_stack = act_on_stack(&multiply, (setup_si_units, setup_default_simplifier, units_mode(true)), input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![quantity(6, var("J"))]));
+  }
+
+  #[test]
+  fn test_units_mode_electrical_derived_units() {
+    let multiply = BinaryFunctionCommand::named("*");
+    let input_stack = vec![quantity(3, var("V")), quantity(2, var("A"))];
+    let output_stack = act_on_stack(&multiply, (setup_si_units, setup_default_simplifier, units_mode(true)), input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![quantity(6, var("W"))]));
+    let input_stack = vec![quantity(3, var("A")), quantity(2, var("s"))];
+    let output_stack = act_on_stack(&multiply, (setup_si_units, setup_default_simplifier, units_mode(true)), input_stack).unwrap();
+    assert_eq!(output_stack, stack_of(vec![quantity(6, var("C"))]));
+  }
 }
@@ -0,0 +1,312 @@
+
+//! Commands for defining functions at runtime.
+
+use super::arguments::{NullaryArgumentSchema, UnaryArgumentSchema, validate_schema};
+use super::base::{Command, CommandContext, CommandOutput};
+use super::options::CommandOptions;
+use super::subcommand::Subcommand;
+use crate::expr::function::user::UserFunction;
+use crate::expr::calculus::PartialDerivative;
+use crate::expr::prisms::{StringToVar, expr_to_equation};
+use crate::expr::var::Var;
+use crate::state::ApplicationState;
+use crate::state::undo::{UpdateUserFunctionChange, UpdateDerivativeRuleChange};
+use crate::stack::base::{StackLike, RandomAccessStackLike};
+use crate::stack::keepable::KeepableStack;
+use crate::util::prism::Prism;
+
+/// This command takes no arguments. The top stack element must be an
+/// equation of the form `f(x, y, ...) = body`, where the parameters
+/// on the left-hand side are distinct variables. Defines `f` as a
+/// user function, replacing any previous definition of the same
+/// name.
+///
+/// Fails if the stack is empty or if the top element is not a valid
+/// definition, or if `f` is the name of a built-in function. Respects
+/// the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct DefineFunctionCommand {
+  _priv: (),
+}
+
+impl DefineFunctionCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Command for DefineFunctionCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+
+    // Validate before touching the stack, so a bad definition leaves
+    // the stack as it was.
+    let top = state.main_stack().get(0)?.clone();
+    let Ok(equation) = expr_to_equation().narrow_type(top) else {
+      anyhow::bail!("Expected equation");
+    };
+    let (name, function) = UserFunction::define_from_equation(equation, context.function_table)?;
+
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    stack.pop()?;
+    let old_value = state.user_functions_mut().insert(name.clone(), function.clone());
+    state.undo_stack_mut().push_change(UpdateUserFunctionChange::new(name, old_value, Some(function)));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+/// This command takes a variable `v` as an argument. The top stack
+/// element must be an equation of the form `f(x, y, ...) = body`,
+/// as for [`DefineFunctionCommand`], where `v` is one of the
+/// parameters. Registers `body` as the partial derivative of `f` with
+/// respect to `v`, which is used to differentiate `f` if it has no
+/// built-in derivative. Partial derivatives with respect to the other
+/// parameters are kept, provided that they were registered with the
+/// same parameter list.
+///
+/// Fails if the stack is empty or if the top element is not a valid
+/// definition. Respects the "keep" modifier.
+#[derive(Debug, Default)]
+pub struct DefineDerivativeCommand {
+  _priv: (),
+}
+
+impl DefineDerivativeCommand {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+
+  fn argument_schema() -> UnaryArgumentSchema<StringToVar, Var> {
+    UnaryArgumentSchema::new(
+      "variable name".to_owned(),
+      StringToVar::new(),
+    )
+  }
+}
+
+impl Command for DefineDerivativeCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    let var = validate_schema(&DefineDerivativeCommand::argument_schema(), args)?;
+
+    // Validate before touching the stack, so a bad definition leaves
+    // the stack as it was.
+    let top = state.main_stack().get(0)?.clone();
+    let Ok(equation) = expr_to_equation().narrow_type(top) else {
+      anyhow::bail!("Expected equation");
+    };
+    let partial = PartialDerivative::from_equation(equation, &var)?;
+    let name = partial.function_name.clone();
+    let rule = state.derivative_rules().with_partial(partial);
+
+    state.undo_stack_mut().push_cut();
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    stack.pop()?;
+    let old_value = state.derivative_rules_mut().insert(name.clone(), rule.clone());
+    state.undo_stack_mut().push_change(UpdateDerivativeRuleChange::new(name, old_value, Some(rule)));
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::test_utils::act_on_stack;
+  use crate::errorlist::ErrorList;
+  use crate::expr::Expr;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::function::user::UserFunctionError;
+  use crate::expr::calculus::DerivativeRuleError;
+  use crate::expr::simplifier::{default_simplifier_with_user_functions, default_simplifier_with_derivative_rules};
+  use crate::expr::var::Var;
+  use crate::stack::test_utils::stack_of;
+  use crate::state::test_utils::state_for_stack;
+
+  fn var(name: &str) -> Expr {
+    Expr::from(Var::new(name).unwrap())
+  }
+
+  /// The equation `f(x) = x^2 + 1`.
+  fn square_plus_one() -> Expr {
+    let body = Expr::call("+", vec![Expr::call("^", vec![var("x"), Expr::from(2)]), Expr::from(1)]);
+    Expr::call("=", vec![Expr::call("f", vec![var("x")]), body])
+  }
+
+  #[test]
+  fn test_define_function() {
+    let mut state = state_for_stack(vec![Expr::from(10), square_plus_one()]);
+    let context = CommandContext::default();
+    DefineFunctionCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    assert_eq!(state.user_functions().get("f").unwrap().arity(), 1);
+  }
+
+  #[test]
+  fn test_define_function_rejects_builtin_name() {
+    let equation = Expr::call("=", vec![Expr::call("sin", vec![var("x")]), var("x")]);
+    let mut state = state_for_stack(vec![equation.clone()]);
+    let function_table = build_function_table();
+    let context = CommandContext {
+      function_table: &function_table,
+      ..CommandContext::default()
+    };
+    let err = DefineFunctionCommand::new().run_command(&mut state, vec![], &context).unwrap_err();
+    assert_eq!(err.downcast::<UserFunctionError>().unwrap(), UserFunctionError::BuiltinName("sin".to_owned()));
+    assert!(state.user_functions().is_empty());
+    assert_eq!(state.main_stack(), &stack_of(vec![equation]));
+  }
+
+  #[test]
+  fn test_define_function_is_undoable() {
+    let mut state = state_for_stack(vec![square_plus_one()]);
+    let context = CommandContext::default();
+    DefineFunctionCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    state.undo().unwrap();
+    assert!(state.user_functions().is_empty());
+    assert_eq!(state.main_stack(), &stack_of(vec![square_plus_one()]));
+    state.redo().unwrap();
+    assert!(state.user_functions().get("f").is_some());
+  }
+
+  #[test]
+  fn test_user_function_is_evaluated() {
+    let mut state = state_for_stack(vec![square_plus_one()]);
+    let context = CommandContext::default();
+    DefineFunctionCommand::new().run_command(&mut state, vec![], &context).unwrap();
+
+    let function_table = build_function_table();
+    let context = CommandContext {
+      simplifier: default_simplifier_with_user_functions(&function_table, state.user_functions().clone()),
+      ..CommandContext::default()
+    };
+    let mut errors = ErrorList::new();
+    let expr = Expr::call("f", vec![Expr::from(3)]);
+    let expr = context.simplify_expr(expr, state.calculation_mode().clone(), &mut errors);
+    assert!(errors.is_empty());
+    assert_eq!(expr, Expr::from(10));
+    // Wrong arity is left alone.
+    let expr = Expr::call("f", vec![Expr::from(3), Expr::from(4)]);
+    let simplified = context.simplify_expr(expr.clone(), state.calculation_mode().clone(), &mut errors);
+    assert_eq!(simplified, expr);
+  }
+
+  #[test]
+  fn test_define_function_with_keep_arg() {
+    let mut state = state_for_stack(vec![square_plus_one()]);
+    let context = CommandContext {
+      opts: CommandOptions::default().with_keep_modifier(),
+      ..CommandContext::default()
+    };
+    DefineFunctionCommand::new().run_command(&mut state, vec![], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![square_plus_one()]));
+    assert!(state.user_functions().get("f").is_some());
+  }
+
+  #[test]
+  fn test_define_function_invalid() {
+    let err = act_on_stack(&DefineFunctionCommand::new(), (), vec![Expr::from(10)]).unwrap_err();
+    assert_eq!(err.to_string(), "Expected equation");
+    let bad_definition = Expr::call("=", vec![Expr::call("f", vec![Expr::from(1)]), Expr::from(2)]);
+    let err = act_on_stack(&DefineFunctionCommand::new(), (), vec![bad_definition]).unwrap_err();
+    assert_eq!(err.downcast::<UserFunctionError>().unwrap(), UserFunctionError::ExpectedParameter(Expr::from(1)));
+  }
+
+  /// The equation `g(x, y) = body`.
+  fn partial_of_g(body: Expr) -> Expr {
+    Expr::call("=", vec![Expr::call("g", vec![var("x"), var("y")]), body])
+  }
+
+  #[test]
+  fn test_define_derivative() {
+    let mut state = state_for_stack(vec![Expr::from(10), partial_of_g(var("y"))]);
+    let context = CommandContext::default();
+    DefineDerivativeCommand::new().run_command(&mut state, vec!["x".to_owned()], &context).unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    let rule = state.derivative_rules().get("g").unwrap();
+    assert_eq!(rule.partial_at(0, &[Expr::from(1), Expr::from(2)]), Some(Expr::from(2)));
+    assert_eq!(rule.partial_at(1, &[Expr::from(1), Expr::from(2)]), None);
+  }
+
+  #[test]
+  fn test_define_derivative_is_undoable() {
+    let mut state = state_for_stack(vec![partial_of_g(var("y"))]);
+    let context = CommandContext::default();
+    DefineDerivativeCommand::new().run_command(&mut state, vec!["x".to_owned()], &context).unwrap();
+    state.undo().unwrap();
+    assert!(state.derivative_rules().is_empty());
+    assert_eq!(state.main_stack(), &stack_of(vec![partial_of_g(var("y"))]));
+    state.redo().unwrap();
+    assert!(state.derivative_rules().get("g").is_some());
+  }
+
+  #[test]
+  fn test_registered_derivative_is_used() {
+    let mut state = state_for_stack(vec![partial_of_g(var("y")), partial_of_g(var("x"))]);
+    let context = CommandContext::default();
+    DefineDerivativeCommand::new().run_command(&mut state, vec!["y".to_owned()], &context).unwrap();
+    DefineDerivativeCommand::new().run_command(&mut state, vec!["x".to_owned()], &context).unwrap();
+
+    let function_table = build_function_table();
+    let context = CommandContext {
+      simplifier: default_simplifier_with_derivative_rules(&function_table, state.derivative_rules().clone()),
+      ..CommandContext::default()
+    };
+    let mut errors = ErrorList::new();
+    // d/dt g(t, 3) = (dg/dx)(t, 3) = 3
+    let expr = Expr::call("deriv", vec![Expr::call("g", vec![var("t"), Expr::from(3)]), var("t")]);
+    let expr = context.simplify_expr(expr, state.calculation_mode().clone(), &mut errors);
+    assert!(errors.is_empty());
+    assert_eq!(expr, Expr::from(3));
+    // d/dt g(t, t) = t + t
+    let expr = Expr::call("deriv", vec![Expr::call("g", vec![var("t"), var("t")]), var("t")]);
+    let expr = context.simplify_expr(expr, state.calculation_mode().clone(), &mut errors);
+    assert!(errors.is_empty());
+    assert_eq!(expr, Expr::call("*", vec![Expr::from(2), var("t")]));
+  }
+
+  #[test]
+  fn test_unregistered_partial_derivative_fails() {
+    let mut state = state_for_stack(vec![partial_of_g(var("y"))]);
+    let context = CommandContext::default();
+    DefineDerivativeCommand::new().run_command(&mut state, vec!["x".to_owned()], &context).unwrap();
+
+    let function_table = build_function_table();
+    let context = CommandContext {
+      simplifier: default_simplifier_with_derivative_rules(&function_table, state.derivative_rules().clone()),
+      ..CommandContext::default()
+    };
+    let mut errors = ErrorList::new();
+    let expr = Expr::call("deriv", vec![Expr::call("g", vec![Expr::from(3), var("t")]), var("t")]);
+    context.simplify_expr(expr, state.calculation_mode().clone(), &mut errors);
+    assert!(!errors.is_empty());
+  }
+
+  #[test]
+  fn test_define_derivative_invalid() {
+    let err = act_on_stack(&DefineDerivativeCommand::new(), vec!["z"], vec![partial_of_g(Expr::from(1))]).unwrap_err();
+    assert_eq!(
+      err.downcast::<DerivativeRuleError>().unwrap(),
+      DerivativeRuleError::NotAParameter(Var::new("z").unwrap()),
+    );
+  }
+}
@@ -1,7 +1,7 @@
 
 //! Specialized commands for working with variables in particular.
 
-use super::arguments::{UnaryArgumentSchema, BinaryArgumentSchema, validate_schema};
+use super::arguments::{NullaryArgumentSchema, UnaryArgumentSchema, BinaryArgumentSchema, validate_schema};
 use super::base::{Command, CommandContext, CommandOutput};
 use super::options::CommandOptions;
 use super::subcommand::Subcommand;
@@ -9,7 +9,7 @@ use crate::errorlist::ErrorList;
 use crate::expr::Expr;
 use crate::expr::prisms::StringToVar;
 use crate::expr::var::Var;
-use crate::expr::var::constants::validate_non_reserved_var_name;
+use crate::expr::var::constants::{validate_non_reserved_var_name, RESERVED_NAMES};
 use crate::util::prism::Identity;
 use crate::state::ApplicationState;
 use crate::state::undo::UpdateVarChange;
@@ -51,6 +51,13 @@ pub struct UnbindVarCommand {
   _priv: (),
 }
 
+/// This command takes no arguments. Unbinds every user-defined
+/// variable, leaving the built-in constants intact.
+#[derive(Debug, Default)]
+pub struct ClearVarsCommand {
+  _priv: (),
+}
+
 impl SubstituteVarCommand {
   pub fn new() -> SubstituteVarCommand {
     SubstituteVarCommand { _priv: () }
@@ -92,6 +99,12 @@ impl UnbindVarCommand {
   }
 }
 
+impl ClearVarsCommand {
+  pub fn new() -> ClearVarsCommand {
+    ClearVarsCommand { _priv: () }
+  }
+}
+
 impl Command for SubstituteVarCommand {
   fn run_command(
     &self,
@@ -175,3 +188,31 @@ impl Command for UnbindVarCommand {
     None
   }
 }
+
+impl Command for ClearVarsCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    _context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+
+    let user_vars: Vec<Var> = state.variable_table().keys()
+      .filter(|var| !RESERVED_NAMES.contains(*var))
+      .cloned()
+      .collect();
+    for var in user_vars {
+      if let Some(old_value) = state.variable_table_mut().remove(&var) {
+        state.undo_stack_mut().push_change(UpdateVarChange::destroy_var(var, old_value));
+      }
+    }
+
+    Ok(CommandOutput::success())
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
@@ -16,7 +16,7 @@ use crate::expr::Expr;
 use crate::expr::prisms;
 use crate::expr::atom::Atom;
 use crate::expr::algebra::infinity::InfiniteConstant;
-use crate::expr::number::{Number, ComplexNumber, Quaternion};
+use crate::expr::number::{Number, ComplexNumber, Quaternion, Octonion};
 use crate::expr::vector::Vector;
 use crate::expr::vector::matrix::Matrix;
 use crate::expr::simplifier::error::DomainError;
@@ -27,6 +27,8 @@ use crate::stack::keepable::KeepableStack;
 
 use num::BigInt;
 
+use std::iter;
+
 /// `PackCommand` packs several stack elements into a vector.
 ///
 /// With no numerical argument, `PackCommand` pops a single value off
@@ -169,6 +171,27 @@ pub struct ComplexFromIncompleteObjectCommand {
   _priv: (),
 }
 
+/// `AggregateFromIncompleteObjectCommand` pops stack elements until
+/// it finds the given incomplete object. Then it pushes a call to the
+/// given function, with every value popped up to that point as
+/// arguments. The resulting call is simplified, so the function's
+/// own rules decide the final shape of the aggregate.
+///
+/// This is used for the aggregate types which have no dedicated
+/// command, such as sets (`{`) and tuples (`<`).
+///
+/// If we don't find the incomplete object or if we find the wrong
+/// incomplete object, produces an error and does NOT modify the
+/// stack.
+///
+/// Respects the "keep" modifier but does not use a numerical
+/// argument.
+#[derive(Debug)]
+pub struct AggregateFromIncompleteObjectCommand {
+  delimiter: ObjectType,
+  function_name: String,
+}
+
 impl PackCommand {
   pub fn new() -> Self {
     Self::default()
@@ -195,7 +218,7 @@ impl PackCommand {
     match prisms::expr_to_usize().narrow_type(elem) {
       Err(elem) => {
         // Failed to convert, so put it back.
-        stack.push(elem.clone());
+        stack.restore_popped([elem.clone()]);
         Err(anyhow::anyhow!(DomainError::new(format!("Expected small positive integer, got {}", elem))))
       }
       Ok(arg) => {
@@ -286,6 +309,25 @@ impl ComplexFromIncompleteObjectCommand {
   }
 }
 
+impl AggregateFromIncompleteObjectCommand {
+  pub fn new(delimiter: ObjectType, function_name: impl Into<String>) -> Self {
+    Self {
+      delimiter,
+      function_name: function_name.into(),
+    }
+  }
+
+  /// Collects elements up to [`ObjectType::LeftBrace`] into a set.
+  pub fn set() -> Self {
+    Self::new(ObjectType::LeftBrace, "set")
+  }
+
+  /// Collects elements up to [`ObjectType::LeftAngle`] into a tuple.
+  pub fn tuple() -> Self {
+    Self::new(ObjectType::LeftAngle, "tuple")
+  }
+}
+
 pub fn nth_element_command() -> IndexedVectorCommand {
   IndexedVectorCommand::for_function("nth")
 }
@@ -335,22 +377,26 @@ impl Command for PackCommand {
       state.main_stack_mut().push(expr);
     } else {
       // Pop one value, use that to determine the length of the
-      // vector. Take care to respect the "keep" modifier.
-      let arg = PackCommand::pop_non_negative_integer(&mut state.main_stack_mut())?;
-      // TODO (CLEANUP): Messy code pattern here to perform stack
-      // cleanup in case of error.
-      let vector = match PackCommand::pop_and_construct_vector(state, context, arg) {
-        Ok(vector) => vector,
+      // vector. Take care to respect the "keep" modifier. The length
+      // is always popped without "keep" semantics, so that the
+      // vector's elements are the values beneath it, and then put
+      // back if necessary. The same stack is used throughout, so that
+      // a pinned length is read only once.
+      let mut stack = state.main_stack_mut();
+      let arg = PackCommand::pop_non_negative_integer(&mut stack)?;
+      let mut stack = KeepableStack::new(stack, context.opts.keep_modifier);
+      let values = match stack.pop_several(arg) {
+        Ok(values) => values,
         Err(err) => {
-          state.main_stack_mut().push(Expr::from(BigInt::from(arg)));
-          return Err(err);
+          stack.get_inner_mut().restore_popped([Expr::from(BigInt::from(arg))]);
+          return Err(err.into());
         }
       };
-      let expr = context.simplify_expr(vector.into(), calculation_mode, &mut errors);
+      let expr = context.simplify_expr(Vector::from(values).into(), calculation_mode, &mut errors);
       if context.opts.keep_modifier {
-        state.main_stack_mut().push(Expr::from(BigInt::from(arg)));
+        stack.get_inner_mut().restore_popped([Expr::from(BigInt::from(arg))]);
       }
-      state.main_stack_mut().push(expr);
+      stack.push(expr);
     }
 
     Ok(CommandOutput::from_errors(errors))
@@ -385,11 +431,9 @@ impl Command for UnpackCommand {
         stack.push_several(chars);
       }
       expr @ Expr::Atom(Atom::Number(_) | Atom::Var(_)) => {
-        if !context.opts.keep_modifier {
-          // If we actually popped the value, then push it back since
-          // this is an error condition.
-          stack.push(expr.clone());
-        }
+        // If we actually popped the value, then put it back since
+        // this is an error condition.
+        stack.restore_popped([expr.clone()]);
         return Err(anyhow::anyhow!(DomainError::new(format!("Cannot unpack {expr}"))));
       }
     }
@@ -450,18 +494,14 @@ impl Command for DiagonalCommand {
       Err(scalar) => {
         // Scalar was provided; argument is not optional.
         let Some(arg) = arg else {
-          if !context.opts.keep_modifier {
-            stack.push(scalar);
-          }
+          stack.restore_popped([scalar]);
           anyhow::bail!("Missing numerical argument for diagonal matrix");
         };
         util::repeated(scalar, arg.max(0) as usize)
       }
       Ok(vector) => {
         if arg.is_some() && Some(vector.len()  as i64) != arg {
-          if !context.opts.keep_modifier {
-            stack.push(vector.into());
-          }
+          stack.restore_popped([vector.into()]);
           anyhow::bail!("Vector length mismatch");
         }
         Vec::from(vector)
@@ -654,12 +694,18 @@ impl Command for ComplexFromIncompleteObjectCommand {
         stack.push(quat);
         Ok(CommandOutput::from_errors(errors))
       }
+      8 => {
+        // Octonion.
+        let mut errors = ErrorList::new();
+        let oct = Expr::call(Octonion::FUNCTION_NAME, elems);
+        let oct = context.simplify_expr(oct, calculation_mode, &mut errors);
+        stack.push(oct);
+        Ok(CommandOutput::from_errors(errors))
+      }
       len => {
-        if !context.opts.keep_modifier {
-          // Return the stack elements if we didn't keep them.
-          stack.push(IncompleteObject::new(ObjectType::LeftParen).into());
-          stack.push_several(elems);
-        }
+        // Return the stack elements if we didn't keep them.
+        let delimiter: Expr = IncompleteObject::new(ObjectType::LeftParen).into();
+        stack.restore_popped(iter::once(delimiter).chain(elems));
         anyhow::bail!("Expected 1 or 2 elements, got {len}");
       }
     }
@@ -670,6 +716,32 @@ impl Command for ComplexFromIncompleteObjectCommand {
   }
 }
 
+impl Command for AggregateFromIncompleteObjectCommand {
+  fn run_command(
+    &self,
+    state: &mut ApplicationState,
+    args: Vec<String>,
+    context: &CommandContext,
+  ) -> anyhow::Result<CommandOutput> {
+    validate_schema(&NullaryArgumentSchema::new(), args)?;
+    state.undo_stack_mut().push_cut();
+
+    let calculation_mode = state.calculation_mode().clone();
+
+    let mut stack = KeepableStack::new(state.main_stack_mut(), context.opts.keep_modifier);
+    let elems = pop_until_delimiter(&mut stack, &IncompleteObject::new(self.delimiter))?;
+    let mut errors = ErrorList::new();
+    let aggregate = Expr::call(&self.function_name, elems);
+    let aggregate = context.simplify_expr(aggregate, calculation_mode, &mut errors);
+    stack.push(aggregate);
+    Ok(CommandOutput::from_errors(errors))
+  }
+
+  fn as_subcommand(&self, _opts: &CommandOptions) -> Option<Subcommand> {
+    None
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -678,6 +750,7 @@ mod tests {
   use crate::command::subcommand::test_utils::{try_call as try_call_subcommand};
   use crate::command::options::CommandOptions;
   use crate::expr::number::ComplexNumber;
+  use crate::expr::incomplete::PopUntilDelimiterError;
 
   #[test]
   fn test_simple_pack_vector() {
@@ -983,6 +1056,52 @@ mod tests {
     assert_eq!(err, StackError::NotEnoughElements { expected: 1, actual: 0 });
   }
 
+  #[test]
+  fn test_set_from_incomplete_object() {
+    let opts = CommandOptions::default();
+    let input_stack = vec![
+      Expr::from(10),
+      IncompleteObject::new(ObjectType::LeftBrace).into(),
+      Expr::from(20),
+      Expr::from(30),
+    ];
+    let output_stack = act_on_stack(&AggregateFromIncompleteObjectCommand::set(), opts, input_stack).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![
+      Expr::from(10),
+      Expr::call("set", vec![Expr::from(20), Expr::from(30)]),
+    ]));
+  }
+
+  #[test]
+  fn test_tuple_from_incomplete_object_with_keep_arg() {
+    let opts = CommandOptions::default().with_keep_modifier();
+    let input_stack = vec![
+      IncompleteObject::new(ObjectType::LeftAngle).into(),
+      Expr::from(20),
+    ];
+    let output_stack = act_on_stack(&AggregateFromIncompleteObjectCommand::tuple(), opts, input_stack).unwrap();
+    assert_eq!(output_stack, Stack::from(vec![
+      IncompleteObject::new(ObjectType::LeftAngle).into(),
+      Expr::from(20),
+      Expr::call("tuple", vec![Expr::from(20)]),
+    ]));
+  }
+
+  #[test]
+  fn test_set_from_incomplete_object_with_wrong_delimiter() {
+    let opts = CommandOptions::default();
+    let input_stack = vec![
+      IncompleteObject::new(ObjectType::LeftAngle).into(),
+      Expr::from(20),
+    ];
+    let err = act_on_stack(&AggregateFromIncompleteObjectCommand::set(), opts, input_stack).unwrap_err();
+    let err = err.downcast::<PopUntilDelimiterError>().unwrap();
+    assert_eq!(err, PopUntilDelimiterError::UnexpectedDelimiter {
+      expected: IncompleteObject::new(ObjectType::LeftBrace),
+      actual: IncompleteObject::new(ObjectType::LeftAngle),
+    });
+  }
+
   #[test]
   fn test_subvector_command_as_subcommand() {
     let command = SubvectorCommand::for_function("test_func");
@@ -0,0 +1,243 @@
+
+//! Descriptive statistics of real-valued data sets: histograms and
+//! summary statistics.
+
+use crate::expr::number::Number;
+use crate::expr::number::inexact::DivInexact;
+use crate::expr::prisms::{PositiveNumber, NumberToUsize};
+use crate::util::prism::Prism;
+
+use num::{Zero, One};
+use thiserror::Error;
+
+/// The largest number of bins that a histogram is permitted to have.
+pub const MAX_HISTOGRAM_BINS: usize = 10_000;
+
+/// One bin of a histogram, covering the half-open interval from
+/// `lower_bound` to the next bin's lower bound. The last bin of a
+/// histogram is closed on both ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistogramBin {
+  pub lower_bound: Number,
+  pub count: usize,
+}
+
+/// Summary statistics of a data set. The quartiles are computed by
+/// linear interpolation between the closest data points, and the
+/// standard deviation is the sample standard deviation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+  pub min: Number,
+  pub first_quartile: Number,
+  pub median: Number,
+  pub third_quartile: Number,
+  pub max: Number,
+  pub mean: Number,
+  pub std_dev: Number,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum DescriptiveError {
+  #[error("Expected at least {expected} data points, got {actual}")]
+  NotEnoughData { expected: usize, actual: usize },
+  #[error("Expected at least one bin")]
+  NoBins,
+  #[error("Histogram would have more than {MAX_HISTOGRAM_BINS} bins")]
+  TooManyBins,
+}
+
+/// Sorts the data into `bin_count` equal-width bins spanning the
+/// range of the data.
+pub fn histogram_by_count(data: &[Number], bin_count: usize) -> Result<Vec<HistogramBin>, DescriptiveError> {
+  if bin_count == 0 {
+    return Err(DescriptiveError::NoBins);
+  }
+  if bin_count > MAX_HISTOGRAM_BINS {
+    return Err(DescriptiveError::TooManyBins);
+  }
+  let (min, max) = min_and_max(data)?;
+  let mut range = max - &min;
+  if range.is_zero() {
+    // All of the data is the same value, so pick an arbitrary
+    // nonzero range to divide into bins.
+    range = Number::one();
+  }
+  let width = range.clone() / Number::from(bin_count);
+  let lower_bounds = (0..bin_count).map(|i| min.clone() + width.clone() * Number::from(i)).collect();
+  // Compute (x - min) * n / range rather than (x - min) / width, so
+  // that exact inputs are binned exactly.
+  let bin_index = |x: &Number| (x.clone() - &min) * Number::from(bin_count);
+  Ok(count_into_bins(data, lower_bounds, |x| bin_index(x).div_floor(&range)))
+}
+
+/// Sorts the data into bins of the given width. The bins are aligned
+/// so that each bin's lower bound is a multiple of the width.
+pub fn histogram_by_width(data: &[Number], bin_width: PositiveNumber) -> Result<Vec<HistogramBin>, DescriptiveError> {
+  let width = Number::from(bin_width);
+  let (min, max) = min_and_max(data)?;
+  let start = min.div_floor(&width) * &width;
+  let bin_count = NumberToUsize.narrow_type((max - &start).div_floor(&width) + Number::one()).ok()
+    .filter(|bin_count| *bin_count <= MAX_HISTOGRAM_BINS)
+    .ok_or(DescriptiveError::TooManyBins)?;
+  let lower_bounds = (0..bin_count).map(|i| start.clone() + width.clone() * Number::from(i)).collect();
+  Ok(count_into_bins(data, lower_bounds, |x| (x.clone() - &start).div_floor(&width)))
+}
+
+/// Computes summary statistics of the data. If `fractional` is true,
+/// then averages of exact values are computed exactly, as fractions.
+pub fn summarize(data: &[Number], fractional: bool) -> Result<Summary, DescriptiveError> {
+  if data.len() < 2 {
+    return Err(DescriptiveError::NotEnoughData { expected: 2, actual: data.len() });
+  }
+  let divide = |a: Number, b: usize| {
+    if fractional { a / Number::from(b) } else { a.div_inexact(&Number::from(b)) }
+  };
+  let mut sorted = data.to_vec();
+  sorted.sort();
+  let quartile = |quarters: usize| {
+    // Interpolate between the closest points, at position (n - 1) *
+    // quarters / 4.
+    let position = (sorted.len() - 1) * quarters;
+    let (index, remainder) = (position / 4, position % 4);
+    if remainder == 0 {
+      sorted[index].clone()
+    } else {
+      let offset = (sorted[index + 1].clone() - &sorted[index]) * Number::from(remainder);
+      sorted[index].clone() + divide(offset, 4)
+    }
+  };
+
+  let mean = divide(data.iter().cloned().sum(), data.len());
+  let sum_of_squares: Number = data.iter().map(|x| {
+    let deviation = x.clone() - &mean;
+    deviation.clone() * deviation
+  }).sum();
+  let std_dev = Number::from((sum_of_squares / Number::from(data.len() - 1)).powf(0.5));
+  Ok(Summary {
+    min: sorted[0].clone(),
+    first_quartile: quartile(1),
+    median: quartile(2),
+    third_quartile: quartile(3),
+    max: sorted[sorted.len() - 1].clone(),
+    mean,
+    std_dev,
+  })
+}
+
+fn min_and_max(data: &[Number]) -> Result<(Number, Number), DescriptiveError> {
+  let min = data.iter().min().ok_or(DescriptiveError::NotEnoughData { expected: 1, actual: 0 })?;
+  // unwrap: data is non-empty, since it has a minimum.
+  let max = data.iter().max().unwrap();
+  Ok((min.clone(), max.clone()))
+}
+
+/// Counts the data into bins with the given lower bounds. `bin_index`
+/// should return the (integer) index of a value's bin. Indices out of
+/// bounds, which can occur due to floating-point rounding, are
+/// clamped into the valid range.
+fn count_into_bins<F>(data: &[Number], lower_bounds: Vec<Number>, bin_index: F) -> Vec<HistogramBin>
+where F: Fn(&Number) -> Number {
+  let mut counts = vec![0; lower_bounds.len()];
+  let last = lower_bounds.len() - 1;
+  for x in data {
+    let index = NumberToUsize.narrow_type(bin_index(x)).unwrap_or(0);
+    counts[index.min(last)] += 1;
+  }
+  lower_bounds.into_iter()
+    .zip(counts)
+    .map(|(lower_bound, count)| HistogramBin { lower_bound, count })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn numbers(values: &[i64]) -> Vec<Number> {
+    values.iter().copied().map(Number::from).collect()
+  }
+
+  fn bins(bins: &[(Number, usize)]) -> Vec<HistogramBin> {
+    bins.iter().map(|(lower_bound, count)| HistogramBin { lower_bound: lower_bound.clone(), count: *count }).collect()
+  }
+
+  #[test]
+  fn test_histogram_by_count() {
+    let histogram = histogram_by_count(&numbers(&[0, 1, 1, 2, 3, 5, 6]), 3).unwrap();
+    assert_eq!(histogram, bins(&[
+      (Number::from(0), 3),
+      (Number::from(2), 2),
+      (Number::from(4), 2),
+    ]));
+
+    let histogram = histogram_by_count(&numbers(&[0, 1]), 3).unwrap();
+    assert_eq!(histogram, bins(&[
+      (Number::from(0), 1),
+      (Number::ratio(1, 3), 0),
+      (Number::ratio(2, 3), 1),
+    ]));
+  }
+
+  #[test]
+  fn test_histogram_by_count_of_constant_data() {
+    let histogram = histogram_by_count(&numbers(&[4, 4, 4]), 2).unwrap();
+    assert_eq!(histogram, bins(&[
+      (Number::from(4), 3),
+      (Number::ratio(9, 2), 0),
+    ]));
+  }
+
+  #[test]
+  fn test_histogram_by_width() {
+    let width = PositiveNumber::new(Number::from(5)).unwrap();
+    let histogram = histogram_by_width(&numbers(&[-3, 0, 4, 5, 12]), width).unwrap();
+    assert_eq!(histogram, bins(&[
+      (Number::from(-5), 1),
+      (Number::from(0), 2),
+      (Number::from(5), 1),
+      (Number::from(10), 1),
+    ]));
+  }
+
+  #[test]
+  fn test_histogram_errors() {
+    assert!(matches!(histogram_by_count(&numbers(&[1]), 0), Err(DescriptiveError::NoBins)));
+    assert!(matches!(histogram_by_count(&numbers(&[1]), MAX_HISTOGRAM_BINS + 1), Err(DescriptiveError::TooManyBins)));
+    assert!(matches!(histogram_by_count(&[], 1), Err(DescriptiveError::NotEnoughData { expected: 1, actual: 0 })));
+    let width = PositiveNumber::new(Number::ratio(1, 1000)).unwrap();
+    assert!(matches!(histogram_by_width(&numbers(&[0, 100]), width), Err(DescriptiveError::TooManyBins)));
+  }
+
+  #[test]
+  fn test_summarize() {
+    let summary = summarize(&numbers(&[7, 1, 3, 5, 4]), true).unwrap();
+    assert_eq!(summary.min, Number::from(1));
+    assert_eq!(summary.first_quartile, Number::from(3));
+    assert_eq!(summary.median, Number::from(4));
+    assert_eq!(summary.third_quartile, Number::from(5));
+    assert_eq!(summary.max, Number::from(7));
+    assert_eq!(summary.mean, Number::from(4));
+    assert_eq!(summary.std_dev, Number::from(5f64.powf(0.5)));
+  }
+
+  #[test]
+  fn test_summarize_interpolates_quartiles() {
+    let summary = summarize(&numbers(&[1, 2, 3, 4]), true).unwrap();
+    assert_eq!(summary.first_quartile, Number::ratio(7, 4));
+    assert_eq!(summary.median, Number::ratio(5, 2));
+    assert_eq!(summary.third_quartile, Number::ratio(13, 4));
+    assert_eq!(summary.mean, Number::ratio(5, 2));
+
+    let summary = summarize(&numbers(&[1, 2, 3, 4]), false).unwrap();
+    assert_eq!(summary.median, Number::from(2.5));
+  }
+
+  #[test]
+  fn test_summarize_not_enough_data() {
+    assert!(matches!(
+      summarize(&numbers(&[1]), true),
+      Err(DescriptiveError::NotEnoughData { expected: 2, actual: 1 }),
+    ));
+  }
+}
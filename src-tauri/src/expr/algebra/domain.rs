@@ -0,0 +1,238 @@
+
+//! The natural domain of an expression in one real variable.
+//!
+//! The natural domain is the set of real values of the variable at
+//! which every subexpression is defined and real. Each partial
+//! function in the expression (division, even roots, logarithms, and
+//! so on) contributes a constraint on its arguments, which is solved
+//! with the sign analysis of [`super::inequality`].
+
+use super::inequality::{RationalFunction, InequalityError};
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::var::Var;
+use crate::expr::interval::IntervalUnion;
+use crate::expr::simplifier::Simplifier;
+
+use num::{Zero, Integer};
+use thiserror::Error;
+
+use std::cmp::Ordering;
+
+/// Functions which are defined and real for every real argument.
+pub const TOTAL_FUNCTIONS: [&str; 14] = [
+  "+", "-", "*", "negate", "abs", "signum", "exp", "sin", "cos",
+  "atan", "sinh", "cosh", "tanh", "asinh",
+];
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum DomainError {
+  #[error("Cannot determine the domain of function {0}")]
+  UnsupportedFunction(String),
+  #[error("{0}")]
+  InequalityError(#[from] InequalityError),
+}
+
+/// Accumulates the constraints imposed by the subexpressions of an
+/// expression.
+struct DomainAnalysis<'a> {
+  var: &'a Var,
+  simplifier: &'a dyn Simplifier,
+  domain: IntervalUnion,
+}
+
+/// Computes the natural domain of `expr`, as a function of `var`.
+///
+/// The arguments to partial functions must be rational functions of
+/// `var`. Variables other than `var` are not allowed in those
+/// arguments, but may appear elsewhere in the expression.
+pub fn natural_domain(expr: &Expr, var: &Var, simplifier: &dyn Simplifier) -> Result<IntervalUnion, DomainError> {
+  let mut analysis = DomainAnalysis { var, simplifier, domain: IntervalUnion::all_reals() };
+  analysis.visit(expr)?;
+  Ok(analysis.domain)
+}
+
+impl<'a> DomainAnalysis<'a> {
+  fn visit(&mut self, expr: &Expr) -> Result<(), DomainError> {
+    let Expr::Call(name, args) = expr else {
+      return Ok(());
+    };
+    // Constrain the arguments first, so that an argument which is
+    // undefined everywhere has already emptied the domain.
+    for arg in args {
+      self.visit(arg)?;
+    }
+    match (name.as_str(), args.as_slice()) {
+      ("/", [_, denominator]) => {
+        self.require(denominator, |sign| sign != Ordering::Equal)
+      }
+      ("recip", [arg]) => {
+        self.require(arg, |sign| sign != Ordering::Equal)
+      }
+      ("sqrt", [arg]) => {
+        self.require(arg, |sign| sign != Ordering::Less)
+      }
+      ("ln", [arg]) => {
+        self.require(arg, |sign| sign == Ordering::Greater)
+      }
+      ("log", [arg, base]) => {
+        self.require(arg, |sign| sign == Ordering::Greater)?;
+        self.require(base, |sign| sign == Ordering::Greater)?;
+        self.require(&Expr::call("-", vec![base.clone(), Expr::one()]), |sign| sign != Ordering::Equal)
+      }
+      ("asin" | "acos", [arg]) => {
+        self.require(&Expr::call("+", vec![arg.clone(), Expr::one()]), |sign| sign != Ordering::Less)?;
+        self.require(&Expr::call("-", vec![Expr::one(), arg.clone()]), |sign| sign != Ordering::Less)
+      }
+      ("^", [base, exponent]) => {
+        self.require_power(base, exponent)
+      }
+      (name, _) if TOTAL_FUNCTIONS.contains(&name) => {
+        Ok(())
+      }
+      (name, _) => {
+        Err(DomainError::UnsupportedFunction(name.to_owned()))
+      }
+    }
+  }
+
+  /// Restricts the domain to the points at which the sign of `expr`
+  /// satisfies `predicate`.
+  fn require<F>(&mut self, expr: &Expr, predicate: F) -> Result<(), DomainError>
+  where F: Fn(Ordering) -> bool {
+    if self.domain.is_empty() {
+      // Nothing left to restrict, and `expr` may not even be a
+      // well-formed rational function (such as `1 / 0`).
+      return Ok(());
+    }
+    let function = RationalFunction::from_expr(expr, self.var)?;
+    let chart = function.sign_chart(self.var, self.simplifier)?;
+    self.domain = self.domain.intersection(&chart.solution_set(predicate));
+    Ok(())
+  }
+
+  /// Restricts the domain to the points at which `base ^ exponent`
+  /// is real. Following [`pow_real`], a base is raised to a fraction
+  /// `p / q` by taking the principal `q`th root of its `p`th power,
+  /// so the power is real at a negative base only when `p` is even.
+  ///
+  /// [`pow_real`]: crate::expr::number::pow_real
+  fn require_power(&mut self, base: &Expr, exponent: &Expr) -> Result<(), DomainError> {
+    let Expr::Atom(Atom::Number(exponent)) = exponent else {
+      // A symbolic exponent may take any real value, which is only
+      // well-defined for a positive base.
+      return self.require(base, |sign| sign == Ordering::Greater);
+    };
+    let is_negative = exponent < &Number::zero();
+    match exponent.to_ratio() {
+      Some(ratio) if ratio.is_integer() || ratio.numer().is_even() => {
+        if is_negative {
+          self.require(base, |sign| sign != Ordering::Equal)
+        } else {
+          Ok(())
+        }
+      }
+      _ => {
+        if is_negative {
+          self.require(base, |sign| sign == Ordering::Greater)
+        } else {
+          self.require(base, |sign| sign != Ordering::Less)
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::interval::{Interval, IntervalType};
+  use crate::expr::algebra::infinity::UnboundedNumber;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::function::table::FunctionTable;
+  use crate::expr::simplifier::default_simplifier;
+
+  use once_cell::sync::Lazy;
+
+  static FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(build_function_table);
+
+  fn x() -> Expr {
+    Expr::var("x").unwrap()
+  }
+
+  fn domain(expr: Expr) -> Result<IntervalUnion, DomainError> {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    natural_domain(&expr, &Var::new("x").unwrap(), simplifier.as_ref())
+  }
+
+  fn finite(n: i64) -> UnboundedNumber {
+    UnboundedNumber::finite(n)
+  }
+
+  fn interval(left: UnboundedNumber, interval_type: IntervalType, right: UnboundedNumber) -> Interval<UnboundedNumber> {
+    Interval::new(left, interval_type, right)
+  }
+
+  #[test]
+  fn test_total_expression() {
+    let expr = Expr::call("+", vec![Expr::call("sin", vec![x()]), Expr::call("^", vec![x(), Expr::from(3)])]);
+    assert_eq!(domain(expr).unwrap(), IntervalUnion::all_reals());
+  }
+
+  #[test]
+  fn test_division() {
+    // 1 / (x^2 - 1)
+    let expr = Expr::call("/", vec![
+      Expr::from(1),
+      Expr::call("-", vec![Expr::call("^", vec![x(), Expr::from(2)]), Expr::from(1)]),
+    ]);
+    assert_eq!(domain(expr).unwrap().intervals(), &[
+      interval(UnboundedNumber::NEG_INFINITY, IntervalType::FullOpen, finite(-1)),
+      interval(finite(-1), IntervalType::FullOpen, finite(1)),
+      interval(finite(1), IntervalType::FullOpen, UnboundedNumber::POS_INFINITY),
+    ]);
+  }
+
+  #[test]
+  fn test_square_root_and_logarithm() {
+    // sqrt(x + 2) + ln(3 - x)
+    let expr = Expr::call("+", vec![
+      Expr::call("sqrt", vec![Expr::call("+", vec![x(), Expr::from(2)])]),
+      Expr::call("ln", vec![Expr::call("-", vec![Expr::from(3), x()])]),
+    ]);
+    assert_eq!(domain(expr).unwrap().intervals(), &[
+      interval(finite(-2), IntervalType::RightOpen, finite(3)),
+    ]);
+  }
+
+  #[test]
+  fn test_fractional_powers() {
+    // x^(1/2) requires x >= 0, but x^(2/3) is real everywhere.
+    let expr = Expr::call("^", vec![x(), Expr::from(Number::ratio(1, 2))]);
+    assert_eq!(domain(expr).unwrap().intervals(), &[
+      interval(finite(0), IntervalType::RightOpen, UnboundedNumber::POS_INFINITY),
+    ]);
+    let expr = Expr::call("^", vec![x(), Expr::from(Number::ratio(2, 3))]);
+    assert_eq!(domain(expr).unwrap(), IntervalUnion::all_reals());
+    let expr = Expr::call("^", vec![x(), Expr::from(Number::ratio(-2, 3))]);
+    assert_eq!(domain(expr).unwrap().intervals(), &[
+      interval(UnboundedNumber::NEG_INFINITY, IntervalType::FullOpen, finite(0)),
+      interval(finite(0), IntervalType::FullOpen, UnboundedNumber::POS_INFINITY),
+    ]);
+  }
+
+  #[test]
+  fn test_empty_domain() {
+    // ln(-x^2)
+    let expr = Expr::call("ln", vec![Expr::call("negate", vec![Expr::call("^", vec![x(), Expr::from(2)])])]);
+    assert!(domain(expr).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_unsupported_function() {
+    let expr = Expr::call("tan", vec![x()]);
+    assert!(matches!(domain(expr), Err(DomainError::UnsupportedFunction(name)) if name == "tan"));
+  }
+}
@@ -13,6 +13,7 @@ use thiserror::Error;
 use std::fmt::{self, Formatter, Display};
 use std::convert::TryFrom;
 use std::str::FromStr;
+use std::cmp::Ordering;
 
 /// A formula is defined as an application of a [`FormulaOp`] to two
 /// expression arguments.
@@ -79,6 +80,19 @@ impl FormulaOp {
       FormulaOp::NotEq => "!=",
     }
   }
+
+  /// Whether the relation holds between two values which compare
+  /// with the given ordering.
+  pub fn holds(self, ordering: Ordering) -> bool {
+    match self {
+      FormulaOp::Less => ordering == Ordering::Less,
+      FormulaOp::LessEq => ordering != Ordering::Greater,
+      FormulaOp::Eq => ordering == Ordering::Equal,
+      FormulaOp::Greater => ordering == Ordering::Greater,
+      FormulaOp::GreaterEq => ordering != Ordering::Less,
+      FormulaOp::NotEq => ordering != Ordering::Equal,
+    }
+  }
 }
 
 impl From<Equation> for Formula {
@@ -0,0 +1,467 @@
+
+//! Solving polynomial and rational inequalities in one real variable.
+//!
+//! An inequality `lhs op rhs` is rearranged into `f(x) op 0`, where
+//! `f` is a quotient of polynomials. The real roots of the numerator
+//! and denominator of `f` cut the real line into open intervals, on
+//! each of which `f` has constant sign, so the solution set can be
+//! read off from a table of signs.
+
+use super::formula::Formula;
+use super::polynomial::dense::{DensePolynomial, PolynomialError};
+use super::polynomial::ring::RationalField;
+use super::root_finding::bisection::{BisectionFunction, BisectionMethod, BisectionError};
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::var::Var;
+use crate::expr::interval::{Interval, IntervalType, IntervalUnion};
+use crate::expr::algebra::infinity::UnboundedNumber;
+use crate::expr::prisms::expr_to_i64;
+use crate::expr::simplifier::Simplifier;
+use crate::util::prism::Prism;
+
+use num::{Zero, One};
+use thiserror::Error;
+
+use std::cmp::Ordering;
+use std::iter;
+
+type RealPolynomial = DensePolynomial<RationalField>;
+
+/// A quotient of two polynomials in one variable.
+///
+/// The quotient is never reduced to lowest terms, since cancelling a
+/// common factor would remove points from the domain of the function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RationalFunction {
+  numerator: RealPolynomial,
+  denominator: RealPolynomial,
+}
+
+/// The sign of a rational function across the whole real line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignChart {
+  /// The real roots of the numerator and the denominator, in
+  /// increasing order.
+  boundaries: Vec<Boundary>,
+  /// The sign of the function on each open interval between
+  /// consecutive boundaries. Always has one more element than
+  /// `boundaries`, since the outermost intervals are unbounded.
+  signs: Vec<Ordering>,
+}
+
+/// A point at which a rational function may change sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boundary {
+  pub point: Number,
+  pub kind: BoundaryKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryKind {
+  /// The function is zero at this point.
+  Zero,
+  /// The function is undefined at this point.
+  Pole,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum InequalityError {
+  #[error("Expression is not a rational function of {0}")]
+  NotARationalFunction(Var),
+  #[error("Division by zero")]
+  DivisionByZero,
+  #[error("{0}")]
+  PolynomialError(#[from] PolynomialError),
+  #[error("{0}")]
+  BisectionError(#[from] BisectionError),
+}
+
+impl RationalFunction {
+  /// Tolerance used when locating irrational boundary points.
+  pub const ROOT_EPSILON: f64 = 1e-10;
+
+  /// Irrational boundary points are replaced by a nearby rational
+  /// number with at most this denominator, if that number is an
+  /// exact root.
+  pub const MAX_SNAP_DENOMINATOR: i64 = 1000;
+
+  pub fn polynomial(numerator: RealPolynomial) -> Self {
+    Self { numerator, denominator: RealPolynomial::constant(RationalField, Number::one()) }
+  }
+
+  pub fn constant(value: Number) -> Self {
+    Self::polynomial(RealPolynomial::constant(RationalField, value))
+  }
+
+  pub fn numerator(&self) -> &RealPolynomial {
+    &self.numerator
+  }
+
+  pub fn denominator(&self) -> &RealPolynomial {
+    &self.denominator
+  }
+
+  /// Interprets an expression as a rational function of `var`. The
+  /// expression may use `+`, `-`, `*`, `/`, `negate`, and integer
+  /// powers.
+  pub fn from_expr(expr: &Expr, var: &Var) -> Result<Self, InequalityError> {
+    let not_rational = || InequalityError::NotARationalFunction(var.clone());
+    match expr {
+      Expr::Atom(Atom::Number(n)) => {
+        Ok(Self::constant(n.clone()))
+      }
+      Expr::Atom(Atom::Var(v)) if v == var => {
+        Ok(Self::polynomial(RealPolynomial::identity(RationalField)))
+      }
+      Expr::Atom(_) => {
+        Err(not_rational())
+      }
+      Expr::Call(name, args) if name == "^" && args.len() == 2 => {
+        let exponent = expr_to_i64().narrow_type(args[1].clone())
+          .map_err(|_| not_rational())?;
+        Self::from_expr(&args[0], var)?.powi(exponent)
+      }
+      Expr::Call(name, args) => {
+        let mut args = args.iter().map(|arg| Self::from_expr(arg, var));
+        match (name.as_str(), args.len()) {
+          ("+", _) => args.try_fold(Self::constant(Number::zero()), |acc, arg| Ok(acc.add(&arg?))),
+          ("*", _) => args.try_fold(Self::constant(Number::one()), |acc, arg| Ok(acc.mul(&arg?))),
+          ("-", 2) => Ok(args.next().unwrap()?.sub(&args.next().unwrap()?)),
+          ("negate", 1) => Ok(args.next().unwrap()?.neg()),
+          ("/", 2) => args.next().unwrap()?.div(&args.next().unwrap()?),
+          _ => Err(not_rational()),
+        }
+      }
+    }
+  }
+
+  pub fn add(&self, other: &Self) -> Self {
+    Self {
+      numerator: self.numerator.mul(&other.denominator).add(&other.numerator.mul(&self.denominator)),
+      denominator: self.denominator.mul(&other.denominator),
+    }
+  }
+
+  pub fn neg(&self) -> Self {
+    Self { numerator: self.numerator.neg(), denominator: self.denominator.clone() }
+  }
+
+  pub fn sub(&self, other: &Self) -> Self {
+    self.add(&other.neg())
+  }
+
+  pub fn mul(&self, other: &Self) -> Self {
+    Self {
+      numerator: self.numerator.mul(&other.numerator),
+      denominator: self.denominator.mul(&other.denominator),
+    }
+  }
+
+  pub fn div(&self, other: &Self) -> Result<Self, InequalityError> {
+    if other.numerator.is_zero() {
+      return Err(InequalityError::DivisionByZero);
+    }
+    Ok(Self {
+      numerator: self.numerator.mul(&other.denominator),
+      denominator: self.denominator.mul(&other.numerator),
+    })
+  }
+
+  pub fn powi(&self, exponent: i64) -> Result<Self, InequalityError> {
+    let power = exponent.unsigned_abs() as usize;
+    let result = Self { numerator: self.numerator.pow(power), denominator: self.denominator.pow(power) };
+    if exponent < 0 {
+      Self::constant(Number::one()).div(&result)
+    } else {
+      Ok(result)
+    }
+  }
+
+  /// The sign of the function at `x`, which must not be a pole.
+  pub fn sign_at(&self, x: &Number) -> Ordering {
+    let value = self.numerator.eval(x) * self.denominator.eval(x);
+    value.cmp(&Number::zero())
+  }
+
+  /// Computes the sign of the function on the whole real line.
+  ///
+  /// Boundary points are found by bisection between the critical
+  /// points of each polynomial, so they are exact only if they are
+  /// rational (with small denominator) or roots of linear factors.
+  pub fn sign_chart(&self, var: &Var, simplifier: &dyn Simplifier) -> Result<SignChart, InequalityError> {
+    let numerator = squarefree_part(&self.numerator)?;
+    let denominator = squarefree_part(&self.denominator)?;
+    // A root of both the numerator and the denominator is a pole,
+    // so it is only counted once.
+    let common = numerator.gcd(&denominator)?;
+    let (numerator, _) = numerator.div_rem(&common)?;
+
+    let zeroes = real_roots(&numerator, var, simplifier)?.into_iter()
+      .map(|point| Boundary { point, kind: BoundaryKind::Zero });
+    let poles = real_roots(&denominator, var, simplifier)?.into_iter()
+      .map(|point| Boundary { point, kind: BoundaryKind::Pole });
+    let mut boundaries: Vec<_> = zeroes.chain(poles).collect();
+    boundaries.sort_by(|a, b| a.point.cmp(&b.point));
+
+    let signs = test_points(&boundaries).iter().map(|x| self.sign_at(x)).collect();
+    Ok(SignChart { boundaries, signs })
+  }
+}
+
+impl SignChart {
+  pub fn boundaries(&self) -> &[Boundary] {
+    &self.boundaries
+  }
+
+  /// The set of real numbers at which the function is defined and
+  /// its sign satisfies `predicate`.
+  pub fn solution_set<F>(&self, predicate: F) -> IntervalUnion
+  where F: Fn(Ordering) -> bool {
+    let endpoints: Vec<_> = iter::once(UnboundedNumber::NEG_INFINITY)
+      .chain(self.boundaries.iter().map(|b| UnboundedNumber::Finite(b.point.clone())))
+      .chain(iter::once(UnboundedNumber::POS_INFINITY))
+      .collect();
+    let gaps = self.signs.iter()
+      .zip(endpoints.windows(2))
+      .filter(|(sign, _)| predicate(**sign))
+      .map(|(_, ends)| Interval::new(ends[0].clone(), IntervalType::FullOpen, ends[1].clone()));
+    let points = self.boundaries.iter()
+      .filter(|b| b.kind == BoundaryKind::Zero && predicate(Ordering::Equal))
+      .map(|b| Interval::singleton(UnboundedNumber::Finite(b.point.clone())));
+    IntervalUnion::from_intervals(gaps.chain(points))
+  }
+}
+
+/// Solves the formula for real values of `var`. Both sides of the
+/// formula must be rational functions of `var`.
+pub fn solve_inequality(
+  formula: Formula,
+  var: &Var,
+  simplifier: &dyn Simplifier,
+) -> Result<IntervalUnion, InequalityError> {
+  let difference = Expr::call("-", vec![formula.left, formula.right]);
+  let function = RationalFunction::from_expr(&difference, var)?;
+  let chart = function.sign_chart(var, simplifier)?;
+  Ok(chart.solution_set(|sign| formula.op.holds(sign)))
+}
+
+/// Divides out repeated factors, so that every root of the result
+/// is simple. Constants (including zero) are returned unchanged.
+fn squarefree_part(poly: &RealPolynomial) -> Result<RealPolynomial, PolynomialError> {
+  if poly.degree().is_none_or(|d| d == 0) {
+    return Ok(poly.clone());
+  }
+  let repeated = poly.gcd(&poly.derivative())?;
+  let (result, _) = poly.div_rem(&repeated)?;
+  Ok(result)
+}
+
+/// The real roots of a squarefree polynomial, in increasing order.
+fn real_roots(poly: &RealPolynomial, var: &Var, simplifier: &dyn Simplifier) -> Result<Vec<Number>, InequalityError> {
+  let coefficients = poly.coefficients();
+  match poly.degree() {
+    None | Some(0) => Ok(Vec::new()),
+    Some(1) => Ok(vec![-(&coefficients[0] / &coefficients[1])]),
+    Some(_) => {
+      // The polynomial is monotone between consecutive critical
+      // points, so each such interval contains at most one root.
+      let critical_points = real_roots(&squarefree_part(&poly.derivative())?, var, simplifier)?;
+      let bound = root_bound(poly);
+      let endpoints: Vec<_> = iter::once(-&bound)
+        .chain(critical_points)
+        .chain(iter::once(bound))
+        .collect();
+
+      let function = BisectionFunction::from_expr(poly.to_expr(var), var.clone(), simplifier);
+      let method = BisectionMethod::new(RationalFunction::ROOT_EPSILON, BisectionMethod::DEFAULT_MAX_ITERATIONS);
+      let mut roots = Vec::new();
+      for ends in endpoints.windows(2) {
+        let (left, right) = (poly.eval(&ends[0]), poly.eval(&ends[1]));
+        if left.is_zero() {
+          roots.push(ends[0].clone());
+        } else if !right.is_zero() && left.signum() != right.signum() {
+          // Since the result is not exact anyway, do the search in
+          // floating point rather than with ever-growing rationals.
+          let root = method.find_root(&function, ends[0].to_inexact(), ends[1].to_inexact())?.value;
+          roots.push(snap_to_rational(poly, &root).unwrap_or(root));
+        }
+      }
+      Ok(roots)
+    }
+  }
+}
+
+/// Cauchy's bound, which is strictly greater than the absolute value
+/// of every root of the (nonconstant) polynomial.
+fn root_bound(poly: &RealPolynomial) -> Number {
+  let coefficients = poly.coefficients();
+  let leading = coefficients.last().unwrap().abs(); // unwrap: Polynomial is nonconstant
+  let largest_ratio = coefficients[..coefficients.len() - 1].iter()
+    .map(|c| c.abs() / &leading)
+    .fold(Number::zero(), Number::max);
+  largest_ratio + Number::one()
+}
+
+/// Finds a rational number close to `approx` which is an exact root
+/// of the polynomial, by trying the continued fraction convergents of
+/// `approx` in turn.
+fn snap_to_rational(poly: &RealPolynomial, approx: &Number) -> Option<Number> {
+  let mut x = approx.to_f64()?;
+  let whole = x.floor();
+  let (mut prev_numer, mut numer) = (1i64, whole as i64);
+  let (mut prev_denom, mut denom) = (0i64, 1i64);
+  loop {
+    let candidate = Number::ratio(numer, denom);
+    if poly.eval(&candidate).is_zero() {
+      return Some(candidate);
+    }
+    let fractional = x - x.floor();
+    if fractional < f64::EPSILON {
+      return None;
+    }
+    x = fractional.recip();
+    let term = x.floor() as i64;
+    (prev_numer, numer) = (numer, term.checked_mul(numer)?.checked_add(prev_numer)?);
+    (prev_denom, denom) = (denom, term.checked_mul(denom)?.checked_add(prev_denom)?);
+    if denom > RationalFunction::MAX_SNAP_DENOMINATOR {
+      return None;
+    }
+  }
+}
+
+/// One point strictly inside each of the open intervals cut out by
+/// the boundaries.
+fn test_points(boundaries: &[Boundary]) -> Vec<Number> {
+  let (Some(first), Some(last)) = (boundaries.first(), boundaries.last()) else {
+    return vec![Number::zero()];
+  };
+  let midpoints = boundaries.windows(2)
+    .map(|pair| (&pair[0].point + &pair[1].point) / Number::from(2));
+  iter::once(&first.point - Number::one())
+    .chain(midpoints)
+    .chain(iter::once(&last.point + Number::one()))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::algebra::formula::FormulaOp;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::function::table::FunctionTable;
+  use crate::expr::simplifier::default_simplifier;
+
+  use once_cell::sync::Lazy;
+
+  static FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(build_function_table);
+
+  fn x() -> Expr {
+    Expr::var("x").unwrap()
+  }
+
+  fn solve(left: Expr, op: FormulaOp, right: Expr) -> Result<IntervalUnion, InequalityError> {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    solve_inequality(Formula::new(left, op, right), &Var::new("x").unwrap(), simplifier.as_ref())
+  }
+
+  fn finite(n: i64) -> UnboundedNumber {
+    UnboundedNumber::finite(n)
+  }
+
+  fn interval(left: UnboundedNumber, interval_type: IntervalType, right: UnboundedNumber) -> Interval<UnboundedNumber> {
+    Interval::new(left, interval_type, right)
+  }
+
+  #[test]
+  fn test_linear_inequality() {
+    // 2x + 1 <= 4
+    let left = Expr::call("+", vec![Expr::call("*", vec![Expr::from(2), x()]), Expr::from(1)]);
+    let solution = solve(left, FormulaOp::LessEq, Expr::from(4)).unwrap();
+    assert_eq!(solution, IntervalUnion::from(interval(
+      UnboundedNumber::NEG_INFINITY,
+      IntervalType::LeftOpen,
+      UnboundedNumber::finite(Number::ratio(3, 2)),
+    )));
+  }
+
+  #[test]
+  fn test_quadratic_inequality() {
+    // x^2 > 4
+    let left = Expr::call("^", vec![x(), Expr::from(2)]);
+    let solution = solve(left, FormulaOp::Greater, Expr::from(4)).unwrap();
+    assert_eq!(solution.intervals(), &[
+      interval(UnboundedNumber::NEG_INFINITY, IntervalType::FullOpen, finite(-2)),
+      interval(finite(2), IntervalType::FullOpen, UnboundedNumber::POS_INFINITY),
+    ]);
+  }
+
+  #[test]
+  fn test_repeated_root() {
+    // (x - 1)^2 <= 0
+    let left = Expr::call("^", vec![Expr::call("-", vec![x(), Expr::from(1)]), Expr::from(2)]);
+    let solution = solve(left.clone(), FormulaOp::LessEq, Expr::from(0)).unwrap();
+    assert_eq!(solution.intervals(), &[Interval::singleton(finite(1))]);
+    let solution = solve(left, FormulaOp::Greater, Expr::from(0)).unwrap();
+    assert_eq!(solution.intervals(), &[
+      interval(UnboundedNumber::NEG_INFINITY, IntervalType::FullOpen, finite(1)),
+      interval(finite(1), IntervalType::FullOpen, UnboundedNumber::POS_INFINITY),
+    ]);
+  }
+
+  #[test]
+  fn test_rational_inequality() {
+    // (x - 1) / (x + 2) >= 0
+    let left = Expr::call("/", vec![
+      Expr::call("-", vec![x(), Expr::from(1)]),
+      Expr::call("+", vec![x(), Expr::from(2)]),
+    ]);
+    let solution = solve(left, FormulaOp::GreaterEq, Expr::from(0)).unwrap();
+    assert_eq!(solution.intervals(), &[
+      interval(UnboundedNumber::NEG_INFINITY, IntervalType::FullOpen, finite(-2)),
+      interval(finite(1), IntervalType::RightOpen, UnboundedNumber::POS_INFINITY),
+    ]);
+  }
+
+  #[test]
+  fn test_cancelled_factor_is_still_excluded() {
+    // x / x >= 1
+    let left = Expr::call("/", vec![x(), x()]);
+    let solution = solve(left, FormulaOp::GreaterEq, Expr::from(1)).unwrap();
+    assert_eq!(solution.intervals(), &[
+      interval(UnboundedNumber::NEG_INFINITY, IntervalType::FullOpen, finite(0)),
+      interval(finite(0), IntervalType::FullOpen, UnboundedNumber::POS_INFINITY),
+    ]);
+  }
+
+  #[test]
+  fn test_no_solutions() {
+    // x^2 + 1 < 0
+    let left = Expr::call("+", vec![Expr::call("^", vec![x(), Expr::from(2)]), Expr::from(1)]);
+    let solution = solve(left, FormulaOp::Less, Expr::from(0)).unwrap();
+    assert!(solution.is_empty());
+  }
+
+  #[test]
+  fn test_irrational_boundary() {
+    // x^2 < 2
+    let left = Expr::call("^", vec![x(), Expr::from(2)]);
+    let solution = solve(left, FormulaOp::Less, Expr::from(2)).unwrap();
+    let [only] = solution.intervals() else { panic!("Expected one interval, got {solution:?}") };
+    assert_eq!(only.interval_type(), IntervalType::FullOpen);
+    let (UnboundedNumber::Finite(left), UnboundedNumber::Finite(right)) = (only.left(), only.right()) else {
+      panic!("Expected finite bounds, got {only:?}");
+    };
+    assert!((left.to_f64_or_nan() + 2f64.sqrt()).abs() < 1e-6);
+    assert!((right.to_f64_or_nan() - 2f64.sqrt()).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_not_a_rational_function() {
+    let left = Expr::call("sin", vec![x()]);
+    let err = solve(left, FormulaOp::Less, Expr::from(0)).unwrap_err();
+    assert!(matches!(err, InequalityError::NotARationalFunction(_)));
+  }
+}
@@ -0,0 +1,289 @@
+
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::var::Var;
+use super::{InfiniteConstant, INFINITESIMAL_NAME};
+
+use num::{Zero, One, BigInt};
+
+use std::ops::{Add, Sub, Mul, Neg};
+
+/// A first-order hyperreal value `standard + infinitesimal * eps`,
+/// where `eps` is the infinitesimal constant. The constant `eps` is
+/// positive but smaller than every positive real number, and its
+/// square is absorbed (treated as zero), so that products of
+/// hyperreals never need higher powers of `eps`.
+///
+/// Hyperreals are ordered lexicographically: first by standard part,
+/// then by infinitesimal part.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hyperreal {
+  pub standard: Number,
+  pub infinitesimal: Number,
+}
+
+impl Hyperreal {
+  pub fn new(standard: Number, infinitesimal: Number) -> Self {
+    Self { standard, infinitesimal }
+  }
+
+  /// The infinitesimal constant `eps` itself.
+  pub fn eps() -> Self {
+    Self::new(Number::zero(), Number::one())
+  }
+
+  pub fn real(value: Number) -> Self {
+    Self::new(value, Number::zero())
+  }
+
+  /// Whether this value has no infinitesimal part.
+  pub fn is_real(&self) -> bool {
+    self.infinitesimal.is_zero()
+  }
+
+  /// Whether this value is a nonzero multiple of `eps`.
+  pub fn is_pure_infinitesimal(&self) -> bool {
+    self.standard.is_zero() && !self.infinitesimal.is_zero()
+  }
+
+  /// The sign of this value, as `-1`, `0`, or `1`.
+  pub fn signum(&self) -> Number {
+    if self.standard.is_zero() {
+      self.infinitesimal.signum()
+    } else {
+      self.standard.signum()
+    }
+  }
+
+  /// Parses an expression built from real numbers and `eps` with
+  /// the arithmetic operators. Returns `None` if the expression
+  /// contains anything else, or if it divides by a (possibly
+  /// infinitesimal) zero.
+  pub fn from_expr(expr: &Expr) -> Option<Hyperreal> {
+    match expr {
+      Expr::Atom(Atom::Number(n)) => Some(Hyperreal::real(n.clone())),
+      Expr::Atom(Atom::Var(v)) if v.as_str() == INFINITESIMAL_NAME => Some(Hyperreal::eps()),
+      Expr::Atom(_) => None,
+      Expr::Call(function, args) => {
+        match (function.as_str(), args.as_slice()) {
+          ("negate", [arg]) => Some(- Hyperreal::from_expr(arg)?),
+          ("+", args) => {
+            args.iter().try_fold(Hyperreal::real(Number::zero()), |acc, arg| Some(acc + Hyperreal::from_expr(arg)?))
+          }
+          ("-", [left, right]) => Some(Hyperreal::from_expr(left)? - Hyperreal::from_expr(right)?),
+          ("*", args) => {
+            args.iter().try_fold(Hyperreal::real(Number::one()), |acc, arg| Some(acc * Hyperreal::from_expr(arg)?))
+          }
+          ("/", [left, right]) => Hyperreal::from_expr(left)?.checked_div(&Hyperreal::from_expr(right)?),
+          ("^", [base, Expr::Atom(Atom::Number(exponent))]) => {
+            let exponent = BigInt::try_from(exponent.clone()).ok()?;
+            Hyperreal::from_expr(base)?.checked_powi(exponent)
+          }
+          _ => None,
+        }
+      }
+    }
+  }
+
+  /// Writes this value as an expression, in the canonical form `a +
+  /// k * eps`, omitting whichever parts are trivial.
+  pub fn to_expr(&self) -> Expr {
+    let eps = Expr::from(Var::new(INFINITESIMAL_NAME).unwrap());
+    if self.infinitesimal.is_zero() {
+      return Expr::from(self.standard.clone());
+    }
+    let (operator, coefficient) = if self.standard.is_zero() || self.infinitesimal.is_positive() {
+      ("+", self.infinitesimal.clone())
+    } else {
+      ("-", - &self.infinitesimal)
+    };
+    let infinitesimal_term = if coefficient.is_one() {
+      eps
+    } else if (- &coefficient).is_one() {
+      Expr::call("negate", vec![eps])
+    } else {
+      Expr::call("*", vec![Expr::from(coefficient), eps])
+    };
+    if self.standard.is_zero() {
+      infinitesimal_term
+    } else {
+      Expr::call(operator, vec![Expr::from(self.standard.clone()), infinitesimal_term])
+    }
+  }
+
+  /// Divides two hyperreals, or returns `None` if the divisor has
+  /// no standard part. Dividing two multiples of `eps` is permitted,
+  /// and yields a real number.
+  pub fn checked_div(&self, other: &Hyperreal) -> Option<Hyperreal> {
+    if other.standard.is_zero() {
+      if self.is_real() && self.standard.is_zero() {
+        // 0 / (k eps) = 0
+        return (!other.infinitesimal.is_zero()).then(|| Hyperreal::real(Number::zero()));
+      }
+      if self.standard.is_zero() && !other.infinitesimal.is_zero() {
+        return Some(Hyperreal::real(&self.infinitesimal / &other.infinitesimal));
+      }
+      return None;
+    }
+    // (a + b eps) / (c + d eps) = a/c + (bc - ad)/c^2 eps
+    let standard = &self.standard / &other.standard;
+    let infinitesimal = (&self.infinitesimal * &other.standard - &self.standard * &other.infinitesimal)
+      / (&other.standard * &other.standard);
+    Some(Hyperreal::new(standard, infinitesimal))
+  }
+
+  /// Raises a hyperreal to an integer power, or returns `None` if
+  /// the result would be infinite.
+  pub fn checked_powi(&self, exponent: BigInt) -> Option<Hyperreal> {
+    if exponent.is_zero() {
+      return Some(Hyperreal::real(Number::one()));
+    }
+    if exponent < BigInt::zero() {
+      return Hyperreal::real(Number::one()).checked_div(&self.checked_powi(- exponent)?);
+    }
+    if self.standard.is_zero() {
+      // eps^2 is absorbed.
+      return Some(if exponent.is_one() { self.clone() } else { Hyperreal::real(Number::zero()) });
+    }
+    // (a + k eps)^n = a^n + n a^(n-1) k eps
+    let standard = self.standard.powi(exponent.clone());
+    let infinitesimal = Number::from(exponent.clone()) * self.standard.powi(exponent - 1) * &self.infinitesimal;
+    Some(Hyperreal::new(standard, infinitesimal))
+  }
+
+  /// The infinite constant which results from dividing a value with
+  /// nonzero standard part by an infinitesimal, or `None` if `self`
+  /// is not such a quotient's numerator for `divisor`.
+  pub fn divide_to_infinity(&self, divisor: &Hyperreal) -> Option<InfiniteConstant> {
+    if self.standard.is_zero() || !divisor.is_pure_infinitesimal() {
+      return None;
+    }
+    if self.standard.is_positive() == divisor.infinitesimal.is_positive() {
+      Some(InfiniteConstant::PosInfinity)
+    } else {
+      Some(InfiniteConstant::NegInfinity)
+    }
+  }
+}
+
+impl Add for Hyperreal {
+  type Output = Hyperreal;
+
+  fn add(self, other: Hyperreal) -> Hyperreal {
+    Hyperreal::new(self.standard + other.standard, self.infinitesimal + other.infinitesimal)
+  }
+}
+
+impl Sub for Hyperreal {
+  type Output = Hyperreal;
+
+  fn sub(self, other: Hyperreal) -> Hyperreal {
+    self + (- other)
+  }
+}
+
+impl Neg for Hyperreal {
+  type Output = Hyperreal;
+
+  fn neg(self) -> Hyperreal {
+    Hyperreal::new(- self.standard, - self.infinitesimal)
+  }
+}
+
+/// Multiplication of hyperreals, absorbing the `eps^2` term.
+impl Mul for Hyperreal {
+  type Output = Hyperreal;
+
+  fn mul(self, other: Hyperreal) -> Hyperreal {
+    // (a + b eps) (c + d eps) = ac + (ad + bc) eps
+    let infinitesimal = &self.standard * &other.infinitesimal + &self.infinitesimal * &other.standard;
+    Hyperreal::new(self.standard * other.standard, infinitesimal)
+  }
+}
+
+/// Whether the expression mentions the infinitesimal constant
+/// anywhere.
+pub fn contains_infinitesimal(expr: &Expr) -> bool {
+  match expr {
+    Expr::Atom(Atom::Var(v)) => v.as_str() == INFINITESIMAL_NAME,
+    Expr::Atom(_) => false,
+    Expr::Call(_, args) => args.iter().any(contains_infinitesimal),
+  }
+}
+
+/// Discards the infinitesimal constant from an expression, by
+/// replacing it with zero. The result should be simplified
+/// afterward. For a finite hyperreal, this is its standard part.
+pub fn standard_part(expr: Expr) -> Expr {
+  expr.substitute_var(Var::new(INFINITESIMAL_NAME).unwrap(), Expr::zero())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn eps() -> Expr {
+    Expr::var("eps").unwrap()
+  }
+
+  fn hyperreal(standard: i64, infinitesimal: i64) -> Hyperreal {
+    Hyperreal::new(Number::from(standard), Number::from(infinitesimal))
+  }
+
+  #[test]
+  fn test_from_expr() {
+    assert_eq!(Hyperreal::from_expr(&eps()), Some(hyperreal(0, 1)));
+    let expr = Expr::call("+", vec![Expr::from(3), Expr::call("*", vec![Expr::from(2), eps()])]);
+    assert_eq!(Hyperreal::from_expr(&expr), Some(hyperreal(3, 2)));
+    let expr = Expr::call("*", vec![eps(), eps()]);
+    assert_eq!(Hyperreal::from_expr(&expr), Some(hyperreal(0, 0)));
+    let expr = Expr::call("^", vec![Expr::call("+", vec![Expr::from(3), eps()]), Expr::from(2)]);
+    assert_eq!(Hyperreal::from_expr(&expr), Some(hyperreal(9, 6)));
+    let expr = Expr::call("/", vec![Expr::from(1), eps()]);
+    assert_eq!(Hyperreal::from_expr(&expr), None);
+    let expr = Expr::call("+", vec![Expr::var("x").unwrap(), eps()]);
+    assert_eq!(Hyperreal::from_expr(&expr), None);
+  }
+
+  #[test]
+  fn test_to_expr() {
+    assert_eq!(hyperreal(0, 1).to_expr(), eps());
+    assert_eq!(hyperreal(0, -1).to_expr(), Expr::call("negate", vec![eps()]));
+    assert_eq!(hyperreal(4, 0).to_expr(), Expr::from(4));
+    assert_eq!(
+      hyperreal(3, 2).to_expr(),
+      Expr::call("+", vec![Expr::from(3), Expr::call("*", vec![Expr::from(2), eps()])]),
+    );
+    assert_eq!(hyperreal(3, -1).to_expr(), Expr::call("-", vec![Expr::from(3), eps()]));
+    for value in [hyperreal(0, 5), hyperreal(-2, -3), hyperreal(1, 1)] {
+      assert_eq!(Hyperreal::from_expr(&value.to_expr()), Some(value));
+    }
+  }
+
+  #[test]
+  fn test_ordering() {
+    assert!(hyperreal(0, 1) > hyperreal(0, 0));
+    assert!(hyperreal(0, 1000) < hyperreal(1, 0));
+    assert!(hyperreal(1, -1) < hyperreal(1, 0));
+    assert!(hyperreal(-1, 5) < hyperreal(0, -5));
+  }
+
+  #[test]
+  fn test_division() {
+    assert_eq!(hyperreal(0, 6).checked_div(&hyperreal(0, 3)), Some(hyperreal(2, 0)));
+    assert_eq!(hyperreal(1, 0).checked_div(&hyperreal(0, 3)), None);
+    assert_eq!(hyperreal(4, 2).checked_div(&hyperreal(2, 0)), Some(hyperreal(2, 1)));
+    assert_eq!(hyperreal(1, 0).divide_to_infinity(&hyperreal(0, -1)), Some(InfiniteConstant::NegInfinity));
+    assert_eq!(hyperreal(-2, 0).divide_to_infinity(&hyperreal(0, -1)), Some(InfiniteConstant::PosInfinity));
+    assert_eq!(hyperreal(0, 1).divide_to_infinity(&hyperreal(0, 1)), None);
+  }
+
+  #[test]
+  fn test_standard_part() {
+    let expr = Expr::call("+", vec![Expr::from(3), eps()]);
+    assert_eq!(standard_part(expr), Expr::call("+", vec![Expr::from(3), Expr::zero()]));
+    assert!(contains_infinitesimal(&Expr::call("sin", vec![eps()])));
+    assert!(!contains_infinitesimal(&Expr::call("sin", vec![Expr::from(1)])));
+  }
+}
@@ -1,10 +1,12 @@
 
 mod base;
+mod infinitesimal;
 mod prisms;
 mod signed;
 mod unbounded;
 
 pub use base::InfiniteConstant;
+pub use infinitesimal::{Hyperreal, contains_infinitesimal, standard_part};
 pub use signed::{SignedInfinity, ExpectedSignedInfinityError};
 pub use prisms::{ExprToInfinity, infinity_to_signed_infinity,
                  expr_to_signed_infinity, expr_to_unbounded_number};
@@ -21,6 +23,7 @@ use std::cmp::Ordering;
 pub const INFINITY_NAME: &str = "inf";
 pub const UNDIRECTED_INFINITY_NAME: &str = "uinf";
 pub const NAN_NAME: &str = "nan";
+pub const INFINITESIMAL_NAME: &str = "eps";
 
 pub fn is_infinite_constant(expr: &Expr) -> bool {
   InfiniteConstant::ALL.iter().any(|c| &Expr::from(c) == expr)
@@ -1,6 +1,6 @@
 
 use crate::expr::{Expr, TryFromExprError};
-use crate::expr::number::Number;
+use crate::expr::number::{Number, pow_real};
 use crate::util::PreOne;
 use crate::util::prism::Prism;
 use super::prisms::expr_to_unbounded_number;
@@ -52,6 +52,37 @@ impl UnboundedNumber {
   pub fn finite(n: impl Into<Number>) -> Self {
     UnboundedNumber::Finite(n.into())
   }
+
+  /// Raises `self` to a real power, where `self` must be
+  /// nonnegative. Returns an error for the indeterminate forms `0^0`,
+  /// `inf^0`, and `1^inf`.
+  ///
+  /// Panics if `self` is negative.
+  pub fn try_pow_nonnegative(self, exponent: UnboundedNumber) -> Result<UnboundedNumber, IndeterminateFormError> {
+    use UnboundedNumber::*;
+    assert!(self >= UnboundedNumber::zero(), "Expected nonnegative base, got {:?}", self);
+    match (self, exponent) {
+      (Finite(a), Finite(b)) if a.is_zero() => match b.cmp(&Number::zero()) {
+        Ordering::Greater => Ok(UnboundedNumber::zero()),
+        Ordering::Less => Ok(Self::POS_INFINITY),
+        Ordering::Equal => Err(IndeterminateFormError { message: "0 ^ 0" }),
+      },
+      // unwrap: A positive base raised to a real power is always real.
+      (Finite(a), Finite(b)) => Ok(Finite(pow_real(a, b).unwrap_real())),
+      (Infinite(_), Finite(b)) => match b.cmp(&Number::zero()) {
+        Ordering::Greater => Ok(Self::POS_INFINITY),
+        Ordering::Less => Ok(UnboundedNumber::zero()),
+        Ordering::Equal => Err(IndeterminateFormError { message: "inf ^ 0" }),
+      },
+      (Finite(a), Infinite(b)) => match (a.cmp(&Number::one()), b) {
+        (Ordering::Equal, _) => Err(IndeterminateFormError { message: "1 ^ inf" }),
+        (Ordering::Greater, SignedInfinity::PosInfinity) | (Ordering::Less, SignedInfinity::NegInfinity) => Ok(Self::POS_INFINITY),
+        (Ordering::Greater, SignedInfinity::NegInfinity) | (Ordering::Less, SignedInfinity::PosInfinity) => Ok(UnboundedNumber::zero()),
+      },
+      (Infinite(_), Infinite(SignedInfinity::PosInfinity)) => Ok(Self::POS_INFINITY),
+      (Infinite(_), Infinite(SignedInfinity::NegInfinity)) => Ok(UnboundedNumber::zero()),
+    }
+  }
 }
 
 impl TryFrom<Expr> for UnboundedNumber {
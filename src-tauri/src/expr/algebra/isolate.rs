@@ -0,0 +1,232 @@
+
+//! Symbolic rearrangement of equations to isolate a single variable.
+
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::var::Var;
+use crate::expr::algebra::formula::{Equation, Formula};
+
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error)]
+pub enum IsolateError {
+  #[error("Variable {0} does not appear in the equation")]
+  VarNotFound(Var),
+  #[error("Variable {var} appears more than once in {expr}")]
+  MultipleOccurrences { var: Var, expr: Expr },
+  #[error("Don't know how to invert {0}")]
+  NotInvertible(Expr),
+}
+
+/// Rearranges the equation so that the given variable stands alone
+/// on the left-hand side.
+///
+/// The variable must appear exactly once in the equation, nested
+/// inside a chain of invertible operations: `+`, `-`, `*`, `/`, `^`,
+/// `negate`, `recip`, `sqrt`, `exp`, `ln`, `log`, and the
+/// (hyperbolic) trigonometric functions and their inverses. Inverses
+/// always use the principal branch, so (for instance) isolating `x`
+/// in `x^2 = 4` produces `x = 4^(1/2)` and loses the negative root.
+///
+/// The result is not simplified.
+pub fn isolate(equation: Equation, var: &Var) -> Result<Equation, IsolateError> {
+  let left_count = count_occurrences(&equation.left, var);
+  let right_count = count_occurrences(&equation.right, var);
+  let (mut target, mut value) = match (left_count, right_count) {
+    (0, 0) => return Err(IsolateError::VarNotFound(var.clone())),
+    (1, 0) => (equation.left, equation.right),
+    (0, 1) => (equation.right, equation.left),
+    _ => return Err(IsolateError::MultipleOccurrences { var: var.clone(), expr: Expr::from(Formula::from(equation)) }),
+  };
+  loop {
+    match target {
+      Expr::Atom(Atom::Var(v)) if &v == var => {
+        return Ok(Equation::new(Expr::from(v), value));
+      }
+      Expr::Call(name, args) => {
+        (target, value) = invert_step(name, args, value, var)?;
+      }
+      Expr::Atom(_) => unreachable!("Atom {target} should contain {var}"),
+    }
+  }
+}
+
+/// Given the equation `name(args) = value`, where exactly one of
+/// `args` contains `var`, produces an equivalent equation with that
+/// argument alone on the left-hand side.
+fn invert_step(name: String, mut args: Vec<Expr>, value: Expr, var: &Var) -> Result<(Expr, Expr), IsolateError> {
+  let Some(index) = args.iter().position(|arg| count_occurrences(arg, var) > 0) else {
+    unreachable!("Some argument of {name} should contain {var}");
+  };
+  let inverse_function = match name.as_str() {
+    "negate" => Some("negate"),
+    "recip" => Some("recip"),
+    "exp" => Some("ln"),
+    "ln" => Some("exp"),
+    "sin" => Some("asin"),
+    "cos" => Some("acos"),
+    "tan" => Some("atan"),
+    "asin" => Some("sin"),
+    "acos" => Some("cos"),
+    "atan" => Some("tan"),
+    "sinh" => Some("asinh"),
+    "cosh" => Some("acosh"),
+    "tanh" => Some("atanh"),
+    "asinh" => Some("sinh"),
+    "acosh" => Some("cosh"),
+    "atanh" => Some("tanh"),
+    _ => None,
+  };
+  if let Some(inverse_function) = inverse_function {
+    if args.len() == 1 {
+      let arg = args.pop().unwrap();
+      return Ok((arg, Expr::call(inverse_function, vec![value])));
+    }
+  }
+
+  let target = args[index].clone();
+  let new_value = match (name.as_str(), args.len(), index) {
+    ("sqrt", 1, _) => Expr::call("^", vec![value, Expr::from(2)]),
+    ("+", _, _) => {
+      args.remove(index);
+      Expr::call("-", vec![value, sum_or_product("+", args)])
+    }
+    ("*", _, _) => {
+      args.remove(index);
+      Expr::call("/", vec![value, sum_or_product("*", args)])
+    }
+    ("-", 2, 0) => Expr::call("+", vec![value, args.pop().unwrap()]),
+    ("-", 2, _) => Expr::call("-", vec![args.swap_remove(0), value]),
+    ("/", 2, 0) => Expr::call("*", vec![value, args.pop().unwrap()]),
+    ("/", 2, _) => Expr::call("/", vec![args.swap_remove(0), value]),
+    ("^", 2, 0) => {
+      let exponent = args.pop().unwrap();
+      Expr::call("^", vec![value, Expr::call("/", vec![Expr::from(1), exponent])])
+    }
+    ("^", 2, _) => Expr::call("log", vec![value, args.swap_remove(0)]),
+    ("log", 2, 0) => Expr::call("^", vec![args.pop().unwrap(), value]),
+    ("log", 2, _) => Expr::call("^", vec![args.swap_remove(0), Expr::call("/", vec![Expr::from(1), value])]),
+    _ => return Err(IsolateError::NotInvertible(Expr::Call(name, args))),
+  };
+  Ok((target, new_value))
+}
+
+fn sum_or_product(name: &str, mut args: Vec<Expr>) -> Expr {
+  if args.len() == 1 {
+    args.pop().unwrap()
+  } else {
+    Expr::call(name, args)
+  }
+}
+
+fn count_occurrences(expr: &Expr, var: &Var) -> usize {
+  match expr {
+    Expr::Atom(Atom::Var(v)) => usize::from(v == var),
+    Expr::Atom(_) => 0,
+    Expr::Call(_, args) => args.iter().map(|arg| count_occurrences(arg, var)).sum(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> Expr {
+    Expr::var(name).unwrap()
+  }
+
+  fn isolate_x(left: Expr, right: Expr) -> Result<Expr, IsolateError> {
+    let x = Var::new("x").unwrap();
+    isolate(Equation::new(left, right), &x).map(|eq| {
+      assert_eq!(eq.left, var("x"));
+      eq.right
+    })
+  }
+
+  #[test]
+  fn test_isolate_trivial() {
+    assert_eq!(isolate_x(var("x"), var("y")).unwrap(), var("y"));
+    assert_eq!(isolate_x(var("y"), var("x")).unwrap(), var("y"));
+  }
+
+  #[test]
+  fn test_isolate_linear() {
+    // 2 * x + 3 = y
+    let left = Expr::call("+", vec![
+      Expr::call("*", vec![Expr::from(2), var("x")]),
+      Expr::from(3),
+    ]);
+    assert_eq!(
+      isolate_x(left, var("y")).unwrap(),
+      Expr::call("/", vec![
+        Expr::call("-", vec![var("y"), Expr::from(3)]),
+        Expr::from(2),
+      ]),
+    );
+  }
+
+  #[test]
+  fn test_isolate_variadic() {
+    // a + x + b = y
+    let left = Expr::call("+", vec![var("a"), var("x"), var("b")]);
+    assert_eq!(
+      isolate_x(left, var("y")).unwrap(),
+      Expr::call("-", vec![var("y"), Expr::call("+", vec![var("a"), var("b")])]),
+    );
+  }
+
+  #[test]
+  fn test_isolate_subtraction_and_division() {
+    // a - x = y
+    let left = Expr::call("-", vec![var("a"), var("x")]);
+    assert_eq!(isolate_x(left, var("y")).unwrap(), Expr::call("-", vec![var("a"), var("y")]));
+    // x - a = y
+    let left = Expr::call("-", vec![var("x"), var("a")]);
+    assert_eq!(isolate_x(left, var("y")).unwrap(), Expr::call("+", vec![var("y"), var("a")]));
+    // a / x = y
+    let left = Expr::call("/", vec![var("a"), var("x")]);
+    assert_eq!(isolate_x(left, var("y")).unwrap(), Expr::call("/", vec![var("a"), var("y")]));
+  }
+
+  #[test]
+  fn test_isolate_powers_and_logs() {
+    // x^2 = y
+    let left = Expr::call("^", vec![var("x"), Expr::from(2)]);
+    assert_eq!(
+      isolate_x(left, var("y")).unwrap(),
+      Expr::call("^", vec![var("y"), Expr::call("/", vec![Expr::from(1), Expr::from(2)])]),
+    );
+    // 2^x = y
+    let left = Expr::call("^", vec![Expr::from(2), var("x")]);
+    assert_eq!(isolate_x(left, var("y")).unwrap(), Expr::call("log", vec![var("y"), Expr::from(2)]));
+    // ln(exp(x)) = y
+    let left = Expr::call("ln", vec![Expr::call("exp", vec![var("x")])]);
+    assert_eq!(
+      isolate_x(left, var("y")).unwrap(),
+      Expr::call("ln", vec![Expr::call("exp", vec![var("y")])]),
+    );
+  }
+
+  #[test]
+  fn test_isolate_trig() {
+    // y = sin(x) + 1
+    let right = Expr::call("+", vec![Expr::call("sin", vec![var("x")]), Expr::from(1)]);
+    assert_eq!(
+      isolate_x(var("y"), right).unwrap(),
+      Expr::call("asin", vec![Expr::call("-", vec![var("y"), Expr::from(1)])]),
+    );
+  }
+
+  #[test]
+  fn test_isolate_errors() {
+    assert!(matches!(isolate_x(var("y"), var("z")), Err(IsolateError::VarNotFound(_))));
+    assert!(matches!(
+      isolate_x(var("x"), Expr::call("+", vec![var("x"), Expr::from(1)])),
+      Err(IsolateError::MultipleOccurrences { .. }),
+    ));
+    assert!(matches!(
+      isolate_x(Expr::call("abs", vec![var("x")]), Expr::from(1)),
+      Err(IsolateError::NotInvertible(_)),
+    ));
+  }
+}
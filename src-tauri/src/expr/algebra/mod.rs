@@ -1,11 +1,20 @@
 
 //! Helpers for manipulating expressions algebraically.
 
+pub mod descriptive;
+pub mod domain;
 pub mod factor;
 pub mod formula;
+pub mod inequality;
 pub mod infinity;
+pub mod isolate;
+pub mod optimization;
 pub mod root_finding;
+pub mod sequence;
 pub mod polynomial;
+pub mod quadrature;
+pub mod regression;
+pub mod summation;
 pub mod term;
 
 use crate::errorlist::ErrorList;
@@ -36,6 +45,14 @@ pub struct ExprFunction2<'a> {
   simplifier: &'a dyn Simplifier,
 }
 
+/// An expression, being treated as a function of any fixed number of
+/// arguments, subject to a particular simplifier.
+pub struct ExprFunctionN<'a> {
+  expr: Expr,
+  vars: Vec<Var>,
+  simplifier: &'a dyn Simplifier,
+}
+
 /// An error during function evaluation.
 #[derive(Debug, Clone, Error)]
 #[error("Failed to evaluate function, expecting {expected}, got {evaluated_value}")]
@@ -153,3 +170,71 @@ impl<'a> ExprFunction2<'a> {
     self.eval_at(first_value, second_value, "real number", &expr_to_number())
   }
 }
+
+impl<'a> ExprFunctionN<'a> {
+  /// Create a new expression function.
+  pub fn new(expr: Expr, vars: Vec<Var>, simplifier: &'a dyn Simplifier) -> ExprFunctionN<'a> {
+    ExprFunctionN {
+      expr,
+      vars,
+      simplifier,
+    }
+  }
+
+  pub fn arity(&self) -> usize {
+    self.vars.len()
+  }
+
+  /// Whether the computation using this function has been cancelled.
+  /// See [`Simplifier::is_cancelled`].
+  pub fn is_cancelled(&self) -> bool {
+    self.simplifier.is_cancelled()
+  }
+
+  fn simplify_expr(&self, expr: Expr) -> Expr {
+    // Note: When we simplify expressions for the purpose of
+    // ExprFunctionN, we ignore any errors that arise from the
+    // simplifier.
+    let mut errors = ErrorList::new();
+    let mut context = SimplifierContext {
+      base_simplifier: self.simplifier,
+      calculation_mode: CalculationMode::for_algebra(),
+      errors: &mut errors,
+    };
+    self.simplifier.simplify_expr(expr, &mut context)
+  }
+
+  /// Evaluates the function at the given position, expecting a value
+  /// compatible with the given prism.
+  ///
+  /// Panics if the number of values does not match the arity of the
+  /// function.
+  pub fn eval_at<E, P, Down>(&self, values: Vec<E>, expected: &'static str, prism: &P) -> Result<Down, FunctionEvalError>
+  where P: Prism<Expr, Down>,
+        E: Into<Expr> {
+    assert_eq!(values.len(), self.vars.len(), "Wrong number of arguments to ExprFunctionN");
+    let evaluated_value = self.vars.iter().zip(values).fold(self.expr.clone(), |expr, (var, value)| {
+      expr.substitute_var(var.clone(), value.into())
+    });
+    let evaluated_value = self.simplify_expr(evaluated_value);
+    prism.narrow_type(evaluated_value)
+      .map_err(|evaluated_value| FunctionEvalError {
+        evaluated_value,
+        function: self.expr.clone(),
+        expected,
+        _priv: (),
+      })
+  }
+
+  /// Evaluates the function at the given position, expecting a real
+  /// numerical result.
+  pub fn eval_at_real(&self, values: Vec<Number>) -> Result<Number, FunctionEvalError> {
+    self.eval_at(values, "real number", &expr_to_number())
+  }
+
+  /// Evaluates the function at the given position, expecting a
+  /// numerical result.
+  pub fn eval_at_complex(&self, values: Vec<ComplexLike>) -> Result<ComplexLike, FunctionEvalError> {
+    self.eval_at(values, "numerical literal", &ExprToComplex)
+  }
+}
@@ -0,0 +1,317 @@
+
+//! Global minimization of real-valued functions over a box, by
+//! branch-and-bound on interval evaluations of the function.
+//!
+//! Evaluating the function over an interval (via the simplifier's
+//! interval arithmetic) gives a lower bound for the function on that
+//! region, while evaluating it at any single point gives an upper
+//! bound on the global minimum. Regions whose lower bound exceeds the
+//! best known upper bound can never contain the minimum, so they are
+//! discarded; the rest are bisected until the two bounds meet.
+//!
+//! Bounds are rigorous up to floating-point rounding in the interval
+//! arithmetic itself.
+
+use crate::expr::Expr;
+use crate::expr::algebra::{ExprFunctionN, FunctionEvalError};
+use crate::expr::algebra::infinity::{UnboundedNumber, SignedInfinity};
+use crate::expr::interval::{Interval, IntervalType};
+use crate::expr::number::Number;
+use crate::expr::prisms::expr_to_unbounded_interval_like;
+use crate::expr::simplifier::Simplifier;
+use crate::expr::simplifier::cancellable::CancelledError;
+use crate::expr::var::Var;
+
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct BranchAndBound {
+  tolerance: f64,
+  max_iterations: usize,
+}
+
+/// A function of several variables which can be evaluated both at
+/// points and over boxes.
+pub struct IntervalFunction<'a> {
+  function: ExprFunctionN<'a>,
+}
+
+/// A closed box in `n`-dimensional space, given as a lower and upper
+/// bound for each coordinate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchBox {
+  bounds: Vec<(f64, f64)>,
+}
+
+/// Bounds on the global minimum of a function, as computed by
+/// [`BranchAndBound::find_minimum`]. The true minimum lies between
+/// `lower` and `upper`, and `upper` is attained at `minimizer`.
+#[derive(Debug, Clone)]
+pub struct MinimumBounds {
+  pub lower: f64,
+  pub upper: f64,
+  pub minimizer: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum GlobalMinError {
+  #[error("{0}")]
+  FunctionEvalError(#[from] FunctionEvalError),
+  #[error("Expected {expected} bounds for search box, got {actual}")]
+  DimensionMismatch { expected: usize, actual: usize },
+  #[error("Search box must be finite and nonempty")]
+  InvalidBox,
+  #[error("Function is undefined at every point sampled")]
+  NoFeasiblePoint,
+  #[error("{0}")]
+  Cancelled(#[from] CancelledError),
+}
+
+impl BranchAndBound {
+  pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+  pub const DEFAULT_MAX_ITERATIONS: usize = 500;
+
+  pub fn new(tolerance: f64, max_iterations: usize) -> Self {
+    Self { tolerance, max_iterations }
+  }
+
+  /// Bounds the minimum of `function` over `search_box`. If the
+  /// iteration limit is reached before the bounds are within
+  /// tolerance of one another, the (still valid) looser bounds are
+  /// returned. Fails with [`GlobalMinError::Cancelled`] if the
+  /// function's computation is cancelled partway through.
+  pub fn find_minimum(
+    &self,
+    function: &IntervalFunction,
+    search_box: SearchBox,
+  ) -> Result<MinimumBounds, GlobalMinError> {
+    if search_box.dimension() != function.arity() {
+      return Err(GlobalMinError::DimensionMismatch {
+        expected: function.arity(),
+        actual: search_box.dimension(),
+      });
+    }
+    if !search_box.is_valid() {
+      return Err(GlobalMinError::InvalidBox);
+    }
+
+    let mut upper = f64::INFINITY;
+    let mut minimizer = search_box.midpoint();
+    let mut update_upper_bound = |point: Vec<f64>, upper: &mut f64| {
+      if let Some(value) = function.eval_at_point(&point) {
+        if value < *upper {
+          *upper = value;
+          minimizer = point;
+        }
+      }
+    };
+
+    update_upper_bound(search_box.midpoint(), &mut upper);
+    let lower = function.lower_bound_over(&search_box)?;
+    let mut candidates = vec![(lower, search_box)];
+
+    for _ in 0..self.max_iterations {
+      if function.is_cancelled() {
+        return Err(GlobalMinError::Cancelled(CancelledError));
+      }
+      candidates.retain(|(lower, _)| *lower <= upper);
+      let Some(index) = index_of_least_lower_bound(&candidates) else {
+        break;
+      };
+      if upper - candidates[index].0 <= self.tolerance {
+        break;
+      }
+      let (parent_lower, parent_box) = candidates.swap_remove(index);
+      for child_box in parent_box.bisect() {
+        update_upper_bound(child_box.midpoint(), &mut upper);
+        // Interval evaluation over a subset is never worse in truth,
+        // but may be numerically worse, so keep the tighter bound.
+        let child_lower = function.lower_bound_over(&child_box)?.max(parent_lower);
+        candidates.push((child_lower, child_box));
+      }
+    }
+
+    if upper == f64::INFINITY {
+      return Err(GlobalMinError::NoFeasiblePoint);
+    }
+    let lower = index_of_least_lower_bound(&candidates)
+      .map_or(upper, |index| candidates[index].0.min(upper));
+    Ok(MinimumBounds { lower, upper, minimizer })
+  }
+}
+
+fn index_of_least_lower_bound(candidates: &[(f64, SearchBox)]) -> Option<usize> {
+  candidates.iter()
+    .enumerate()
+    .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+    .map(|(index, _)| index)
+}
+
+impl<'a> IntervalFunction<'a> {
+  pub fn new(function: ExprFunctionN<'a>) -> Self {
+    Self { function }
+  }
+
+  pub fn from_expr(
+    expr: Expr,
+    vars: Vec<Var>,
+    simplifier: &'a dyn Simplifier,
+  ) -> Self {
+    Self::new(ExprFunctionN::new(expr, vars, simplifier))
+  }
+
+  pub fn arity(&self) -> usize {
+    self.function.arity()
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.function.is_cancelled()
+  }
+
+  /// Evaluates the function at a point, returning `None` if the
+  /// function is undefined (or non-real) there.
+  pub fn eval_at_point(&self, point: &[f64]) -> Option<f64> {
+    let values = point.iter().map(|x| Number::from(*x)).collect();
+    self.function.eval_at_real(values).ok()
+      .map(|y| y.to_f64_or_nan())
+      .filter(|y| !y.is_nan())
+  }
+
+  /// Computes a lower bound for the function over the box, using
+  /// interval arithmetic.
+  pub fn lower_bound_over(&self, search_box: &SearchBox) -> Result<f64, FunctionEvalError> {
+    let values = search_box.bounds.iter()
+      .map(|(lo, hi)| Expr::from(Interval::new(Number::from(*lo), IntervalType::Closed, Number::from(*hi))))
+      .collect();
+    let range = self.function.eval_at(values, "real interval", &expr_to_unbounded_interval_like())?;
+    let (lower, _) = Interval::from(range).into_extremes();
+    Ok(match lower {
+      UnboundedNumber::Finite(n) => n.to_f64().unwrap_or(f64::NEG_INFINITY),
+      UnboundedNumber::Infinite(SignedInfinity::PosInfinity) => f64::INFINITY,
+      UnboundedNumber::Infinite(SignedInfinity::NegInfinity) => f64::NEG_INFINITY,
+    })
+  }
+}
+
+impl SearchBox {
+  /// Constructs a box from per-coordinate bounds. Bounds given in
+  /// the wrong order are swapped.
+  pub fn new(bounds: Vec<(f64, f64)>) -> Self {
+    let bounds = bounds.into_iter()
+      .map(|(a, b)| if a <= b { (a, b) } else { (b, a) })
+      .collect();
+    Self { bounds }
+  }
+
+  pub fn dimension(&self) -> usize {
+    self.bounds.len()
+  }
+
+  fn is_valid(&self) -> bool {
+    !self.bounds.is_empty() && self.bounds.iter().all(|(lo, hi)| lo.is_finite() && hi.is_finite())
+  }
+
+  fn midpoint(&self) -> Vec<f64> {
+    self.bounds.iter().map(|(lo, hi)| lo + (hi - lo) / 2.0).collect()
+  }
+
+  /// Splits the box in half along its widest coordinate.
+  fn bisect(self) -> [SearchBox; 2] {
+    let (widest, _) = self.bounds.iter()
+      .enumerate()
+      .max_by(|(_, a), (_, b)| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+      .expect("SearchBox should be nonempty");
+    let (lo, hi) = self.bounds[widest];
+    let mid = lo + (hi - lo) / 2.0;
+    let mut left = self.clone();
+    let mut right = self;
+    left.bounds[widest].1 = mid;
+    right.bounds[widest].0 = mid;
+    [left, right]
+  }
+}
+
+impl Default for BranchAndBound {
+  fn default() -> Self {
+    Self::new(
+      Self::DEFAULT_TOLERANCE,
+      Self::DEFAULT_MAX_ITERATIONS,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::function::table::FunctionTable;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::default_simplifier;
+
+  use once_cell::sync::Lazy;
+
+  static FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(build_function_table);
+
+  fn var(name: &str) -> Var {
+    Var::new(name).unwrap()
+  }
+
+  #[test]
+  fn test_midpoint() {
+    let search_box = SearchBox::new(vec![(0.0, 2.0), (5.0, -1.0)]);
+    assert_eq!(search_box.midpoint(), vec![1.0, 2.0]);
+  }
+
+  #[test]
+  fn test_bisect_along_widest_side() {
+    let search_box = SearchBox::new(vec![(0.0, 1.0), (0.0, 4.0)]);
+    let [left, right] = search_box.bisect();
+    assert_eq!(left, SearchBox::new(vec![(0.0, 1.0), (0.0, 2.0)]));
+    assert_eq!(right, SearchBox::new(vec![(0.0, 1.0), (2.0, 4.0)]));
+  }
+
+  #[test]
+  fn test_validity() {
+    assert!(SearchBox::new(vec![(0.0, 1.0)]).is_valid());
+    assert!(!SearchBox::new(vec![]).is_valid());
+    assert!(!SearchBox::new(vec![(0.0, f64::INFINITY)]).is_valid());
+  }
+
+  #[test]
+  fn test_minimum_of_quadratic() {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    // x^2 - 2x, minimized at x = 1 with value -1.
+    let expr = Expr::call("-", vec![
+      Expr::call("^", vec![Expr::var("x").unwrap(), Expr::from(2)]),
+      Expr::call("*", vec![Expr::from(2), Expr::var("x").unwrap()]),
+    ]);
+    let function = IntervalFunction::from_expr(expr, vec![var("x")], simplifier.as_ref());
+    let bounds = BranchAndBound::default().find_minimum(&function, SearchBox::new(vec![(-3.0, 3.0)])).unwrap();
+    assert!(bounds.lower <= -1.0 && -1.0 <= bounds.upper);
+    assert!(bounds.upper - bounds.lower < 1e-2);
+    assert!((bounds.minimizer[0] - 1.0).abs() < 1e-2);
+  }
+
+  #[test]
+  fn test_minimum_in_two_dimensions() {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    // (x - 1)^2 + (y + 2)^2 + 3
+    let expr = Expr::call("+", vec![
+      Expr::call("^", vec![Expr::call("-", vec![Expr::var("x").unwrap(), Expr::from(1)]), Expr::from(2)]),
+      Expr::call("^", vec![Expr::call("+", vec![Expr::var("y").unwrap(), Expr::from(2)]), Expr::from(2)]),
+      Expr::from(3),
+    ]);
+    let function = IntervalFunction::from_expr(expr, vec![var("x"), var("y")], simplifier.as_ref());
+    let bounds = BranchAndBound::default().find_minimum(&function, SearchBox::new(vec![(-5.0, 5.0), (-5.0, 5.0)])).unwrap();
+    assert!(bounds.lower <= 3.0 && 3.0 <= bounds.upper);
+    assert!(bounds.upper - bounds.lower < 1e-2);
+  }
+
+  #[test]
+  fn test_dimension_mismatch() {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    let function = IntervalFunction::from_expr(Expr::var("x").unwrap(), vec![var("x")], simplifier.as_ref());
+    let result = BranchAndBound::default().find_minimum(&function, SearchBox::new(vec![(0.0, 1.0), (0.0, 1.0)]));
+    assert!(matches!(result, Err(GlobalMinError::DimensionMismatch { expected: 1, actual: 2 })));
+  }
+}
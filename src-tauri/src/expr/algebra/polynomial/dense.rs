@@ -0,0 +1,555 @@
+
+//! Dense univariate polynomials with coefficients drawn from an
+//! arbitrary [`CoefficientRing`].
+//!
+//! Unlike the symbolic [`Polynomial`](super::Polynomial), a
+//! [`DensePolynomial`] stores one coefficient for every power of a
+//! single variable, which makes it suitable for exact arithmetic
+//! such as division with remainder and GCDs.
+
+use super::ring::{CoefficientRing, IntegersMod};
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::var::Var;
+use crate::expr::prisms::expr_to_usize;
+use crate::util::prism::Prism;
+
+use thiserror::Error;
+
+/// A polynomial in one variable over the ring `R`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensePolynomial<R: CoefficientRing> {
+  ring: R,
+  /// Coefficients, in increasing order of degree. Never has trailing
+  /// zeroes.
+  coefficients: Vec<R::Elem>,
+}
+
+/// A factorization of a polynomial into a unit (the leading
+/// coefficient, as a constant polynomial) times powers of monic
+/// irreducible polynomials.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factorization<R: CoefficientRing> {
+  pub unit: DensePolynomial<R>,
+  pub factors: Vec<(DensePolynomial<R>, usize)>,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum PolynomialError {
+  #[error("Expression is not a polynomial in {0}")]
+  NotAPolynomial(Var),
+  #[error("Coefficient {0} is not an element of the coefficient ring")]
+  InvalidCoefficient(Number),
+  #[error("Division by zero polynomial")]
+  DivisionByZero,
+  #[error("Leading coefficient is not invertible in the coefficient ring")]
+  NonInvertibleLeadingCoefficient,
+  #[error("Factorization requires a prime modulus")]
+  NotAField,
+  #[error("Polynomial is too large to factor")]
+  TooLargeToFactor,
+}
+
+impl<R: CoefficientRing> DensePolynomial<R> {
+  /// Constructs a polynomial from its coefficients, in increasing
+  /// order of degree.
+  pub fn new(ring: R, coefficients: Vec<R::Elem>) -> Self {
+    let mut poly = Self { ring, coefficients };
+    poly.trim();
+    poly
+  }
+
+  pub fn zero(ring: R) -> Self {
+    Self::new(ring, Vec::new())
+  }
+
+  pub fn constant(ring: R, value: R::Elem) -> Self {
+    Self::new(ring, vec![value])
+  }
+
+  /// The polynomial `x`.
+  pub fn identity(ring: R) -> Self {
+    let coefficients = vec![ring.zero(), ring.one()];
+    Self::new(ring, coefficients)
+  }
+
+  pub fn ring(&self) -> &R {
+    &self.ring
+  }
+
+  pub fn coefficients(&self) -> &[R::Elem] {
+    &self.coefficients
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.coefficients.is_empty()
+  }
+
+  pub fn is_one(&self) -> bool {
+    self.coefficients.len() == 1 && self.coefficients[0] == self.ring.one()
+  }
+
+  /// The degree of the polynomial, or `None` for the zero
+  /// polynomial.
+  pub fn degree(&self) -> Option<usize> {
+    self.coefficients.len().checked_sub(1)
+  }
+
+  pub fn leading_coefficient(&self) -> Option<&R::Elem> {
+    self.coefficients.last()
+  }
+
+  fn trim(&mut self) {
+    while self.coefficients.last().is_some_and(|c| self.ring.is_zero(c)) {
+      self.coefficients.pop();
+    }
+  }
+
+  fn coefficient(&self, degree: usize) -> R::Elem {
+    self.coefficients.get(degree).cloned().unwrap_or_else(|| self.ring.zero())
+  }
+
+  pub fn add(&self, other: &Self) -> Self {
+    let len = self.coefficients.len().max(other.coefficients.len());
+    let coefficients = (0..len)
+      .map(|i| self.ring.add(&self.coefficient(i), &other.coefficient(i)))
+      .collect();
+    Self::new(self.ring.clone(), coefficients)
+  }
+
+  pub fn neg(&self) -> Self {
+    let coefficients = self.coefficients.iter().map(|c| self.ring.neg(c)).collect();
+    Self::new(self.ring.clone(), coefficients)
+  }
+
+  pub fn sub(&self, other: &Self) -> Self {
+    self.add(&other.neg())
+  }
+
+  /// Multiplies every coefficient by `scalar`.
+  pub fn scale(&self, scalar: &R::Elem) -> Self {
+    let coefficients = self.coefficients.iter().map(|c| self.ring.mul(c, scalar)).collect();
+    Self::new(self.ring.clone(), coefficients)
+  }
+
+  pub fn mul(&self, other: &Self) -> Self {
+    if self.is_zero() || other.is_zero() {
+      return Self::zero(self.ring.clone());
+    }
+    let mut coefficients = vec![self.ring.zero(); self.coefficients.len() + other.coefficients.len() - 1];
+    for (i, a) in self.coefficients.iter().enumerate() {
+      for (j, b) in other.coefficients.iter().enumerate() {
+        coefficients[i + j] = self.ring.add(&coefficients[i + j], &self.ring.mul(a, b));
+      }
+    }
+    Self::new(self.ring.clone(), coefficients)
+  }
+
+  pub fn pow(&self, exponent: usize) -> Self {
+    let mut result = Self::constant(self.ring.clone(), self.ring.one());
+    for _ in 0..exponent {
+      result = result.mul(self);
+    }
+    result
+  }
+
+  /// Evaluates the polynomial at `x`, using Horner's method.
+  pub fn eval(&self, x: &R::Elem) -> R::Elem {
+    self.coefficients.iter()
+      .rev()
+      .fold(self.ring.zero(), |acc, c| self.ring.add(&self.ring.mul(&acc, x), c))
+  }
+
+  /// The formal derivative of the polynomial.
+  pub fn derivative(&self) -> Self {
+    let mut multiplier = self.ring.zero();
+    let coefficients = self.coefficients.iter()
+      .skip(1)
+      .map(|c| {
+        multiplier = self.ring.add(&multiplier, &self.ring.one());
+        self.ring.mul(c, &multiplier)
+      })
+      .collect();
+    Self::new(self.ring.clone(), coefficients)
+  }
+
+  /// Polynomial long division, returning the quotient and remainder.
+  /// Requires the leading coefficient of the divisor to be a unit of
+  /// the ring.
+  pub fn div_rem(&self, divisor: &Self) -> Result<(Self, Self), PolynomialError> {
+    let Some(divisor_degree) = divisor.degree() else {
+      return Err(PolynomialError::DivisionByZero);
+    };
+    let leading_inverse = divisor.leading_inverse()?;
+    let mut remainder = self.clone();
+    let mut quotient = vec![self.ring.zero(); self.coefficients.len().saturating_sub(divisor_degree)];
+    while let Some(remainder_degree) = remainder.degree().filter(|d| *d >= divisor_degree) {
+      let shift = remainder_degree - divisor_degree;
+      let factor = self.ring.mul(&remainder.coefficients[remainder_degree], &leading_inverse);
+      for (i, c) in divisor.coefficients.iter().enumerate() {
+        let term = self.ring.mul(c, &factor);
+        remainder.coefficients[i + shift] = self.ring.sub(&remainder.coefficients[i + shift], &term);
+      }
+      // The leading term cancels exactly, even if the ring's
+      // arithmetic is inexact.
+      remainder.coefficients[remainder_degree] = self.ring.zero();
+      remainder.trim();
+      quotient[shift] = factor;
+    }
+    Ok((Self::new(self.ring.clone(), quotient), remainder))
+  }
+
+  /// Divides through by the leading coefficient. The zero polynomial
+  /// is returned unchanged.
+  pub fn monic(&self) -> Result<Self, PolynomialError> {
+    if self.is_zero() {
+      return Ok(self.clone());
+    }
+    Ok(self.scale(&self.leading_inverse()?))
+  }
+
+  /// The monic greatest common divisor of two polynomials, computed
+  /// by the Euclidean algorithm. The GCD of two zero polynomials is
+  /// zero.
+  pub fn gcd(&self, other: &Self) -> Result<Self, PolynomialError> {
+    let mut a = self.clone();
+    let mut b = other.clone();
+    while !b.is_zero() {
+      let (_, r) = a.div_rem(&b)?;
+      a = b;
+      b = r;
+    }
+    a.monic()
+  }
+
+  fn leading_inverse(&self) -> Result<R::Elem, PolynomialError> {
+    let leading = self.leading_coefficient().ok_or(PolynomialError::DivisionByZero)?;
+    self.ring.inverse(leading).ok_or(PolynomialError::NonInvertibleLeadingCoefficient)
+  }
+
+  /// Interprets an expression as a polynomial in `var`. The
+  /// expression may use `+`, `-`, `*`, `negate`, division by
+  /// constants, and non-negative integer powers.
+  pub fn from_expr(ring: R, expr: &Expr, var: &Var) -> Result<Self, PolynomialError> {
+    let not_a_polynomial = || PolynomialError::NotAPolynomial(var.clone());
+    match expr {
+      Expr::Atom(Atom::Number(n)) => {
+        let value = ring.embed_number(n.clone()).ok_or_else(|| PolynomialError::InvalidCoefficient(n.clone()))?;
+        Ok(Self::constant(ring, value))
+      }
+      Expr::Atom(Atom::Var(v)) if v == var => {
+        Ok(Self::identity(ring))
+      }
+      Expr::Atom(_) => {
+        Err(not_a_polynomial())
+      }
+      Expr::Call(name, args) if name == "^" && args.len() == 2 => {
+        let exponent = expr_to_usize().narrow_type(args[1].clone())
+          .map_err(|_| not_a_polynomial())?;
+        Ok(Self::from_expr(ring, &args[0], var)?.pow(exponent))
+      }
+      Expr::Call(name, args) => {
+        let mut args = args.iter().map(|arg| Self::from_expr(ring.clone(), arg, var));
+        match (name.as_str(), args.len()) {
+          ("+", _) => args.try_fold(Self::zero(ring.clone()), |acc, arg| Ok(acc.add(&arg?))),
+          ("*", _) => args.try_fold(Self::constant(ring.clone(), ring.one()), |acc, arg| Ok(acc.mul(&arg?))),
+          ("-", 2) => Ok(args.next().unwrap()?.sub(&args.next().unwrap()?)),
+          ("negate", 1) => Ok(args.next().unwrap()?.neg()),
+          ("/", 2) => {
+            let numerator = args.next().unwrap()?;
+            let denominator = args.next().unwrap()?;
+            if denominator.degree().is_some_and(|d| d > 0) {
+              return Err(not_a_polynomial());
+            }
+            Ok(numerator.scale(&denominator.leading_inverse()?))
+          }
+          _ => Err(not_a_polynomial()),
+        }
+      }
+    }
+  }
+
+  /// Converts the polynomial to an expression in `var`, with terms
+  /// in decreasing order of degree.
+  pub fn to_expr(&self, var: &Var) -> Expr {
+    let mut terms: Vec<Expr> = self.coefficients.iter()
+      .enumerate()
+      .rev()
+      .filter(|(_, c)| !self.ring.is_zero(c))
+      .map(|(degree, c)| {
+        let power = match degree {
+          0 => return self.ring.to_expr(c.clone()),
+          1 => Expr::from(var.clone()),
+          _ => Expr::call("^", vec![Expr::from(var.clone()), Expr::from(Number::from(degree))]),
+        };
+        if c == &self.ring.one() {
+          power
+        } else {
+          Expr::call("*", vec![self.ring.to_expr(c.clone()), power])
+        }
+      })
+      .collect();
+    match terms.len() {
+      0 => Expr::zero(),
+      1 => terms.pop().unwrap(),
+      _ => Expr::call("+", terms),
+    }
+  }
+}
+
+impl DensePolynomial<IntegersMod> {
+  /// Upper bound on the number of trial divisors that
+  /// [`DensePolynomial::factor`] will attempt.
+  pub const MAX_FACTOR_CANDIDATES: u64 = 100_000;
+
+  /// Factors the polynomial into monic irreducibles over the field
+  /// of integers mod a prime. Irreducible factors are found by trial
+  /// division, trying every monic polynomial in increasing order of
+  /// degree, so this is only practical for small fields and
+  /// low-degree polynomials.
+  pub fn factor(&self) -> Result<Factorization<IntegersMod>, PolynomialError> {
+    if !self.ring.is_field() {
+      return Err(PolynomialError::NotAField);
+    }
+    let Some(unit) = self.leading_coefficient().cloned() else {
+      return Ok(Factorization { unit: self.clone(), factors: Vec::new() });
+    };
+    let modulus = self.ring.modulus();
+    let mut remaining = self.monic()?;
+    let mut factors = Vec::new();
+    let mut candidates_tried = 0u64;
+    let mut degree = 1;
+    while remaining.degree().is_some_and(|d| d >= 2 * degree) {
+      let count = u32::try_from(degree).ok()
+        .and_then(|d| modulus.checked_pow(d))
+        .filter(|count| candidates_tried + count <= Self::MAX_FACTOR_CANDIDATES)
+        .ok_or(PolynomialError::TooLargeToFactor)?;
+      candidates_tried += count;
+      for index in 0..count {
+        let candidate = monic_polynomial_from_index(self.ring, degree, index);
+        let mut multiplicity = 0;
+        loop {
+          let (quotient, remainder) = remaining.div_rem(&candidate)?;
+          if !remainder.is_zero() {
+            break;
+          }
+          remaining = quotient;
+          multiplicity += 1;
+        }
+        if multiplicity > 0 {
+          factors.push((candidate, multiplicity));
+        }
+      }
+      degree += 1;
+    }
+    // Anything left over has no factor of degree at most half its
+    // own, so it is irreducible.
+    if remaining.degree().is_some_and(|d| d > 0) {
+      factors.push((remaining, 1));
+    }
+    factors.sort_by_key(|(f, _)| f.degree());
+    Ok(Factorization { unit: Self::constant(self.ring, unit), factors })
+  }
+}
+
+impl<R: CoefficientRing> Factorization<R> {
+  /// Converts the factorization to a product expression in `var`.
+  pub fn to_expr(&self, var: &Var) -> Expr {
+    let mut product = Vec::new();
+    if self.factors.is_empty() || !self.unit.is_one() {
+      product.push(self.unit.to_expr(var));
+    }
+    for (factor, multiplicity) in &self.factors {
+      let factor = factor.to_expr(var);
+      product.push(match multiplicity {
+        1 => factor,
+        n => Expr::call("^", vec![factor, Expr::from(Number::from(*n))]),
+      });
+    }
+    match product.len() {
+      1 => product.pop().unwrap(),
+      _ => Expr::call("*", product),
+    }
+  }
+}
+
+/// The monic polynomial of the given degree whose lower coefficients
+/// are the base-`p` digits of `index`.
+fn monic_polynomial_from_index(ring: IntegersMod, degree: usize, mut index: u64) -> DensePolynomial<IntegersMod> {
+  let modulus = ring.modulus();
+  let mut coefficients = Vec::with_capacity(degree + 1);
+  for _ in 0..degree {
+    coefficients.push(index % modulus);
+    index /= modulus;
+  }
+  coefficients.push(1);
+  DensePolynomial::new(ring, coefficients)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::super::ring::RationalField;
+
+  fn x() -> Var {
+    Var::new("x").unwrap()
+  }
+
+  fn mod_poly(modulus: u64, coefficients: Vec<u64>) -> DensePolynomial<IntegersMod> {
+    DensePolynomial::new(IntegersMod::new(modulus), coefficients)
+  }
+
+  fn rational_poly(coefficients: Vec<Number>) -> DensePolynomial<RationalField> {
+    DensePolynomial::new(RationalField, coefficients)
+  }
+
+  #[test]
+  fn test_trims_trailing_zeroes() {
+    let p = mod_poly(5, vec![1, 2, 0, 0]);
+    assert_eq!(p.coefficients(), &[1, 2]);
+    assert_eq!(p.degree(), Some(1));
+    assert_eq!(mod_poly(5, vec![0, 0]).degree(), None);
+  }
+
+  #[test]
+  fn test_mul_mod_n() {
+    // (x + 1)^2 = x^2 + 1 mod 2
+    let p = mod_poly(2, vec![1, 1]);
+    assert_eq!(p.mul(&p), mod_poly(2, vec![1, 0, 1]));
+    // (2x + 3)(3x + 2) = 6x^2 + 13x + 6 = x mod 6
+    let p = mod_poly(6, vec![3, 2]);
+    let q = mod_poly(6, vec![2, 3]);
+    assert_eq!(p.mul(&q), mod_poly(6, vec![0, 1]));
+  }
+
+  #[test]
+  fn test_div_rem_mod_p() {
+    // x^3 + 2x + 1 = (x + 1)(x^2 + 4x + 3) + 3 mod 5
+    let p = mod_poly(5, vec![1, 2, 0, 1]);
+    let d = mod_poly(5, vec![1, 1]);
+    let (q, r) = p.div_rem(&d).unwrap();
+    assert_eq!(q, mod_poly(5, vec![3, 4, 1]));
+    assert_eq!(r, mod_poly(5, vec![3]));
+    assert_eq!(q.mul(&d).add(&r), p);
+  }
+
+  #[test]
+  fn test_div_rem_over_rationals() {
+    // x^2 + 1 = (2x) (x/2) + 1
+    let p = rational_poly(vec![Number::from(1), Number::from(0), Number::from(1)]);
+    let d = rational_poly(vec![Number::from(0), Number::from(2)]);
+    let (q, r) = p.div_rem(&d).unwrap();
+    assert_eq!(q, rational_poly(vec![Number::from(0), Number::ratio(1, 2)]));
+    assert_eq!(r, rational_poly(vec![Number::from(1)]));
+  }
+
+  #[test]
+  fn test_div_rem_failures() {
+    let p = mod_poly(6, vec![1, 1]);
+    assert!(matches!(p.div_rem(&mod_poly(6, vec![])), Err(PolynomialError::DivisionByZero)));
+    assert!(matches!(p.div_rem(&mod_poly(6, vec![1, 2])), Err(PolynomialError::NonInvertibleLeadingCoefficient)));
+  }
+
+  #[test]
+  fn test_gcd() {
+    // gcd((x + 1)(x + 2), (x + 1)(x + 3)) = x + 1 mod 7
+    let a = mod_poly(7, vec![1, 1]);
+    let p = a.mul(&mod_poly(7, vec![2, 1]));
+    let q = a.mul(&mod_poly(7, vec![3, 1])).scale(&3);
+    assert_eq!(p.gcd(&q).unwrap(), a);
+    assert_eq!(p.gcd(&mod_poly(7, vec![])).unwrap(), p);
+  }
+
+  #[test]
+  fn test_factor_mod_2() {
+    // x^4 + x^3 + x + 1 = (x + 1)^2 (x^2 + x + 1) mod 2
+    let p = mod_poly(2, vec![1, 1, 0, 1, 1]);
+    let factorization = p.factor().unwrap();
+    assert!(factorization.unit.is_one());
+    assert_eq!(factorization.factors, vec![
+      (mod_poly(2, vec![1, 1]), 2),
+      (mod_poly(2, vec![1, 1, 1]), 1),
+    ]);
+  }
+
+  #[test]
+  fn test_factor_with_unit_and_irreducible_remainder() {
+    // 2x^3 + 2x + 2 = 2 (x + 2) (x^2 + x + 2) mod 3
+    let p = mod_poly(3, vec![2, 2, 0, 2]);
+    let factorization = p.factor().unwrap();
+    assert_eq!(factorization.unit, mod_poly(3, vec![2]));
+    assert_eq!(factorization.factors, vec![
+      (mod_poly(3, vec![2, 1]), 1),
+      (mod_poly(3, vec![2, 1, 1]), 1),
+    ]);
+    // x^2 + 1 is irreducible mod 3.
+    let p = mod_poly(3, vec![1, 0, 1]);
+    assert_eq!(p.factor().unwrap().factors, vec![(p, 1)]);
+  }
+
+  #[test]
+  fn test_factor_requires_field() {
+    let p = mod_poly(4, vec![1, 0, 1]);
+    assert!(matches!(p.factor(), Err(PolynomialError::NotAField)));
+  }
+
+  #[test]
+  fn test_from_expr() {
+    // (x + 3)^2 - x / 2 mod 7
+    let expr = Expr::call("-", vec![
+      Expr::call("^", vec![Expr::call("+", vec![Expr::var("x").unwrap(), Expr::from(3)]), Expr::from(2)]),
+      Expr::call("/", vec![Expr::var("x").unwrap(), Expr::from(2)]),
+    ]);
+    let p = DensePolynomial::from_expr(IntegersMod::new(7), &expr, &x()).unwrap();
+    // x^2 + 6x + 9 - 4x = x^2 + 2x + 2
+    assert_eq!(p, mod_poly(7, vec![2, 2, 1]));
+  }
+
+  #[test]
+  fn test_from_expr_failures() {
+    let expr = Expr::call("sin", vec![Expr::var("x").unwrap()]);
+    assert!(matches!(
+      DensePolynomial::from_expr(RationalField, &expr, &x()),
+      Err(PolynomialError::NotAPolynomial(_)),
+    ));
+    let expr = Expr::call("+", vec![Expr::var("x").unwrap(), Expr::var("y").unwrap()]);
+    assert!(matches!(
+      DensePolynomial::from_expr(RationalField, &expr, &x()),
+      Err(PolynomialError::NotAPolynomial(_)),
+    ));
+    let expr = Expr::call("+", vec![Expr::var("x").unwrap(), Expr::from(0.5)]);
+    assert!(matches!(
+      DensePolynomial::from_expr(IntegersMod::new(5), &expr, &x()),
+      Err(PolynomialError::InvalidCoefficient(_)),
+    ));
+  }
+
+  #[test]
+  fn test_to_expr() {
+    let p = mod_poly(5, vec![3, 1, 0, 2]);
+    assert_eq!(p.to_expr(&x()), Expr::call("+", vec![
+      Expr::call("*", vec![Expr::from(2), Expr::call("^", vec![Expr::var("x").unwrap(), Expr::from(3)])]),
+      Expr::var("x").unwrap(),
+      Expr::from(3),
+    ]));
+    assert_eq!(mod_poly(5, vec![]).to_expr(&x()), Expr::zero());
+  }
+
+  #[test]
+  fn test_eval() {
+    // 2x^2 - 3x + 1 at x = 2
+    let p = rational_poly(vec![Number::from(1), Number::from(-3), Number::from(2)]);
+    assert_eq!(p.eval(&Number::from(2)), Number::from(3));
+    assert_eq!(mod_poly(5, vec![]).eval(&3), 0);
+  }
+
+  #[test]
+  fn test_derivative() {
+    let p = rational_poly(vec![Number::from(1), Number::from(-3), Number::from(2)]);
+    assert_eq!(p.derivative(), rational_poly(vec![Number::from(-3), Number::from(4)]));
+    // d/dx (x^5 + x) = 5x^4 + 1 = 1 mod 5
+    assert_eq!(mod_poly(5, vec![0, 1, 0, 0, 0, 1]).derivative(), mod_poly(5, vec![1]));
+  }
+}
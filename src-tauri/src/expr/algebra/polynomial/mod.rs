@@ -1,3 +1,5 @@
+pub mod dense;
+pub mod ring;
 
 use super::term::{Term, Sign, SignedTerm};
 use crate::expr::Expr;
@@ -0,0 +1,222 @@
+
+//! Coefficient rings over which a [`DensePolynomial`] can be
+//! defined.
+//!
+//! [`DensePolynomial`]: super::dense::DensePolynomial
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+
+use num::{BigInt, Integer, Zero, One, ToPrimitive};
+
+use std::fmt::Debug;
+
+/// A commutative ring with identity, whose elements are used as the
+/// coefficients of a polynomial.
+///
+/// A ring is a value rather than a type, so that families of rings
+/// (such as the integers modulo `n`) can be chosen at runtime.
+pub trait CoefficientRing: Clone + Debug {
+  type Elem: Clone + Debug + PartialEq;
+
+  fn zero(&self) -> Self::Elem;
+
+  fn one(&self) -> Self::Elem;
+
+  fn is_zero(&self, a: &Self::Elem) -> bool {
+    a == &self.zero()
+  }
+
+  fn add(&self, a: &Self::Elem, b: &Self::Elem) -> Self::Elem;
+
+  fn neg(&self, a: &Self::Elem) -> Self::Elem;
+
+  fn sub(&self, a: &Self::Elem, b: &Self::Elem) -> Self::Elem {
+    self.add(a, &self.neg(b))
+  }
+
+  fn mul(&self, a: &Self::Elem, b: &Self::Elem) -> Self::Elem;
+
+  /// The multiplicative inverse of `a`, or `None` if `a` is not a
+  /// unit of the ring.
+  fn inverse(&self, a: &Self::Elem) -> Option<Self::Elem>;
+
+  /// Interprets a number as an element of the ring, or returns
+  /// `None` if the number has no meaning in this ring.
+  fn embed_number(&self, n: Number) -> Option<Self::Elem>;
+
+  fn to_expr(&self, a: Self::Elem) -> Expr;
+}
+
+/// The field of rational numbers, which is the default coefficient
+/// ring. Floating-point coefficients are permitted and behave as
+/// approximate rationals.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RationalField;
+
+/// The ring of integers modulo `n`, for some `n >= 2`. This ring is
+/// a field if and only if `n` is prime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegersMod {
+  modulus: u64,
+}
+
+impl IntegersMod {
+  /// Panics if `modulus < 2`.
+  pub fn new(modulus: u64) -> Self {
+    assert!(modulus >= 2, "Modulus must be at least 2, got {modulus}");
+    Self { modulus }
+  }
+
+  pub fn modulus(&self) -> u64 {
+    self.modulus
+  }
+
+  /// True if the ring is a field, i.e. if the modulus is prime.
+  pub fn is_field(&self) -> bool {
+    is_prime(self.modulus)
+  }
+}
+
+impl CoefficientRing for RationalField {
+  type Elem = Number;
+
+  fn zero(&self) -> Number {
+    Number::zero()
+  }
+
+  fn one(&self) -> Number {
+    Number::one()
+  }
+
+  fn is_zero(&self, a: &Number) -> bool {
+    a.is_zero()
+  }
+
+  fn add(&self, a: &Number, b: &Number) -> Number {
+    a + b
+  }
+
+  fn neg(&self, a: &Number) -> Number {
+    -a
+  }
+
+  fn sub(&self, a: &Number, b: &Number) -> Number {
+    a - b
+  }
+
+  fn mul(&self, a: &Number, b: &Number) -> Number {
+    a * b
+  }
+
+  fn inverse(&self, a: &Number) -> Option<Number> {
+    if a.is_zero() {
+      None
+    } else {
+      Some(a.recip())
+    }
+  }
+
+  fn embed_number(&self, n: Number) -> Option<Number> {
+    Some(n)
+  }
+
+  fn to_expr(&self, a: Number) -> Expr {
+    Expr::from(a)
+  }
+}
+
+impl CoefficientRing for IntegersMod {
+  type Elem = u64;
+
+  fn zero(&self) -> u64 {
+    0
+  }
+
+  fn one(&self) -> u64 {
+    1
+  }
+
+  fn add(&self, a: &u64, b: &u64) -> u64 {
+    ((*a as u128 + *b as u128) % self.modulus as u128) as u64
+  }
+
+  fn neg(&self, a: &u64) -> u64 {
+    if *a == 0 { 0 } else { self.modulus - a }
+  }
+
+  fn mul(&self, a: &u64, b: &u64) -> u64 {
+    ((*a as u128 * *b as u128) % self.modulus as u128) as u64
+  }
+
+  fn inverse(&self, a: &u64) -> Option<u64> {
+    let egcd = BigInt::from(*a).extended_gcd(&BigInt::from(self.modulus));
+    if !egcd.gcd.is_one() {
+      return None;
+    }
+    egcd.x.mod_floor(&BigInt::from(self.modulus)).to_u64()
+  }
+
+  fn embed_number(&self, n: Number) -> Option<u64> {
+    let n = BigInt::try_from(n).ok()?;
+    n.mod_floor(&BigInt::from(self.modulus)).to_u64()
+  }
+
+  fn to_expr(&self, a: u64) -> Expr {
+    Expr::from(Number::from(BigInt::from(a)))
+  }
+}
+
+fn is_prime(n: u64) -> bool {
+  if n < 2 {
+    return false;
+  }
+  let mut d = 2u64;
+  while d.saturating_mul(d) <= n {
+    if n.is_multiple_of(d) {
+      return false;
+    }
+    d += 1;
+  }
+  true
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_integers_mod_arithmetic() {
+    let ring = IntegersMod::new(7);
+    assert_eq!(ring.add(&5, &4), 2);
+    assert_eq!(ring.sub(&2, &5), 4);
+    assert_eq!(ring.neg(&0), 0);
+    assert_eq!(ring.mul(&3, &5), 1);
+  }
+
+  #[test]
+  fn test_integers_mod_inverse() {
+    let ring = IntegersMod::new(7);
+    assert_eq!(ring.inverse(&3), Some(5));
+    assert_eq!(ring.inverse(&0), None);
+    let ring = IntegersMod::new(8);
+    assert_eq!(ring.inverse(&3), Some(3));
+    assert_eq!(ring.inverse(&4), None);
+  }
+
+  #[test]
+  fn test_integers_mod_embed_number() {
+    let ring = IntegersMod::new(5);
+    assert_eq!(ring.embed_number(Number::from(-1)), Some(4));
+    assert_eq!(ring.embed_number(Number::from(12)), Some(2));
+    assert_eq!(ring.embed_number(Number::ratio(1, 2)), None);
+    assert_eq!(ring.embed_number(Number::from(1.5)), None);
+  }
+
+  #[test]
+  fn test_is_field() {
+    assert!(IntegersMod::new(2).is_field());
+    assert!(IntegersMod::new(13).is_field());
+    assert!(!IntegersMod::new(12).is_field());
+  }
+}
@@ -0,0 +1,266 @@
+
+//! Least-squares fitting of data points to simple models.
+//!
+//! Polynomial models are linear in their coefficients, so they are
+//! fitted directly and exactly when the data is exact. Exponential
+//! and logarithmic models are linearized by taking the logarithm of
+//! `y` or `x` respectively, so their coefficients are inexact and
+//! minimize the squared error of the linearized model.
+
+use crate::expr::number::Number;
+use crate::util::matrix::{Matrix, SingularMatrixError};
+use crate::util::prism::Prism;
+
+use num::{Zero, One, BigInt};
+use thiserror::Error;
+
+use std::fmt::{self, Display, Formatter};
+
+/// A family of curves to fit data points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitModel {
+  /// `y = a + b x`, with coefficients `[a, b]`.
+  Linear,
+  /// `y = c0 + c1 x + ... + cn x^n`, with coefficients `[c0, ...,
+  /// cn]`.
+  Polynomial(usize),
+  /// `y = a exp(b x)`, with coefficients `[a, b]`. Requires positive
+  /// `y` values.
+  Exponential,
+  /// `y = a + b ln(x)`, with coefficients `[a, b]`. Requires positive
+  /// `x` values.
+  Logarithmic,
+}
+
+/// Prism which parses a string as a [`FitModel`]. Accepts `linear`,
+/// `exponential`, `logarithmic`, or `poly` followed by a degree.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToFitModel;
+
+/// The result of fitting a model to data points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fit {
+  pub coefficients: Vec<Number>,
+  /// The coefficient of determination of the fitted curve, measured
+  /// against the original (not linearized) data.
+  pub r_squared: Number,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum FitError {
+  #[error("Expected at least {expected} data points, got {actual}")]
+  NotEnoughPoints { expected: usize, actual: usize },
+  #[error("Expected positive x values")]
+  NonPositiveX,
+  #[error("Expected positive y values")]
+  NonPositiveY,
+  #[error("Data does not determine a unique fit")]
+  SingularMatrixError(#[from] SingularMatrixError),
+  #[error("Data has no variance in y")]
+  NoVariance,
+  #[error("Fitted curve is too large to represent")]
+  Overflow,
+}
+
+impl FitModel {
+  /// The number of coefficients in the model.
+  pub fn parameter_count(self) -> usize {
+    match self {
+      FitModel::Linear | FitModel::Exponential | FitModel::Logarithmic => 2,
+      FitModel::Polynomial(degree) => degree + 1,
+    }
+  }
+
+  /// Fits the model to the given `[x, y]` points by least squares.
+  pub fn fit(self, points: &[[Number; 2]]) -> Result<Fit, FitError> {
+    if points.len() < self.parameter_count() {
+      return Err(FitError::NotEnoughPoints { expected: self.parameter_count(), actual: points.len() });
+    }
+    let coefficients = match self {
+      FitModel::Linear => fit_polynomial(1, points)?,
+      FitModel::Polynomial(degree) => fit_polynomial(degree, points)?,
+      FitModel::Exponential => {
+        if points.iter().any(|[_, y]| !y.is_positive()) {
+          return Err(FitError::NonPositiveY);
+        }
+        let design = Matrix::from_generator(points.len(), 2, |index| {
+          if index.x == 0 { Number::one() } else { points[index.y][0].clone() }
+        });
+        let observations: Vec<_> = points.iter().map(|[_, y]| y.ln()).collect();
+        let [log_a, b] = design.least_squares(&observations)?.try_into().unwrap();
+        vec![finite_number(log_a.to_f64_or_nan().exp())?, b]
+      }
+      FitModel::Logarithmic => {
+        if points.iter().any(|[x, _]| !x.is_positive()) {
+          return Err(FitError::NonPositiveX);
+        }
+        let design = Matrix::from_generator(points.len(), 2, |index| {
+          if index.x == 0 { Number::one() } else { points[index.y][0].ln() }
+        });
+        let observations: Vec<_> = points.iter().map(|[_, y]| y.clone()).collect();
+        design.least_squares(&observations)?
+      }
+    };
+    let r_squared = self.r_squared(&coefficients, points)?;
+    Ok(Fit { coefficients, r_squared })
+  }
+
+  /// The value at `x` of the curve with the given coefficients.
+  /// Panics if an exponential or logarithmic model is not given
+  /// exactly two coefficients.
+  pub fn predict(self, coefficients: &[Number], x: &Number) -> Result<Number, FitError> {
+    match self {
+      FitModel::Linear | FitModel::Polynomial(_) => {
+        // Horner's method
+        Ok(coefficients.iter().rev().fold(Number::zero(), |acc, c| acc * x + c))
+      }
+      FitModel::Exponential => {
+        let [a, b] = coefficients else { panic!("Expected two coefficients") };
+        finite_number(a.to_f64_or_nan() * (b.to_f64_or_nan() * x.to_f64_or_nan()).exp())
+      }
+      FitModel::Logarithmic => {
+        let [a, b] = coefficients else { panic!("Expected two coefficients") };
+        Ok(a.clone() + b.clone() * x.ln())
+      }
+    }
+  }
+
+  fn r_squared(self, coefficients: &[Number], points: &[[Number; 2]]) -> Result<Number, FitError> {
+    let count = Number::from(points.len());
+    let mean = points.iter().map(|[_, y]| y.clone()).sum::<Number>() / count;
+    let mut total_sum_of_squares = Number::zero();
+    let mut residual_sum_of_squares = Number::zero();
+    for [x, y] in points {
+      let deviation = y.clone() - &mean;
+      total_sum_of_squares = total_sum_of_squares + deviation.clone() * deviation;
+      let residual = y.clone() - self.predict(coefficients, x)?;
+      residual_sum_of_squares = residual_sum_of_squares + residual.clone() * residual;
+    }
+    if total_sum_of_squares.is_zero() {
+      return Err(FitError::NoVariance);
+    }
+    Ok(Number::one() - residual_sum_of_squares / total_sum_of_squares)
+  }
+}
+
+fn fit_polynomial(degree: usize, points: &[[Number; 2]]) -> Result<Vec<Number>, FitError> {
+  let design = Matrix::from_generator(points.len(), degree + 1, |index| {
+    points[index.y][0].powi(BigInt::from(index.x))
+  });
+  let observations: Vec<_> = points.iter().map(|[_, y]| y.clone()).collect();
+  Ok(design.least_squares(&observations)?)
+}
+
+fn finite_number(value: f64) -> Result<Number, FitError> {
+  if value.is_finite() {
+    Ok(Number::from(value))
+  } else {
+    Err(FitError::Overflow)
+  }
+}
+
+impl Display for FitModel {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      FitModel::Linear => write!(f, "linear"),
+      FitModel::Polynomial(degree) => write!(f, "poly {degree}"),
+      FitModel::Exponential => write!(f, "exponential"),
+      FitModel::Logarithmic => write!(f, "logarithmic"),
+    }
+  }
+}
+
+impl Prism<String, FitModel> for StringToFitModel {
+  fn narrow_type(&self, input: String) -> Result<FitModel, String> {
+    let words: Vec<_> = input.split_whitespace().collect();
+    match words.as_slice() {
+      ["linear"] => Ok(FitModel::Linear),
+      ["exponential"] => Ok(FitModel::Exponential),
+      ["logarithmic"] => Ok(FitModel::Logarithmic),
+      ["poly", degree] => match degree.parse() {
+        Ok(degree) => Ok(FitModel::Polynomial(degree)),
+        Err(_) => Err(input),
+      },
+      _ => Err(input),
+    }
+  }
+
+  fn widen_type(&self, model: FitModel) -> String {
+    model.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn points(points: &[(i64, i64)]) -> Vec<[Number; 2]> {
+    points.iter().map(|(x, y)| [Number::from(*x), Number::from(*y)]).collect()
+  }
+
+  fn assert_close(actual: &Number, expected: f64) {
+    let actual = actual.to_f64_or_nan();
+    assert!((actual - expected).abs() < 1e-9, "Expected {expected}, got {actual}");
+  }
+
+  #[test]
+  fn test_parse_fit_model() {
+    assert_eq!(StringToFitModel.narrow_type(String::from("linear")), Ok(FitModel::Linear));
+    assert_eq!(StringToFitModel.narrow_type(String::from(" poly  3 ")), Ok(FitModel::Polynomial(3)));
+    assert_eq!(StringToFitModel.narrow_type(String::from("poly")), Err(String::from("poly")));
+    assert_eq!(StringToFitModel.narrow_type(String::from("poly -1")), Err(String::from("poly -1")));
+    assert_eq!(StringToFitModel.narrow_type(String::from("quadratic")), Err(String::from("quadratic")));
+    for model in [FitModel::Linear, FitModel::Polynomial(2), FitModel::Exponential, FitModel::Logarithmic] {
+      assert_eq!(StringToFitModel.narrow_type(StringToFitModel.widen_type(model)), Ok(model));
+    }
+  }
+
+  #[test]
+  fn test_linear_fit() {
+    let fit = FitModel::Linear.fit(&points(&[(0, 1), (1, 3), (2, 5)])).unwrap();
+    assert_eq!(fit, Fit { coefficients: vec![Number::from(1), Number::from(2)], r_squared: Number::from(1) });
+
+    // y = 1/2 + x/2, with residuals 1/2, -1, 1/2
+    let fit = FitModel::Linear.fit(&points(&[(0, 1), (1, 0), (2, 2)])).unwrap();
+    assert_eq!(fit.coefficients, vec![Number::ratio(1, 2), Number::ratio(1, 2)]);
+    assert_eq!(fit.r_squared, Number::ratio(1, 4));
+  }
+
+  #[test]
+  fn test_polynomial_fit() {
+    let fit = FitModel::Polynomial(2).fit(&points(&[(-1, 2), (0, 1), (1, 2), (2, 5)])).unwrap();
+    assert_eq!(fit.coefficients, vec![Number::from(1), Number::from(0), Number::from(1)]);
+    assert_eq!(fit.r_squared, Number::from(1));
+  }
+
+  #[test]
+  fn test_exponential_fit() {
+    let data: Vec<_> = (0..4).map(|x| [Number::from(x), Number::from(3.0 * (0.5 * x as f64).exp())]).collect();
+    let fit = FitModel::Exponential.fit(&data).unwrap();
+    assert_close(&fit.coefficients[0], 3.0);
+    assert_close(&fit.coefficients[1], 0.5);
+    assert_close(&fit.r_squared, 1.0);
+  }
+
+  #[test]
+  fn test_logarithmic_fit() {
+    let data: Vec<_> = (1..5).map(|x| [Number::from(x), Number::from(2.0 - 4.0 * (x as f64).ln())]).collect();
+    let fit = FitModel::Logarithmic.fit(&data).unwrap();
+    assert_close(&fit.coefficients[0], 2.0);
+    assert_close(&fit.coefficients[1], -4.0);
+    assert_close(&fit.r_squared, 1.0);
+  }
+
+  #[test]
+  fn test_fit_errors() {
+    assert!(matches!(
+      FitModel::Polynomial(2).fit(&points(&[(0, 1), (1, 2)])),
+      Err(FitError::NotEnoughPoints { expected: 3, actual: 2 }),
+    ));
+    assert!(matches!(FitModel::Exponential.fit(&points(&[(0, 1), (1, 0)])), Err(FitError::NonPositiveY)));
+    assert!(matches!(FitModel::Logarithmic.fit(&points(&[(0, 1), (1, 2)])), Err(FitError::NonPositiveX)));
+    assert!(matches!(FitModel::Linear.fit(&points(&[(1, 1), (1, 2)])), Err(FitError::SingularMatrixError(_))));
+    assert!(matches!(FitModel::Linear.fit(&points(&[(0, 1), (1, 1)])), Err(FitError::NoVariance)));
+  }
+}
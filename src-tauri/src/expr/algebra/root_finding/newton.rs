@@ -3,6 +3,12 @@
 //! differentiable functions.
 //!
 //! See <https://en.wikipedia.org/wiki/Newton%27s_method>.
+//!
+//! Where possible, the derivative is computed by forward-mode
+//! automatic differentiation (see [`DualFunction`]), which avoids
+//! building (and repeatedly simplifying) a symbolic derivative.
+//! Functions unknown to the automatic differentiator fall back to
+//! symbolic differentiation.
 
 use super::FoundRoot;
 use crate::expr::Expr;
@@ -10,11 +16,12 @@ use crate::expr::algebra::{ExprFunction, FunctionEvalError};
 use crate::expr::simplifier::Simplifier;
 use crate::expr::var::Var;
 use crate::expr::function::table::FunctionTable;
-use crate::expr::number::ComplexLike;
-use crate::expr::calculus::{differentiate, DifferentiationError, DifferentiationFailure};
+use crate::expr::number::{ComplexLike, ComplexNumber, Number};
+use crate::expr::calculus::{differentiate, DifferentiationError, DifferentiationFailure, DualFunction};
 
 use thiserror::Error;
 use num::Zero;
+use num::complex::Complex64;
 
 #[derive(Debug)]
 pub struct NewtonRaphsonMethod {
@@ -24,7 +31,12 @@ pub struct NewtonRaphsonMethod {
 
 pub struct NewtonRaphsonFunction<'a> {
   function: ExprFunction<'a>,
-  derivative: ExprFunction<'a>,
+  derivative: Derivative<'a>,
+}
+
+enum Derivative<'a> {
+  Automatic(DualFunction),
+  Symbolic(ExprFunction<'a>),
 }
 
 #[derive(Debug, Clone, Error)]
@@ -82,10 +94,16 @@ impl<'a> NewtonRaphsonFunction<'a> {
     function_table: &FunctionTable,
     simplifier: &'a dyn Simplifier,
   ) -> Result<Self, DifferentiationFailure> {
-    let deriv = differentiate(function_table, expr.clone(), var.clone())?;
+    let derivative = match DualFunction::compile(&expr, &var) {
+      Ok(dual) => Derivative::Automatic(dual),
+      Err(_) => {
+        let deriv = differentiate(function_table, expr.clone(), var.clone())?;
+        Derivative::Symbolic(ExprFunction::new(deriv, var.clone(), simplifier))
+      }
+    };
     Ok(Self {
-      function: ExprFunction::new(expr, var.clone(), simplifier),
-      derivative: ExprFunction::new(deriv, var, simplifier),
+      function: ExprFunction::new(expr, var, simplifier),
+      derivative,
     })
   }
 
@@ -95,8 +113,22 @@ impl<'a> NewtonRaphsonFunction<'a> {
   }
 
   pub fn eval_deriv_at(&self, value: ComplexLike) -> Result<ComplexLike, FunctionEvalError> {
-    let x = self.derivative.eval_at_complex(value)?;
-    Ok(x)
+    match &self.derivative {
+      Derivative::Automatic(dual) => {
+        let is_real = value.is_real();
+        let (re, im) = ComplexNumber::from(value).into_parts();
+        let deriv = dual.eval(Complex64::new(re.to_f64_or_nan(), im.to_f64_or_nan())).deriv;
+        if is_real && deriv.im == 0.0 {
+          Ok(ComplexLike::Real(Number::from(deriv.re)))
+        } else {
+          Ok(ComplexLike::Complex(ComplexNumber::new(deriv.re, deriv.im)))
+        }
+      }
+      Derivative::Symbolic(derivative) => {
+        let x = derivative.eval_at_complex(value)?;
+        Ok(x)
+      }
+    }
   }
 }
 
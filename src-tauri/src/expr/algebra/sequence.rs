@@ -0,0 +1,339 @@
+
+//! Guessing the rule that generates a sequence from its first few
+//! terms.
+//!
+//! Terms are indexed from one, so the rules produced here describe
+//! `a(1), a(2), ...`. All arithmetic is exact.
+
+use super::polynomial::dense::DensePolynomial;
+use super::polynomial::ring::{CoefficientRing, RationalField};
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::var::Var;
+
+use num::{Zero, One};
+
+/// A rule generating a sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceRule {
+  /// `a(n) = p(n)` for a polynomial `p`.
+  Polynomial(DensePolynomial<RationalField>),
+  /// `a(n) = initial * ratio^(n - 1)`.
+  Geometric { initial: Number, ratio: Number },
+  /// `a(n) = c_1 a(n - 1) + c_2 a(n - 2) + ... + c_k a(n - k)`, where
+  /// the first `k` terms are given explicitly.
+  LinearRecurrence { coefficients: Vec<Number> },
+}
+
+impl SequenceRule {
+  /// Attempts to identify the rule generating `terms`. Polynomials
+  /// are preferred, then geometric sequences, then general linear
+  /// recurrences.
+  ///
+  /// A rule is only accepted if the terms contain more information
+  /// than is needed to determine it, so that at least one term
+  /// confirms the guess. Returns `None` if no rule is found.
+  pub fn guess(terms: &[Number]) -> Option<Self> {
+    guess_polynomial(terms)
+      .or_else(|| guess_geometric(terms))
+      .or_else(|| guess_linear_recurrence(terms))
+  }
+
+  /// The `count` terms following `terms`, which must be generated by
+  /// this rule.
+  pub fn extend(&self, terms: &[Number], count: usize) -> Vec<Number> {
+    match self {
+      SequenceRule::Polynomial(polynomial) => {
+        (terms.len() + 1..=terms.len() + count)
+          .map(|n| evaluate(polynomial, &Number::from(n)))
+          .collect()
+      }
+      SequenceRule::Geometric { ratio, .. } => {
+        let mut last = terms.last().cloned().unwrap_or_else(Number::zero);
+        (0..count).map(|_| {
+          last = &last * ratio;
+          last.clone()
+        }).collect()
+      }
+      SequenceRule::LinearRecurrence { coefficients } => {
+        let mut all_terms = terms.to_vec();
+        for _ in 0..count {
+          let next = coefficients.iter()
+            .zip(all_terms.iter().rev())
+            .fold(Number::zero(), |acc, (c, a)| acc + c * a);
+          all_terms.push(next);
+        }
+        all_terms.split_off(terms.len())
+      }
+    }
+  }
+
+  /// The rule as an equation defining `function(n)`.
+  pub fn to_equation(&self, function: &str, n: &Var) -> Expr {
+    let n_expr = Expr::from(n.clone());
+    let lhs = Expr::call(function, vec![n_expr.clone()]);
+    let rhs = match self {
+      SequenceRule::Polynomial(polynomial) => polynomial.to_expr(n),
+      SequenceRule::Geometric { initial, ratio } => {
+        let power = Expr::call("^", vec![
+          Expr::from(ratio.clone()),
+          Expr::call("-", vec![n_expr, Expr::from(1)]),
+        ]);
+        if initial.is_one() {
+          power
+        } else {
+          Expr::call("*", vec![Expr::from(initial.clone()), power])
+        }
+      }
+      SequenceRule::LinearRecurrence { coefficients } => {
+        let mut terms = coefficients.iter()
+          .enumerate()
+          .filter(|(_, c)| !c.is_zero())
+          .map(|(i, c)| {
+            let previous = Expr::call(function, vec![
+              Expr::call("-", vec![n_expr.clone(), Expr::from(Number::from(i + 1))]),
+            ]);
+            let magnitude = c.abs();
+            let term = if magnitude.is_one() {
+              previous
+            } else {
+              Expr::call("*", vec![Expr::from(magnitude), previous])
+            };
+            (c.is_negative(), term)
+          });
+        // There is always a nonzero coefficient, since the last one
+        // is nonzero by construction.
+        let (first_negative, first) = terms.next().expect("recurrence should have a nonzero coefficient");
+        let first = if first_negative { Expr::call("negate", vec![first]) } else { first };
+        terms.fold(first, |acc, (negative, term)| {
+          Expr::call(if negative { "-" } else { "+" }, vec![acc, term])
+        })
+      }
+    };
+    Expr::call("=", vec![lhs, rhs])
+  }
+}
+
+fn evaluate(polynomial: &DensePolynomial<RationalField>, x: &Number) -> Number {
+  polynomial.coefficients().iter()
+    .rev()
+    .fold(Number::zero(), |acc, c| acc * x + c)
+}
+
+/// Finds a polynomial by repeatedly taking finite differences until a
+/// row of zeroes appears.
+fn guess_polynomial(terms: &[Number]) -> Option<SequenceRule> {
+  let mut row = terms.to_vec();
+  // Leading entries of each difference row, i.e. the coefficients in
+  // Newton's forward difference formula.
+  let mut leading_differences = Vec::new();
+  while !row.is_empty() {
+    if row.iter().all(Zero::is_zero) {
+      return Some(SequenceRule::Polynomial(newton_polynomial(&leading_differences)));
+    }
+    leading_differences.push(row[0].clone());
+    row = row.windows(2).map(|pair| &pair[1] - &pair[0]).collect();
+  }
+  None
+}
+
+/// The polynomial `p` with `Δ^k p(1) = differences[k]`. That is,
+/// `p(n) = sum of differences[k] * binomial(n - 1, k)`.
+fn newton_polynomial(differences: &[Number]) -> DensePolynomial<RationalField> {
+  let ring = RationalField;
+  let mut result = DensePolynomial::zero(ring);
+  // binomial(n - 1, k), as a polynomial in n.
+  let mut binomial = DensePolynomial::constant(ring, ring.one());
+  for (k, difference) in differences.iter().enumerate() {
+    result = result.add(&binomial.scale(difference));
+    // binomial(n - 1, k + 1) = binomial(n - 1, k) * (n - 1 - k) / (k + 1)
+    let factor = DensePolynomial::new(ring, vec![Number::from(-(k as i64) - 1), Number::one()]);
+    binomial = binomial.mul(&factor).scale(&(Number::one() / Number::from(k + 1)));
+  }
+  result
+}
+
+fn guess_geometric(terms: &[Number]) -> Option<SequenceRule> {
+  if terms.len() < 3 || terms.iter().any(Zero::is_zero) {
+    return None;
+  }
+  let ratio = &terms[1] / &terms[0];
+  let is_geometric = terms.windows(2).all(|pair| &pair[0] * &ratio == pair[1]);
+  is_geometric.then(|| SequenceRule::Geometric { initial: terms[0].clone(), ratio })
+}
+
+fn guess_linear_recurrence(terms: &[Number]) -> Option<SequenceRule> {
+  let coefficients = berlekamp_massey(terms);
+  // A recurrence of order k is determined by 2k terms, so we need at
+  // least one more to confirm it.
+  if coefficients.is_empty() || 2 * coefficients.len() >= terms.len() {
+    return None;
+  }
+  Some(SequenceRule::LinearRecurrence { coefficients })
+}
+
+/// The Berlekamp-Massey algorithm, over the rationals. Returns the
+/// coefficients `c` of the shortest linear recurrence
+/// `s[i] = c[0] s[i - 1] + ... + c[k - 1] s[i - k]` satisfied by
+/// every term of `s` from index `k` onward.
+fn berlekamp_massey(s: &[Number]) -> Vec<Number> {
+  // The connection polynomial C(x) = 1 - c[0] x - ... - c[k - 1] x^k,
+  // and the previous value of C, from the last time the length
+  // changed.
+  let mut current = vec![Number::one()];
+  let mut previous = vec![Number::one()];
+  let mut length = 0;
+  let mut shift = 1;
+  let mut previous_discrepancy = Number::one();
+  for n in 0..s.len() {
+    let discrepancy = (1..=length).fold(s[n].clone(), |acc, i| acc + &current[i] * &s[n - i]);
+    if discrepancy.is_zero() {
+      shift += 1;
+      continue;
+    }
+    let scale = &discrepancy / &previous_discrepancy;
+    let mut updated = current.clone();
+    if updated.len() < previous.len() + shift {
+      updated.resize(previous.len() + shift, Number::zero());
+    }
+    for (i, b) in previous.iter().enumerate() {
+      updated[i + shift] = &updated[i + shift] - &(&scale * b);
+    }
+    if 2 * length <= n {
+      length = n + 1 - length;
+      previous = std::mem::replace(&mut current, updated);
+      previous_discrepancy = discrepancy;
+      shift = 1;
+    } else {
+      current = updated;
+      shift += 1;
+    }
+    if current.len() <= length {
+      current.resize(length + 1, Number::zero());
+    }
+  }
+  current.resize(length + 1, Number::zero());
+  current[1..].iter().map(|c| -c).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn numbers(terms: &[i64]) -> Vec<Number> {
+    terms.iter().copied().map(Number::from).collect()
+  }
+
+  fn var(name: &str) -> Var {
+    Var::new(name).unwrap()
+  }
+
+  fn call(name: &str, args: Vec<Expr>) -> Expr {
+    Expr::call(name, args)
+  }
+
+  fn a_of(arg: Expr) -> Expr {
+    call("a", vec![arg])
+  }
+
+  fn n_minus(k: i64) -> Expr {
+    call("-", vec![Expr::var("n").unwrap(), Expr::from(k)])
+  }
+
+  #[test]
+  fn test_polynomial() {
+    let terms = numbers(&[2, 5, 10, 17, 26]);
+    let rule = SequenceRule::guess(&terms).unwrap();
+    assert!(matches!(rule, SequenceRule::Polynomial(_)));
+    assert_eq!(rule.extend(&terms, 2), numbers(&[37, 50]));
+    let n = Expr::var("n").unwrap();
+    assert_eq!(
+      rule.to_equation("a", &var("n")),
+      call("=", vec![a_of(n.clone()), call("+", vec![call("^", vec![n, Expr::from(2)]), Expr::from(1)])]),
+    );
+  }
+
+  #[test]
+  fn test_polynomial_with_rational_coefficients() {
+    // Triangular numbers, n (n + 1) / 2
+    let terms = numbers(&[1, 3, 6, 10, 15]);
+    let rule = SequenceRule::guess(&terms).unwrap();
+    let SequenceRule::Polynomial(polynomial) = &rule else { panic!("Expected polynomial, got {rule:?}") };
+    let half = Number::one() / Number::from(2);
+    assert_eq!(polynomial.coefficients(), &[Number::zero(), half.clone(), half]);
+    assert_eq!(rule.extend(&terms, 1), numbers(&[21]));
+  }
+
+  #[test]
+  fn test_constant_and_zero_sequences() {
+    let rule = SequenceRule::guess(&numbers(&[7, 7])).unwrap();
+    assert_eq!(rule.extend(&numbers(&[7, 7]), 2), numbers(&[7, 7]));
+    let rule = SequenceRule::guess(&numbers(&[0, 0, 0])).unwrap();
+    assert_eq!(rule.to_equation("a", &var("n")), call("=", vec![a_of(Expr::var("n").unwrap()), Expr::from(0)]));
+  }
+
+  #[test]
+  fn test_geometric() {
+    let terms = numbers(&[3, 6, 12, 24]);
+    let rule = SequenceRule::guess(&terms).unwrap();
+    assert_eq!(rule, SequenceRule::Geometric { initial: Number::from(3), ratio: Number::from(2) });
+    assert_eq!(rule.extend(&terms, 2), numbers(&[48, 96]));
+    assert_eq!(
+      rule.to_equation("a", &var("n")),
+      call("=", vec![
+        a_of(Expr::var("n").unwrap()),
+        call("*", vec![Expr::from(3), call("^", vec![Expr::from(2), n_minus(1)])]),
+      ]),
+    );
+  }
+
+  #[test]
+  fn test_fibonacci() {
+    let terms = numbers(&[1, 1, 2, 3, 5, 8]);
+    let rule = SequenceRule::guess(&terms).unwrap();
+    assert_eq!(rule, SequenceRule::LinearRecurrence { coefficients: numbers(&[1, 1]) });
+    assert_eq!(rule.extend(&terms, 3), numbers(&[13, 21, 34]));
+    assert_eq!(
+      rule.to_equation("a", &var("n")),
+      call("=", vec![
+        a_of(Expr::var("n").unwrap()),
+        call("+", vec![a_of(n_minus(1)), a_of(n_minus(2))]),
+      ]),
+    );
+  }
+
+  #[test]
+  fn test_recurrence_with_negative_coefficients() {
+    // a(n) = a(n - 1) - a(n - 2), which has period six.
+    let terms = numbers(&[1, 2, 1, -1, -2, -1, 1]);
+    let rule = SequenceRule::guess(&terms).unwrap();
+    assert_eq!(rule, SequenceRule::LinearRecurrence { coefficients: numbers(&[1, -1]) });
+    assert_eq!(rule.extend(&terms, 2), numbers(&[2, 1]));
+    assert_eq!(
+      rule.to_equation("a", &var("n")),
+      call("=", vec![
+        a_of(Expr::var("n").unwrap()),
+        call("-", vec![a_of(n_minus(1)), a_of(n_minus(2))]),
+      ]),
+    );
+  }
+
+  #[test]
+  fn test_no_rule() {
+    assert_eq!(SequenceRule::guess(&numbers(&[])), None);
+    assert_eq!(SequenceRule::guess(&numbers(&[1])), None);
+    assert_eq!(SequenceRule::guess(&numbers(&[1, 1, 2, 3])), None);
+    assert_eq!(SequenceRule::guess(&numbers(&[3, 1, 4, 1, 5, 9, 2, 6])), None);
+  }
+
+  #[test]
+  fn test_berlekamp_massey_reproduces_sequence() {
+    // a(n) = 2 a(n - 1) + a(n - 3)
+    let mut terms = numbers(&[1, 0, 3]);
+    for i in 3..12 {
+      let next = &terms[i - 1] * &Number::from(2) + terms[i - 3].clone();
+      terms.push(next);
+    }
+    assert_eq!(berlekamp_massey(&terms), numbers(&[2, 0, 1]));
+  }
+}
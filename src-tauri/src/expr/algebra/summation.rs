@@ -0,0 +1,276 @@
+
+//! Finite sums and products over an integer index variable.
+//!
+//! Closed forms are used where the summand has a recognizable shape
+//! (constant, arithmetic, or geometric), so that the evaluation does
+//! not depend on the number of terms. Anything else is expanded term
+//! by term, up to a fixed limit.
+
+use crate::errorlist::ErrorList;
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::var::Var;
+use crate::expr::calculus::differentiate;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::simplifier::{Simplifier, SimplifierContext};
+use crate::mode::calculation::CalculationMode;
+
+use num::BigInt;
+use thiserror::Error;
+
+/// Whether a [`FiniteSeries`] adds or multiplies its terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKind {
+  Sum,
+  Product,
+}
+
+/// The sum or product of `expr`, as `var` ranges over the integers
+/// from `lower` to `upper`, inclusive.
+#[derive(Debug, Clone)]
+pub struct FiniteSeries {
+  pub kind: SeriesKind,
+  pub expr: Expr,
+  pub var: Var,
+  pub lower: i64,
+  pub upper: i64,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum SeriesError {
+  #[error("No closed form found, and {0} terms is too many to evaluate directly")]
+  TooManyTerms(i128),
+}
+
+/// Evaluation context for a series, bundling the simplifier with the
+/// calculation mode that results should be simplified in.
+struct SeriesEvaluator<'a> {
+  function_table: &'a FunctionTable,
+  simplifier: &'a dyn Simplifier,
+  calculation_mode: CalculationMode,
+}
+
+impl SeriesKind {
+  pub fn function_name(self) -> &'static str {
+    match self {
+      SeriesKind::Sum => "+",
+      SeriesKind::Product => "*",
+    }
+  }
+
+  /// The value of the empty series.
+  pub fn identity(self) -> Expr {
+    match self {
+      SeriesKind::Sum => Expr::zero(),
+      SeriesKind::Product => Expr::one(),
+    }
+  }
+}
+
+impl FiniteSeries {
+  /// Maximum number of terms which will be expanded when no closed
+  /// form applies.
+  pub const MAX_EXPANDED_TERMS: i128 = 10_000;
+
+  /// The number of terms in the series. This is zero if the bounds
+  /// are reversed.
+  pub fn term_count(&self) -> i128 {
+    i128::max(0, self.upper as i128 - self.lower as i128 + 1)
+  }
+
+  pub fn evaluate(
+    &self,
+    function_table: &FunctionTable,
+    simplifier: &dyn Simplifier,
+    calculation_mode: CalculationMode,
+  ) -> Result<Expr, SeriesError> {
+    let evaluator = SeriesEvaluator { function_table, simplifier, calculation_mode };
+    if self.term_count() == 0 {
+      return Ok(self.kind.identity());
+    }
+    if let Some(closed_form) = self.closed_form(&evaluator) {
+      return Ok(evaluator.simplify(closed_form));
+    }
+    self.expand(&evaluator)
+  }
+
+  fn closed_form(&self, evaluator: &SeriesEvaluator) -> Option<Expr> {
+    let n = Expr::from(Number::from(BigInt::from(self.term_count())));
+    if !depends_on(&self.expr, &self.var) {
+      return Some(match self.kind {
+        SeriesKind::Sum => Expr::call("*", vec![n, self.expr.clone()]),
+        SeriesKind::Product => Expr::call("^", vec![self.expr.clone(), n]),
+      });
+    }
+    match self.kind {
+      SeriesKind::Sum => {
+        self.arithmetic_sum(evaluator, &n).or_else(|| self.geometric_sum(evaluator, &n))
+      }
+      SeriesKind::Product => {
+        self.product_of_powers(evaluator)
+      }
+    }
+  }
+
+  /// If the summand is linear in the index, the sum is the number of
+  /// terms times the average of the first and last terms.
+  fn arithmetic_sum(&self, evaluator: &SeriesEvaluator, n: &Expr) -> Option<Expr> {
+    let derivative = differentiate(evaluator.function_table, self.expr.clone(), self.var.clone()).ok()?;
+    let derivative = evaluator.simplify(derivative);
+    if depends_on(&derivative, &self.var) {
+      return None;
+    }
+    let endpoints = Expr::call("+", vec![self.term_at(self.lower), self.term_at(self.upper)]);
+    Some(Expr::call("/", vec![Expr::call("*", vec![n.clone(), endpoints]), Expr::from(2)]))
+  }
+
+  /// If consecutive terms have a constant ratio `q`, the sum is
+  /// `a (1 - q^n) / (1 - q)`, where `a` is the first term.
+  fn geometric_sum(&self, evaluator: &SeriesEvaluator, n: &Expr) -> Option<Expr> {
+    let next_index = Expr::call("+", vec![Expr::Atom(Atom::Var(self.var.clone())), Expr::one()]);
+    let next_term = self.expr.clone().substitute_var(self.var.clone(), next_index);
+    let ratio = evaluator.simplify(Expr::call("/", vec![next_term, self.expr.clone()]));
+    if depends_on(&ratio, &self.var) || ratio.is_one() {
+      return None;
+    }
+    let one_minus = |e: Expr| Expr::call("-", vec![Expr::one(), e]);
+    Some(Expr::call("/", vec![
+      Expr::call("*", vec![self.term_at(self.lower), one_minus(Expr::call("^", vec![ratio.clone(), n.clone()]))]),
+      one_minus(ratio),
+    ]))
+  }
+
+  /// A product of powers of a constant base is the base raised to the
+  /// sum of the exponents.
+  fn product_of_powers(&self, evaluator: &SeriesEvaluator) -> Option<Expr> {
+    let Expr::Call(function, args) = &self.expr else {
+      return None;
+    };
+    if function != "^" || args.len() != 2 || depends_on(&args[0], &self.var) {
+      return None;
+    }
+    let exponent_sum = FiniteSeries {
+      kind: SeriesKind::Sum,
+      expr: args[1].clone(),
+      ..self.clone()
+    };
+    let exponent_sum = exponent_sum.closed_form(evaluator)?;
+    Some(Expr::call("^", vec![args[0].clone(), exponent_sum]))
+  }
+
+  fn expand(&self, evaluator: &SeriesEvaluator) -> Result<Expr, SeriesError> {
+    let term_count = self.term_count();
+    if term_count > Self::MAX_EXPANDED_TERMS {
+      return Err(SeriesError::TooManyTerms(term_count));
+    }
+    let terms = (self.lower..=self.upper).map(|i| self.term_at(i)).collect();
+    Ok(evaluator.simplify(Expr::call(self.kind.function_name(), terms)))
+  }
+
+  fn term_at(&self, index: i64) -> Expr {
+    self.expr.clone().substitute_var(self.var.clone(), Expr::from(index))
+  }
+}
+
+impl<'a> SeriesEvaluator<'a> {
+  fn simplify(&self, expr: Expr) -> Expr {
+    // Errors will be reported when the caller simplifies the result,
+    // so we don't need to report them here.
+    let mut errors = ErrorList::new();
+    let mut context = SimplifierContext {
+      base_simplifier: self.simplifier,
+      calculation_mode: self.calculation_mode.clone(),
+      errors: &mut errors,
+    };
+    self.simplifier.simplify_expr(expr, &mut context)
+  }
+}
+
+fn depends_on(expr: &Expr, var: &Var) -> bool {
+  match expr {
+    Expr::Atom(Atom::Var(v)) => v == var,
+    Expr::Atom(_) => false,
+    Expr::Call(_, args) => args.iter().any(|arg| depends_on(arg, var)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::default_simplifier;
+
+  use once_cell::sync::Lazy;
+
+  static FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(build_function_table);
+
+  fn k() -> Expr {
+    Expr::var("k").unwrap()
+  }
+
+  fn evaluate(kind: SeriesKind, expr: Expr, lower: i64, upper: i64) -> Result<Expr, SeriesError> {
+    let series = FiniteSeries { kind, expr, var: Var::new("k").unwrap(), lower, upper };
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    series.evaluate(&FUNCTION_TABLE, simplifier.as_ref(), CalculationMode::default())
+  }
+
+  #[test]
+  fn test_empty_series() {
+    assert_eq!(evaluate(SeriesKind::Sum, k(), 5, 4).unwrap(), Expr::zero());
+    assert_eq!(evaluate(SeriesKind::Product, k(), 5, 4).unwrap(), Expr::one());
+  }
+
+  #[test]
+  fn test_constant_series() {
+    let x = Expr::var("x").unwrap();
+    assert_eq!(
+      evaluate(SeriesKind::Sum, x.clone(), 1, 10).unwrap(),
+      Expr::call("*", vec![Expr::from(10), x.clone()]),
+    );
+    assert_eq!(
+      evaluate(SeriesKind::Product, x.clone(), 1, 10).unwrap(),
+      Expr::call("^", vec![x, Expr::from(10)]),
+    );
+  }
+
+  #[test]
+  fn test_arithmetic_sum() {
+    // Far too many terms to expand.
+    let expr = Expr::call("+", vec![Expr::call("*", vec![Expr::from(2), k()]), Expr::from(1)]);
+    assert_eq!(evaluate(SeriesKind::Sum, expr, 0, 999_999).unwrap(), Expr::from(1_000_000_000_000i64));
+  }
+
+  #[test]
+  fn test_geometric_sum() {
+    let expr = Expr::call("^", vec![Expr::from(2), k()]);
+    assert_eq!(evaluate(SeriesKind::Sum, expr, 0, 62).unwrap(), Expr::from(i64::MAX));
+  }
+
+  #[test]
+  fn test_product_of_powers() {
+    // prod x^k for k = 1..100000 = x^(100000 * 100001 / 2)
+    let expr = Expr::call("^", vec![Expr::var("x").unwrap(), k()]);
+    assert_eq!(
+      evaluate(SeriesKind::Product, expr, 1, 100_000).unwrap(),
+      Expr::call("^", vec![Expr::var("x").unwrap(), Expr::from(5_000_050_000i64)]),
+    );
+  }
+
+  #[test]
+  fn test_expanded_series() {
+    let expr = Expr::call("^", vec![k(), Expr::from(2)]);
+    assert_eq!(evaluate(SeriesKind::Sum, expr, 1, 10).unwrap(), Expr::from(385));
+    assert_eq!(evaluate(SeriesKind::Product, k(), 1, 10).unwrap(), Expr::from(3_628_800));
+  }
+
+  #[test]
+  fn test_too_many_terms() {
+    let expr = Expr::call("^", vec![k(), Expr::from(2)]);
+    assert!(matches!(
+      evaluate(SeriesKind::Sum, expr, 1, 1_000_000),
+      Err(SeriesError::TooManyTerms(1_000_000)),
+    ));
+  }
+}
@@ -0,0 +1,220 @@
+
+//! User-declared assumptions about variables, which the simplifier
+//! may consult to justify simplifications that are not valid in
+//! general.
+
+use super::Expr;
+use super::atom::Atom;
+use super::number::NumberRepr;
+use super::var::Var;
+use crate::util::prism::Prism;
+
+use num::Zero;
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A property which a variable can be declared to have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Assumption {
+  /// The variable is a real number.
+  Real,
+  /// The variable is an integer. Implies [`Assumption::Real`].
+  Integer,
+  /// The variable is a real number greater than or equal to zero.
+  Nonnegative,
+  /// The variable is a real number strictly greater than zero.
+  /// Implies [`Assumption::Nonnegative`] and [`Assumption::Nonzero`].
+  Positive,
+  /// The variable is not zero.
+  Nonzero,
+}
+
+/// Prism which parses a string as an [`Assumption`] name.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToAssumption;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("Unknown assumption '{0}'")]
+pub struct ParseAssumptionError(String);
+
+/// The assumptions declared for each variable. Variables with no
+/// declared assumptions are not present in the table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssumptionTable {
+  table: BTreeMap<Var, BTreeSet<Assumption>>,
+}
+
+impl Assumption {
+  pub const ALL: [Assumption; 5] = [
+    Assumption::Real,
+    Assumption::Integer,
+    Assumption::Nonnegative,
+    Assumption::Positive,
+    Assumption::Nonzero,
+  ];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Assumption::Real => "real",
+      Assumption::Integer => "integer",
+      Assumption::Nonnegative => "nonnegative",
+      Assumption::Positive => "positive",
+      Assumption::Nonzero => "nonzero",
+    }
+  }
+
+  /// Whether a variable with this property necessarily has property
+  /// `other` as well. Every assumption implies itself.
+  pub fn implies(self, other: Assumption) -> bool {
+    use Assumption::*;
+    match (self, other) {
+      (a, b) if a == b => true,
+      (Integer | Nonnegative | Positive, Real) => true,
+      (Positive, Nonnegative | Nonzero) => true,
+      _ => false,
+    }
+  }
+}
+
+impl AssumptionTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.table.is_empty()
+  }
+
+  /// The assumptions declared directly on `var`, not counting those
+  /// which are merely implied.
+  pub fn get(&self, var: &Var) -> BTreeSet<Assumption> {
+    self.table.get(var).cloned().unwrap_or_default()
+  }
+
+  /// Replaces the assumptions declared on `var`, returning the old
+  /// set.
+  pub fn set(&mut self, var: Var, assumptions: BTreeSet<Assumption>) -> BTreeSet<Assumption> {
+    let old = if assumptions.is_empty() {
+      self.table.remove(&var)
+    } else {
+      self.table.insert(var, assumptions)
+    };
+    old.unwrap_or_default()
+  }
+
+  /// Whether `var` has the given property, either directly or as a
+  /// consequence of some other declared property.
+  pub fn holds(&self, var: &Var, assumption: Assumption) -> bool {
+    self.table.get(var).is_some_and(|declared| {
+      declared.iter().any(|d| d.implies(assumption))
+    })
+  }
+
+  /// Whether `expr` is known to have the given property. Real number
+  /// literals are checked directly, and variables are checked against
+  /// the table. No other expressions are known to have any property.
+  pub fn expr_satisfies(&self, expr: &Expr, assumption: Assumption) -> bool {
+    match expr {
+      Expr::Atom(Atom::Var(var)) => self.holds(var, assumption),
+      Expr::Atom(Atom::Number(n)) => match assumption {
+        Assumption::Real => true,
+        Assumption::Integer => n.repr() == NumberRepr::Integer,
+        Assumption::Nonnegative => !n.is_negative(),
+        Assumption::Positive => n.is_positive(),
+        Assumption::Nonzero => !n.is_zero(),
+      },
+      _ => false,
+    }
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (&Var, &BTreeSet<Assumption>)> {
+    self.table.iter()
+  }
+}
+
+impl Display for Assumption {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+impl FromStr for Assumption {
+  type Err = ParseAssumptionError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Assumption::ALL.into_iter()
+      .find(|assumption| assumption.name() == s)
+      .ok_or_else(|| ParseAssumptionError(s.to_owned()))
+  }
+}
+
+impl Prism<String, Assumption> for StringToAssumption {
+  fn narrow_type(&self, input: String) -> Result<Assumption, String> {
+    Assumption::from_str(&input).map_err(|_| input)
+  }
+
+  fn widen_type(&self, assumption: Assumption) -> String {
+    assumption.name().to_owned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> Var {
+    Var::new(name).unwrap()
+  }
+
+  #[test]
+  fn test_parse_assumption() {
+    for assumption in Assumption::ALL {
+      assert_eq!(assumption.name().parse::<Assumption>(), Ok(assumption));
+    }
+    assert!("complex".parse::<Assumption>().is_err());
+    assert_eq!(StringToAssumption.narrow_type("Real".to_owned()), Err("Real".to_owned()));
+  }
+
+  #[test]
+  fn test_implies() {
+    assert!(Assumption::Positive.implies(Assumption::Nonzero));
+    assert!(Assumption::Positive.implies(Assumption::Real));
+    assert!(Assumption::Integer.implies(Assumption::Real));
+    assert!(!Assumption::Integer.implies(Assumption::Nonnegative));
+    assert!(!Assumption::Nonzero.implies(Assumption::Real));
+    assert!(!Assumption::Real.implies(Assumption::Integer));
+  }
+
+  #[test]
+  fn test_table() {
+    let mut table = AssumptionTable::new();
+    assert!(!table.holds(&var("x"), Assumption::Real));
+    table.set(var("x"), BTreeSet::from([Assumption::Positive]));
+    assert!(table.holds(&var("x"), Assumption::Nonnegative));
+    assert!(!table.holds(&var("x"), Assumption::Integer));
+    assert!(!table.holds(&var("y"), Assumption::Real));
+    let old = table.set(var("x"), BTreeSet::new());
+    assert_eq!(old, BTreeSet::from([Assumption::Positive]));
+    assert!(table.is_empty());
+  }
+
+  #[test]
+  fn test_expr_satisfies() {
+    let mut table = AssumptionTable::new();
+    table.set(var("n"), BTreeSet::from([Assumption::Integer]));
+    assert!(table.expr_satisfies(&Expr::from(var("n")), Assumption::Real));
+    assert!(!table.expr_satisfies(&Expr::from(var("n")), Assumption::Nonnegative));
+    assert!(table.expr_satisfies(&Expr::from(0), Assumption::Nonnegative));
+    assert!(!table.expr_satisfies(&Expr::from(0), Assumption::Positive));
+    assert!(table.expr_satisfies(&Expr::from(-2), Assumption::Integer));
+    assert!(!table.expr_satisfies(&Expr::from(1.5), Assumption::Integer));
+    let call = Expr::call("f", vec![Expr::from(var("n"))]);
+    assert!(!table.expr_satisfies(&call, Assumption::Real));
+  }
+}
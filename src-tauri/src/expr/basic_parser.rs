@@ -3,7 +3,7 @@
 //! mode](crate::display::basic::BasicLanguageMode].
 
 use super::Expr;
-use super::number::{ComplexNumber, Quaternion};
+use super::number::{ComplexNumber, Quaternion, Octonion};
 use super::vector::Vector;
 use super::tokenizer::{ExprTokenizer, Token, TokenData, TokenizerError};
 use crate::parsing::shunting_yard::{self, ShuntingYardDriver, ShuntingYardError};
@@ -13,6 +13,7 @@ use crate::parsing::operator::chain::{self, Token as ChainToken, TaggedToken, Op
 use crate::parsing::operator::fixity::{InfixProperties, PrefixProperties, PostfixProperties};
 use crate::parsing::source::{Span, Spanned, SourceOffset};
 use crate::parsing::tokenizer::TokenizerState;
+use crate::mode::display::locale::Locale;
 
 use thiserror::Error;
 
@@ -42,7 +43,7 @@ pub enum ParsingError {
   ExpectedStartOfExpr(SourceOffset),
   #[error("Expected rest of argument list, got {0} at {1}")]
   ExpectedRestOfArgList(TokenData, SourceOffset),
-  #[error("Expected parenthesized expression or complex number at {0}")]
+  #[error("Expected parenthesized expression, complex number, quaternion, or octonion at {0}")]
   ExpectedParensOrComplex(SourceOffset),
   #[error("Expected closing bracket '{expected}', found '{actual}' at {offset}")]
   WrongClosingBracket { expected: TokenData, actual: TokenData, offset: SourceOffset },
@@ -67,6 +68,13 @@ impl<'a> ExprParser<'a> {
     }
   }
 
+  /// Configures this parser to read numbers and argument separators
+  /// according to the given locale.
+  pub fn with_locale(mut self, locale: Locale) -> Self {
+    self.tokenizer = self.tokenizer.with_locale(locale);
+    self
+  }
+
   pub fn tokenizer(&self) -> &ExprTokenizer<'a> {
     &self.tokenizer
   }
@@ -188,6 +196,11 @@ impl<'a> ExprParser<'a> {
             let expr = Expr::call(Quaternion::FUNCTION_NAME, vec![r, i, j, k]);
             Ok((Spanned::new(expr, span), tail))
           }
+          8 => {
+            // Octonion expression
+            let expr = Expr::call(Octonion::FUNCTION_NAME, args);
+            Ok((Spanned::new(expr, span), tail))
+          }
           _ => {
             Err(ParsingError::ExpectedParensOrComplex(token.span.start).into())
           }
@@ -353,6 +366,18 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_octonion_expr() {
+    let table = OperatorTable::common_operators();
+    let parser = ExprParser::new(&table);
+
+    let expr = parser.tokenize_and_parse("(1, 2, 3, 4, 5, 6, 7, 8)").unwrap();
+    assert_eq!(
+      expr,
+      Expr::call("oct", (1..=8).map(Expr::from).collect()),
+    );
+  }
+
   #[test]
   fn test_operator_sequence() {
     let table = OperatorTable::common_operators();
@@ -526,4 +551,23 @@ mod tests {
       ]),
     );
   }
+
+  #[test]
+  fn test_polar_literal_parse() {
+    let table = OperatorTable::common_operators();
+    let parser = ExprParser::new(&table);
+
+    let expr = parser.tokenize_and_parse("2∠30").unwrap();
+    assert_eq!(expr, Expr::call("∠", vec![Expr::from(2), Expr::from(30)]));
+    let expr = parser.tokenize_and_parse("-2 angle 30 + 1").unwrap();
+    assert_eq!(
+      expr,
+      Expr::call("+", vec![
+        Expr::call("negate", vec![
+          Expr::call("angle", vec![Expr::from(2), Expr::from(30)]),
+        ]),
+        Expr::from(1),
+      ]),
+    );
+  }
 }
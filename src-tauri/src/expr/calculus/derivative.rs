@@ -4,6 +4,7 @@ use crate::expr::atom::Atom;
 use crate::expr::var::Var;
 use crate::expr::function::table::FunctionTable;
 use crate::expr::simplifier::error::ArityError;
+use super::derivative_rules::DerivativeRuleTable;
 
 use thiserror::Error;
 
@@ -20,6 +21,7 @@ pub struct DerivativeEngine<'a> {
   target_variable: Var,
   original_expr: Expr,
   function_table: &'a FunctionTable,
+  derivative_rules: Option<&'a DerivativeRuleTable>,
 }
 
 /// A failure to differentiate a function successfully.
@@ -61,10 +63,12 @@ impl<'a> DerivativeEngine<'a> {
   pub fn differentiate(&self, expr: Expr) -> Result<Expr, DifferentiationFailure> {
     match expr {
       Expr::Call(function, args) => {
-        let Some(known_function) = self.function_table.get(&function) else {
-          return Err(self.error(DifferentiationError::UnknownDerivative(function)));
-        };
-        known_function.differentiate(args, self)
+        match self.function_table.get(&function) {
+          Some(known_function) if known_function.has_derivative_rule() => {
+            known_function.differentiate(args, self)
+          }
+          _ => self.differentiate_by_registered_rule(function, args),
+        }
       }
       Expr::Atom(Atom::Number(_) | Atom::String(_)) => {
         Ok(Expr::zero())
@@ -79,6 +83,34 @@ impl<'a> DerivativeEngine<'a> {
     }
   }
 
+  /// Differentiates a call to a function which has no built-in
+  /// derivative, using the chain rule with the partial derivatives
+  /// registered in the derivative rule table. Only the partial
+  /// derivatives with respect to arguments which actually depend on
+  /// the target variable are required.
+  fn differentiate_by_registered_rule(&self, function: String, args: Vec<Expr>) -> Result<Expr, DifferentiationFailure> {
+    let Some(rule) = self.derivative_rules.and_then(|table| table.get(&function)) else {
+      return Err(self.error(DifferentiationError::UnknownDerivative(function)));
+    };
+    let mut terms = Vec::new();
+    for (index, arg) in args.iter().enumerate() {
+      let arg_deriv = self.differentiate(arg.clone())?;
+      if arg_deriv == Expr::zero() {
+        continue;
+      }
+      let Some(partial) = rule.partial_at(index, &args) else {
+        return Err(self.error(DifferentiationError::UnknownDerivative(function)));
+      };
+      terms.push(Expr::call("*", vec![partial, arg_deriv]));
+    }
+    Ok(match terms.len() {
+      0 => Expr::zero(),
+      // unwrap: The vector has exactly one element.
+      1 => terms.pop().unwrap(),
+      _ => Expr::call("+", terms),
+    })
+  }
+
   /// Helper function which differentiates each argument in turn.
   /// Equivalent to calling [`DerivativeEngine::differentiate`] on
   /// each argument and compiling the results. Short-circuits out if
@@ -113,10 +145,23 @@ impl DifferentiationError {
 /// original (un-differentiated) expression can be recovered from
 /// `failure_object.original_expr`.
 pub fn differentiate(function_table: &FunctionTable, expr: Expr, var: Var) -> Result<Expr, DifferentiationFailure> {
+  differentiate_with_rules(function_table, None, expr, var)
+}
+
+/// As [`differentiate`], but functions with no built-in derivative
+/// are differentiated using the rules in `derivative_rules`, if
+/// given.
+pub fn differentiate_with_rules(
+  function_table: &FunctionTable,
+  derivative_rules: Option<&DerivativeRuleTable>,
+  expr: Expr,
+  var: Var,
+) -> Result<Expr, DifferentiationFailure> {
   let engine = DerivativeEngine {
     target_variable: var,
     original_expr: expr.clone(),
     function_table,
+    derivative_rules,
   };
   engine.differentiate(expr)
 }
@@ -0,0 +1,212 @@
+
+//! Derivative rules registered by the user at runtime, for functions
+//! which have no built-in derivative.
+
+use crate::expr::Expr;
+use crate::expr::algebra::formula::Equation;
+use crate::expr::function::user::{UserFunction, UserFunctionError};
+use crate::expr::var::Var;
+use crate::expr::var::table::VarTable;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::collections::HashMap;
+
+/// The known partial derivatives of a function, each written in
+/// terms of the function's parameters. A partial derivative which
+/// has not been registered is `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DerivativeRule {
+  params: Vec<Var>,
+  partials: Vec<Option<Expr>>,
+}
+
+/// A table of user-registered derivative rules, keyed by function
+/// name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DerivativeRuleTable {
+  rules: HashMap<String, DerivativeRule>,
+}
+
+/// A single partial derivative of a function, as parsed from an
+/// equation `f(x, y, ...) = body`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialDerivative {
+  pub function_name: String,
+  pub params: Vec<Var>,
+  pub param_index: usize,
+  pub body: Expr,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DerivativeRuleError {
+  #[error("{0}")]
+  InvalidDefinition(#[from] UserFunctionError),
+  #[error("{0} is not a parameter of the function")]
+  NotAParameter(Var),
+}
+
+impl DerivativeRule {
+  /// A rule for a function with the given parameters and no known
+  /// partial derivatives.
+  pub fn new(params: Vec<Var>) -> Self {
+    let partials = vec![None; params.len()];
+    Self { params, partials }
+  }
+
+  pub fn params(&self) -> &[Var] {
+    &self.params
+  }
+
+  pub fn arity(&self) -> usize {
+    self.params.len()
+  }
+
+  /// Sets the partial derivative with respect to the parameter at the
+  /// given index. Panics if the index is out of bounds.
+  pub fn set_partial(&mut self, index: usize, body: Expr) {
+    self.partials[index] = Some(body);
+  }
+
+  /// The partial derivative with respect to the parameter at the
+  /// given index, evaluated at the given arguments. Returns `None` if
+  /// that partial derivative is unknown or if the arity does not
+  /// match.
+  pub fn partial_at(&self, index: usize, args: &[Expr]) -> Option<Expr> {
+    if args.len() != self.arity() {
+      return None;
+    }
+    let body = self.partials.get(index)?.as_ref()?;
+    let bindings: VarTable<Expr> = self.params.iter().cloned().zip(args.iter().cloned()).collect();
+    Some(body.clone().substitute_vars(&bindings))
+  }
+}
+
+impl PartialDerivative {
+  /// Interprets an equation of the form `f(x, y, ...) = body` as the
+  /// partial derivative of `f` with respect to `var`, which must be
+  /// one of the parameters on the left-hand side.
+  pub fn from_equation(equation: Equation, var: &Var) -> Result<Self, DerivativeRuleError> {
+    // The left-hand side follows the same rules as a user function
+    // definition.
+    let (function_name, function) = UserFunction::from_equation(equation)?;
+    let Some(param_index) = function.params().iter().position(|param| param == var) else {
+      return Err(DerivativeRuleError::NotAParameter(var.clone()));
+    };
+    Ok(Self {
+      function_name,
+      params: function.params().to_vec(),
+      param_index,
+      body: function.body().clone(),
+    })
+  }
+}
+
+impl DerivativeRuleTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.rules.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rules.is_empty()
+  }
+
+  pub fn get(&self, name: &str) -> Option<&DerivativeRule> {
+    self.rules.get(name)
+  }
+
+  /// Sets the rule for the function, returning its previous rule (if
+  /// any).
+  pub fn insert(&mut self, name: String, rule: DerivativeRule) -> Option<DerivativeRule> {
+    self.rules.insert(name, rule)
+  }
+
+  pub fn remove(&mut self, name: &str) -> Option<DerivativeRule> {
+    self.rules.remove(name)
+  }
+
+  /// The rule which results from adding the given partial derivative
+  /// to the function's existing rule. If the existing rule was
+  /// written with different parameters, it is discarded.
+  pub fn with_partial(&self, partial: PartialDerivative) -> DerivativeRule {
+    let mut rule = match self.get(&partial.function_name) {
+      Some(rule) if rule.params == partial.params => rule.clone(),
+      _ => DerivativeRule::new(partial.params),
+    };
+    rule.set_partial(partial.param_index, partial.body);
+    rule
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> Var {
+    Var::new(name).unwrap()
+  }
+
+  fn partial(left: Expr, right: Expr, var_name: &str) -> Result<PartialDerivative, DerivativeRuleError> {
+    PartialDerivative::from_equation(Equation { left, right }, &var(var_name))
+  }
+
+  #[test]
+  fn test_partial_from_equation() {
+    let left = Expr::call("f", vec![Expr::from(var("x")), Expr::from(var("y"))]);
+    let partial = partial(left, Expr::from(var("y")), "y").unwrap();
+    assert_eq!(partial, PartialDerivative {
+      function_name: "f".to_owned(),
+      params: vec![var("x"), var("y")],
+      param_index: 1,
+      body: Expr::from(var("y")),
+    });
+  }
+
+  #[test]
+  fn test_partial_from_equation_errors() {
+    let left = Expr::call("f", vec![Expr::from(var("x"))]);
+    assert_eq!(partial(left, Expr::from(1), "y"), Err(DerivativeRuleError::NotAParameter(var("y"))));
+    assert_eq!(
+      partial(Expr::from(var("x")), Expr::from(1), "x"),
+      Err(DerivativeRuleError::InvalidDefinition(UserFunctionError::ExpectedFunctionCall(Expr::from(var("x"))))),
+    );
+  }
+
+  #[test]
+  fn test_with_partial_merges_matching_params() {
+    let mut table = DerivativeRuleTable::new();
+    let left = Expr::call("f", vec![Expr::from(var("x")), Expr::from(var("y"))]);
+    let rule = table.with_partial(partial(left.clone(), Expr::from(var("y")), "x").unwrap());
+    table.insert("f".to_owned(), rule);
+    let rule = table.with_partial(partial(left, Expr::from(var("x")), "y").unwrap());
+    let args = [Expr::from(2), Expr::from(3)];
+    assert_eq!(rule.partial_at(0, &args), Some(Expr::from(3)));
+    assert_eq!(rule.partial_at(1, &args), Some(Expr::from(2)));
+  }
+
+  #[test]
+  fn test_with_partial_replaces_mismatched_params() {
+    let mut table = DerivativeRuleTable::new();
+    let rule = table.with_partial(partial(Expr::call("f", vec![Expr::from(var("x"))]), Expr::from(1), "x").unwrap());
+    table.insert("f".to_owned(), rule);
+    let left = Expr::call("f", vec![Expr::from(var("s")), Expr::from(var("t"))]);
+    let rule = table.with_partial(partial(left, Expr::from(var("s")), "t").unwrap());
+    let args = [Expr::from(2), Expr::from(3)];
+    assert_eq!(rule.partial_at(0, &args), None);
+    assert_eq!(rule.partial_at(1, &args), Some(Expr::from(2)));
+  }
+
+  #[test]
+  fn test_partial_at_wrong_arity() {
+    let mut rule = DerivativeRule::new(vec![var("x")]);
+    rule.set_partial(0, Expr::from(var("x")));
+    assert_eq!(rule.partial_at(0, &[]), None);
+    assert_eq!(rule.partial_at(0, &[Expr::from(5)]), Some(Expr::from(5)));
+  }
+}
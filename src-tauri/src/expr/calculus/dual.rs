@@ -0,0 +1,443 @@
+
+//! Forward-mode automatic differentiation, via dual numbers.
+//!
+//! A [`DualFunction`] is an expression compiled into a small tree of
+//! numerical operations over a single variable. Evaluating it at a
+//! point produces a [`DualNumber`] carrying both the value of the
+//! function and the exact (up to floating-point rounding) value of
+//! its derivative at that point. Unlike symbolic differentiation,
+//! this never builds an intermediate derivative expression, and
+//! unlike finite differences, it introduces no truncation error.
+
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::var::Var;
+use crate::expr::number::ComplexNumber;
+use crate::expr::prisms::ExprToComplex;
+use crate::util::prism::Prism;
+
+use num::complex::Complex64;
+use num::{Zero, One};
+use thiserror::Error;
+
+use std::f64::consts::{PI, E};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+/// A dual number `value + deriv * ε`, where `ε² = 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DualNumber {
+  pub value: Complex64,
+  pub deriv: Complex64,
+}
+
+/// An expression compiled for repeated numerical evaluation (and
+/// differentiation) with respect to a single variable.
+#[derive(Debug, Clone)]
+pub struct DualFunction {
+  root: Node,
+}
+
+/// An error while compiling an expression into a [`DualFunction`].
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum DualCompileError {
+  #[error("Function '{0}' is not supported by automatic differentiation")]
+  UnsupportedFunction(String),
+  #[error("Free variable '{0}' in automatic differentiation")]
+  FreeVariable(Var),
+  #[error("Non-numerical value in automatic differentiation")]
+  NonNumerical,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+  Constant(Complex64),
+  Variable,
+  Unary(UnaryOp, Box<Node>),
+  Sum(Vec<Node>),
+  Product(Vec<Node>),
+  Difference(Box<Node>, Box<Node>),
+  Quotient(Box<Node>, Box<Node>),
+  Power(Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UnaryOp {
+  Negate, Recip, Ln, Exp, Sqrt,
+  Sin, Cos, Tan, Sinh, Cosh, Tanh,
+  Asin, Acos, Atan,
+}
+
+impl DualNumber {
+  pub fn constant(value: Complex64) -> Self {
+    Self { value, deriv: Complex64::zero() }
+  }
+
+  /// The identity function's value at `value`, i.e. a dual number
+  /// whose derivative component is one.
+  pub fn variable(value: Complex64) -> Self {
+    Self { value, deriv: Complex64::one() }
+  }
+
+  /// Applies a scalar function `f` with known derivative `df` via
+  /// the chain rule.
+  fn chain(self, f: impl FnOnce(Complex64) -> Complex64, df: impl FnOnce(Complex64) -> Complex64) -> Self {
+    Self { value: f(self.value), deriv: df(self.value) * self.deriv }
+  }
+
+  pub fn recip(self) -> Self {
+    self.chain(|x| x.inv(), |x| - (x * x).inv())
+  }
+
+  pub fn ln(self) -> Self {
+    self.chain(|x| x.ln(), |x| x.inv())
+  }
+
+  pub fn exp(self) -> Self {
+    self.chain(|x| x.exp(), |x| x.exp())
+  }
+
+  pub fn sqrt(self) -> Self {
+    self.chain(|x| x.sqrt(), |x| (x.sqrt() * 2.0).inv())
+  }
+
+  pub fn sin(self) -> Self {
+    self.chain(|x| x.sin(), |x| x.cos())
+  }
+
+  pub fn cos(self) -> Self {
+    self.chain(|x| x.cos(), |x| - x.sin())
+  }
+
+  pub fn tan(self) -> Self {
+    self.chain(|x| x.tan(), |x| (x.cos() * x.cos()).inv())
+  }
+
+  pub fn sinh(self) -> Self {
+    self.chain(|x| x.sinh(), |x| x.cosh())
+  }
+
+  pub fn cosh(self) -> Self {
+    self.chain(|x| x.cosh(), |x| x.sinh())
+  }
+
+  pub fn tanh(self) -> Self {
+    self.chain(|x| x.tanh(), |x| (x.cosh() * x.cosh()).inv())
+  }
+
+  pub fn asin(self) -> Self {
+    self.chain(|x| x.asin(), |x| (Complex64::one() - x * x).sqrt().inv())
+  }
+
+  pub fn acos(self) -> Self {
+    self.chain(|x| x.acos(), |x| - (Complex64::one() - x * x).sqrt().inv())
+  }
+
+  pub fn atan(self) -> Self {
+    self.chain(|x| x.atan(), |x| (Complex64::one() + x * x).inv())
+  }
+
+  pub fn pow(self, exp: DualNumber) -> Self {
+    if exp.deriv.is_zero() {
+      // Constant exponent; use the power rule, which (unlike the
+      // general case) is well-defined at a zero base.
+      let n = exp.value;
+      let (value, lowered) = if let Some(k) = small_integer(n) {
+        (self.value.powi(k), self.value.powi(k - 1))
+      } else {
+        (self.value.powc(n), self.value.powc(n - 1.0))
+      };
+      Self { value, deriv: n * lowered * self.deriv }
+    } else {
+      // d/dx u^v = u^v (v' ln u + v u' / u)
+      let value = self.value.powc(exp.value);
+      let deriv = value * (exp.deriv * self.value.ln() + exp.value * self.deriv / self.value);
+      Self { value, deriv }
+    }
+  }
+}
+
+fn small_integer(z: Complex64) -> Option<i32> {
+  if z.im == 0.0 && z.re.fract() == 0.0 && z.re.abs() < i32::MAX as f64 {
+    Some(z.re as i32)
+  } else {
+    None
+  }
+}
+
+impl Add for DualNumber {
+  type Output = DualNumber;
+
+  fn add(self, other: DualNumber) -> DualNumber {
+    DualNumber { value: self.value + other.value, deriv: self.deriv + other.deriv }
+  }
+}
+
+impl Sub for DualNumber {
+  type Output = DualNumber;
+
+  fn sub(self, other: DualNumber) -> DualNumber {
+    DualNumber { value: self.value - other.value, deriv: self.deriv - other.deriv }
+  }
+}
+
+impl Mul for DualNumber {
+  type Output = DualNumber;
+
+  fn mul(self, other: DualNumber) -> DualNumber {
+    DualNumber {
+      value: self.value * other.value,
+      deriv: self.deriv * other.value + self.value * other.deriv,
+    }
+  }
+}
+
+impl Div for DualNumber {
+  type Output = DualNumber;
+
+  fn div(self, other: DualNumber) -> DualNumber {
+    DualNumber {
+      value: self.value / other.value,
+      deriv: (self.deriv * other.value - self.value * other.deriv) / (other.value * other.value),
+    }
+  }
+}
+
+impl Neg for DualNumber {
+  type Output = DualNumber;
+
+  fn neg(self) -> DualNumber {
+    DualNumber { value: - self.value, deriv: - self.deriv }
+  }
+}
+
+impl DualFunction {
+  /// Compiles `expr`, as a function of `var`, for automatic
+  /// differentiation. Fails if the expression contains any function
+  /// whose derivative is not known to this module, or any variable
+  /// other than `var` and the well-known numerical constants.
+  pub fn compile(expr: &Expr, var: &Var) -> Result<Self, DualCompileError> {
+    Ok(Self { root: compile_node(expr, var)? })
+  }
+
+  /// Evaluates the function and its derivative at the given point.
+  pub fn eval(&self, at: Complex64) -> DualNumber {
+    self.root.eval(DualNumber::variable(at))
+  }
+}
+
+impl Node {
+  fn eval(&self, x: DualNumber) -> DualNumber {
+    match self {
+      Node::Constant(c) => DualNumber::constant(*c),
+      Node::Variable => x,
+      Node::Unary(op, arg) => op.apply(arg.eval(x)),
+      Node::Sum(args) => args.iter().fold(DualNumber::constant(Complex64::zero()), |acc, arg| acc + arg.eval(x)),
+      Node::Product(args) => args.iter().fold(DualNumber::constant(Complex64::one()), |acc, arg| acc * arg.eval(x)),
+      Node::Difference(a, b) => a.eval(x) - b.eval(x),
+      Node::Quotient(a, b) => a.eval(x) / b.eval(x),
+      Node::Power(a, b) => a.eval(x).pow(b.eval(x)),
+    }
+  }
+}
+
+impl UnaryOp {
+  fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "negate" => Some(UnaryOp::Negate),
+      "recip" => Some(UnaryOp::Recip),
+      "ln" => Some(UnaryOp::Ln),
+      "exp" => Some(UnaryOp::Exp),
+      "sqrt" => Some(UnaryOp::Sqrt),
+      "sin" => Some(UnaryOp::Sin),
+      "cos" => Some(UnaryOp::Cos),
+      "tan" => Some(UnaryOp::Tan),
+      "sinh" => Some(UnaryOp::Sinh),
+      "cosh" => Some(UnaryOp::Cosh),
+      "tanh" => Some(UnaryOp::Tanh),
+      "asin" => Some(UnaryOp::Asin),
+      "acos" => Some(UnaryOp::Acos),
+      "atan" => Some(UnaryOp::Atan),
+      _ => None,
+    }
+  }
+
+  fn apply(self, x: DualNumber) -> DualNumber {
+    match self {
+      UnaryOp::Negate => - x,
+      UnaryOp::Recip => x.recip(),
+      UnaryOp::Ln => x.ln(),
+      UnaryOp::Exp => x.exp(),
+      UnaryOp::Sqrt => x.sqrt(),
+      UnaryOp::Sin => x.sin(),
+      UnaryOp::Cos => x.cos(),
+      UnaryOp::Tan => x.tan(),
+      UnaryOp::Sinh => x.sinh(),
+      UnaryOp::Cosh => x.cosh(),
+      UnaryOp::Tanh => x.tanh(),
+      UnaryOp::Asin => x.asin(),
+      UnaryOp::Acos => x.acos(),
+      UnaryOp::Atan => x.atan(),
+    }
+  }
+}
+
+fn compile_node(expr: &Expr, var: &Var) -> Result<Node, DualCompileError> {
+  if let Ok(z) = ExprToComplex.narrow_type(expr.clone()) {
+    let (re, im) = ComplexNumber::from(z).into_parts();
+    return Ok(Node::Constant(Complex64::new(re.to_f64_or_nan(), im.to_f64_or_nan())));
+  }
+  match expr {
+    Expr::Atom(Atom::Var(v)) => {
+      if v == var {
+        Ok(Node::Variable)
+      } else {
+        constant_value(v).map(Node::Constant).ok_or_else(|| DualCompileError::FreeVariable(v.clone()))
+      }
+    }
+    Expr::Atom(_) => Err(DualCompileError::NonNumerical),
+    Expr::Call(name, args) => {
+      let compiled = args.iter()
+        .map(|arg| compile_node(arg, var))
+        .collect::<Result<Vec<_>, _>>()?;
+      compile_call(name, compiled)
+    }
+  }
+}
+
+fn compile_call(name: &str, mut args: Vec<Node>) -> Result<Node, DualCompileError> {
+  let unsupported = || DualCompileError::UnsupportedFunction(name.to_owned());
+  match (name, args.len()) {
+    ("+", _) => Ok(Node::Sum(args)),
+    ("*", _) => Ok(Node::Product(args)),
+    ("-" | "/" | "^", 2) => {
+      let b = Box::new(args.pop().unwrap());
+      let a = Box::new(args.pop().unwrap());
+      Ok(match name {
+        "-" => Node::Difference(a, b),
+        "/" => Node::Quotient(a, b),
+        _ => Node::Power(a, b),
+      })
+    }
+    (_, 1) => {
+      let op = UnaryOp::from_name(name).ok_or_else(unsupported)?;
+      Ok(Node::Unary(op, Box::new(args.pop().unwrap())))
+    }
+    _ => Err(unsupported()),
+  }
+}
+
+fn constant_value(var: &Var) -> Option<Complex64> {
+  match var.as_str() {
+    "pi" => Some(Complex64::from(PI)),
+    "e" => Some(Complex64::from(E)),
+    "i" => Some(Complex64::i()),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::number::Number;
+
+  use approx::assert_abs_diff_eq;
+
+  fn var(name: &str) -> Var {
+    Var::new(name).unwrap()
+  }
+
+  fn compile(expr: Expr) -> DualFunction {
+    DualFunction::compile(&expr, &var("x")).unwrap()
+  }
+
+  fn assert_dual_eq(actual: DualNumber, value: f64, deriv: f64) {
+    assert_abs_diff_eq!(actual.value.re, value, epsilon = 1e-9);
+    assert_abs_diff_eq!(actual.value.im, 0.0, epsilon = 1e-9);
+    assert_abs_diff_eq!(actual.deriv.re, deriv, epsilon = 1e-9);
+    assert_abs_diff_eq!(actual.deriv.im, 0.0, epsilon = 1e-9);
+  }
+
+  #[test]
+  fn test_polynomial() {
+    // x^3 - 2x + 1
+    let expr = Expr::call("+", vec![
+      Expr::call("-", vec![
+        Expr::call("^", vec![Expr::var("x").unwrap(), Expr::from(3)]),
+        Expr::call("*", vec![Expr::from(2), Expr::var("x").unwrap()]),
+      ]),
+      Expr::from(1),
+    ]);
+    let f = compile(expr);
+    assert_dual_eq(f.eval(Complex64::from(2.0)), 5.0, 10.0);
+    assert_dual_eq(f.eval(Complex64::from(0.0)), 1.0, -2.0);
+  }
+
+  #[test]
+  fn test_product_and_chain_rule() {
+    // x * sin(x^2)
+    let expr = Expr::call("*", vec![
+      Expr::var("x").unwrap(),
+      Expr::call("sin", vec![Expr::call("^", vec![Expr::var("x").unwrap(), Expr::from(2)])]),
+    ]);
+    let f = compile(expr);
+    let x = 1.5f64;
+    let expected_deriv = (x * x).sin() + 2.0 * x * x * (x * x).cos();
+    assert_dual_eq(f.eval(Complex64::from(x)), x * (x * x).sin(), expected_deriv);
+  }
+
+  #[test]
+  fn test_quotient_and_constants() {
+    // exp(x) / (x + pi)
+    let expr = Expr::call("/", vec![
+      Expr::call("exp", vec![Expr::var("x").unwrap()]),
+      Expr::call("+", vec![Expr::var("x").unwrap(), Expr::var("pi").unwrap()]),
+    ]);
+    let f = compile(expr);
+    let x = 0.5f64;
+    let expected_deriv = x.exp() * (x + PI - 1.0) / ((x + PI) * (x + PI));
+    assert_dual_eq(f.eval(Complex64::from(x)), x.exp() / (x + PI), expected_deriv);
+  }
+
+  #[test]
+  fn test_variable_exponent() {
+    // x^x
+    let expr = Expr::call("^", vec![Expr::var("x").unwrap(), Expr::var("x").unwrap()]);
+    let f = compile(expr);
+    let x = 2.0f64;
+    assert_dual_eq(f.eval(Complex64::from(x)), 4.0, 4.0 * (x.ln() + 1.0));
+  }
+
+  #[test]
+  fn test_power_at_zero_base() {
+    let expr = Expr::call("^", vec![Expr::var("x").unwrap(), Expr::from(2)]);
+    let f = compile(expr);
+    assert_dual_eq(f.eval(Complex64::zero()), 0.0, 0.0);
+  }
+
+  #[test]
+  fn test_complex_input() {
+    // x^2 at x = i, derivative 2i
+    let expr = Expr::call("^", vec![Expr::var("x").unwrap(), Expr::from(2)]);
+    let f = compile(expr);
+    let result = f.eval(Complex64::i());
+    assert_abs_diff_eq!(result.value.re, -1.0, epsilon = 1e-9);
+    assert_abs_diff_eq!(result.deriv.im, 2.0, epsilon = 1e-9);
+  }
+
+  #[test]
+  fn test_compile_failures() {
+    let expr = Expr::call("gamma", vec![Expr::var("x").unwrap()]);
+    assert!(matches!(
+      DualFunction::compile(&expr, &var("x")),
+      Err(DualCompileError::UnsupportedFunction(name)) if name == "gamma",
+    ));
+    let expr = Expr::call("+", vec![Expr::var("x").unwrap(), Expr::var("y").unwrap()]);
+    assert!(matches!(
+      DualFunction::compile(&expr, &var("x")),
+      Err(DualCompileError::FreeVariable(v)) if v == var("y"),
+    ));
+    let expr = Expr::call("+", vec![Expr::var("x").unwrap(), Expr::from(Number::from(1))]);
+    assert!(DualFunction::compile(&expr, &var("x")).is_ok());
+  }
+}
@@ -0,0 +1,260 @@
+
+use super::derivative::differentiate;
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::var::Var;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::simplifier::error::ArityError;
+
+use thiserror::Error;
+
+/// An `IntegrationEngine` is an engine for recursively computing
+/// antiderivatives, in terms of the arguments to a function. Like
+/// [`DerivativeEngine`](super::DerivativeEngine), callers outside
+/// this module can never construct `IntegrationEngine` instances.
+/// They are only used during the recursive descent of the
+/// [`integrate`] function.
+#[derive(Debug)]
+pub struct IntegrationEngine<'a> {
+  target_variable: Var,
+  original_expr: Expr,
+  function_table: &'a FunctionTable,
+}
+
+/// A failure to integrate a function successfully.
+///
+/// Constructed by [`IntegrationEngine::error`].
+#[derive(Debug, Clone)]
+pub struct IntegrationFailure {
+  /// The original top-level expression that we attempted to
+  /// integrate. This is NOT the specific expression where the error
+  /// occurred; it's the whole expression that the [`integrate`] call
+  /// was originally made on.
+  pub original_expr: Expr,
+  /// The reason for the failure.
+  pub error: IntegrationError,
+  _priv: (), // Prevent construction outside of this module
+}
+
+/// An error during integration.
+#[derive(Debug, Clone, Error)]
+pub enum IntegrationError {
+  #[error("{0}")]
+  CustomError(String),
+  #[error("Antiderivative of function '{0}' is not known")]
+  UnknownIntegral(String),
+  #[error("Antiderivative of function '{0}' is only known for linear arguments")]
+  NonLinearArgument(String),
+  #[error("Arity error on function '{0}': {1}")]
+  ArityError(String, ArityError),
+}
+
+impl<'a> IntegrationEngine<'a> {
+  pub fn target_variable(&self) -> &Var {
+    &self.target_variable
+  }
+
+  /// Attempts to recursively integrate a sub-expression.
+  ///
+  /// If you're looking to start an integration process, use the
+  /// module-level function [`integrate`]. This method is only for
+  /// recursive descent of the integration process.
+  pub fn integrate(&self, expr: Expr) -> Result<Expr, IntegrationFailure> {
+    if self.is_constant(&expr) {
+      return Ok(Expr::call("*", vec![expr, self.target_expr()]));
+    }
+    match expr {
+      Expr::Call(function, args) => {
+        let Some(known_function) = self.function_table.get(&function) else {
+          return Err(self.error(IntegrationError::UnknownIntegral(function)));
+        };
+        known_function.integrate(args, self)
+      }
+      Expr::Atom(Atom::Var(_)) => {
+        // Not constant, so it must be the target variable.
+        Ok(Expr::call("/", vec![
+          Expr::call("^", vec![expr, Expr::from(2)]),
+          Expr::from(2),
+        ]))
+      }
+      Expr::Atom(Atom::Number(_) | Atom::String(_)) => {
+        unreachable!("Atomic literals are always constant")
+      }
+    }
+  }
+
+  /// Helper function which integrates each argument in turn.
+  /// Equivalent to calling [`IntegrationEngine::integrate`] on each
+  /// argument and compiling the results. Short-circuits out if any
+  /// errors are encountered.
+  pub fn integrate_each(&self, exprs: Vec<Expr>) -> Result<Vec<Expr>, IntegrationFailure> {
+    exprs.into_iter()
+      .map(|expr| self.integrate(expr))
+      .collect()
+  }
+
+  /// Returns true if the expression does not depend on the target
+  /// variable.
+  pub fn is_constant(&self, expr: &Expr) -> bool {
+    match expr {
+      Expr::Atom(Atom::Var(v)) => v != &self.target_variable,
+      Expr::Atom(_) => true,
+      Expr::Call(_, args) => args.iter().all(|arg| self.is_constant(arg)),
+    }
+  }
+
+  /// Integrates `f(arg)`, where `arg` is a linear function of the
+  /// target variable, given an antiderivative `antiderivative(u)` of
+  /// `f(u)`. If `arg` is not linear, produces an appropriate error
+  /// naming `function_name`.
+  pub fn integrate_linear_substitution(
+    &self,
+    function_name: &str,
+    arg: Expr,
+    antiderivative: impl FnOnce(Expr) -> Expr,
+  ) -> Result<Expr, IntegrationFailure> {
+    let non_linear = || self.error(IntegrationError::NonLinearArgument(function_name.to_owned()));
+    let slope = differentiate(self.function_table, arg.clone(), self.target_variable.clone())
+      .map_err(|_| non_linear())?;
+    if !self.is_constant(&slope) || slope.is_zero() {
+      return Err(non_linear());
+    }
+    let result = antiderivative(arg);
+    if slope.is_one() {
+      Ok(result)
+    } else {
+      Ok(Expr::call("/", vec![result, slope]))
+    }
+  }
+
+  /// Produces an [`IntegrationFailure`] for a failure that occurred
+  /// during the process this `IntegrationEngine` was responsible
+  /// for.
+  pub fn error(&self, reason: IntegrationError) -> IntegrationFailure {
+    IntegrationFailure {
+      original_expr: self.original_expr.clone(),
+      error: reason,
+      _priv: (),
+    }
+  }
+
+  fn target_expr(&self) -> Expr {
+    Expr::Atom(Atom::Var(self.target_variable.clone()))
+  }
+}
+
+impl IntegrationError {
+  pub fn custom_error(msg: &str) -> IntegrationError {
+    IntegrationError::CustomError(msg.to_string())
+  }
+}
+
+/// Computes an antiderivative of the expression in terms of the given
+/// variable, given a table of known functions. The constant of
+/// integration is always taken to be zero. Returns the antiderivative
+/// or an [`IntegrationFailure`]. As with [`differentiate`], the
+/// original expression can be recovered from
+/// `failure_object.original_expr` in the latter case.
+pub fn integrate(function_table: &FunctionTable, expr: Expr, var: Var) -> Result<Expr, IntegrationFailure> {
+  let engine = IntegrationEngine {
+    target_variable: var,
+    original_expr: expr.clone(),
+    function_table,
+  };
+  engine.integrate(expr)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::calculus::DualFunction;
+  use crate::expr::function::library::build_function_table;
+
+  use num::complex::Complex64;
+  use once_cell::sync::Lazy;
+
+  static FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(build_function_table);
+
+  fn x() -> Expr {
+    Expr::var("x").unwrap()
+  }
+
+  /// Checks that the derivative of the computed antiderivative
+  /// agrees with the original expression at a few sample points.
+  fn assert_antiderivative(expr: Expr) {
+    let var = Var::new("x").unwrap();
+    let antiderivative = integrate(&FUNCTION_TABLE, expr.clone(), var.clone()).unwrap();
+    let original = DualFunction::compile(&expr, &var).unwrap();
+    let antiderivative = DualFunction::compile(&antiderivative, &var).unwrap();
+    for point in [0.3, 0.7, 1.9] {
+      let point = Complex64::from(point);
+      let expected = original.eval(point).value;
+      let actual = antiderivative.eval(point).deriv;
+      assert!((expected - actual).norm() < 1e-9, "At {point}: expected {expected}, got {actual}");
+    }
+  }
+
+  #[test]
+  fn test_integrate_polynomial() {
+    // 3x^2 + 2x - 5
+    assert_antiderivative(Expr::call("-", vec![
+      Expr::call("+", vec![
+        Expr::call("*", vec![Expr::from(3), Expr::call("^", vec![x(), Expr::from(2)])]),
+        Expr::call("*", vec![Expr::from(2), x()]),
+      ]),
+      Expr::from(5),
+    ]));
+  }
+
+  #[test]
+  fn test_integrate_reciprocal_power() {
+    assert_antiderivative(Expr::call("^", vec![x(), Expr::from(-1)]));
+    assert_antiderivative(Expr::call("/", vec![Expr::from(4), x()]));
+    assert_antiderivative(Expr::call("recip", vec![Expr::call("+", vec![x(), Expr::from(1)])]));
+  }
+
+  #[test]
+  fn test_integrate_linear_substitution() {
+    // sin(2x + 1) + exp(3x) - cos(x / 2)
+    let expr = Expr::call("-", vec![
+      Expr::call("+", vec![
+        Expr::call("sin", vec![Expr::call("+", vec![Expr::call("*", vec![Expr::from(2), x()]), Expr::from(1)])]),
+        Expr::call("exp", vec![Expr::call("*", vec![Expr::from(3), x()])]),
+      ]),
+      Expr::call("cos", vec![Expr::call("/", vec![x(), Expr::from(2)])]),
+    ]);
+    assert_antiderivative(expr);
+  }
+
+  #[test]
+  fn test_integrate_transcendental() {
+    for name in ["ln", "exp", "sqrt", "sin", "cos", "tan", "sinh", "cosh", "tanh"] {
+      assert_antiderivative(Expr::call(name, vec![x()]));
+    }
+    assert_antiderivative(Expr::call("^", vec![Expr::from(2), x()]));
+  }
+
+  #[test]
+  fn test_integrate_constant() {
+    let var = Var::new("x").unwrap();
+    let result = integrate(&FUNCTION_TABLE, Expr::var("y").unwrap(), var).unwrap();
+    assert_eq!(result, Expr::call("*", vec![Expr::var("y").unwrap(), x()]));
+  }
+
+  #[test]
+  fn test_integrate_failures() {
+    let var = Var::new("x").unwrap();
+    let expr = Expr::call("sin", vec![Expr::call("^", vec![x(), Expr::from(2)])]);
+    let failure = integrate(&FUNCTION_TABLE, expr.clone(), var.clone()).unwrap_err();
+    assert!(matches!(failure.error, IntegrationError::NonLinearArgument(name) if name == "sin"));
+    assert_eq!(failure.original_expr, expr);
+
+    let expr = Expr::call("*", vec![x(), Expr::call("sin", vec![x()])]);
+    let failure = integrate(&FUNCTION_TABLE, expr, var.clone()).unwrap_err();
+    assert!(matches!(failure.error, IntegrationError::CustomError(_)));
+
+    let expr = Expr::call("gamma", vec![x()]);
+    let failure = integrate(&FUNCTION_TABLE, expr, var).unwrap_err();
+    assert!(matches!(failure.error, IntegrationError::UnknownIntegral(name) if name == "gamma"));
+  }
+}
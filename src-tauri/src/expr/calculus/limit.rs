@@ -0,0 +1,440 @@
+
+use super::derivative::differentiate;
+use crate::errorlist::ErrorList;
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::var::Var;
+use crate::expr::algebra::infinity::{InfiniteConstant, ExprToInfinity, Hyperreal, is_infinite_constant,
+                                     contains_infinitesimal, standard_part};
+use crate::expr::function::table::FunctionTable;
+use crate::expr::simplifier::{Simplifier, SimplifierContext};
+use crate::mode::calculation::CalculationMode;
+use crate::util::prism::Prism;
+
+use thiserror::Error;
+
+use std::cell::Cell;
+
+/// Engine for computing limits. Limits are computed by recursive
+/// descent: the limit of a function application is the function
+/// applied to the limits of its arguments, except where this would
+/// produce one of the indeterminate forms. Indeterminate quotients
+/// are resolved with L'Hôpital's rule, and the other indeterminate
+/// forms are rewritten as quotients first.
+///
+/// The point may be offset by the infinitesimal `eps` to take a
+/// one-sided limit, such as `eps` for the limit from above zero or
+/// `2 - eps` for the limit from below two. Subexpressions whose
+/// limits are infinitesimal count as zero when detecting
+/// indeterminate forms, and the final result is reduced to its
+/// standard part.
+struct LimitEngine<'a> {
+  target_variable: Var,
+  point: Expr,
+  function_table: &'a FunctionTable,
+  simplifier: &'a dyn Simplifier,
+  lhopital_steps: Cell<usize>,
+}
+
+/// An error while computing a limit.
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum LimitError {
+  #[error("Limit is an indeterminate form")]
+  Indeterminate,
+  #[error("Gave up after {0} applications of L'Hôpital's rule")]
+  TooManySteps(usize),
+  #[error("{0}")]
+  DifferentiationError(#[from] super::DifferentiationError),
+  #[error("Point cannot be approached from that side")]
+  NoSuchSide,
+}
+
+/// The side from which a one-sided limit approaches its point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitDirection {
+  /// Approach from below the point.
+  Left,
+  /// Approach from above the point.
+  Right,
+}
+
+/// The limiting behavior of a subexpression.
+enum LimitKind {
+  Zero,
+  Infinite,
+  Other,
+}
+
+impl<'a> LimitEngine<'a> {
+  const MAX_LHOPITAL_STEPS: usize = 8;
+
+  fn limit(&self, expr: Expr) -> Result<Expr, LimitError> {
+    if self.is_constant(&expr) {
+      return Ok(self.simplify(expr));
+    }
+    let Expr::Call(function, args) = expr else {
+      // Not constant, so it must be the target variable.
+      return Ok(self.point.clone());
+    };
+    match (function.as_str(), args.len()) {
+      ("/", 2) => {
+        let [numer, denom] = args.try_into().unwrap(); // unwrap: Just checked length
+        self.limit_of_quotient(numer, denom)
+      }
+      ("*", _) => {
+        self.limit_of_product(args)
+      }
+      ("^", 2) => {
+        let [base, exponent] = args.try_into().unwrap(); // unwrap: Just checked length
+        self.limit_of_power(base, exponent)
+      }
+      _ => {
+        let arg_limits = args.into_iter()
+          .map(|arg| self.limit(arg))
+          .collect::<Result<Vec<_>, _>>()?;
+        self.combine(Expr::Call(function, arg_limits))
+      }
+    }
+  }
+
+  fn limit_of_quotient(&self, numer: Expr, denom: Expr) -> Result<Expr, LimitError> {
+    let numer_limit = self.limit(numer.clone())?;
+    let denom_limit = self.limit(denom.clone())?;
+    match (self.classify(&numer_limit), self.classify(&denom_limit)) {
+      (LimitKind::Zero, LimitKind::Zero) | (LimitKind::Infinite, LimitKind::Infinite) => {
+        self.apply_lhopital(numer, denom)
+      }
+      _ => {
+        self.combine(Expr::call("/", vec![numer_limit, denom_limit]))
+      }
+    }
+  }
+
+  fn limit_of_product(&self, factors: Vec<Expr>) -> Result<Expr, LimitError> {
+    let mut zeroes = Vec::new();
+    let mut infinities = Vec::new();
+    let mut others = Vec::new();
+    for factor in factors {
+      let factor_limit = self.limit(factor.clone())?;
+      match self.classify(&factor_limit) {
+        LimitKind::Zero => zeroes.push((factor, factor_limit)),
+        LimitKind::Infinite => infinities.push((factor, factor_limit)),
+        LimitKind::Other => others.push(factor_limit),
+      }
+    }
+    if zeroes.is_empty() || infinities.is_empty() {
+      let limits = others.into_iter()
+        .chain(zeroes.into_iter().map(|(_, limit)| limit))
+        .chain(infinities.into_iter().map(|(_, limit)| limit))
+        .collect();
+      return self.combine(Expr::call("*", limits));
+    }
+    // 0 * inf: rewrite f * g as f / (1 / g). If L'Hôpital's rule
+    // makes no progress on that form, try g / (1 / f) instead.
+    let zeroes = Expr::call("*", zeroes.into_iter().map(|(factor, _)| factor).collect());
+    let infinities = Expr::call("*", infinities.into_iter().map(|(factor, _)| factor).collect());
+    let steps_so_far = self.lhopital_steps.get();
+    let quotient_limit = match self.limit_of_quotient(zeroes.clone(), reciprocal(infinities.clone())) {
+      Err(LimitError::TooManySteps(_)) => {
+        self.lhopital_steps.set(steps_so_far);
+        self.limit_of_quotient(infinities, reciprocal(zeroes))?
+      }
+      result => result?,
+    };
+    others.push(quotient_limit);
+    self.combine(Expr::call("*", others))
+  }
+
+  fn limit_of_power(&self, base: Expr, exponent: Expr) -> Result<Expr, LimitError> {
+    let base_limit = self.limit(base.clone())?;
+    let exponent_limit = self.limit(exponent.clone())?;
+    let is_indeterminate = match (self.classify(&base_limit), self.classify(&exponent_limit)) {
+      (LimitKind::Zero | LimitKind::Infinite, LimitKind::Zero) => true,
+      (LimitKind::Other, LimitKind::Infinite) => base_limit.is_one(),
+      _ => false,
+    };
+    if is_indeterminate {
+      // b^e = exp(e ln b)
+      let log_limit = self.limit_of_product(vec![exponent, Expr::call("ln", vec![base])])?;
+      self.combine(Expr::call("exp", vec![log_limit]))
+    } else {
+      self.combine(Expr::call("^", vec![base_limit, exponent_limit]))
+    }
+  }
+
+  fn apply_lhopital(&self, numer: Expr, denom: Expr) -> Result<Expr, LimitError> {
+    let steps = self.lhopital_steps.get() + 1;
+    if steps > Self::MAX_LHOPITAL_STEPS {
+      return Err(LimitError::TooManySteps(Self::MAX_LHOPITAL_STEPS));
+    }
+    self.lhopital_steps.set(steps);
+    let numer = self.differentiate(numer)?;
+    let denom = self.differentiate(denom)?;
+    // Simplify the new quotient as a whole, so that factors common to
+    // both derivatives can cancel.
+    let quotient = self.simplify_with_mode(Expr::call("/", vec![numer, denom]), CalculationMode::for_algebra());
+    self.limit(quotient)
+  }
+
+  fn differentiate(&self, expr: Expr) -> Result<Expr, LimitError> {
+    let derivative = differentiate(self.function_table, expr, self.target_variable.clone())
+      .map_err(|failure| failure.error)?;
+    Ok(self.simplify_with_mode(derivative, CalculationMode::for_algebra()))
+  }
+
+  /// Simplifies an expression built from the limits of
+  /// subexpressions, failing if the result is undefined.
+  fn combine(&self, expr: Expr) -> Result<Expr, LimitError> {
+    let result = self.simplify(expr);
+    if result == Expr::from(InfiniteConstant::NotANumber) {
+      Err(LimitError::Indeterminate)
+    } else {
+      Ok(result)
+    }
+  }
+
+  fn classify(&self, limit: &Expr) -> LimitKind {
+    if contains_infinitesimal(limit) {
+      classify(&self.simplify(standard_part(limit.clone())))
+    } else {
+      classify(limit)
+    }
+  }
+
+  /// Discards any infinitesimal part of a finite result.
+  fn standard_part(&self, result: Expr) -> Expr {
+    if contains_infinitesimal(&result) {
+      self.simplify(standard_part(result))
+    } else {
+      result
+    }
+  }
+
+  fn is_constant(&self, expr: &Expr) -> bool {
+    match expr {
+      Expr::Atom(Atom::Var(v)) => v != &self.target_variable,
+      Expr::Atom(_) => true,
+      Expr::Call(_, args) => args.iter().all(|arg| self.is_constant(arg)),
+    }
+  }
+
+  /// Simplifies with the infinity flag on, so that operations on
+  /// infinite and zero limits produce infinities rather than errors.
+  fn simplify(&self, expr: Expr) -> Expr {
+    let mut calculation_mode = CalculationMode::for_algebra();
+    calculation_mode.set_infinity_flag(true);
+    self.simplify_with_mode(expr, calculation_mode)
+  }
+
+  fn simplify_with_mode(&self, expr: Expr, calculation_mode: CalculationMode) -> Expr {
+    // As with ExprFunction, errors from the simplifier are ignored
+    // here.
+    let mut errors = ErrorList::new();
+    let mut context = SimplifierContext {
+      base_simplifier: self.simplifier,
+      calculation_mode,
+      errors: &mut errors,
+    };
+    self.simplifier.simplify_expr(expr, &mut context)
+  }
+}
+
+fn reciprocal(expr: Expr) -> Expr {
+  Expr::call("/", vec![Expr::one(), expr])
+}
+
+fn classify(limit: &Expr) -> LimitKind {
+  if limit.is_zero() {
+    LimitKind::Zero
+  } else if is_infinite_constant(limit) {
+    LimitKind::Infinite
+  } else {
+    LimitKind::Other
+  }
+}
+
+/// Computes the limit of `expr` as `var` approaches `point`, which
+/// may be finite or one of the infinite constants, and may be offset
+/// by the infinitesimal `eps` to take a one-sided limit. A limit which
+/// diverges is reported as the appropriate infinite constant.
+pub fn limit(
+  function_table: &FunctionTable,
+  simplifier: &dyn Simplifier,
+  expr: Expr,
+  var: Var,
+  point: Expr,
+) -> Result<Expr, LimitError> {
+  let engine = LimitEngine {
+    target_variable: var,
+    point,
+    function_table,
+    simplifier,
+    lhopital_steps: Cell::new(0),
+  };
+  let result = engine.limit(expr)?;
+  Ok(engine.standard_part(result))
+}
+
+/// Computes the limit of `expr` as `var` approaches `point` from one
+/// side, by offsetting a finite point by the infinitesimal `eps`.
+/// Near a pole, the sign of `eps` determines the sign of the
+/// resulting infinity, and at a jump discontinuity it determines
+/// which side of the jump is reported.
+///
+/// `inf` can only be approached from the left, and `-inf` from the
+/// right, in which case this is the same as [`limit`]. Any other
+/// approach to an infinite constant fails with
+/// [`LimitError::NoSuchSide`].
+pub fn one_sided_limit(
+  function_table: &FunctionTable,
+  simplifier: &dyn Simplifier,
+  expr: Expr,
+  var: Var,
+  point: Expr,
+  direction: LimitDirection,
+) -> Result<Expr, LimitError> {
+  let point = match (ExprToInfinity.narrow_type(point), direction) {
+    (Ok(InfiniteConstant::PosInfinity), LimitDirection::Left) => Expr::from(InfiniteConstant::PosInfinity),
+    (Ok(InfiniteConstant::NegInfinity), LimitDirection::Right) => Expr::from(InfiniteConstant::NegInfinity),
+    (Ok(_), _) => return Err(LimitError::NoSuchSide),
+    (Err(point), LimitDirection::Left) => Expr::call("-", vec![point, Hyperreal::eps().to_expr()]),
+    (Err(point), LimitDirection::Right) => Expr::call("+", vec![point, Hyperreal::eps().to_expr()]),
+  };
+  limit(function_table, simplifier, expr, var, point)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::default_simplifier;
+
+  use once_cell::sync::Lazy;
+
+  static FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(build_function_table);
+
+  fn x() -> Expr {
+    Expr::var("x").unwrap()
+  }
+
+  fn limit_at(expr: Expr, point: Expr) -> Result<Expr, LimitError> {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    limit(&FUNCTION_TABLE, simplifier.as_ref(), expr, Var::new("x").unwrap(), point)
+  }
+
+  #[test]
+  fn test_continuous_limit() {
+    let expr = Expr::call("+", vec![Expr::call("^", vec![x(), Expr::from(2)]), Expr::from(1)]);
+    assert_eq!(limit_at(expr, Expr::from(3)).unwrap(), Expr::from(10));
+  }
+
+  #[test]
+  fn test_lhopital() {
+    // sin(x) / x -> 1 as x -> 0
+    let expr = Expr::call("/", vec![Expr::call("sin", vec![x()]), x()]);
+    assert_eq!(limit_at(expr, Expr::zero()).unwrap(), Expr::from(1));
+    // (1 - cos(x)) / x^2 -> 1/2 as x -> 0, requiring two steps
+    let expr = Expr::call("/", vec![
+      Expr::call("-", vec![Expr::from(1), Expr::call("cos", vec![x()])]),
+      Expr::call("^", vec![x(), Expr::from(2)]),
+    ]);
+    assert_eq!(limit_at(expr, Expr::zero()).unwrap(), Expr::from(0.5));
+  }
+
+  #[test]
+  fn test_limit_at_infinity() {
+    // (2x + 1) / (x + 3) -> 2 as x -> inf
+    let expr = Expr::call("/", vec![
+      Expr::call("+", vec![Expr::call("*", vec![Expr::from(2), x()]), Expr::from(1)]),
+      Expr::call("+", vec![x(), Expr::from(3)]),
+    ]);
+    assert_eq!(limit_at(expr, Expr::from(InfiniteConstant::PosInfinity)).unwrap(), Expr::from(2));
+    // exp(-x) -> 0 as x -> inf
+    let expr = Expr::call("exp", vec![Expr::call("negate", vec![x()])]);
+    assert_eq!(limit_at(expr, Expr::from(InfiniteConstant::PosInfinity)).unwrap(), Expr::zero());
+  }
+
+  #[test]
+  fn test_zero_times_infinity() {
+    // x ln(x) -> 0 as x -> 0
+    let expr = Expr::call("*", vec![x(), Expr::call("ln", vec![x()])]);
+    assert_eq!(limit_at(expr, Expr::zero()).unwrap(), Expr::zero());
+  }
+
+  #[test]
+  fn test_one_to_the_infinity() {
+    // (1 + 1/x)^x -> e as x -> inf
+    let expr = Expr::call("^", vec![
+      Expr::call("+", vec![Expr::from(1), Expr::call("/", vec![Expr::from(1), x()])]),
+      x(),
+    ]);
+    let result = limit_at(expr, Expr::from(InfiniteConstant::PosInfinity)).unwrap();
+    assert_eq!(result, Expr::from(std::f64::consts::E));
+  }
+
+  #[test]
+  fn test_divergent_limit() {
+    let expr = Expr::call("/", vec![Expr::from(1), Expr::call("^", vec![x(), Expr::from(2)])]);
+    assert_eq!(limit_at(expr, Expr::zero()).unwrap(), Expr::from(InfiniteConstant::UndirInfinity));
+  }
+
+  fn one_sided_limit_at(expr: Expr, point: Expr, direction: LimitDirection) -> Result<Expr, LimitError> {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    one_sided_limit(&FUNCTION_TABLE, simplifier.as_ref(), expr, Var::new("x").unwrap(), point, direction)
+  }
+
+  #[test]
+  fn test_one_sided_limit_at_pole() {
+    let expr = Expr::call("/", vec![Expr::from(1), x()]);
+    assert_eq!(
+      one_sided_limit_at(expr.clone(), Expr::zero(), LimitDirection::Right).unwrap(),
+      Expr::from(InfiniteConstant::PosInfinity),
+    );
+    assert_eq!(
+      one_sided_limit_at(expr, Expr::zero(), LimitDirection::Left).unwrap(),
+      Expr::from(InfiniteConstant::NegInfinity),
+    );
+    // 1 / (x^2 - 4) -> -inf as x -> 2 from below
+    let expr = Expr::call("/", vec![
+      Expr::from(1),
+      Expr::call("-", vec![Expr::call("^", vec![x(), Expr::from(2)]), Expr::from(4)]),
+    ]);
+    assert_eq!(
+      one_sided_limit_at(expr, Expr::from(2), LimitDirection::Left).unwrap(),
+      Expr::from(InfiniteConstant::NegInfinity),
+    );
+  }
+
+  #[test]
+  fn test_one_sided_limit_at_jump() {
+    let expr = Expr::call("signum", vec![x()]);
+    assert_eq!(one_sided_limit_at(expr.clone(), Expr::zero(), LimitDirection::Left).unwrap(), Expr::from(-1));
+    assert_eq!(one_sided_limit_at(expr, Expr::zero(), LimitDirection::Right).unwrap(), Expr::from(1));
+    let expr = Expr::call("round", vec![x()]);
+    let half = Expr::call("/", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(one_sided_limit_at(expr.clone(), half.clone(), LimitDirection::Left).unwrap(), Expr::from(0));
+    assert_eq!(one_sided_limit_at(expr, half, LimitDirection::Right).unwrap(), Expr::from(1));
+  }
+
+  #[test]
+  fn test_one_sided_limit_at_infinity() {
+    let expr = Expr::call("exp", vec![Expr::call("negate", vec![x()])]);
+    let inf = Expr::from(InfiniteConstant::PosInfinity);
+    assert_eq!(one_sided_limit_at(expr.clone(), inf.clone(), LimitDirection::Left).unwrap(), Expr::zero());
+    assert!(matches!(
+      one_sided_limit_at(expr, inf, LimitDirection::Right),
+      Err(LimitError::NoSuchSide),
+    ));
+  }
+
+  #[test]
+  fn test_indeterminate_limit() {
+    // x - x^2 -> inf - inf as x -> inf
+    let expr = Expr::call("-", vec![x(), Expr::call("^", vec![x(), Expr::from(2)])]);
+    assert!(matches!(
+      limit_at(expr, Expr::from(InfiniteConstant::PosInfinity)),
+      Err(LimitError::Indeterminate),
+    ));
+  }
+}
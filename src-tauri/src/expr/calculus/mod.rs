@@ -1,7 +1,22 @@
 
 //! Subsystems for doing basic calculus on expressions, such as taking
-//! derivatives and integrals.
+//! derivatives and integrals, either symbolically or via automatic
+//! differentiation.
 
 mod derivative;
+mod derivative_rules;
+mod dual;
+mod integral;
+mod limit;
+mod monte_carlo;
+mod numerical;
+mod sensitivity;
 
-pub use derivative::{DerivativeEngine, DifferentiationFailure, DifferentiationError, differentiate};
+pub use derivative::{DerivativeEngine, DifferentiationFailure, DifferentiationError, differentiate, differentiate_with_rules};
+pub use derivative_rules::{DerivativeRule, DerivativeRuleTable, DerivativeRuleError, PartialDerivative};
+pub use integral::{IntegrationEngine, IntegrationFailure, IntegrationError, integrate};
+pub use limit::{LimitError, LimitDirection, limit, one_sided_limit};
+pub use dual::{DualNumber, DualFunction, DualCompileError};
+pub use monte_carlo::{MonteCarloEstimate, MonteCarloError, estimate_expectation, integrate_over_box};
+pub use numerical::{NumericalDerivative, richardson_derivative};
+pub use sensitivity::{Sensitivity, SensitivityInput, SensitivityError, sensitivity_analysis};
@@ -0,0 +1,151 @@
+
+//! Monte Carlo estimation of expectations and integrals.
+//!
+//! The cost of a Monte Carlo estimate is independent of the number
+//! of variables, which makes it the method of choice for
+//! high-dimensional integrals, at the expense of converging only as
+//! `1 / sqrt(n)` in the number of samples.
+
+use crate::expr::algebra::FunctionEvalError;
+use crate::expr::distribution::{Sampler, DistributionError};
+use crate::expr::simplifier::cancellable::CancelledError;
+
+use rand::Rng;
+use rand::distributions::Distribution;
+use thiserror::Error;
+
+/// A Monte Carlo estimate of some quantity, together with the
+/// standard error of that estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonteCarloEstimate {
+  pub mean: f64,
+  pub standard_error: f64,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum MonteCarloError {
+  #[error("{0}")]
+  FunctionEvalError(#[from] FunctionEvalError),
+  #[error("{0}")]
+  DistributionError(#[from] DistributionError),
+  #[error("Expected {expected} sampling ranges, got {actual}")]
+  DimensionMismatch { expected: usize, actual: usize },
+  #[error("Monte Carlo estimation requires at least two samples")]
+  TooFewSamples,
+  #[error("{0}")]
+  Cancelled(#[from] CancelledError),
+}
+
+impl MonteCarloEstimate {
+  /// Multiplies both the estimate and its error by a constant factor.
+  pub fn scale(self, factor: f64) -> Self {
+    Self {
+      mean: self.mean * factor,
+      standard_error: self.standard_error * factor.abs(),
+    }
+  }
+}
+
+/// Estimates the expected value of `f(X1, ..., Xn)`, where each `Xi`
+/// is drawn independently from the corresponding sampler, using
+/// `samples` draws. Errors from `f` are propagated immediately.
+pub fn estimate_expectation<R, F, E>(
+  rng: &mut R,
+  samplers: &[Sampler],
+  samples: usize,
+  mut f: F,
+) -> Result<MonteCarloEstimate, E>
+where R: Rng + ?Sized,
+      F: FnMut(&[f64]) -> Result<f64, E> {
+  // Welford's algorithm, for numerically stable running variance.
+  let mut mean = 0.0;
+  let mut sum_of_squares = 0.0;
+  let mut point = vec![0.0; samplers.len()];
+  for count in 1..=samples {
+    for (coord, sampler) in point.iter_mut().zip(samplers) {
+      *coord = sampler.sample(rng);
+    }
+    let value = f(&point)?;
+    let delta = value - mean;
+    mean += delta / count as f64;
+    sum_of_squares += delta * (value - mean);
+  }
+  let standard_error = if samples > 1 {
+    let variance = sum_of_squares / (samples - 1) as f64;
+    (variance / samples as f64).sqrt()
+  } else {
+    f64::INFINITY
+  };
+  Ok(MonteCarloEstimate { mean, standard_error })
+}
+
+/// Estimates the integral of `f` over the box with the given
+/// per-coordinate bounds, by sampling uniformly from the box.
+pub fn integrate_over_box<R, F, E>(
+  rng: &mut R,
+  bounds: &[(f64, f64)],
+  samples: usize,
+  f: F,
+) -> Result<MonteCarloEstimate, E>
+where R: Rng + ?Sized,
+      F: FnMut(&[f64]) -> Result<f64, E> {
+  let samplers: Vec<_> = bounds.iter()
+    .map(|(lo, hi)| Sampler::Uniform(rand_distr::Uniform::new_inclusive(*lo, *hi)))
+    .collect();
+  let volume: f64 = bounds.iter().map(|(lo, hi)| hi - lo).product();
+  let estimate = estimate_expectation(rng, &samplers, samples, f)?;
+  Ok(estimate.scale(volume))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::distribution::SampleDistribution;
+  use crate::expr::number::Number;
+
+  use rand::SeedableRng;
+  use rand::rngs::StdRng;
+
+  use std::convert::Infallible;
+
+  #[test]
+  fn test_integrate_over_unit_square() {
+    let mut rng = StdRng::seed_from_u64(1);
+    // Integral of x * y over [0, 1]^2 is 1/4.
+    let result: Result<_, Infallible> =
+      integrate_over_box(&mut rng, &[(0.0, 1.0), (0.0, 1.0)], 20_000, |p| Ok(p[0] * p[1]));
+    let result = result.unwrap();
+    assert!((result.mean - 0.25).abs() < 4.0 * result.standard_error);
+    assert!(result.standard_error < 0.01);
+  }
+
+  #[test]
+  fn test_integrate_scales_by_volume() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let result: Result<_, Infallible> =
+      integrate_over_box(&mut rng, &[(0.0, 2.0), (-1.0, 2.0), (0.0, 0.5)], 100, |_| Ok(1.0));
+    let result = result.unwrap();
+    assert_eq!(result.mean, 3.0);
+    assert_eq!(result.standard_error, 0.0);
+  }
+
+  #[test]
+  fn test_expectation_of_normal() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let normal = SampleDistribution::Normal { mean: Number::from(2), std_dev: Number::from(1) };
+    let samplers = [normal.sampler().unwrap()];
+    // E[X^2] = mu^2 + sigma^2 = 5
+    let result: Result<_, Infallible> =
+      estimate_expectation(&mut rng, &samplers, 20_000, |p| Ok(p[0] * p[0]));
+    let result = result.unwrap();
+    assert!((result.mean - 5.0).abs() < 4.0 * result.standard_error);
+  }
+
+  #[test]
+  fn test_propagates_errors() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let result = integrate_over_box(&mut rng, &[(0.0, 1.0)], 10, |_| Err("failure"));
+    assert_eq!(result, Err("failure"));
+  }
+}
@@ -0,0 +1,107 @@
+
+//! Numerical differentiation by Richardson-extrapolated central
+//! differences.
+//!
+//! This is the fallback for functions which neither the symbolic
+//! differentiator nor the automatic differentiator understand.
+
+/// The result of a numerical differentiation, together with an
+/// estimate of its absolute error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericalDerivative {
+  pub value: f64,
+  pub error_estimate: f64,
+}
+
+/// Factor by which the step size shrinks between successive
+/// columns of the extrapolation tableau.
+const STEP_SHRINK: f64 = 1.4;
+
+/// Maximum size of the extrapolation tableau.
+const TABLEAU_SIZE: usize = 10;
+
+/// If the error of a new row of the tableau exceeds that of the
+/// previous best estimate by this factor, the extrapolation has
+/// become dominated by rounding error, so we stop early.
+const SAFETY_FACTOR: f64 = 2.0;
+
+/// Computes the derivative of `f` at `x`, starting from an initial
+/// step size of `h`, using Ridders' method: central differences at
+/// successively smaller step sizes, combined with Neville-style
+/// Richardson extrapolation.
+///
+/// `h` should be large compared to the scale on which `f` is noisy,
+/// but small compared to the scale on which `f` changes
+/// significantly. Errors from `f` are propagated immediately.
+pub fn richardson_derivative<F, E>(mut f: F, x: f64, h: f64) -> Result<NumericalDerivative, E>
+where F: FnMut(f64) -> Result<f64, E> {
+  let mut central_difference = |h: f64| -> Result<f64, E> {
+    Ok((f(x + h)? - f(x - h)?) / (2.0 * h))
+  };
+
+  let shrink_squared = STEP_SHRINK * STEP_SHRINK;
+  let mut h = h;
+  let mut previous_row = vec![central_difference(h)?];
+  let mut best = NumericalDerivative { value: previous_row[0], error_estimate: f64::INFINITY };
+  for i in 1..TABLEAU_SIZE {
+    h /= STEP_SHRINK;
+    let mut row = Vec::with_capacity(i + 1);
+    row.push(central_difference(h)?);
+    let mut factor = shrink_squared;
+    for j in 1..=i {
+      let extrapolated = (row[j - 1] * factor - previous_row[j - 1]) / (factor - 1.0);
+      row.push(extrapolated);
+      factor *= shrink_squared;
+      let error = f64::max((row[j] - row[j - 1]).abs(), (row[j] - previous_row[j - 1]).abs());
+      if error <= best.error_estimate {
+        best = NumericalDerivative { value: row[j], error_estimate: error };
+      }
+    }
+    if (row[i] - previous_row[i - 1]).abs() >= SAFETY_FACTOR * best.error_estimate {
+      break;
+    }
+    previous_row = row;
+  }
+  Ok(best)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::convert::Infallible;
+
+  fn derivative_of(f: impl Fn(f64) -> f64, x: f64) -> NumericalDerivative {
+    let result: Result<_, Infallible> = richardson_derivative(|x| Ok(f(x)), x, 0.1);
+    result.unwrap()
+  }
+
+  #[test]
+  fn test_polynomial_derivative() {
+    let result = derivative_of(|x| x * x * x, 2.0);
+    assert!((result.value - 12.0).abs() < 1e-9);
+    assert!(result.error_estimate < 1e-6);
+  }
+
+  #[test]
+  fn test_transcendental_derivative() {
+    let result = derivative_of(f64::sin, 1.0);
+    assert!((result.value - 1f64.cos()).abs() < 1e-10);
+    let result = derivative_of(f64::exp, 0.5);
+    assert!((result.value - 0.5f64.exp()).abs() < 1e-10);
+  }
+
+  #[test]
+  fn test_error_estimate_bounds_actual_error() {
+    let result = derivative_of(|x| (3.0 * x).cos() / (1.0 + x * x), 0.7);
+    let x = 0.7f64;
+    let expected = (-3.0 * (3.0 * x).sin() * (1.0 + x * x) - 2.0 * x * (3.0 * x).cos()) / ((1.0 + x * x) * (1.0 + x * x));
+    assert!((result.value - expected).abs() <= result.error_estimate.max(1e-12) * 10.0);
+  }
+
+  #[test]
+  fn test_propagates_errors() {
+    let result = richardson_derivative(|x| if x > 1.0 { Err("out of domain") } else { Ok(x) }, 1.0, 0.1);
+    assert_eq!(result, Err("out of domain"));
+  }
+}
@@ -0,0 +1,158 @@
+
+//! First-order sensitivity analysis, which attributes the spread in
+//! the value of an expression to the spreads in its inputs.
+//!
+//! The expression is linearized at a point using its partial
+//! derivatives, so each input `x` contributes `|df/dx| * sigma_x` to
+//! the standard deviation of the output. For independent inputs, the
+//! squares of these contributions add up to the variance of the
+//! linearized output.
+
+use super::derivative::differentiate_with_rules;
+use super::derivative_rules::DerivativeRuleTable;
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::var::Var;
+use crate::expr::algebra::{ExprFunctionN, FunctionEvalError};
+use crate::expr::function::table::FunctionTable;
+use crate::expr::simplifier::Simplifier;
+
+use thiserror::Error;
+
+/// A variable of the expression under analysis, together with the
+/// point at which to linearize and the standard deviation of the
+/// variable about that point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityInput {
+  pub var: Var,
+  pub point: f64,
+  pub std_dev: f64,
+}
+
+/// The sensitivity of an expression to one of its inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensitivity {
+  pub var: Var,
+  /// The partial derivative of the expression with respect to the
+  /// input, at the point.
+  pub partial: f64,
+  /// The input's contribution to the standard deviation of the
+  /// output, `|partial| * std_dev`.
+  pub contribution: f64,
+  /// The input's share of the total output variance, between zero
+  /// and one.
+  pub share: f64,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum SensitivityError {
+  #[error("{0}")]
+  DifferentiationError(#[from] super::DifferentiationError),
+  #[error("{0}")]
+  FunctionEvalError(#[from] FunctionEvalError),
+  #[error("Variable {0} is bound more than once")]
+  DuplicateVariable(Var),
+}
+
+/// Computes the sensitivity of `expr` to each of the inputs, sorted
+/// with the largest contribution first. Partial derivatives are
+/// computed symbolically, then evaluated at the point.
+pub fn sensitivity_analysis(
+  function_table: &FunctionTable,
+  derivative_rules: Option<&DerivativeRuleTable>,
+  simplifier: &dyn Simplifier,
+  expr: &Expr,
+  inputs: &[SensitivityInput],
+) -> Result<Vec<Sensitivity>, SensitivityError> {
+  for (i, input) in inputs.iter().enumerate() {
+    if inputs[..i].iter().any(|other| other.var == input.var) {
+      return Err(SensitivityError::DuplicateVariable(input.var.clone()));
+    }
+  }
+  let vars: Vec<_> = inputs.iter().map(|input| input.var.clone()).collect();
+  let point: Vec<_> = inputs.iter().map(|input| Number::from(input.point)).collect();
+
+  let mut sensitivities = Vec::with_capacity(inputs.len());
+  for input in inputs {
+    let partial = differentiate_with_rules(function_table, derivative_rules, expr.clone(), input.var.clone())
+      .map_err(|failure| failure.error)?;
+    let partial = ExprFunctionN::new(partial, vars.clone(), simplifier)
+      .eval_at_real(point.clone())?
+      .to_f64_or_nan();
+    sensitivities.push(Sensitivity {
+      var: input.var.clone(),
+      partial,
+      contribution: partial.abs() * input.std_dev,
+      share: 0.0,
+    });
+  }
+
+  let total_variance: f64 = sensitivities.iter().map(|s| s.contribution * s.contribution).sum();
+  if total_variance > 0.0 {
+    for s in &mut sensitivities {
+      s.share = s.contribution * s.contribution / total_variance;
+    }
+  }
+  sensitivities.sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+  Ok(sensitivities)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::default_simplifier;
+
+  use once_cell::sync::Lazy;
+
+  static FUNCTION_TABLE: Lazy<FunctionTable> = Lazy::new(build_function_table);
+
+  fn var(name: &str) -> Var {
+    Var::new(name).unwrap()
+  }
+
+  fn input(name: &str, point: f64, std_dev: f64) -> SensitivityInput {
+    SensitivityInput { var: var(name), point, std_dev }
+  }
+
+  fn analyze(expr: Expr, inputs: &[SensitivityInput]) -> Result<Vec<Sensitivity>, SensitivityError> {
+    let simplifier = default_simplifier(&FUNCTION_TABLE);
+    sensitivity_analysis(&FUNCTION_TABLE, None, simplifier.as_ref(), &expr, inputs)
+  }
+
+  #[test]
+  fn test_product_sensitivity() {
+    // f(x, y) = x * y at (2, 3), so df/dx = 3 and df/dy = 2.
+    let expr = Expr::call("*", vec![Expr::from(var("x")), Expr::from(var("y"))]);
+    let result = analyze(expr, &[input("x", 2.0, 1.0), input("y", 3.0, 4.0)]).unwrap();
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].var, var("y"));
+    assert!((result[0].partial - 2.0).abs() < 1e-9);
+    assert!((result[0].contribution - 8.0).abs() < 1e-9);
+    assert!((result[0].share - 64.0 / 73.0).abs() < 1e-9);
+    assert_eq!(result[1].var, var("x"));
+    assert!((result[1].partial - 3.0).abs() < 1e-9);
+    assert!((result[1].contribution - 3.0).abs() < 1e-9);
+    assert!((result[1].share - 9.0 / 73.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_constant_expression_has_no_shares() {
+    let result = analyze(Expr::from(5), &[input("x", 1.0, 1.0)]).unwrap();
+    assert_eq!(result, vec![Sensitivity { var: var("x"), partial: 0.0, contribution: 0.0, share: 0.0 }]);
+  }
+
+  #[test]
+  fn test_duplicate_variable() {
+    let err = analyze(Expr::from(var("x")), &[input("x", 1.0, 1.0), input("x", 2.0, 1.0)]).unwrap_err();
+    assert!(matches!(err, SensitivityError::DuplicateVariable(v) if v == var("x")));
+  }
+
+  #[test]
+  fn test_unknown_derivative() {
+    let expr = Expr::call("no_such_function", vec![Expr::from(var("x"))]);
+    let err = analyze(expr, &[input("x", 1.0, 1.0)]).unwrap_err();
+    assert!(matches!(err, SensitivityError::DifferentiationError(_)));
+  }
+}
@@ -0,0 +1,192 @@
+
+//! Structural differences between expressions.
+//!
+//! An [`ExprDelta`] records only the subexpressions which differ
+//! between two versions of an expression, so that a small edit to a
+//! large expression (such as replacing one element of a long vector)
+//! can be undone without keeping two full copies of the expression
+//! around.
+
+use super::Expr;
+
+use std::mem;
+
+/// The difference between an old and a new expression. A delta can
+/// be played forward (turning the old expression into the new one)
+/// or backward (turning the new expression into the old one).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprDelta {
+  /// The two expressions are identical.
+  Unchanged,
+  /// The expression was replaced wholesale.
+  Replace { old: Expr, new: Expr },
+  /// The expression is a function call whose name and arity did not
+  /// change, but some of whose arguments did. Each changed argument
+  /// is listed, in order, with its index.
+  Args(Vec<(usize, ExprDelta)>),
+}
+
+impl ExprDelta {
+  /// Computes the delta which takes `old` to `new`, sharing as much
+  /// of the unchanged structure as possible.
+  pub fn between(old: &Expr, new: &Expr) -> ExprDelta {
+    match (old, new) {
+      (Expr::Call(old_name, old_args), Expr::Call(new_name, new_args))
+        if old_name == new_name && old_args.len() == new_args.len() => {
+        let changed: Vec<_> = old_args.iter()
+          .zip(new_args)
+          .enumerate()
+          .map(|(index, (old_arg, new_arg))| (index, ExprDelta::between(old_arg, new_arg)))
+          .filter(|(_, delta)| !delta.is_unchanged())
+          .collect();
+        if changed.is_empty() {
+          ExprDelta::Unchanged
+        } else if changed.len() == old_args.len() && changed.iter().all(|(_, delta)| delta.is_replace()) {
+          // Nothing is shared, so storing the arguments one by one
+          // would only add overhead.
+          ExprDelta::Replace { old: old.clone(), new: new.clone() }
+        } else {
+          ExprDelta::Args(changed)
+        }
+      }
+      (old, new) if old == new => ExprDelta::Unchanged,
+      (old, new) => ExprDelta::Replace { old: old.clone(), new: new.clone() },
+    }
+  }
+
+  pub fn is_unchanged(&self) -> bool {
+    matches!(self, ExprDelta::Unchanged)
+  }
+
+  fn is_replace(&self) -> bool {
+    matches!(self, ExprDelta::Replace { .. })
+  }
+
+  /// Applies the delta to the old expression, producing the new one.
+  /// If `expr` does not have the shape that the delta expects, the
+  /// mismatched parts are left alone.
+  pub fn play_forward(&self, expr: &mut Expr) {
+    self.apply(expr, true);
+  }
+
+  /// Applies the delta in reverse to the new expression, producing
+  /// the old one. If `expr` does not have the shape that the delta
+  /// expects, the mismatched parts are left alone.
+  pub fn play_backward(&self, expr: &mut Expr) {
+    self.apply(expr, false);
+  }
+
+  fn apply(&self, expr: &mut Expr, forward: bool) {
+    match self {
+      ExprDelta::Unchanged => {}
+      ExprDelta::Replace { old, new } => {
+        *expr = if forward { new.clone() } else { old.clone() };
+      }
+      ExprDelta::Args(changed) => {
+        if let Expr::Call(_, args) = expr {
+          for (index, delta) in changed {
+            if let Some(arg) = args.get_mut(*index) {
+              delta.apply(arg, forward);
+            }
+          }
+        }
+      }
+    }
+  }
+
+  /// An estimate of the memory held by this delta, in bytes,
+  /// including the delta itself.
+  pub fn approx_size_in_bytes(&self) -> usize {
+    mem::size_of::<ExprDelta>() + match self {
+      ExprDelta::Unchanged => 0,
+      ExprDelta::Replace { old, new } => old.approx_size_in_bytes() + new.approx_size_in_bytes(),
+      ExprDelta::Args(changed) => {
+        changed.iter().map(|(_, delta)| mem::size_of::<usize>() + delta.approx_size_in_bytes()).sum()
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn vector(len: i64, replaced: Option<(i64, Expr)>) -> Expr {
+    Expr::call("vector", (0..len).map(|i| match &replaced {
+      Some((index, expr)) if *index == i => expr.clone(),
+      _ => Expr::from(i),
+    }).collect())
+  }
+
+  #[test]
+  fn test_unchanged() {
+    let expr = vector(3, None);
+    assert_eq!(ExprDelta::between(&expr, &expr), ExprDelta::Unchanged);
+    assert_eq!(ExprDelta::between(&Expr::from(1), &Expr::from(1)), ExprDelta::Unchanged);
+  }
+
+  #[test]
+  fn test_replace_atom() {
+    let delta = ExprDelta::between(&Expr::from(1), &Expr::from("x"));
+    assert_eq!(delta, ExprDelta::Replace { old: Expr::from(1), new: Expr::from("x") });
+  }
+
+  #[test]
+  fn test_replace_on_different_heads_or_arities() {
+    let old = Expr::call("+", vec![Expr::from(1), Expr::from(2)]);
+    let new = Expr::call("*", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(ExprDelta::between(&old, &new), ExprDelta::Replace { old: old.clone(), new: new.clone() });
+    let new = Expr::call("+", vec![Expr::from(1), Expr::from(2), Expr::from(3)]);
+    assert_eq!(ExprDelta::between(&old, &new), ExprDelta::Replace { old: old.clone(), new: new.clone() });
+  }
+
+  #[test]
+  fn test_replace_when_every_argument_changes() {
+    let old = Expr::call("+", vec![Expr::from(1), Expr::from(2)]);
+    let new = Expr::call("+", vec![Expr::from(3), Expr::from(4)]);
+    assert_eq!(ExprDelta::between(&old, &new), ExprDelta::Replace { old, new });
+  }
+
+  #[test]
+  fn test_nested_change() {
+    let old = Expr::call("vector", vec![vector(3, None), Expr::from(10)]);
+    let new = Expr::call("vector", vec![vector(3, Some((1, Expr::from("y")))), Expr::from(10)]);
+    let delta = ExprDelta::between(&old, &new);
+    assert_eq!(delta, ExprDelta::Args(vec![
+      (0, ExprDelta::Args(vec![
+        (1, ExprDelta::Replace { old: Expr::from(1), new: Expr::from("y") }),
+      ])),
+    ]));
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let old = vector(100, None);
+    let new = vector(100, Some((42, Expr::call("sin", vec![Expr::from("x")]))));
+    let delta = ExprDelta::between(&old, &new);
+    let mut expr = old.clone();
+    delta.play_forward(&mut expr);
+    assert_eq!(expr, new);
+    delta.play_backward(&mut expr);
+    assert_eq!(expr, old);
+  }
+
+  #[test]
+  fn test_delta_is_smaller_than_both_expressions() {
+    let old = vector(1000, None);
+    let new = vector(1000, Some((500, Expr::from("x"))));
+    let delta = ExprDelta::between(&old, &new);
+    assert!(delta.approx_size_in_bytes() * 100 < old.approx_size_in_bytes());
+  }
+
+  #[test]
+  fn test_apply_to_mismatched_expr() {
+    let delta = ExprDelta::Args(vec![(5, ExprDelta::Replace { old: Expr::from(1), new: Expr::from(2) })]);
+    let mut expr = vector(2, None);
+    delta.play_forward(&mut expr);
+    assert_eq!(expr, vector(2, None));
+    let mut expr = Expr::from(0);
+    delta.play_forward(&mut expr);
+    assert_eq!(expr, Expr::from(0));
+  }
+}
@@ -0,0 +1,229 @@
+
+//! Probability distributions over the real numbers, for use in
+//! random sampling.
+//!
+//! In the expression language, a distribution is written as a call
+//! to one of the (otherwise uninterpreted) functions `uniform(a, b)`,
+//! `normal(mu, sigma)`, or `exponential(lambda)`, each with real
+//! literal arguments.
+
+use super::Expr;
+use super::number::Number;
+use super::prisms::expr_to_number;
+use crate::util::prism::Prism;
+
+use rand::Rng;
+use rand::distributions::Distribution;
+use rand_distr::{Uniform, Normal, Exp};
+use thiserror::Error;
+
+/// A real-valued probability distribution with literal parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SampleDistribution {
+  Uniform { low: Number, high: Number },
+  Normal { mean: Number, std_dev: Number },
+  Exponential { rate: Number },
+}
+
+/// A validated [`SampleDistribution`], ready to draw samples from.
+#[derive(Debug, Clone, Copy)]
+pub enum Sampler {
+  Uniform(Uniform<f64>),
+  Normal(Normal<f64>),
+  Exponential(Exp<f64>),
+}
+
+/// Prism which parses a call to `uniform`, `normal`, or
+/// `exponential` as a [`SampleDistribution`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExprToDistribution;
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum DistributionError {
+  #[error("Uniform distribution requires low < high")]
+  EmptyUniformRange,
+  #[error("Normal distribution requires a finite, non-negative standard deviation")]
+  InvalidStdDev,
+  #[error("Exponential distribution requires a positive rate")]
+  InvalidRate,
+}
+
+impl SampleDistribution {
+  pub const UNIFORM_NAME: &'static str = "uniform";
+  pub const NORMAL_NAME: &'static str = "normal";
+  pub const EXPONENTIAL_NAME: &'static str = "exponential";
+
+  /// The uniform distribution over a closed range.
+  pub fn uniform(low: Number, high: Number) -> Self {
+    SampleDistribution::Uniform { low, high }
+  }
+
+  /// The mean of the distribution.
+  pub fn mean(&self) -> f64 {
+    match self {
+      SampleDistribution::Uniform { low, high } => (low.to_f64_or_nan() + high.to_f64_or_nan()) / 2.0,
+      SampleDistribution::Normal { mean, .. } => mean.to_f64_or_nan(),
+      SampleDistribution::Exponential { rate } => 1.0 / rate.to_f64_or_nan(),
+    }
+  }
+
+  /// The standard deviation of the distribution.
+  pub fn std_dev(&self) -> f64 {
+    match self {
+      SampleDistribution::Uniform { low, high } => (high.to_f64_or_nan() - low.to_f64_or_nan()).abs() / 12f64.sqrt(),
+      SampleDistribution::Normal { std_dev, .. } => std_dev.to_f64_or_nan(),
+      SampleDistribution::Exponential { rate } => 1.0 / rate.to_f64_or_nan(),
+    }
+  }
+
+  /// Checks the parameters of the distribution and produces a
+  /// [`Sampler`] for it.
+  pub fn sampler(&self) -> Result<Sampler, DistributionError> {
+    match self {
+      SampleDistribution::Uniform { low, high } => {
+        let (low, high) = (low.to_f64_or_nan(), high.to_f64_or_nan());
+        if !(low.is_finite() && high.is_finite() && low < high) {
+          return Err(DistributionError::EmptyUniformRange);
+        }
+        Ok(Sampler::Uniform(Uniform::new_inclusive(low, high)))
+      }
+      SampleDistribution::Normal { mean, std_dev } => {
+        let std_dev = std_dev.to_f64_or_nan();
+        if !(std_dev.is_finite() && std_dev >= 0.0) {
+          return Err(DistributionError::InvalidStdDev);
+        }
+        Normal::new(mean.to_f64_or_nan(), std_dev)
+          .map(Sampler::Normal)
+          .map_err(|_| DistributionError::InvalidStdDev)
+      }
+      SampleDistribution::Exponential { rate } => {
+        let rate = rate.to_f64_or_nan();
+        if rate.is_nan() || rate <= 0.0 {
+          return Err(DistributionError::InvalidRate);
+        }
+        Exp::new(rate)
+          .map(Sampler::Exponential)
+          .map_err(|_| DistributionError::InvalidRate)
+      }
+    }
+  }
+}
+
+impl Distribution<f64> for Sampler {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    match self {
+      Sampler::Uniform(d) => d.sample(rng),
+      Sampler::Normal(d) => d.sample(rng),
+      Sampler::Exponential(d) => d.sample(rng),
+    }
+  }
+}
+
+impl From<SampleDistribution> for Expr {
+  fn from(dist: SampleDistribution) -> Expr {
+    match dist {
+      SampleDistribution::Uniform { low, high } =>
+        Expr::call(SampleDistribution::UNIFORM_NAME, vec![low.into(), high.into()]),
+      SampleDistribution::Normal { mean, std_dev } =>
+        Expr::call(SampleDistribution::NORMAL_NAME, vec![mean.into(), std_dev.into()]),
+      SampleDistribution::Exponential { rate } =>
+        Expr::call(SampleDistribution::EXPONENTIAL_NAME, vec![rate.into()]),
+    }
+  }
+}
+
+impl Prism<Expr, SampleDistribution> for ExprToDistribution {
+  fn narrow_type(&self, input: Expr) -> Result<SampleDistribution, Expr> {
+    let Expr::Call(name, args) = input else {
+      return Err(input);
+    };
+    let expected_arity = match name.as_str() {
+      SampleDistribution::UNIFORM_NAME | SampleDistribution::NORMAL_NAME => 2,
+      SampleDistribution::EXPONENTIAL_NAME => 1,
+      _ => return Err(Expr::Call(name, args)),
+    };
+    if args.len() != expected_arity || !args.iter().all(|arg| arg.as_real_ref().is_some()) {
+      return Err(Expr::Call(name, args));
+    }
+    let mut params = args.into_iter().map(|arg| expr_to_number().narrow_type(arg).unwrap()); // unwrap: checked above
+    let mut param = || params.next().unwrap(); // unwrap: checked arity above
+    Ok(match name.as_str() {
+      SampleDistribution::UNIFORM_NAME => SampleDistribution::Uniform { low: param(), high: param() },
+      SampleDistribution::NORMAL_NAME => SampleDistribution::Normal { mean: param(), std_dev: param() },
+      _ => SampleDistribution::Exponential { rate: param() },
+    })
+  }
+
+  fn widen_type(&self, input: SampleDistribution) -> Expr {
+    input.into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use rand::SeedableRng;
+  use rand::rngs::StdRng;
+
+  #[test]
+  fn test_parse_distribution() {
+    let expr = Expr::call("normal", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(
+      ExprToDistribution.narrow_type(expr),
+      Ok(SampleDistribution::Normal { mean: Number::from(1), std_dev: Number::from(2) }),
+    );
+    let expr = Expr::call("exponential", vec![Expr::from(3)]);
+    assert_eq!(
+      ExprToDistribution.narrow_type(expr),
+      Ok(SampleDistribution::Exponential { rate: Number::from(3) }),
+    );
+  }
+
+  #[test]
+  fn test_parse_distribution_failures() {
+    let expr = Expr::call("normal", vec![Expr::from(1)]);
+    assert_eq!(ExprToDistribution.narrow_type(expr.clone()), Err(expr));
+    let expr = Expr::call("uniform", vec![Expr::from(1), Expr::var("x").unwrap()]);
+    assert_eq!(ExprToDistribution.narrow_type(expr.clone()), Err(expr));
+    let expr = Expr::call("poisson", vec![Expr::from(1)]);
+    assert_eq!(ExprToDistribution.narrow_type(expr.clone()), Err(expr));
+  }
+
+  #[test]
+  fn test_widen_distribution() {
+    let dist = SampleDistribution::uniform(Number::from(0), Number::from(5));
+    assert_eq!(
+      ExprToDistribution.widen_type(dist),
+      Expr::call("uniform", vec![Expr::from(0), Expr::from(5)]),
+    );
+  }
+
+  #[test]
+  fn test_invalid_parameters() {
+    assert!(SampleDistribution::uniform(Number::from(1), Number::from(1)).sampler().is_err());
+    assert!(SampleDistribution::Normal { mean: Number::from(0), std_dev: Number::from(-1) }.sampler().is_err());
+    assert!(SampleDistribution::Exponential { rate: Number::from(0) }.sampler().is_err());
+  }
+
+  #[test]
+  fn test_mean_and_std_dev() {
+    let dist = SampleDistribution::uniform(Number::from(0), Number::from(6));
+    assert_eq!(dist.mean(), 3.0);
+    assert!((dist.std_dev() - 3f64.sqrt()).abs() < 1e-12);
+    let dist = SampleDistribution::Exponential { rate: Number::from(4) };
+    assert_eq!(dist.mean(), 0.25);
+    assert_eq!(dist.std_dev(), 0.25);
+  }
+
+  #[test]
+  fn test_uniform_samples_in_range() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let sampler = SampleDistribution::uniform(Number::from(2), Number::from(3)).sampler().unwrap();
+    for _ in 0..100 {
+      let x = sampler.sample(&mut rng);
+      assert!((2.0..=3.0).contains(&x));
+    }
+  }
+}
@@ -4,7 +4,8 @@ use crate::util::prism::{Prism, Identity, OnVec};
 use crate::expr::Expr;
 use crate::expr::function::FunctionContext;
 use crate::expr::simplifier::error::ArityError;
-use crate::expr::calculus::{DerivativeEngine, DifferentiationFailure, DifferentiationError};
+use crate::expr::calculus::{DerivativeEngine, DifferentiationFailure, DifferentiationError,
+                            IntegrationEngine, IntegrationFailure, IntegrationError};
 
 use std::marker::PhantomData;
 
@@ -432,3 +433,34 @@ pub fn arity_two_deriv(
   }
 }
 
+pub fn arity_one_integral(
+  function_name: &str,
+  f: impl Fn(Expr, &IntegrationEngine) -> Result<Expr, IntegrationFailure> + Send + Sync + 'static
+) -> impl Fn(Vec<Expr>, &IntegrationEngine) -> Result<Expr, IntegrationFailure> + Send + Sync + 'static {
+  let function_name = function_name.to_owned();
+  move |mut args, engine| {
+    if args.len() != 1 {
+      let err = ArityError { expected: 1, actual: args.len() };
+      return Err(engine.error(IntegrationError::ArityError(function_name.clone(), err)));
+    }
+    let arg = args.pop().unwrap(); // unwrap: len() == 1
+    f(arg, engine)
+  }
+}
+
+pub fn arity_two_integral(
+  function_name: &str,
+  f: impl Fn(Expr, Expr, &IntegrationEngine) -> Result<Expr, IntegrationFailure> + Send + Sync + 'static
+) -> impl Fn(Vec<Expr>, &IntegrationEngine) -> Result<Expr, IntegrationFailure> + Send + Sync + 'static {
+  let function_name = function_name.to_owned();
+  move |mut args, engine| {
+    if args.len() != 2 {
+      let err = ArityError { expected: 2, actual: args.len() };
+      return Err(engine.error(IntegrationError::ArityError(function_name.clone(), err)));
+    }
+    let arg2 = args.pop().unwrap(); // unwrap: len() == 2
+    let arg1 = args.pop().unwrap(); // unwrap: len() == 2
+    f(arg1, arg2, engine)
+  }
+}
+
@@ -4,14 +4,15 @@
 pub mod matcher;
 
 pub use matcher::{arity_one, arity_two, arity_three, arity_four, any_arity,
-                  arity_one_deriv, arity_two_deriv};
+                  non_zero_arity, arity_one_deriv, arity_two_deriv,
+                  arity_one_integral, arity_two_integral};
 
-use super::{Function, FunctionContext, FunctionDeriv, FunctionImpl, no_identity_value};
+use super::{Function, FunctionContext, FunctionDeriv, FunctionIntegral, FunctionImpl, no_identity_value};
 use super::flags::FunctionFlags;
 use super::partial::{simplify_sequences, simplify_sequences_with_reordering};
 use crate::graphics::response::GraphicsDirective;
 use crate::expr::Expr;
-use crate::expr::calculus::{DerivativeEngine, DifferentiationFailure};
+use crate::expr::calculus::{DerivativeEngine, DifferentiationFailure, IntegrationEngine, IntegrationFailure};
 
 pub struct FunctionBuilder {
   /// The name of the function.
@@ -24,6 +25,8 @@ pub struct FunctionBuilder {
   identity_predicate: Box<dyn Fn(&Expr) -> bool + Send + Sync + 'static>,
   /// The rule for calculating the derivative of the function.
   derivative_rule: Option<Box<FunctionDeriv>>,
+  /// The rule for calculating an antiderivative of the function.
+  integral_rule: Option<Box<FunctionIntegral>>,
   /// Cases for ordinary, full evaluation of this function.
   cases: Vec<Box<FunctionCase<Expr>>>,
   /// Cases for evaluation of this function as part of the graphics
@@ -70,6 +73,7 @@ impl FunctionBuilder {
       flags: FunctionFlags::default(),
       identity_predicate: Box::new(no_identity_value),
       derivative_rule: None,
+      integral_rule: None,
       cases: Vec::new(),
       graphics_cases: Vec::new(),
       partial_eval_predicates: Vec::new(),
@@ -126,6 +130,18 @@ impl FunctionBuilder {
     self
   }
 
+  /// Sets the rule for how to integrate this function. If an
+  /// integral rule has already been set, then `set_integral` panics.
+  pub fn set_integral(mut self, rule: impl Fn(Vec<Expr>, &IntegrationEngine) -> Result<Expr, IntegrationFailure> + Send + Sync + 'static) -> Self {
+    assert!(
+      self.integral_rule.is_none(),
+      "Cannot set integral rule on function {} that already has one.",
+      self.name,
+    );
+    self.integral_rule = Some(Box::new(rule));
+    self
+  }
+
   /// Enables the
   /// [`PERMITS_FLATTENING`](FunctionFlags::PERMITS_FLATTENING) flag
   /// for `self`.
@@ -179,6 +195,7 @@ impl FunctionBuilder {
       flags: self.flags,
       identity_predicate: self.identity_predicate,
       derivative_rule: self.derivative_rule,
+      integral_rule: self.integral_rule,
       body: function_body,
       graphics_body: build_function_body(self.graphics_cases),
     }
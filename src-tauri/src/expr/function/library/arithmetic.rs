@@ -2,26 +2,30 @@
 //! Basic arithmetic function evaluation rules.
 
 use crate::expr::Expr;
-use crate::expr::interval::{Interval, interval_div, interval_div_inexact,
-                            interval_recip, interval_recip_inexact, includes_infinity};
+use crate::expr::interval::{Interval, IntervalOrScalar, interval_div, interval_div_inexact,
+                            interval_recip, interval_recip_inexact, interval_pow, includes_infinity};
 use crate::expr::function::{Function, FunctionContext};
 use crate::expr::function::table::FunctionTable;
 use crate::expr::function::builder::{self, FunctionBuilder, FunctionCaseResult};
 use crate::expr::vector::Vector;
+use crate::expr::tuple::Tuple;
+use crate::expr::modular::{ModularInt, ModularError, unify_moduli};
 use crate::expr::vector::matrix::Matrix;
 use crate::expr::vector::tensor::Tensor;
-use crate::expr::prisms::{self, expr_to_number, ExprToComplex, ExprToQuaternion};
+use crate::expr::vector::packed::{PackedTensor, real_quotient};
+use crate::expr::prisms::{self, expr_to_number, expr_to_i64, expr_to_typed_vector, ExprToComplex, ExprToQuaternion, ExprToOctonion};
 use crate::expr::predicates;
-use crate::expr::number::{Number, ComplexNumber, Quaternion, QuaternionLike,
+use crate::expr::number::{Number, ComplexNumber, Quaternion, QuaternionLike, Octonion,
                           pow_real, pow_complex, pow_complex_to_real};
 use crate::expr::number::inexact::{DivInexact, WithInexactDiv};
+use crate::expr::number::mixed_radix;
 use crate::expr::simplifier::error::{SimplifierError, DomainError};
-use crate::expr::calculus::DifferentiationError;
+use crate::expr::calculus::{DifferentiationError, IntegrationError};
 use crate::expr::algebra::infinity::{InfiniteConstant, UnboundedNumber, is_infinite_constant,
                                      multiply_infinities, infinite_pow};
 use crate::graphics::GRAPHICS_NAME;
 use crate::util::{repeated, TryPow};
-use crate::util::prism::Identity;
+use crate::util::prism::{Prism, Identity};
 use crate::util::matrix::{Matrix as UtilMatrix, SingularMatrixError};
 
 use num::{Zero, One, BigInt};
@@ -30,6 +34,10 @@ use try_traits::ops::{TryAdd, TrySub, TryMul, TryDiv};
 
 use std::cmp::Ordering;
 
+/// The largest number of decimal places, in either direction, to
+/// which `round` will round a value.
+pub const MAX_ROUNDING_PLACES: u64 = 10_000;
+
 pub fn append_arithmetic_functions(table: &mut FunctionTable) {
   table.insert(addition());
   table.insert(subtraction());
@@ -37,11 +45,16 @@ pub fn append_arithmetic_functions(table: &mut FunctionTable) {
   table.insert(division());
   table.insert(power());
   table.insert(modulo());
+  table.insert(modular_form());
   table.insert(floor_division());
+  table.insert(floor_division_with_remainder());
+  table.insert(to_mixed_radix());
+  table.insert(from_mixed_radix());
   table.insert(arithmetic_negate());
   table.insert(reciprocal());
   table.insert(abs());
   table.insert(signum());
+  table.insert(round());
 }
 
 pub fn addition() -> Function {
@@ -49,11 +62,12 @@ pub fn addition() -> Function {
     .permit_flattening()
     .permit_reordering()
     .set_identity(Expr::is_zero)
-    .add_partial_eval_rule(Box::new(predicates::is_quaternion))
+    .add_partial_eval_rule(Box::new(predicates::is_octonion))
     .add_partial_eval_rule(Box::new(predicates::is_tensor))
     .add_partial_eval_rule(Box::new(predicates::is_string))
     .add_partial_eval_rule(Box::new(predicates::is_complex_or_inf))
     .add_partial_eval_rule(Box::new(predicates::is_unbounded_interval_like))
+    .add_partial_eval_rule(Box::new(predicates::is_modular_int))
     .add_case(
       // Unary simplification
       builder::arity_one().and_then(|arg, _| {
@@ -87,6 +101,22 @@ pub fn addition() -> Function {
         Ok(Expr::from(sum))
       })
     )
+    .add_case(
+      // Octonion addition
+      builder::any_arity().of_type(ExprToOctonion).and_then(|args, _| {
+        let sum = args.into_iter()
+          .map(Octonion::from)
+          .reduce(|a, b| a + b)
+          .unwrap_or(Octonion::zero());
+        Ok(Expr::from(sum))
+      })
+    )
+    .add_case(
+      // Vector addition of real numbers (fast path)
+      builder::any_arity().of_type(prisms::ExprToPackedTensor).and_then(|args, _| {
+        fold_packed_tensors(args, |a, b| a.clone() + b.clone())
+      })
+    )
     .add_case(
       // Vector addition (with broadcasting)
       builder::any_arity().of_type(prisms::ExprToTensor).and_then(|args, context| {
@@ -134,6 +164,12 @@ pub fn addition() -> Function {
         }
       })
     )
+    .add_case(
+      // Modular integer addition
+      builder::any_arity().of_type(prisms::expr_to_modular_like()).and_then(|args, ctx| {
+        fold_modular(ctx, "+", args, ModularInt::checked_add)
+      })
+    )
     .add_case(
       // Graphics object concatenation
       builder::any_arity().of_type(prisms::Graphics2D::prism()).and_then(|args, _| {
@@ -149,6 +185,12 @@ pub fn addition() -> Function {
         Ok(Expr::call("+", args))
       }
     )
+    .set_integral(
+      |args, engine| {
+        let args = engine.integrate_each(args)?;
+        Ok(Expr::call("+", args))
+      }
+    )
     .build()
 }
 
@@ -175,6 +217,19 @@ pub fn subtraction() -> Function {
         Ok(Expr::from(difference))
       })
     )
+    .add_case(
+      // Octonion subtraction
+      builder::arity_two().both_of_type(ExprToOctonion).and_then(|arg1, arg2, _| {
+        let difference = Octonion::from(arg1) - Octonion::from(arg2);
+        Ok(Expr::from(difference))
+      })
+    )
+    .add_case(
+      // Vector subtraction of real numbers (fast path)
+      builder::arity_two().both_of_type(prisms::ExprToPackedTensor).and_then(|arg1, arg2, _| {
+        binary_packed_tensors(arg1, arg2, |a, b| a.clone() - b.clone())
+      })
+    )
     .add_case(
       // Vector subtraction (with broadcasting)
       builder::arity_two().both_of_type(prisms::ExprToTensor).and_then(|arg1, arg2, context| {
@@ -217,11 +272,22 @@ pub fn subtraction() -> Function {
         }
       })
     )
+    .add_case(
+      // Modular integer subtraction
+      builder::arity_two().both_of_type(prisms::expr_to_modular_like()).and_then(|arg1, arg2, ctx| {
+        binary_modular(ctx, "-", arg1, arg2, ModularInt::checked_sub)
+      })
+    )
     .set_derivative(
       builder::arity_two_deriv("-", |arg1, arg2, engine| {
         Ok(Expr::call("-", vec![engine.differentiate(arg1)?, engine.differentiate(arg2)?]))
       })
     )
+    .set_integral(
+      builder::arity_two_integral("-", |arg1, arg2, engine| {
+        Ok(Expr::call("-", vec![engine.integrate(arg1)?, engine.integrate(arg2)?]))
+      })
+    )
     .build()
 }
 
@@ -233,6 +299,7 @@ pub fn multiplication() -> Function {
     .add_partial_eval_rule(Box::new(predicates::is_tensor))
     .add_partial_eval_rule(Box::new(predicates::is_complex_or_inf))
     .add_partial_eval_rule(Box::new(predicates::is_unbounded_interval_like))
+    .add_partial_eval_rule(Box::new(predicates::is_modular_int))
     .add_case(
       // Unary simplification
       builder::arity_one().and_then(|arg, _| {
@@ -279,13 +346,33 @@ pub fn multiplication() -> Function {
       })
     )
     .add_case(
-      // Quaternions (Trap case; you must use '@' not '*' for
-      // non-commutative multiplication)
-      builder::any_arity().of_type(ExprToQuaternion).and_then(|args, context| {
-        context.errors.push(
-          SimplifierError::new("*", DomainError::new("Quaternion multiplication is non-commutative; use @ not *")),
-        );
-        Err(args)
+      // Quaternion multiplication, in the order written. FactorSorter
+      // declines to reorder products containing quaternion literals,
+      // so the written order is preserved up to this point.
+      builder::any_arity().of_type(ExprToQuaternion).and_then(|args, _| {
+        let product = args.into_iter()
+          .map(Quaternion::from)
+          .reduce(|a, b| a * b)
+          .unwrap_or(Quaternion::one());
+        Ok(Expr::from(product))
+      })
+    )
+    .add_case(
+      // Octonion multiplication. Octonions are not associative, so
+      // the product is taken left-to-right, consistent with how the
+      // parser associates `*`.
+      builder::any_arity().of_type(ExprToOctonion).and_then(|args, _| {
+        let product = args.into_iter()
+          .map(Octonion::from)
+          .reduce(|a, b| a * b)
+          .unwrap_or(Octonion::one());
+        Ok(Expr::from(product))
+      })
+    )
+    .add_case(
+      // Vector multiplication of real numbers (fast path)
+      builder::any_arity().of_type(prisms::ExprToPackedTensor).and_then(|args, _| {
+        fold_packed_tensors(args, |a, b| a.clone() * b.clone())
       })
     )
     .add_case(
@@ -323,6 +410,12 @@ pub fn multiplication() -> Function {
         }
       })
     )
+    .add_case(
+      // Modular integer multiplication
+      builder::any_arity().of_type(prisms::expr_to_modular_like()).and_then(|args, ctx| {
+        fold_modular(ctx, "*", args, ModularInt::checked_mul)
+      })
+    )
     .set_derivative(
       |args, engine| {
         let mut final_terms = Vec::with_capacity(args.len());
@@ -341,6 +434,22 @@ pub fn multiplication() -> Function {
         Ok(Expr::call("+", final_terms))
       }
     )
+    .set_integral(
+      |args, engine| {
+        // Pull constant factors out of the integral. We can only
+        // integrate the product if at most one factor is
+        // non-constant.
+        let (constant_factors, mut variable_factors): (Vec<_>, Vec<_>) =
+          args.into_iter().partition(|arg| engine.is_constant(arg));
+        if variable_factors.len() != 1 {
+          return Err(engine.error(IntegrationError::custom_error("Cannot integrate product of non-constant terms")));
+        }
+        let integral = engine.integrate(variable_factors.pop().unwrap())?; // unwrap: len() == 1
+        let mut factors = constant_factors;
+        factors.push(integral);
+        Ok(Expr::call("*", factors))
+      }
+    )
     .build()
 }
 
@@ -358,12 +467,7 @@ pub fn division() -> Function {
         if arg2.is_zero() {
           return division_by_zero(context, "/", (arg1, arg2));
         }
-        let quotient = if context.calculation_mode.has_fractional_flag() {
-          arg1 / arg2
-        } else {
-          arg1.div_inexact(&arg2)
-        };
-        Ok(Expr::from(quotient))
+        Ok(Expr::from(real_quotient(arg1, arg2, &context.calculation_mode)))
       })
     )
     .add_case(
@@ -382,6 +486,16 @@ pub fn division() -> Function {
         Ok(Expr::from(quotient))
       })
     )
+    .add_case(
+      // Vector division of real numbers (fast path). Division by zero
+      // is left to the general case, which reports it.
+      builder::arity_two().both_of_type(prisms::ExprToPackedTensor).and_then(|arg1, arg2, context| {
+        if arg2.data().iter().any(Number::is_zero) {
+          return Err((arg1, arg2));
+        }
+        binary_packed_tensors(arg1, arg2, |a, b| real_quotient(a.clone(), b.clone(), &context.calculation_mode))
+      })
+    )
     .add_case(
       // Vector division (with broadcasting)
       builder::arity_two().both_of_type(prisms::ExprToTensor).and_then(|arg1, arg2, context| {
@@ -463,6 +577,12 @@ pub fn division() -> Function {
         }
       })
     )
+    .add_case(
+      // Modular integer division
+      builder::arity_two().both_of_type(prisms::expr_to_modular_like()).and_then(|arg1, arg2, ctx| {
+        binary_modular(ctx, "/", arg1, arg2, ModularInt::checked_div)
+      })
+    )
     .set_derivative(
       builder::arity_two_deriv("/", |arg1, arg2, engine| {
         let arg1_deriv = engine.differentiate(arg1.clone())?;
@@ -482,6 +602,20 @@ pub fn division() -> Function {
         }
       })
     )
+    .set_integral(
+      builder::arity_two_integral("/", |arg1, arg2, engine| {
+        if engine.is_constant(&arg2) {
+          // Constant denominator
+          Ok(Expr::call("/", vec![engine.integrate(arg1)?, arg2]))
+        } else if engine.is_constant(&arg1) {
+          // Constant numerator; integrate as a negative power.
+          let recip = engine.integrate(Expr::call("^", vec![arg2, Expr::from(-1)]))?;
+          Ok(Expr::call("*", vec![arg1, recip]))
+        } else {
+          Err(engine.error(IntegrationError::custom_error("Cannot integrate quotient of non-constant terms")))
+        }
+      })
+    )
     .build() // TODO Trap case for quaternions if we implement a division variant of the infix `@`
 }
 
@@ -580,6 +714,28 @@ pub fn power() -> Function {
         Ok(result.into())
       })
     )
+    .add_case(
+      // Octonion power function (only integer powers are supported)
+      builder::arity_two().of_types(ExprToOctonion, ExprToQuaternion).and_then(|arg1, arg2, context| {
+        let arg2 = match try_quat_into_bigint(arg2) {
+          Ok(arg2) => arg2,
+          Err(original_arg2) => {
+            context.errors.push(SimplifierError::custom_error("^", "Only integer powers are supported for octonion exponentiation"));
+            return Err((arg1, original_arg2));
+          }
+        };
+        let has_input_ratio = arg1.has_proper_ratio();
+        let result = Octonion::from(arg1).powi(arg2);
+        let result = if context.calculation_mode.has_fractional_flag() {
+          result
+        } else if result.has_proper_ratio() && !has_input_ratio {
+          result.to_inexact()
+        } else {
+          result
+        };
+        Ok(result.into())
+      })
+    )
     .add_case(
       // Vector to scalar power function
       //
@@ -623,6 +779,18 @@ pub fn power() -> Function {
         }
       })
     )
+    .add_case(
+      // Modular integer to integer power
+      builder::arity_two().of_types(prisms::ExprToModularInt, prisms::expr_to_bigint()).and_then(|arg1, arg2, ctx| {
+        match arg1.pow(&arg2) {
+          Ok(result) => Ok(result.into()),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("^", err));
+            Err((arg1, arg2))
+          }
+        }
+      })
+    )
     .add_case(
       // Infinity to real power
       builder::arity_two().of_types(prisms::ExprToInfinity, expr_to_number()).and_then(|arg1, arg2, _| {
@@ -715,6 +883,37 @@ pub fn power() -> Function {
         }
       })
     )
+    .add_case(
+      // Interval to real power, or real to interval power (nonnegative base only)
+      builder::arity_two().both_of_type(prisms::expr_to_unbounded_interval_like()).and_then(|arg1, arg2, ctx| {
+        if matches!((&arg1, &arg2), (IntervalOrScalar::Scalar(_), IntervalOrScalar::Scalar(_))) {
+          return Err((arg1, arg2));
+        }
+        let base = Interval::from(arg1.clone());
+        let exponent = Interval::from(arg2.clone());
+        if base.left() < &UnboundedNumber::zero() {
+          ctx.errors.push(SimplifierError::custom_error("^", "Expected nonnegative base for interval exponentiation"));
+          return Err((arg1, arg2));
+        }
+        let inputs_have_infinity = includes_infinity(&base) || includes_infinity(&exponent);
+        match interval_pow(base, exponent) {
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("^", err));
+            Err((arg1, arg2))
+          }
+          Ok(result) => {
+            if !inputs_have_infinity && includes_infinity(&result) && !ctx.calculation_mode.has_infinity_flag() {
+              // Do not produce infinities if the calculation mode
+              // doesn't allow it.
+              ctx.errors.push(SimplifierError::division_by_zero("^"));
+              Err((arg1, arg2))
+            } else {
+              Ok(result.into())
+            }
+          }
+        }
+      })
+    )
     .set_derivative(
       builder::arity_two_deriv("^", |arg1, arg2, engine| {
         // TODO: Write a variant of postorder_walk that produces `()`
@@ -769,6 +968,36 @@ pub fn power() -> Function {
         }
       })
     )
+    .set_integral(
+      builder::arity_two_integral("^", |arg1, arg2, engine| {
+        if engine.is_constant(&arg2) {
+          // Power Rule (or logarithm, for exponent -1)
+          let is_negative_one = expr_to_number().narrow_type(arg2.clone())
+            .is_ok_and(|n| n == Number::from(-1));
+          engine.integrate_linear_substitution("^", arg1, |u| {
+            if is_negative_one {
+              Expr::call("ln", vec![u])
+            } else {
+              let new_exponent = Expr::call("+", vec![arg2, Expr::from(1)]);
+              Expr::call("/", vec![
+                Expr::call("^", vec![u, new_exponent.clone()]),
+                new_exponent,
+              ])
+            }
+          })
+        } else if engine.is_constant(&arg1) {
+          // Exponent Rule
+          engine.integrate_linear_substitution("^", arg2, |u| {
+            Expr::call("/", vec![
+              Expr::call("^", vec![arg1.clone(), u]),
+              Expr::call("ln", vec![arg1]),
+            ])
+          })
+        } else {
+          Err(engine.error(IntegrationError::custom_error("Cannot integrate variable raised to variable power")))
+        }
+      })
+    )
     .build()
 }
 
@@ -820,6 +1049,23 @@ pub fn modulo() -> Function {
     .build()
 }
 
+pub fn modular_form() -> Function {
+  FunctionBuilder::new(ModularInt::FUNCTION_NAME)
+    .add_case(
+      // Normalize the value into the range [0, m)
+      builder::arity_two().both_of_type(prisms::expr_to_bigint()).and_then(|value, modulus, ctx| {
+        match ModularInt::new(value.clone(), modulus.clone()) {
+          Ok(result) => Ok(Expr::from(result)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new(ModularInt::FUNCTION_NAME, err));
+            Err((value, modulus))
+          }
+        }
+      })
+    )
+    .build()
+}
+
 pub fn floor_division() -> Function {
   FunctionBuilder::new("div")
     .add_case(
@@ -841,6 +1087,70 @@ pub fn floor_division() -> Function {
     .build()
 }
 
+pub fn floor_division_with_remainder() -> Function {
+  FunctionBuilder::new("divmod")
+    .add_case(
+      // Real floor div, paired with the remainder
+      builder::arity_two().both_of_type(expr_to_number()).and_then(|arg1, arg2, context| {
+        if arg2.is_zero() {
+          context.errors.push(SimplifierError::division_by_zero("divmod"));
+          return Err((arg1, arg2));
+        }
+        let quotient = arg1.div_floor(&arg2);
+        let remainder = arg1 % arg2;
+        Ok(Tuple::from([Expr::from(quotient), Expr::from(remainder)]).into())
+      })
+    )
+    .add_case(
+      // Trap case: Complex numbers / quaternions
+      builder::arity_two().both_of_type(ExprToQuaternion).and_then(|arg1, arg2, context| {
+        context.errors.push(SimplifierError::expected_real("divmod"));
+        Err((arg1, arg2))
+      })
+    )
+    .build()
+}
+
+/// `to_mixed_radix(n, bases)` splits `n` into a vector of digits in
+/// the given mixed radix, such as `to_mixed_radix(n, [24, 60, 60])`
+/// to write a number of seconds as days, hours, minutes, and seconds.
+/// See [`mixed_radix::to_mixed_radix`].
+pub fn to_mixed_radix() -> Function {
+  FunctionBuilder::new("to_mixed_radix")
+    .add_case(
+      builder::arity_two().of_types(expr_to_number(), expr_to_typed_vector(expr_to_number())).and_then(|n, bases, ctx| {
+        match mixed_radix::to_mixed_radix(&n, &bases) {
+          Some(digits) => Ok(Expr::call("vector", digits.into_iter().map(Expr::from).collect())),
+          None => {
+            ctx.errors.push(SimplifierError::new("to_mixed_radix", DomainError::new("Expected positive bases")));
+            Err((n, bases))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// `from_mixed_radix(digits, bases)` is the inverse of
+/// [`to_mixed_radix`]. There must be exactly one more digit than
+/// there are bases.
+pub fn from_mixed_radix() -> Function {
+  FunctionBuilder::new("from_mixed_radix")
+    .add_case(
+      builder::arity_two().both_of_type(expr_to_typed_vector(expr_to_number())).and_then(|digits, bases, ctx| {
+        match mixed_radix::from_mixed_radix(&digits, &bases) {
+          Some(n) => Ok(Expr::from(n)),
+          None => {
+            let err = DomainError::new("Expected exactly one more digit than there are bases");
+            ctx.errors.push(SimplifierError::new("from_mixed_radix", err));
+            Err((digits, bases))
+          }
+        }
+      })
+    )
+    .build()
+}
+
 pub fn arithmetic_negate() -> Function {
   FunctionBuilder::new("negate")
     .mark_as_involution()
@@ -864,6 +1174,13 @@ pub fn arithmetic_negate() -> Function {
         Ok(Expr::from(- arg))
       })
     )
+    .add_case(
+      // Octonion negation
+      builder::arity_one().of_type(ExprToOctonion).and_then(|arg, _| {
+        let arg = Octonion::from(arg);
+        Ok(Expr::from(- arg))
+      })
+    )
     .add_case(
       // Negation of a vector
       builder::arity_one().of_type(prisms::ExprToVector).and_then(|arg, _| {
@@ -884,11 +1201,22 @@ pub fn arithmetic_negate() -> Function {
         Ok(Expr::from(- arg))
       })
     )
+    .add_case(
+      // Negation of a modular integer
+      builder::arity_one().of_type(prisms::ExprToModularInt).and_then(|arg, _| {
+        Ok(Expr::from(arg.negate()))
+      })
+    )
     .set_derivative(
       builder::arity_one_deriv("negate", |arg, engine| {
         Ok(Expr::call("negate", vec![engine.differentiate(arg)?]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("negate", |arg, engine| {
+        Ok(Expr::call("negate", vec![engine.integrate(arg)?]))
+      })
+    )
     .build()
 }
 
@@ -923,6 +1251,20 @@ pub fn reciprocal() -> Function {
         }
       })
     )
+    .add_case(
+      // Octonion reciprocal
+      builder::arity_one().of_type(ExprToOctonion).and_then(|arg, ctx| {
+        if arg.is_zero() {
+          return division_by_zero(ctx, "recip", arg);
+        }
+        let arg = Octonion::from(arg);
+        if ctx.calculation_mode.has_fractional_flag() {
+          Ok(Expr::from(arg.recip()))
+        } else {
+          Ok(Expr::from(arg.recip_inexact()))
+        }
+      })
+    )
     .add_case(
       // Inverse of a matrix
       builder::arity_one().of_type(prisms::ExprToTypedMatrix::new(ExprToComplex)).and_then(|mat, ctx| {
@@ -963,6 +1305,18 @@ pub fn reciprocal() -> Function {
         }
       })
     )
+    .add_case(
+      // Inverse of a modular integer
+      builder::arity_one().of_type(prisms::ExprToModularInt).and_then(|arg, ctx| {
+        match arg.inverse() {
+          Ok(inverse) => Ok(Expr::from(inverse)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("recip", err));
+            Err(arg)
+          }
+        }
+      })
+    )
     .set_derivative(
       builder::arity_one_deriv("recip", |arg, engine| {
         let arg_deriv = engine.differentiate(arg.clone())?;
@@ -972,14 +1326,20 @@ pub fn reciprocal() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("recip", |arg, engine| {
+        engine.integrate_linear_substitution("recip", arg, |u| Expr::call("ln", vec![u]))
+      })
+    )
     .build()
 }
 
-fn inverse_matrix(
+pub(super) fn inverse_matrix(
   mat: UtilMatrix<ComplexNumber>,
   ctx: &FunctionContext,
 ) -> Result<UtilMatrix<ComplexNumber>, SingularMatrixError> {
-  if ctx.calculation_mode.has_fractional_flag() {
+  let mode = &ctx.calculation_mode;
+  if mode.has_fractional_flag() || mode.has_exact_matrix_flag() {
     mat.inverse_matrix()
   } else {
     let res = mat.map(WithInexactDiv).inverse_matrix()?;
@@ -1016,6 +1376,13 @@ pub fn abs() -> Function {
         Ok(Expr::from(arg.abs()))
       })
     )
+    .add_case(
+      // Octonion length
+      builder::arity_one().of_type(ExprToOctonion).and_then(|arg, _| {
+        let arg = Octonion::from(arg);
+        Ok(Expr::from(arg.abs()))
+      })
+    )
     .add_case(
       // Norm of a vector
       builder::arity_one().of_type(prisms::ExprToVector).and_then(|arg, _| {
@@ -1080,6 +1447,37 @@ pub fn signum() -> Function {
     .build()
 }
 
+/// `round(x)` rounds a real number to an integer, and `round(x, n)`
+/// rounds it to `n` decimal places. Which way a value rounds is
+/// decided by the rounding mode of the calculation mode. See
+/// [`Number::round_to_places`].
+pub fn round() -> Function {
+  FunctionBuilder::new("round")
+    .add_case(
+      builder::arity_one().of_type(expr_to_number()).and_then(|arg, ctx| {
+        Ok(Expr::from(arg.round_to_places(0, ctx.calculation_mode.rounding_mode())))
+      })
+    )
+    .add_case(
+      builder::arity_two().of_types(expr_to_number(), expr_to_i64()).and_then(|arg, places, ctx| {
+        if places.unsigned_abs() > MAX_ROUNDING_PLACES {
+          let err = DomainError::new(format!("Can round to at most {MAX_ROUNDING_PLACES} places"));
+          ctx.errors.push(SimplifierError::new("round", err));
+          return Err((arg, places));
+        }
+        Ok(Expr::from(arg.round_to_places(places, ctx.calculation_mode.rounding_mode())))
+      })
+    )
+    .add_case(
+      // Trap case: Complex numbers / quaternions
+      builder::arity_one().of_type(ExprToQuaternion).and_then(|arg, ctx| {
+        ctx.errors.push(SimplifierError::expected_real("round"));
+        Err(arg)
+      })
+    )
+    .build()
+}
+
 fn vector_norm(vec: Vector) -> Expr {
   let addends = vec.into_iter().map(|x| Expr::call("^", vec![x, Expr::from(2)])).collect();
   Expr::call("^", vec![
@@ -1091,6 +1489,74 @@ fn vector_norm(vec: Vector) -> Expr {
 /// Returns [`InfiniteConstant::UndirInfinity`] if the infinity flag
 /// on `context` is set, or produces an error and refuses to evaluate
 /// otherwise.
+/// Combines modular integers, and any ordinary integers among them,
+/// with the given operation. Falls through if none of the arguments
+/// is a modular integer.
+fn fold_modular<F>(
+  context: &mut FunctionContext,
+  function_name: &str,
+  args: Vec<Either<ModularInt, BigInt>>,
+  mut f: F,
+) -> Result<Expr, Vec<Either<ModularInt, BigInt>>>
+where F: FnMut(&ModularInt, &ModularInt) -> Result<ModularInt, ModularError> {
+  let values = match unify_moduli(&args) {
+    Ok(Some(values)) => values,
+    Ok(None) => return Err(args),
+    Err(err) => {
+      context.errors.push(SimplifierError::new(function_name, err));
+      return Err(args);
+    }
+  };
+  let mut values = values.into_iter();
+  let first = values.next().unwrap(); // unwrap: unify_moduli found at least one modular integer
+  match values.try_fold(first, |a, b| f(&a, &b)) {
+    Ok(result) => Ok(Expr::from(result)),
+    Err(err) => {
+      context.errors.push(SimplifierError::new(function_name, err));
+      Err(args)
+    }
+  }
+}
+
+#[allow(clippy::type_complexity)]
+fn binary_modular<F>(
+  context: &mut FunctionContext,
+  function_name: &str,
+  arg1: Either<ModularInt, BigInt>,
+  arg2: Either<ModularInt, BigInt>,
+  f: F,
+) -> Result<Expr, (Either<ModularInt, BigInt>, Either<ModularInt, BigInt>)>
+where F: FnMut(&ModularInt, &ModularInt) -> Result<ModularInt, ModularError> {
+  fold_modular(context, function_name, vec![arg1, arg2], f).map_err(|args| {
+    let [arg1, arg2] = <[_; 2]>::try_from(args).unwrap(); // unwrap: We passed exactly two arguments
+    (arg1, arg2)
+  })
+}
+
+/// Combines packed tensors elementwise with the given operation.
+/// Falls through if none of the arguments is a vector, or if their
+/// shapes are incompatible (in which case the general tensor case
+/// reports the error).
+fn fold_packed_tensors<F>(args: Vec<PackedTensor>, mut f: F) -> Result<Expr, Vec<PackedTensor>>
+where F: FnMut(&Number, &Number) -> Number {
+  if args.iter().all(|arg| arg.rank() == 0) || PackedTensor::broadcast_shape(args.iter()).is_none() {
+    return Err(args);
+  }
+  let mut args = args.into_iter();
+  let first = args.next().unwrap(); // unwrap: At least one argument is a vector
+  // unwrap: We checked above that the shapes are compatible.
+  let result = args.fold(first, |a, b| a.try_broadcasted_op(b, &mut f).unwrap());
+  Ok(result.into())
+}
+
+fn binary_packed_tensors<F>(arg1: PackedTensor, arg2: PackedTensor, f: F) -> Result<Expr, (PackedTensor, PackedTensor)>
+where F: FnMut(&Number, &Number) -> Number {
+  fold_packed_tensors(vec![arg1, arg2], f).map_err(|args| {
+    let [arg1, arg2] = <[_; 2]>::try_from(args).unwrap(); // unwrap: We passed exactly two arguments
+    (arg1, arg2)
+  })
+}
+
 fn division_by_zero<E>(context: &mut FunctionContext, function_name: &str, err: E) -> Result<Expr, E> {
   if context.calculation_mode.has_infinity_flag() {
     Ok(Expr::from(InfiniteConstant::UndirInfinity))
@@ -0,0 +1,67 @@
+
+//! Positions of the sun and moon.
+//!
+//! Dates are given as UTC strings in ISO 8601 format, and sunrise and
+//! sunset are returned in the same format. Latitude and longitude are
+//! interpreted according to the current angle mode.
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{expr_to_string, expr_to_number};
+use crate::expr::simplifier::error::SimplifierError;
+use crate::util::astronomy::{self, SunEvents};
+use crate::util::calendar::{parse_iso_datetime, format_iso_datetime};
+
+pub fn append_astronomy_functions(table: &mut FunctionTable) {
+  table.insert(sun_event_function("sunrise", |events| events.sunrise));
+  table.insert(sun_event_function("sunset", |events| events.sunset));
+  table.insert(moon_phase());
+}
+
+/// A function `f(date, latitude, longitude)` returning the time of
+/// a solar event on the given day.
+fn sun_event_function(name: &'static str, select: fn(&SunEvents) -> f64) -> Function {
+  FunctionBuilder::new(name)
+    .add_case(
+      builder::arity_three().of_types(expr_to_string(), expr_to_number(), expr_to_number()).and_then(move |date, lat, lon, ctx| {
+        let angle_mode = ctx.calculation_mode.angle_mode();
+        let result = parse_iso_datetime(&date).map_err(anyhow::Error::from).and_then(|julian_date| {
+          let events = astronomy::sun_events(
+            julian_date,
+            angle_mode.to_radians(lat.to_f64_or_nan()),
+            angle_mode.to_radians(lon.to_f64_or_nan()),
+          )?;
+          Ok(format_iso_datetime(select(&events)))
+        });
+        match result {
+          Ok(datetime) => Ok(Expr::from(datetime)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new(name, err));
+            Err((date, lat, lon))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// The fraction of the lunar cycle elapsed at the given date, from 0
+/// (new moon) through 0.5 (full moon) and back around to 1.
+pub fn moon_phase() -> Function {
+  FunctionBuilder::new("moon_phase")
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(|date, ctx| {
+        match parse_iso_datetime(&date) {
+          Ok(julian_date) => Ok(Expr::from(Number::from(astronomy::moon_phase(julian_date)))),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("moon_phase", err));
+            Err(date)
+          }
+        }
+      })
+    )
+    .build()
+}
@@ -1,10 +1,14 @@
 
+use crate::expr::Expr;
 use crate::expr::function::Function;
-use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::function::builder::{self, FunctionBuilder, FunctionCaseResult};
 use crate::expr::function::table::FunctionTable;
+use crate::expr::calculus::{DerivativeEngine, DifferentiationFailure};
 use crate::expr::predicates;
-use crate::expr::prisms;
+use crate::expr::prisms::{self, ExprToComplex};
 use crate::expr::number::ComplexLike;
+use crate::expr::simplifier::error::SimplifierError;
+use crate::util::prism::{Prism, Identity};
 
 use num::{Zero, One};
 
@@ -12,6 +16,13 @@ pub fn append_basic_functions(table: &mut FunctionTable) {
   table.insert(identity_function());
   table.insert(or_function());
   table.insert(and_function());
+  table.insert(logical_and_function());
+  table.insert(logical_or_function());
+  table.insert(logical_not_function());
+  table.insert(logical_xor_function());
+  table.insert(implies_function());
+  table.insert(if_function());
+  table.insert(piecewise_function());
 }
 
 pub fn identity_function() -> Function {
@@ -66,3 +77,300 @@ pub fn and_function() -> Function {
     )
     .build()
 }
+
+// Unlike `&&` and `||`, the functions `and`, `or`, `not`, `xor`, and
+// `implies` always produce 0 or 1. Any nonzero number is considered
+// true. When partial evaluation leaves a single unknown argument `x`,
+// the result is `x != 0` rather than `x` itself, so that it is still
+// 0 or 1 once `x` is known.
+
+pub fn logical_and_function() -> Function {
+  FunctionBuilder::new("and")
+    .permit_flattening()
+    .permit_reordering()
+    .add_case(
+      Box::new(|args, _| short_circuit("and", args, false))
+    )
+    .build()
+}
+
+pub fn logical_or_function() -> Function {
+  FunctionBuilder::new("or")
+    .permit_flattening()
+    .permit_reordering()
+    .add_case(
+      Box::new(|args, _| short_circuit("or", args, true))
+    )
+    .build()
+}
+
+pub fn logical_not_function() -> Function {
+  FunctionBuilder::new("not")
+    .add_case(
+      // Known truth value
+      builder::arity_one().of_type(ExprToComplex).and_then(|arg, _| {
+        Ok(Expr::from(arg.is_zero()))
+      })
+    )
+    .add_case(
+      // Double negation
+      Box::new(|args, _| {
+        let [Expr::Call(f, inner_args)] = args.as_slice() else {
+          return FunctionCaseResult::NoMatch(args);
+        };
+        if f != "not" || inner_args.len() != 1 {
+          return FunctionCaseResult::NoMatch(args);
+        }
+        FunctionCaseResult::Success(is_truthy(inner_args[0].clone()))
+      })
+    )
+    .add_case(
+      // De Morgan's laws
+      Box::new(|args, _| {
+        let [Expr::Call(f, inner_args)] = args.as_slice() else {
+          return FunctionCaseResult::NoMatch(args);
+        };
+        let dual = match f.as_str() {
+          "and" => "or",
+          "or" => "and",
+          "&&" => "||",
+          "||" => "&&",
+          _ => return FunctionCaseResult::NoMatch(args),
+        };
+        let negated_args = inner_args.iter()
+          .map(|arg| Expr::call("not", vec![arg.clone()]))
+          .collect();
+        FunctionCaseResult::Success(Expr::call(dual, negated_args))
+      })
+    )
+    .build()
+}
+
+pub fn logical_xor_function() -> Function {
+  FunctionBuilder::new("xor")
+    .permit_flattening()
+    .permit_reordering()
+    .add_case(
+      Box::new(|args, _| {
+        let mut parity = false;
+        let mut unknown_args = Vec::with_capacity(args.len());
+        for arg in &args {
+          match truth_value(arg) {
+            Some(truth) => parity ^= truth,
+            None => unknown_args.push(arg.clone()),
+          }
+        }
+        if unknown_args.is_empty() {
+          FunctionCaseResult::Success(Expr::from(parity))
+        } else if unknown_args.len() == args.len() {
+          FunctionCaseResult::NoMatch(args)
+        } else {
+          let result = match (parity, unknown_args.len()) {
+            (true, 1) => Expr::call("not", unknown_args),
+            (true, _) => Expr::call("not", vec![Expr::call("xor", unknown_args)]),
+            (false, 1) => is_truthy(unknown_args.swap_remove(0)),
+            (false, _) => Expr::call("xor", unknown_args),
+          };
+          FunctionCaseResult::Success(result)
+        }
+      })
+    )
+    .build()
+}
+
+pub fn implies_function() -> Function {
+  FunctionBuilder::new("implies")
+    .add_case(
+      Box::new(|mut args, _| {
+        if args.len() != 2 {
+          return FunctionCaseResult::NoMatch(args);
+        }
+        match (truth_value(&args[0]), truth_value(&args[1])) {
+          (Some(false), _) | (_, Some(true)) => FunctionCaseResult::Success(Expr::from(true)),
+          (Some(true), Some(false)) => FunctionCaseResult::Success(Expr::from(false)),
+          (Some(true), None) => FunctionCaseResult::Success(is_truthy(args.swap_remove(1))),
+          (None, Some(false)) => FunctionCaseResult::Success(Expr::call("not", vec![args.swap_remove(0)])),
+          _ => FunctionCaseResult::NoMatch(args),
+        }
+      })
+    )
+    .build()
+}
+
+/// Evaluates `and` (if `absorbing` is false) or `or` (if `absorbing`
+/// is true). An argument whose truth value is `absorbing` determines
+/// the result, and arguments with the other truth value are dropped.
+fn short_circuit(function_name: &str, args: Vec<Expr>, absorbing: bool) -> FunctionCaseResult<Expr> {
+  let truths: Vec<_> = args.iter().map(truth_value).collect();
+  if truths.contains(&Some(absorbing)) {
+    FunctionCaseResult::Success(Expr::from(absorbing))
+  } else if truths.iter().all(Option::is_some) {
+    FunctionCaseResult::Success(Expr::from(!absorbing))
+  } else if truths.iter().any(Option::is_some) {
+    let mut unknown_args: Vec<_> = args.into_iter()
+      .zip(truths)
+      .filter(|(_, truth)| truth.is_none())
+      .map(|(arg, _)| arg)
+      .collect();
+    if unknown_args.len() == 1 {
+      FunctionCaseResult::Success(is_truthy(unknown_args.swap_remove(0)))
+    } else {
+      FunctionCaseResult::Success(Expr::call(function_name, unknown_args))
+    }
+  } else {
+    FunctionCaseResult::NoMatch(args)
+  }
+}
+
+pub fn if_function() -> Function {
+  // `if(cond, a, b)` is `a` if `cond` is nonzero and `b` if `cond` is
+  // zero.
+  FunctionBuilder::new("if")
+    .add_case(
+      // Known condition
+      builder::arity_three().of_types(ExprToComplex, Identity, Identity).and_then(|cond, then_branch, else_branch, _| {
+        Ok(if cond.is_zero() { else_branch } else { then_branch })
+      })
+    )
+    .add_case(
+      // Both branches are the same, so the condition is irrelevant
+      Box::new(|mut args, _| {
+        if args.len() == 3 && args[1] == args[2] {
+          FunctionCaseResult::Success(args.swap_remove(1))
+        } else {
+          FunctionCaseResult::NoMatch(args)
+        }
+      })
+    )
+    .set_derivative(differentiate_branches("if"))
+    .build()
+}
+
+pub fn piecewise_function() -> Function {
+  // `piecewise(c1, v1, c2, v2, ..., default)` is the value `vi` of
+  // the first nonzero condition `ci`. The default value is optional,
+  // and is used if every condition is zero.
+  FunctionBuilder::new("piecewise")
+    .add_case(
+      Box::new(|args, context| {
+        if args.is_empty() {
+          return FunctionCaseResult::NoMatch(args);
+        }
+        if args.len() % 2 == 0 && args.iter().step_by(2).all(|cond| truth_value(cond) == Some(false)) {
+          context.errors.push(SimplifierError::custom_error("piecewise", "No branch of piecewise function applies"));
+          return FunctionCaseResult::Failure(args);
+        }
+        let original_len = args.len();
+        let mut branches = Vec::with_capacity(args.len());
+        let mut default = None;
+        let mut args = args.into_iter();
+        while let Some(cond) = args.next() {
+          let Some(value) = args.next() else {
+            default = Some(cond);
+            break;
+          };
+          match ExprToComplex.narrow_type(cond) {
+            Ok(cond) if cond.is_zero() => {}
+            Ok(_) => {
+              // This branch is taken whenever no earlier branch is,
+              // so it replaces the default.
+              default = Some(value);
+              break;
+            }
+            Err(cond) => {
+              branches.push(cond);
+              branches.push(value);
+            }
+          }
+        }
+        branches.extend(default);
+        if branches.len() == 1 {
+          // Only the default value remains.
+          return FunctionCaseResult::Success(branches.swap_remove(0));
+        }
+        if branches.len() == original_len {
+          FunctionCaseResult::NoMatch(branches)
+        } else {
+          FunctionCaseResult::Success(Expr::call("piecewise", branches))
+        }
+      })
+    )
+    .set_derivative(differentiate_branches("piecewise"))
+    .build()
+}
+
+/// The truth value of the expression, if it is a number. Zero is
+/// false and every other number is true.
+fn truth_value(expr: &Expr) -> Option<bool> {
+  ExprToComplex.narrow_type(expr.clone()).ok().map(|z| !z.is_zero())
+}
+
+/// The expression `expr != 0`, which is 1 if `expr` is true and 0 if
+/// it is false.
+fn is_truthy(expr: Expr) -> Expr {
+  Expr::call("!=", vec![expr, Expr::zero()])
+}
+
+/// Derivative of `if` and `piecewise`, which differentiates each
+/// value in place and leaves the conditions alone.
+fn differentiate_branches(
+  function_name: &'static str,
+) -> impl Fn(Vec<Expr>, &DerivativeEngine) -> Result<Expr, DifferentiationFailure> + Send + Sync + 'static {
+  move |args, engine| {
+    let len = args.len();
+    let args = args.into_iter()
+      .enumerate()
+      .map(|(i, arg)| {
+        let is_value = i % 2 == 1 || i == len - 1;
+        if is_value { engine.differentiate(arg) } else { Ok(arg) }
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(Expr::call(function_name, args))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::expr::Expr;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::default_simplifier;
+  use crate::expr::simplifier::test_utils::run_simplifier_no_errors;
+
+  fn simplify(expr: Expr) -> Expr {
+    let function_table = build_function_table();
+    let simplifier = default_simplifier(&function_table);
+    run_simplifier_no_errors(&simplifier.as_ref(), expr)
+  }
+
+  fn x() -> Expr {
+    Expr::var("x").unwrap()
+  }
+
+  fn is_truthy(expr: Expr) -> Expr {
+    Expr::call("!=", vec![expr, Expr::from(0)])
+  }
+
+  #[test]
+  fn test_double_negation_is_zero_or_one() {
+    let expr = Expr::call("not", vec![Expr::call("not", vec![Expr::from(5)])]);
+    assert_eq!(simplify(expr), Expr::from(1));
+    let expr = Expr::call("not", vec![Expr::call("not", vec![x()])]);
+    assert_eq!(simplify(expr), is_truthy(x()));
+  }
+
+  #[test]
+  fn test_partial_short_circuit() {
+    assert_eq!(simplify(Expr::call("and", vec![x(), Expr::from(1)])), is_truthy(x()));
+    assert_eq!(simplify(Expr::call("or", vec![x(), Expr::from(0)])), is_truthy(x()));
+    assert_eq!(simplify(Expr::call("xor", vec![x(), Expr::from(0)])), is_truthy(x()));
+    assert_eq!(simplify(Expr::call("and", vec![x(), Expr::from(0)])), Expr::from(0));
+  }
+
+  #[test]
+  fn test_implies() {
+    assert_eq!(simplify(Expr::call("implies", vec![Expr::from(1), x()])), is_truthy(x()));
+    assert_eq!(simplify(Expr::call("implies", vec![Expr::from(0), x()])), Expr::from(1));
+    assert_eq!(simplify(Expr::call("implies", vec![x(), Expr::from(0)])), Expr::call("not", vec![x()]));
+    assert_eq!(simplify(Expr::call("implies", vec![x(), Expr::from(2)])), Expr::from(1));
+  }
+}
@@ -0,0 +1,159 @@
+
+//! Bitwise operations on integers, for programmer's calculator work.
+//!
+//! These functions respect the word size of the calculation mode (see
+//! [`CalculationMode::word_size`]). Without a word size, integers are
+//! treated as unbounded two's complement values.
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{expr_to_bigint, expr_to_i64};
+use crate::expr::simplifier::error::{SimplifierError, DomainError};
+use crate::mode::calculation::CalculationMode;
+
+use num::{BigInt, One, Zero};
+
+use std::ops::{BitAnd, BitOr, BitXor};
+
+/// The largest left shift permitted when no word size is set.
+pub const MAX_UNBOUNDED_SHIFT: u64 = 1_000_000;
+
+pub fn append_bitwise_functions(table: &mut FunctionTable) {
+  table.insert(band());
+  table.insert(bor());
+  table.insert(bxor());
+  table.insert(bnot());
+  table.insert(lsh());
+  table.insert(rsh());
+}
+
+/// Reduces `n` into the current word, if a word size is set.
+fn wrap(n: BigInt, calculation_mode: &CalculationMode) -> BigInt {
+  match calculation_mode.word_size() {
+    Some(word_size) => word_size.wrap(&n),
+    None => n,
+  }
+}
+
+fn integer(n: BigInt) -> Expr {
+  Expr::from(Number::from(n))
+}
+
+/// Builds an associative, commutative bitwise function from a binary
+/// operation and its identity element.
+fn bitwise_fold(
+  name: &str,
+  identity: fn() -> BigInt,
+  op: fn(BigInt, BigInt) -> BigInt,
+) -> Function {
+  FunctionBuilder::new(name)
+    .permit_flattening()
+    .permit_reordering()
+    .add_case(
+      builder::any_arity().of_type(expr_to_bigint()).and_then(move |args, ctx| {
+        let result = args.into_iter()
+          .map(|n| wrap(n, &ctx.calculation_mode))
+          .reduce(op)
+          .unwrap_or_else(identity);
+        Ok(integer(wrap(result, &ctx.calculation_mode)))
+      })
+    )
+    .build()
+}
+
+/// Shifts `n` left by `k` bits, or right if `k` is negative. Right
+/// shifts round toward negative infinity, so they are logical shifts
+/// on unsigned words and arithmetic shifts on signed ones.
+fn shift(n: BigInt, k: i64, calculation_mode: &CalculationMode) -> Result<BigInt, DomainError> {
+  let n = wrap(n, calculation_mode);
+  let amount = k.unsigned_abs();
+  if k < 0 {
+    // Shifting past the highest bit leaves only the sign.
+    let amount = amount.min(n.bits() + 1);
+    return Ok(n >> amount);
+  }
+  match calculation_mode.word_size() {
+    Some(word_size) if amount >= u64::from(word_size.bits()) => Ok(BigInt::zero()),
+    Some(word_size) => Ok(word_size.wrap(&(n << amount))),
+    None if amount > MAX_UNBOUNDED_SHIFT => {
+      Err(DomainError::new(format!("Shift amount must be at most {MAX_UNBOUNDED_SHIFT} without a word size")))
+    }
+    None => Ok(n << amount),
+  }
+}
+
+/// Builds a shift function. With one argument, shifts by one bit.
+/// `direction` is `1` for a left shift and `-1` for a right shift.
+fn shift_function(name: &'static str, direction: i64) -> Function {
+  FunctionBuilder::new(name)
+    .add_case(
+      builder::arity_one().of_type(expr_to_bigint()).and_then(move |n, ctx| {
+        match shift(n.clone(), direction, &ctx.calculation_mode) {
+          Ok(result) => Ok(integer(result)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new(name, err));
+            Err(n)
+          }
+        }
+      })
+    )
+    .add_case(
+      builder::arity_two().of_types(expr_to_bigint(), expr_to_i64()).and_then(move |n, k, ctx| {
+        let Some(signed_k) = k.checked_mul(direction) else {
+          ctx.errors.push(SimplifierError::new(name, DomainError::new("Shift amount out of range")));
+          return Err((n, k));
+        };
+        match shift(n.clone(), signed_k, &ctx.calculation_mode) {
+          Ok(result) => Ok(integer(result)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new(name, err));
+            Err((n, k))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// Bitwise AND of any number of integers.
+pub fn band() -> Function {
+  bitwise_fold("band", || -BigInt::one(), BigInt::bitand)
+}
+
+/// Bitwise inclusive OR of any number of integers.
+pub fn bor() -> Function {
+  bitwise_fold("bor", BigInt::zero, BigInt::bitor)
+}
+
+/// Bitwise exclusive OR of any number of integers.
+pub fn bxor() -> Function {
+  bitwise_fold("bxor", BigInt::zero, BigInt::bitxor)
+}
+
+/// Bitwise complement of an integer. Without a word size, this is
+/// `-n - 1`.
+pub fn bnot() -> Function {
+  FunctionBuilder::new("bnot")
+    .add_case(
+      builder::arity_one().of_type(expr_to_bigint()).and_then(|n, ctx| {
+        let n = wrap(n, &ctx.calculation_mode);
+        Ok(integer(wrap(-n - 1, &ctx.calculation_mode)))
+      })
+    )
+    .build()
+}
+
+/// Left shift of an integer, by one bit or by the given number of
+/// bits. Negative shift amounts shift right.
+pub fn lsh() -> Function {
+  shift_function("lsh", 1)
+}
+
+/// Right shift of an integer, by one bit or by the given number of
+/// bits. Negative shift amounts shift left.
+pub fn rsh() -> Function {
+  shift_function("rsh", -1)
+}
@@ -6,20 +6,46 @@ use crate::expr::Expr;
 use crate::expr::var::Var;
 use crate::expr::number::Number;
 use crate::expr::simplifier::error::SimplifierError;
+use crate::expr::simplifier::cancellable::ensure_not_cancelled;
 use crate::expr::function::{Function, FunctionContext};
 use crate::expr::function::table::FunctionTable;
 use crate::expr::function::builder::{self, FunctionBuilder};
-use crate::expr::prisms::{ExprToVar, expr_to_number};
-use crate::expr::calculus::differentiate;
+use crate::expr::prisms::{ExprToVar, expr_to_number, expr_to_usize, expr_to_interval, expr_to_typed_vector, expr_to_equation};
+use crate::expr::vector::Vector;
+use crate::expr::interval::RawInterval;
+use crate::expr::distribution::{SampleDistribution, ExprToDistribution};
+use crate::expr::algebra::{ExprFunction, ExprFunctionN, FunctionEvalError};
+use crate::expr::algebra::formula::Equation;
+use crate::expr::algebra::quadrature::{GaussKronrod, QuadratureError};
+use crate::expr::calculus::{differentiate_with_rules, integrate, richardson_derivative, estimate_expectation,
+                            integrate_over_box, DualFunction, NumericalDerivative, MonteCarloEstimate,
+                            MonteCarloError, LimitDirection, SensitivityInput, limit as compute_limit, one_sided_limit,
+                            sensitivity_analysis};
+use crate::util::prism::{Prism, PrismExt};
 
 use num::{BigInt, ToPrimitive};
+use num::complex::Complex64;
+use either::Either;
 
 use std::convert::TryFrom;
 
 pub fn append_calculus_functions(table: &mut FunctionTable) {
   table.insert(deriv());
+  table.insert(num_deriv());
+  table.insert(integral());
+  table.insert(num_integrate());
+  table.insert(limit());
+  table.insert(limit_left());
+  table.insert(limit_right());
+  table.insert(mc_integrate());
+  table.insert(mc_estimate());
+  table.insert(sensitivity());
 }
 
+/// Number of samples used by Monte Carlo functions when the caller
+/// does not specify.
+const DEFAULT_MONTE_CARLO_SAMPLES: usize = 1000;
+
 pub fn deriv() -> Function {
   FunctionBuilder::new("deriv")
     .add_case(
@@ -46,9 +72,353 @@ pub fn deriv() -> Function {
     .build()
 }
 
+/// Computes a symbolic antiderivative of an expression, with the
+/// constant of integration taken to be zero.
+pub fn integral() -> Function {
+  FunctionBuilder::new("integrate")
+    .add_case(
+      builder::arity_two().of_types(Identity, ExprToVar).and_then(|expr, var, context| {
+        match integrate(context.function_table, expr, var.clone()) {
+          Ok(integral) => Ok(integral),
+          Err(failure) => {
+            context.errors.push(SimplifierError::new("integrate", failure.error));
+            Err((failure.original_expr, var))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// Numerically evaluates the derivative of an expression at a real
+/// point, returning a two-element vector of the derivative and an
+/// estimate of its absolute error.
+///
+/// Expressions understood by the automatic differentiator are
+/// differentiated exactly (up to rounding). Anything else falls back
+/// to Richardson-extrapolated central differences, so this works even
+/// for functions with no known symbolic derivative.
+pub fn num_deriv() -> Function {
+  FunctionBuilder::new("num_deriv")
+    .add_case(
+      builder::arity_three().of_types(Identity, ExprToVar, expr_to_number()).and_then(|expr, var, point, context| {
+        match numerical_derivative_at(&expr, &var, point.to_f64_or_nan(), context) {
+          Ok(result) => {
+            let vector = Vector::from(vec![Expr::from(result.value), Expr::from(result.error_estimate)]);
+            Ok(vector.into())
+          }
+          Err(err) => {
+            context.errors.push(SimplifierError::new("num_deriv", err));
+            Err((expr, var, point))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+fn numerical_derivative_at(
+  expr: &Expr,
+  var: &Var,
+  x: f64,
+  context: &FunctionContext,
+) -> Result<NumericalDerivative, FunctionEvalError> {
+  if let Ok(dual) = DualFunction::compile(expr, var) {
+    let deriv = dual.eval(Complex64::from(x)).deriv;
+    if deriv.im == 0.0 && deriv.re.is_finite() {
+      return Ok(NumericalDerivative { value: deriv.re, error_estimate: f64::EPSILON * deriv.re.abs() });
+    }
+  }
+  let function = ExprFunction::new(expr.clone(), var.clone(), context.simplifier);
+  let initial_step = 0.1 * f64::max(1.0, x.abs());
+  richardson_derivative(|t| {
+    function.eval_at_real(Number::from(t)).map(|y| y.to_f64_or_nan())
+  }, x, initial_step)
+}
+
+/// Numerically integrates an expression over a finite real interval,
+/// returning a two-element vector of the integral and an estimate of
+/// its absolute error.
+pub fn num_integrate() -> Function {
+  FunctionBuilder::new("num_integrate")
+    .add_case(
+      builder::arity_three().of_types(Identity, ExprToVar, expr_to_interval()).and_then(|expr, var, interval, context| {
+        match numerical_integral(&expr, &var, interval.clone(), context) {
+          Ok(expr) => Ok(expr),
+          Err(err) => {
+            context.errors.push(SimplifierError::new("num_integrate", err));
+            Err((expr, var, interval))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+fn numerical_integral(
+  expr: &Expr,
+  var: &Var,
+  interval: RawInterval<Number>,
+  context: &FunctionContext,
+) -> Result<Expr, QuadratureError> {
+  let (left, right) = interval.into_bounds();
+  let (lower, upper) = (left.into_scalar().to_f64_or_nan(), right.into_scalar().to_f64_or_nan());
+  let function = ExprFunction::new(expr.clone(), var.clone(), context.simplifier);
+  let estimate = GaussKronrod::default().integrate(|x| {
+    ensure_not_cancelled(context.simplifier)?;
+    let y = function.eval_at_real(Number::from(x))?;
+    Ok(y.to_f64_or_nan())
+  }, lower, upper)?;
+  Ok(Vector::from(vec![Expr::from(estimate.value), Expr::from(estimate.error_estimate)]).into())
+}
+
+/// The limit of an expression as a variable approaches a point. The
+/// point may be finite or infinite.
+pub fn limit() -> Function {
+  FunctionBuilder::new("limit")
+    .add_case(
+      builder::arity_three().of_types(Identity, ExprToVar, Identity).and_then(|expr, var, point, context| {
+        match compute_limit(context.function_table, context.simplifier, expr.clone(), var.clone(), point.clone()) {
+          Ok(result) => Ok(result),
+          Err(err) => {
+            context.errors.push(SimplifierError::new("limit", err));
+            Err((expr, var, point))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// The limit of an expression as a variable approaches a point from
+/// below. The point may be finite or `inf`.
+pub fn limit_left() -> Function {
+  one_sided_limit_function("limit_left", LimitDirection::Left)
+}
+
+/// The limit of an expression as a variable approaches a point from
+/// above. The point may be finite or `-inf`.
+pub fn limit_right() -> Function {
+  one_sided_limit_function("limit_right", LimitDirection::Right)
+}
+
+fn one_sided_limit_function(name: &'static str, direction: LimitDirection) -> Function {
+  FunctionBuilder::new(name)
+    .add_case(
+      builder::arity_three().of_types(Identity, ExprToVar, Identity).and_then(move |expr, var, point, context| {
+        match one_sided_limit(context.function_table, context.simplifier, expr.clone(), var.clone(), point.clone(), direction) {
+          Ok(result) => Ok(result),
+          Err(err) => {
+            context.errors.push(SimplifierError::new(name, err));
+            Err((expr, var, point))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// Estimates the integral of an expression over a box by Monte Carlo
+/// sampling, returning a two-element vector of the estimate and its
+/// standard error.
+///
+/// Accepts either a single variable and interval, or a vector of
+/// variables and a vector of intervals, followed by an optional
+/// sample count.
+pub fn mc_integrate() -> Function {
+  FunctionBuilder::new("mc_integrate")
+    .add_case(
+      builder::arity_three().of_types(Identity, expr_to_vars(), expr_to_intervals()).and_then(|expr, vars, intervals, context| {
+        monte_carlo_integral(&expr, &vars, &intervals, DEFAULT_MONTE_CARLO_SAMPLES, context)
+          .map_err(|err| {
+            context.errors.push(SimplifierError::new("mc_integrate", err));
+            (expr, vars, intervals)
+          })
+      })
+    )
+    .add_case(
+      builder::arity_four().of_types(Identity, expr_to_vars(), expr_to_intervals(), expr_to_usize()).and_then(|expr, vars, intervals, samples, context| {
+        monte_carlo_integral(&expr, &vars, &intervals, samples, context)
+          .map_err(|err| {
+            context.errors.push(SimplifierError::new("mc_integrate", err));
+            (expr, vars, intervals, samples)
+          })
+      })
+    )
+    .build()
+}
+
+/// Estimates the expected value of an expression whose variables are
+/// drawn independently from the given distributions, returning a
+/// two-element vector of the mean and its standard error.
+///
+/// Accepts either a single variable and distribution, or a vector of
+/// variables and a vector of distributions, followed by an optional
+/// sample count.
+pub fn mc_estimate() -> Function {
+  FunctionBuilder::new("mc_estimate")
+    .add_case(
+      builder::arity_three().of_types(Identity, expr_to_vars(), expr_to_distributions()).and_then(|expr, vars, dists, context| {
+        monte_carlo_expectation(&expr, &vars, &dists, DEFAULT_MONTE_CARLO_SAMPLES, context)
+          .map_err(|err| {
+            context.errors.push(SimplifierError::new("mc_estimate", err));
+            (expr, vars, dists)
+          })
+      })
+    )
+    .add_case(
+      builder::arity_four().of_types(Identity, expr_to_vars(), expr_to_distributions(), expr_to_usize()).and_then(|expr, vars, dists, samples, context| {
+        monte_carlo_expectation(&expr, &vars, &dists, samples, context)
+          .map_err(|err| {
+            context.errors.push(SimplifierError::new("mc_estimate", err));
+            (expr, vars, dists, samples)
+          })
+      })
+    )
+    .build()
+}
+
+/// First-order sensitivity analysis of an expression. The second
+/// argument is a vector of bindings `x = spread`, where each spread
+/// is a distribution or an interval (treated as a uniform
+/// distribution). The expression is linearized at the means of the
+/// spreads.
+///
+/// Returns a matrix with one row `[x, df/dx, contribution, share]`
+/// per variable, sorted with the largest contribution first. The
+/// contribution is the variable's share of the standard deviation of
+/// the output, and the share is its fraction of the output variance.
+pub fn sensitivity() -> Function {
+  FunctionBuilder::new("sensitivity")
+    .add_case(
+      builder::arity_two().of_types(Identity, expr_to_typed_vector(expr_to_equation())).and_then(|expr, bindings, context| {
+        let Some(inputs) = bindings.iter().map(binding_to_sensitivity_input).collect::<Option<Vec<_>>>() else {
+          context.errors.push(SimplifierError::custom_error("sensitivity", "Expected bindings of the form var = distribution or var = interval"));
+          return Err((expr, bindings));
+        };
+        match sensitivity_analysis(context.function_table, context.derivative_rules, context.simplifier, &expr, &inputs) {
+          Ok(sensitivities) => {
+            let rows = sensitivities.into_iter().map(|s| {
+              let row = vec![Expr::from(s.var), Expr::from(s.partial), Expr::from(s.contribution), Expr::from(s.share)];
+              Expr::from(Vector::from(row))
+            });
+            Ok(Vector::from(rows.collect::<Vec<_>>()).into())
+          }
+          Err(err) => {
+            context.errors.push(SimplifierError::new("sensitivity", err));
+            Err((expr, bindings))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+fn binding_to_sensitivity_input(binding: &Equation) -> Option<SensitivityInput> {
+  let var = ExprToVar.narrow_type(binding.left.clone()).ok()?;
+  let dist = match ExprToDistribution.narrow_type(binding.right.clone()) {
+    Ok(dist) => dist,
+    Err(spread) => {
+      let interval = expr_to_interval().narrow_type(spread).ok()?;
+      let (low, high) = interval.into_bounds();
+      SampleDistribution::uniform(low.into_scalar(), high.into_scalar())
+    }
+  };
+  Some(SensitivityInput { var, point: dist.mean(), std_dev: dist.std_dev() })
+}
+
+fn expr_to_vars() -> impl Prism<Expr, Either<Var, Vec<Var>>> + Clone {
+  ExprToVar.or(expr_to_typed_vector(ExprToVar))
+}
+
+// The interval and distribution prisms box their results, to keep
+// the argument tuples (which are also the error payloads of the
+// function cases) reasonably small.
+
+fn expr_to_intervals() -> impl Prism<Expr, Box<Either<RawInterval<Number>, Vec<RawInterval<Number>>>>> + Clone {
+  expr_to_interval().or(expr_to_typed_vector(expr_to_interval()))
+    .rmap(Box::new, |intervals| *intervals)
+}
+
+fn expr_to_distributions() -> impl Prism<Expr, Box<Either<SampleDistribution, Vec<SampleDistribution>>>> + Clone {
+  ExprToDistribution.or(expr_to_typed_vector(ExprToDistribution))
+    .rmap(Box::new, |dists| *dists)
+}
+
+fn either_to_vec<T: Clone>(value: &Either<T, Vec<T>>) -> Vec<T> {
+  match value {
+    Either::Left(x) => vec![x.clone()],
+    Either::Right(xs) => xs.clone(),
+  }
+}
+
+fn monte_carlo_integral(
+  expr: &Expr,
+  vars: &Either<Var, Vec<Var>>,
+  intervals: &Either<RawInterval<Number>, Vec<RawInterval<Number>>>,
+  samples: usize,
+  context: &FunctionContext,
+) -> Result<Expr, MonteCarloError> {
+  let vars = either_to_vec(vars);
+  let bounds: Vec<_> = either_to_vec(intervals).into_iter()
+    .map(|interval| {
+      let (left, right) = interval.into_bounds();
+      let (lo, hi) = (left.into_scalar().to_f64_or_nan(), right.into_scalar().to_f64_or_nan());
+      if lo <= hi { (lo, hi) } else { (hi, lo) }
+    })
+    .collect();
+  check_monte_carlo_args(vars.len(), bounds.len(), samples)?;
+  let function = ExprFunctionN::new(expr.clone(), vars, context.simplifier);
+  let estimate = integrate_over_box(&mut rand::thread_rng(), &bounds, samples, |point| {
+    ensure_not_cancelled(context.simplifier)?;
+    eval_at_point(&function, point)
+  })?;
+  Ok(estimate_to_expr(estimate))
+}
+
+fn monte_carlo_expectation(
+  expr: &Expr,
+  vars: &Either<Var, Vec<Var>>,
+  dists: &Either<SampleDistribution, Vec<SampleDistribution>>,
+  samples: usize,
+  context: &FunctionContext,
+) -> Result<Expr, MonteCarloError> {
+  let vars = either_to_vec(vars);
+  let samplers = either_to_vec(dists).iter()
+    .map(|dist| dist.sampler())
+    .collect::<Result<Vec<_>, _>>()?;
+  check_monte_carlo_args(vars.len(), samplers.len(), samples)?;
+  let function = ExprFunctionN::new(expr.clone(), vars, context.simplifier);
+  let estimate = estimate_expectation(&mut rand::thread_rng(), &samplers, samples, |point| {
+    ensure_not_cancelled(context.simplifier)?;
+    eval_at_point(&function, point)
+  })?;
+  Ok(estimate_to_expr(estimate))
+}
+
+fn check_monte_carlo_args(var_count: usize, range_count: usize, samples: usize) -> Result<(), MonteCarloError> {
+  if var_count != range_count {
+    return Err(MonteCarloError::DimensionMismatch { expected: var_count, actual: range_count });
+  }
+  if samples < 2 {
+    return Err(MonteCarloError::TooFewSamples);
+  }
+  Ok(())
+}
+
+fn eval_at_point(function: &ExprFunctionN, point: &[f64]) -> Result<f64, MonteCarloError> {
+  let values = point.iter().map(|x| Number::from(*x)).collect();
+  let value = function.eval_at_real(values)?;
+  Ok(value.to_f64_or_nan())
+}
+
+fn estimate_to_expr(estimate: MonteCarloEstimate) -> Expr {
+  Vector::from(vec![Expr::from(estimate.mean), Expr::from(estimate.standard_error)]).into()
+}
+
 fn nth_derivative(mut expr: Expr, var: Var, n: usize, context: &mut FunctionContext) -> Result<Expr, (Expr, Var, Number)> {
   for _ in 0..n {
-    match differentiate(context.function_table, expr, var.clone()) {
+    match differentiate_with_rules(context.function_table, context.derivative_rules, expr, var.clone()) {
       Ok(dexpr) => {
         expr = dexpr;
       }
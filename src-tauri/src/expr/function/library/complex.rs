@@ -9,16 +9,21 @@ use crate::expr::Expr;
 use crate::expr::function::Function;
 use crate::expr::function::table::FunctionTable;
 use crate::expr::function::builder::{self, FunctionBuilder};
-use crate::expr::prisms::{self, ExprToComplex, ExprToQuaternion};
-use crate::expr::number::{ComplexNumber, Quaternion};
+use crate::expr::prisms::{self, ExprToComplex, ExprToQuaternion, ExprToOctonion};
+use crate::expr::number::{ComplexNumber, Quaternion, Octonion};
 use crate::expr::vector::Vector;
 use crate::expr::algebra::infinity::InfiniteConstant;
+use crate::util::angles::Radians;
+
+use num::Zero;
 
 use std::f64::consts::PI;
 
 pub fn append_complex_functions(table: &mut FunctionTable) {
   table.insert(conjugate());
   table.insert(arg());
+  table.insert(arg_degrees());
+  table.insert(polar());
   table.insert(re());
   table.insert(im());
 }
@@ -44,6 +49,12 @@ pub fn conjugate() -> Function {
         Ok(Expr::from(Quaternion::from(arg).conj()))
       })
     )
+    .add_case(
+      // Conjugate of an octonion
+      builder::arity_one().of_type(ExprToOctonion).and_then(|arg, _| {
+        Ok(Expr::from(Octonion::from(arg).conj()))
+      })
+    )
     .add_case(
       // Pointwise conjugate of a vector
       builder::arity_one().of_type(prisms::ExprToVector).and_then(|vec, _| {
@@ -64,8 +75,9 @@ pub fn arg() -> Function {
   FunctionBuilder::new("arg")
     .add_case(
       // Argument (phase) of a complex number
-      builder::arity_one().of_type(ExprToComplex).and_then(|arg, _| {
+      builder::arity_one().of_type(ExprToComplex).and_then(|arg, ctx| {
         let angle = ComplexNumber::from(arg).angle();
+        let angle = ctx.calculation_mode.branch_cut().principal_angle(angle);
         Ok(Expr::from(angle.0))
       })
     )
@@ -90,6 +102,55 @@ pub fn arg() -> Function {
     .build()
 }
 
+pub fn arg_degrees() -> Function {
+  FunctionBuilder::new("arg_deg")
+    .add_case(
+      // Argument (phase) of a complex number, in degrees
+      builder::arity_one().of_type(ExprToComplex).and_then(|arg, ctx| {
+        let angle = ComplexNumber::from(arg).angle();
+        let angle = ctx.calculation_mode.branch_cut().principal_angle(angle);
+        Ok(Expr::from(angle.into_degrees().0))
+      })
+    )
+    .add_case(
+      // Pointwise argument of a vector
+      builder::arity_one().of_type(prisms::ExprToVector).and_then(|vec, _| {
+        let vec: Vector = vec.into_iter().map(|e| Expr::call("arg_deg", vec![e])).collect();
+        Ok(Expr::from(vec))
+      })
+    )
+    .add_case(
+      // Argument (phase) of infinity
+      builder::arity_one().of_type(prisms::ExprToInfinity).and_then(|arg, _| {
+        let phase = match arg {
+          InfiniteConstant::PosInfinity => Expr::zero(),
+          InfiniteConstant::NegInfinity => Expr::from(Radians(PI).into_degrees().0),
+          InfiniteConstant::NotANumber | InfiniteConstant::UndirInfinity => Expr::from(InfiniteConstant::NotANumber),
+        };
+        Ok(phase)
+      })
+    )
+    .build()
+}
+
+/// Constructs a complex number from polar coordinates. This is the
+/// function underlying the `r ∠ θ` literal syntax, and the angle is
+/// interpreted according to the current angle mode.
+pub fn polar() -> Function {
+  FunctionBuilder::new("angle")
+    .add_case(
+      builder::arity_two().both_of_type(prisms::expr_to_number()).and_then(|r, theta, ctx| {
+        if theta.is_zero() {
+          return Ok(Expr::from(r));
+        }
+        let r = r.to_f64().unwrap_or(f64::NAN);
+        let theta = ctx.calculation_mode.angle_mode().to_radians(theta.to_f64().unwrap_or(f64::NAN));
+        Ok(Expr::from(ComplexNumber::from_polar_inexact(r, theta)))
+      })
+    )
+    .build()
+}
+
 pub fn re() -> Function {
   FunctionBuilder::new("re")
     .mark_as_idempotent()
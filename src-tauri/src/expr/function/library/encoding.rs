@@ -0,0 +1,95 @@
+
+//! Hashing and encoding functions on byte sequences.
+//!
+//! A byte sequence is either a string, which is taken as its UTF-8
+//! encoding, or a vector of integers from 0 to 255.
+
+use crate::expr::Expr;
+use crate::expr::vector::Vector;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{expr_to_string, expr_to_bytes};
+use crate::expr::simplifier::error::SimplifierError;
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD as BASE64_STANDARD};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+pub fn append_encoding_functions(table: &mut FunctionTable) {
+  table.insert(bytes());
+  table.insert(bytes_to_string());
+  table.insert(byte_function("md5", |bytes| Expr::from(to_hex(&Md5::digest(bytes)))));
+  table.insert(byte_function("sha256", |bytes| Expr::from(to_hex(&Sha256::digest(bytes)))));
+  table.insert(byte_function("crc32", |bytes| Expr::from(i64::from(crc32fast::hash(bytes)))));
+  table.insert(byte_function("base64_encode", |bytes| Expr::from(BASE64_STANDARD.encode(bytes))));
+  table.insert(base64_decode());
+}
+
+/// The UTF-8 encoding of a string, as a vector of bytes.
+pub fn bytes() -> Function {
+  FunctionBuilder::new("bytes")
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(|arg, _| {
+        Ok(bytes_to_vector(arg.into_bytes()))
+      })
+    )
+    .build()
+}
+
+/// Decodes a vector of bytes as a UTF-8 string.
+pub fn bytes_to_string() -> Function {
+  FunctionBuilder::new("bytes_to_string")
+    .add_case(
+      builder::arity_one().of_type(expr_to_bytes()).and_then(|arg, ctx| {
+        String::from_utf8(arg).map(Expr::from).map_err(|err| {
+          ctx.errors.push(SimplifierError::new("bytes_to_string", err.utf8_error()));
+          err.into_bytes()
+        })
+      })
+    )
+    .build()
+}
+
+/// Decodes a base64 string. The result is a string if the decoded
+/// bytes are valid UTF-8, or a vector of bytes otherwise.
+pub fn base64_decode() -> Function {
+  FunctionBuilder::new("base64_decode")
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(|arg, ctx| {
+        match BASE64_STANDARD.decode(arg.trim()) {
+          Ok(bytes) => Ok(String::from_utf8(bytes).map_or_else(|err| bytes_to_vector(err.into_bytes()), Expr::from)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("base64_decode", err));
+            Err(arg)
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// A function of one byte sequence.
+fn byte_function(name: &str, body: fn(&[u8]) -> Expr) -> Function {
+  FunctionBuilder::new(name)
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(move |arg, _| {
+        Ok(body(arg.as_bytes()))
+      })
+    )
+    .add_case(
+      builder::arity_one().of_type(expr_to_bytes()).and_then(move |arg, _| {
+        Ok(body(&arg))
+      })
+    )
+    .build()
+}
+
+fn bytes_to_vector(bytes: Vec<u8>) -> Expr {
+  Vector::from(bytes.into_iter().map(|b| Expr::from(i64::from(b))).collect::<Vec<_>>()).into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
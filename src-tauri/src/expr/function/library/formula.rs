@@ -4,11 +4,14 @@
 
 use crate::expr::Expr;
 use crate::expr::function::Function;
-use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::function::builder::{self, FunctionBuilder, FunctionCase, FunctionCaseResult};
 use crate::expr::function::table::FunctionTable;
 use crate::expr::algebra::infinity::UnboundedNumber;
+use crate::expr::interval::{Interval, interval_min, interval_max, interval_wrap};
 use crate::expr::predicates;
-use crate::expr::prisms;
+use crate::expr::prisms::{self, ExprToVector};
+use crate::expr::simplifier::error::SimplifierError;
+use crate::util::prism::Prism;
 
 pub fn append_formula_functions(table: &mut FunctionTable) {
   table.insert(equal_to());
@@ -19,6 +22,8 @@ pub fn append_formula_functions(table: &mut FunctionTable) {
   table.insert(greater_than_or_equal());
   table.insert(min_function());
   table.insert(max_function());
+  table.insert(clamp_function());
+  table.insert(wrap_function());
 }
 
 pub fn equal_to() -> Function {
@@ -174,6 +179,19 @@ pub fn min_function() -> Function {
         Ok(Expr::from(result))
       })
     )
+    .add_case(
+      // Interval comparison
+      builder::any_arity().of_type(prisms::expr_to_unbounded_interval_like()).and_then(|args, _| {
+        // unwrap: The first case would've triggered on an empty
+        // vector. So we can assume the vector is non-empty.
+        let result = args.into_iter()
+          .map(Interval::from)
+          .reduce(interval_min)
+          .unwrap();
+        Ok(Expr::from(result))
+      })
+    )
+    .add_case(flatten_vector_args("min"))
     .build()
 }
 
@@ -198,5 +216,88 @@ pub fn max_function() -> Function {
         Ok(Expr::from(result))
       })
     )
+    .add_case(
+      // Interval comparison
+      builder::any_arity().of_type(prisms::expr_to_unbounded_interval_like()).and_then(|args, _| {
+        // unwrap: The first case would've triggered on an empty
+        // vector. So we can assume the vector is non-empty.
+        let result = args.into_iter()
+          .map(Interval::from)
+          .reduce(interval_max)
+          .unwrap();
+        Ok(Expr::from(result))
+      })
+    )
+    .add_case(flatten_vector_args("max"))
+    .build()
+}
+
+/// `clamp(x, lo, hi)` is `x`, restricted to lie between `lo` and
+/// `hi`. Equivalent to `min(max(x, lo), hi)`.
+pub fn clamp_function() -> Function {
+  FunctionBuilder::new("clamp")
+    .add_case(
+      // Unbounded real numbers
+      builder::arity_three().all_of_type(prisms::expr_to_unbounded_number()).and_then(|x, lo, hi, ctx| {
+        if lo > hi {
+          ctx.errors.push(SimplifierError::custom_error("clamp", "Lower bound exceeds upper bound"));
+          return Err((x, lo, hi));
+        }
+        Ok(Expr::from(x.max(lo).min(hi)))
+      })
+    )
+    .add_case(
+      // Intervals
+      builder::arity_three().all_of_type(prisms::expr_to_unbounded_interval_like()).and_then(|x, lo, hi, _| {
+        let result = interval_min(interval_max(Interval::from(x), Interval::from(lo)), Interval::from(hi));
+        Ok(Expr::from(result))
+      })
+    )
     .build()
 }
+
+/// `wrap(x, lo, hi)` is the unique value in the half-open interval
+/// `lo ..^ hi` which differs from `x` by an integer multiple of `hi -
+/// lo`.
+pub fn wrap_function() -> Function {
+  FunctionBuilder::new("wrap")
+    .add_case(
+      // Real numbers
+      builder::arity_three().all_of_type(prisms::expr_to_number()).and_then(|x, lo, hi, ctx| {
+        if lo >= hi {
+          ctx.errors.push(SimplifierError::custom_error("wrap", "Lower bound must be less than upper bound"));
+          return Err((x, lo, hi));
+        }
+        let width = &hi - &lo;
+        Ok(Expr::from(&lo + (x - &lo) % width))
+      })
+    )
+    .add_case(
+      // Real interval with real number bounds
+      builder::arity_three().of_types(prisms::expr_to_interval(), prisms::expr_to_number(), prisms::expr_to_number()).and_then(|x, lo, hi, ctx| {
+        if lo >= hi {
+          ctx.errors.push(SimplifierError::custom_error("wrap", "Lower bound must be less than upper bound"));
+          return Err((x, lo, hi));
+        }
+        Ok(Expr::from(interval_wrap(Interval::from(x), lo, hi)))
+      })
+    )
+    .build()
+}
+
+/// Function case which matches if any argument is a vector, and
+/// replaces each vector argument with its elements.
+fn flatten_vector_args(function_name: &'static str) -> Box<FunctionCase<Expr>> {
+  Box::new(move |args, _context| {
+    if !args.iter().any(|arg| matches!(arg.as_call(), Some(("vector", _)))) {
+      return FunctionCaseResult::NoMatch(args);
+    }
+    let args = args.into_iter()
+      .flat_map(|arg| match ExprToVector.narrow_type(arg) {
+        Ok(vector) => vector.into_iter().collect(),
+        Err(arg) => vec![arg],
+      })
+      .collect();
+    FunctionCaseResult::Success(Expr::call(function_name, args))
+  })
+}
@@ -0,0 +1,108 @@
+
+//! Distances and bearings on the Earth's surface.
+//!
+//! Positions are given as two-element vectors `[latitude, longitude]`,
+//! interpreted according to the current angle mode. Distances are in
+//! meters.
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{expr_to_number, expr_to_string, expr_to_typed_array};
+use crate::expr::simplifier::error::{SimplifierError, DomainError};
+use crate::mode::calculation::AngleMode;
+use crate::util::geodesy::{self, GeoPoint};
+use crate::util::prism::Prism;
+
+pub fn append_geodesy_functions(table: &mut FunctionTable) {
+  table.insert(geo_distance());
+  table.insert(bearing());
+  table.insert(destination_point());
+}
+
+fn expr_to_geo_point() -> impl Prism<Expr, [Number; 2]> + Clone {
+  expr_to_typed_array(expr_to_number())
+}
+
+fn to_geo_point([lat, lon]: &[Number; 2], angle_mode: AngleMode) -> GeoPoint {
+  GeoPoint::new(angle_mode.to_radians(lat.to_f64_or_nan()), angle_mode.to_radians(lon.to_f64_or_nan()))
+}
+
+fn from_geo_point(point: GeoPoint, angle_mode: AngleMode) -> Expr {
+  Expr::call("vector", vec![
+    Expr::from(Number::from(angle_mode.from_radians(point.latitude))),
+    Expr::from(Number::from(angle_mode.from_radians(point.longitude))),
+  ])
+}
+
+fn meters(distance: f64) -> Expr {
+  let meters = Expr::var("m").expect("m should be a valid variable name");
+  Expr::call("*", vec![Expr::from(Number::from(distance)), meters])
+}
+
+/// `geo_distance(p1, p2)` is the great-circle distance between two
+/// points, using the haversine formula. `geo_distance(p1, p2, method)`
+/// selects the method explicitly: either `"haversine"` or
+/// `"vincenty"`, the latter of which accounts for the flattening of
+/// the Earth.
+pub fn geo_distance() -> Function {
+  FunctionBuilder::new("geo_distance")
+    .add_case(
+      builder::arity_two().both_of_type(expr_to_geo_point()).and_then(|p1, p2, ctx| {
+        let angle_mode = ctx.calculation_mode.angle_mode();
+        let distance = geodesy::haversine_distance(to_geo_point(&p1, angle_mode), to_geo_point(&p2, angle_mode));
+        Ok(meters(distance))
+      })
+    )
+    .add_case(
+      builder::arity_three().of_types(expr_to_geo_point(), expr_to_geo_point(), expr_to_string()).and_then(|p1, p2, method, ctx| {
+        let angle_mode = ctx.calculation_mode.angle_mode();
+        let (point1, point2) = (to_geo_point(&p1, angle_mode), to_geo_point(&p2, angle_mode));
+        let distance = match method.as_str() {
+          "haversine" => Ok(geodesy::haversine_distance(point1, point2)),
+          "vincenty" => geodesy::vincenty_distance(point1, point2).map_err(anyhow::Error::from),
+          _ => Err(DomainError::new(format!("Unknown distance method '{method}', expected 'haversine' or 'vincenty'")).into()),
+        };
+        match distance {
+          Ok(distance) => Ok(meters(distance)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("geo_distance", err));
+            Err((p1, p2, method))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// The initial bearing of the great-circle path from the first point
+/// to the second, measured clockwise from north.
+pub fn bearing() -> Function {
+  FunctionBuilder::new("bearing")
+    .add_case(
+      builder::arity_two().both_of_type(expr_to_geo_point()).and_then(|p1, p2, ctx| {
+        let angle_mode = ctx.calculation_mode.angle_mode();
+        let bearing = geodesy::initial_bearing(to_geo_point(&p1, angle_mode), to_geo_point(&p2, angle_mode));
+        Ok(Expr::from(Number::from(angle_mode.from_radians(bearing))))
+      })
+    )
+    .build()
+}
+
+/// `destination_point(p, bearing, distance)` is the point reached by
+/// travelling `distance` meters from `p` along a great circle with
+/// the given initial bearing.
+pub fn destination_point() -> Function {
+  FunctionBuilder::new("destination_point")
+    .add_case(
+      builder::arity_three().of_types(expr_to_geo_point(), expr_to_number(), expr_to_number()).and_then(|p, bearing, distance, ctx| {
+        let angle_mode = ctx.calculation_mode.angle_mode();
+        let bearing = angle_mode.to_radians(bearing.to_f64_or_nan());
+        let destination = geodesy::destination_point(to_geo_point(&p, angle_mode), bearing, distance.to_f64_or_nan());
+        Ok(from_geo_point(destination, angle_mode))
+      })
+    )
+    .build()
+}
@@ -0,0 +1,60 @@
+
+//! Functions for plane geometry.
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{self, expr_to_number};
+use crate::expr::simplifier::error::{SimplifierError, DomainError};
+use crate::util::prism::Prism;
+use crate::util::angles::Radians;
+use crate::util::triangle;
+
+pub fn append_geometry_functions(table: &mut FunctionTable) {
+  table.insert(solve_triangle());
+}
+
+/// `solve_triangle([a, b, c, A, B, C])` solves a triangle with sides
+/// `a`, `b`, `c` opposite angles `A`, `B`, `C`. Known values are
+/// given as real numbers, and unknown ones as anything else (usually
+/// a variable). Angles are interpreted according to the current
+/// angle mode.
+///
+/// Returns the vector `[a, b, c, A, B, C, area]`. In the ambiguous
+/// case (two sides and an angle not between them), the solution in
+/// which the unknown angle opposite a given side is acute is chosen.
+pub fn solve_triangle() -> Function {
+  FunctionBuilder::new("solve_triangle")
+    .add_case(
+      builder::arity_one().of_type(prisms::ExprToVector).and_then(|values, ctx| {
+        if values.len() != 6 {
+          ctx.errors.push(SimplifierError::new("solve_triangle", DomainError::new("Expected a vector of six values")));
+          return Err(values);
+        }
+        let angle_mode = ctx.calculation_mode.angle_mode();
+        let known: Vec<_> = values.iter()
+          .map(|value| expr_to_number().narrow_type(value.clone()).ok().map(|n| n.to_f64_or_nan()))
+          .collect();
+        let sides = [known[0], known[1], known[2]];
+        let angles = [known[3], known[4], known[5]].map(|a| a.map(|a| angle_mode.to_radians(a).0));
+        match triangle::solve_triangle(sides, angles) {
+          Ok(solution) => {
+            let angles = solution.angles.map(|a| angle_mode.from_radians(Radians(a)));
+            let result = solution.sides.into_iter()
+              .chain(angles)
+              .chain([solution.area()])
+              .map(|x| Expr::from(Number::from(x)))
+              .collect();
+            Ok(Expr::call("vector", result))
+          }
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("solve_triangle", err));
+            Err(values)
+          }
+        }
+      })
+    )
+    .build()
+}
@@ -6,20 +6,30 @@ use crate::expr::number::Number;
 use crate::expr::function::Function;
 use crate::expr::function::builder::{self, FunctionBuilder};
 use crate::expr::function::table::FunctionTable;
-use crate::expr::simplifier::error::SimplifierError;
+use crate::expr::simplifier::error::{SimplifierError, DomainError};
 use crate::expr::algebra::{ExprFunction, ExprFunction2};
 use crate::expr::prisms;
 use crate::util::{into_singleton, into_ordered};
-use crate::util::prism::{Identity, Prism};
+use crate::util::prism::{Identity, Prism, PrismExt};
 use crate::graphics::dataset::ExprToXDataSet;
 use crate::graphics::plot::PlotDirective;
 use crate::graphics::contour_plot::ContourPlotDirective;
+use crate::graphics::time_plot::{TimePlotDirective, ExprToTimePoint};
+use crate::graphics::bar_chart::BarChartDirective;
+use crate::graphics::pie_chart::PieChartDirective;
+use crate::graphics::scatter_plot::ScatterPlotDirective;
+use crate::expr::algebra::regression::StringToFitModel;
 use crate::graphics::response::GraphicsDirective;
 
 pub fn append_graphics_functions(table: &mut FunctionTable) {
   table.insert(graphics_function());
   table.insert(plot_function());
   table.insert(contour_plot_function());
+  table.insert(time_plot_function());
+  table.insert(bar_chart_function());
+  table.insert(stacked_bar_function());
+  table.insert(pie_chart_function());
+  table.insert(scatter_plot_function());
 }
 
 /// The two-dimensional `graphics` directive. We don't actually define
@@ -115,6 +125,158 @@ pub fn contour_plot_function() -> Function {
     .build()
 }
 
+/// Plots a vector of `[datetime, value]` pairs, with datetimes given
+/// as UTC strings in ISO 8601 format, against a date axis.
+pub fn time_plot_function() -> Function {
+  FunctionBuilder::new("timeplot")
+    .add_graphics_case(
+      builder::arity_one().of_type(prisms::expr_to_typed_vector(ExprToTimePoint)).and_then(|points, ctx| {
+        match TimePlotDirective::from_time_points(&points) {
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("timeplot", err));
+            Err(points)
+          }
+          Ok(plot) => {
+            Ok(GraphicsDirective::TimePlot(plot))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// Draws a bar chart of the rows of a matrix, with a vector of labels
+/// for the columns. The rows are drawn side by side. A single vector
+/// of numbers is treated as a one-row matrix.
+pub fn bar_chart_function() -> Function {
+  bar_chart_family_function("barchart", GraphicsDirective::BarChart)
+}
+
+/// As [`bar_chart_function`], but the rows are stacked on top of one
+/// another.
+pub fn stacked_bar_function() -> Function {
+  bar_chart_family_function("stackedbar", GraphicsDirective::StackedBar)
+}
+
+fn bar_chart_family_function(
+  name: &'static str,
+  to_directive: fn(BarChartDirective) -> GraphicsDirective,
+) -> Function {
+  FunctionBuilder::new(name)
+    .add_graphics_case(
+      // Matrix of values, one row per series.
+      builder::arity_two().of_types(vec_vec_number_prism(), prisms::expr_to_typed_vector(Identity))
+        .and_then(move |data, labels, ctx| {
+          match BarChartDirective::from_data(&data, &labels) {
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new(name, err));
+              Err((data, labels))
+            }
+            Ok(chart) => {
+              Ok(to_directive(chart))
+            }
+          }
+        })
+    )
+    .add_graphics_case(
+      // Vector of values, as a single series.
+      builder::arity_two().of_types(prisms::expr_to_typed_vector(prisms::expr_to_number()), prisms::expr_to_typed_vector(Identity))
+        .and_then(move |data, labels, ctx| {
+          match BarChartDirective::from_data(std::slice::from_ref(&data), &labels) {
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new(name, err));
+              Err((data, labels))
+            }
+            Ok(chart) => {
+              Ok(to_directive(chart))
+            }
+          }
+        })
+    )
+    .build()
+}
+
+/// Draws a pie chart of a vector of non-negative values, with an
+/// optional vector of labels for the wedges.
+pub fn pie_chart_function() -> Function {
+  FunctionBuilder::new("piechart")
+    .add_graphics_case(
+      // Values without labels.
+      builder::arity_one().of_type(prisms::expr_to_typed_vector(prisms::expr_to_nonnegative_number()))
+        .and_then(|values, ctx| {
+          match PieChartDirective::from_data(values.clone(), None) {
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("piechart", err));
+              Err(values)
+            }
+            Ok(chart) => {
+              Ok(GraphicsDirective::PieChart(chart))
+            }
+          }
+        })
+    )
+    .add_graphics_case(
+      // Values with labels.
+      builder::arity_two().of_types(prisms::expr_to_typed_vector(prisms::expr_to_nonnegative_number()), prisms::expr_to_typed_vector(Identity))
+        .and_then(|values, labels, ctx| {
+          match PieChartDirective::from_data(values.clone(), Some(&labels)) {
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("piechart", err));
+              Err((values, labels))
+            }
+            Ok(chart) => {
+              Ok(GraphicsDirective::PieChart(chart))
+            }
+          }
+        })
+    )
+    .add_graphics_case(
+      // A vector of real numbers which failed the cases above has a
+      // negative value.
+      builder::any_arity().and_then(|args, ctx| {
+        let real_values = prisms::expr_to_typed_vector(prisms::expr_to_number());
+        if matches!(args.len(), 1 | 2) && real_values.narrow_type(args[0].clone()).is_ok() {
+          ctx.errors.push(SimplifierError::new("piechart", DomainError::new("Expected non-negative values")));
+        }
+        Err(args)
+      })
+    )
+    .build()
+}
+
+/// Draws a scatter plot of a matrix of `[x, y]` rows. If a fit model
+/// (as accepted by `fit`) is given as well, the least-squares curve
+/// of that model is drawn through the points.
+pub fn scatter_plot_function() -> Function {
+  FunctionBuilder::new("scatterplot")
+    .add_graphics_case(
+      // Points only.
+      builder::arity_one().of_type(data_points_prism()).and_then(|points, _| {
+        Ok(GraphicsDirective::ScatterPlot(ScatterPlotDirective::from_points(&points)))
+      })
+    )
+    .add_graphics_case(
+      // Points with a regression curve.
+      builder::arity_two().of_types(data_points_prism(), prisms::expr_to_string().composed(StringToFitModel))
+        .and_then(|points, model, ctx| {
+          match ScatterPlotDirective::with_regression(&points, model) {
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("scatterplot", err));
+              Err((points, model))
+            }
+            Ok(plot) => {
+              Ok(GraphicsDirective::ScatterPlot(plot))
+            }
+          }
+        })
+    )
+    .build()
+}
+
+fn data_points_prism() -> impl Prism<Expr, Vec<[Number; 2]>> {
+  prisms::expr_to_typed_vector(prisms::expr_to_typed_array(prisms::expr_to_number()))
+}
+
 fn vec_vec_number_prism() -> impl Prism<Expr, Vec<Vec<Number>>> {
   prisms::expr_to_typed_vector(
     prisms::expr_to_typed_vector(prisms::expr_to_number()),
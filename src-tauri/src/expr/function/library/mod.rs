@@ -7,12 +7,22 @@
 use super::table::FunctionTable;
 
 mod arithmetic;
+mod astronomy;
 mod basic;
+mod bitwise;
 mod calculus;
 mod complex;
 mod datatypes;
+mod encoding;
 mod formula;
+mod geodesy;
+mod geometry;
 mod graphics;
+mod number_theory;
+mod polynomial;
+mod sequences;
+mod sets;
+mod special;
 mod statistics;
 mod string;
 mod symbolic;
@@ -22,12 +32,22 @@ mod tensor;
 pub fn build_function_table() -> FunctionTable {
   let mut table = FunctionTable::new();
   arithmetic::append_arithmetic_functions(&mut table);
+  astronomy::append_astronomy_functions(&mut table);
   basic::append_basic_functions(&mut table);
+  bitwise::append_bitwise_functions(&mut table);
   calculus::append_calculus_functions(&mut table);
   complex::append_complex_functions(&mut table);
   datatypes::append_datatype_functions(&mut table);
+  encoding::append_encoding_functions(&mut table);
   formula::append_formula_functions(&mut table);
+  geodesy::append_geodesy_functions(&mut table);
+  geometry::append_geometry_functions(&mut table);
   graphics::append_graphics_functions(&mut table);
+  number_theory::append_number_theory_functions(&mut table);
+  polynomial::append_polynomial_functions(&mut table);
+  sequences::append_sequence_functions(&mut table);
+  sets::append_set_functions(&mut table);
+  special::append_special_functions(&mut table);
   statistics::append_statistics_functions(&mut table);
   string::append_string_functions(&mut table);
   symbolic::append_symbolic_functions(&mut table);
@@ -0,0 +1,198 @@
+
+//! Functions for counting and enumerating primes, and for modular
+//! arithmetic on integers.
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{expr_to_usize, expr_to_bigint, expr_to_typed_vector};
+use crate::expr::simplifier::error::{SimplifierError, DomainError};
+use crate::util::{primes, modular, rsa};
+
+use num::{BigInt, Integer, Signed, ToPrimitive};
+
+/// The largest argument accepted by `prime_pi`. Larger arguments
+/// would take several seconds to compute.
+pub const MAX_PRIME_PI_ARGUMENT: usize = 100_000_000_000;
+
+/// The largest index accepted by `nth_prime`.
+pub const MAX_NTH_PRIME_INDEX: usize = 1_000_000_000;
+
+/// The largest interval width accepted by `prime_range`.
+pub const MAX_PRIME_RANGE_WIDTH: usize = 10_000_000;
+
+/// The largest upper bound accepted by `prime_range`.
+pub const MAX_PRIME_RANGE_BOUND: usize = 100_000_000_000_000;
+
+/// The largest modulus accepted by `discrete_log`. Baby-step
+/// giant-step needs memory proportional to the square root of the
+/// modulus.
+pub const MAX_DISCRETE_LOG_MODULUS: u64 = 1_000_000_000_000;
+
+pub fn append_number_theory_functions(table: &mut FunctionTable) {
+  table.insert(prime_pi());
+  table.insert(nth_prime());
+  table.insert(prime_range());
+  table.insert(modinv());
+  table.insert(crt());
+  table.insert(discrete_log());
+  table.insert(rsa_keygen());
+}
+
+fn number(n: u64) -> Expr {
+  Expr::from(Number::from(n as usize))
+}
+
+/// The number of primes less than or equal to the argument.
+pub fn prime_pi() -> Function {
+  FunctionBuilder::new("prime_pi")
+    .add_case(
+      builder::arity_one().of_type(expr_to_usize()).and_then(|n, ctx| {
+        if n > MAX_PRIME_PI_ARGUMENT {
+          let err = DomainError::new(format!("prime_pi only supports arguments up to {MAX_PRIME_PI_ARGUMENT}"));
+          ctx.errors.push(SimplifierError::new("prime_pi", err));
+          return Err(n);
+        }
+        Ok(number(primes::prime_pi(n as u64)))
+      })
+    )
+    .build()
+}
+
+/// The `k`th prime, where `nth_prime(1) = 2`.
+pub fn nth_prime() -> Function {
+  FunctionBuilder::new("nth_prime")
+    .add_case(
+      builder::arity_one().of_type(expr_to_usize()).and_then(|k, ctx| {
+        if k == 0 || k > MAX_NTH_PRIME_INDEX {
+          let err = DomainError::new(format!("nth_prime expects an index from 1 to {MAX_NTH_PRIME_INDEX}"));
+          ctx.errors.push(SimplifierError::new("nth_prime", err));
+          return Err(k);
+        }
+        let p = primes::nth_prime(k as u64).expect("k should be positive");
+        Ok(number(p))
+      })
+    )
+    .build()
+}
+
+/// `prime_range(a, b)` is the vector of primes `p` with
+/// `a <= p <= b`, in increasing order.
+pub fn prime_range() -> Function {
+  FunctionBuilder::new("prime_range")
+    .add_case(
+      builder::arity_two().both_of_type(expr_to_usize()).and_then(|a, b, ctx| {
+        if b > MAX_PRIME_RANGE_BOUND || b.saturating_sub(a) > MAX_PRIME_RANGE_WIDTH {
+          let err = DomainError::new(format!(
+            "prime_range supports bounds up to {MAX_PRIME_RANGE_BOUND}, spanning at most {MAX_PRIME_RANGE_WIDTH}",
+          ));
+          ctx.errors.push(SimplifierError::new("prime_range", err));
+          return Err((a, b));
+        }
+        let primes = primes::primes_in_range(a as u64, b as u64);
+        Ok(Expr::call("vector", primes.into_iter().map(number).collect()))
+      })
+    )
+    .build()
+}
+
+/// `modinv(a, m)` is the inverse of `a` modulo `m`, in the range
+/// `[0, m)`.
+pub fn modinv() -> Function {
+  FunctionBuilder::new("modinv")
+    .add_case(
+      builder::arity_two().both_of_type(expr_to_bigint()).and_then(|a, m, ctx| {
+        match modular::modinv(&a, &m) {
+          Some(inverse) => Ok(Expr::from(Number::from(inverse))),
+          None => {
+            let err = DomainError::new(format!("{a} has no inverse modulo {m}"));
+            ctx.errors.push(SimplifierError::new("modinv", err));
+            Err((a, m))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// `crt(residues, moduli)` is the smallest nonnegative `x` such that
+/// `x = residues[i] (mod moduli[i])` for each `i`, by the Chinese
+/// remainder theorem. The moduli need not be pairwise coprime, as
+/// long as the congruences are compatible.
+pub fn crt() -> Function {
+  FunctionBuilder::new("crt")
+    .add_case(
+      builder::arity_two().both_of_type(expr_to_typed_vector(expr_to_bigint())).and_then(|residues, moduli, ctx| {
+        if residues.len() != moduli.len() || moduli.iter().any(|m| !m.is_positive()) {
+          let err = DomainError::new("Expected vectors of residues and positive moduli of the same length");
+          ctx.errors.push(SimplifierError::new("crt", err));
+          return Err((residues, moduli));
+        }
+        match modular::crt(&residues, &moduli) {
+          Some((x, _)) => Ok(Expr::from(Number::from(x))),
+          None => {
+            ctx.errors.push(SimplifierError::new("crt", DomainError::new("The congruences have no common solution")));
+            Err((residues, moduli))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// `discrete_log(g, h, m)` is the smallest nonnegative `x` such that
+/// `g^x = h (mod m)`.
+pub fn discrete_log() -> Function {
+  FunctionBuilder::new("discrete_log")
+    .add_case(
+      builder::arity_three().of_types(expr_to_bigint(), expr_to_bigint(), expr_to_bigint()).and_then(|g, h, m, ctx| {
+        let m_small = m.to_u64().filter(|m| (1..=MAX_DISCRETE_LOG_MODULUS).contains(m));
+        let Some(m_small) = m_small else {
+          let err = DomainError::new(format!("discrete_log expects a modulus from 1 to {MAX_DISCRETE_LOG_MODULUS}"));
+          ctx.errors.push(SimplifierError::new("discrete_log", err));
+          return Err((g, h, m));
+        };
+        let reduce = |n: &BigInt| n.mod_floor(&m).to_u64().expect("residue should be less than the modulus");
+        match modular::discrete_log(reduce(&g), reduce(&h), m_small) {
+          Some(x) => Ok(Expr::from(Number::from(BigInt::from(x)))),
+          None => {
+            let err = DomainError::new(format!("No power of {g} is congruent to {h} modulo {m}"));
+            ctx.errors.push(SimplifierError::new("discrete_log", err));
+            Err((g, h, m))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// `rsa_keygen(bits)` generates a toy RSA key whose modulus has the
+/// given number of bits, returning the vector `[n, e, d, p, q]`.
+/// Each call produces a new random key.
+pub fn rsa_keygen() -> Function {
+  FunctionBuilder::new("rsa_keygen")
+    .add_case(
+      builder::arity_one().of_type(expr_to_usize()).and_then(|bits, ctx| {
+        let bits_u32 = u32::try_from(bits).unwrap_or(u32::MAX);
+        match rsa::rsa_keygen(&mut rand::thread_rng(), bits_u32) {
+          Ok(key) => {
+            let values = vec![
+              Expr::from(Number::from(key.modulus)),
+              Expr::from(Number::from(key.public_exponent)),
+              Expr::from(Number::from(key.private_exponent)),
+              number(key.p),
+              number(key.q),
+            ];
+            Ok(Expr::call("vector", values))
+          }
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("rsa_keygen", err));
+            Err(bits)
+          }
+        }
+      })
+    )
+    .build()
+}
@@ -0,0 +1,128 @@
+
+//! Functions for exact arithmetic on polynomials in one variable.
+//!
+//! The coefficients of the polynomials are rational numbers, unless
+//! modular arithmetic mode is on, in which case they are integers
+//! modulo the current modulus.
+
+use crate::expr::Expr;
+use crate::expr::var::Var;
+use crate::expr::vector::Vector;
+use crate::expr::function::{Function, FunctionContext};
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::ExprToVar;
+use crate::expr::algebra::polynomial::dense::{DensePolynomial, PolynomialError};
+use crate::expr::algebra::polynomial::ring::{CoefficientRing, RationalField, IntegersMod};
+use crate::expr::simplifier::error::SimplifierError;
+use crate::util::prism::Identity;
+
+pub fn append_polynomial_functions(table: &mut FunctionTable) {
+  table.insert(poly_mul());
+  table.insert(poly_div());
+  table.insert(poly_rem());
+  table.insert(poly_gcd());
+  table.insert(poly_factor());
+}
+
+/// Evaluates `body` with `ring` bound to the coefficient ring
+/// indicated by the calculation mode.
+macro_rules! in_coefficient_ring {
+  ($ctx:expr, |$ring:ident| $body:expr) => {
+    match $ctx.calculation_mode.modulus() {
+      Some(n) => { let $ring = IntegersMod::new(n); $body }
+      None => { let $ring = RationalField; $body }
+    }
+  };
+}
+
+pub fn poly_mul() -> Function {
+  FunctionBuilder::new("poly_mul")
+    .add_case(
+      builder::arity_three().of_types(Identity, Identity, ExprToVar).and_then(|p, q, var, ctx| {
+        let result = in_coefficient_ring!(ctx, |ring| {
+          parse_pair(ring, &p, &q, &var).map(|(p, q)| p.mul(&q).to_expr(&var))
+        });
+        report_errors("poly_mul", result, ctx).ok_or((p, q, var))
+      })
+    )
+    .build()
+}
+
+/// Polynomial division with remainder. Produces a vector of the
+/// quotient and the remainder.
+pub fn poly_div() -> Function {
+  FunctionBuilder::new("poly_div")
+    .add_case(
+      builder::arity_three().of_types(Identity, Identity, ExprToVar).and_then(|p, q, var, ctx| {
+        let result = in_coefficient_ring!(ctx, |ring| {
+          parse_pair(ring, &p, &q, &var).and_then(|(p, q)| p.div_rem(&q)).map(|(quotient, remainder)| {
+            Vector::from(vec![quotient.to_expr(&var), remainder.to_expr(&var)]).into()
+          })
+        });
+        report_errors("poly_div", result, ctx).ok_or((p, q, var))
+      })
+    )
+    .build()
+}
+
+pub fn poly_rem() -> Function {
+  FunctionBuilder::new("poly_rem")
+    .add_case(
+      builder::arity_three().of_types(Identity, Identity, ExprToVar).and_then(|p, q, var, ctx| {
+        let result = in_coefficient_ring!(ctx, |ring| {
+          parse_pair(ring, &p, &q, &var).and_then(|(p, q)| p.div_rem(&q)).map(|(_, remainder)| remainder.to_expr(&var))
+        });
+        report_errors("poly_rem", result, ctx).ok_or((p, q, var))
+      })
+    )
+    .build()
+}
+
+/// The monic greatest common divisor of two polynomials.
+pub fn poly_gcd() -> Function {
+  FunctionBuilder::new("poly_gcd")
+    .add_case(
+      builder::arity_three().of_types(Identity, Identity, ExprToVar).and_then(|p, q, var, ctx| {
+        let result = in_coefficient_ring!(ctx, |ring| {
+          parse_pair(ring, &p, &q, &var).and_then(|(p, q)| p.gcd(&q)).map(|gcd| gcd.to_expr(&var))
+        });
+        report_errors("poly_gcd", result, ctx).ok_or((p, q, var))
+      })
+    )
+    .build()
+}
+
+/// Factors a polynomial into irreducibles. Only supported over the
+/// integers mod a prime, i.e. in modular arithmetic mode.
+pub fn poly_factor() -> Function {
+  FunctionBuilder::new("poly_factor")
+    .add_case(
+      builder::arity_two().of_types(Identity, ExprToVar).and_then(|p, var, ctx| {
+        let result = match ctx.calculation_mode.modulus() {
+          None => Err(PolynomialError::NotAField),
+          Some(n) => DensePolynomial::from_expr(IntegersMod::new(n), &p, &var)
+            .and_then(|p| p.factor())
+            .map(|factorization| factorization.to_expr(&var)),
+        };
+        report_errors("poly_factor", result, ctx).ok_or((p, var))
+      })
+    )
+    .build()
+}
+
+fn parse_pair<R: CoefficientRing>(
+  ring: R,
+  p: &Expr,
+  q: &Expr,
+  var: &Var,
+) -> Result<(DensePolynomial<R>, DensePolynomial<R>), PolynomialError> {
+  let p = DensePolynomial::from_expr(ring.clone(), p, var)?;
+  let q = DensePolynomial::from_expr(ring, q, var)?;
+  Ok((p, q))
+}
+
+fn report_errors(function_name: &str, result: Result<Expr, PolynomialError>, ctx: &mut FunctionContext) -> Option<Expr> {
+  result.map_err(|err| ctx.errors.push(SimplifierError::new(function_name, err))).ok()
+}
+
@@ -0,0 +1,60 @@
+
+//! Functions for recognizing integer sequences.
+
+use crate::expr::Expr;
+use crate::expr::var::Var;
+use crate::expr::number::Number;
+use crate::expr::algebra::sequence::SequenceRule;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{expr_to_usize, expr_to_bigint, expr_to_typed_vector};
+use crate::expr::simplifier::error::{SimplifierError, DomainError};
+
+use num::BigInt;
+
+/// The number of terms predicted by `guess_sequence` when no count
+/// is given.
+pub const DEFAULT_PREDICTED_TERMS: usize = 5;
+
+pub fn append_sequence_functions(table: &mut FunctionTable) {
+  table.insert(guess_sequence());
+}
+
+/// `guess_sequence(v)` identifies the rule generating the integer
+/// vector `v` and returns `[a(n) = formula, next]`, where `next` is
+/// a vector of the following terms. `guess_sequence(v, k)` predicts
+/// `k` terms rather than the default.
+pub fn guess_sequence() -> Function {
+  FunctionBuilder::new("guess_sequence")
+    .add_case(
+      builder::arity_one().of_type(expr_to_typed_vector(expr_to_bigint())).and_then(|terms, ctx| {
+        guess_sequence_impl(&terms, DEFAULT_PREDICTED_TERMS)
+          .ok_or_else(|| {
+            ctx.errors.push(SimplifierError::new("guess_sequence", DomainError::new("Could not identify the sequence")));
+            terms
+          })
+      })
+    )
+    .add_case(
+      builder::arity_two().of_types(expr_to_typed_vector(expr_to_bigint()), expr_to_usize()).and_then(|terms, count, ctx| {
+        guess_sequence_impl(&terms, count)
+          .ok_or_else(|| {
+            ctx.errors.push(SimplifierError::new("guess_sequence", DomainError::new("Could not identify the sequence")));
+            (terms, count)
+          })
+      })
+    )
+    .build()
+}
+
+fn guess_sequence_impl(terms: &[BigInt], count: usize) -> Option<Expr> {
+  let terms: Vec<Number> = terms.iter().cloned().map(Number::from).collect();
+  let rule = SequenceRule::guess(&terms)?;
+  let next = rule.extend(&terms, count).into_iter().map(Expr::from).collect();
+  let n = Var::new("n").expect("n should be a valid variable name");
+  Some(Expr::call("vector", vec![
+    rule.to_equation("a", &n),
+    Expr::call("vector", next),
+  ]))
+}
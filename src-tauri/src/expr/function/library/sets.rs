@@ -0,0 +1,149 @@
+
+//! Functions which operate on finite sets.
+
+use crate::expr::Expr;
+use crate::expr::set::Set;
+use crate::expr::interval::IntervalUnion;
+use crate::expr::literal::Literal;
+use crate::expr::function::Function;
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms;
+use crate::expr::simplifier::error::SimplifierError;
+use crate::util::prism::Identity;
+
+use num::BigInt;
+
+/// The largest set whose power set we are willing to compute. The
+/// result of `powerset` has `2^n` elements, so this must stay small.
+pub const MAX_POWERSET_SIZE: usize = 16;
+
+pub fn append_set_functions(table: &mut FunctionTable) {
+  table.insert(set());
+  table.insert(union());
+  table.insert(intersection());
+  table.insert(difference());
+  table.insert(powerset());
+  table.insert(cardinality());
+  table.insert(member());
+}
+
+pub fn set() -> Function {
+  FunctionBuilder::new(Set::FUNCTION_NAME)
+    .add_case(
+      // Normalize the order of elements and remove duplicates.
+      builder::any_arity().of_type(Identity).and_then(|args, _| {
+        if Set::is_canonical(&args) {
+          return Err(args);
+        }
+        Ok(Set::from_iter(args).into())
+      })
+    )
+    .build()
+}
+
+pub fn union() -> Function {
+  FunctionBuilder::new("union")
+    .permit_flattening()
+    .permit_reordering()
+    .set_identity(|expr| expr == &Expr::from(Set::empty()))
+    .add_case(
+      builder::any_arity().of_type(prisms::ExprToSet).and_then(|args, _| {
+        let result = args.into_iter().fold(Set::empty(), Set::union);
+        Ok(result.into())
+      })
+    )
+    .add_case(
+      // Merge overlapping intervals and sort them in increasing
+      // order.
+      builder::any_arity().of_type(Identity).and_then(|args, _| {
+        let original = Expr::call(IntervalUnion::FUNCTION_NAME, args);
+        match IntervalUnion::parse(original.clone()) {
+          Ok(union) if Expr::from(union.clone()) != original => Ok(union.into()),
+          _ => {
+            let Expr::Call(_, args) = original else { unreachable!() };
+            Err(args)
+          }
+        }
+      })
+    )
+    .build()
+}
+
+pub fn intersection() -> Function {
+  FunctionBuilder::new("intersection")
+    .permit_flattening()
+    .permit_reordering()
+    .add_case(
+      // Symbolic elements might be equal to one another, so we can
+      // only intersect sets whose elements are all literals.
+      builder::non_zero_arity().of_type(prisms::ExprToSet).and_then(|args, _| {
+        if !args.iter().all(Set::is_literal) {
+          return Err(args);
+        }
+        let mut args = args.into_iter();
+        let first = args.next().unwrap(); // unwrap: non_zero_arity
+        let result = args.fold(first, |acc, s| acc.intersection(&s));
+        Ok(result.into())
+      })
+    )
+    .build()
+}
+
+pub fn difference() -> Function {
+  FunctionBuilder::new("difference")
+    .add_case(
+      builder::arity_two().both_of_type(prisms::ExprToSet).and_then(|a, b, _| {
+        if !a.is_literal() || !b.is_literal() {
+          return Err((a, b));
+        }
+        Ok(a.difference(&b).into())
+      })
+    )
+    .build()
+}
+
+pub fn powerset() -> Function {
+  FunctionBuilder::new("powerset")
+    .add_case(
+      builder::arity_one().of_type(prisms::ExprToSet).and_then(|arg, ctx| {
+        if arg.len() > MAX_POWERSET_SIZE {
+          ctx.errors.push(SimplifierError::custom_error("powerset", "Set is too large to compute its power set"));
+          return Err(arg);
+        }
+        Ok(arg.powerset().into())
+      })
+    )
+    .build()
+}
+
+pub fn cardinality() -> Function {
+  FunctionBuilder::new("cardinality")
+    .add_case(
+      builder::arity_one().of_type(prisms::ExprToSet).and_then(|arg, _| {
+        if !arg.is_literal() {
+          return Err(arg);
+        }
+        Ok(Expr::from(BigInt::from(arg.len())))
+      })
+    )
+    .build()
+}
+
+pub fn member() -> Function {
+  FunctionBuilder::new("member")
+    .add_case(
+      builder::arity_two().of_types(Identity, prisms::ExprToSet).and_then(|value, set, _| {
+        if set.contains(&value) {
+          return Ok(Expr::from(true));
+        }
+        // A symbolic value might still be equal to one of the
+        // elements, so we can only rule out membership for literals.
+        if set.is_literal() && Literal::try_from(value.clone()).is_ok() {
+          return Ok(Expr::from(false));
+        }
+        Err((value, set))
+      })
+    )
+    .build()
+}
@@ -0,0 +1,196 @@
+
+//! Evaluation rules for special functions, such as the gamma and
+//! error functions.
+//!
+//! These functions are evaluated numerically on real arguments, and
+//! left alone on symbolic arguments.
+
+use crate::expr::Expr;
+use crate::expr::simplifier::error::{SimplifierError, DomainError};
+use crate::expr::function::{Function, FunctionContext};
+use crate::expr::function::table::FunctionTable;
+use crate::expr::function::builder::{self, FunctionBuilder};
+use crate::expr::prisms::{expr_to_number, NumberToUsize};
+use crate::expr::number::Number;
+use crate::util::prism::Prism;
+use crate::util::special;
+
+use num::BigInt;
+
+/// The largest positive integer on which `gamma` is evaluated
+/// exactly, as a factorial. Larger integers are approximated.
+pub const MAX_EXACT_GAMMA_ARG: usize = 1000;
+
+pub fn append_special_functions(table: &mut FunctionTable) {
+  table.insert(gamma());
+  table.insert(ln_gamma());
+  table.insert(beta());
+  table.insert(digamma());
+  table.insert(erf());
+  table.insert(erfc());
+  table.insert(zeta());
+}
+
+/// Converts the result of one of the [`special`] functions to an
+/// expression, reporting an error if the result is a pole or
+/// overflows.
+fn float_result(function_name: &str, result: f64, ctx: &mut FunctionContext) -> Option<Expr> {
+  if result.is_nan() {
+    ctx.errors.push(SimplifierError::new(function_name, DomainError::new("Argument is a pole of the function")));
+    None
+  } else if result.is_infinite() {
+    ctx.errors.push(SimplifierError::custom_error(function_name, "Result is too large to represent"));
+    None
+  } else {
+    Some(Expr::from(Number::from(result)))
+  }
+}
+
+pub fn gamma() -> Function {
+  FunctionBuilder::new("gamma")
+    .add_case(
+      // Real number case
+      builder::arity_one().of_type(expr_to_number()).and_then(|arg, ctx| {
+        if let Ok(n) = NumberToUsize.narrow_type(arg.clone()) {
+          if (1..=MAX_EXACT_GAMMA_ARG).contains(&n) {
+            let factorial = (1..n).map(BigInt::from).product::<BigInt>();
+            return Ok(Expr::from(Number::from(factorial)));
+          }
+        }
+        float_result("gamma", special::gamma(arg.to_f64_or_nan()), ctx).ok_or(arg)
+      })
+    )
+    .set_derivative(
+      builder::arity_one_deriv("gamma", |arg, engine| {
+        let arg_deriv = engine.differentiate(arg.clone())?;
+        Ok(Expr::call("*", vec![
+          arg_deriv,
+          Expr::call("gamma", vec![arg.clone()]),
+          Expr::call("digamma", vec![arg]),
+        ]))
+      })
+    )
+    .build()
+}
+
+pub fn ln_gamma() -> Function {
+  FunctionBuilder::new("lngamma")
+    .add_case(
+      // Real number case
+      builder::arity_one().of_type(expr_to_number()).and_then(|arg, ctx| {
+        if arg <= Number::from(0) {
+          ctx.errors.push(SimplifierError::new("lngamma", DomainError::new("Expected positive real number")));
+          return Err(arg);
+        }
+        float_result("lngamma", special::ln_gamma(arg.to_f64_or_nan()), ctx).ok_or(arg)
+      })
+    )
+    .set_derivative(
+      builder::arity_one_deriv("lngamma", |arg, engine| {
+        let arg_deriv = engine.differentiate(arg.clone())?;
+        Ok(Expr::call("*", vec![
+          arg_deriv,
+          Expr::call("digamma", vec![arg]),
+        ]))
+      })
+    )
+    .build()
+}
+
+pub fn beta() -> Function {
+  FunctionBuilder::new("beta")
+    .add_case(
+      // Real number case
+      builder::arity_two().both_of_type(expr_to_number()).and_then(|a, b, ctx| {
+        let result = special::beta(a.to_f64_or_nan(), b.to_f64_or_nan());
+        float_result("beta", result, ctx).ok_or((a, b))
+      })
+    )
+    .set_derivative(
+      builder::arity_two_deriv("beta", |a, b, engine| {
+        // Convert to gamma(a) gamma(b) / gamma(a + b) and do the
+        // Quotient Rule.
+        let equivalent_expr = Expr::call("/", vec![
+          Expr::call("*", vec![
+            Expr::call("gamma", vec![a.clone()]),
+            Expr::call("gamma", vec![b.clone()]),
+          ]),
+          Expr::call("gamma", vec![Expr::call("+", vec![a, b])]),
+        ]);
+        engine.differentiate(equivalent_expr)
+      })
+    )
+    .build()
+}
+
+pub fn digamma() -> Function {
+  FunctionBuilder::new("digamma")
+    .add_case(
+      // Real number case
+      builder::arity_one().of_type(expr_to_number()).and_then(|arg, ctx| {
+        float_result("digamma", special::digamma(arg.to_f64_or_nan()), ctx).ok_or(arg)
+      })
+    )
+    .build()
+}
+
+pub fn erf() -> Function {
+  FunctionBuilder::new("erf")
+    .add_case(
+      // Real number case
+      builder::arity_one().of_type(expr_to_number()).and_then(|arg, ctx| {
+        float_result("erf", special::erf(arg.to_f64_or_nan()), ctx).ok_or(arg)
+      })
+    )
+    .set_derivative(
+      builder::arity_one_deriv("erf", |arg, engine| {
+        let arg_deriv = engine.differentiate(arg.clone())?;
+        Ok(Expr::call("*", vec![arg_deriv, erf_derivative(arg)]))
+      })
+    )
+    .build()
+}
+
+pub fn erfc() -> Function {
+  FunctionBuilder::new("erfc")
+    .add_case(
+      // Real number case
+      builder::arity_one().of_type(expr_to_number()).and_then(|arg, ctx| {
+        float_result("erfc", special::erfc(arg.to_f64_or_nan()), ctx).ok_or(arg)
+      })
+    )
+    .set_derivative(
+      builder::arity_one_deriv("erfc", |arg, engine| {
+        let arg_deriv = engine.differentiate(arg.clone())?;
+        Ok(Expr::call("negate", vec![
+          Expr::call("*", vec![arg_deriv, erf_derivative(arg)]),
+        ]))
+      })
+    )
+    .build()
+}
+
+/// The derivative of `erf` at `arg`, which is `2 / sqrt(pi) *
+/// exp(-arg^2)`.
+fn erf_derivative(arg: Expr) -> Expr {
+  Expr::call("/", vec![
+    Expr::call("*", vec![
+      Expr::from(2),
+      Expr::call("exp", vec![
+        Expr::call("negate", vec![Expr::call("^", vec![arg, Expr::from(2)])]),
+      ]),
+    ]),
+    Expr::call("sqrt", vec![Expr::var("pi").unwrap()]),
+  ])
+}
+
+pub fn zeta() -> Function {
+  FunctionBuilder::new("zeta")
+    .add_case(
+      // Real number case
+      builder::arity_one().of_type(expr_to_number()).and_then(|arg, ctx| {
+        float_result("zeta", special::zeta(arg.to_f64_or_nan()), ctx).ok_or(arg)
+      })
+    )
+    .build()
+}
@@ -2,11 +2,15 @@
 use crate::expr::Expr;
 use crate::expr::number::{Number, ComplexNumber, pow_real};
 use crate::expr::number::inexact::DivInexact;
-use crate::expr::function::Function;
+use crate::expr::function::{Function, FunctionContext};
 use crate::expr::function::table::FunctionTable;
 use crate::expr::function::builder::{self, FunctionBuilder};
 use crate::expr::prisms;
 use crate::expr::simplifier::error::SimplifierError;
+use crate::expr::vector::Vector;
+use crate::expr::algebra::regression::StringToFitModel;
+use crate::expr::algebra::descriptive::{self, HistogramBin, DescriptiveError};
+use crate::util::prism::PrismExt;
 
 use num::{BigInt, Zero, One};
 
@@ -24,6 +28,10 @@ pub fn append_statistics_functions(table: &mut FunctionTable) {
   table.insert(sample_covariance());
   table.insert(pop_covariance());
   table.insert(correlation());
+  table.insert(curve_fit());
+  table.insert(histogram_by_count());
+  table.insert(histogram_by_width());
+  table.insert(summary());
 }
 
 pub fn arithmetic_mean() -> Function {
@@ -335,6 +343,116 @@ pub fn correlation() -> Function {
     .build()
 }
 
+/// Fits a model to a matrix of `[x, y]` rows, producing the vector
+/// `[coefficients, r_squared]`. The model is given as a string, such
+/// as `"linear"` or `"poly 2"`.
+pub fn curve_fit() -> Function {
+  FunctionBuilder::new("fit")
+    .add_case(
+      builder::arity_two().of_types(
+        prisms::expr_to_typed_vector(prisms::expr_to_typed_array(prisms::expr_to_number())),
+        prisms::expr_to_string().composed(StringToFitModel),
+      ).and_then(|points, model, ctx| {
+        match model.fit(&points) {
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("fit", err));
+            Err((points, model))
+          }
+          Ok(fit) => {
+            let coefficients = Vector::from(fit.coefficients.into_iter().map(Expr::from).collect::<Vec<_>>());
+            Ok(Vector::from(vec![Expr::from(coefficients), Expr::from(fit.r_squared)]).into())
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// Bins a vector of real numbers into the given number of
+/// equal-width bins. Produces a matrix with one `[lower_bound,
+/// count]` row per bin.
+pub fn histogram_by_count() -> Function {
+  FunctionBuilder::new("histogram")
+    .add_case(
+      builder::arity_two().of_types(
+        prisms::expr_to_typed_vector(prisms::expr_to_number()),
+        prisms::expr_to_usize(),
+      ).and_then(|data, bin_count, ctx| {
+        histogram_result("histogram", descriptive::histogram_by_count(&data, bin_count), ctx)
+          .ok_or((data, bin_count))
+      })
+    )
+    .build()
+}
+
+/// Bins a vector of real numbers into bins of the given width.
+/// Produces a matrix with one `[lower_bound, count]` row per bin.
+pub fn histogram_by_width() -> Function {
+  FunctionBuilder::new("histogramw")
+    .add_case(
+      builder::arity_two().of_types(
+        prisms::expr_to_typed_vector(prisms::expr_to_number()),
+        prisms::expr_to_positive_number(),
+      ).and_then(|data, bin_width, ctx| {
+        histogram_result("histogramw", descriptive::histogram_by_width(&data, bin_width.clone()), ctx)
+          .ok_or((data, bin_width))
+      })
+    )
+    .build()
+}
+
+fn histogram_result(
+  function_name: &str,
+  histogram: Result<Vec<HistogramBin>, DescriptiveError>,
+  ctx: &mut FunctionContext,
+) -> Option<Expr> {
+  match histogram {
+    Err(err) => {
+      ctx.errors.push(SimplifierError::new(function_name, err));
+      None
+    }
+    Ok(bins) => {
+      let rows = bins.into_iter().map(|bin| {
+        Expr::from(Vector::from(vec![Expr::from(bin.lower_bound), Expr::from(Number::from(bin.count))]))
+      });
+      Some(Vector::from(rows.collect::<Vec<_>>()).into())
+    }
+  }
+}
+
+/// Summary statistics of a vector of real numbers, as a vector of
+/// equations labeling each statistic.
+pub fn summary() -> Function {
+  FunctionBuilder::new("summary")
+    .add_case(
+      builder::arity_one().of_type(prisms::expr_to_typed_vector(prisms::expr_to_number())).and_then(|data, ctx| {
+        match descriptive::summarize(&data, ctx.calculation_mode.has_fractional_flag()) {
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("summary", err));
+            Err(data)
+          }
+          Ok(summary) => {
+            let statistics = [
+              ("min", summary.min),
+              ("q1", summary.first_quartile),
+              ("median", summary.median),
+              ("q3", summary.third_quartile),
+              ("max", summary.max),
+              ("mean", summary.mean),
+              ("stddev", summary.std_dev),
+            ];
+            let equations = statistics.into_iter().map(|(name, value)| {
+              // unwrap: These are all valid variable names.
+              Expr::call("=", vec![Expr::var(name).unwrap(), Expr::from(value)])
+            });
+            Ok(Vector::from(equations.collect::<Vec<_>>()).into())
+          }
+        }
+      })
+    )
+    .build()
+}
+
 fn covar_sum_of_differences(x: Vec<ComplexNumber>, y: Vec<ComplexNumber>) -> ComplexNumber {
   assert!(x.len() == y.len(), "Precondition failed: covar_sum_of_differences got vectors of different lengths");
   let len = x.len() as i64;
@@ -7,10 +7,22 @@ use crate::expr::function::Function;
 use crate::expr::function::table::FunctionTable;
 use crate::expr::function::builder::{self, FunctionBuilder};
 use crate::expr::prisms::expr_to_string;
+use crate::expr::simplifier::error::SimplifierError;
+
+use html_escape::{encode_safe, decode_html_entities};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode, percent_decode_str};
+
+/// Characters which are percent-encoded by `url_encode`. This is
+/// everything except the unreserved characters of RFC 3986.
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
 
 pub fn append_string_functions(table: &mut FunctionTable) {
   table.insert(to_lowercase());
   table.insert(to_uppercase());
+  table.insert(url_encode());
+  table.insert(url_decode());
+  table.insert(html_escape());
+  table.insert(html_unescape());
 }
 
 pub fn to_lowercase() -> Function {
@@ -34,3 +46,49 @@ pub fn to_uppercase() -> Function {
     )
     .build()
 }
+
+pub fn url_encode() -> Function {
+  FunctionBuilder::new("url_encode")
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(|arg, _| {
+        Ok(Expr::from(utf8_percent_encode(&arg, URL_ENCODE_SET).to_string()))
+      })
+    )
+    .build()
+}
+
+pub fn url_decode() -> Function {
+  FunctionBuilder::new("url_decode")
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(|arg, ctx| {
+        match percent_decode_str(&arg).decode_utf8() {
+          Ok(decoded) => Ok(Expr::from(decoded.into_owned())),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("url_decode", err));
+            Err(arg)
+          }
+        }
+      })
+    )
+    .build()
+}
+
+pub fn html_escape() -> Function {
+  FunctionBuilder::new("html_escape")
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(|arg, _| {
+        Ok(Expr::from(encode_safe(&arg).into_owned()))
+      })
+    )
+    .build()
+}
+
+pub fn html_unescape() -> Function {
+  FunctionBuilder::new("html_unescape")
+    .add_case(
+      builder::arity_one().of_type(expr_to_string()).and_then(|arg, _| {
+        Ok(Expr::from(decode_html_entities(&arg).into_owned()))
+      })
+    )
+    .build()
+}
@@ -9,7 +9,13 @@ use crate::expr::function::table::FunctionTable;
 use crate::expr::simplifier::Simplifier;
 use crate::expr::simplifier::error::SimplifierError;
 use crate::expr::number::{Number, ComplexLike};
-use crate::expr::interval::Interval;
+use crate::expr::interval::{Interval, IntervalType, RawInterval};
+use crate::expr::algebra::formula::Formula;
+use crate::expr::algebra::isolate::isolate;
+use crate::expr::algebra::inequality::solve_inequality;
+use crate::expr::algebra::domain::natural_domain;
+use crate::expr::algebra::summation::{FiniteSeries, SeriesKind};
+use crate::expr::algebra::optimization::{BranchAndBound, IntervalFunction, SearchBox, GlobalMinError};
 use crate::expr::algebra::root_finding::{RootFindingInput, expr_to_root_finding_input};
 use crate::expr::algebra::root_finding::newton::{NewtonRaphsonFunction, NewtonRaphsonMethod, NewtonRaphsonError};
 use crate::expr::algebra::root_finding::secant::{SecantMethodFunction, SecantMethod, SecantMethodError};
@@ -20,6 +26,12 @@ use crate::util::prism::Identity;
 pub fn append_symbolic_functions(table: &mut FunctionTable) {
   table.insert(substitute_function());
   table.insert(find_root_function());
+  table.insert(isolate_function());
+  table.insert(solve_inequality_function());
+  table.insert(domain_function());
+  table.insert(global_min_function());
+  table.insert(series_function("sum", SeriesKind::Sum));
+  table.insert(series_function("prod", SeriesKind::Product));
 }
 
 /// Replaces all instances of the needle variable with the given
@@ -79,6 +91,144 @@ pub fn find_root_function() -> Function {
     .build()
 }
 
+/// Rearranges an equation so that the given variable stands alone on
+/// the left-hand side. See [`isolate`] for the supported operations.
+pub fn isolate_function() -> Function {
+  FunctionBuilder::new("isolate")
+    .add_case(
+      builder::arity_two().of_types(prisms::expr_to_equation(), prisms::ExprToVar)
+        .and_then(|equation, var, ctx| {
+          match isolate(equation.clone(), &var) {
+            Ok(result) => Ok(Expr::from(Formula::from(result))),
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("isolate", err));
+              Err((equation, var))
+            }
+          }
+        })
+    )
+    .build()
+}
+
+/// Solves a polynomial or rational inequality in one variable,
+/// producing the set of real solutions as a union of intervals.
+pub fn solve_inequality_function() -> Function {
+  FunctionBuilder::new("solve_inequality")
+    .add_case(
+      builder::arity_two().of_types(prisms::expr_to_formula(), prisms::ExprToVar)
+        .and_then(|formula, var, ctx| {
+          match solve_inequality(formula.clone(), &var, ctx.simplifier) {
+            Ok(solution) => Ok(solution.into()),
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("solve_inequality", err));
+              Err((formula, var))
+            }
+          }
+        })
+    )
+    .build()
+}
+
+/// The natural domain of an expression in one variable, as a union
+/// of intervals. See [`natural_domain`] for the supported functions.
+pub fn domain_function() -> Function {
+  FunctionBuilder::new("domain")
+    .add_case(
+      builder::arity_two().of_types(Identity, prisms::ExprToVar)
+        .and_then(|expr, var, ctx| {
+          match natural_domain(&expr, &var, ctx.simplifier) {
+            Ok(domain) => Ok(domain.into()),
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("domain", err));
+              Err((expr, var))
+            }
+          }
+        })
+    )
+    .build()
+}
+
+/// Bounds the global minimum of a real-valued function over a box,
+/// by branch-and-bound on interval evaluations of the function.
+///
+/// The function may be given a single variable and interval, or a
+/// vector of variables and a vector of intervals of the same length.
+/// The result is a closed interval which is guaranteed (up to
+/// floating-point rounding) to contain the minimum value.
+pub fn global_min_function() -> Function {
+  FunctionBuilder::new("global_min")
+    // Function of one variable
+    .add_case(
+      builder::arity_three().of_types(Identity, prisms::ExprToVar, prisms::expr_to_interval())
+        .and_then(|expr, var, interval, ctx| {
+          let search_box = SearchBox::new(vec![interval_to_bounds(interval.clone())]);
+          match global_min(expr.clone(), vec![var.clone()], search_box, ctx.simplifier) {
+            Ok(expr) => Ok(expr),
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("global_min", err));
+              Err((expr, var, interval))
+            }
+          }
+        })
+    )
+    // Function of several variables
+    .add_case(
+      builder::arity_three().of_types(
+        Identity,
+        prisms::expr_to_typed_vector(prisms::ExprToVar),
+        prisms::expr_to_typed_vector(prisms::expr_to_interval()),
+      ).and_then(|expr, vars, intervals, ctx| {
+        let search_box = SearchBox::new(intervals.iter().cloned().map(interval_to_bounds).collect());
+        match global_min(expr.clone(), vars.clone(), search_box, ctx.simplifier) {
+          Ok(expr) => Ok(expr),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("global_min", err));
+            Err((expr, vars, intervals))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+/// The sum or product of an expression as the index variable ranges
+/// over an inclusive range of integers. Arithmetic and geometric sums
+/// are evaluated in closed form, regardless of the number of terms.
+pub fn series_function(name: &'static str, kind: SeriesKind) -> Function {
+  FunctionBuilder::new(name)
+    .add_case(
+      builder::arity_four().of_types(Identity, prisms::ExprToVar, prisms::expr_to_i64(), prisms::expr_to_i64())
+        .and_then(move |expr, var, lower, upper, ctx| {
+          let series = FiniteSeries { kind, expr, var, lower, upper };
+          match series.evaluate(ctx.function_table, ctx.simplifier, ctx.calculation_mode.clone()) {
+            Ok(expr) => Ok(expr),
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new(name, err));
+              Err((series.expr, series.var, lower, upper))
+            }
+          }
+        })
+    )
+    .build()
+}
+
+fn interval_to_bounds(interval: RawInterval<Number>) -> (f64, f64) {
+  let (left, right) = interval.into_bounds();
+  (left.into_scalar().to_f64_or_nan(), right.into_scalar().to_f64_or_nan())
+}
+
+fn global_min(
+  expr: Expr,
+  vars: Vec<Var>,
+  search_box: SearchBox,
+  simplifier: &dyn Simplifier,
+) -> Result<Expr, GlobalMinError> {
+  let function = IntervalFunction::from_expr(expr, vars, simplifier);
+  let bounds = BranchAndBound::default().find_minimum(&function, search_box)?;
+  let interval = Interval::new(Number::from(bounds.lower), IntervalType::Closed, Number::from(bounds.upper));
+  Ok(interval.into())
+}
+
 fn find_root(
   expr: Expr,
   var: Var,
@@ -2,8 +2,8 @@
 //! Functions which operate on vectors and/or matrices.
 
 use crate::expr::Expr;
-use crate::expr::number::{ComplexNumber, Quaternion};
-use crate::expr::function::Function;
+use crate::expr::number::{Number, ComplexNumber, ComplexLike, Quaternion, Octonion};
+use crate::expr::function::{Function, FunctionContext};
 use crate::expr::function::table::FunctionTable;
 use crate::expr::function::builder::{self, FunctionBuilder};
 use crate::expr::vector::{Vector, vector_shape};
@@ -13,12 +13,17 @@ use crate::expr::prisms;
 use crate::expr::predicates;
 use crate::expr::ordering::cmp_expr;
 use crate::expr::simplifier::error::SimplifierError;
+use crate::expr::simplifier::cancellable::CancelledError;
 use crate::expr::algebra::infinity::InfiniteConstant;
 use crate::util::{repeated, clamp};
 use crate::util::matrix::{Matrix as UtilMatrix};
+use crate::util::matrix::numeric::{self, ComplexMatrix, MatrixFunctionError};
+use crate::util::matrix::decomposition::{self, RealMatrix, DecompositionError};
+use super::arithmetic::inverse_matrix;
 use crate::util::prism::{Prism, Identity, OnVec};
 
-use num::BigInt;
+use num::{BigInt, Zero};
+use num::complex::Complex64;
 use itertools::Itertools;
 use try_traits::ops::TryMul;
 
@@ -58,6 +63,14 @@ pub fn append_tensor_functions(table: &mut FunctionTable) {
   table.insert(trace());
   table.insert(matrix_multiplication());
   table.insert(kronecker_multiplication());
+  table.insert(matrix_exponential());
+  table.insert(expm());
+  table.insert(matrix_power());
+  table.insert(matrix_logarithm());
+  table.insert(lu_decomposition());
+  table.insert(qr_decomposition());
+  table.insert(cholesky_decomposition());
+  table.insert(singular_value_decomposition());
 }
 
 fn is_empty_vector(expr: &Expr) -> bool {
@@ -613,7 +626,13 @@ pub fn determinant() -> Function {
           ctx.errors.push(SimplifierError::custom_error("det", "Expected square matrix"));
           return Err(mat);
         }
-        Ok(mat.map(ComplexNumber::from).determinant().into())
+        match mat.clone().map(ComplexNumber::from).determinant_with_cancellation(|| ctx.simplifier.is_cancelled()) {
+          Some(det) => Ok(det.into()),
+          None => {
+            ctx.errors.push(SimplifierError::new("det", CancelledError));
+            Err(mat)
+          }
+        }
       })
     )
     .build()
@@ -643,6 +662,14 @@ pub fn matrix_multiplication() -> Function {
         Ok((a * b).into())
       })
     )
+    .add_case(
+      // Octonion times octonion
+      builder::arity_two().of_types(prisms::ExprToOctonion, prisms::ExprToOctonion).and_then(|a, b, _| {
+        let a = Octonion::from(a);
+        let b = Octonion::from(b);
+        Ok((a * b).into())
+      })
+    )
     .add_case(
       // Vector times scalar
       builder::arity_two().of_types(prisms::ExprToVector, prisms::ExprToQuaternion).and_then(|v, q, _| {
@@ -710,6 +737,257 @@ pub fn matrix_multiplication() -> Function {
     .build()
 }
 
+pub fn matrix_exponential() -> Function {
+  matrix_exponential_function("matexp")
+}
+
+/// Synonym for `matexp`, under the name most numerical packages use.
+pub fn expm() -> Function {
+  matrix_exponential_function("expm")
+}
+
+fn matrix_exponential_function(name: &'static str) -> Function {
+  FunctionBuilder::new(name)
+    .add_case(
+      builder::arity_one().of_type(prisms::ExprToTypedMatrix::new(prisms::ExprToComplex)).and_then(move |mat, ctx| {
+        if mat.width() != mat.height() {
+          ctx.errors.push(SimplifierError::custom_error(name, "Expected square matrix"));
+          return Err(mat);
+        }
+        if rejects_inexact_result(name, ctx) {
+          return Err(mat);
+        }
+        match numeric::expm(&to_numeric_matrix(&mat)) {
+          Ok(result) => Ok(from_numeric_matrix(result)),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new(name, err));
+            Err(mat)
+          }
+        }
+      })
+    )
+    .build()
+}
+
+pub fn matrix_power() -> Function {
+  FunctionBuilder::new("matpow")
+    .add_case(
+      // Integer powers are computed exactly, by repeated
+      // multiplication (and inversion, for negative powers).
+      builder::arity_two().of_types(prisms::ExprToTypedMatrix::new(prisms::ExprToComplex), prisms::expr_to_i64()).and_then(|mat, n, ctx| {
+        if mat.width() != mat.height() {
+          ctx.errors.push(SimplifierError::custom_error("matpow", "Expected square matrix"));
+          return Err((mat, n));
+        }
+        let base = mat.clone().map(ComplexNumber::from);
+        let base = if n < 0 {
+          match inverse_matrix(base, ctx) {
+            Ok(inverse) => inverse,
+            Err(err) => {
+              ctx.errors.push(SimplifierError::new("matpow", err));
+              return Err((mat, n));
+            }
+          }
+        } else {
+          base
+        };
+        let result = base.powu(n.unsigned_abs());
+        Ok(Matrix::from(result.map(Expr::from)).into())
+      })
+    )
+    .add_case(
+      // Fractional powers are computed numerically, by
+      // diagonalization.
+      builder::arity_two().of_types(prisms::ExprToTypedMatrix::new(prisms::ExprToComplex), prisms::expr_to_number()).and_then(|mat, p, ctx| {
+        if mat.width() != mat.height() {
+          ctx.errors.push(SimplifierError::custom_error("matpow", "Expected square matrix"));
+          return Err((mat, p));
+        }
+        if rejects_inexact_result("matpow", ctx) {
+          return Err((mat, p));
+        }
+        let exponent = p.to_f64_or_nan();
+        let result = apply_by_diagonalization(&mat, |lambda| {
+          if !lambda.is_zero() {
+            Ok(lambda.powf(exponent))
+          } else if exponent > 0.0 {
+            Ok(Complex64::zero())
+          } else {
+            Err(MatrixFunctionError::Singular)
+          }
+        });
+        match result {
+          Ok(result) => Ok(result),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("matpow", err));
+            Err((mat, p))
+          }
+        }
+      })
+    )
+    .build()
+}
+
+pub fn matrix_logarithm() -> Function {
+  FunctionBuilder::new("matlog")
+    .add_case(
+      builder::arity_one().of_type(prisms::ExprToTypedMatrix::new(prisms::ExprToComplex)).and_then(|mat, ctx| {
+        if mat.width() != mat.height() {
+          ctx.errors.push(SimplifierError::custom_error("matlog", "Expected square matrix"));
+          return Err(mat);
+        }
+        if rejects_inexact_result("matlog", ctx) {
+          return Err(mat);
+        }
+        let result = apply_by_diagonalization(&mat, |lambda| {
+          if lambda.is_zero() {
+            Err(MatrixFunctionError::Singular)
+          } else {
+            Ok(lambda.ln())
+          }
+        });
+        match result {
+          Ok(result) => Ok(result),
+          Err(err) => {
+            ctx.errors.push(SimplifierError::new("matlog", err));
+            Err(mat)
+          }
+        }
+      })
+    )
+    .build()
+}
+
+pub fn lu_decomposition() -> Function {
+  FunctionBuilder::new("lu")
+    .add_case(
+      // Returns [P, L, U], where A = P L U.
+      builder::arity_one().of_type(prisms::ExprToTypedMatrix::new(prisms::expr_to_number())).and_then(|mat, ctx| {
+        if mat.width() != mat.height() {
+          ctx.errors.push(SimplifierError::custom_error("lu", "Expected square matrix"));
+          return Err(mat);
+        }
+        decompose("lu", &mat, ctx, |mat| {
+          let decomposition::LuDecomposition { permutation, lower, upper } = decomposition::lu(mat);
+          Ok(vec![permutation, lower, upper])
+        }).ok_or(mat)
+      })
+    )
+    .build()
+}
+
+pub fn qr_decomposition() -> Function {
+  FunctionBuilder::new("qr")
+    .add_case(
+      // Returns [Q, R], where A = Q R.
+      builder::arity_one().of_type(prisms::ExprToTypedMatrix::new(prisms::expr_to_number())).and_then(|mat, ctx| {
+        decompose("qr", &mat, ctx, |mat| {
+          let decomposition::QrDecomposition { orthogonal, upper } = decomposition::qr(mat);
+          Ok(vec![orthogonal, upper])
+        }).ok_or(mat)
+      })
+    )
+    .build()
+}
+
+pub fn cholesky_decomposition() -> Function {
+  FunctionBuilder::new("cholesky")
+    .add_case(
+      // Returns [L, L^T], where A = L L^T.
+      builder::arity_one().of_type(prisms::ExprToTypedMatrix::new(prisms::expr_to_number())).and_then(|mat, ctx| {
+        if mat.width() != mat.height() {
+          ctx.errors.push(SimplifierError::custom_error("cholesky", "Expected square matrix"));
+          return Err(mat);
+        }
+        decompose("cholesky", &mat, ctx, |mat| {
+          let lower = decomposition::cholesky(mat)?;
+          Ok(vec![lower.clone(), lower.transpose()])
+        }).ok_or(mat)
+      })
+    )
+    .build()
+}
+
+pub fn singular_value_decomposition() -> Function {
+  FunctionBuilder::new("svd")
+    .add_case(
+      // Returns [U, S, V^T], where A = U S V^T.
+      builder::arity_one().of_type(prisms::ExprToTypedMatrix::new(prisms::expr_to_number())).and_then(|mat, ctx| {
+        decompose("svd", &mat, ctx, |mat| {
+          let decomposition = decomposition::svd(mat)?;
+          let singular_values = decomposition.singular_value_matrix();
+          Ok(vec![decomposition.left, singular_values, decomposition.right.transpose()])
+        }).ok_or(mat)
+      })
+    )
+    .build()
+}
+
+/// Computes a numerical decomposition of a real matrix, returning
+/// the factors as a vector of matrices. On failure, reports an error
+/// and returns `None`.
+fn decompose<F>(function_name: &str, mat: &UtilMatrix<Number>, ctx: &mut FunctionContext, f: F) -> Option<Expr>
+where F: FnOnce(&RealMatrix) -> Result<Vec<RealMatrix>, DecompositionError> {
+  if rejects_inexact_result(function_name, ctx) {
+    return None;
+  }
+  let mat = mat.clone().map(|x| x.to_f64_or_nan());
+  if !mat.items().all(|x| x.is_finite()) {
+    ctx.errors.push(SimplifierError::custom_error(function_name, "Matrix entries are too large to represent"));
+    return None;
+  }
+  let factors = match f(&mat) {
+    Ok(factors) => factors,
+    Err(err) => {
+      ctx.errors.push(SimplifierError::new(function_name, err));
+      return None;
+    }
+  };
+  if !factors.iter().flat_map(|factor| factor.items()).all(|x| x.is_finite()) {
+    ctx.errors.push(SimplifierError::custom_error(function_name, "Result is too large to represent"));
+    return None;
+  }
+  let factors: Vector = factors.into_iter()
+    .map(|factor| Matrix::from(factor.map(|x| Expr::from(Number::from(x)))).into())
+    .collect();
+  Some(factors.into())
+}
+
+/// Matrix functions which can only be computed numerically are
+/// disabled in exact matrix mode. Reports an error and returns true
+/// if that mode is on.
+fn rejects_inexact_result(function_name: &str, ctx: &mut FunctionContext) -> bool {
+  if ctx.calculation_mode.has_exact_matrix_flag() {
+    ctx.errors.push(SimplifierError::custom_error(function_name, "Result would be inexact, but exact matrix mode is on"));
+    true
+  } else {
+    false
+  }
+}
+
+fn to_numeric_matrix(mat: &UtilMatrix<ComplexLike>) -> ComplexMatrix {
+  mat.clone().map(|z| {
+    let (re, im) = ComplexNumber::from(z).into_parts();
+    Complex64::new(re.to_f64_or_nan(), im.to_f64_or_nan())
+  })
+}
+
+fn from_numeric_matrix(mat: ComplexMatrix) -> Expr {
+  Matrix::from(mat.map(|z| Expr::from(ComplexNumber::new(z.re, z.im)))).into()
+}
+
+/// Applies a scalar function to a diagonalizable matrix, by applying
+/// it to each eigenvalue. If the input matrix is real and the result
+/// is real up to rounding error, the result is made real.
+fn apply_by_diagonalization<F>(mat: &UtilMatrix<ComplexLike>, f: F) -> Result<Expr, MatrixFunctionError>
+where F: FnMut(Complex64) -> Result<Complex64, MatrixFunctionError> {
+  let is_real = mat.items().all(|z| matches!(z, ComplexLike::Real(_)));
+  let decomposition = numeric::diagonalize(&to_numeric_matrix(mat))?;
+  let result = decomposition.map_eigenvalues(f)?;
+  let result = if is_real { numeric::chop_imaginary_parts(result) } else { result };
+  Ok(from_numeric_matrix(result))
+}
+
 pub fn kronecker_multiplication() -> Function {
   FunctionBuilder::new("kron")
     .add_case(
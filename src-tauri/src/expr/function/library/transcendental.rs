@@ -9,7 +9,7 @@ use crate::expr::function::builder::{self, FunctionBuilder};
 use crate::expr::prisms::{self, expr_to_number, ExprToComplex};
 use crate::expr::number::{Number, ComplexNumber, ComplexLike, pow_real, pow_complex};
 use crate::expr::algebra::infinity::{InfiniteConstant, SignedInfinity, UnboundedNumber};
-use crate::expr::interval::{RawInterval, Interval, includes_infinity};
+use crate::expr::interval::{RawInterval, Interval, includes_infinity, interval_sin, interval_cos};
 
 use num::{Zero, One};
 
@@ -46,6 +46,7 @@ pub fn append_transcendental_functions(table: &mut FunctionTable) {
   table.insert(arsecant_hyper());
   table.insert(arcosecant_hyper());
   table.insert(arcotangent_hyper());
+  table.insert(normalize_angle());
 }
 
 pub fn natural_log() -> Function {
@@ -67,8 +68,9 @@ pub fn natural_log() -> Function {
             return Err(arg);
           }
         }
-        let arg = ComplexNumber::from(arg);
-        Ok(Expr::from(arg.ln()))
+        let (magn, angle) = ComplexNumber::from(arg).to_polar();
+        let angle = ctx.calculation_mode.branch_cut().principal_angle(angle);
+        Ok(Expr::from(ComplexNumber::new(magn.ln(), angle.0)))
       })
     )
     .add_case(
@@ -93,6 +95,17 @@ pub fn natural_log() -> Function {
         Ok(Expr::call("/", vec![arg_deriv, arg]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("ln", |arg, engine| {
+        engine.integrate_linear_substitution("ln", arg, |u| {
+          // u ln(u) - u
+          Expr::call("-", vec![
+            Expr::call("*", vec![u.clone(), Expr::call("ln", vec![u.clone()])]),
+            u,
+          ])
+        })
+      })
+    )
     .build()
 }
 
@@ -231,6 +244,9 @@ pub fn exponent() -> Function {
     .add_case(
       // Real number case
       builder::arity_one().of_type(expr_to_number()).and_then(|arg, _| {
+        if let Some(power) = arg.big_float_exp() {
+          return Ok(Expr::from(power));
+        }
         let e = Number::from(consts::E);
         let power = pow_real(e, arg);
         Ok(Expr::from(power))
@@ -281,6 +297,13 @@ pub fn exponent() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("exp", |arg, engine| {
+        engine.integrate_linear_substitution("exp", arg, |u| {
+          Expr::call("exp", vec![u])
+        })
+      })
+    )
     .build()
 }
 
@@ -345,6 +368,20 @@ pub fn sqrt() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("sqrt", |arg, engine| {
+        engine.integrate_linear_substitution("sqrt", arg, |u| {
+          // (2/3) u^(3/2)
+          Expr::call("/", vec![
+            Expr::call("*", vec![
+              Expr::from(2),
+              Expr::call("^", vec![u, Expr::call("/", vec![Expr::from(3), Expr::from(2)])]),
+            ]),
+            Expr::from(3),
+          ])
+        })
+      })
+    )
     .build()
 }
 
@@ -363,6 +400,12 @@ pub fn sine() -> Function {
         Ok(Expr::from(arg.sin()))
       })
     )
+    .add_case(
+      // Interval case
+      builder::arity_one().of_type(prisms::expr_to_unbounded_interval()).and_then(|arg, _| {
+        Ok(Expr::from(interval_sin(Interval::from(arg))))
+      })
+    )
     .set_derivative(
       builder::arity_one_deriv("sin", |arg, engine| {
         let arg_deriv = engine.differentiate(arg.clone())?;
@@ -372,6 +415,13 @@ pub fn sine() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("sin", |arg, engine| {
+        engine.integrate_linear_substitution("sin", arg, |u| {
+          Expr::call("negate", vec![Expr::call("cos", vec![u])])
+        })
+      })
+    )
     .build()
 }
 
@@ -390,6 +440,12 @@ pub fn cosine() -> Function {
         Ok(Expr::from(arg.cos()))
       })
     )
+    .add_case(
+      // Interval case
+      builder::arity_one().of_type(prisms::expr_to_unbounded_interval()).and_then(|arg, _| {
+        Ok(Expr::from(interval_cos(Interval::from(arg))))
+      })
+    )
     .set_derivative(
       builder::arity_one_deriv("cos", |arg, engine| {
         let arg_deriv = engine.differentiate(arg.clone())?;
@@ -400,6 +456,13 @@ pub fn cosine() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("cos", |arg, engine| {
+        engine.integrate_linear_substitution("cos", arg, |u| {
+          Expr::call("sin", vec![u])
+        })
+      })
+    )
     .build()
 }
 
@@ -454,6 +517,13 @@ pub fn tangent() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("tan", |arg, engine| {
+        engine.integrate_linear_substitution("tan", arg, |u| {
+          Expr::call("negate", vec![Expr::call("ln", vec![Expr::call("cos", vec![u])])])
+        })
+      })
+    )
     .build()
 }
 
@@ -551,6 +621,13 @@ pub fn sine_hyper() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("sinh", |arg, engine| {
+        engine.integrate_linear_substitution("sinh", arg, |u| {
+          Expr::call("cosh", vec![u])
+        })
+      })
+    )
     .build()
 }
 
@@ -572,6 +649,13 @@ pub fn cosine_hyper() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("cosh", |arg, engine| {
+        engine.integrate_linear_substitution("cosh", arg, |u| {
+          Expr::call("sinh", vec![u])
+        })
+      })
+    )
     .build()
 }
 
@@ -626,6 +710,13 @@ pub fn tangent_hyper() -> Function {
         ]))
       })
     )
+    .set_integral(
+      builder::arity_one_integral("tanh", |arg, engine| {
+        engine.integrate_linear_substitution("tanh", arg, |u| {
+          Expr::call("ln", vec![Expr::call("cosh", vec![u])])
+        })
+      })
+    )
     .build()
 }
 
@@ -1135,3 +1226,22 @@ pub fn arcotangent_hyper() -> Function {
     )
     .build()
 }
+
+/// `normalize_angle(theta, lo, hi)` maps `theta` into the half-open
+/// range `[lo, hi)` by adding or subtracting multiples of the range's
+/// width. The bounds are arbitrary, so this works equally well for
+/// radians, degrees, or any other periodic quantity.
+pub fn normalize_angle() -> Function {
+  FunctionBuilder::new("normalize_angle")
+    .add_case(
+      builder::arity_three().all_of_type(expr_to_number()).and_then(|theta, lo, hi, ctx| {
+        if hi <= lo {
+          ctx.errors.push(SimplifierError::custom_error("normalize_angle", "Expected lower bound less than upper bound"));
+          return Err((theta, lo, hi));
+        }
+        let width = &hi - &lo;
+        Ok(Expr::from((theta - &lo) % width + lo))
+      })
+    )
+    .build()
+}
@@ -5,6 +5,7 @@ pub mod flags;
 pub mod library;
 pub mod partial;
 pub mod table;
+pub mod user;
 
 use flags::FunctionFlags;
 use table::FunctionTable;
@@ -13,7 +14,8 @@ use crate::graphics::response::GraphicsDirective;
 use crate::expr::Expr;
 use crate::expr::simplifier::Simplifier;
 use crate::expr::simplifier::error::SimplifierError;
-use crate::expr::calculus::{DerivativeEngine, DifferentiationFailure, DifferentiationError};
+use crate::expr::calculus::{DerivativeEngine, DifferentiationFailure, DifferentiationError, DerivativeRuleTable,
+                            IntegrationEngine, IntegrationFailure, IntegrationError};
 use crate::errorlist::ErrorList;
 
 use std::fmt::{self, Formatter, Debug};
@@ -27,6 +29,7 @@ pub struct Function {
   flags: FunctionFlags,
   identity_predicate: Box<dyn Fn(&Expr) -> bool + Send + Sync + 'static>,
   derivative_rule: Option<Box<FunctionDeriv>>,
+  integral_rule: Option<Box<FunctionIntegral>>,
   body: Box<FunctionImpl<Expr>>,
   graphics_body: Box<FunctionImpl<GraphicsDirective>>,
 }
@@ -35,6 +38,9 @@ pub struct FunctionContext<'a, 'b, 'c> {
   pub errors: &'a mut ErrorList<SimplifierError>,
   pub simplifier: &'b dyn Simplifier,
   pub function_table: &'c FunctionTable,
+  /// The user's registered derivative rules, for functions with no
+  /// built-in derivative.
+  pub derivative_rules: Option<&'c DerivativeRuleTable>,
   pub calculation_mode: CalculationMode,
   _private: (),
 }
@@ -45,6 +51,9 @@ type FunctionImpl<T> =
 type FunctionDeriv =
   dyn Fn(Vec<Expr>, &DerivativeEngine) -> Result<Expr, DifferentiationFailure> + Send + Sync;
 
+type FunctionIntegral =
+  dyn Fn(Vec<Expr>, &IntegrationEngine) -> Result<Expr, IntegrationFailure> + Send + Sync;
+
 impl Function {
   /// The function's name.
   pub fn name(&self) -> &str {
@@ -75,9 +84,10 @@ impl Function {
     errors: &mut ErrorList<SimplifierError>,
     simplifier: &dyn Simplifier,
     function_table: &FunctionTable,
+    derivative_rules: Option<&DerivativeRuleTable>,
     calculation_mode: CalculationMode,
   ) -> Result<Expr, Vec<Expr>> {
-    let mut context = FunctionContext { errors, simplifier, function_table, calculation_mode, _private: () };
+    let mut context = FunctionContext { errors, simplifier, function_table, derivative_rules, calculation_mode, _private: () };
     (self.body)(args, &mut context)
   }
 
@@ -90,10 +100,15 @@ impl Function {
     function_table: &FunctionTable,
     calculation_mode: CalculationMode,
   ) -> Result<GraphicsDirective, Vec<Expr>> {
-    let mut context = FunctionContext { errors, simplifier, function_table, calculation_mode, _private: () };
+    let mut context = FunctionContext { errors, simplifier, function_table, derivative_rules: None, calculation_mode, _private: () };
     (self.graphics_body)(args, &mut context)
   }
 
+  /// Whether the function has a built-in derivative rule.
+  pub fn has_derivative_rule(&self) -> bool {
+    self.derivative_rule.is_some()
+  }
+
   pub fn differentiate(
     &self,
     args: Vec<Expr>,
@@ -104,6 +119,17 @@ impl Function {
     };
     derivative_rule(args, engine)
   }
+
+  pub fn integrate(
+    &self,
+    args: Vec<Expr>,
+    engine: &IntegrationEngine,
+  ) -> Result<Expr, IntegrationFailure> {
+    let Some(integral_rule) = &self.integral_rule else {
+      return Err(engine.error(IntegrationError::UnknownIntegral(self.name().to_owned())));
+    };
+    integral_rule(args, engine)
+  }
 }
 
 impl Debug for Function {
@@ -0,0 +1,206 @@
+
+//! Functions defined by the user at runtime, as opposed to the
+//! built-in functions in a [`FunctionTable`](super::table::FunctionTable).
+
+use super::table::FunctionTable;
+use crate::expr::Expr;
+use crate::expr::algebra::formula::Equation;
+use crate::expr::var::Var;
+use crate::expr::var::table::VarTable;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::collections::HashMap;
+
+/// A function defined by the user, such as `f(x) = x^2 + 1`. Calls
+/// to the function are expanded by substituting the arguments for
+/// the parameters in the body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserFunction {
+  params: Vec<Var>,
+  body: Expr,
+}
+
+/// A table of user-defined functions, keyed by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserFunctionTable {
+  functions: HashMap<String, UserFunction>,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UserFunctionError {
+  #[error("Expected a function call on the left-hand side, got {0}")]
+  ExpectedFunctionCall(Expr),
+  #[error("Invalid function name: {0}")]
+  InvalidName(String),
+  #[error("Expected a variable as function parameter, got {0}")]
+  ExpectedParameter(Expr),
+  #[error("Duplicate parameter {0}")]
+  DuplicateParameter(Var),
+  #[error("Cannot redefine built-in function {0}")]
+  BuiltinName(String),
+}
+
+impl UserFunction {
+  pub fn new(params: Vec<Var>, body: Expr) -> Result<Self, UserFunctionError> {
+    for (i, param) in params.iter().enumerate() {
+      if params[..i].contains(param) {
+        return Err(UserFunctionError::DuplicateParameter(param.clone()));
+      }
+    }
+    Ok(Self { params, body })
+  }
+
+  /// Interprets an equation of the form `f(x, y, ...) = body` as a
+  /// function definition, returning the function's name and the
+  /// function itself.
+  pub fn from_equation(equation: Equation) -> Result<(String, Self), UserFunctionError> {
+    let Expr::Call(name, args) = equation.left else {
+      return Err(UserFunctionError::ExpectedFunctionCall(equation.left));
+    };
+    // Function names follow the same rules as variable names, which
+    // in particular rules out redefining operators like `+`.
+    if Var::new(name.as_str()).is_none() {
+      return Err(UserFunctionError::InvalidName(name));
+    }
+    let params = args.into_iter()
+      .map(|arg| Var::try_from(arg).map_err(|err| UserFunctionError::ExpectedParameter(err.original_expr)))
+      .collect::<Result<Vec<_>, _>>()?;
+    let function = Self::new(params, equation.right)?;
+    Ok((name, function))
+  }
+
+  /// As [`UserFunction::from_equation`], but also rejects the names
+  /// of built-in functions. The evaluator always prefers a built-in
+  /// function to a user-defined one of the same name, so such a
+  /// definition would never take effect.
+  pub fn define_from_equation(equation: Equation, builtins: &FunctionTable) -> Result<(String, Self), UserFunctionError> {
+    let (name, function) = Self::from_equation(equation)?;
+    if builtins.get(&name).is_some() {
+      return Err(UserFunctionError::BuiltinName(name));
+    }
+    Ok((name, function))
+  }
+
+  pub fn params(&self) -> &[Var] {
+    &self.params
+  }
+
+  pub fn body(&self) -> &Expr {
+    &self.body
+  }
+
+  pub fn arity(&self) -> usize {
+    self.params.len()
+  }
+
+  /// Substitutes the arguments for the parameters in the body. The
+  /// substitution is simultaneous, so an argument which mentions a
+  /// parameter name is not substituted into again. Returns the
+  /// arguments unmodified if the arity does not match.
+  pub fn expand(&self, args: Vec<Expr>) -> Result<Expr, Vec<Expr>> {
+    if args.len() != self.arity() {
+      return Err(args);
+    }
+    let bindings: VarTable<Expr> = self.params.iter().cloned().zip(args).collect();
+    Ok(self.body.clone().substitute_vars(&bindings))
+  }
+}
+
+impl UserFunctionTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.functions.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.functions.is_empty()
+  }
+
+  pub fn get(&self, name: &str) -> Option<&UserFunction> {
+    self.functions.get(name)
+  }
+
+  /// Defines the function, returning its previous definition (if
+  /// any).
+  pub fn insert(&mut self, name: String, function: UserFunction) -> Option<UserFunction> {
+    self.functions.insert(name, function)
+  }
+
+  pub fn remove(&mut self, name: &str) -> Option<UserFunction> {
+    self.functions.remove(name)
+  }
+
+  /// Iterates over the functions in an unspecified order.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &UserFunction)> {
+    self.functions.iter().map(|(name, function)| (name.as_str(), function))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::number::Number;
+
+  fn var(name: &str) -> Var {
+    Var::new(name).unwrap()
+  }
+
+  fn equation(left: Expr, right: Expr) -> Equation {
+    Equation { left, right }
+  }
+
+  #[test]
+  fn test_from_equation() {
+    let eq = equation(
+      Expr::call("f", vec![Expr::from(var("x")), Expr::from(var("y"))]),
+      Expr::call("+", vec![Expr::from(var("x")), Expr::from(var("y"))]),
+    );
+    let (name, function) = UserFunction::from_equation(eq).unwrap();
+    assert_eq!(name, "f");
+    assert_eq!(function.params(), &[var("x"), var("y")]);
+  }
+
+  #[test]
+  fn test_from_equation_errors() {
+    let eq = equation(Expr::from(var("x")), Expr::from(1));
+    assert_eq!(UserFunction::from_equation(eq), Err(UserFunctionError::ExpectedFunctionCall(Expr::from(var("x")))));
+    let eq = equation(Expr::call("+", vec![Expr::from(var("x"))]), Expr::from(1));
+    assert_eq!(UserFunction::from_equation(eq), Err(UserFunctionError::InvalidName("+".to_owned())));
+    let eq = equation(Expr::call("f", vec![Expr::from(2)]), Expr::from(1));
+    assert_eq!(UserFunction::from_equation(eq), Err(UserFunctionError::ExpectedParameter(Expr::from(2))));
+    let eq = equation(Expr::call("f", vec![Expr::from(var("x")), Expr::from(var("x"))]), Expr::from(1));
+    assert_eq!(UserFunction::from_equation(eq), Err(UserFunctionError::DuplicateParameter(var("x"))));
+  }
+
+  #[test]
+  fn test_define_from_equation_rejects_builtins() {
+    let builtins = build_function_table();
+    let eq = equation(Expr::call("sin", vec![Expr::from(var("x"))]), Expr::from(var("x")));
+    assert_eq!(UserFunction::define_from_equation(eq, &builtins), Err(UserFunctionError::BuiltinName("sin".to_owned())));
+    let eq = equation(Expr::call("f", vec![Expr::from(var("x"))]), Expr::from(var("x")));
+    assert!(UserFunction::define_from_equation(eq, &builtins).is_ok());
+  }
+
+  #[test]
+  fn test_expand_is_simultaneous() {
+    let function = UserFunction::new(
+      vec![var("x"), var("y")],
+      Expr::call("-", vec![Expr::from(var("x")), Expr::from(var("y"))]),
+    ).unwrap();
+    let expanded = function.expand(vec![Expr::from(var("y")), Expr::from(Number::from(3))]).unwrap();
+    assert_eq!(expanded, Expr::call("-", vec![Expr::from(var("y")), Expr::from(3)]));
+  }
+
+  #[test]
+  fn test_expand_wrong_arity() {
+    let function = UserFunction::new(vec![var("x")], Expr::from(var("x"))).unwrap();
+    assert_eq!(function.expand(vec![]), Err(vec![]));
+  }
+}
@@ -32,6 +32,10 @@ pub enum ObjectType {
   /// a corner case) ordinary expressions, if given only one
   /// expression.
   LeftParen,
+  /// An incomplete object used for inputting sets.
+  LeftBrace,
+  /// An incomplete object used for inputting tuples.
+  LeftAngle,
 }
 
 #[derive(Debug, Clone, Error)]
@@ -76,6 +80,8 @@ impl ObjectType {
     match self {
       ObjectType::LeftBracket => "[",
       ObjectType::LeftParen => "(",
+      ObjectType::LeftBrace => "{",
+      ObjectType::LeftAngle => "<",
     }
   }
 
@@ -83,6 +89,8 @@ impl ObjectType {
     match input_string {
       "[" => Ok(ObjectType::LeftBracket),
       "(" => Ok(ObjectType::LeftParen),
+      "{" => Ok(ObjectType::LeftBrace),
+      "<" => Ok(ObjectType::LeftAngle),
       _ => Err(ObjectTypeParseError { input_string: input_string.into() }),
     }
   }
@@ -173,16 +181,22 @@ mod tests {
   fn test_to_string() {
     assert_eq!(IncompleteObject::new(ObjectType::LeftBracket).to_string(), "[ ...");
     assert_eq!(IncompleteObject::new(ObjectType::LeftParen).to_string(), "( ...");
+    assert_eq!(IncompleteObject::new(ObjectType::LeftBrace).to_string(), "{ ...");
+    assert_eq!(IncompleteObject::new(ObjectType::LeftAngle).to_string(), "< ...");
   }
 
   #[test]
   fn test_parse_object_type() {
     assert_eq!(ObjectType::parse("[").unwrap(), ObjectType::LeftBracket);
     assert_eq!(ObjectType::parse("(").unwrap(), ObjectType::LeftParen);
+    assert_eq!(ObjectType::parse("{").unwrap(), ObjectType::LeftBrace);
+    assert_eq!(ObjectType::parse("<").unwrap(), ObjectType::LeftAngle);
     ObjectType::parse("]").unwrap_err();
     ObjectType::parse("e").unwrap_err();
     ObjectType::parse("").unwrap_err();
     ObjectType::parse("((").unwrap_err();
+    ObjectType::parse("}").unwrap_err();
+    ObjectType::parse(">").unwrap_err();
   }
 
   #[test]
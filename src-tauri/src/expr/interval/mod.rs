@@ -6,11 +6,13 @@ mod base;
 mod bound;
 mod interval_type;
 mod raw;
+mod union;
 
 pub use base::Interval;
 pub use bound::{Bounded, BoundType};
 pub use raw::RawInterval;
 pub use interval_type::IntervalType;
+pub use union::{IntervalUnion, ExprToIntervalUnion};
 
 use crate::expr::algebra::infinity::{UnboundedNumber, IndeterminateFormError, SignedInfinity};
 use crate::expr::number::Number;
@@ -19,6 +21,7 @@ use try_traits::ops::TryMul;
 use num::Zero;
 
 use std::cmp::Ordering;
+use std::f64::consts;
 
 /// The disjoint union of the types [`RawInterval<T>`] and `T`. This type
 /// can be used as the target of any prism that wishes to treat
@@ -152,6 +155,150 @@ pub fn interval_div_inexact(
   left.try_mul(interval_recip_inexact(right))
 }
 
+/// Raises an interval of nonnegative reals to an interval of real
+/// powers, producing the tightest interval containing every possible
+/// result. Fails if any of the corner cases is an indeterminate form.
+///
+/// Panics if `base` contains negative numbers.
+pub fn interval_pow(
+  base: Interval<UnboundedNumber>,
+  exponent: Interval<UnboundedNumber>,
+) -> Result<Interval<UnboundedNumber>, IndeterminateFormError> {
+  // On the nonnegative reals, `x^y` is monotone in each argument
+  // separately, so the extremes are attained at the corners.
+  base.apply_monotone_err(exponent, UnboundedNumber::try_pow_nonnegative)
+}
+
+/// The set of all values `min(x, y)` for `x` in `left` and `y` in
+/// `right`.
+pub fn interval_min<T: Ord + Default>(left: Interval<T>, right: Interval<T>) -> Interval<T> {
+  if left.is_empty() || right.is_empty() {
+    return Interval::empty();
+  }
+  let (left_lower, left_upper) = left.into_bounds();
+  let (right_lower, right_upper) = right.into_bounds();
+  // The result only attains a shared upper bound if both arguments
+  // do, so take the stricter bound type in case of a tie.
+  Interval::from_bounds(left_lower.min(right_lower), strict_min(left_upper, right_upper))
+}
+
+/// The set of all values `max(x, y)` for `x` in `left` and `y` in
+/// `right`.
+pub fn interval_max<T: Ord + Default>(left: Interval<T>, right: Interval<T>) -> Interval<T> {
+  if left.is_empty() || right.is_empty() {
+    return Interval::empty();
+  }
+  let (left_lower, left_upper) = left.into_bounds();
+  let (right_lower, right_upper) = right.into_bounds();
+  Interval::from_bounds(strict_max(left_lower, right_lower), left_upper.max(right_upper))
+}
+
+fn strict_min<T: Ord>(left: Bounded<T>, right: Bounded<T>) -> Bounded<T> {
+  match left.scalar.cmp(&right.scalar) {
+    Ordering::Less => left,
+    Ordering::Greater => right,
+    Ordering::Equal => Bounded::new(left.scalar, left.bound_type.min(right.bound_type)),
+  }
+}
+
+fn strict_max<T: Ord>(left: Bounded<T>, right: Bounded<T>) -> Bounded<T> {
+  match left.scalar.cmp(&right.scalar) {
+    Ordering::Greater => left,
+    Ordering::Less => right,
+    Ordering::Equal => Bounded::new(left.scalar, left.bound_type.min(right.bound_type)),
+  }
+}
+
+/// Computes the image of an interval under `wrap(x, lo, hi)`, which
+/// reduces `x` into the half-open interval `lo ..^ hi`. If the
+/// interval straddles a point where the function wraps around, the
+/// result is all of `lo ..^ hi`.
+///
+/// Requires `lo < hi`.
+pub fn interval_wrap(interval: Interval<Number>, lo: Number, hi: Number) -> Interval<Number> {
+  if interval.is_empty() {
+    return interval;
+  }
+  let width = &hi - &lo;
+  let interval_type = interval.interval_type();
+  let (left, right) = interval.into_extremes();
+  let offset = (&left - &lo).div_floor(&width) * width;
+  let left = left - &offset;
+  let right = right - offset;
+  if right < hi || (right == hi && !interval_type.includes_right()) {
+    Interval::new(left, interval_type, right)
+  } else {
+    Interval::new(lo, IntervalType::RightOpen, hi)
+  }
+}
+
+/// Computes the image of an interval under the sine function.
+pub fn interval_sin(interval: Interval<UnboundedNumber>) -> Interval<UnboundedNumber> {
+  interval_periodic(interval, Number::sin, consts::FRAC_PI_2, 3.0 * consts::FRAC_PI_2)
+}
+
+/// Computes the image of an interval under the cosine function.
+pub fn interval_cos(interval: Interval<UnboundedNumber>) -> Interval<UnboundedNumber> {
+  interval_periodic(interval, Number::cos, 0.0, consts::PI)
+}
+
+/// Computes the image of an interval under a function with period
+/// `2 pi` and range `[-1, 1]`, which attains its maximum at
+/// `max_phase` and its minimum at `min_phase` and is monotone in
+/// between.
+fn interval_periodic<F>(
+  interval: Interval<UnboundedNumber>,
+  f: F,
+  max_phase: f64,
+  min_phase: f64,
+) -> Interval<UnboundedNumber>
+where F: Fn(&Number) -> Number {
+  use UnboundedNumber::Finite;
+  if interval.is_empty() {
+    return Interval::empty();
+  }
+  let full_range = Interval::new(UnboundedNumber::finite(-1), IntervalType::Closed, UnboundedNumber::finite(1));
+  let (lower, upper) = interval.into_bounds();
+  let (Finite(a), Finite(b)) = (&lower.scalar, &upper.scalar) else {
+    return full_range;
+  };
+  let (a_f64, b_f64) = (a.to_f64_or_nan(), b.to_f64_or_nan());
+  if b_f64 - a_f64 >= consts::TAU {
+    return full_range;
+  }
+
+  let lower_image = Bounded::new(Finite(f(a)), lower.bound_type);
+  let upper_image = Bounded::new(Finite(f(b)), upper.bound_type);
+  let mut result_lower = lower_image.clone().min(upper_image.clone());
+  let mut result_upper = lower_image.max(upper_image);
+  if contains_phase(&lower, &upper, max_phase) {
+    result_upper = Bounded::new(UnboundedNumber::finite(1), BoundType::Inclusive);
+  }
+  if contains_phase(&lower, &upper, min_phase) {
+    result_lower = Bounded::new(UnboundedNumber::finite(-1), BoundType::Inclusive);
+  }
+  Interval::from_bounds(result_lower, result_upper)
+}
+
+/// Whether the (finite) interval with the given bounds contains any
+/// number of the form `phase + 2 pi k`, for an integer `k`.
+fn contains_phase(lower: &Bounded<UnboundedNumber>, upper: &Bounded<UnboundedNumber>, phase: f64) -> bool {
+  let Some(a) = unbounded_to_f64(lower.scalar()) else { return false };
+  let Some(b) = unbounded_to_f64(upper.scalar()) else { return false };
+  let mut candidate = phase + ((a - phase) / consts::TAU).ceil() * consts::TAU;
+  if candidate == a && lower.bound_type() == BoundType::Exclusive {
+    candidate += consts::TAU;
+  }
+  candidate < b || (candidate == b && upper.bound_type() == BoundType::Inclusive)
+}
+
+fn unbounded_to_f64(n: &UnboundedNumber) -> Option<f64> {
+  match n {
+    UnboundedNumber::Finite(n) => n.to_f64(),
+    UnboundedNumber::Infinite(_) => None,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -288,4 +435,133 @@ mod tests {
       Interval::new(finite(-2.0), IntervalType::FullOpen, finite(-0.5)),
     );
   }
+
+  #[test]
+  fn test_interval_pow() {
+    let base = Interval::new(finite(1.0), IntervalType::Closed, finite(4.0));
+    let exponent = Interval::singleton(finite(0.5));
+    assert_eq!(
+      interval_pow(base, exponent).unwrap(),
+      Interval::new(finite(1.0), IntervalType::Closed, finite(2.0)),
+    );
+
+    let base = Interval::singleton(finite(2.0));
+    let exponent = Interval::new(finite(-1.0), IntervalType::RightOpen, finite(3.0));
+    assert_eq!(
+      interval_pow(base, exponent).unwrap(),
+      Interval::new(finite(0.5), IntervalType::RightOpen, finite(8.0)),
+    );
+
+    let base = Interval::singleton(finite(0.5));
+    let exponent = Interval::new(finite(1.0), IntervalType::Closed, pos_infinity());
+    assert_eq!(
+      interval_pow(base, exponent).unwrap(),
+      Interval::new(finite(0.0), IntervalType::Closed, finite(0.5)),
+    );
+  }
+
+  #[test]
+  fn test_interval_pow_with_zero_base() {
+    let base = Interval::new(finite(0.0), IntervalType::Closed, finite(4.0));
+    let exponent = Interval::singleton(finite(-0.5));
+    assert_eq!(
+      interval_pow(base, exponent).unwrap(),
+      Interval::new(finite(0.5), IntervalType::Closed, pos_infinity()),
+    );
+
+    let base = Interval::new(finite(0.0), IntervalType::Closed, finite(4.0));
+    let exponent = Interval::new(finite(0.0), IntervalType::Closed, finite(1.0));
+    interval_pow(base, exponent).unwrap_err();
+  }
+
+  #[test]
+  fn test_interval_min_and_max() {
+    let left = Interval::new(finite(1.0), IntervalType::Closed, finite(3.0));
+    let right = Interval::new(finite(2.0), IntervalType::RightOpen, finite(4.0));
+    assert_eq!(
+      interval_min(left.clone(), right.clone()),
+      Interval::new(finite(1.0), IntervalType::Closed, finite(3.0)),
+    );
+    assert_eq!(
+      interval_max(left.clone(), right),
+      Interval::new(finite(2.0), IntervalType::RightOpen, finite(4.0)),
+    );
+    let touching = Interval::new(finite(1.0), IntervalType::LeftOpen, finite(3.0));
+    assert_eq!(
+      interval_max(left.clone(), touching.clone()),
+      Interval::new(finite(1.0), IntervalType::LeftOpen, finite(3.0)),
+    );
+    assert_eq!(interval_min(left, Interval::empty()), Interval::empty());
+  }
+
+  #[test]
+  fn test_interval_wrap() {
+    let wrap = |left: i64, interval_type, right: i64| {
+      let interval = Interval::new(Number::from(left), interval_type, Number::from(right));
+      interval_wrap(interval, Number::from(0), Number::from(360))
+    };
+    assert_eq!(
+      wrap(370, IntervalType::Closed, 380),
+      Interval::new(Number::from(10), IntervalType::Closed, Number::from(20)),
+    );
+    assert_eq!(
+      wrap(-20, IntervalType::LeftOpen, -10),
+      Interval::new(Number::from(340), IntervalType::LeftOpen, Number::from(350)),
+    );
+    assert_eq!(
+      wrap(350, IntervalType::RightOpen, 360),
+      Interval::new(Number::from(350), IntervalType::RightOpen, Number::from(360)),
+    );
+    assert_eq!(
+      wrap(350, IntervalType::Closed, 360),
+      Interval::new(Number::from(0), IntervalType::RightOpen, Number::from(360)),
+    );
+  }
+
+  #[test]
+  fn test_interval_sin_monotone_region() {
+    let interval = Interval::new(finite(0.0), IntervalType::RightOpen, finite(1.0));
+    assert_eq!(
+      interval_sin(interval),
+      Interval::new(finite(0.0), IntervalType::RightOpen, finite(1.0_f64.sin())),
+    );
+  }
+
+  #[test]
+  fn test_interval_sin_through_extrema() {
+    // Contains pi/2 but not 3 pi/2
+    let interval = Interval::new(finite(1.0), IntervalType::Closed, finite(3.0));
+    assert_eq!(
+      interval_sin(interval),
+      Interval::new(finite(3.0_f64.sin()), IntervalType::Closed, finite(1.0)),
+    );
+    // Contains 3 pi/2 (and 3 pi/2 - 2 pi) but not pi/2
+    let interval = Interval::new(finite(-3.0), IntervalType::Closed, finite(-1.0));
+    assert_eq!(
+      interval_sin(interval),
+      Interval::new(finite(-1.0), IntervalType::Closed, finite((-3.0_f64).sin())),
+    );
+  }
+
+  #[test]
+  fn test_interval_sin_full_period() {
+    let interval = Interval::new(finite(0.0), IntervalType::Closed, finite(7.0));
+    assert_eq!(interval_sin(interval), Interval::new(finite(-1.0), IntervalType::Closed, finite(1.0)));
+    let interval = Interval::new(finite(0.0), IntervalType::Closed, pos_infinity());
+    assert_eq!(interval_sin(interval), Interval::new(finite(-1.0), IntervalType::Closed, finite(1.0)));
+  }
+
+  #[test]
+  fn test_interval_cos() {
+    let interval = Interval::new(finite(-1.0), IntervalType::Closed, finite(2.0));
+    assert_eq!(
+      interval_cos(interval),
+      Interval::new(finite(2.0_f64.cos()), IntervalType::Closed, finite(1.0)),
+    );
+    let interval = Interval::new(finite(3.0), IntervalType::FullOpen, finite(4.0));
+    assert_eq!(
+      interval_cos(interval),
+      Interval::new(finite(-1.0), IntervalType::RightOpen, finite(4.0_f64.cos())),
+    );
+  }
 }
@@ -0,0 +1,269 @@
+
+//! Finite unions of intervals, used to describe subsets of the real
+//! line which are not themselves intervals, such as the solution set
+//! of an inequality.
+
+use super::{Interval, IntervalType, RawInterval, Bounded, BoundType};
+use crate::expr::Expr;
+use crate::expr::set::Set;
+use crate::expr::algebra::infinity::UnboundedNumber;
+use crate::util::prism::Prism;
+
+use std::cmp::Ordering;
+
+/// A union of intervals of (extended) real numbers.
+///
+/// In the expression language, an `IntervalUnion` is a call to the
+/// function called "union" whose arguments are all intervals. A union
+/// of one interval is represented as that interval, and the empty
+/// union is represented as the empty set `set()`. Internally, the
+/// intervals are always nonempty, sorted in increasing order, and
+/// pairwise disjoint and non-adjacent, so two unions describing the
+/// same set are structurally equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalUnion {
+  intervals: Vec<Interval<UnboundedNumber>>,
+}
+
+/// Prism which accepts interval unions, single intervals, and the
+/// empty set.
+///
+/// Delegates to [`IntervalUnion::parse`] for narrowing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExprToIntervalUnion;
+
+impl IntervalUnion {
+  pub const FUNCTION_NAME: &'static str = "union";
+
+  pub fn empty() -> Self {
+    Self { intervals: vec![] }
+  }
+
+  /// The union consisting of the whole real line.
+  pub fn all_reals() -> Self {
+    Self::from(Interval::new(UnboundedNumber::NEG_INFINITY, IntervalType::FullOpen, UnboundedNumber::POS_INFINITY))
+  }
+
+  /// Builds the union of the given intervals, merging any which
+  /// overlap or touch.
+  pub fn from_intervals(intervals: impl IntoIterator<Item = Interval<UnboundedNumber>>) -> Self {
+    let mut intervals: Vec<_> = intervals.into_iter()
+      .filter(|interval| !interval.is_empty())
+      .map(Interval::into_bounds)
+      .collect();
+    intervals.sort_by(|(a, _), (b, _)| cmp_lower_bounds(a, b));
+
+    let mut merged: Vec<(Bounded<UnboundedNumber>, Bounded<UnboundedNumber>)> = Vec::with_capacity(intervals.len());
+    for (lower, upper) in intervals {
+      match merged.last_mut() {
+        Some((_, last_upper)) if connects(last_upper, &lower) => {
+          if cmp_upper_bounds(&upper, last_upper) == Ordering::Greater {
+            *last_upper = upper;
+          }
+        }
+        _ => merged.push((lower, upper)),
+      }
+    }
+    let intervals = merged.into_iter()
+      .map(|(lower, upper)| Interval::from_bounds(lower, upper))
+      .collect();
+    Self { intervals }
+  }
+
+  /// The intervals making up this union, in increasing order.
+  pub fn intervals(&self) -> &[Interval<UnboundedNumber>] {
+    &self.intervals
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.intervals.is_empty()
+  }
+
+  /// The set of points belonging to both unions.
+  pub fn intersection(&self, other: &Self) -> Self {
+    let pieces = self.intervals.iter().flat_map(|a| {
+      other.intervals.iter().map(move |b| intersect_intervals(a.clone(), b.clone()))
+    });
+    Self::from_intervals(pieces)
+  }
+
+  /// Interprets the expression as a union of intervals, if possible.
+  /// The arguments of a `union` call need not be in normal form.
+  pub fn parse(expr: Expr) -> Result<Self, Expr> {
+    match expr {
+      Expr::Call(name, args) if name == IntervalUnion::FUNCTION_NAME => {
+        match args.iter().map(|arg| parse_interval(arg.clone())).collect::<Option<Vec<_>>>() {
+          Some(intervals) => Ok(Self::from_intervals(intervals)),
+          None => Err(Expr::Call(name, args)),
+        }
+      }
+      Expr::Call(name, args) if name == Set::FUNCTION_NAME && args.is_empty() => {
+        Ok(Self::empty())
+      }
+      expr => match parse_interval(expr.clone()) {
+        Some(interval) => Ok(Self::from(interval)),
+        None => Err(expr),
+      }
+    }
+  }
+
+  pub fn into_expr(mut self) -> Expr {
+    match self.intervals.len() {
+      0 => Set::empty().into(),
+      1 => self.intervals.pop().unwrap().into(), // unwrap: Just checked the length.
+      _ => Expr::call(IntervalUnion::FUNCTION_NAME, self.intervals.into_iter().map(Expr::from).collect()),
+    }
+  }
+}
+
+fn parse_interval(expr: Expr) -> Option<Interval<UnboundedNumber>> {
+  RawInterval::<UnboundedNumber>::try_from(expr).ok().map(Interval::from)
+}
+
+fn intersect_intervals(a: Interval<UnboundedNumber>, b: Interval<UnboundedNumber>) -> Interval<UnboundedNumber> {
+  let (a_lower, a_upper) = a.into_bounds();
+  let (b_lower, b_upper) = b.into_bounds();
+  let lower = if cmp_lower_bounds(&a_lower, &b_lower) == Ordering::Less { b_lower } else { a_lower };
+  let upper = if cmp_upper_bounds(&a_upper, &b_upper) == Ordering::Greater { b_upper } else { a_upper };
+  Interval::from_bounds(lower, upper)
+}
+
+/// Orders lower bounds by the position at which they start including
+/// points, so an inclusive bound precedes an exclusive one at the
+/// same scalar.
+fn cmp_lower_bounds(a: &Bounded<UnboundedNumber>, b: &Bounded<UnboundedNumber>) -> Ordering {
+  a.scalar.cmp(&b.scalar).then(b.bound_type.cmp(&a.bound_type))
+}
+
+/// Orders upper bounds by the position at which they stop including
+/// points, so an exclusive bound precedes an inclusive one at the
+/// same scalar.
+fn cmp_upper_bounds(a: &Bounded<UnboundedNumber>, b: &Bounded<UnboundedNumber>) -> Ordering {
+  a.scalar.cmp(&b.scalar).then(a.bound_type.cmp(&b.bound_type))
+}
+
+/// Whether an interval ending at `upper` and one starting at `lower`
+/// (no earlier than the first starts) have a union which is an
+/// interval.
+fn connects(upper: &Bounded<UnboundedNumber>, lower: &Bounded<UnboundedNumber>) -> bool {
+  match upper.scalar.cmp(&lower.scalar) {
+    Ordering::Greater => true,
+    Ordering::Equal => upper.bound_type == BoundType::Inclusive || lower.bound_type == BoundType::Inclusive,
+    Ordering::Less => false,
+  }
+}
+
+impl From<Interval<UnboundedNumber>> for IntervalUnion {
+  fn from(interval: Interval<UnboundedNumber>) -> Self {
+    Self::from_intervals([interval])
+  }
+}
+
+impl From<IntervalUnion> for Expr {
+  fn from(union: IntervalUnion) -> Expr {
+    union.into_expr()
+  }
+}
+
+impl Prism<Expr, IntervalUnion> for ExprToIntervalUnion {
+  fn narrow_type(&self, input: Expr) -> Result<IntervalUnion, Expr> {
+    IntervalUnion::parse(input)
+  }
+  fn widen_type(&self, input: IntervalUnion) -> Expr {
+    input.into_expr()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn interval(left: i64, interval_type: IntervalType, right: i64) -> Interval<UnboundedNumber> {
+    Interval::new(UnboundedNumber::finite(left), interval_type, UnboundedNumber::finite(right))
+  }
+
+  #[test]
+  fn test_merges_overlapping_intervals() {
+    let union = IntervalUnion::from_intervals([
+      interval(3, IntervalType::Closed, 5),
+      interval(0, IntervalType::Closed, 2),
+      interval(1, IntervalType::FullOpen, 4),
+    ]);
+    assert_eq!(union.intervals(), &[interval(0, IntervalType::Closed, 5)]);
+  }
+
+  #[test]
+  fn test_merges_touching_intervals() {
+    let union = IntervalUnion::from_intervals([
+      interval(0, IntervalType::RightOpen, 1),
+      interval(1, IntervalType::Closed, 2),
+      interval(2, IntervalType::FullOpen, 3),
+    ]);
+    assert_eq!(union.intervals(), &[interval(0, IntervalType::RightOpen, 3)]);
+  }
+
+  #[test]
+  fn test_keeps_separated_intervals() {
+    let union = IntervalUnion::from_intervals([
+      interval(1, IntervalType::FullOpen, 2),
+      interval(0, IntervalType::RightOpen, 1),
+      interval(5, IntervalType::Closed, 5),
+    ]);
+    assert_eq!(union.intervals(), &[
+      interval(0, IntervalType::RightOpen, 1),
+      interval(1, IntervalType::FullOpen, 2),
+      interval(5, IntervalType::Closed, 5),
+    ]);
+  }
+
+  #[test]
+  fn test_discards_empty_intervals() {
+    let union = IntervalUnion::from_intervals([interval(1, IntervalType::RightOpen, 1)]);
+    assert!(union.is_empty());
+    assert_eq!(union.into_expr(), Expr::call("set", vec![]));
+  }
+
+  #[test]
+  fn test_intersection() {
+    let a = IntervalUnion::from_intervals([
+      interval(0, IntervalType::Closed, 4),
+      interval(6, IntervalType::FullOpen, 9),
+    ]);
+    let b = IntervalUnion::from_intervals([
+      interval(2, IntervalType::FullOpen, 7),
+      interval(9, IntervalType::Closed, 10),
+    ]);
+    assert_eq!(a.intersection(&b).intervals(), &[
+      interval(2, IntervalType::LeftOpen, 4),
+      interval(6, IntervalType::FullOpen, 7),
+    ]);
+    assert!(a.intersection(&IntervalUnion::empty()).is_empty());
+    assert_eq!(a.intersection(&IntervalUnion::all_reals()), a);
+  }
+
+  #[test]
+  fn test_round_trip_through_expr() {
+    let union = IntervalUnion::from_intervals([
+      interval(0, IntervalType::Closed, 1),
+      interval(2, IntervalType::LeftOpen, 3),
+    ]);
+    let expr = union.clone().into_expr();
+    assert_eq!(
+      expr,
+      Expr::call("union", vec![
+        Expr::call("..", vec![Expr::from(0), Expr::from(1)]),
+        Expr::call("^..", vec![Expr::from(2), Expr::from(3)]),
+      ]),
+    );
+    assert_eq!(IntervalUnion::parse(expr), Ok(union));
+  }
+
+  #[test]
+  fn test_parse_failure() {
+    let expr = Expr::call("union", vec![
+      Expr::call("..", vec![Expr::from(0), Expr::from(1)]),
+      Expr::var("x").unwrap(),
+    ]);
+    assert_eq!(ExprToIntervalUnion.narrow_type(expr.clone()), Err(expr));
+  }
+}
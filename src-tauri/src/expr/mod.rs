@@ -1,19 +1,28 @@
 
 pub mod algebra;
 pub mod arithmetic;
+pub mod assumptions;
 pub mod atom;
 pub mod basic_parser;
 pub mod calculus;
+pub mod delta;
+pub mod distribution;
 pub mod function;
 pub mod incomplete;
 pub mod interval;
 pub mod literal;
+pub mod modular;
 pub mod number;
 pub mod ordering;
 pub mod predicates;
 pub mod prisms;
+pub mod random;
+pub mod rewrite;
+pub mod set;
 pub mod simplifier;
+pub mod template;
 pub mod tokenizer;
+pub mod tuple;
 pub mod units;
 pub mod var;
 pub mod vector;
@@ -22,7 +31,7 @@ pub mod walker;
 use atom::Atom;
 use var::Var;
 use var::table::VarTable;
-use number::{Number, ComplexNumber, Quaternion};
+use number::{Number, ComplexNumber, Quaternion, Octonion};
 use crate::util::prism::ErrorWithPayload;
 use crate::util::stricteq::StrictEq;
 
@@ -83,6 +92,8 @@ impl Expr {
           args[0].is_zero() && args[1].is_zero()
         } else if f == Quaternion::FUNCTION_NAME && args.len() == 4 {
           args[0].is_zero() && args[1].is_zero() && args[2].is_zero() && args[3].is_zero()
+        } else if f == Octonion::FUNCTION_NAME && args.len() == 8 {
+          args.iter().all(Expr::is_zero)
         } else {
           false
         }
@@ -102,6 +113,8 @@ impl Expr {
           args[0].is_one() && args[1].is_zero()
         } else if f == Quaternion::FUNCTION_NAME && args.len() == 4 {
           args[0].is_one() && args[1].is_zero() && args[2].is_zero() && args[3].is_zero()
+        } else if f == Octonion::FUNCTION_NAME && args.len() == 8 {
+          args[0].is_one() && args[1..].iter().all(Expr::is_zero)
         } else {
           false
         }
@@ -110,6 +123,30 @@ impl Expr {
     }
   }
 
+  /// The total number of nodes (atoms and calls) in this expression
+  /// tree, including the expression itself.
+  pub fn node_count(&self) -> usize {
+    let mut count = 0;
+    walker::postorder_walk_borrowed(self, |_| count += 1);
+    count
+  }
+
+  /// A rough estimate of the number of bytes of memory occupied by
+  /// this expression tree.
+  pub fn approx_size_in_bytes(&self) -> usize {
+    let mut size = 0;
+    walker::postorder_walk_borrowed(self, |expr| {
+      size += mem::size_of::<Expr>();
+      size += match expr {
+        Expr::Atom(Atom::Number(n)) => n.approx_heap_size(),
+        Expr::Atom(Atom::String(s)) => s.capacity(),
+        Expr::Atom(Atom::Var(v)) => v.as_str().len(),
+        Expr::Call(f, _) => f.capacity(),
+      };
+    });
+    size
+  }
+
   /// Equivalent to [`Expr::from`] but can be used to make the
   /// intention clearer.
   pub fn string(s: impl Into<String>) -> Expr {
@@ -334,6 +371,13 @@ impl From<Quaternion> for Expr {
   }
 }
 
+impl From<Octonion> for Expr {
+  fn from(o: Octonion) -> Expr {
+    let components = o.into_components().into_iter().map(Expr::from).collect();
+    Expr::call(Octonion::FUNCTION_NAME, components)
+  }
+}
+
 impl From<BigInt> for Expr {
   fn from(b: BigInt) -> Expr {
     Expr::Atom(Atom::Number(b.into()))
@@ -401,6 +445,21 @@ mod tests {
     Expr::var(name).unwrap()
   }
 
+  #[test]
+  fn test_node_count() {
+    assert_eq!(Expr::from(1).node_count(), 1);
+    let expr = Expr::call("+", vec![var("x"), Expr::call("*", vec![Expr::from(2), var("y")])]);
+    assert_eq!(expr.node_count(), 5);
+  }
+
+  #[test]
+  fn test_approx_size_in_bytes_grows_with_expr() {
+    let small = Expr::from(1);
+    let large = Expr::call("+", vec![Expr::from(1), Expr::string("a longer string value")]);
+    assert!(small.approx_size_in_bytes() >= mem::size_of::<Expr>());
+    assert!(large.approx_size_in_bytes() > small.approx_size_in_bytes());
+  }
+
   #[test]
   fn test_var_substitute_with_no_variables() {
     let expr = Expr::call("+", vec![Expr::from(1), Expr::from(2)]);
@@ -0,0 +1,282 @@
+
+//! Integers modulo a fixed positive modulus.
+
+use super::Expr;
+use super::atom::Atom;
+use crate::util::modular::modinv;
+use crate::util::prism::Prism;
+
+use num::{BigInt, Zero, One, Integer, Signed};
+use either::Either;
+use thiserror::Error;
+
+/// A `ModularInt` is an integer considered modulo some positive
+/// integer modulus, similar to Emacs Calc's modulo forms.
+///
+/// A `ModularInt` is represented in the expression language as a
+/// call to the function called "modulo", with the value as the first
+/// argument and the modulus as the second. The value is always kept
+/// in the range `[0, m)`, where `m` is the modulus.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModularInt {
+  value: BigInt,
+  modulus: BigInt,
+}
+
+/// Prism which accepts only modular integers in canonical form (i.e.
+/// calls to the function "modulo" whose arguments are integers, with
+/// a positive modulus and a value in range).
+///
+/// Delegates to [`ModularInt::parse`] for narrowing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExprToModularInt;
+
+#[derive(Debug, Error)]
+#[error("Expected a modular integer, got {original_expr}")]
+pub struct ParseModularIntError {
+  pub original_expr: Expr,
+  _priv: (),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ModularError {
+  #[error("Expected a positive modulus, got {0}")]
+  NonPositiveModulus(BigInt),
+  #[error("Cannot combine values modulo {0} and modulo {1}")]
+  MismatchedModuli(BigInt, BigInt),
+  #[error("{value} has no inverse modulo {modulus}")]
+  NotInvertible { value: BigInt, modulus: BigInt },
+}
+
+impl ModularInt {
+  pub const FUNCTION_NAME: &'static str = "modulo";
+
+  /// Constructs a modular integer, reducing `value` into the range
+  /// `[0, modulus)`. Fails if the modulus is not positive.
+  pub fn new(value: BigInt, modulus: BigInt) -> Result<ModularInt, ModularError> {
+    if !modulus.is_positive() {
+      return Err(ModularError::NonPositiveModulus(modulus));
+    }
+    let value = value.mod_floor(&modulus);
+    Ok(ModularInt { value, modulus })
+  }
+
+  /// If the expression is of the form `modulo(a, m)`, where `a` and
+  /// `m` are integers with `0 <= a < m`, returns the corresponding
+  /// [`ModularInt`]. Non-canonical forms such as `modulo(9, 7)` are
+  /// rejected, so that parsing is always reversible; the "modulo"
+  /// function in the function library normalizes them.
+  pub fn parse(expr: Expr) -> Result<ModularInt, ParseModularIntError> {
+    if let Expr::Call(name, args) = &expr {
+      if name == ModularInt::FUNCTION_NAME {
+        if let [Expr::Atom(Atom::Number(value)), Expr::Atom(Atom::Number(modulus))] = args.as_slice() {
+          if let (Ok(value), Ok(modulus)) = (BigInt::try_from(value.clone()), BigInt::try_from(modulus.clone())) {
+            if modulus.is_positive() && !value.is_negative() && value < modulus {
+              return Ok(ModularInt { value, modulus });
+            }
+          }
+        }
+      }
+    }
+    Err(ParseModularIntError { original_expr: expr, _priv: () })
+  }
+
+  pub fn value(&self) -> &BigInt {
+    &self.value
+  }
+
+  pub fn modulus(&self) -> &BigInt {
+    &self.modulus
+  }
+
+  /// Reduces an ordinary integer using this value's modulus.
+  pub fn lift(&self, value: BigInt) -> ModularInt {
+    let value = value.mod_floor(&self.modulus);
+    ModularInt { value, modulus: self.modulus.clone() }
+  }
+
+  pub fn checked_add(&self, other: &ModularInt) -> Result<ModularInt, ModularError> {
+    self.check_same_modulus(other)?;
+    Ok(self.lift(&self.value + &other.value))
+  }
+
+  pub fn checked_sub(&self, other: &ModularInt) -> Result<ModularInt, ModularError> {
+    self.check_same_modulus(other)?;
+    Ok(self.lift(&self.value - &other.value))
+  }
+
+  pub fn checked_mul(&self, other: &ModularInt) -> Result<ModularInt, ModularError> {
+    self.check_same_modulus(other)?;
+    Ok(self.lift(&self.value * &other.value))
+  }
+
+  pub fn checked_div(&self, other: &ModularInt) -> Result<ModularInt, ModularError> {
+    self.check_same_modulus(other)?;
+    let inverse = other.inverse()?;
+    Ok(self.lift(&self.value * inverse.value))
+  }
+
+  pub fn negate(&self) -> ModularInt {
+    self.lift(- &self.value)
+  }
+
+  /// The multiplicative inverse of this value. Fails if the value is
+  /// not coprime to the modulus.
+  pub fn inverse(&self) -> Result<ModularInt, ModularError> {
+    match modinv(&self.value, &self.modulus) {
+      Some(value) => Ok(ModularInt { value, modulus: self.modulus.clone() }),
+      None => Err(ModularError::NotInvertible { value: self.value.clone(), modulus: self.modulus.clone() }),
+    }
+  }
+
+  /// Raises this value to an integer power. Negative exponents are
+  /// permitted if the value is invertible.
+  pub fn pow(&self, exponent: &BigInt) -> Result<ModularInt, ModularError> {
+    if exponent.is_negative() {
+      let inverse = self.inverse()?;
+      return inverse.pow(&-exponent);
+    }
+    if self.modulus.is_one() {
+      return Ok(ModularInt { value: BigInt::zero(), modulus: self.modulus.clone() });
+    }
+    let value = self.value.modpow(exponent, &self.modulus);
+    Ok(ModularInt { value, modulus: self.modulus.clone() })
+  }
+
+  pub fn into_expr(self) -> Expr {
+    Expr::call(ModularInt::FUNCTION_NAME, vec![Expr::from(self.value), Expr::from(self.modulus)])
+  }
+
+  fn check_same_modulus(&self, other: &ModularInt) -> Result<(), ModularError> {
+    if self.modulus == other.modulus {
+      Ok(())
+    } else {
+      Err(ModularError::MismatchedModuli(self.modulus.clone(), other.modulus.clone()))
+    }
+  }
+}
+
+/// Given a list of values, each of which is either a modular integer
+/// or an ordinary integer, reduces all of them to modular integers
+/// with a common modulus. Returns `Ok(None)` if none of the values
+/// are modular integers, or an error if two of them have different
+/// moduli.
+pub fn unify_moduli<'a, I>(values: I) -> Result<Option<Vec<ModularInt>>, ModularError>
+where I: IntoIterator<Item = &'a Either<ModularInt, BigInt>>,
+      I::IntoIter: Clone {
+  let values = values.into_iter();
+  let mut template: Option<&ModularInt> = None;
+  for value in values.clone() {
+    if let Either::Left(modular) = value {
+      match template {
+        None => { template = Some(modular); }
+        Some(template) => { template.check_same_modulus(modular)?; }
+      }
+    }
+  }
+  let Some(template) = template else {
+    return Ok(None);
+  };
+  let result = values
+    .map(|value| match value {
+      Either::Left(modular) => modular.clone(),
+      Either::Right(n) => template.lift(n.clone()),
+    })
+    .collect();
+  Ok(Some(result))
+}
+
+impl From<ModularInt> for Expr {
+  fn from(modular: ModularInt) -> Expr {
+    modular.into_expr()
+  }
+}
+
+impl Prism<Expr, ModularInt> for ExprToModularInt {
+  fn narrow_type(&self, input: Expr) -> Result<ModularInt, Expr> {
+    ModularInt::parse(input).map_err(|err| err.original_expr)
+  }
+  fn widen_type(&self, input: ModularInt) -> Expr {
+    input.into_expr()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn modular(value: i64, modulus: i64) -> ModularInt {
+    ModularInt::new(BigInt::from(value), BigInt::from(modulus)).unwrap()
+  }
+
+  #[test]
+  fn test_new_normalizes() {
+    assert_eq!(modular(9, 7).value(), &BigInt::from(2));
+    assert_eq!(modular(-1, 7).value(), &BigInt::from(6));
+    assert_eq!(
+      ModularInt::new(BigInt::from(3), BigInt::zero()),
+      Err(ModularError::NonPositiveModulus(BigInt::zero())),
+    );
+  }
+
+  #[test]
+  fn test_parse() {
+    let expr = Expr::call("modulo", vec![Expr::from(3), Expr::from(7)]);
+    assert_eq!(ModularInt::parse(expr).unwrap(), modular(3, 7));
+    for args in [(9, 7), (-1, 7), (3, 0), (3, -7)] {
+      let expr = Expr::call("modulo", vec![Expr::from(args.0), Expr::from(args.1)]);
+      assert!(ModularInt::parse(expr).is_err());
+    }
+    let expr = Expr::call("modulo", vec![Expr::from(1.5), Expr::from(7)]);
+    assert!(ModularInt::parse(expr).is_err());
+  }
+
+  #[test]
+  fn test_prism_round_trip() {
+    let expr = Expr::call("modulo", vec![Expr::from(4), Expr::from(11)]);
+    let value = ExprToModularInt.narrow_type(expr.clone()).unwrap();
+    assert_eq!(ExprToModularInt.widen_type(value), expr);
+  }
+
+  #[test]
+  fn test_arithmetic() {
+    assert_eq!(modular(5, 7).checked_add(&modular(4, 7)), Ok(modular(2, 7)));
+    assert_eq!(modular(2, 7).checked_sub(&modular(4, 7)), Ok(modular(5, 7)));
+    assert_eq!(modular(3, 7).checked_mul(&modular(5, 7)), Ok(modular(1, 7)));
+    assert_eq!(modular(3, 7).negate(), modular(4, 7));
+    assert_eq!(modular(0, 7).negate(), modular(0, 7));
+    assert_eq!(
+      modular(3, 7).checked_add(&modular(3, 8)),
+      Err(ModularError::MismatchedModuli(BigInt::from(7), BigInt::from(8))),
+    );
+  }
+
+  #[test]
+  fn test_inverse_and_division() {
+    assert_eq!(modular(3, 7).inverse(), Ok(modular(5, 7)));
+    assert_eq!(modular(6, 7).checked_div(&modular(3, 7)), Ok(modular(2, 7)));
+    assert_eq!(
+      modular(4, 8).inverse(),
+      Err(ModularError::NotInvertible { value: BigInt::from(4), modulus: BigInt::from(8) }),
+    );
+  }
+
+  #[test]
+  fn test_pow() {
+    assert_eq!(modular(3, 7).pow(&BigInt::from(6)), Ok(modular(1, 7)));
+    assert_eq!(modular(2, 13).pow(&BigInt::from(10)), Ok(modular(10, 13)));
+    assert_eq!(modular(3, 7).pow(&BigInt::from(-1)), Ok(modular(5, 7)));
+    assert_eq!(modular(5, 1).pow(&BigInt::zero()), Ok(modular(0, 1)));
+    assert!(modular(2, 4).pow(&BigInt::from(-2)).is_err());
+  }
+
+  #[test]
+  fn test_unify_moduli() {
+    let values = vec![Either::Right(BigInt::from(10)), Either::Left(modular(2, 7))];
+    assert_eq!(unify_moduli(&values), Ok(Some(vec![modular(3, 7), modular(2, 7)])));
+    let values = vec![Either::Right(BigInt::from(10)), Either::Right(BigInt::from(1))];
+    assert_eq!(unify_moduli(&values), Ok(None));
+    let values = vec![Either::Left(modular(1, 5)), Either::Left(modular(2, 7))];
+    assert!(unify_moduli(&values).is_err());
+  }
+}
@@ -0,0 +1,1169 @@
+
+//! Arbitrary-precision decimal floating-point numbers.
+
+use num::{BigInt, BigRational, Zero, One, Signed, Integer, ToPrimitive};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops;
+use std::str::FromStr;
+
+/// The number of significant decimal digits needed to round-trip any
+/// `f64` value. Literals with more significant digits than this are
+/// parsed as a [`BigFloat`].
+pub const F64_PRECISION: u32 = 17;
+
+/// The largest supported precision, in significant decimal digits.
+pub const MAX_PRECISION: u32 = 1_000;
+
+/// Extra digits carried by a quotient before it is rounded to its
+/// final precision.
+const DIVISION_GUARD_DIGITS: i64 = 2;
+
+/// Extra digits carried through the elementary functions before the
+/// result is rounded to its final precision.
+const FUNCTION_GUARD_DIGITS: i64 = 10;
+
+/// The largest argument, as a power of ten, for which
+/// [`BigFloat::exp`] is computed. Anything larger would overflow the
+/// exponent of the result.
+const MAX_EXP_ARGUMENT_DIGITS: i64 = 15;
+
+/// An inexact decimal number with a configurable number of
+/// significant digits.
+///
+/// A `BigFloat` represents the value `mantissa * 10^exponent`, where
+/// the mantissa has at most `precision` decimal digits. The mantissa
+/// never has trailing zeroes, so every value has exactly one
+/// representation for a given precision. Equality and ordering
+/// compare only the value, not the precision.
+///
+/// Binary operations on two `BigFloat` values are carried out to the
+/// larger of the two precisions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BigFloat {
+  mantissa: BigInt,
+  exponent: i64,
+  precision: u32,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("Failed to parse '{input}' as a decimal number")]
+pub struct ParseBigFloatError {
+  pub input: String,
+}
+
+impl BigFloat {
+  /// The value zero, at the given precision.
+  pub fn zero(precision: u32) -> Self {
+    BigFloat { mantissa: BigInt::zero(), exponent: 0, precision: checked_precision(precision) }
+  }
+
+  /// Rounds the exact rational value to the given number of
+  /// significant digits. Ties are rounded away from zero.
+  pub fn from_rational(value: &BigRational, precision: u32) -> Self {
+    let precision = checked_precision(precision);
+    if value.is_zero() {
+      return Self::zero(precision);
+    }
+    // This initial guess is either correct or one too small.
+    let mut exponent = decimal_len(value.numer()) - decimal_len(value.denom()) - i64::from(precision);
+    loop {
+      let mantissa = scale_rational(value, - exponent).round().to_integer();
+      if decimal_len(&mantissa) <= i64::from(precision) {
+        return Self::normalized(mantissa, exponent, precision);
+      }
+      exponent += 1;
+    }
+  }
+
+  /// Converts an `f64` to a `BigFloat`. The conversion uses the
+  /// shortest decimal representation which round-trips to the same
+  /// `f64`, so that (for instance) `0.1` converts to exactly one
+  /// tenth rather than to the nearest binary fraction.
+  ///
+  /// Panics if `value` is not finite.
+  pub fn from_f64(value: f64, precision: u32) -> Self {
+    assert!(value.is_finite(), "BigFloat::from_f64({}) is not finite", value);
+    let (mantissa, exponent) = parse_decimal(&format!("{:e}", value))
+      .expect("f64 should format as a valid decimal");
+    Self::rounded(mantissa, exponent, checked_precision(precision))
+  }
+
+  /// The decimal digits of this value, such that `self` is equal to
+  /// `mantissa * 10^exponent` for some integer exponent.
+  pub fn mantissa(&self) -> &BigInt {
+    &self.mantissa
+  }
+
+  /// The power of ten by which the mantissa is scaled.
+  pub fn exponent(&self) -> i64 {
+    self.exponent
+  }
+
+  /// The number of significant digits carried by this value.
+  pub fn precision(&self) -> u32 {
+    self.precision
+  }
+
+  /// Rounds or extends this value to the given precision.
+  pub fn with_precision(&self, precision: u32) -> Self {
+    Self::rounded(self.mantissa.clone(), self.exponent, checked_precision(precision))
+  }
+
+  /// The exact rational value of `self`.
+  pub fn to_rational(&self) -> BigRational {
+    scale_rational(&BigRational::from_integer(self.mantissa.clone()), self.exponent)
+  }
+
+  /// The nearest `f64` to `self`. Values too large for an `f64`
+  /// become infinite.
+  pub fn to_f64(&self) -> f64 {
+    format!("{}e{}", self.mantissa, self.exponent).parse().expect("BigFloat should format as a valid f64")
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.mantissa.is_zero()
+  }
+
+  pub fn is_one(&self) -> bool {
+    self.mantissa.is_one() && self.exponent == 0
+  }
+
+  pub fn is_negative(&self) -> bool {
+    self.mantissa.is_negative()
+  }
+
+  /// The square root of `self`, to the precision of `self`.
+  ///
+  /// Panics if `self` is negative.
+  pub fn sqrt(&self) -> Self {
+    assert!(!self.is_negative(), "Argument to BigFloat::sqrt should be nonnegative, got {}", self);
+    if self.is_zero() {
+      return self.clone();
+    }
+    // Scale the mantissa so that it has enough digits for the integer
+    // square root to carry a few more digits than we need, and so
+    // that the remaining power of ten is even.
+    let wanted_len = 2 * i64::from(self.precision) + 4;
+    let mut shift = (wanted_len - decimal_len(&self.mantissa)).max(0);
+    if (self.exponent - shift) % 2 != 0 {
+      shift += 1;
+    }
+    let root = (&self.mantissa * pow10(shift)).sqrt();
+    let root = BigRational::from_integer(root);
+    Self::from_rational(&scale_rational(&root, (self.exponent - shift) / 2), self.precision)
+  }
+
+  /// Raises `self` to a nonnegative integer power, by repeated
+  /// squaring. A few guard digits are carried through the
+  /// intermediate products.
+  pub fn powi(&self, exp: &BigInt) -> Self {
+    assert!(!exp.is_negative(), "Exponent to BigFloat::powi should be nonnegative, got {}", exp);
+    let working_precision = (self.precision + 5).min(MAX_PRECISION);
+    let mut result = Self::rounded(BigInt::one(), 0, working_precision);
+    let mut base = self.with_precision(working_precision);
+    let mut exp = exp.clone();
+    let two = BigInt::from(2);
+    while !exp.is_zero() {
+      if (&exp % &two).is_one() {
+        result = result.mul_to_precision(&base, working_precision);
+      }
+      exp /= &two;
+      if !exp.is_zero() {
+        base = base.mul_to_precision(&base, working_precision);
+      }
+    }
+    result.with_precision(self.precision)
+  }
+
+  fn mul_to_precision(&self, other: &Self, precision: u32) -> Self {
+    Self::rounded(&self.mantissa * &other.mantissa, self.exponent + other.exponent, precision)
+  }
+
+  fn from_integer(n: impl Into<BigInt>, precision: u32) -> Self {
+    Self::rounded(n.into(), 0, precision)
+  }
+
+  fn one(precision: u32) -> Self {
+    Self::from_integer(1, precision)
+  }
+
+  /// As [`BigFloat::with_precision`], for the internal working
+  /// precisions, which have already been checked.
+  fn at_precision(&self, precision: u32) -> Self {
+    Self::rounded(self.mantissa.clone(), self.exponent, precision)
+  }
+
+  fn abs(&self) -> Self {
+    BigFloat { mantissa: self.mantissa.abs(), exponent: self.exponent, precision: self.precision }
+  }
+
+  /// Whether `self` is too small to affect a sum whose leading digit
+  /// is at `reference_top`, at the precision of `self`.
+  fn is_negligible(&self, reference_top: i64) -> bool {
+    self.is_zero() || self.top() < reference_top - i64::from(self.precision) - 2
+  }
+
+  /// The nearest integer to `self`. Ties are rounded away from zero.
+  fn round_to_integer(&self) -> BigInt {
+    if self.is_zero() || self.top() < 0 {
+      return BigInt::zero();
+    }
+    if self.exponent >= 0 {
+      return &self.mantissa * pow10(self.exponent);
+    }
+    let divisor = pow10(- self.exponent);
+    let magnitude = self.mantissa.abs();
+    let mut quotient = &magnitude / &divisor;
+    if (&magnitude % &divisor) * 2 >= divisor {
+      quotient += 1;
+    }
+    if self.is_negative() { - quotient } else { quotient }
+  }
+
+  /// The position just past the leading digit of `self`, so that
+  /// `10^(top-1) <= |self| < 10^top`. Must not be called on zero.
+  fn top(&self) -> i64 {
+    self.exponent + decimal_len(&self.mantissa)
+  }
+
+  /// Rounds the decimal value `mantissa * 10^exponent` to the given
+  /// number of significant digits. Ties are rounded away from zero.
+  fn rounded(mantissa: BigInt, exponent: i64, precision: u32) -> Self {
+    let excess = decimal_len(&mantissa) - i64::from(precision);
+    if excess <= 0 {
+      return Self::normalized(mantissa, exponent, precision);
+    }
+    let divisor = pow10(excess);
+    let magnitude = mantissa.abs();
+    let mut quotient = &magnitude / &divisor;
+    if (&magnitude % &divisor) * 2 >= divisor {
+      quotient += 1;
+    }
+    if mantissa.is_negative() {
+      quotient = - quotient;
+    }
+    // If rounding carried into a new digit, the quotient is a power
+    // of ten, so normalizing brings it back within the precision.
+    Self::normalized(quotient, exponent + excess, precision)
+  }
+
+  /// Strips trailing zeroes from the mantissa.
+  fn normalized(mut mantissa: BigInt, mut exponent: i64, precision: u32) -> Self {
+    if mantissa.is_zero() {
+      return Self::zero(precision);
+    }
+    let ten = BigInt::from(10);
+    while (&mantissa % &ten).is_zero() {
+      mantissa /= &ten;
+      exponent += 1;
+    }
+    BigFloat { mantissa, exponent, precision }
+  }
+}
+
+/// The elementary functions.
+///
+/// Each function is computed to the precision of its argument (or the
+/// larger precision, for functions of two arguments), carrying
+/// [`FUNCTION_GUARD_DIGITS`] extra digits internally, plus more where
+/// the computation is known to cancel digits. As with
+/// [`BigFloat::powi`], the working precision never exceeds
+/// [`MAX_PRECISION`], so the last few digits of a result at the
+/// maximum precision may be inexact.
+impl BigFloat {
+  /// The value of pi, to the given precision.
+  pub fn pi(precision: u32) -> Self {
+    let precision = checked_precision(precision);
+    pi(working_precision(precision, 0)).at_precision(precision)
+  }
+
+  /// `e^self`. Returns `None` if `|self| >= 10^15`, since the result
+  /// would be too large (or small) to represent.
+  pub fn exp(&self) -> Option<Self> {
+    if self.is_zero() {
+      return Some(Self::one(self.precision));
+    }
+    if self.top() > MAX_EXP_ARGUMENT_DIGITS {
+      return None;
+    }
+    // The relative error of the result is the absolute error of the
+    // reduced argument, so carry the digits before the decimal point
+    // as well.
+    let precision = working_precision(self.precision, self.top().max(0));
+    let x = self.at_precision(precision);
+    // Write x = k ln(10) + r, so that e^x = 10^k e^r with |r| small.
+    let ln10 = ln10(precision);
+    let k = (x.clone() / ln10.clone()).round_to_integer();
+    let r = x - ln10 * Self::from_integer(k.clone(), precision);
+    let mut result = exp_near_zero(r);
+    result.exponent += k.to_i64().expect("Reduced exponent should fit in an i64");
+    Some(result.at_precision(self.precision))
+  }
+
+  /// The natural logarithm of `self`. Returns `None` if `self` is not
+  /// positive.
+  pub fn ln(&self) -> Option<Self> {
+    if self.is_zero() || self.is_negative() {
+      return None;
+    }
+    let precision = working_precision(self.precision, 0);
+    // Write self = f 10^t, with 1/sqrt(10) <= f < sqrt(10). Choosing
+    // f as close to one as possible means that no digits cancel when
+    // the two logarithms are added back together.
+    let mut t = self.top() - 1;
+    let mut f = BigFloat { mantissa: self.mantissa.clone(), exponent: self.exponent - t, precision };
+    if f.clone() * f.clone() >= Self::from_integer(10, precision) {
+      f.exponent -= 1;
+      t += 1;
+    }
+    let mut result = ln_near_one(f);
+    if t != 0 {
+      result = result + ln10(precision) * Self::from_integer(t, precision);
+    }
+    Some(result.at_precision(self.precision))
+  }
+
+  /// `self^exponent`. Returns `None` if `self` is not positive, or if
+  /// the result is too large (or small) to represent.
+  pub fn powf(&self, exponent: &Self) -> Option<Self> {
+    let precision = self.precision.max(exponent.precision);
+    if exponent.is_zero() {
+      return Some(Self::one(precision));
+    }
+    let mut extra = 0;
+    loop {
+      let working = working_precision(precision, extra);
+      let log = self.at_precision(working).ln()? * exponent.at_precision(working);
+      // As in `exp`, the digits of the logarithm before the decimal
+      // point must be carried as well.
+      if !log.is_zero() && log.top() > extra && working < MAX_PRECISION {
+        extra = log.top();
+        continue;
+      }
+      return log.exp().map(|result| result.at_precision(precision));
+    }
+  }
+
+  /// The sine of `self`, in radians. Returns `None` if `self` is too
+  /// large to reduce modulo pi.
+  pub fn sin(&self) -> Option<Self> {
+    self.sin_cos().map(|(sin, _)| sin)
+  }
+
+  /// The cosine of `self`, in radians. Returns `None` if `self` is
+  /// too large to reduce modulo pi.
+  pub fn cos(&self) -> Option<Self> {
+    self.sin_cos().map(|(_, cos)| cos)
+  }
+
+  /// The tangent of `self`, in radians. Returns `None` if `self` is
+  /// too large to reduce modulo pi.
+  pub fn tan(&self) -> Option<Self> {
+    let precision = working_precision(self.precision, 0);
+    let (sin, cos) = self.at_precision(precision).sin_cos()?;
+    Some((sin / cos).at_precision(self.precision))
+  }
+
+  fn sin_cos(&self) -> Option<(Self, Self)> {
+    if self.is_zero() {
+      return Some((self.clone(), Self::one(self.precision)));
+    }
+    if self.top() > i64::from(MAX_PRECISION / 2) {
+      return None;
+    }
+    let mut extra = self.top().max(0);
+    loop {
+      let precision = working_precision(self.precision, extra);
+      let x = self.at_precision(precision);
+      // Write x = q (pi / 2) + r, with |r| <= pi / 4.
+      let half_pi = pi(precision) / Self::from_integer(2, precision);
+      let quadrant = (x.clone() / half_pi.clone()).round_to_integer();
+      let r = x - half_pi * Self::from_integer(quadrant.clone(), precision);
+      // If x is close to a multiple of pi / 2, the reduction cancels
+      // digits, so try again with enough digits to make up for it.
+      let needed = self.top().max(0) - r.top().min(0);
+      if !quadrant.is_zero() && !r.is_zero() && needed > extra && precision < MAX_PRECISION {
+        extra = needed;
+        continue;
+      }
+      let (sin, cos) = sin_cos_near_zero(r);
+      let (sin, cos) = match quadrant.mod_floor(&BigInt::from(4)).to_u8() {
+        Some(0) => (sin, cos),
+        Some(1) => (cos, - sin),
+        Some(2) => (- sin, - cos),
+        _ => (- cos, sin),
+      };
+      return Some((sin.at_precision(self.precision), cos.at_precision(self.precision)));
+    }
+  }
+
+  /// The arcsine of `self`, in radians. Returns `None` if `|self| > 1`.
+  pub fn asin(&self) -> Option<Self> {
+    let precision = working_precision(self.precision, 0);
+    let one = Self::one(precision);
+    let x = self.at_precision(precision);
+    if x.abs() > one {
+      return None;
+    }
+    let result = if x.abs() == one {
+      let half_pi = pi(precision) / Self::from_integer(2, precision);
+      if x.is_negative() { - half_pi } else { half_pi }
+    } else {
+      // asin(x) = atan(x / sqrt(1 - x^2)). Factoring 1 - x^2 avoids
+      // cancellation when x is close to 1.
+      let cos = ((one.clone() - x.clone()) * (one + x.clone())).sqrt();
+      atan(x / cos)
+    };
+    Some(result.at_precision(self.precision))
+  }
+
+  /// The arccosine of `self`, in radians. Returns `None` if
+  /// `|self| > 1`.
+  pub fn acos(&self) -> Option<Self> {
+    let precision = working_precision(self.precision, 0);
+    let one = Self::one(precision);
+    let x = self.at_precision(precision);
+    if x.abs() > one {
+      return None;
+    }
+    let result = if x == - one.clone() {
+      pi(precision)
+    } else {
+      // acos(x) = 2 atan(sqrt((1 - x) / (1 + x))), which, unlike
+      // pi / 2 - asin(x), does not cancel digits when x is close to 1.
+      let half_angle = atan(((one.clone() - x.clone()) / (one + x)).sqrt());
+      half_angle * Self::from_integer(2, precision)
+    };
+    Some(result.at_precision(self.precision))
+  }
+
+  /// The arctangent of `self`, in radians.
+  pub fn atan(&self) -> Self {
+    if self.is_zero() {
+      return self.clone();
+    }
+    atan(self.at_precision(working_precision(self.precision, 0))).at_precision(self.precision)
+  }
+
+  /// The angle, in radians, of the point `(other, self)`, in the
+  /// range `(-pi, pi]`. As [`f64::atan2`], this is zero if both
+  /// arguments are zero.
+  pub fn atan2(&self, other: &Self) -> Self {
+    let precision = self.precision.max(other.precision);
+    let working = working_precision(precision, 0);
+    let (y, x) = (self.at_precision(working), other.at_precision(working));
+    let result = if x.is_zero() {
+      if y.is_zero() {
+        return Self::zero(precision);
+      }
+      let half_pi = pi(working) / Self::from_integer(2, working);
+      if y.is_negative() { - half_pi } else { half_pi }
+    } else {
+      let angle = atan(y.clone() / x.clone());
+      if !x.is_negative() {
+        angle
+      } else if y.is_negative() {
+        angle - pi(working)
+      } else {
+        angle + pi(working)
+      }
+    };
+    result.at_precision(precision)
+  }
+
+  /// The hyperbolic sine of `self`. Returns `None` if the result is
+  /// too large to represent.
+  pub fn sinh(&self) -> Option<Self> {
+    if self.is_nearly_odd_identity() {
+      return Some(self.clone());
+    }
+    // e^x - e^-x cancels as many digits as x has leading zeroes.
+    let precision = working_precision(self.precision, (- self.top()).max(0));
+    let exp = self.at_precision(precision).exp()?;
+    let result = (exp.clone() - Self::one(precision) / exp) / Self::from_integer(2, precision);
+    Some(result.at_precision(self.precision))
+  }
+
+  /// The hyperbolic cosine of `self`. Returns `None` if the result is
+  /// too large to represent.
+  pub fn cosh(&self) -> Option<Self> {
+    let precision = working_precision(self.precision, 0);
+    let exp = self.at_precision(precision).exp()?;
+    let result = (exp.clone() + Self::one(precision) / exp) / Self::from_integer(2, precision);
+    Some(result.at_precision(self.precision))
+  }
+
+  /// The hyperbolic tangent of `self`.
+  pub fn tanh(&self) -> Self {
+    if self.is_nearly_odd_identity() {
+      return self.clone();
+    }
+    let precision = working_precision(self.precision, (- self.top()).max(0));
+    let x = self.at_precision(precision);
+    match (x.sinh(), x.cosh()) {
+      (Some(sinh), Some(cosh)) => (sinh / cosh).at_precision(self.precision),
+      // |x| is enormous, so the result is 1 to any precision.
+      _ => {
+        let one = Self::one(self.precision);
+        if self.is_negative() { - one } else { one }
+      }
+    }
+  }
+
+  /// The inverse hyperbolic sine of `self`.
+  pub fn asinh(&self) -> Self {
+    if self.is_nearly_odd_identity() {
+      return self.clone();
+    }
+    // The logarithm's argument is close to 1 when x is small, so
+    // carry extra digits to make up for it.
+    let precision = working_precision(self.precision, (- self.top()).max(0));
+    let x = self.at_precision(precision).abs();
+    let hypotenuse = (x.clone() * x.clone() + Self::one(precision)).sqrt();
+    let result = (x + hypotenuse).ln().expect("Argument to ln should be positive");
+    let result = if self.is_negative() { - result } else { result };
+    result.at_precision(self.precision)
+  }
+
+  /// The inverse hyperbolic cosine of `self`. Returns `None` if
+  /// `self < 1`.
+  pub fn acosh(&self) -> Option<Self> {
+    let one = Self::one(self.precision);
+    if self < &one {
+      return None;
+    }
+    if self == &one {
+      return Some(Self::zero(self.precision));
+    }
+    let distance = self.clone() - one;
+    let precision = working_precision(self.precision, (- distance.top()).max(0));
+    let x = self.at_precision(precision);
+    let one = Self::one(precision);
+    let leg = ((x.clone() - one.clone()) * (x.clone() + one)).sqrt();
+    let result = (x + leg).ln().expect("Argument to ln should be positive");
+    Some(result.at_precision(self.precision))
+  }
+
+  /// The inverse hyperbolic tangent of `self`. Returns `None` if
+  /// `|self| >= 1`.
+  pub fn atanh(&self) -> Option<Self> {
+    if self.abs() >= Self::one(self.precision) {
+      return None;
+    }
+    if self.is_nearly_odd_identity() {
+      return Some(self.clone());
+    }
+    let precision = working_precision(self.precision, (- self.top()).max(0));
+    let x = self.at_precision(precision);
+    let one = Self::one(precision);
+    let ratio = (one.clone() + x.clone()) / (one - x);
+    let result = ratio.ln().expect("Argument to ln should be positive") / Self::from_integer(2, precision);
+    Some(result.at_precision(self.precision))
+  }
+
+  /// Whether `self` is so small that an odd function which is the
+  /// identity to first order (such as sinh or atanh) returns `self`
+  /// at this precision.
+  fn is_nearly_odd_identity(&self) -> bool {
+    // The next term of each such series is on the order of x^3.
+    self.is_zero() || self.top() < - i64::from(self.precision) / 2 - 1
+  }
+}
+
+/// The precision at which to compute a function whose result is
+/// wanted to `precision` digits, given that the computation is known
+/// to cancel `extra` digits.
+fn working_precision(precision: u32, extra: i64) -> u32 {
+  let working = i64::from(precision) + FUNCTION_GUARD_DIGITS + extra;
+  u32::try_from(working.min(i64::from(MAX_PRECISION))).expect("Working precision should fit in a u32")
+}
+
+/// Pi, to the precision given, by Machin's formula.
+fn pi(precision: u32) -> BigFloat {
+  let fifth = BigFloat::one(precision) / BigFloat::from_integer(5, precision);
+  let two_hundred_thirty_ninth = BigFloat::one(precision) / BigFloat::from_integer(239, precision);
+  arctan_series(fifth, true) * BigFloat::from_integer(16, precision)
+    - arctan_series(two_hundred_thirty_ninth, true) * BigFloat::from_integer(4, precision)
+}
+
+/// The natural logarithm of ten, to the precision given.
+fn ln10(precision: u32) -> BigFloat {
+  // ln(x) = 2 atanh((x - 1) / (x + 1)), and 10 = 2^3 (5 / 4).
+  let two = BigFloat::from_integer(2, precision);
+  let third = BigFloat::one(precision) / BigFloat::from_integer(3, precision);
+  let ninth = BigFloat::one(precision) / BigFloat::from_integer(9, precision);
+  let ln2 = arctan_series(third, false) * two.clone();
+  let ln_five_fourths = arctan_series(ninth, false) * two;
+  ln2 * BigFloat::from_integer(3, precision) + ln_five_fourths
+}
+
+/// The sum `y + s y^3 / 3 + y^5 / 5 + s y^7 / 7 + ...`, where `s` is
+/// -1 if `alternating` (giving the arctangent of `y`) and 1 otherwise
+/// (giving the inverse hyperbolic tangent). Converges quickly for
+/// small `|y|`. Computed to the precision of `y`.
+fn arctan_series(y: BigFloat, alternating: bool) -> BigFloat {
+  if y.is_zero() {
+    return y;
+  }
+  let precision = y.precision;
+  let reference_top = y.top();
+  let y_squared = y.clone() * y.clone();
+  let mut power = y.clone();
+  let mut sum = y;
+  let mut n = 1;
+  loop {
+    power = power * y_squared.clone();
+    n += 2;
+    if power.is_negligible(reference_top) {
+      return sum;
+    }
+    let term = power.clone() / BigFloat::from_integer(n, precision);
+    sum = if alternating && n % 4 == 3 { sum - term } else { sum + term };
+  }
+}
+
+/// `e^r`, for `|r|` no larger than about one, to the precision of
+/// `r`.
+fn exp_near_zero(r: BigFloat) -> BigFloat {
+  // Sum the series for e^(r / 2^k) and square the result k times.
+  // The squaring loses a few digits, which the guard digits cover.
+  const HALVINGS: u32 = 8;
+  let precision = r.precision;
+  let r = r / BigFloat::from_integer(1 << HALVINGS, precision);
+  let mut sum = BigFloat::one(precision);
+  let mut term = BigFloat::one(precision);
+  let mut n = 1;
+  loop {
+    term = term * r.clone() / BigFloat::from_integer(n, precision);
+    if term.is_negligible(0) {
+      break;
+    }
+    sum = sum + term.clone();
+    n += 1;
+  }
+  for _ in 0..HALVINGS {
+    sum = sum.clone() * sum;
+  }
+  sum
+}
+
+/// `ln(f)`, for `f` within a factor of `sqrt(10)` of one, to the
+/// precision of `f`.
+fn ln_near_one(f: BigFloat) -> BigFloat {
+  let precision = f.precision;
+  let one = BigFloat::one(precision);
+  let lower = BigFloat::from_rational(&BigRational::new(9.into(), 10.into()), precision);
+  let upper = BigFloat::from_rational(&BigRational::new(11.into(), 10.into()), precision);
+  // ln(f) = 2^k ln(f^(1 / 2^k)). Taking roots brings f closer to one,
+  // where the series converges quickly. This is only done when f is
+  // not already close to one, since f - 1 would cancel digits.
+  let mut f = f;
+  let mut roots = 0;
+  while f < lower || f > upper {
+    f = f.sqrt();
+    roots += 1;
+  }
+  let y = (f.clone() - one.clone()) / (f + one);
+  arctan_series(y, false) * BigFloat::from_integer(2 << roots, precision)
+}
+
+/// `(sin r, cos r)`, for `|r| <= pi / 4`, to the precision of `r`.
+fn sin_cos_near_zero(r: BigFloat) -> (BigFloat, BigFloat) {
+  let precision = r.precision;
+  let one = BigFloat::one(precision);
+  if r.is_zero() {
+    return (r, one);
+  }
+  let reference_top = r.top();
+  let mut sin = BigFloat::zero(precision);
+  let mut cos = BigFloat::zero(precision);
+  // term = r^n / n!
+  let mut term = one;
+  let mut n: u32 = 0;
+  loop {
+    let signed_term = if (n / 2).is_multiple_of(2) { term.clone() } else { - term.clone() };
+    if n.is_multiple_of(2) {
+      cos = cos + signed_term;
+    } else {
+      sin = sin + signed_term;
+    }
+    n += 1;
+    term = term * r.clone() / BigFloat::from_integer(n, precision);
+    if term.is_negligible(reference_top) {
+      return (sin, cos);
+    }
+  }
+}
+
+/// The arctangent of `x`, to the precision of `x`.
+fn atan(x: BigFloat) -> BigFloat {
+  let precision = x.precision;
+  let one = BigFloat::one(precision);
+  let magnitude = x.abs();
+  let result = if magnitude > one {
+    // atan(x) = pi / 2 - atan(1 / x) for positive x.
+    let half_pi = pi(precision) / BigFloat::from_integer(2, precision);
+    half_pi - atan_at_most_one(one / magnitude)
+  } else {
+    atan_at_most_one(magnitude)
+  };
+  if x.is_negative() { - result } else { result }
+}
+
+/// The arctangent of `0 <= a <= 1`, to the precision of `a`.
+fn atan_at_most_one(a: BigFloat) -> BigFloat {
+  let precision = a.precision;
+  let one = BigFloat::one(precision);
+  let tenth = BigFloat::from_rational(&BigRational::new(1.into(), 10.into()), precision);
+  // atan(a) = 2 atan(a / (1 + sqrt(1 + a^2))). Halving the angle a
+  // few times makes the series converge quickly.
+  let mut a = a;
+  let mut halvings = 0;
+  while a > tenth {
+    let hypotenuse = (one.clone() + a.clone() * a.clone()).sqrt();
+    a = a / (one.clone() + hypotenuse);
+    halvings += 1;
+  }
+  arctan_series(a, true) * BigFloat::from_integer(1 << halvings, precision)
+}
+
+fn checked_precision(precision: u32) -> u32 {
+  assert!((1..=MAX_PRECISION).contains(&precision), "Invalid BigFloat precision {}", precision);
+  precision
+}
+
+fn pow10(exp: i64) -> BigInt {
+  let exp = usize::try_from(exp).expect("Exponent to pow10 should be nonnegative");
+  num::pow(BigInt::from(10), exp)
+}
+
+/// The number of decimal digits in `n`, ignoring sign. Zero has one
+/// digit.
+fn decimal_len(n: &BigInt) -> i64 {
+  n.magnitude().to_str_radix(10).len() as i64
+}
+
+/// Multiplies `value` by `10^exp`.
+fn scale_rational(value: &BigRational, exp: i64) -> BigRational {
+  if exp >= 0 {
+    value * BigRational::from_integer(pow10(exp))
+  } else {
+    value / BigRational::from_integer(pow10(- exp))
+  }
+}
+
+/// Parses a decimal string of the form `-123.456e-7` into a mantissa
+/// and a power of ten. The sign, fractional part, and exponent are
+/// all optional.
+fn parse_decimal(s: &str) -> Option<(BigInt, i64)> {
+  let (body, exponent) = match s.find(['e', 'E']) {
+    None => (s, 0),
+    Some(index) => (&s[..index], s[index+1..].parse::<i64>().ok()?),
+  };
+  let (sign, body) = match body.strip_prefix('-') {
+    Some(body) => (-1, body),
+    None => (1, body.strip_prefix('+').unwrap_or(body)),
+  };
+  let (integral, fractional) = body.split_once('.').unwrap_or((body, ""));
+  if integral.is_empty() && fractional.is_empty() {
+    return None;
+  }
+  if !integral.chars().chain(fractional.chars()).all(|c| c.is_ascii_digit()) {
+    return None;
+  }
+  let mantissa = BigInt::from_str(&format!("{integral}{fractional}")).ok()?;
+  let exponent = exponent.checked_sub(i64::try_from(fractional.len()).ok()?)?;
+  Some((mantissa * sign, exponent))
+}
+
+/// Counts the significant digits in a decimal string, as accepted by
+/// [`parse_decimal`].
+fn significant_digits(s: &str) -> usize {
+  let body = s.split(['e', 'E']).next().unwrap_or("");
+  body.chars()
+    .filter(|c| c.is_ascii_digit())
+    .skip_while(|c| *c == '0')
+    .count()
+}
+
+impl FromStr for BigFloat {
+  type Err = ParseBigFloatError;
+
+  /// Parses a decimal string, keeping every significant digit that
+  /// was written. The precision of the result is the number of
+  /// significant digits in the input.
+  fn from_str(s: &str) -> Result<Self, ParseBigFloatError> {
+    let err = || ParseBigFloatError { input: s.to_owned() };
+    let (mantissa, exponent) = parse_decimal(s).ok_or_else(err)?;
+    let precision = u32::try_from(significant_digits(s)).unwrap_or(u32::MAX).clamp(1, MAX_PRECISION);
+    Ok(Self::rounded(mantissa, exponent, precision))
+  }
+}
+
+impl Display for BigFloat {
+  /// Displays the number in positional notation if it is of a
+  /// reasonable size, or in scientific notation otherwise. In either
+  /// case, the output can be read back by [`BigFloat::from_str`]
+  /// without loss.
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if self.is_negative() {
+      write!(f, "-")?;
+    }
+    let digits = self.mantissa.magnitude().to_str_radix(10);
+    let len = digits.len() as i64;
+    // The position of the decimal point, relative to the start of
+    // `digits`.
+    let point = self.exponent + len;
+    if point - 1 < -6 || point - 1 > 20 {
+      let fraction = if len > 1 { &digits[1..] } else { "0" };
+      write!(f, "{}.{}e{}", &digits[..1], fraction, point - 1)
+    } else if point <= 0 {
+      write!(f, "0.{}{}", "0".repeat((- point) as usize), digits)
+    } else if point < len {
+      let point = point as usize;
+      write!(f, "{}.{}", &digits[..point], &digits[point..])
+    } else {
+      write!(f, "{}{}.0", digits, "0".repeat((point - len) as usize))
+    }
+  }
+}
+
+impl PartialEq for BigFloat {
+  fn eq(&self, other: &BigFloat) -> bool {
+    self.mantissa == other.mantissa && self.exponent == other.exponent
+  }
+}
+
+impl Eq for BigFloat {}
+
+impl Hash for BigFloat {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.mantissa.hash(state);
+    self.exponent.hash(state);
+  }
+}
+
+impl PartialOrd for BigFloat {
+  fn partial_cmp(&self, other: &BigFloat) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for BigFloat {
+  fn cmp(&self, other: &BigFloat) -> Ordering {
+    let sign_order = self.mantissa.sign().cmp(&other.mantissa.sign());
+    if sign_order != Ordering::Equal || self.is_zero() {
+      return sign_order;
+    }
+    let magnitude_order = match self.top().cmp(&other.top()) {
+      Ordering::Equal => {
+        // The values have the same leading position, so aligning the
+        // mantissas only costs as many digits as the precision.
+        let exponent = self.exponent.min(other.exponent);
+        let left = self.mantissa.magnitude() * pow10(self.exponent - exponent).magnitude();
+        let right = other.mantissa.magnitude() * pow10(other.exponent - exponent).magnitude();
+        left.cmp(&right)
+      }
+      order => order,
+    };
+    if self.is_negative() { magnitude_order.reverse() } else { magnitude_order }
+  }
+}
+
+impl ops::Add for BigFloat {
+  type Output = BigFloat;
+
+  fn add(self, other: BigFloat) -> BigFloat {
+    let precision = self.precision.max(other.precision);
+    if self.is_zero() {
+      return other.with_precision(precision);
+    }
+    if other.is_zero() {
+      return self.with_precision(precision);
+    }
+    // If one value is too small to affect the rounded result, skip
+    // the (potentially enormous) alignment below.
+    let gap = i64::from(precision) + 2;
+    if self.top() - other.top() > gap {
+      return self.with_precision(precision);
+    }
+    if other.top() - self.top() > gap {
+      return other.with_precision(precision);
+    }
+    let exponent = self.exponent.min(other.exponent);
+    let mantissa = self.mantissa * pow10(self.exponent - exponent) + other.mantissa * pow10(other.exponent - exponent);
+    BigFloat::rounded(mantissa, exponent, precision)
+  }
+}
+
+impl ops::Sub for BigFloat {
+  type Output = BigFloat;
+
+  fn sub(self, other: BigFloat) -> BigFloat {
+    self + (- other)
+  }
+}
+
+impl ops::Mul for BigFloat {
+  type Output = BigFloat;
+
+  fn mul(self, other: BigFloat) -> BigFloat {
+    let precision = self.precision.max(other.precision);
+    self.mul_to_precision(&other, precision)
+  }
+}
+
+/// Panics if `other` is zero.
+impl ops::Div for BigFloat {
+  type Output = BigFloat;
+
+  fn div(self, other: BigFloat) -> BigFloat {
+    assert!(!other.is_zero(), "Division of BigFloat by zero");
+    let precision = self.precision.max(other.precision);
+    if self.is_zero() {
+      return BigFloat::zero(precision);
+    }
+    // Scale the dividend's mantissa so that the integer quotient has
+    // a few digits beyond the precision. The quotient is truncated,
+    // but a truncated digit can only matter for a tie, and the guard
+    // digits rule out a false tie.
+    let shift = (i64::from(precision) + DIVISION_GUARD_DIGITS + decimal_len(&other.mantissa) - decimal_len(&self.mantissa)).max(0);
+    let quotient = self.mantissa * pow10(shift) / other.mantissa;
+    BigFloat::rounded(quotient, self.exponent - other.exponent - shift, precision)
+  }
+}
+
+/// Truncated remainder, matching the behavior of `%` on the primitive
+/// floating-point types. Panics if `other` is zero.
+impl ops::Rem for BigFloat {
+  type Output = BigFloat;
+
+  fn rem(self, other: BigFloat) -> BigFloat {
+    assert!(!other.is_zero(), "Remainder of BigFloat by zero");
+    let precision = self.precision.max(other.precision);
+    if self.is_zero() || self.top() < other.top() {
+      // |self| < |other|, so the remainder is self.
+      return self.with_precision(precision);
+    }
+    let divisor = other.mantissa.abs();
+    let remainder = if self.exponent >= other.exponent {
+      // Working in units of 10^(other.exponent), the dividend is
+      // self.mantissa * 10^(self.exponent - other.exponent), which
+      // may be enormous, so reduce the power of ten modulo the
+      // divisor instead of computing it.
+      let scale = BigInt::from(10).modpow(&BigInt::from(self.exponent - other.exponent), &divisor);
+      (self.mantissa.abs() * scale) % &divisor
+    } else {
+      // |self| >= |other|, so the exponents differ by no more than
+      // the length of the mantissa and the scaling is cheap.
+      self.mantissa.abs() % (divisor * pow10(other.exponent - self.exponent))
+    };
+    let remainder = if self.is_negative() { - remainder } else { remainder };
+    BigFloat::rounded(remainder, self.exponent.min(other.exponent), precision)
+  }
+}
+
+impl ops::Neg for BigFloat {
+  type Output = BigFloat;
+
+  fn neg(self) -> BigFloat {
+    BigFloat { mantissa: - self.mantissa, exponent: self.exponent, precision: self.precision }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn big(s: &str, precision: u32) -> BigFloat {
+    BigFloat::from_str(s).unwrap().with_precision(precision)
+  }
+
+  #[test]
+  fn test_from_rational() {
+    let third = BigFloat::from_rational(&BigRational::new(1.into(), 3.into()), 20);
+    assert_eq!(third.to_string(), "0.33333333333333333333");
+    let two_thirds = BigFloat::from_rational(&BigRational::new((-2).into(), 3.into()), 5);
+    assert_eq!(two_thirds.to_string(), "-0.66667");
+    let big_integer = BigFloat::from_rational(&BigRational::from_integer(123_456.into()), 3);
+    assert_eq!(big_integer.to_string(), "123000.0");
+  }
+
+  #[test]
+  fn test_from_rational_rounding_carry() {
+    let value = BigFloat::from_rational(&BigRational::new(9_999.into(), 1_000.into()), 3);
+    assert_eq!(value.to_string(), "10.0");
+  }
+
+  #[test]
+  fn test_from_f64() {
+    assert_eq!(BigFloat::from_f64(0.1, 30).to_string(), "0.1");
+    assert_eq!(BigFloat::from_f64(-2.5, 30).to_string(), "-2.5");
+    assert_eq!(BigFloat::from_f64(1234.5678, 5).to_string(), "1234.6");
+    assert!(BigFloat::from_f64(0.0, 30).is_zero());
+  }
+
+  #[test]
+  fn test_parse_and_display() {
+    let value = BigFloat::from_str("3.14159265358979323846264338327950288").unwrap();
+    assert_eq!(value.precision(), 36);
+    assert_eq!(value.to_string(), "3.14159265358979323846264338327950288");
+    let value = BigFloat::from_str("-0.000120").unwrap();
+    assert_eq!(value.precision(), 3);
+    assert_eq!(value.to_string(), "-0.00012");
+    let value = BigFloat::from_str("1.5e40").unwrap();
+    assert_eq!(value.to_string(), "1.5e40");
+    let value = BigFloat::from_str("2e-30").unwrap();
+    assert_eq!(value.to_string(), "2.0e-30");
+    assert_eq!(BigFloat::from_str("2e-30").unwrap(), BigFloat::from_str(&value.to_string()).unwrap());
+    assert!(BigFloat::from_str("1.2.3").is_err());
+    assert!(BigFloat::from_str("e5").is_err());
+  }
+
+  #[test]
+  fn test_arithmetic() {
+    assert_eq!((big("1", 25) / big("3", 25)).to_string(), "0.3333333333333333333333333");
+    assert_eq!((big("0.1", 25) + big("0.2", 25)).to_string(), "0.3");
+    assert_eq!((big("1.5", 10) * big("-4", 10)).to_string(), "-6.0");
+    assert_eq!((big("1", 10) - big("1e-20", 10)).to_string(), "1.0");
+    assert_eq!((big("1", 30) - big("1e-20", 30)).to_string(), "0.99999999999999999999");
+    assert_eq!((big("7.5", 10) % big("2", 10)).to_string(), "1.5");
+    assert_eq!((big("-7.5", 10) % big("2", 10)).to_string(), "-1.5");
+    assert_eq!((big("7.5", 10) % big("-2", 10)).to_string(), "1.5");
+    assert_eq!((big("1.5", 10) % big("20", 10)).to_string(), "1.5");
+    assert_eq!((big("-2", 10) / big("3", 10)).to_string(), "-0.6666666667");
+    assert_eq!((big("1", 10) / big("8", 10)).to_string(), "0.125");
+  }
+
+  #[test]
+  fn test_division_with_distant_exponents() {
+    // These would be far too slow if either operand's power of ten
+    // were ever written out in full.
+    let huge = big("1e1000000000", 10);
+    assert_eq!((huge.clone() / big("3", 10)).to_string(), "3.333333333e999999999");
+    assert_eq!((big("3", 10) / huge.clone()).to_string(), "3.0e-1000000000");
+    assert_eq!((huge % big("7", 10)).to_string(), "4.0");
+    assert_eq!((big("1e1000000000", 10) % big("2.5e999999999", 10)).to_string(), "0.0");
+  }
+
+  #[test]
+  fn test_mixed_precision_uses_larger() {
+    let value = big("1", 5) / big("3", 30);
+    assert_eq!(value.precision(), 30);
+  }
+
+  #[test]
+  fn test_ordering() {
+    assert!(big("1.5", 10) < big("2", 10));
+    assert!(big("-1.5", 10) > big("-2", 10));
+    assert!(big("-1", 10) < big("0", 10));
+    assert!(big("1e-50", 10) > big("0", 10));
+    assert!(big("123.4", 10) > big("123.39999", 10));
+    assert_eq!(big("2.50", 10), big("2.5", 30));
+  }
+
+  #[test]
+  fn test_sqrt() {
+    assert_eq!(big("2", 30).sqrt().to_string(), "1.41421356237309504880168872421");
+    assert_eq!(big("0.0004", 10).sqrt().to_string(), "0.02");
+    assert_eq!(big("1e21", 12).sqrt().to_string(), "31622776601.7");
+  }
+
+  #[test]
+  fn test_powi() {
+    assert_eq!(big("1.1", 20).powi(&BigInt::from(10)).to_string(), "2.5937424601");
+    assert_eq!(big("2", 5).powi(&BigInt::from(0)).to_string(), "1.0");
+    assert_eq!(big("3", 5).powi(&BigInt::from(20)).to_string(), "3486800000.0");
+  }
+
+  #[test]
+  fn test_to_f64() {
+    assert_eq!(big("0.1", 30).to_f64(), 0.1);
+    assert_eq!(big("-2.5e10", 30).to_f64(), -2.5e10);
+  }
+
+  /// Asserts that `actual` agrees with `expected` to within a few
+  /// units in the last of 30 significant digits.
+  fn assert_close(actual: BigFloat, expected: &str) {
+    let expected = big(expected, 30);
+    let tolerance = BigFloat::rounded(BigInt::from(5), expected.top() - 30, 30);
+    let error = (actual.clone() - expected.clone()).abs();
+    assert!(error <= tolerance, "Expected {expected}, got {actual}");
+  }
+
+  #[test]
+  fn test_pi() {
+    assert_eq!(BigFloat::pi(30).to_string(), "3.14159265358979323846264338328");
+    assert_eq!(BigFloat::pi(30).precision(), 30);
+  }
+
+  #[test]
+  fn test_exp() {
+    assert_eq!(big("1", 30).exp().unwrap().to_string(), "2.71828182845904523536028747135");
+    assert_eq!(big("-50.5", 30).exp().unwrap().to_string(), "1.16984591770619646858516251845e-22");
+    assert_eq!(big("0", 30).exp().unwrap().to_string(), "1.0");
+    assert!(big("1e16", 30).exp().is_none());
+  }
+
+  #[test]
+  fn test_ln() {
+    assert_eq!(big("2", 30).ln().unwrap().to_string(), "0.693147180559945309417232121458");
+    assert_eq!(big("3e-300", 30).ln().unwrap().to_string(), "-689.676915609545595514002191168");
+    // The digits of ln(1 + x) for small x are not lost to
+    // cancellation.
+    assert_eq!(big("1.00000000000000000001", 30).ln().unwrap().to_string(), "9.99999999999999999995e-21");
+    assert!(big("0", 30).ln().is_none());
+    assert!(big("-1", 30).ln().is_none());
+  }
+
+  #[test]
+  fn test_powf() {
+    assert_eq!(big("2", 30).powf(&big("0.5", 30)).unwrap().to_string(), "1.41421356237309504880168872421");
+    assert_eq!(big("10", 30).powf(&big("100.3", 30)).unwrap().to_string(), "1.99526231496887960135245539674e100");
+    assert_close(big("2", 30).powf(&BigFloat::from_rational(&BigRational::new(1.into(), 3.into()), 30)).unwrap(), "1.25992104989487316476721060728");
+    assert!(big("-2", 30).powf(&big("0.5", 30)).is_none());
+  }
+
+  #[test]
+  fn test_trigonometry() {
+    assert_eq!(big("1", 30).sin().unwrap().to_string(), "0.84147098480789650665250232163");
+    assert_eq!(big("1", 30).cos().unwrap().to_string(), "0.540302305868139717400936607443");
+    assert_eq!(big("-4", 30).sin().unwrap().to_string(), "0.756802495307928251372639094512");
+    assert_eq!(big("-4", 30).cos().unwrap().to_string(), "-0.653643620863611914639168183098");
+    assert_eq!(big("1.5", 30).tan().unwrap().to_string(), "14.101419947171719387646083652");
+    // 355 is very nearly a multiple of pi, so the reduction must
+    // carry extra digits.
+    assert_eq!(big("355", 30).sin().unwrap().to_string(), "-0.0000301443533594884492143302800087");
+    assert_eq!(big("1e20", 30).sin().unwrap().to_string(), "-0.645251285265780844205811711313");
+  }
+
+  #[test]
+  fn test_inverse_trigonometry() {
+    assert_eq!((big("1", 30).atan() * big("4", 30)).to_string(), BigFloat::pi(30).to_string());
+    assert_close(big("-7", 30).atan(), "-1.42889927219073269641847007454");
+    assert_close(big("0.5", 30).asin().unwrap(), "0.523598775598298873077107230547");
+    assert_close(big("-0.5", 30).acos().unwrap(), "2.09439510239319549230842892219");
+    assert_close(big("-1", 30).atan2(&big("-1", 30)), "-2.35619449019234492884698253746");
+    assert_eq!(big("1", 30).acos().unwrap().to_string(), "0.0");
+    assert!(big("1.5", 30).asin().is_none());
+    assert!(big("-1.5", 30).acos().is_none());
+  }
+
+  #[test]
+  fn test_hyperbolic() {
+    assert_close(big("1e-5", 30).sinh().unwrap(), "0.0000100000000001666666666675000000");
+    assert_close(big("2", 30).cosh().unwrap(), "3.76219569108363145956221347777");
+    assert_close(big("0.5", 30).tanh(), "0.462117157260009758502318483644");
+    assert_eq!(big("1e20", 30).tanh().to_string(), "1.0");
+    assert_close(big("-3", 30).asinh(), "-1.81844645923206682348369896356");
+    assert_close(big("1.5", 30).acosh().unwrap(), "0.962423650119206894995517826849");
+    assert_close(big("0.999", 30).atanh().unwrap(), "3.80020116725020003177596726781");
+    assert!(big("0.5", 30).acosh().is_none());
+    assert!(big("1", 30).atanh().is_none());
+  }
+}
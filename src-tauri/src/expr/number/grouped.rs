@@ -1,9 +1,10 @@
 
 use crate::expr::Expr;
-use super::visitor::QuaternionPair;
+use super::visitor::{QuaternionPair, OctonionPair};
 use super::real::Number;
 use super::complex::ComplexNumber;
 use super::quaternion::Quaternion;
+use super::octonion::Octonion;
 use crate::util::stricteq::StrictEq;
 
 use num::{Zero, One};
@@ -38,6 +39,16 @@ pub enum QuaternionLike {
   Quaternion(Quaternion),
 }
 
+/// A real number, complex number, quaternion, or octonion. Like
+/// [`QuaternionLike`], this is used as a target for a prism and can
+/// be lifted to an [`Octonion`] via [`From::from`] when desired.
+#[derive(Clone, Debug)]
+pub enum OctonionLike {
+  Quaternion(QuaternionLike),
+  // Boxed, since octonions are twice the size of the other variant.
+  Octonion(Box<Octonion>),
+}
+
 impl ComplexLike {
   /// Panics if `self` is a [`ComplexLike::Complex`].
   pub fn unwrap_real(self) -> Number {
@@ -145,6 +156,19 @@ impl QuaternionLike {
   }
 }
 
+impl OctonionLike {
+  pub fn is_octonion(&self) -> bool {
+    matches!(self, OctonionLike::Octonion(_))
+  }
+
+  pub fn has_proper_ratio(&self) -> bool {
+    match self {
+      OctonionLike::Quaternion(q) => q.has_proper_ratio(),
+      OctonionLike::Octonion(o) => o.has_proper_ratio(),
+    }
+  }
+}
+
 impl From<ComplexLike> for QuaternionLike {
   fn from(input: ComplexLike) -> QuaternionLike {
     match input {
@@ -204,6 +228,30 @@ impl From<QuaternionLike> for Expr {
   }
 }
 
+impl From<QuaternionLike> for OctonionLike {
+  fn from(input: QuaternionLike) -> OctonionLike {
+    OctonionLike::Quaternion(input)
+  }
+}
+
+impl From<OctonionLike> for Octonion {
+  fn from(input: OctonionLike) -> Octonion {
+    match input {
+      OctonionLike::Quaternion(quat) => Quaternion::from(quat).into(),
+      OctonionLike::Octonion(oct) => *oct,
+    }
+  }
+}
+
+impl From<OctonionLike> for Expr {
+  fn from(input: OctonionLike) -> Expr {
+    match input {
+      OctonionLike::Quaternion(quat) => quat.into(),
+      OctonionLike::Octonion(oct) => (*oct).into(),
+    }
+  }
+}
+
 impl TryFrom<ComplexLike> for Number {
   type Error = ComplexLike;
 
@@ -249,6 +297,17 @@ impl PartialEq for QuaternionLike {
 
 impl Eq for QuaternionLike {}
 
+impl PartialEq for OctonionLike {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (OctonionLike::Quaternion(a), OctonionLike::Quaternion(b)) => a == b,
+      (a, b) => Octonion::from(a.to_owned()) == Octonion::from(b.to_owned()),
+    }
+  }
+}
+
+impl Eq for OctonionLike {}
+
 impl StrictEq for ComplexLike {
   fn strict_eq(&self, other: &Self) -> bool {
     match (self, other) {
@@ -451,3 +510,62 @@ impl Div for QuaternionLike {
     }
   }
 }
+
+impl Zero for OctonionLike {
+  fn zero() -> Self {
+    OctonionLike::Quaternion(QuaternionLike::zero())
+  }
+
+  fn is_zero(&self) -> bool {
+    match self {
+      OctonionLike::Quaternion(q) => q.is_zero(),
+      OctonionLike::Octonion(o) => o.is_zero(),
+    }
+  }
+}
+
+impl One for OctonionLike {
+  fn one() -> Self {
+    OctonionLike::Quaternion(QuaternionLike::one())
+  }
+
+  fn is_one(&self) -> bool {
+    match self {
+      OctonionLike::Quaternion(q) => q.is_one(),
+      OctonionLike::Octonion(o) => o.is_one(),
+    }
+  }
+}
+
+impl Add for OctonionLike {
+  type Output = OctonionLike;
+
+  fn add(self, other: Self) -> Self::Output {
+    match OctonionPair::promote(self, other) {
+      OctonionPair::Quaternions(a, b) => OctonionLike::Quaternion(a + b),
+      OctonionPair::Octonions(a, b) => OctonionLike::Octonion(Box::new(a + b)),
+    }
+  }
+}
+
+impl Sub for OctonionLike {
+  type Output = OctonionLike;
+
+  fn sub(self, other: Self) -> Self::Output {
+    match OctonionPair::promote(self, other) {
+      OctonionPair::Quaternions(a, b) => OctonionLike::Quaternion(a - b),
+      OctonionPair::Octonions(a, b) => OctonionLike::Octonion(Box::new(a - b)),
+    }
+  }
+}
+
+impl Mul for OctonionLike {
+  type Output = OctonionLike;
+
+  fn mul(self, other: Self) -> Self::Output {
+    match OctonionPair::promote(self, other) {
+      OctonionPair::Quaternions(a, b) => OctonionLike::Quaternion(a * b),
+      OctonionPair::Octonions(a, b) => OctonionLike::Octonion(Box::new(a * b)),
+    }
+  }
+}
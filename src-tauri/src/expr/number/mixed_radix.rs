@@ -0,0 +1,116 @@
+
+//! Decomposition of real numbers into mixed-radix digits, such as
+//! writing a number of seconds as days, hours, minutes, and seconds.
+
+use super::real::Number;
+
+use num::Zero;
+
+/// Splits `n` into mixed-radix digits. The bases are given from most
+/// significant to least significant, and the result has one more
+/// digit than there are bases: the leading digit is unbounded, and
+/// each subsequent digit `i` lies in `[0, bases[i-1])`. For instance,
+/// with bases `[24, 60, 60]`, a number of seconds is split into days,
+/// hours, minutes, and seconds.
+///
+/// If `n` is not an integer, the fractional part ends up in the last
+/// digit. Negative values are decomposed by magnitude, and then every
+/// digit is negated, so that (for instance) `-90` seconds is `-1`
+/// minute and `-30` seconds.
+///
+/// Returns `None` if any base is not positive.
+pub fn to_mixed_radix(n: &Number, bases: &[Number]) -> Option<Vec<Number>> {
+  if bases.iter().any(|base| !base.is_positive()) {
+    return None;
+  }
+  let mut remaining = n.abs();
+  let mut digits = Vec::with_capacity(bases.len() + 1);
+  for base in bases.iter().rev() {
+    let quotient = remaining.div_floor(base);
+    digits.push(&remaining - &(&quotient * base));
+    remaining = quotient;
+  }
+  digits.push(remaining);
+  digits.reverse();
+  if n < &Number::zero() {
+    digits = digits.into_iter().map(|d| -d).collect();
+  }
+  Some(digits)
+}
+
+/// The inverse of [`to_mixed_radix`]. Combines the digits, from most
+/// significant to least, into a single number. The digits need not
+/// lie within the range of their respective bases.
+///
+/// Returns `None` if there is not exactly one more digit than there
+/// are bases.
+pub fn from_mixed_radix(digits: &[Number], bases: &[Number]) -> Option<Number> {
+  let (first, rest) = digits.split_first()?;
+  if rest.len() != bases.len() {
+    return None;
+  }
+  let value = rest.iter().zip(bases).fold(first.clone(), |acc, (digit, base)| acc * base + digit);
+  Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::assert_strict_eq;
+
+  fn numbers(ns: &[i64]) -> Vec<Number> {
+    ns.iter().copied().map(Number::from).collect()
+  }
+
+  #[test]
+  fn test_to_mixed_radix() {
+    let bases = numbers(&[24, 60, 60]);
+    assert_eq!(to_mixed_radix(&Number::from(90061), &bases), Some(numbers(&[1, 1, 1, 1])));
+    assert_eq!(to_mixed_radix(&Number::from(59), &bases), Some(numbers(&[0, 0, 0, 59])));
+    assert_eq!(to_mixed_radix(&Number::from(0), &bases), Some(numbers(&[0, 0, 0, 0])));
+    assert_eq!(to_mixed_radix(&Number::from(1_000_000), &bases), Some(numbers(&[11, 13, 46, 40])));
+    assert_eq!(to_mixed_radix(&Number::from(7), &[]), Some(numbers(&[7])));
+  }
+
+  #[test]
+  fn test_to_mixed_radix_negative() {
+    let bases = numbers(&[60]);
+    assert_eq!(to_mixed_radix(&Number::from(-90), &bases), Some(numbers(&[-1, -30])));
+  }
+
+  #[test]
+  fn test_to_mixed_radix_fractional() {
+    let bases = numbers(&[60, 60]);
+    let digits = to_mixed_radix(&Number::ratio(7323, 2), &bases).unwrap();
+    assert_strict_eq!(digits[0], Number::from(1));
+    assert_strict_eq!(digits[1], Number::from(1));
+    assert_strict_eq!(digits[2], Number::ratio(3, 2));
+    let digits = to_mixed_radix(&Number::from(3661.5), &bases).unwrap();
+    assert_eq!(digits, vec![Number::from(1), Number::from(1), Number::from(1.5)]);
+  }
+
+  #[test]
+  fn test_to_mixed_radix_invalid_bases() {
+    assert_eq!(to_mixed_radix(&Number::from(10), &numbers(&[24, 0])), None);
+    assert_eq!(to_mixed_radix(&Number::from(10), &numbers(&[-3])), None);
+  }
+
+  #[test]
+  fn test_from_mixed_radix() {
+    let bases = numbers(&[24, 60, 60]);
+    assert_eq!(from_mixed_radix(&numbers(&[1, 1, 1, 1]), &bases), Some(Number::from(90061)));
+    assert_eq!(from_mixed_radix(&numbers(&[0, 0, 90, 0]), &bases), Some(Number::from(5400)));
+    assert_eq!(from_mixed_radix(&numbers(&[1, 1, 1]), &bases), None);
+    assert_eq!(from_mixed_radix(&[], &[]), None);
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let bases = numbers(&[7, 24, 60]);
+    for n in [-100_000, -1, 0, 1, 9_999, 123_456] {
+      let n = Number::from(n);
+      let digits = to_mixed_radix(&n, &bases).unwrap();
+      assert_eq!(from_mixed_radix(&digits, &bases), Some(n));
+    }
+  }
+}
@@ -1,7 +1,10 @@
 
+pub mod bigfloat;
 mod complex;
 mod grouped;
 pub mod inexact;
+pub mod mixed_radix;
+mod octonion;
 mod power;
 mod quaternion;
 mod real;
@@ -12,9 +15,10 @@ pub mod prisms;
 pub use real::{Number, ParseNumberError};
 pub use complex::ComplexNumber;
 pub use quaternion::Quaternion;
+pub use octonion::Octonion;
 pub use repr::NumberRepr;
 pub use power::{pow_real, pow_complex_to_real, pow_complex, root_real, root_complex};
-pub use grouped::{ComplexLike, QuaternionLike};
+pub use grouped::{ComplexLike, QuaternionLike, OctonionLike};
 
 use crate::util::{unwrap_infallible, PreOne};
 
@@ -0,0 +1,326 @@
+
+use super::{Number, ComplexNumber, Quaternion, powi_by_repeated_square};
+use crate::util::stricteq::StrictEq;
+
+use serde::{Serialize, Deserialize};
+use num::{BigInt, Zero, One};
+
+use std::fmt::{self, Display, Formatter};
+use std::ops;
+use std::cmp::Ordering;
+
+/// An octonion has eight components: a real part and seven non-real
+/// parts associated with the unit octonions `e1` through `e7`.
+///
+/// Octonions are built from quaternions via the Cayley-Dickson
+/// construction. An octonion is a pair `(a, b)` of quaternions,
+/// standing for `a + b e4`. Multiplication of octonions is neither
+/// commutative nor associative, though it is alternative, so powers
+/// of a single octonion are well-defined.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Octonion {
+  a: Quaternion,
+  b: Quaternion,
+}
+
+impl Octonion {
+  pub const FUNCTION_NAME: &'static str = "oct";
+
+  /// Constructs an octonion from its Cayley-Dickson pair of
+  /// quaternions.
+  pub fn from_pair(a: Quaternion, b: Quaternion) -> Self {
+    Self { a, b }
+  }
+
+  /// Constructs an octonion from its eight components, in the order
+  /// `1, e1, ..., e7`.
+  pub fn from_components(components: [Number; 8]) -> Self {
+    let [c0, c1, c2, c3, c4, c5, c6, c7] = components;
+    Self::from_pair(Quaternion::new(c0, c1, c2, c3), Quaternion::new(c4, c5, c6, c7))
+  }
+
+  /// The unit octonion `e_n`, where `e_0` is the real unit. Panics if
+  /// `n >= 8`.
+  pub fn basis(n: usize) -> Self {
+    assert!(n < 8, "Octonion basis index out of range: {n}");
+    let mut components: [Number; 8] = Default::default();
+    components[n] = Number::one();
+    Self::from_components(components)
+  }
+
+  pub fn into_pair(self) -> (Quaternion, Quaternion) {
+    (self.a, self.b)
+  }
+
+  /// The eight components of this octonion, in the order `1, e1,
+  /// ..., e7`.
+  pub fn into_components(self) -> [Number; 8] {
+    let (r0, i0, j0, k0) = self.a.into_parts();
+    let (r1, i1, j1, k1) = self.b.into_parts();
+    [r0, i0, j0, k0, r1, i1, j1, k1]
+  }
+
+  pub fn from_real(real: impl Into<Number>) -> Self {
+    Self::from_pair(Quaternion::from_real(real), Quaternion::zero())
+  }
+
+  pub fn to_inexact(&self) -> Self {
+    Self::from_pair(self.a.to_inexact(), self.b.to_inexact())
+  }
+
+  /// The conjugate of `self`.
+  pub fn conj(self) -> Self {
+    Self::from_pair(self.a.conj(), -self.b)
+  }
+
+  /// Computes the square of the absolute value.
+  pub fn abs_sqr(&self) -> Number {
+    self.a.abs_sqr() + self.b.abs_sqr()
+  }
+
+  /// Computes the absolute value (Euclidean norm) of this octonion.
+  /// As with quaternions, this is always an inexact quantity.
+  pub fn abs(&self) -> f64 {
+    self.abs_sqr().powf(0.5)
+  }
+
+  /// The multiplicative inverse of this octonion. Panics if
+  /// `self.is_zero()`.
+  pub fn recip(self) -> Self {
+    assert!(!self.is_zero(), "Attempted to take reciprocal of zero octonion");
+    let abs_sqr = Quaternion::from_real(self.abs_sqr());
+    let (a, b) = self.conj().into_pair();
+    Self::from_pair(a / abs_sqr.clone(), b / abs_sqr)
+  }
+
+  /// The multiplicative inverse of this octonion, falling back to
+  /// floating-point if the result is not an integer. Panics if
+  /// `self.is_zero()`.
+  pub fn recip_inexact(self) -> Self {
+    let exact = self.recip();
+    if exact.has_proper_ratio() {
+      exact.to_inexact()
+    } else {
+      exact
+    }
+  }
+
+  pub fn powi(&self, exp: BigInt) -> Octonion {
+    match exp.cmp(&BigInt::zero()) {
+      Ordering::Equal => {
+        Octonion::one()
+      }
+      Ordering::Greater => {
+        powi_by_repeated_square(self.clone(), exp)
+      }
+      Ordering::Less => {
+        powi_by_repeated_square(self.clone().recip(), -exp)
+      }
+    }
+  }
+
+  /// True if any of the components of this octonion are a proper
+  /// (non-integer) ratio.
+  pub fn has_proper_ratio(&self) -> bool {
+    self.a.has_proper_ratio() || self.b.has_proper_ratio()
+  }
+}
+
+impl StrictEq for Octonion {
+  fn strict_eq(&self, other: &Self) -> bool {
+    self.a.strict_eq(&other.a) && self.b.strict_eq(&other.b)
+  }
+}
+
+impl From<Number> for Octonion {
+  fn from(n: Number) -> Self {
+    Self::from_real(n)
+  }
+}
+
+impl From<ComplexNumber> for Octonion {
+  fn from(z: ComplexNumber) -> Self {
+    Self::from(Quaternion::from(z))
+  }
+}
+
+impl From<Quaternion> for Octonion {
+  fn from(q: Quaternion) -> Self {
+    Self::from_pair(q, Quaternion::zero())
+  }
+}
+
+impl Display for Octonion {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    let [c0, c1, c2, c3, c4, c5, c6, c7] = self.clone().into_components();
+    write!(f, "({c0}, {c1}, {c2}, {c3}, {c4}, {c5}, {c6}, {c7})")
+  }
+}
+
+impl ops::Add for Octonion {
+  type Output = Octonion;
+
+  fn add(self, other: Self) -> Self {
+    Self::from_pair(self.a + other.a, self.b + other.b)
+  }
+}
+
+impl ops::Sub for Octonion {
+  type Output = Octonion;
+
+  fn sub(self, other: Self) -> Self {
+    Self::from_pair(self.a - other.a, self.b - other.b)
+  }
+}
+
+impl ops::Mul for Octonion {
+  type Output = Octonion;
+
+  /// Cayley-Dickson product: `(a, b) (c, d) = (a c - d* b, d a + b
+  /// c*)`, where `*` denotes the quaternion conjugate.
+  fn mul(self, other: Self) -> Self {
+    let (a, b) = self.into_pair();
+    let (c, d) = other.into_pair();
+    let first = a.clone() * c.clone() - d.clone().conj() * b.clone();
+    let second = d * a + b * c.conj();
+    Self::from_pair(first, second)
+  }
+}
+
+// Needed to call powi_by_repeated_square.
+impl ops::MulAssign for Octonion {
+  fn mul_assign(&mut self, other: Self) {
+    *self = self.clone() * other
+  }
+}
+
+impl ops::Div for Octonion {
+  type Output = Octonion;
+
+  #[allow(clippy::suspicious_arithmetic_impl)] // Multiplication by reciprocal is correct
+  fn div(self, other: Self) -> Self {
+    self * other.recip()
+  }
+}
+
+impl ops::Neg for Octonion {
+  type Output = Octonion;
+
+  fn neg(self) -> Self {
+    Self::from_pair(-self.a, -self.b)
+  }
+}
+
+impl Zero for Octonion {
+  fn zero() -> Self {
+    Self::from_pair(Quaternion::zero(), Quaternion::zero())
+  }
+
+  fn is_zero(&self) -> bool {
+    self.a.is_zero() && self.b.is_zero()
+  }
+}
+
+impl One for Octonion {
+  fn one() -> Self {
+    Self::from_pair(Quaternion::one(), Quaternion::zero())
+  }
+
+  fn is_one(&self) -> bool {
+    self.a.is_one() && self.b.is_zero()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::assert_strict_eq;
+
+  fn e(n: usize) -> Octonion {
+    Octonion::basis(n)
+  }
+
+  fn oct(components: [i64; 8]) -> Octonion {
+    Octonion::from_components(components.map(Number::from))
+  }
+
+  #[test]
+  fn test_basis_elements_square_to_minus_one() {
+    for n in 1..8 {
+      assert_eq!(e(n) * e(n), Octonion::from_real(-1));
+    }
+    assert_eq!(e(0) * e(0), Octonion::one());
+  }
+
+  #[test]
+  fn test_agrees_with_quaternions() {
+    let p = Quaternion::new(1, 2, 3, 4);
+    let q = Quaternion::new(-2, 0, 5, 1);
+    assert_eq!(
+      Octonion::from(p.clone()) * Octonion::from(q.clone()),
+      Octonion::from(p * q),
+    );
+  }
+
+  #[test]
+  fn test_basis_anticommutes() {
+    for m in 1..8 {
+      for n in 1..8 {
+        if m != n {
+          assert_eq!(e(m) * e(n), - (e(n) * e(m)));
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_non_associative() {
+    let left = (e(1) * e(2)) * e(4);
+    let right = e(1) * (e(2) * e(4));
+    assert_eq!(left, - right);
+  }
+
+  #[test]
+  fn test_norm_is_multiplicative() {
+    let x = oct([1, 2, -1, 0, 3, 1, 1, -2]);
+    let y = oct([0, 1, 4, -3, 2, 0, 1, 1]);
+    assert_eq!((x.clone() * y.clone()).abs_sqr(), x.abs_sqr() * y.abs_sqr());
+  }
+
+  #[test]
+  fn test_conjugate() {
+    let x = oct([1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_strict_eq!(x.clone().conj(), oct([1, -2, -3, -4, -5, -6, -7, -8]));
+    assert_eq!(x.clone() * x.clone().conj(), Octonion::from_real(x.abs_sqr()));
+  }
+
+  #[test]
+  fn test_recip() {
+    let x = oct([1, 1, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(x.clone().recip(), Octonion::from_components([
+      Number::ratio(1, 3),
+      Number::ratio(-1, 3),
+      Number::from(0),
+      Number::from(0),
+      Number::from(0),
+      Number::from(0),
+      Number::from(0),
+      Number::ratio(-1, 3),
+    ]));
+    assert_eq!(x.clone() * x.recip(), Octonion::one());
+  }
+
+  #[test]
+  fn test_powi() {
+    let x = oct([1, 2, 0, 0, 1, 0, 0, 0]);
+    assert_eq!(x.powi(BigInt::from(3)), x.clone() * x.clone() * x.clone());
+    assert_eq!(x.powi(BigInt::from(0)), Octonion::one());
+    assert_eq!(x.powi(BigInt::from(-1)), x.recip());
+  }
+
+  #[test]
+  fn test_components_round_trip() {
+    let x = oct([1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_strict_eq!(Octonion::from_components(x.clone().into_components()), x);
+  }
+}
@@ -5,11 +5,12 @@
 //! [`Number::powf`](super::Number::powf).
 
 use super::real::{Number, NumberImpl};
+use super::bigfloat::BigFloat;
 use super::complex::ComplexNumber;
 use super::ComplexLike;
 use crate::util::angles::Radians;
 
-use num::{BigInt, Zero, One, ToPrimitive};
+use num::{BigInt, BigRational, Zero, One, ToPrimitive};
 
 use std::f64::consts::PI;
 
@@ -27,18 +28,55 @@ pub fn pow_real(x: Number, y: Number) -> ComplexLike {
       root_real(big_x, y.denom().clone())
     }
     NumberImpl::Float(y) => {
-      if x > Number::zero() {
-        // Just do floating exponentiation.
-        let result = x.powf(y);
-        ComplexLike::Real(Number::from(result))
-      } else {
-        // Calculate the result in polar coordinates.
-        let magnitude = x.abs().powf(y);
-        let angle = Radians(PI * y);
-        ComplexLike::Complex(ComplexNumber::from_polar_inexact(magnitude, angle))
+      match x.big_float_precision() {
+        Some(precision) if y.is_finite() => pow_real_to_big_float(x, BigFloat::from_f64(y, precision)),
+        _ => pow_real_to_float(x, y),
+      }
+    }
+    NumberImpl::BigFloat(y) => {
+      pow_real_to_big_float(x, *y)
+    }
+  }
+}
+
+/// Raises `x` to a `BigFloat` power, to the larger of the two
+/// precisions. If `x` is not positive, the result may be complex, and
+/// is computed with `f64` values instead.
+fn pow_real_to_big_float(x: Number, y: BigFloat) -> ComplexLike {
+  let precision = x.big_float_precision().map_or(y.precision(), |precision| precision.max(y.precision()));
+  let x_big = x.to_big_float(precision);
+  if y.to_rational() == BigRational::new(BigInt::one(), BigInt::from(2)) {
+    if let Some(root) = x_big.big_float_sqrt() {
+      return ComplexLike::Real(root);
+    }
+  }
+  if x > Number::zero() {
+    if let NumberImpl::BigFloat(x_big) = x_big.inner {
+      if let Some(result) = x_big.powf(&y) {
+        return ComplexLike::Real(Number::from(result));
       }
     }
   }
+  pow_real_to_float(x, y.to_f64())
+}
+
+fn pow_real_to_float(x: Number, y: f64) -> ComplexLike {
+  if y == 0.5 {
+    // Square roots of big floats can be computed to full precision.
+    if let Some(root) = x.big_float_sqrt() {
+      return ComplexLike::Real(root);
+    }
+  }
+  if x > Number::zero() {
+    // Just do floating exponentiation.
+    let result = x.powf(y);
+    ComplexLike::Real(Number::from(result))
+  } else {
+    // Calculate the result in polar coordinates.
+    let magnitude = x.abs().powf(y);
+    let angle = Radians(PI * y);
+    ComplexLike::Complex(ComplexNumber::from_polar_inexact(magnitude, angle))
+  }
 }
 
 /// Finds the principal nth root of a real number. The result may be
@@ -56,6 +94,13 @@ pub fn root_real(x: Number, n: BigInt) -> ComplexLike {
     return ComplexLike::Real(x);
   }
 
+  if let Some(precision) = x.big_float_precision() {
+    if x > Number::zero() {
+      let exponent = BigFloat::from_rational(&BigRational::new(BigInt::one(), n), precision);
+      return pow_real_to_big_float(x, exponent);
+    }
+  }
+
   // TODO Currently, for nontrivial roots, we just delegate to inexact
   // computations as a matter of course. In principle, we should try
   // to stay exact in situatons where it's reasonable to do so.
@@ -84,14 +129,21 @@ pub fn pow_complex_to_real(x: ComplexNumber, y: Number) -> ComplexNumber {
       root_complex(big_x, y.denom().clone())
     }
     NumberImpl::Float(y) => {
-      // Calculate the result in polar coordinates.
-      let magnitude = x.abs().powf(y);
-      let angle = x.angle() * y;
-      ComplexNumber::from_polar_inexact(magnitude, angle)
+      pow_complex_to_float(x, y)
+    }
+    NumberImpl::BigFloat(y) => {
+      pow_complex_to_float(x, y.to_f64())
     }
   }
 }
 
+fn pow_complex_to_float(x: ComplexNumber, y: f64) -> ComplexNumber {
+  // Calculate the result in polar coordinates.
+  let magnitude = x.abs().powf(y);
+  let angle = x.angle() * y;
+  ComplexNumber::from_polar_inexact(magnitude, angle)
+}
+
 fn raise_to_power_i(x: ComplexNumber) -> ComplexNumber {
   let abs = x.abs();
   let angle = x.angle();
@@ -267,6 +319,17 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_pow_real_with_big_float_exponent() {
+    let value = pow_real(Number::from(2), Number::from(0.5).to_big_float(30)).unwrap_real();
+    assert_eq!(value.to_string(), "1.41421356237309504880168872421");
+    let value = pow_real(Number::from(10).to_big_float(30), Number::from(100.3)).unwrap_real();
+    assert_eq!(value.to_string(), "1.99526231496887960135245539674e100");
+    let value = pow_real(Number::from(2).to_big_float(30), Number::ratio(1, 3)).unwrap_real();
+    assert_eq!(value.big_float_precision(), Some(30));
+    assert_abs_diff_eq!(value.to_f64().unwrap(), 1.259921049894873, epsilon = 1e-15);
+  }
+
   #[test]
   fn test_pow_complex() {
     let value = pow_complex(
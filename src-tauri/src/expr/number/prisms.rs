@@ -17,6 +17,16 @@ pub struct NumberToUsize;
 #[derive(Debug, Copy, Clone, Default)]
 pub struct NumberToI64;
 
+/// Prism which converts a [`Number`] to an arbitrary-precision
+/// integer. Fails if the `Number` is not an integer.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NumberToBigInt;
+
+/// Prism which converts a [`Number`] to a `u8`, i.e. a byte. Fails
+/// if the `Number` is not an integer in the range `[0, 255]`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NumberToU8;
+
 impl Prism<Number, usize> for NumberToUsize {
   fn narrow_type(&self, number: Number) -> Result<usize, Number> {
     let bigint = BigInt::try_from(number).map_err(|err| err.number)?;
@@ -27,6 +37,25 @@ impl Prism<Number, usize> for NumberToUsize {
   }
 }
 
+impl Prism<Number, u8> for NumberToU8 {
+  fn narrow_type(&self, number: Number) -> Result<u8, Number> {
+    let bigint = BigInt::try_from(number).map_err(|err| err.number)?;
+    bigint.to_u8().ok_or_else(|| bigint.into())
+  }
+  fn widen_type(&self, number: u8) -> Number {
+    Number::from(i64::from(number))
+  }
+}
+
+impl Prism<Number, BigInt> for NumberToBigInt {
+  fn narrow_type(&self, number: Number) -> Result<BigInt, Number> {
+    BigInt::try_from(number).map_err(|err| err.number)
+  }
+  fn widen_type(&self, number: BigInt) -> Number {
+    Number::from(number)
+  }
+}
+
 impl Prism<Number, i64> for NumberToI64 {
   fn narrow_type(&self, number: Number) -> Result<i64, Number> {
     let bigint = BigInt::try_from(number).map_err(|err| err.number)?;
@@ -1,13 +1,16 @@
 
 use super::visitor::NumberPair;
 use super::repr::NumberRepr;
+use super::bigfloat::{BigFloat, F64_PRECISION};
 use super::inexact::DivInexact;
 use super::powi_by_repeated_square;
 use crate::util::Sign;
 use crate::util::stricteq::StrictEq;
 use crate::util::radix::{Radix, Digits, ToDigits};
+use crate::mode::display::float_format::{FloatFormat, DecimalDigits};
+use crate::mode::calculation::{RoundingMode, Discarded, WordSize};
 
-use num::{BigInt, BigRational, Zero, One, FromPrimitive};
+use num::{BigInt, BigRational, Zero, One, FromPrimitive, Signed, Integer};
 use num::pow::Pow;
 use num::integer::div_floor;
 use num::traits::ToPrimitive;
@@ -29,7 +32,9 @@ use std::convert::TryFrom;
 /// demand it.
 ///
 /// A real number can be represented as an exact (arbitrary-precision)
-/// integer, a rational number, or an IEEE 754 floating point value.
+/// integer, a rational number, an IEEE 754 floating point value, or
+/// an inexact decimal value with a configurable number of significant
+/// digits ([`BigFloat`]).
 /// Use [`Number::repr`] to get the number's current representation.
 ///
 /// Note that, if a `Number` is being represented as a floating point
@@ -47,6 +52,7 @@ pub(super) enum NumberImpl {
   Integer(Box<BigInt>),
   Ratio(Box<BigRational>),
   Float(f64),
+  BigFloat(Box<BigFloat>),
 }
 
 #[derive(Debug, Error, Clone)]
@@ -67,6 +73,21 @@ impl Number {
       NumberImpl::Integer(_) => NumberRepr::Integer,
       NumberImpl::Ratio(_) => NumberRepr::Ratio,
       NumberImpl::Float(_) => NumberRepr::Float,
+      NumberImpl::BigFloat(_) => NumberRepr::BigFloat,
+    }
+  }
+
+  /// An estimate of the number of bytes of heap memory used by this
+  /// number, not counting the `Number` itself.
+  pub fn approx_heap_size(&self) -> usize {
+    fn bigint_size(n: &BigInt) -> usize {
+      std::mem::size_of::<BigInt>() + (n.bits() as usize).div_ceil(8)
+    }
+    match &self.inner {
+      NumberImpl::Integer(n) => bigint_size(n),
+      NumberImpl::Ratio(r) => bigint_size(r.numer()) + bigint_size(r.denom()),
+      NumberImpl::Float(_) => 0,
+      NumberImpl::BigFloat(b) => std::mem::size_of::<BigFloat>() + bigint_size(b.mantissa()),
     }
   }
 
@@ -108,6 +129,40 @@ impl Number {
         let quotient = (left / right).floor();
         Number::from(BigInt::from_f64(quotient).expect("floor should produce integer value"))
       }
+      NumberPair::BigFloats(left, right) => {
+        let quotient = (left.to_rational() / right.to_rational()).floor();
+        Number::from(quotient.to_integer())
+      }
+    }
+  }
+
+  /// Rounds to the given number of decimal places, according to
+  /// `rounding_mode`. A negative number of places rounds to a
+  /// multiple of a power of ten, so that rounding to `-2` places
+  /// produces a multiple of one hundred.
+  ///
+  /// Inexact values are rounded as the decimal which they are
+  /// written as, so `2.675` rounds to `2.68` with
+  /// [`RoundingMode::HalfUp`], even though the nearest `f64` is
+  /// slightly smaller than that. This agrees with the rounding done
+  /// by the float formats. Inexact values stay inexact, with the same
+  /// representation.
+  pub fn round_to_places(&self, places: i64, rounding_mode: RoundingMode) -> Number {
+    match &self.inner {
+      NumberImpl::Integer(_) if places >= 0 => self.clone(),
+      NumberImpl::Integer(n) => {
+        let value = BigRational::from_integer((**n).clone());
+        Number::from(round_rational(&value, places, rounding_mode))
+      }
+      NumberImpl::Ratio(r) => Number::from(round_rational(r, places, rounding_mode)),
+      NumberImpl::Float(f) => {
+        let value = DecimalDigits::from_f64(*f).expect("Float number should be finite").to_rational();
+        Number::from(round_rational(&value, places, rounding_mode).to_f64().unwrap_or(f64::NAN))
+      }
+      NumberImpl::BigFloat(b) => {
+        let rounded = round_rational(&b.to_rational(), places, rounding_mode);
+        Number::from(BigFloat::from_rational(&rounded, b.precision()))
+      }
     }
   }
 
@@ -158,6 +213,7 @@ impl Number {
             // with an inexact result, so just rely on the hardware
             // powf implementation instead of repeated squaring.
             Number::from(f.powf(exp.to_f64().unwrap_or(f64::NAN))),
+          NumberImpl::BigFloat(b) => Number::from(b.powi(&exp)),
         }
       }
     }
@@ -169,6 +225,7 @@ impl Number {
       NumberImpl::Integer(i) => i.to_f64(),
       NumberImpl::Ratio(r) => r.to_f64(),
       NumberImpl::Float(d) => Some(*d),
+      NumberImpl::BigFloat(b) => Some(b.to_f64()),
     }
   }
 
@@ -189,6 +246,69 @@ impl Number {
     }
   }
 
+  /// Converts `self` to an inexact [`BigFloat`] with the given number
+  /// of significant digits. Values which are already `BigFloat`s are
+  /// rounded or extended to the new precision.
+  ///
+  /// Panics if `precision` is zero or exceeds
+  /// [`MAX_PRECISION`](super::bigfloat::MAX_PRECISION).
+  pub fn to_big_float(&self, precision: u32) -> Self {
+    let value = match &self.inner {
+      NumberImpl::Integer(i) => BigFloat::from_rational(&BigRational::from_integer((**i).clone()), precision),
+      NumberImpl::Ratio(r) => BigFloat::from_rational(r, precision),
+      NumberImpl::Float(f) => BigFloat::from_f64(*f, precision),
+      NumberImpl::BigFloat(b) => b.with_precision(precision),
+    };
+    Number::from(value)
+  }
+
+  /// The number of significant digits carried by `self`, if `self`
+  /// is a [`BigFloat`].
+  pub fn big_float_precision(&self) -> Option<u32> {
+    match &self.inner {
+      NumberImpl::BigFloat(b) => Some(b.precision()),
+      _ => None,
+    }
+  }
+
+  /// As [`DivInexact::div_inexact`], except that an inexact result
+  /// is computed as a [`BigFloat`] with the given number of
+  /// significant digits, rather than as an `f64`.
+  pub fn div_inexact_to_precision(&self, other: &Number, precision: u32) -> Number {
+    let quotient = self / other;
+    if quotient.is_proper_ratio() && !self.is_proper_ratio() && !other.is_proper_ratio() {
+      quotient.to_big_float(precision)
+    } else {
+      quotient
+    }
+  }
+
+  /// The square root of a nonnegative `BigFloat`, to its own
+  /// precision. Returns `None` if `self` is not a `BigFloat` or is
+  /// negative.
+  pub fn big_float_sqrt(&self) -> Option<Number> {
+    match &self.inner {
+      NumberImpl::BigFloat(b) if !b.is_negative() => Some(Number::from(b.sqrt())),
+      _ => None,
+    }
+  }
+
+  /// `e^self`, to the precision of `self`. Returns `None` if `self`
+  /// is not a `BigFloat`, or if the result is too large (or small) to
+  /// represent.
+  pub fn big_float_exp(&self) -> Option<Number> {
+    self.map_big_float(BigFloat::exp)
+  }
+
+  /// Applies `f` to `self` if `self` is a `BigFloat`. Returns `None`
+  /// if `self` is not a `BigFloat` or if `f` returns `None`.
+  fn map_big_float(&self, f: impl FnOnce(&BigFloat) -> Option<BigFloat>) -> Option<Number> {
+    match &self.inner {
+      NumberImpl::BigFloat(b) => f(b).map(Number::from),
+      _ => None,
+    }
+  }
+
   /// Raises a `Number` to a floating point power. The result is
   /// always a floating point, which may be NaN if the value does not,
   /// mathematically, exist as a real number.
@@ -229,13 +349,27 @@ impl Number {
     self.repr() == NumberRepr::Ratio
   }
 
+  /// The exact value of `self` as a fraction in lowest terms, or
+  /// `None` if `self` is inexact.
+  pub fn to_ratio(&self) -> Option<BigRational> {
+    match &self.inner {
+      NumberImpl::Integer(i) => Some(BigRational::from_integer((**i).clone())),
+      NumberImpl::Ratio(r) => Some((**r).clone()),
+      NumberImpl::Float(_) | NumberImpl::BigFloat(_) => None,
+    }
+  }
+
   /// Returns the natural logarithm of `self`. This always returns an
-  /// inexact floating result. Panics if `self <= 0`.
+  /// inexact floating result, which is a `BigFloat` of the same
+  /// precision if `self` is a `BigFloat`. Panics if `self <= 0`.
   pub fn ln(&self) -> Number {
     if self.is_one() && self.repr() < NumberRepr::Float {
       // Return exact zero in this case.
       return Number::zero();
     }
+    if let Some(result) = self.map_big_float(BigFloat::ln) {
+      return result;
+    }
     let x = self.to_f64().expect("Could not convert number to f64");
     assert!(x > 0.0, "Argument to Number::ln should be positive, got {}", x);
     Number::from(x.ln())
@@ -272,73 +406,106 @@ impl Number {
       NumberPair::Integers(arg, base) => rational_log(arg.into(), base.into()),
       NumberPair::Ratios(arg, base) => rational_log(arg, base),
       NumberPair::Floats(arg, base) => float_log(arg, base),
+      NumberPair::BigFloats(arg, base) => {
+        match (arg.ln(), base.ln()) {
+          (Some(arg_ln), Some(base_ln)) if !base_ln.is_zero() => Number::from(arg_ln / base_ln),
+          _ => float_log(arg.to_f64(), base.to_f64()),
+        }
+      }
     }
   }
 
   pub fn sin(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.sin())
+    self.map_big_float(BigFloat::sin).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.sin())
+    })
   }
 
   pub fn cos(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.cos())
+    self.map_big_float(BigFloat::cos).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.cos())
+    })
   }
 
   pub fn tan(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.tan())
+    self.map_big_float(BigFloat::tan).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.tan())
+    })
   }
 
   pub fn asin(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.asin())
+    self.map_big_float(BigFloat::asin).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.asin())
+    })
   }
 
   pub fn acos(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.acos())
+    self.map_big_float(BigFloat::acos).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.acos())
+    })
   }
 
   pub fn atan(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.atan())
+    self.map_big_float(|b| Some(b.atan())).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.atan())
+    })
   }
 
   pub fn atan2(&self, other: &Number) -> Number {
+    if let NumberPair::BigFloats(y, x) = NumberPair::promote(self.clone(), other.clone()) {
+      return Number::from(y.atan2(&x));
+    }
     let f = self.to_f64_or_nan();
     let g = other.to_f64_or_nan();
     Number::from(f.atan2(g))
   }
 
   pub fn sinh(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.sinh())
+    self.map_big_float(BigFloat::sinh).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.sinh())
+    })
   }
 
   pub fn cosh(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.cosh())
+    self.map_big_float(BigFloat::cosh).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.cosh())
+    })
   }
 
   pub fn tanh(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.tanh())
+    self.map_big_float(|b| Some(b.tanh())).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.tanh())
+    })
   }
 
   pub fn asinh(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.asinh())
+    self.map_big_float(|b| Some(b.asinh())).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.asinh())
+    })
   }
 
   pub fn acosh(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.acosh())
+    self.map_big_float(BigFloat::acosh).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.acosh())
+    })
   }
 
   pub fn atanh(&self) -> Number {
-    let f = self.to_f64_or_nan();
-    Number::from(f.atanh())
+    self.map_big_float(BigFloat::atanh).unwrap_or_else(|| {
+      let f = self.to_f64_or_nan();
+      Number::from(f.atanh())
+    })
   }
 
   /// Converts the number to a string, using the given numerical
@@ -359,8 +526,83 @@ impl Number {
       NumberImpl::Float(f) => {
         digits_to_string_radix(f.to_digits(radix), true, radix)
       }
+      NumberImpl::BigFloat(b) => {
+        if radix == Radix::DECIMAL {
+          b.to_string()
+        } else {
+          // Non-decimal output is only as precise as an f64.
+          digits_to_string_radix(b.to_f64().to_digits(radix), true, radix)
+        }
+      }
     }
   }
+
+  /// Writes an integer as its bit pattern in the given word (see
+  /// [`WordSize::bit_pattern`]), padded with leading zeroes to the
+  /// full width of the word. Returns `None` if `self` is not an
+  /// integer which fits in the word, or if `radix` is not a power of
+  /// two.
+  pub fn to_string_twos_complement(&self, radix: Radix, word_size: WordSize) -> Option<String> {
+    let NumberImpl::Integer(n) = &self.inner else {
+      return None;
+    };
+    let radix_value = u8::from(radix);
+    if !radix_value.is_power_of_two() {
+      return None;
+    }
+    let width = word_size.bits().div_ceil(radix_value.trailing_zeros()) as usize;
+    let mut digits = word_size.bit_pattern(n)?.to_digits(radix);
+    let mut whole = vec![0; width.saturating_sub(digits.whole.len())];
+    whole.append(&mut digits.whole);
+    digits.whole = whole;
+    Some(digits_to_string_radix(digits, false, radix))
+  }
+
+  /// As [`Number::to_string_radix`], except that inexact values
+  /// written in decimal follow the given [`FloatFormat`]. Exact
+  /// values, and values in other radixes, are unaffected by the
+  /// format. Digits which the format discards are rounded according
+  /// to `rounding_mode`.
+  pub fn to_string_formatted(&self, radix: Radix, format: FloatFormat, rounding_mode: RoundingMode) -> String {
+    if radix != Radix::DECIMAL {
+      return self.to_string_radix(radix);
+    }
+    let digits = match &self.inner {
+      NumberImpl::Integer(_) | NumberImpl::Ratio(_) => None,
+      NumberImpl::Float(f) => DecimalDigits::from_f64(*f),
+      NumberImpl::BigFloat(b) => Some(DecimalDigits::from_big_float(b)),
+    };
+    digits.and_then(|digits| format.format(&digits, rounding_mode))
+      .unwrap_or_else(|| self.to_string_radix(radix))
+  }
+}
+
+/// Rounds an exact value to the given number of decimal places. See
+/// [`Number::round_to_places`].
+fn round_rational(value: &BigRational, places: i64, rounding_mode: RoundingMode) -> BigRational {
+  let scale = BigRational::from_integer(BigInt::from(10).pow(places.unsigned_abs() as u32));
+  let scaled = if places >= 0 { value * &scale } else { value / &scale };
+  let magnitude = scaled.abs();
+  let truncated = magnitude.trunc();
+  let remainder = &magnitude - &truncated;
+  let discarded = if remainder.is_zero() {
+    Discarded::Zero
+  } else {
+    match (remainder * BigRational::from_integer(BigInt::from(2))).cmp(&BigRational::one()) {
+      Ordering::Less => Discarded::BelowHalf,
+      Ordering::Equal => Discarded::Half,
+      Ordering::Greater => Discarded::AboveHalf,
+    }
+  };
+  let mut result = truncated.to_integer();
+  if rounding_mode.rounds_away(discarded, result.is_odd()) {
+    result += 1;
+  }
+  if value.is_negative() {
+    result = -result;
+  }
+  let result = BigRational::from_integer(result);
+  if places >= 0 { result / scale } else { result * scale }
 }
 
 fn digits_to_string_radix(mut digits: Digits, is_floating: bool, radix: Radix) -> String {
@@ -439,6 +681,14 @@ impl From<BigRational> for Number {
   }
 }
 
+/// Constructs an arbitrary-precision floating-point number from a
+/// [`BigFloat`] value.
+impl From<BigFloat> for Number {
+  fn from(b: BigFloat) -> Number {
+    Number { inner: NumberImpl::BigFloat(Box::new(b)) }
+  }
+}
+
 /// Constructs a floating-point number from an `f64` value.
 impl From<f64> for Number {
   fn from(f: f64) -> Number {
@@ -471,6 +721,9 @@ impl Display for Number {
           write!(f, "{}", d)
         }
       }
+      NumberImpl::BigFloat(b) => {
+        b.fmt(f)
+      }
     }
   }
 }
@@ -500,6 +753,7 @@ impl PartialEq for Number {
       NumberPair::Integers(left, right) => left == right,
       NumberPair::Ratios(left, right) => left == right,
       NumberPair::Floats(left, right) => left == right,
+      NumberPair::BigFloats(left, right) => left == right,
     }
   }
 }
@@ -583,7 +837,7 @@ impl Hash for Number {
           r.numer().hash(state);
           r.denom().hash(state);
         }
-        NumberImpl::Float(_) => unreachable!()
+        NumberImpl::Float(_) | NumberImpl::BigFloat(_) => unreachable!()
       }
     }
   }
@@ -603,6 +857,7 @@ impl Ord for Number {
       NumberPair::Integers(left, right) => left.cmp(&right),
       NumberPair::Ratios(left, right) => left.cmp(&right),
       NumberPair::Floats(left, right) => left.partial_cmp(&right).expect("f64 should be finite"),
+      NumberPair::BigFloats(left, right) => left.cmp(&right),
     }
   }
 }
@@ -615,6 +870,7 @@ impl ops::Add for Number {
       NumberPair::Integers(left, right) => Number::from(left + right),
       NumberPair::Ratios(left, right) => Number::from(left + right),
       NumberPair::Floats(left, right) => Number::from(left + right),
+      NumberPair::BigFloats(left, right) => Number::from(left + right),
     }
   }
 }
@@ -657,6 +913,7 @@ impl ops::Sub for Number {
       NumberPair::Integers(left, right) => Number::from(left - right),
       NumberPair::Ratios(left, right) => Number::from(left - right),
       NumberPair::Floats(left, right) => Number::from(left - right),
+      NumberPair::BigFloats(left, right) => Number::from(left - right),
     }
   }
 }
@@ -693,6 +950,7 @@ impl ops::Mul for Number {
       NumberPair::Integers(left, right) => Number::from(left * right),
       NumberPair::Ratios(left, right) => Number::from(left * right),
       NumberPair::Floats(left, right) => Number::from(left * right),
+      NumberPair::BigFloats(left, right) => Number::from(left * right),
     }
   }
 }
@@ -734,6 +992,7 @@ impl ops::Div for Number {
         Number::from(BigRational::from(left) / BigRational::from(right)),
       NumberPair::Ratios(left, right) => Number::from(left / right),
       NumberPair::Floats(left, right) => Number::from(left / right),
+      NumberPair::BigFloats(left, right) => Number::from(left / right),
     }
   }
 }
@@ -803,6 +1062,7 @@ impl ops::Rem for Number {
       NumberPair::Integers(left, right) => Number::from(left % right),
       NumberPair::Ratios(left, right) => Number::from(left % right),
       NumberPair::Floats(left, right) => Number::from(left % right),
+      NumberPair::BigFloats(left, right) => Number::from(left % right),
     };
     // Adjust sign to match divisor
     if result.signum() * other.signum() == Number::from(-1) {
@@ -829,6 +1089,7 @@ impl ops::Neg for Number {
       NumberImpl::Integer(i) => Number::from(- (*i)),
       NumberImpl::Ratio(r) => Number::from(- (*r)),
       NumberImpl::Float(f) => Number::from(- f),
+      NumberImpl::BigFloat(b) => Number::from(- *b),
     }
   }
 }
@@ -866,6 +1127,7 @@ impl Zero for Number {
       NumberImpl::Integer(i) => i.is_zero(),
       NumberImpl::Ratio(r) => r.is_zero(),
       NumberImpl::Float(f) => f.is_zero(),
+      NumberImpl::BigFloat(b) => b.is_zero(),
     }
   }
 }
@@ -879,6 +1141,7 @@ impl One for Number {
       NumberImpl::Integer(i) => i.is_one(),
       NumberImpl::Ratio(r) => r.is_one(),
       NumberImpl::Float(f) => f.is_one(),
+      NumberImpl::BigFloat(b) => b.is_one(),
     }
   }
 }
@@ -915,8 +1178,15 @@ fn parse_ratio(s: &str) -> Option<Number> {
   Some(Number::from(ratio))
 }
 
+/// Parses a floating-point literal. Literals with more significant
+/// digits than an `f64` can hold are parsed as a [`BigFloat`], so
+/// that no digits are lost.
 fn parse_float(s: &str) -> Option<Number> {
-  f64::from_str(s).map(Number::from).ok()
+  let f = f64::from_str(s).ok()?;
+  match BigFloat::from_str(s) {
+    Ok(b) if b.precision() > F64_PRECISION => Some(Number::from(b)),
+    _ => Some(Number::from(f)),
+  }
 }
 
 #[cfg(test)]
@@ -1274,4 +1544,84 @@ mod tests {
       hash(Number::ratio(big_int, 1)),
     );
   }
+
+  #[test]
+  fn test_parse_high_precision_float() {
+    let n: Number = "3.14159265358979323846264338327950".parse().unwrap();
+    assert_eq!(n.repr(), NumberRepr::BigFloat);
+    assert_eq!(n.big_float_precision(), Some(33));
+    assert_eq!(n.to_string(), "3.1415926535897932384626433832795");
+    let n: Number = "3.25".parse().unwrap();
+    assert_eq!(n.repr(), NumberRepr::Float);
+  }
+
+  #[test]
+  fn test_div_inexact_to_precision() {
+    let quotient = Number::from(1).div_inexact_to_precision(&Number::from(3), 25);
+    assert_eq!(quotient.big_float_precision(), Some(25));
+    assert_eq!(quotient.to_string(), "0.3333333333333333333333333");
+    let quotient = Number::from(6).div_inexact_to_precision(&Number::from(3), 25);
+    assert_strict_eq!(quotient, Number::from(2));
+  }
+
+  #[test]
+  fn test_big_float_contaminates() {
+    let a = Number::from(1).to_big_float(20);
+    let sum = a + Number::from(0.5);
+    assert_eq!(sum.repr(), NumberRepr::BigFloat);
+    assert_eq!(sum, Number::from(1.5));
+  }
+
+  #[test]
+  fn test_big_float_elementary_functions() {
+    let two = Number::from(2).to_big_float(30);
+    assert_eq!(two.ln().big_float_precision(), Some(30));
+    assert_eq!(two.ln().to_string(), "0.693147180559945309417232121458");
+    let one = Number::from(1).to_big_float(30);
+    assert_eq!(one.sin().to_string(), "0.84147098480789650665250232163");
+    assert_eq!(one.atan2(&Number::from(0)).to_string(), "1.57079632679489661923132169164");
+    assert_eq!(one.big_float_exp().unwrap().to_string(), "2.71828182845904523536028747135");
+    assert!(Number::from(1).big_float_exp().is_none());
+  }
+
+  #[test]
+  fn test_to_string_twos_complement() {
+    let u8 = WordSize::new(8, false);
+    let i16 = WordSize::new(16, true);
+    assert_eq!(Number::from(-1).to_string_twos_complement(Radix::HEXADECIMAL, u8).unwrap(), "16#FF");
+    assert_eq!(Number::from(5).to_string_twos_complement(Radix::BINARY, u8).unwrap(), "2#00000101");
+    assert_eq!(Number::from(-2).to_string_twos_complement(Radix::OCTAL, i16).unwrap(), "8#177776");
+    assert_eq!(Number::from(0).to_string_twos_complement(Radix::HEXADECIMAL, i16).unwrap(), "16#0000");
+    assert_eq!(Number::from(300).to_string_twos_complement(Radix::HEXADECIMAL, u8), None);
+    assert_eq!(Number::from(10).to_string_twos_complement(Radix::DECIMAL, u8), None);
+    assert_eq!(Number::ratio(1, 2).to_string_twos_complement(Radix::HEXADECIMAL, u8), None);
+  }
+
+  #[test]
+  fn test_round_to_places_exact() {
+    let mode = RoundingMode::HalfUp;
+    assert_strict_eq!(Number::from(7).round_to_places(2, mode), Number::from(7));
+    assert_strict_eq!(Number::from(1250).round_to_places(-2, mode), Number::from(1300));
+    assert_strict_eq!(Number::ratio(5, 2).round_to_places(0, mode), Number::from(3));
+    assert_strict_eq!(Number::ratio(-5, 2).round_to_places(0, mode), Number::from(-3));
+    assert_strict_eq!(Number::ratio(1, 3).round_to_places(2, mode), Number::ratio(33, 100));
+    assert_strict_eq!(Number::ratio(5, 2).round_to_places(0, RoundingMode::HalfEven), Number::from(2));
+    assert_strict_eq!(Number::ratio(7, 2).round_to_places(0, RoundingMode::HalfEven), Number::from(4));
+    assert_strict_eq!(Number::ratio(-7, 3).round_to_places(0, RoundingMode::TowardZero), Number::from(-2));
+    assert_strict_eq!(Number::ratio(-7, 3).round_to_places(0, RoundingMode::AwayFromZero), Number::from(-3));
+    assert_strict_eq!(Number::from(1201).round_to_places(-2, RoundingMode::AwayFromZero), Number::from(1300));
+  }
+
+  #[test]
+  fn test_round_to_places_inexact() {
+    let mode = RoundingMode::HalfUp;
+    assert_strict_eq!(Number::from(2.675).round_to_places(2, mode), Number::from(2.68));
+    assert_strict_eq!(Number::from(2.665).round_to_places(2, RoundingMode::HalfEven), Number::from(2.66));
+    assert_strict_eq!(Number::from(-0.5).round_to_places(0, mode), Number::from(-1.0));
+    assert_strict_eq!(Number::from(0.001).round_to_places(2, RoundingMode::AwayFromZero), Number::from(0.01));
+    let n: Number = Number::from(2.5).to_big_float(20);
+    let rounded = n.round_to_places(0, RoundingMode::HalfEven);
+    assert_eq!(rounded.big_float_precision(), Some(20));
+    assert_eq!(rounded, Number::from(2));
+  }
 }
@@ -13,6 +13,9 @@ pub enum NumberRepr {
   Ratio,
   /// An inexact IEEE 754 floating-point value.
   Float,
+  /// An inexact decimal value with a configurable number of
+  /// significant digits. See [`BigFloat`](super::bigfloat::BigFloat).
+  BigFloat,
 }
 
 impl NumberRepr {
@@ -23,6 +26,7 @@ impl NumberRepr {
       NumberRepr::Integer => true,
       NumberRepr::Ratio => true,
       NumberRepr::Float => false,
+      NumberRepr::BigFloat => false,
     }
   }
 }
@@ -4,9 +4,11 @@
 //! directly exposed outside of `crate::expr::number`.
 
 use super::real::{Number, NumberImpl};
+use super::bigfloat::BigFloat;
 use super::complex::ComplexNumber;
 use super::quaternion::Quaternion;
-use super::QuaternionLike;
+use super::octonion::Octonion;
+use super::{QuaternionLike, OctonionLike};
 
 use num::{BigInt, BigRational, ToPrimitive};
 
@@ -17,6 +19,7 @@ pub enum NumberPair {
   Integers(BigInt, BigInt),
   Ratios(BigRational, BigRational),
   Floats(f64, f64),
+  BigFloats(BigFloat, BigFloat),
 }
 
 /// Promotion of two [`QuaternionLike`] values to the greatest lower
@@ -28,6 +31,16 @@ pub enum QuaternionPair {
   Quaternions(Quaternion, Quaternion),
 }
 
+/// Promotion of two [`OctonionLike`] values to the greatest lower
+/// bound of the two. Pairs with no octonion component are left as
+/// [`QuaternionLike`] values, whose own arithmetic handles any
+/// further promotion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OctonionPair {
+  Quaternions(QuaternionLike, QuaternionLike),
+  Octonions(Octonion, Octonion),
+}
+
 impl NumberPair {
   /// Promote two numbers to a common representation, so we can do
   /// arithmetic on them.
@@ -35,6 +48,17 @@ impl NumberPair {
     use NumberImpl::*;
     use NumberPair::*;
     match (left.inner, right.inner) {
+      // Coerce both to big floats, at the precision of whichever
+      // side is already a big float
+      (NumberImpl::BigFloat(left), NumberImpl::BigFloat(right)) => BigFloats(*left, *right),
+      (NumberImpl::BigFloat(left), right) => {
+        let right = to_big_float(right, left.precision());
+        BigFloats(*left, right)
+      }
+      (left, NumberImpl::BigFloat(right)) => {
+        let left = to_big_float(left, right.precision());
+        BigFloats(left, *right)
+      }
       // Coerce both to integers
       (Integer(left), Integer(right)) => Integers(*left, *right),
       // Coerce both to rational
@@ -73,6 +97,25 @@ impl QuaternionPair {
   }
 }
 
+impl OctonionPair {
+  /// Promote two [`OctonionLike`] values to a common representation.
+  pub fn promote(left: OctonionLike, right: OctonionLike) -> OctonionPair {
+    match (left, right) {
+      (OctonionLike::Quaternion(left), OctonionLike::Quaternion(right)) => OctonionPair::Quaternions(left, right),
+      (left, right) => OctonionPair::Octonions(left.into(), right.into()),
+    }
+  }
+}
+
+fn to_big_float(n: NumberImpl, precision: u32) -> BigFloat {
+  match n {
+    NumberImpl::Integer(i) => BigFloat::from_rational(&int_to_rational(*i), precision),
+    NumberImpl::Ratio(r) => BigFloat::from_rational(&r, precision),
+    NumberImpl::Float(f) => BigFloat::from_f64(f, precision),
+    NumberImpl::BigFloat(b) => *b,
+  }
+}
+
 fn int_to_rational(i: BigInt) -> BigRational {
   BigRational::from_integer(i)
 }
@@ -3,9 +3,10 @@
 
 use super::Expr;
 use super::atom::Atom;
-use super::number::{ComplexNumber, Quaternion};
+use super::number::{ComplexNumber, Quaternion, Octonion};
 use super::vector::borrowed::BorrowedVector;
 use super::interval::IntervalType;
+use super::modular::ModularInt;
 
 pub use super::algebra::infinity::{is_infinite_constant, is_signed_infinite_constant};
 
@@ -56,6 +57,20 @@ pub fn is_quaternion(expr: &Expr) -> bool {
   false
 }
 
+/// Returns true if `expr` is a real, complex, quaternion, or octonion
+/// literal.
+pub fn is_octonion(expr: &Expr) -> bool {
+  if is_quaternion(expr) {
+    return true;
+  }
+  if let Expr::Call(f, args) = expr {
+    if f == Octonion::FUNCTION_NAME && args.len() == 8 {
+      return true;
+    }
+  }
+  false
+}
+
 /// Returns true if `expr` is an [`Expr::Call`] whose head is the
 /// vector function.
 pub fn is_vector(expr: &Expr) -> bool {
@@ -88,3 +103,9 @@ pub fn is_unbounded_interval(expr: &Expr) -> bool {
 pub fn is_unbounded_interval_like(expr: &Expr) -> bool {
   is_unbounded_interval(expr) || is_unbounded_number(expr)
 }
+
+/// Returns true if `expr` is a modular integer in canonical form, per
+/// [`ModularInt::parse`].
+pub fn is_modular_int(expr: &Expr) -> bool {
+  ModularInt::parse(expr.clone()).is_ok()
+}
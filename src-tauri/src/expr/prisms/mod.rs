@@ -6,9 +6,10 @@ pub use matcher::{MatcherSpec, MatchedExpr};
 use super::Expr;
 use super::var::Var;
 use super::atom::Atom;
-use super::number::{Number, ComplexNumber, Quaternion, ComplexLike, QuaternionLike};
+use super::number::{Number, ComplexNumber, Quaternion, Octonion, ComplexLike, QuaternionLike, OctonionLike};
 use super::interval::{RawInterval, IntervalOrScalar};
 use super::literal::Literal;
+use super::modular::ModularInt;
 use super::incomplete::IncompleteObject;
 use super::algebra::formula::{Formula, Equation};
 use super::algebra::infinity::InfiniteConstant;
@@ -16,15 +17,19 @@ use crate::util::prism::{Prism, PrismExt, Iso, OnVec, OnTuple2, Only, Conversion
                          LosslessConversion, VecToArray};
 use crate::graphics::GRAPHICS_NAME;
 
-use num::{Zero, One};
+use num::{Zero, One, BigInt};
 use either::Either;
 
 // Re-export some useful expression-adjacent prisms.
 pub use super::var::StringToVar;
 pub use super::vector::ExprToVector;
+pub use super::set::ExprToSet;
+pub use super::tuple::ExprToTuple;
+pub use super::modular::ExprToModularInt;
 pub use super::vector::matrix::{ExprToTypedMatrix, expr_to_matrix};
 pub use super::vector::tensor::ExprToTensor;
-pub use super::number::prisms::{NumberToUsize, NumberToI64};
+pub use super::vector::packed::ExprToPackedTensor;
+pub use super::number::prisms::{NumberToUsize, NumberToU8, NumberToI64, NumberToBigInt};
 pub use super::algebra::infinity::{ExprToInfinity, UnboundedNumber,
                                    infinity_to_signed_infinity,
                                    expr_to_signed_infinity, expr_to_unbounded_number};
@@ -67,6 +72,11 @@ pub struct ExprToComplex;
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ExprToQuaternion;
 
+/// Prism which downcasts an [`Expr`] to an [`OctonionLike`]: a real
+/// number, complex number, quaternion, or octonion.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExprToOctonion;
+
 /// Prism which only accepts expressions which are a [`Var`].
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ExprToVar;
@@ -83,6 +93,18 @@ pub struct PositiveNumber {
   data: Number,
 }
 
+/// Prism which downcasts a [`Number`] to a [`NonNegativeNumber`].
+/// Fails on negative numbers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NumberToNonNegativeNumber;
+
+/// A real number which is guaranteed to be zero or positive. This is
+/// the result type of the [`expr_to_nonnegative_number`] prism.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonNegativeNumber {
+  data: Number,
+}
+
 /// Prism which reads a string as a non-negative integer.
 #[derive(Debug, Clone)]
 pub struct StringToUsize;
@@ -139,18 +161,48 @@ pub fn expr_to_positive_number() -> impl Prism<Expr, PositiveNumber> + Clone {
   expr_to_number().composed(NumberToPositiveNumber)
 }
 
+/// Prism which accepts only real numbers which are zero or positive.
+pub fn expr_to_nonnegative_number() -> impl Prism<Expr, NonNegativeNumber> + Clone {
+  expr_to_number().composed(NumberToNonNegativeNumber)
+}
+
 /// Prism which only accepts expressions containing [`Number`] values
 /// representable by a `usize`.
 pub fn expr_to_usize() -> impl Prism<Expr, usize> + Clone {
   expr_to_number().composed(NumberToUsize)
 }
 
+/// Prism which only accepts expressions containing integer
+/// [`Number`] values, of any size.
+pub fn expr_to_bigint() -> impl Prism<Expr, BigInt> + Clone {
+  expr_to_number().composed(NumberToBigInt)
+}
+
+/// Prism which accepts either a [`ModularInt`] or an ordinary
+/// integer. Arithmetic functions use this to combine modular integers
+/// with integer constants.
+pub fn expr_to_modular_like() -> impl Prism<Expr, Either<ModularInt, BigInt>> + Clone {
+  ExprToModularInt.or(expr_to_bigint())
+}
+
 /// Prism which only accepts expressions containing [`Number`] values
 /// representable by an `i64`.
 pub fn expr_to_i64() -> impl Prism<Expr, i64> + Clone {
   expr_to_number().composed(NumberToI64)
 }
 
+/// Prism which only accepts expressions containing [`Number`] values
+/// representable by a `u8`.
+pub fn expr_to_u8() -> impl Prism<Expr, u8> + Clone {
+  expr_to_number().composed(NumberToU8)
+}
+
+/// Prism which accepts a sequence of bytes, represented as a vector
+/// of integers from 0 to 255.
+pub fn expr_to_bytes() -> impl Prism<Expr, Vec<u8>> + Clone {
+  expr_to_typed_vector(expr_to_u8())
+}
+
 /// Prism which accepts [`Literal`] values.
 pub fn expr_to_literal() -> Conversion<Expr, Literal> {
   Conversion::new()
@@ -248,6 +300,18 @@ impl PositiveNumber {
   }
 }
 
+impl NonNegativeNumber {
+  /// Creates a `NonNegativeNumber`, or returns the input number
+  /// unmodified if the value is negative.
+  pub fn new(number: Number) -> Result<Self, Number> {
+    if number >= Number::zero() {
+      Ok(NonNegativeNumber { data: number })
+    } else {
+      Err(number)
+    }
+  }
+}
+
 impl LiteralZero {
   pub fn new(arg: Expr) -> Result<LiteralZero, Expr> {
     ExprToZero.narrow_type(arg)
@@ -304,6 +368,12 @@ impl From<PositiveNumber> for Number {
   }
 }
 
+impl From<NonNegativeNumber> for Number {
+  fn from(arg: NonNegativeNumber) -> Self {
+    arg.data
+  }
+}
+
 impl From<LiteralZero> for Expr {
   fn from(arg: LiteralZero) -> Self {
     arg.expr
@@ -410,6 +480,33 @@ impl Prism<Expr, QuaternionLike> for ExprToQuaternion {
   }
 }
 
+impl Prism<Expr, OctonionLike> for ExprToOctonion {
+  fn narrow_type(&self, input: Expr) -> Result<OctonionLike, Expr> {
+    let input = match ExprToQuaternion.narrow_type(input) {
+      Ok(quat) => return Ok(OctonionLike::Quaternion(quat)),
+      Err(input) => input,
+    };
+    match input {
+      Expr::Call(function_name, args) if function_name == Octonion::FUNCTION_NAME && args.len() == 8 => {
+        if !args.iter().all(|arg| matches!(arg, Expr::Atom(Atom::Number(_)))) {
+          return Err(Expr::Call(function_name, args));
+        }
+        let components = args.into_iter()
+          .map(|arg| expr_to_number().narrow_type(arg).unwrap()) // unwrap: checked above
+          .collect::<Vec<_>>()
+          .try_into()
+          .unwrap(); // unwrap: checked arity above
+        Ok(OctonionLike::Octonion(Box::new(Octonion::from_components(components))))
+      }
+      input => Err(input),
+    }
+  }
+
+  fn widen_type(&self, input: OctonionLike) -> Expr {
+    input.into()
+  }
+}
+
 impl Prism<Expr, Var> for ExprToVar {
   fn narrow_type(&self, input: Expr) -> Result<Var, Expr> {
     if let Expr::Atom(Atom::Var(var)) = input {
@@ -432,6 +529,15 @@ impl Prism<Number, PositiveNumber> for NumberToPositiveNumber {
   }
 }
 
+impl Prism<Number, NonNegativeNumber> for NumberToNonNegativeNumber {
+  fn narrow_type(&self, input: Number) -> Result<NonNegativeNumber, Number> {
+    NonNegativeNumber::new(input)
+  }
+  fn widen_type(&self, input: NonNegativeNumber) -> Number {
+    input.into()
+  }
+}
+
 impl Prism<String, ParsedUsize> for StringToUsize {
   fn narrow_type(&self, input: String) -> Result<ParsedUsize, String> {
     if let Ok(value) = input.parse() {
@@ -577,6 +683,36 @@ mod tests {
     assert!(prism.narrow_type(Expr::var("abc").unwrap()).is_err());
   }
 
+  #[test]
+  fn test_expr_to_octonion_narrow() {
+    let prism = ExprToOctonion;
+    assert_eq!(
+      prism.narrow_type(Expr::call("oct", (1..=8).map(Expr::from).collect())).unwrap(),
+      OctonionLike::Octonion(Box::new(Octonion::from_components([1, 2, 3, 4, 5, 6, 7, 8].map(Number::from)))),
+    );
+    assert_eq!(
+      prism.narrow_type(Expr::call("quat", vec![Expr::from(1), Expr::from(4), Expr::from(7), Expr::from(9)])).unwrap(),
+      OctonionLike::Quaternion(QuaternionLike::Quaternion(Quaternion::new(1, 4, 7, 9))),
+    );
+    assert!(prism.narrow_type(Expr::call("oct", (1..=7).map(Expr::from).collect())).is_err());
+    let mut args: Vec<_> = (1..=7).map(Expr::from).collect();
+    args.push(Expr::var("x").unwrap());
+    assert!(prism.narrow_type(Expr::call("oct", args)).is_err());
+  }
+
+  #[test]
+  fn test_expr_to_octonion_widen() {
+    let prism = ExprToOctonion;
+    assert_eq!(
+      prism.widen_type(OctonionLike::Octonion(Box::new(Octonion::basis(5)))),
+      Expr::call("oct", [0, 0, 0, 0, 0, 1, 0, 0].into_iter().map(Expr::from).collect()),
+    );
+    assert_eq!(
+      prism.widen_type(OctonionLike::Quaternion(QuaternionLike::Real(Number::from(3)))),
+      Expr::from(3),
+    );
+  }
+
   #[test]
   fn test_expr_to_var() {
     fn var(s: &str) -> Var {
@@ -614,6 +750,22 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_number_to_nonnegative_number() {
+    assert_eq!(
+      NumberToNonNegativeNumber.narrow_type(Number::from(9)).unwrap(),
+      NonNegativeNumber { data: Number::from(9) },
+    );
+    assert_eq!(
+      NumberToNonNegativeNumber.narrow_type(Number::from(0)).unwrap(),
+      NonNegativeNumber { data: Number::from(0) },
+    );
+    assert_eq!(
+      NumberToNonNegativeNumber.narrow_type(Number::from(-9)).unwrap_err(),
+      Number::from(-9),
+    );
+  }
+
   #[test]
   fn test_string_to_usize() {
     assert_eq!(StringToUsize.widen_type(ParsedUsize { value: 3, input: String::from("3") }), String::from("3"));
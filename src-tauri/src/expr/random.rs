@@ -0,0 +1,161 @@
+
+//! Generating random expressions from a simple grammar, for practice
+//! problems and for exercising the simplifier.
+
+use super::Expr;
+use super::number::Number;
+use super::var::Var;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use thiserror::Error;
+
+/// The operators which may appear in a random expression, together
+/// with their arities.
+pub const RANDOM_OPERATORS: &[(&str, usize)] = &[
+  ("+", 2),
+  ("-", 2),
+  ("*", 2),
+  ("/", 2),
+  ("^", 2),
+  ("negate", 1),
+  ("abs", 1),
+  ("sqrt", 1),
+  ("exp", 1),
+  ("ln", 1),
+  ("sin", 1),
+  ("cos", 1),
+  ("tan", 1),
+];
+
+/// The largest integer constant generated by default.
+pub const DEFAULT_MAX_CONSTANT: u32 = 9;
+
+/// Description of the random expressions to generate.
+///
+/// Leaves of the expression are positive integer constants no larger
+/// than `max_constant` and variables from `variables`. Interior nodes
+/// are calls to `operators`. No branch of the expression is deeper
+/// than `max_depth`, where a lone leaf has depth zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RandomExprGrammar {
+  pub max_depth: usize,
+  operators: Vec<(&'static str, usize)>,
+  pub variables: Vec<Var>,
+  pub max_constant: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Unknown operator for random expressions: {0}")]
+pub struct UnknownOperatorError(pub String);
+
+impl RandomExprGrammar {
+  /// A grammar using the named operators, each of which must appear
+  /// in [`RANDOM_OPERATORS`].
+  pub fn new<S: AsRef<str>>(
+    max_depth: usize,
+    operator_names: &[S],
+    variables: Vec<Var>,
+  ) -> Result<Self, UnknownOperatorError> {
+    let operators = operator_names.iter()
+      .map(|name| {
+        let name = name.as_ref();
+        RANDOM_OPERATORS.iter()
+          .find(|(op, _)| *op == name)
+          .copied()
+          .ok_or_else(|| UnknownOperatorError(name.to_owned()))
+      })
+      .collect::<Result<_, _>>()?;
+    Ok(Self { max_depth, operators, variables, max_constant: DEFAULT_MAX_CONSTANT })
+  }
+
+  /// Generates an expression. The root is an operator call whenever
+  /// the depth and operator list allow it. Below the root, each node
+  /// is a leaf with probability one in four, so expressions vary in
+  /// shape rather than always being complete trees.
+  pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Expr {
+    self.generate_at_depth(rng, self.max_depth, true)
+  }
+
+  fn generate_at_depth<R: Rng + ?Sized>(&self, rng: &mut R, depth: usize, is_root: bool) -> Expr {
+    let stop_early = !is_root && rng.gen_ratio(1, 4);
+    let Some(&(name, arity)) = self.operators.choose(rng).filter(|_| depth > 0 && !stop_early) else {
+      return self.generate_leaf(rng);
+    };
+    let args = (0..arity).map(|_| self.generate_at_depth(rng, depth - 1, false)).collect();
+    Expr::call(name, args)
+  }
+
+  fn generate_leaf<R: Rng + ?Sized>(&self, rng: &mut R) -> Expr {
+    // Variables and constants are equally likely, if both are
+    // available.
+    if !self.variables.is_empty() && rng.gen_bool(0.5) {
+      let var = self.variables.choose(rng).expect("variable list should be nonempty");
+      Expr::from(var.clone())
+    } else {
+      let max_constant = self.max_constant.max(1);
+      Expr::from(Number::from(rng.gen_range(1..=max_constant) as usize))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use rand::SeedableRng;
+  use rand::rngs::StdRng;
+
+  fn depth(expr: &Expr) -> usize {
+    match expr {
+      Expr::Call(_, args) => 1 + args.iter().map(depth).max().unwrap_or(0),
+      Expr::Atom(_) => 0,
+    }
+  }
+
+  fn uses_only(expr: &Expr, operators: &[(&str, usize)], variables: &[&str]) -> bool {
+    match expr {
+      Expr::Call(name, args) => {
+        operators.contains(&(name.as_str(), args.len())) &&
+          args.iter().all(|arg| uses_only(arg, operators, variables))
+      }
+      Expr::Atom(_) => {
+        (1..=DEFAULT_MAX_CONSTANT).any(|n| *expr == Expr::from(n as i64)) ||
+          variables.iter().any(|v| *expr == Expr::var(v).unwrap())
+      }
+    }
+  }
+
+  fn vars(names: &[&str]) -> Vec<Var> {
+    names.iter().map(|name| Var::new(*name).unwrap()).collect()
+  }
+
+  #[test]
+  fn test_unknown_operator() {
+    assert_eq!(
+      RandomExprGrammar::new(3, &["+", "frobnicate"], vec![]),
+      Err(UnknownOperatorError(String::from("frobnicate"))),
+    );
+  }
+
+  #[test]
+  fn test_generated_expressions_respect_grammar() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let grammar = RandomExprGrammar::new(4, &["+", "*", "sin"], vars(&["x", "y"])).unwrap();
+    for _ in 0..200 {
+      let expr = grammar.generate(&mut rng);
+      assert!((1..=4).contains(&depth(&expr)), "bad depth in {expr}");
+      assert!(uses_only(&expr, &[("+", 2), ("*", 2), ("sin", 1)], &["x", "y"]), "bad expression {expr}");
+    }
+  }
+
+  #[test]
+  fn test_degenerate_grammars() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let grammar = RandomExprGrammar::new(0, &["+"], vars(&["x"])).unwrap();
+    assert_eq!(depth(&grammar.generate(&mut rng)), 0);
+    let grammar = RandomExprGrammar::new::<&str>(5, &[], vec![]).unwrap();
+    let expr = grammar.generate(&mut rng);
+    assert!((1..=DEFAULT_MAX_CONSTANT).any(|n| expr == Expr::from(n as i64)));
+  }
+}
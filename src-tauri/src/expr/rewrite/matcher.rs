@@ -0,0 +1,114 @@
+
+//! Structural matching of expressions against patterns.
+
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::var::Var;
+use crate::expr::var::table::VarTable;
+
+/// The values bound to pattern variables by a successful match.
+pub type Bindings = VarTable<Expr>;
+
+/// A pattern variable is a variable whose name ends in an
+/// underscore, such as `x_`. In a pattern, a pattern variable matches
+/// any expression, while every other part of the pattern must match
+/// exactly.
+pub fn is_pattern_var(var: &Var) -> bool {
+  var.as_str().ends_with('_')
+}
+
+/// Matches `target` against `pattern`, returning the values bound to
+/// each pattern variable if the match succeeds.
+///
+/// Matching is purely structural: function calls match if they have
+/// the same name and arity and their arguments match pairwise, and
+/// atoms match only if they are equal. A pattern variable which
+/// appears more than once must match the same expression each time,
+/// so `f(x_, x_)` matches `f(1, 1)` but not `f(1, 2)`. No algebraic
+/// identities are used, so `a_ + b_` does not match `x * y`, and
+/// arguments of commutative operators are not reordered.
+pub fn match_pattern(pattern: &Expr, target: &Expr) -> Option<Bindings> {
+  let mut bindings = Bindings::new();
+  if match_into(pattern, target, &mut bindings) {
+    Some(bindings)
+  } else {
+    None
+  }
+}
+
+fn match_into(pattern: &Expr, target: &Expr, bindings: &mut Bindings) -> bool {
+  match pattern {
+    Expr::Atom(Atom::Var(var)) if is_pattern_var(var) => {
+      match bindings.get(var) {
+        Some(existing) => existing == target,
+        None => {
+          bindings.insert(var.clone(), target.clone());
+          true
+        }
+      }
+    }
+    Expr::Atom(atom) => {
+      matches!(target, Expr::Atom(target_atom) if atom == target_atom)
+    }
+    Expr::Call(f, args) => {
+      let Expr::Call(g, target_args) = target else {
+        return false;
+      };
+      f == g &&
+        args.len() == target_args.len() &&
+        args.iter().zip(target_args).all(|(arg, target_arg)| match_into(arg, target_arg, bindings))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> Expr {
+    Expr::var(name).unwrap()
+  }
+
+  #[test]
+  fn test_is_pattern_var() {
+    assert!(is_pattern_var(&Var::new("x_").unwrap()));
+    assert!(!is_pattern_var(&Var::new("x").unwrap()));
+  }
+
+  #[test]
+  fn test_match_literal() {
+    let pattern = Expr::call("f", vec![Expr::from(1), var("y")]);
+    assert!(match_pattern(&pattern, &pattern).unwrap().is_empty());
+    assert!(match_pattern(&pattern, &Expr::call("f", vec![Expr::from(2), var("y")])).is_none());
+    assert!(match_pattern(&pattern, &Expr::call("g", vec![Expr::from(1), var("y")])).is_none());
+    assert!(match_pattern(&pattern, &Expr::call("f", vec![Expr::from(1)])).is_none());
+  }
+
+  #[test]
+  fn test_match_pattern_vars() {
+    let pattern = Expr::call("+", vec![var("a_"), Expr::call("^", vec![var("b_"), Expr::from(2)])]);
+    let target = Expr::call("+", vec![
+      Expr::call("sin", vec![var("x")]),
+      Expr::call("^", vec![var("y"), Expr::from(2)]),
+    ]);
+    let bindings = match_pattern(&pattern, &target).unwrap();
+    assert_eq!(bindings.len(), 2);
+    assert_eq!(bindings.get(&Var::new("a_").unwrap()), Some(&Expr::call("sin", vec![var("x")])));
+    assert_eq!(bindings.get(&Var::new("b_").unwrap()), Some(&var("y")));
+  }
+
+  #[test]
+  fn test_match_repeated_pattern_var() {
+    let pattern = Expr::call("f", vec![var("x_"), var("x_")]);
+    let bindings = match_pattern(&pattern, &Expr::call("f", vec![var("z"), var("z")])).unwrap();
+    assert_eq!(bindings.get(&Var::new("x_").unwrap()), Some(&var("z")));
+    assert!(match_pattern(&pattern, &Expr::call("f", vec![var("z"), var("w")])).is_none());
+  }
+
+  #[test]
+  fn test_pattern_var_does_not_match_structure() {
+    let pattern = Expr::call("f", vec![var("x_")]);
+    assert!(match_pattern(&pattern, &var("x")).is_none());
+    assert!(match_pattern(&var("x"), &var("x_")).is_none());
+  }
+}
@@ -0,0 +1,193 @@
+
+//! User-defined rewrite rules, in the style of Emacs Calc.
+
+pub mod matcher;
+
+use matcher::{match_pattern, is_pattern_var};
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::var::Var;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+/// A rewrite rule, written `lhs := rhs` in the expression language.
+/// Any expression which matches the left-hand side (per
+/// [`match_pattern`]) is replaced by the right-hand side, with the
+/// pattern variables substituted for the values they matched. For
+/// instance, the rule `f(x_, x_) := 2 * f(x_)` rewrites `f(y, y)` to
+/// `2 * f(y)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewriteRule {
+  lhs: Expr,
+  rhs: Expr,
+}
+
+/// An ordered collection of rewrite rules. Where several rules match
+/// the same expression, the earliest one takes effect.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewriteRuleTable {
+  rules: Vec<RewriteRule>,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RewriteRuleError {
+  #[error("Expected a rewrite rule of the form lhs := rhs, got {0}")]
+  ExpectedRule(Expr),
+  #[error("Left-hand side of a rewrite rule cannot be a lone pattern variable")]
+  LonePatternVar,
+  #[error("Pattern variable {0} does not appear on the left-hand side")]
+  UnboundPatternVar(Var),
+}
+
+impl RewriteRule {
+  pub const FUNCTION_NAME: &'static str = ":=";
+
+  /// Constructs a rewrite rule. Fails if the left-hand side would
+  /// match every expression, or if the right-hand side uses a pattern
+  /// variable that the left-hand side never binds.
+  pub fn new(lhs: Expr, rhs: Expr) -> Result<Self, RewriteRuleError> {
+    if matches!(&lhs, Expr::Atom(Atom::Var(var)) if is_pattern_var(var)) {
+      return Err(RewriteRuleError::LonePatternVar);
+    }
+    let lhs_vars = lhs.clone().free_vars();
+    let mut rhs_vars: Vec<_> = rhs.clone().free_vars().into_iter()
+      .filter(|var| is_pattern_var(var) && !lhs_vars.contains(var))
+      .collect();
+    rhs_vars.sort();
+    if let Some(var) = rhs_vars.into_iter().next() {
+      return Err(RewriteRuleError::UnboundPatternVar(var));
+    }
+    Ok(Self { lhs, rhs })
+  }
+
+  /// Interprets an expression of the form `lhs := rhs` as a rewrite
+  /// rule.
+  pub fn from_expr(expr: Expr) -> Result<Self, RewriteRuleError> {
+    match expr {
+      Expr::Call(name, args) if name == RewriteRule::FUNCTION_NAME && args.len() == 2 => {
+        let [lhs, rhs] = args.try_into().unwrap(); // unwrap: Just checked the length
+        Self::new(lhs, rhs)
+      }
+      expr => Err(RewriteRuleError::ExpectedRule(expr)),
+    }
+  }
+
+  pub fn lhs(&self) -> &Expr {
+    &self.lhs
+  }
+
+  pub fn rhs(&self) -> &Expr {
+    &self.rhs
+  }
+
+  /// Rewrites `expr` if it matches this rule's left-hand side. Only
+  /// the expression itself is considered, not its subexpressions.
+  pub fn apply(&self, expr: &Expr) -> Option<Expr> {
+    let bindings = match_pattern(&self.lhs, expr)?;
+    Some(self.rhs.clone().substitute_vars(&bindings))
+  }
+
+  pub fn into_expr(self) -> Expr {
+    Expr::call(RewriteRule::FUNCTION_NAME, vec![self.lhs, self.rhs])
+  }
+}
+
+impl RewriteRuleTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.rules.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rules.is_empty()
+  }
+
+  /// Adds a rule with lower priority than every existing rule.
+  pub fn push(&mut self, rule: RewriteRule) {
+    self.rules.push(rule);
+  }
+
+  /// Removes the most recently added rule.
+  pub fn pop(&mut self) -> Option<RewriteRule> {
+    self.rules.pop()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &RewriteRule> {
+    self.rules.iter()
+  }
+
+  /// Rewrites `expr` using the first rule which matches it, if any.
+  pub fn apply(&self, expr: &Expr) -> Option<Expr> {
+    self.rules.iter().find_map(|rule| rule.apply(expr))
+  }
+}
+
+impl From<RewriteRule> for Expr {
+  fn from(rule: RewriteRule) -> Expr {
+    rule.into_expr()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> Expr {
+    Expr::var(name).unwrap()
+  }
+
+  fn sample_rule() -> RewriteRule {
+    RewriteRule::new(
+      Expr::call("f", vec![var("x_"), var("x_")]),
+      Expr::call("*", vec![Expr::from(2), Expr::call("f", vec![var("x_")])]),
+    ).unwrap()
+  }
+
+  #[test]
+  fn test_apply() {
+    let rule = sample_rule();
+    assert_eq!(
+      rule.apply(&Expr::call("f", vec![var("y"), var("y")])),
+      Some(Expr::call("*", vec![Expr::from(2), Expr::call("f", vec![var("y")])])),
+    );
+    assert_eq!(rule.apply(&Expr::call("f", vec![var("y"), var("z")])), None);
+  }
+
+  #[test]
+  fn test_from_expr() {
+    let expr = sample_rule().into_expr();
+    assert_eq!(RewriteRule::from_expr(expr).unwrap(), sample_rule());
+    let expr = Expr::call("=", vec![var("x"), var("y")]);
+    assert_eq!(RewriteRule::from_expr(expr.clone()), Err(RewriteRuleError::ExpectedRule(expr)));
+  }
+
+  #[test]
+  fn test_invalid_rules() {
+    assert_eq!(
+      RewriteRule::new(var("x_"), Expr::from(0)),
+      Err(RewriteRuleError::LonePatternVar),
+    );
+    assert_eq!(
+      RewriteRule::new(Expr::call("f", vec![var("x_")]), var("y_")),
+      Err(RewriteRuleError::UnboundPatternVar(Var::new("y_").unwrap())),
+    );
+    // Ordinary variables on the right-hand side are fine.
+    RewriteRule::new(Expr::call("f", vec![var("x_")]), var("y")).unwrap();
+  }
+
+  #[test]
+  fn test_table_uses_first_matching_rule() {
+    let mut table = RewriteRuleTable::new();
+    assert_eq!(table.apply(&var("a")), None);
+    table.push(RewriteRule::new(Expr::call("g", vec![Expr::from(0)]), Expr::from(1)).unwrap());
+    table.push(RewriteRule::new(Expr::call("g", vec![var("x_")]), var("x_")).unwrap());
+    assert_eq!(table.apply(&Expr::call("g", vec![Expr::from(0)])), Some(Expr::from(1)));
+    assert_eq!(table.apply(&Expr::call("g", vec![Expr::from(5)])), Some(Expr::from(5)));
+    assert_eq!(table.apply(&Expr::call("h", vec![Expr::from(5)])), None);
+  }
+}
@@ -0,0 +1,220 @@
+
+//! Finite mathematical sets of expressions.
+
+use super::Expr;
+use super::literal::Literal;
+use super::ordering::cmp_expr;
+use crate::util::prism::Prism;
+
+use thiserror::Error;
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+/// A `Set` is an unordered collection of expressions without
+/// duplicates.
+///
+/// A `Set` is represented in the expression language as a call to the
+/// function called "set". Internally, the elements are always stored
+/// in canonical order (per [`cmp_expr`]) with duplicates removed, so
+/// two sets with the same elements are structurally equal.
+///
+/// Elements are compared structurally. A set containing symbolic
+/// elements, such as `set(x, y)`, may have fewer distinct elements
+/// than it appears to; see [`Set::is_literal`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Set {
+  data: Vec<Expr>,
+}
+
+/// Prism which accepts only sets (i.e. expressions which are calls to
+/// a function called "set").
+///
+/// Delegates to [`Set::parse`] for narrowing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExprToSet;
+
+#[derive(Debug, Error)]
+#[error("Expected a set, got {original_expr}")]
+pub struct ParseSetError {
+  pub original_expr: Expr,
+  _priv: (),
+}
+
+impl Set {
+  pub const FUNCTION_NAME: &'static str = "set";
+
+  /// A new, empty `Set`.
+  pub fn empty() -> Self {
+    Self { data: vec![] }
+  }
+
+  /// If the expression is a function call of the form `set(...)`,
+  /// returns a [`Set`] containing the arguments. If the expression is
+  /// of any other form, returns an appropriate error.
+  pub fn parse(expr: Expr) -> Result<Set, ParseSetError> {
+    match expr {
+      Expr::Call(name, args) if name == Set::FUNCTION_NAME => Ok(Set::from_iter(args)),
+      expr => Err(ParseSetError { original_expr: expr, _priv: () }),
+    }
+  }
+
+  /// Returns true if the elements are in canonical order and contain
+  /// no duplicates, i.e. if `set(...)` with these arguments is
+  /// already in normal form.
+  pub fn is_canonical(elements: &[Expr]) -> bool {
+    elements.windows(2).all(|w| cmp_expr(&w[0], &w[1]) == Ordering::Less)
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Expr> {
+    self.data.iter()
+  }
+
+  pub fn as_slice(&self) -> &[Expr] {
+    &self.data
+  }
+
+  /// Returns true if every element of this set is a [`Literal`].
+  /// For such sets, structural equality coincides with mathematical
+  /// equality, so membership and cardinality can be decided.
+  pub fn is_literal(&self) -> bool {
+    self.data.iter().all(|elem| Literal::try_from(elem.clone()).is_ok())
+  }
+
+  pub fn contains(&self, value: &Expr) -> bool {
+    self.data.binary_search_by(|elem| cmp_expr(elem, value)).is_ok()
+  }
+
+  pub fn union(self, other: Set) -> Set {
+    self.data.into_iter().chain(other.data).collect()
+  }
+
+  pub fn intersection(self, other: &Set) -> Set {
+    self.data.into_iter().filter(|elem| other.contains(elem)).collect()
+  }
+
+  pub fn difference(self, other: &Set) -> Set {
+    self.data.into_iter().filter(|elem| !other.contains(elem)).collect()
+  }
+
+  /// The set of all subsets of `self`. The result has `2^n` elements,
+  /// so callers should check the size of `self` first.
+  pub fn powerset(&self) -> Set {
+    let n = self.data.len();
+    (0..(1usize << n))
+      .map(|mask| {
+        let subset: Set = self.data.iter()
+          .enumerate()
+          .filter(|(i, _)| mask & (1 << i) != 0)
+          .map(|(_, elem)| elem.clone())
+          .collect();
+        subset.into_expr()
+      })
+      .collect()
+  }
+
+  pub fn into_expr(self) -> Expr {
+    Expr::call(Set::FUNCTION_NAME, self.data)
+  }
+}
+
+impl FromIterator<Expr> for Set {
+  fn from_iter<I: IntoIterator<Item = Expr>>(iter: I) -> Self {
+    let mut data: Vec<Expr> = iter.into_iter().collect();
+    data.sort_by(cmp_expr);
+    data.dedup();
+    Set { data }
+  }
+}
+
+impl IntoIterator for Set {
+  type Item = Expr;
+  type IntoIter = std::vec::IntoIter<Expr>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.data.into_iter()
+  }
+}
+
+impl From<Set> for Expr {
+  fn from(set: Set) -> Expr {
+    set.into_expr()
+  }
+}
+
+impl Prism<Expr, Set> for ExprToSet {
+  fn narrow_type(&self, input: Expr) -> Result<Set, Expr> {
+    Set::parse(input).map_err(|err| err.original_expr)
+  }
+  fn widen_type(&self, input: Set) -> Expr {
+    input.into_expr()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn set_of(values: Vec<i64>) -> Set {
+    values.into_iter().map(Expr::from).collect()
+  }
+
+  #[test]
+  fn test_from_iter_sorts_and_dedups() {
+    let set = set_of(vec![3, 1, 2, 3, 1]);
+    assert_eq!(set.as_slice(), &[Expr::from(1), Expr::from(2), Expr::from(3)]);
+  }
+
+  #[test]
+  fn test_is_canonical() {
+    assert!(Set::is_canonical(&[]));
+    assert!(Set::is_canonical(&[Expr::from(1), Expr::from(2)]));
+    assert!(!Set::is_canonical(&[Expr::from(2), Expr::from(1)]));
+    assert!(!Set::is_canonical(&[Expr::from(1), Expr::from(1)]));
+  }
+
+  #[test]
+  fn test_parse() {
+    let set = Set::parse(Expr::call("set", vec![Expr::from(2), Expr::from(1)])).unwrap();
+    assert_eq!(set, set_of(vec![1, 2]));
+    let err = Set::parse(Expr::call("vector", vec![])).unwrap_err();
+    assert_eq!(err.original_expr, Expr::call("vector", vec![]));
+  }
+
+  #[test]
+  fn test_set_operations() {
+    assert_eq!(set_of(vec![1, 2]).union(set_of(vec![2, 3])), set_of(vec![1, 2, 3]));
+    assert_eq!(set_of(vec![1, 2]).intersection(&set_of(vec![2, 3])), set_of(vec![2]));
+    assert_eq!(set_of(vec![1, 2]).difference(&set_of(vec![2, 3])), set_of(vec![1]));
+  }
+
+  #[test]
+  fn test_contains() {
+    let set = set_of(vec![1, 5, 9]);
+    assert!(set.contains(&Expr::from(5)));
+    assert!(!set.contains(&Expr::from(4)));
+  }
+
+  #[test]
+  fn test_powerset() {
+    let powerset = set_of(vec![1, 2]).powerset();
+    assert_eq!(powerset.len(), 4);
+    assert!(powerset.contains(&Set::empty().into_expr()));
+    assert!(powerset.contains(&set_of(vec![1, 2]).into_expr()));
+  }
+
+  #[test]
+  fn test_is_literal() {
+    assert!(set_of(vec![1, 2]).is_literal());
+    let set: Set = vec![Expr::from(1), Expr::var("x").unwrap()].into_iter().collect();
+    assert!(!set.is_literal());
+  }
+}
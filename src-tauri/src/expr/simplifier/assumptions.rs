@@ -0,0 +1,126 @@
+
+use crate::expr::Expr;
+use crate::expr::assumptions::{Assumption, AssumptionTable};
+use super::base::{Simplifier, SimplifierContext};
+
+/// [`Simplifier`] which uses the user's declared assumptions about
+/// variables to simplify expressions which cannot be simplified in
+/// general. For instance, `sqrt(x^2)` simplifies to `x` if `x` is
+/// known to be nonnegative, and `im(x)` simplifies to zero if `x` is
+/// known to be real.
+#[derive(Debug)]
+pub struct AssumptionSimplifier<'a> {
+  assumptions: &'a AssumptionTable,
+}
+
+impl<'a> AssumptionSimplifier<'a> {
+  pub fn new(assumptions: &'a AssumptionTable) -> Self {
+    Self { assumptions }
+  }
+
+  fn satisfies(&self, expr: &Expr, assumption: Assumption) -> bool {
+    self.assumptions.expr_satisfies(expr, assumption)
+  }
+}
+
+impl<'a> Simplifier for AssumptionSimplifier<'a> {
+  fn simplify_expr_part(&self, expr: Expr, _ctx: &mut SimplifierContext) -> Expr {
+    let Expr::Call(function_name, mut args) = expr else {
+      return expr;
+    };
+    if args.len() != 1 {
+      return Expr::Call(function_name, args);
+    }
+    let arg = &args[0];
+    match function_name.as_str() {
+      "sqrt" => {
+        if let Some(("^", [base, exponent])) = arg.as_call() {
+          if *exponent == Expr::from(2) {
+            if self.satisfies(base, Assumption::Nonnegative) {
+              return base.clone();
+            } else if self.satisfies(base, Assumption::Real) {
+              return Expr::call("abs", vec![base.clone()]);
+            }
+          }
+        }
+      }
+      "abs" if self.satisfies(arg, Assumption::Nonnegative) => {
+        return args.swap_remove(0);
+      }
+      "signum" if self.satisfies(arg, Assumption::Positive) => {
+        return Expr::one();
+      }
+      "re" | "conj" if self.satisfies(arg, Assumption::Real) => {
+        return args.swap_remove(0);
+      }
+      "im" if self.satisfies(arg, Assumption::Real) => {
+        return Expr::zero();
+      }
+      _ => {}
+    }
+    Expr::Call(function_name, args)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::var::Var;
+  use crate::expr::simplifier::test_utils::run_simplifier_no_errors;
+
+  use std::collections::BTreeSet;
+
+  fn var(name: &str) -> Expr {
+    Expr::var(name).unwrap()
+  }
+
+  fn sample_table() -> AssumptionTable {
+    let mut table = AssumptionTable::new();
+    table.set(Var::new("p").unwrap(), BTreeSet::from([Assumption::Positive]));
+    table.set(Var::new("r").unwrap(), BTreeSet::from([Assumption::Real]));
+    table
+  }
+
+  fn simplify(expr: Expr) -> Expr {
+    let table = sample_table();
+    run_simplifier_no_errors(&AssumptionSimplifier::new(&table), expr)
+  }
+
+  fn square(expr: Expr) -> Expr {
+    Expr::call("^", vec![expr, Expr::from(2)])
+  }
+
+  #[test]
+  fn test_sqrt_of_square() {
+    assert_eq!(simplify(Expr::call("sqrt", vec![square(var("p"))])), var("p"));
+    assert_eq!(
+      simplify(Expr::call("sqrt", vec![square(var("r"))])),
+      Expr::call("abs", vec![var("r")]),
+    );
+    let expr = Expr::call("sqrt", vec![square(var("z"))]);
+    assert_eq!(simplify(expr.clone()), expr);
+  }
+
+  #[test]
+  fn test_abs_and_signum() {
+    assert_eq!(simplify(Expr::call("abs", vec![var("p")])), var("p"));
+    assert_eq!(simplify(Expr::call("signum", vec![var("p")])), Expr::from(1));
+    let expr = Expr::call("abs", vec![var("r")]);
+    assert_eq!(simplify(expr.clone()), expr);
+  }
+
+  #[test]
+  fn test_real_parts() {
+    assert_eq!(simplify(Expr::call("re", vec![var("r")])), var("r"));
+    assert_eq!(simplify(Expr::call("conj", vec![var("p")])), var("p"));
+    assert_eq!(simplify(Expr::call("im", vec![var("r")])), Expr::from(0));
+    let expr = Expr::call("im", vec![var("z")]);
+    assert_eq!(simplify(expr.clone()), expr);
+  }
+
+  #[test]
+  fn test_nested() {
+    let expr = Expr::call("+", vec![Expr::call("abs", vec![var("p")]), Expr::call("im", vec![var("r")])]);
+    assert_eq!(simplify(expr), Expr::call("+", vec![var("p"), Expr::from(0)]));
+  }
+}
@@ -27,6 +27,18 @@ pub trait Simplifier {
   fn simplify_expr(&self, expr: Expr, ctx: &mut SimplifierContext) -> Expr {
     postorder_walk_ok(expr, |e| self.simplify_expr_part(e, ctx))
   }
+
+  /// Whether the computation this simplifier is running has been
+  /// cancelled. Once it has, the simplifier leaves expressions
+  /// unchanged, and long-running functions which don't go through the
+  /// simplifier for each step should stop as soon as they notice.
+  ///
+  /// The default implementation returns false. Simplifiers which wrap
+  /// other simplifiers should forward this method to them. See
+  /// [`CancellableSimplifier`](super::cancellable::CancellableSimplifier).
+  fn is_cancelled(&self) -> bool {
+    false
+  }
 }
 
 pub struct SimplifierContext<'a, 'b> {
@@ -44,4 +56,8 @@ where S: Simplifier + ?Sized {
   fn simplify_expr_part(&self, expr: Expr, ctx: &mut SimplifierContext) -> Expr {
     (**self).simplify_expr_part(expr, ctx)
   }
+
+  fn is_cancelled(&self) -> bool {
+    (**self).is_cancelled()
+  }
 }
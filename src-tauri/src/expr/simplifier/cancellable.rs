@@ -0,0 +1,159 @@
+
+//! Simplifiers which can be stopped partway through a long
+//! computation.
+//!
+//! A [`CancellableSimplifier`] checks its [`CancellationToken`] before
+//! simplifying each node of an expression. It only sees the nodes it
+//! is asked to simplify, so it should sit underneath any simplifier
+//! which walks the tree itself, such as a
+//! [`RepeatedSimplifier`](super::repeated::RepeatedSimplifier). See
+//! [`profiled_cancellable_default_simplifier`](super::profiled_cancellable_default_simplifier).
+//!
+//! Functions which loop for a long time without going through the
+//! simplifier on each iteration check [`Simplifier::is_cancelled`]
+//! themselves, and fail with [`CancelledError`] once it is set.
+
+use super::base::{Simplifier, SimplifierContext};
+use crate::expr::Expr;
+
+use thiserror::Error;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Shared flag used to ask a [`CancellableSimplifier`] to stop. The
+/// token also counts the expression nodes simplified so far, as a
+/// rough measure of progress.
+#[derive(Debug, Default)]
+pub struct CancellationToken {
+  cancelled: AtomicBool,
+  steps: AtomicU64,
+}
+
+/// A simplifier which delegates to an inner simplifier until its
+/// [`CancellationToken`] is cancelled. From then on, every
+/// simplification returns its input unchanged, so a command built on
+/// top of the simplifier runs to completion quickly, producing a
+/// result which the caller is expected to discard.
+pub struct CancellableSimplifier<'a> {
+  inner: Box<dyn Simplifier + 'a>,
+  token: Arc<CancellationToken>,
+}
+
+/// Error produced by a long-running function which stopped early
+/// because its computation was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Computation cancelled")]
+pub struct CancelledError;
+
+impl CancellationToken {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed)
+  }
+
+  /// The number of expression nodes simplified since the token was
+  /// created.
+  pub fn steps(&self) -> u64 {
+    self.steps.load(Ordering::Relaxed)
+  }
+}
+
+impl<'a> CancellableSimplifier<'a> {
+  pub fn new(inner: Box<dyn Simplifier + 'a>, token: Arc<CancellationToken>) -> Self {
+    Self { inner, token }
+  }
+}
+
+impl<'a> Simplifier for CancellableSimplifier<'a> {
+  fn simplify_expr_part(&self, expr: Expr, ctx: &mut SimplifierContext) -> Expr {
+    if self.token.is_cancelled() {
+      return expr;
+    }
+    self.token.steps.fetch_add(1, Ordering::Relaxed);
+    self.inner.simplify_expr_part(expr, ctx)
+  }
+
+  fn is_cancelled(&self) -> bool {
+    self.token.is_cancelled()
+  }
+}
+
+/// Fails with [`CancelledError`] if the simplifier's computation has
+/// been cancelled.
+pub fn ensure_not_cancelled(simplifier: &dyn Simplifier) -> Result<(), CancelledError> {
+  if simplifier.is_cancelled() {
+    Err(CancelledError)
+  } else {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::errorlist::ErrorList;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::default_simplifier;
+  use crate::expr::simplifier::evaluator::FunctionEvaluator;
+  use crate::expr::simplifier::test_utils::run_simplifier_no_errors;
+  use crate::expr::var::Var;
+  use crate::mode::calculation::CalculationMode;
+
+  fn sum() -> Expr {
+    Expr::call("+", vec![Expr::from(1), Expr::from(2)])
+  }
+
+  #[test]
+  fn test_simplifies_until_cancelled() {
+    let function_table = build_function_table();
+    let token = Arc::new(CancellationToken::new());
+    let simplifier = CancellableSimplifier::new(default_simplifier(&function_table), Arc::clone(&token));
+    assert_eq!(run_simplifier_no_errors(&simplifier, sum()), Expr::from(3));
+    // One step for each of the three nodes.
+    assert_eq!(token.steps(), 3);
+    assert!(!simplifier.is_cancelled());
+
+    token.cancel();
+    assert!(simplifier.is_cancelled());
+    assert_eq!(run_simplifier_no_errors(&simplifier, sum()), sum());
+    assert_eq!(token.steps(), 3);
+  }
+
+  #[test]
+  fn test_cancel_long_computation() {
+    let function_table = build_function_table();
+    let token = Arc::new(CancellationToken::new());
+    let simplifier = CancellableSimplifier::new(default_simplifier(&function_table), Arc::clone(&token));
+    token.cancel();
+
+    // A billion samples would take hours to compute. Call the
+    // function directly, since the cancelled simplifier would never
+    // get as far as evaluating it.
+    let x = Var::new("x").unwrap();
+    let expr = Expr::call("mc_integrate", vec![
+      Expr::call("^", vec![Expr::from(x.clone()), Expr::from(2)]),
+      Expr::from(x),
+      Expr::call("..", vec![Expr::from(0), Expr::from(1)]),
+      Expr::from(1_000_000_000),
+    ]);
+    let evaluator = FunctionEvaluator::new(&function_table);
+    let mut errors = ErrorList::new();
+    let mut context = SimplifierContext {
+      base_simplifier: &simplifier,
+      calculation_mode: CalculationMode::default(),
+      errors: &mut errors,
+    };
+    let result = evaluator.simplify_expr_part(expr.clone(), &mut context);
+    assert_eq!(result, expr);
+    assert_eq!(errors.into_vec().iter().map(|err| err.to_string()).collect::<Vec<_>>(), vec!["mc_integrate: Computation cancelled"]);
+    assert_eq!(token.steps(), 0);
+  }
+}
@@ -35,4 +35,8 @@ impl<'a, 'b> Simplifier for ChainedSimplifier<'a, 'b> {
     let expr = self.left.simplify_expr_part(expr, ctx);
     self.right.simplify_expr_part(expr, ctx)
   }
+
+  fn is_cancelled(&self) -> bool {
+    self.left.is_cancelled() || self.right.is_cancelled()
+  }
 }
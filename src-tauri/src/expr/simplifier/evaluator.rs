@@ -1,20 +1,50 @@
 
 use crate::expr::Expr;
 use crate::expr::function::table::FunctionTable;
+use crate::expr::function::user::UserFunctionTable;
+use crate::expr::calculus::DerivativeRuleTable;
 use super::base::{Simplifier, SimplifierContext};
 
 /// `FunctionEvaluator` is a [`Simplifier`] that evaluates known
 /// functions when all of the arguments have known acceptable values.
 /// Usually, this means all arguments are numerical ground terms, with
 /// no variables.
+///
+/// If given a table of user-defined functions, the evaluator also
+/// expands calls to those functions. Built-in functions take
+/// precedence over user-defined functions of the same name.
+///
+/// If given a table of derivative rules, the evaluator makes them
+/// available to the functions it calls, so that derivatives of
+/// functions with no built-in derivative can be computed.
 #[derive(Debug)]
 pub struct FunctionEvaluator<'a> {
   function_table: &'a FunctionTable,
+  user_functions: Option<&'a UserFunctionTable>,
+  derivative_rules: Option<&'a DerivativeRuleTable>,
 }
 
 impl<'a> FunctionEvaluator<'a> {
   pub fn new(function_table: &'a FunctionTable) -> Self {
-    Self { function_table }
+    Self { function_table, user_functions: None, derivative_rules: None }
+  }
+
+  pub fn with_user_functions(function_table: &'a FunctionTable, user_functions: &'a UserFunctionTable) -> Self {
+    Self { function_table, user_functions: Some(user_functions), derivative_rules: None }
+  }
+
+  pub fn with_derivative_rules(self, derivative_rules: &'a DerivativeRuleTable) -> Self {
+    Self { derivative_rules: Some(derivative_rules), ..self }
+  }
+
+  /// Expands a call to a user-defined function. Like built-in
+  /// functions, calls with the wrong number of arguments are left
+  /// unevaluated.
+  fn expand_user_function(&self, function_name: String, args: Vec<Expr>) -> Expr {
+    let Some(user_function) = self.user_functions.and_then(|table| table.get(&function_name)) else {
+      return Expr::Call(function_name, args);
+    };
+    user_function.expand(args).unwrap_or_else(|args| Expr::Call(function_name, args))
   }
 }
 
@@ -23,9 +53,9 @@ impl<'a> Simplifier for FunctionEvaluator<'a> {
     match expr {
       Expr::Call(function_name, args) => {
         let Some(known_function) = self.function_table.get(&function_name) else {
-          return Expr::Call(function_name, args);
+          return self.expand_user_function(function_name, args);
         };
-        match known_function.call(args, ctx.errors, ctx.base_simplifier, self.function_table, ctx.calculation_mode.clone()) {
+        match known_function.call(args, ctx.errors, ctx.base_simplifier, self.function_table, self.derivative_rules, ctx.calculation_mode.clone()) {
           Ok(expr) => expr,
           Err(args) => Expr::Call(function_name, args),
         }
@@ -0,0 +1,196 @@
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::algebra::infinity::{Hyperreal, InfiniteConstant, contains_infinitesimal};
+use super::base::{Simplifier, SimplifierContext};
+use crate::mode::calculation::CalculationMode;
+
+use num::{BigInt, Integer, Zero, One};
+
+/// [`Simplifier`] which does arithmetic on the infinitesimal constant
+/// `eps`. See [`Hyperreal`] for the rules.
+///
+/// Arithmetic on real numbers and `eps` is collected into the form
+/// `a + k * eps`, with `eps^2` absorbed. Comparisons between such values
+/// use the hyperreal ordering, so `eps > 0` but `eps < c` for every
+/// positive real `c`. Dividing a nonzero real number by a multiple of
+/// `eps` produces one of the signed infinities, and rounding a
+/// half-integer offset by a multiple of `eps` rounds toward the side
+/// of the offset.
+#[derive(Debug, Default)]
+pub struct InfinitesimalSimplifier {
+  _priv: (),
+}
+
+impl InfinitesimalSimplifier {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Simplifier for InfinitesimalSimplifier {
+  fn simplify_expr_part(&self, expr: Expr, ctx: &mut SimplifierContext) -> Expr {
+    let Expr::Call(function_name, args) = expr else {
+      return expr;
+    };
+    if !args.iter().any(contains_infinitesimal) {
+      return Expr::Call(function_name, args);
+    }
+    let expr = Expr::Call(function_name, args);
+    match simplify_call(&expr, &ctx.calculation_mode) {
+      Some(simplified) if simplified != expr => simplified,
+      _ => expr,
+    }
+  }
+}
+
+fn simplify_call(expr: &Expr, calculation_mode: &CalculationMode) -> Option<Expr> {
+  let (function_name, args) = expr.as_call()?;
+  match (function_name, args) {
+    ("+" | "-" | "*" | "negate", _) => {
+      match Hyperreal::from_expr(expr) {
+        Some(value) => Some(value.to_expr()),
+        None if function_name == "*" => absorb_product(args),
+        None => None,
+      }
+    }
+    ("/", [numer, denom]) => {
+      let numer = Hyperreal::from_expr(numer)?;
+      let denom = Hyperreal::from_expr(denom)?;
+      match numer.checked_div(&denom) {
+        Some(quotient) if calculation_mode.has_fractional_flag() => Some(quotient.to_expr()),
+        Some(quotient) => {
+          // As with ordinary division, inexact results are preferred
+          // to fractions unless the fractional flag is set.
+          let quotient = Hyperreal::new(quotient.standard.ratio_to_inexact(), quotient.infinitesimal.ratio_to_inexact());
+          Some(quotient.to_expr())
+        }
+        None => numer.divide_to_infinity(&denom).map(Expr::from),
+      }
+    }
+    ("^", [base, exponent]) => {
+      let base = Hyperreal::from_expr(base)?;
+      let exponent = BigInt::try_from(exponent.as_real_ref()?.clone()).ok()?;
+      if base.is_pure_infinitesimal() && exponent < BigInt::zero() {
+        // (k eps)^-n = 1 / (k^n eps^n), which is infinite with the
+        // sign of k^n.
+        let is_positive = base.infinitesimal.is_positive() || exponent.is_even();
+        let infinity = if is_positive { InfiniteConstant::PosInfinity } else { InfiniteConstant::NegInfinity };
+        return Some(Expr::from(infinity));
+      }
+      Some(base.checked_powi(exponent)?.to_expr())
+    }
+    ("<" | "<=" | ">" | ">=" | "=" | "!=", [left, right]) => {
+      let left = Hyperreal::from_expr(left)?;
+      let right = Hyperreal::from_expr(right)?;
+      let result = match function_name {
+        "<" => left < right,
+        "<=" => left <= right,
+        ">" => left > right,
+        ">=" => left >= right,
+        "=" => left == right,
+        "!=" => left != right,
+        _ => unreachable!(),
+      };
+      Some(Expr::from(result))
+    }
+    ("abs", [arg]) => {
+      let value = Hyperreal::from_expr(arg)?;
+      let value = if value < Hyperreal::real(Zero::zero()) { - value } else { value };
+      Some(value.to_expr())
+    }
+    ("signum", [arg]) => {
+      let value = Hyperreal::from_expr(arg)?;
+      Some(Expr::from(value.signum()))
+    }
+    ("round", [arg]) => {
+      let value = Hyperreal::from_expr(arg)?;
+      let floor = value.standard.div_floor(&Number::one());
+      if !value.is_real() && &value.standard - &floor == Number::ratio(1, 2) {
+        // Exactly halfway between two integers, so the side of the
+        // offset determines the result.
+        let rounded = if value.infinitesimal.is_positive() { floor + Number::one() } else { floor };
+        Some(Expr::from(rounded))
+      } else {
+        Some(Expr::from(value.standard.round_to_places(0, calculation_mode.rounding_mode())))
+      }
+    }
+    _ => None,
+  }
+}
+
+/// A product containing two or more factors which are multiples of
+/// `eps` is zero, even if its other factors are not numbers.
+fn absorb_product(factors: &[Expr]) -> Option<Expr> {
+  let infinitesimal_factors = factors.iter()
+    .filter(|factor| Hyperreal::from_expr(factor).is_some_and(|value| value.is_pure_infinitesimal()))
+    .count();
+  (infinitesimal_factors >= 2).then(Expr::zero)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::simplifier::test_utils::run_simplifier_no_errors;
+
+  fn eps() -> Expr {
+    Expr::var("eps").unwrap()
+  }
+
+  fn simplify(expr: Expr) -> Expr {
+    run_simplifier_no_errors(&InfinitesimalSimplifier::new(), expr)
+  }
+
+  #[test]
+  fn test_collect_arithmetic() {
+    let expr = Expr::call("+", vec![Expr::from(1), eps(), Expr::from(2), eps()]);
+    assert_eq!(simplify(expr), Expr::call("+", vec![Expr::from(3), Expr::call("*", vec![Expr::from(2), eps()])]));
+    let expr = Expr::call("^", vec![eps(), Expr::from(2)]);
+    assert_eq!(simplify(expr), Expr::zero());
+    // (x + eps)^2 at x = 3 has derivative 6.
+    let expr = Expr::call("^", vec![Expr::call("+", vec![Expr::from(3), eps()]), Expr::from(2)]);
+    assert_eq!(simplify(expr), Expr::call("+", vec![Expr::from(9), Expr::call("*", vec![Expr::from(6), eps()])]));
+  }
+
+  #[test]
+  fn test_absorb_product() {
+    let x = Expr::var("x").unwrap();
+    let expr = Expr::call("*", vec![x.clone(), eps(), eps()]);
+    assert_eq!(simplify(expr), Expr::zero());
+    let expr = Expr::call("*", vec![x, eps()]);
+    assert_eq!(simplify(expr.clone()), expr);
+  }
+
+  #[test]
+  fn test_division_by_infinitesimal() {
+    let expr = Expr::call("/", vec![Expr::from(2), eps()]);
+    assert_eq!(simplify(expr), Expr::from(InfiniteConstant::PosInfinity));
+    let expr = Expr::call("/", vec![Expr::from(2), Expr::call("negate", vec![eps()])]);
+    assert_eq!(simplify(expr), Expr::from(InfiniteConstant::NegInfinity));
+    let expr = Expr::call("^", vec![Expr::call("negate", vec![eps()]), Expr::from(-2)]);
+    assert_eq!(simplify(expr), Expr::from(InfiniteConstant::PosInfinity));
+    let expr = Expr::call("/", vec![Expr::call("*", vec![Expr::from(3), eps()]), eps()]);
+    assert_eq!(simplify(expr), Expr::from(3));
+  }
+
+  #[test]
+  fn test_ordering() {
+    assert_eq!(simplify(Expr::call(">", vec![eps(), Expr::zero()])), Expr::from(true));
+    assert_eq!(simplify(Expr::call("<", vec![eps(), Expr::from(0.001)])), Expr::from(true));
+    assert_eq!(simplify(Expr::call("<", vec![Expr::call("-", vec![Expr::from(1), eps()]), Expr::from(1)])), Expr::from(true));
+    assert_eq!(simplify(Expr::call("=", vec![eps(), Expr::zero()])), Expr::from(false));
+    assert_eq!(simplify(Expr::call("abs", vec![Expr::call("negate", vec![eps()])])), eps());
+    assert_eq!(simplify(Expr::call("signum", vec![eps()])), Expr::from(1));
+  }
+
+  #[test]
+  fn test_round() {
+    let half = Expr::from(Number::ratio(3, 2));
+    let expr = Expr::call("round", vec![Expr::call("-", vec![half.clone(), eps()])]);
+    assert_eq!(simplify(expr), Expr::from(1));
+    let expr = Expr::call("round", vec![Expr::call("+", vec![half, eps()])]);
+    assert_eq!(simplify(expr), Expr::from(2));
+    let expr = Expr::call("round", vec![Expr::call("-", vec![Expr::from(2), eps()])]);
+    assert_eq!(simplify(expr), Expr::from(2));
+  }
+}
@@ -1,5 +1,7 @@
 
 mod base;
+pub mod assumptions;
+pub mod cancellable;
 pub mod chained;
 pub mod dollar_sign;
 pub mod evaluator;
@@ -7,12 +9,16 @@ pub mod flattener;
 pub mod error;
 pub mod idempotent;
 pub mod identity;
+pub mod infinitesimal;
 pub mod interval;
 pub mod involution;
 pub mod numerical;
 pub mod partial;
 pub mod polynomial;
+pub mod precision;
+pub mod profile;
 pub mod repeated;
+pub mod rewrite;
 pub mod term;
 pub mod unicode;
 
@@ -20,17 +26,40 @@ pub use base::{Simplifier, SimplifierContext};
 
 use crate::expr::Expr;
 use crate::expr::function::table::FunctionTable;
+use crate::expr::function::user::UserFunctionTable;
+use crate::expr::rewrite::RewriteRuleTable;
+use crate::expr::assumptions::AssumptionTable;
+use crate::expr::calculus::DerivativeRuleTable;
 use crate::expr::function::distributive::{DistributiveRuleSimplifier, DistributiveRuleset};
+use cancellable::{CancellableSimplifier, CancellationToken};
 use repeated::RepeatedSimplifier;
+use profile::SimplifierProfile;
 use unicode::UnicodeSimplifier;
 
+use std::sync::Arc;
+
 struct DefaultSimplifier<'a> {
   function_table: &'a FunctionTable,
+  user_functions: UserFunctionTable,
+  rewrite_rules: RewriteRuleTable,
+  assumptions: AssumptionTable,
+  derivative_rules: DerivativeRuleTable,
   // We store these in advance since they're nontrivial to construct.
   // The others all have trivial constructors, so we create them
   // during `simplify_expr_part`'s body.
   unicode_simplifier: UnicodeSimplifier,
   distributive_rule_simplifier: DistributiveRuleSimplifier,
+  profile: Option<&'a SimplifierProfile>,
+}
+
+impl<'a> DefaultSimplifier<'a> {
+  /// Runs a single pass, timing it if we have a profile.
+  fn run_pass(&self, pass: &'static str, expr: Expr, f: impl FnOnce(Expr) -> Expr) -> Expr {
+    match self.profile {
+      None => f(expr),
+      Some(profile) => profile.time(pass, || f(expr)),
+    }
+  }
 }
 
 // This could technically be built up as a ChainedSimplifier, but
@@ -40,32 +69,135 @@ struct DefaultSimplifier<'a> {
 // implementation we need.
 impl<'a> Simplifier for DefaultSimplifier<'a> {
   fn simplify_expr_part(&self, mut expr: Expr, ctx: &mut SimplifierContext) -> Expr {
-    expr = self.unicode_simplifier.simplify_expr_part(expr, ctx);
-    expr = partial::IdentityRemover::new(self.function_table).simplify_expr_part(expr, ctx);
-    expr = flattener::FunctionFlattener::new(self.function_table).simplify_expr_part(expr, ctx);
-    expr = involution::InvolutionSimplifier::new(self.function_table).simplify_expr_part(expr, ctx);
-    expr = idempotent::IdempotenceSimplifier::new(self.function_table).simplify_expr_part(expr, ctx);
-    expr = self.distributive_rule_simplifier.simplify_expr_part(expr, ctx);
-    expr = term::FactorSorter::new().simplify_expr_part(expr, ctx);
-    expr = term::TermPartialSplitter::new().simplify_expr_part(expr, ctx);
-    expr = evaluator::FunctionEvaluator::new(self.function_table).simplify_expr_part(expr, ctx);
-    expr = polynomial::TermSorter::new().simplify_expr_part(expr, ctx);
-    expr = evaluator::FunctionEvaluator::new(self.function_table).simplify_expr_part(expr, ctx);
-    expr = interval::IntervalNormalizer::new().simplify_expr_part(expr, ctx);
+    expr = self.run_pass("unicode", expr, |e| self.unicode_simplifier.simplify_expr_part(e, ctx));
+    expr = self.run_pass("precision", expr, |e| precision::PrecisionSimplifier::new().simplify_expr_part(e, ctx));
+    expr = self.run_pass("identity_removal", expr, |e| partial::IdentityRemover::new(self.function_table).simplify_expr_part(e, ctx));
+    expr = self.run_pass("flattening", expr, |e| flattener::FunctionFlattener::new(self.function_table).simplify_expr_part(e, ctx));
+    expr = self.run_pass("involution", expr, |e| involution::InvolutionSimplifier::new(self.function_table).simplify_expr_part(e, ctx));
+    expr = self.run_pass("idempotence", expr, |e| idempotent::IdempotenceSimplifier::new(self.function_table).simplify_expr_part(e, ctx));
+    expr = self.run_pass("distribution", expr, |e| self.distributive_rule_simplifier.simplify_expr_part(e, ctx));
+    expr = self.run_pass("factor_sorting", expr, |e| term::FactorSorter::new().simplify_expr_part(e, ctx));
+    expr = self.run_pass("term_splitting", expr, |e| term::TermPartialSplitter::new().simplify_expr_part(e, ctx));
+    let evaluator = evaluator::FunctionEvaluator::with_user_functions(self.function_table, &self.user_functions)
+      .with_derivative_rules(&self.derivative_rules);
+    expr = self.run_pass("evaluation", expr, |e| evaluator.simplify_expr_part(e, ctx));
+    expr = self.run_pass("term_sorting", expr, |e| polynomial::TermSorter::new().simplify_expr_part(e, ctx));
+    expr = self.run_pass("evaluation", expr, |e| evaluator.simplify_expr_part(e, ctx));
+    expr = self.run_pass("infinitesimals", expr, |e| infinitesimal::InfinitesimalSimplifier::new().simplify_expr_part(e, ctx));
+    if !self.rewrite_rules.is_empty() {
+      let rewriter = rewrite::RewriteRuleSimplifier::new(&self.rewrite_rules);
+      expr = self.run_pass("rewrite_rules", expr, |e| rewriter.simplify_expr_part(e, ctx));
+    }
+    if !self.assumptions.is_empty() {
+      let assumption_simplifier = assumptions::AssumptionSimplifier::new(&self.assumptions);
+      expr = self.run_pass("assumptions", expr, |e| assumption_simplifier.simplify_expr_part(e, ctx));
+    }
+    expr = self.run_pass("interval_normalization", expr, |e| interval::IntervalNormalizer::new().simplify_expr_part(e, ctx));
     expr
   }
 }
 
 pub fn default_simplifier(function_table: &FunctionTable) -> Box<dyn Simplifier + '_> {
+  default_simplifier_with_user_functions(function_table, UserFunctionTable::new())
+}
+
+/// As [`default_simplifier`], but also expands calls to the given
+/// user-defined functions.
+pub fn default_simplifier_with_user_functions(
+  function_table: &FunctionTable,
+  user_functions: UserFunctionTable,
+) -> Box<dyn Simplifier + '_> {
+  build_default_simplifier(function_table, user_functions, RewriteRuleTable::new(), AssumptionTable::new(), DerivativeRuleTable::new(), None, None)
+}
+
+/// As [`default_simplifier_with_user_functions`], but also applies
+/// the given rewrite rules.
+pub fn default_simplifier_with_rewrite_rules(
+  function_table: &FunctionTable,
+  user_functions: UserFunctionTable,
+  rewrite_rules: RewriteRuleTable,
+) -> Box<dyn Simplifier + '_> {
+  build_default_simplifier(function_table, user_functions, rewrite_rules, AssumptionTable::new(), DerivativeRuleTable::new(), None, None)
+}
+
+/// As [`default_simplifier`], but also consults the given assumptions
+/// about variables.
+pub fn default_simplifier_with_assumptions(
+  function_table: &FunctionTable,
+  assumptions: AssumptionTable,
+) -> Box<dyn Simplifier + '_> {
+  build_default_simplifier(function_table, UserFunctionTable::new(), RewriteRuleTable::new(), assumptions, DerivativeRuleTable::new(), None, None)
+}
+
+/// As [`default_simplifier`], but also differentiates functions with
+/// no built-in derivative using the given rules.
+pub fn default_simplifier_with_derivative_rules(
+  function_table: &FunctionTable,
+  derivative_rules: DerivativeRuleTable,
+) -> Box<dyn Simplifier + '_> {
+  build_default_simplifier(function_table, UserFunctionTable::new(), RewriteRuleTable::new(), AssumptionTable::new(), derivative_rules, None, None)
+}
+
+/// The full default simplifier, with the user's functions, rewrite
+/// rules, assumptions, and derivative rules, recording the time spent in each pass of
+/// the pipeline in the given profile.
+pub fn profiled_default_simplifier<'a>(
+  function_table: &'a FunctionTable,
+  user_functions: UserFunctionTable,
+  rewrite_rules: RewriteRuleTable,
+  assumptions: AssumptionTable,
+  derivative_rules: DerivativeRuleTable,
+  profile: &'a SimplifierProfile,
+) -> Box<dyn Simplifier + 'a> {
+  build_default_simplifier(function_table, user_functions, rewrite_rules, assumptions, derivative_rules, Some(profile), None)
+}
+
+/// As [`profiled_default_simplifier`], but stops simplifying once the
+/// given token is cancelled. The token is checked before each pass
+/// over each node of the expression.
+pub fn profiled_cancellable_default_simplifier<'a>(
+  function_table: &'a FunctionTable,
+  user_functions: UserFunctionTable,
+  rewrite_rules: RewriteRuleTable,
+  assumptions: AssumptionTable,
+  derivative_rules: DerivativeRuleTable,
+  profile: &'a SimplifierProfile,
+  token: Arc<CancellationToken>,
+) -> Box<dyn Simplifier + 'a> {
+  build_default_simplifier(function_table, user_functions, rewrite_rules, assumptions, derivative_rules, Some(profile), Some(token))
+}
+
+fn build_default_simplifier<'a>(
+  function_table: &'a FunctionTable,
+  user_functions: UserFunctionTable,
+  rewrite_rules: RewriteRuleTable,
+  assumptions: AssumptionTable,
+  derivative_rules: DerivativeRuleTable,
+  profile: Option<&'a SimplifierProfile>,
+  token: Option<Arc<CancellationToken>>,
+) -> Box<dyn Simplifier + 'a> {
   // We repeat the DefaultSimplifier pipeline a few times, to make
   // sure we get all reasonable simplifications. The choice of 5 times
   // is arbitrary.
   let default_simplifier = DefaultSimplifier {
     function_table,
+    user_functions,
+    rewrite_rules,
+    assumptions,
+    derivative_rules,
     unicode_simplifier: UnicodeSimplifier::from_common_aliases(),
     distributive_rule_simplifier: DistributiveRuleSimplifier::new(DistributiveRuleset::from_common_rules()),
+    profile,
   };
-  Box::new(RepeatedSimplifier::new(default_simplifier, 5))
+  match token {
+    None => Box::new(RepeatedSimplifier::new(default_simplifier, 5)),
+    Some(token) => {
+      // The token must be checked on every pass, so it goes inside
+      // the repetition.
+      let cancellable = CancellableSimplifier::new(Box::new(default_simplifier), token);
+      Box::new(RepeatedSimplifier::new(cancellable, 5))
+    }
+  }
 }
 
 #[cfg(test)]
@@ -0,0 +1,96 @@
+
+use super::base::{Simplifier, SimplifierContext};
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::NumberRepr;
+
+/// A simplifier which, in arbitrary-precision mode, converts
+/// floating-point literals into
+/// [`BigFloat`](crate::expr::number::bigfloat::BigFloat) values at
+/// the current precision. Exact numbers are left alone, and this
+/// simplifier does nothing if arbitrary-precision mode is disabled.
+#[derive(Debug, Clone, Default)]
+pub struct PrecisionSimplifier {
+  _priv: (),
+}
+
+impl PrecisionSimplifier {
+  pub fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Simplifier for PrecisionSimplifier {
+  fn simplify_expr_part(&self, expr: Expr, ctx: &mut SimplifierContext) -> Expr {
+    let Some(precision) = ctx.calculation_mode.precision() else {
+      return expr;
+    };
+    match expr {
+      Expr::Atom(Atom::Number(n)) if n.repr() == NumberRepr::Float => {
+        Expr::from(n.to_big_float(precision))
+      }
+      Expr::Atom(Atom::Number(n)) if n.big_float_precision().is_some_and(|p| p < precision) => {
+        Expr::from(n.to_big_float(precision))
+      }
+      expr => expr,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::number::Number;
+  use crate::expr::simplifier::error::SimplifierError;
+  use crate::mode::calculation::CalculationMode;
+  use crate::errorlist::ErrorList;
+
+  fn simplify_with_precision(expr: Expr, precision: Option<u32>) -> Expr {
+    let simplifier = PrecisionSimplifier::new();
+    let mut errors: ErrorList<SimplifierError> = ErrorList::new();
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_precision(precision);
+    let mut ctx = SimplifierContext {
+      base_simplifier: &simplifier,
+      calculation_mode,
+      errors: &mut errors,
+    };
+    let expr = simplifier.simplify_expr(expr, &mut ctx);
+    assert!(errors.is_empty());
+    expr
+  }
+
+  #[test]
+  fn test_no_precision() {
+    let expr = Expr::call("+", vec![Expr::from(1.5), Expr::from(2)]);
+    assert_eq!(simplify_with_precision(expr.clone(), None), expr);
+  }
+
+  #[test]
+  fn test_floats_are_promoted() {
+    let expr = Expr::call("+", vec![Expr::from(1.5), Expr::from(2)]);
+    let expected = Expr::call("+", vec![
+      Expr::from(Number::from(1.5).to_big_float(30)),
+      Expr::from(2),
+    ]);
+    let result = simplify_with_precision(expr, Some(30));
+    assert_eq!(result, expected);
+    let Expr::Call(_, args) = result else { panic!("Expected call") };
+    let Expr::Atom(Atom::Number(n)) = &args[0] else { panic!("Expected number") };
+    assert_eq!(n.big_float_precision(), Some(30));
+  }
+
+  #[test]
+  fn test_big_floats_are_widened_but_not_narrowed() {
+    let low = Number::from(1.5).to_big_float(20);
+    let high = Number::from(1.5).to_big_float(50);
+    let Expr::Atom(Atom::Number(low)) = simplify_with_precision(Expr::from(low), Some(30)) else {
+      panic!("Expected number");
+    };
+    let Expr::Atom(Atom::Number(high)) = simplify_with_precision(Expr::from(high), Some(30)) else {
+      panic!("Expected number");
+    };
+    assert_eq!(low.big_float_precision(), Some(30));
+    assert_eq!(high.big_float_precision(), Some(50));
+  }
+}
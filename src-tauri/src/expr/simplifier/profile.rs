@@ -0,0 +1,133 @@
+
+//! Timing telemetry for the individual passes of the default
+//! simplifier.
+
+use serde::{Serialize, Deserialize};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Aggregated timing counters for each named simplifier pass. A
+/// profile is shared between commands, so it uses interior
+/// mutability and can be updated through a shared reference.
+#[derive(Debug, Default)]
+pub struct SimplifierProfile {
+  passes: Mutex<HashMap<&'static str, PassCounter>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct PassCounter {
+  calls: u64,
+  total: Duration,
+}
+
+/// The timing totals for a single simplifier pass, as reported to the
+/// frontend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassTiming {
+  /// The name of the pass.
+  pub pass: String,
+  /// The number of expression nodes the pass has been applied to.
+  pub calls: u64,
+  /// The total time spent in the pass, in microseconds.
+  pub total_micros: u64,
+}
+
+impl SimplifierProfile {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Runs `f`, recording the time it takes under the given pass name.
+  pub fn time<T>(&self, pass: &'static str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    self.record(pass, start.elapsed());
+    result
+  }
+
+  /// Records a single application of the named pass.
+  pub fn record(&self, pass: &'static str, duration: Duration) {
+    let mut passes = self.passes.lock().unwrap_or_else(|err| err.into_inner());
+    let counter = passes.entry(pass).or_default();
+    counter.calls += 1;
+    counter.total += duration;
+  }
+
+  /// The timings recorded so far, with the most expensive pass first.
+  pub fn timings(&self) -> Vec<PassTiming> {
+    let passes = self.passes.lock().unwrap_or_else(|err| err.into_inner());
+    let mut timings: Vec<_> = passes.iter()
+      .map(|(pass, counter)| PassTiming {
+        pass: (*pass).to_owned(),
+        calls: counter.calls,
+        total_micros: u64::try_from(counter.total.as_micros()).unwrap_or(u64::MAX),
+      })
+      .collect();
+    timings.sort_by(|a, b| b.total_micros.cmp(&a.total_micros).then_with(|| a.pass.cmp(&b.pass)));
+    timings
+  }
+
+  /// Discards all recorded timings.
+  pub fn reset(&self) {
+    self.passes.lock().unwrap_or_else(|err| err.into_inner()).clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::Expr;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::function::user::UserFunctionTable;
+  use crate::expr::rewrite::RewriteRuleTable;
+  use crate::expr::assumptions::AssumptionTable;
+  use crate::expr::calculus::DerivativeRuleTable;
+  use crate::expr::simplifier::profiled_default_simplifier;
+  use crate::expr::simplifier::test_utils::run_simplifier_no_errors;
+
+  #[test]
+  fn test_record_aggregates_by_pass() {
+    let profile = SimplifierProfile::new();
+    profile.record("evaluation", Duration::from_micros(30));
+    profile.record("flattening", Duration::from_micros(10));
+    profile.record("evaluation", Duration::from_micros(5));
+    assert_eq!(profile.timings(), vec![
+      PassTiming { pass: "evaluation".to_owned(), calls: 2, total_micros: 35 },
+      PassTiming { pass: "flattening".to_owned(), calls: 1, total_micros: 10 },
+    ]);
+  }
+
+  #[test]
+  fn test_time_returns_result() {
+    let profile = SimplifierProfile::new();
+    assert_eq!(profile.time("unicode", || 42), 42);
+    let timings = profile.timings();
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].pass, "unicode");
+    assert_eq!(timings[0].calls, 1);
+  }
+
+  #[test]
+  fn test_reset() {
+    let profile = SimplifierProfile::new();
+    profile.record("evaluation", Duration::from_micros(30));
+    profile.reset();
+    assert_eq!(profile.timings(), vec![]);
+  }
+
+  #[test]
+  fn test_profiled_default_simplifier() {
+    let function_table = build_function_table();
+    let profile = SimplifierProfile::new();
+    let simplifier = profiled_default_simplifier(&function_table, UserFunctionTable::new(), RewriteRuleTable::new(), AssumptionTable::new(), DerivativeRuleTable::new(), &profile);
+    let expr = Expr::call("+", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(run_simplifier_no_errors(&simplifier.as_ref(), expr), Expr::from(3));
+    let timings = profile.timings();
+    let evaluation = timings.iter().find(|timing| timing.pass == "evaluation").unwrap();
+    let flattening = timings.iter().find(|timing| timing.pass == "flattening").unwrap();
+    assert_eq!(evaluation.calls, 2 * flattening.calls);
+  }
+}
@@ -26,4 +26,8 @@ impl<S: Simplifier> Simplifier for RepeatedSimplifier<S> {
   fn simplify_expr_part(&self, expr: Expr, ctx: &mut SimplifierContext) -> Expr {
     self.inner.simplify_expr_part(expr, ctx)
   }
+
+  fn is_cancelled(&self) -> bool {
+    self.inner.is_cancelled()
+  }
 }
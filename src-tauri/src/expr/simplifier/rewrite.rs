@@ -0,0 +1,52 @@
+
+use crate::expr::Expr;
+use crate::expr::rewrite::RewriteRuleTable;
+use super::base::{Simplifier, SimplifierContext};
+
+/// [`Simplifier`] which applies the user's rewrite rules. Each
+/// subexpression is rewritten by the first rule that matches it, if
+/// any.
+#[derive(Debug)]
+pub struct RewriteRuleSimplifier<'a> {
+  rules: &'a RewriteRuleTable,
+}
+
+impl<'a> RewriteRuleSimplifier<'a> {
+  pub fn new(rules: &'a RewriteRuleTable) -> Self {
+    Self { rules }
+  }
+}
+
+impl<'a> Simplifier for RewriteRuleSimplifier<'a> {
+  fn simplify_expr_part(&self, expr: Expr, _ctx: &mut SimplifierContext) -> Expr {
+    self.rules.apply(&expr).unwrap_or(expr)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::rewrite::RewriteRule;
+  use crate::expr::simplifier::test_utils::run_simplifier_no_errors;
+
+  #[test]
+  fn test_rewrites_subexpressions() {
+    let mut rules = RewriteRuleTable::new();
+    rules.push(RewriteRule::new(
+      Expr::call("f", vec![Expr::var("x_").unwrap(), Expr::var("x_").unwrap()]),
+      Expr::call("g", vec![Expr::var("x_").unwrap()]),
+    ).unwrap());
+    let simplifier = RewriteRuleSimplifier::new(&rules);
+    let expr = Expr::call("h", vec![
+      Expr::call("f", vec![Expr::from(1), Expr::from(1)]),
+      Expr::call("f", vec![Expr::from(1), Expr::from(2)]),
+    ]);
+    assert_eq!(
+      run_simplifier_no_errors(&simplifier, expr),
+      Expr::call("h", vec![
+        Expr::call("g", vec![Expr::from(1)]),
+        Expr::call("f", vec![Expr::from(1), Expr::from(2)]),
+      ]),
+    );
+  }
+}
@@ -4,7 +4,7 @@ use crate::expr::algebra::factor::Factor;
 use crate::expr::arithmetic::ArithExpr;
 use crate::expr::Expr;
 use crate::expr::atom::Atom;
-use crate::expr::number::Number;
+use crate::expr::number::{Number, Quaternion, Octonion};
 use crate::expr::ordering::cmp_expr;
 use crate::expr::simplifier::base::{Simplifier, SimplifierContext};
 use crate::util::{retain_into, insert_sorted_by, Recip};
@@ -31,6 +31,11 @@ impl FactorSorter {
 
 impl Simplifier for FactorSorter {
   fn simplify_expr_part(&self, expr: Expr, _ctx: &mut SimplifierContext) -> Expr {
+    if has_quaternion_factor(&expr) {
+      // Quaternion (and octonion) multiplication is order-dependent,
+      // so leave the written order intact.
+      return expr;
+    }
     let term = Term::parse(expr);
     let (numer, denom) = term.into_parts();
     let mut numer = group_and_sort_factors(numer);
@@ -116,6 +121,18 @@ fn flip_negative_terms_to(source: &mut Vec<Factor>, dest: &mut Vec<Factor>) {
   }
 }
 
+/// Returns true if any factor of the product or quotient `expr` is a
+/// quaternion or octonion literal.
+fn has_quaternion_factor(expr: &Expr) -> bool {
+  match expr {
+    Expr::Call(f, args) if f == "*" || f == "/" => args.iter().any(has_quaternion_factor),
+    Expr::Call(f, args) => {
+      (f == Quaternion::FUNCTION_NAME && args.len() == 4) || (f == Octonion::FUNCTION_NAME && args.len() == 8)
+    }
+    Expr::Atom(_) => false,
+  }
+}
+
 /// Returns true if the expression appears negative to a casual
 /// viewer. An expression appears negative if it is literally a
 /// negative real-numbered constant, or if it is a unary application
@@ -235,4 +252,18 @@ mod tests {
       ]),
     ]));
   }
+
+  #[test]
+  fn test_factor_sorter_preserves_order_of_quaternions() {
+    let expr = Expr::call("*", vec![
+      var("y"),
+      Expr::from(Quaternion::jj()),
+      var("x"),
+      Expr::from(Quaternion::ii()),
+      var("y"),
+    ]);
+    let (new_expr, errors) = run_simplifier(&FactorSorter::new(), expr.clone());
+    assert!(errors.is_empty());
+    assert_eq!(new_expr, expr);
+  }
 }
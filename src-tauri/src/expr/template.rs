@@ -0,0 +1,175 @@
+
+//! Skeleton expressions for complicated constructs, with slots to be
+//! filled in one at a time.
+//!
+//! A slot is the reserved variable `slot`. Slots are filled
+//! positionally, left to right, so that the frontend can walk the
+//! user through a template like a form.
+
+use super::Expr;
+use super::atom::Atom;
+use crate::util::prism::Prism;
+
+use std::fmt::{self, Display, Formatter};
+
+pub const SLOT_NAME: &str = "slot";
+
+/// A named skeleton expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+  /// `integrate(expr, var)`
+  Integral,
+  /// `num_integrate(expr, var, interval)`
+  NumIntegral,
+  /// `deriv(expr, var)`
+  Derivative,
+  /// `limit(expr, var, point)`
+  Limit,
+  /// `sum(expr, var, lower, upper)`
+  Sum,
+  /// `prod(expr, var, lower, upper)`
+  Product,
+  /// `substitute(expr, var, replacement)`
+  Substitute,
+}
+
+/// Prism which parses a string as a [`Template`] name.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToTemplate;
+
+impl Template {
+  pub const ALL: [Template; 7] = [
+    Template::Integral,
+    Template::NumIntegral,
+    Template::Derivative,
+    Template::Limit,
+    Template::Sum,
+    Template::Product,
+    Template::Substitute,
+  ];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Template::Integral => "integral",
+      Template::NumIntegral => "num_integral",
+      Template::Derivative => "derivative",
+      Template::Limit => "limit",
+      Template::Sum => "sum",
+      Template::Product => "product",
+      Template::Substitute => "substitute",
+    }
+  }
+
+  /// The skeleton expression, with every argument a slot.
+  pub fn to_expr(self) -> Expr {
+    let (function_name, arity) = match self {
+      Template::Integral => ("integrate", 2),
+      Template::NumIntegral => ("num_integrate", 3),
+      Template::Derivative => ("deriv", 2),
+      Template::Limit => ("limit", 3),
+      Template::Sum => ("sum", 4),
+      Template::Product => ("prod", 4),
+      Template::Substitute => ("substitute", 3),
+    };
+    Expr::call(function_name, vec![slot(); arity])
+  }
+}
+
+impl Display for Template {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+impl Prism<String, Template> for StringToTemplate {
+  fn narrow_type(&self, input: String) -> Result<Template, String> {
+    let trimmed = input.trim();
+    Template::ALL.into_iter()
+      .find(|template| template.name() == trimmed)
+      .ok_or(input)
+  }
+
+  fn widen_type(&self, template: Template) -> String {
+    template.name().to_owned()
+  }
+}
+
+/// An unfilled slot.
+pub fn slot() -> Expr {
+  Expr::var(SLOT_NAME).unwrap() // unwrap: SLOT_NAME is a valid variable name
+}
+
+pub fn is_slot(expr: &Expr) -> bool {
+  matches!(expr, Expr::Atom(Atom::Var(v)) if v.as_str() == SLOT_NAME)
+}
+
+/// The number of unfilled slots in `expr`.
+pub fn count_slots(expr: &Expr) -> usize {
+  match expr {
+    Expr::Atom(_) => usize::from(is_slot(expr)),
+    Expr::Call(_, args) => args.iter().map(count_slots).sum(),
+  }
+}
+
+/// Replaces the leftmost unfilled slot in `expr` with `value`. If
+/// `expr` has no slots, returns `Err` with both arguments unchanged.
+pub fn fill_first_slot(expr: Expr, value: Expr) -> Result<Expr, (Expr, Expr)> {
+  let mut value = Some(value);
+  let expr = fill_first_slot_impl(expr, &mut value);
+  match value {
+    None => Ok(expr),
+    Some(value) => Err((expr, value)),
+  }
+}
+
+fn fill_first_slot_impl(expr: Expr, value: &mut Option<Expr>) -> Expr {
+  if value.is_none() {
+    return expr;
+  }
+  match expr {
+    Expr::Atom(_) if is_slot(&expr) => value.take().unwrap(), // unwrap: Checked above
+    Expr::Atom(_) => expr,
+    Expr::Call(function_name, args) => {
+      let args = args.into_iter().map(|arg| fill_first_slot_impl(arg, value)).collect();
+      Expr::Call(function_name, args)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_template() {
+    assert_eq!(StringToTemplate.narrow_type(String::from(" integral ")), Ok(Template::Integral));
+    assert_eq!(StringToTemplate.narrow_type(String::from("integrate")), Err(String::from("integrate")));
+    for template in Template::ALL {
+      assert_eq!(StringToTemplate.narrow_type(StringToTemplate.widen_type(template)), Ok(template));
+    }
+  }
+
+  #[test]
+  fn test_to_expr() {
+    assert_eq!(Template::Sum.to_expr(), Expr::call("sum", vec![slot(), slot(), slot(), slot()]));
+    assert_eq!(count_slots(&Template::Limit.to_expr()), 3);
+  }
+
+  #[test]
+  fn test_fill_first_slot() {
+    let expr = Expr::call("+", vec![Expr::from(1), Expr::call("f", vec![slot()]), slot()]);
+    let expr = fill_first_slot(expr, Expr::from(2)).unwrap();
+    assert_eq!(expr, Expr::call("+", vec![Expr::from(1), Expr::call("f", vec![Expr::from(2)]), slot()]));
+    let expr = fill_first_slot(expr, Expr::from(3)).unwrap();
+    assert_eq!(expr, Expr::call("+", vec![Expr::from(1), Expr::call("f", vec![Expr::from(2)]), Expr::from(3)]));
+    assert_eq!(count_slots(&expr), 0);
+    assert_eq!(fill_first_slot(expr.clone(), Expr::from(4)), Err((expr, Expr::from(4))));
+  }
+
+  #[test]
+  fn test_fill_slot_with_slot() {
+    // The value is never searched for further slots.
+    let expr = fill_first_slot(slot(), Expr::call("g", vec![slot()])).unwrap();
+    assert_eq!(expr, Expr::call("g", vec![slot()]));
+  }
+}
@@ -5,6 +5,7 @@ use super::atom::{write_escaped_str, process_escape_char, InvalidEscapeError};
 use crate::parsing::operator::{Operator, OperatorTable};
 use crate::parsing::source::{Span, SourceOffset};
 use crate::parsing::tokenizer::{TokenizerState, TokenizerCaptures};
+use crate::mode::display::locale::Locale;
 use crate::util::regex_opt_with;
 use crate::util::radix::{Radix, Digits, FromDigits, RadixFromStrError,
                          ValidateForRadixError, DigitsFromStrError, FromDigitsError};
@@ -21,6 +22,8 @@ use std::fmt::{self, Display, Formatter};
 pub struct ExprTokenizer<'a> {
   operator_table: &'a OperatorTable,
   operator_regex: Regex,
+  locale: Locale,
+  number_regex: Regex,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -82,7 +85,21 @@ impl<'a> ExprTokenizer<'a> {
   pub fn new(operator_table: &'a OperatorTable) -> Self {
     let operator_names = operator_table.iter().map(|op| op.operator_name());
     let operator_regex = regex_opt_with(operator_names, |s| format!("^{s}"));
-    Self { operator_table, operator_regex }
+    let locale = Locale::default();
+    let number_regex = number_literal_regex(&locale);
+    Self { operator_table, operator_regex, locale, number_regex }
+  }
+
+  /// Configures this tokenizer to read number literals and argument
+  /// separators according to the given locale.
+  pub fn with_locale(mut self, locale: Locale) -> Self {
+    self.number_regex = number_literal_regex(&locale);
+    self.locale = locale;
+    self
+  }
+
+  pub fn locale(&self) -> &Locale {
+    &self.locale
   }
 
   pub fn read_tokens(&self, state: &mut TokenizerState<'_>) -> Result<Vec<Token>, TokenizerError> {
@@ -135,12 +152,14 @@ impl<'a> ExprTokenizer<'a> {
   }
 
   fn read_char_token(&self, state: &mut TokenizerState<'_>) -> Option<Token> {
+    let mut separator_buf = [0; 4];
+    let argument_separator = self.locale.argument_separator().encode_utf8(&mut separator_buf);
     #[allow(clippy::manual_map)] // Cleaner in an if-else chain
     if let Some(m) = state.read_literal("(") {
       Some(Token::new(TokenData::LeftParen, m.span()))
     } else if let Some(m) = state.read_literal(")") {
       Some(Token::new(TokenData::RightParen, m.span()))
-    } else if let Some(m) = state.read_literal(",") {
+    } else if let Some(m) = state.read_literal(argument_separator) {
       Some(Token::new(TokenData::Comma, m.span()))
     } else if let Some(m) = state.read_literal("[") {
       Some(Token::new(TokenData::LeftBracket, m.span()))
@@ -197,6 +216,11 @@ impl<'a> ExprTokenizer<'a> {
 
   fn read_variable_token(&self, state: &mut TokenizerState<'_>) -> Option<Token> {
     state.read_regex(&var::VALID_NAME_PREFIX_RE).map(|m| {
+      // Operators whose names are words (such as `angle`) take
+      // priority over variables of the same name.
+      if let Some(operator) = self.operator_table.get_by_operator_name(m.as_str()) {
+        return Token::new(TokenData::Operator(operator.clone()), m.span());
+      }
       let var = Var::new(m.as_str()).expect("expected valid variable name from tokenizer");
       Token::new(TokenData::Var(var), m.span())
     })
@@ -262,14 +286,9 @@ impl<'a> ExprTokenizer<'a> {
   }
 
   fn read_number_literal(&self, state: &mut TokenizerState<'_>) -> Option<Result<Token, TokenizerError>> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-      let ratio_re = r"[0-9]+:[0-9]+";
-      let int_float_re = r"[0-9]+(\.[0-9]+)?([eE][+-]?[0-9]+)?";
-      Regex::new(&format!("^(?:{ratio_re}|{int_float_re})")).unwrap()
-    });
     let reset_pos = state.current_pos();
-    let m = state.read_regex(&RE)?;
-    match Number::from_str(m.as_str()) {
+    let m = state.read_regex(&self.number_regex)?;
+    match Number::from_str(&self.locale.delocalize_number(m.as_str())) {
       Err(err) => {
         state.seek(reset_pos);
         Some(Err(err.into()))
@@ -281,6 +300,13 @@ impl<'a> ExprTokenizer<'a> {
   }
 }
 
+fn number_literal_regex(locale: &Locale) -> Regex {
+  let decimal_separator = regex::escape(locale.decimal_separator().encode_utf8(&mut [0; 4]));
+  let ratio_re = r"[0-9]+:[0-9]+";
+  let int_float_re = format!(r"[0-9]+({decimal_separator}[0-9]+)?([eE][+-]?[0-9]+)?");
+  Regex::new(&format!("^(?:{ratio_re}|{int_float_re})")).unwrap()
+}
+
 impl Token {
   pub fn new(data: TokenData, span: Span) -> Self {
     Self { data, span }
@@ -342,6 +368,7 @@ mod tests {
       Operator::new("+", Fixity::new().with_infix("plus", Associativity::LEFT, Precedence::new(0))),
       Operator::new("++", Fixity::new().with_infix("concat", Associativity::LEFT, Precedence::new(0))),
       Operator::new("*", Fixity::new().with_infix("times", Associativity::LEFT, Precedence::new(0))),
+      Operator::new("mod", Fixity::new().with_infix("modulo", Associativity::LEFT, Precedence::new(0))),
     ].into_iter().collect()
   }
 
@@ -413,6 +440,36 @@ mod tests {
     assert_eq!(state.current_pos(), SourceOffset(2));
   }
 
+  #[test]
+  fn test_number_float_with_decimal_comma() {
+    let table = sample_operator_table();
+    let tokenizer = ExprTokenizer::new(&table).with_locale(Locale::from_str("de").unwrap());
+
+    let mut state = TokenizerState::new("1,5");
+    let token = tokenizer.read_one_token(&mut state).expect("expected token");
+    assert_eq!(token, Token::new(TokenData::Number(Number::from(1.5)), span(0, 3)));
+    assert_eq!(state.current_pos(), SourceOffset(3));
+  }
+
+  #[test]
+  fn test_token_stream_with_decimal_comma() {
+    let table = sample_operator_table();
+    let tokenizer = ExprTokenizer::new(&table).with_locale(Locale::from_str("de").unwrap());
+
+    let mut state = TokenizerState::new("[1,5; 2]");
+    let tokens = tokenizer.read_tokens(&mut state).unwrap();
+    assert_eq!(
+      tokens,
+      vec![
+        Token::new(TokenData::LeftBracket, span(0, 1)),
+        Token::new(TokenData::Number(Number::from(1.5)), span(1, 4)),
+        Token::new(TokenData::Comma, span(4, 5)),
+        Token::new(TokenData::Number(Number::from(2)), span(6, 7)),
+        Token::new(TokenData::RightBracket, span(7, 8)),
+      ],
+    );
+  }
+
   #[test]
   fn test_number_ratio() {
     let table = sample_operator_table();
@@ -582,6 +639,24 @@ mod tests {
     assert!(state.is_eof());
   }
 
+  #[test]
+  fn test_token_stream_with_word_operator() {
+    let table = sample_operator_table();
+    let tokenizer = ExprTokenizer::new(&table);
+
+    let mut state = TokenizerState::new("a mod modulus");
+    let tokens = tokenizer.read_tokens(&mut state).unwrap();
+    assert_eq!(
+      tokens,
+      vec![
+        Token::new(TokenData::Var(Var::new("a").unwrap()), span(0, 1)),
+        Token::new(TokenData::Operator(table.get_by_operator_name("mod").unwrap().clone()), span(2, 5)),
+        Token::new(TokenData::Var(Var::new("modulus").unwrap()), span(6, 13)),
+      ],
+    );
+    assert!(state.is_eof());
+  }
+
   #[test]
   fn test_token_stream_with_extra_whitespace() {
     let table = sample_operator_table();
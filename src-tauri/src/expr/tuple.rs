@@ -0,0 +1,146 @@
+
+//! Fixed-size heterogeneous tuples of expressions.
+
+use super::Expr;
+use crate::util::prism::Prism;
+
+use thiserror::Error;
+
+use std::ops::Index;
+
+/// A `Tuple` is an immutable, ordered collection of expressions.
+///
+/// A `Tuple` is represented in the expression language as a call to
+/// the function called "tuple". Unlike vectors, tuples carry no
+/// mathematical structure: arithmetic operations never broadcast over
+/// them, so they are suitable for bundling together several
+/// unrelated results, such as the quotient and remainder of a
+/// division.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tuple {
+  data: Vec<Expr>,
+}
+
+/// Prism which accepts only tuples (i.e. expressions which are calls
+/// to a function called "tuple").
+///
+/// Delegates to [`Tuple::parse`] for narrowing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExprToTuple;
+
+#[derive(Debug, Error)]
+#[error("Expected a tuple, got {original_expr}")]
+pub struct ParseTupleError {
+  pub original_expr: Expr,
+  _priv: (),
+}
+
+impl Tuple {
+  pub const FUNCTION_NAME: &'static str = "tuple";
+
+  /// If the expression is a function call of the form `tuple(...)`,
+  /// returns a [`Tuple`] containing the arguments. If the expression
+  /// is of any other form, returns an appropriate error.
+  pub fn parse(expr: Expr) -> Result<Tuple, ParseTupleError> {
+    match expr {
+      Expr::Call(name, args) if name == Tuple::FUNCTION_NAME => Ok(Tuple { data: args }),
+      expr => Err(ParseTupleError { original_expr: expr, _priv: () }),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<&Expr> {
+    self.data.get(index)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Expr> {
+    self.data.iter()
+  }
+
+  pub fn as_slice(&self) -> &[Expr] {
+    &self.data
+  }
+
+  pub fn into_expr(self) -> Expr {
+    Expr::call(Tuple::FUNCTION_NAME, self.data)
+  }
+}
+
+impl From<Vec<Expr>> for Tuple {
+  fn from(data: Vec<Expr>) -> Self {
+    Self { data }
+  }
+}
+
+impl<const N: usize> From<[Expr; N]> for Tuple {
+  fn from(data: [Expr; N]) -> Self {
+    Self { data: data.into() }
+  }
+}
+
+impl FromIterator<Expr> for Tuple {
+  fn from_iter<I: IntoIterator<Item = Expr>>(iter: I) -> Self {
+    Self { data: iter.into_iter().collect() }
+  }
+}
+
+impl IntoIterator for Tuple {
+  type Item = Expr;
+  type IntoIter = std::vec::IntoIter<Expr>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.data.into_iter()
+  }
+}
+
+impl Index<usize> for Tuple {
+  type Output = Expr;
+
+  fn index(&self, index: usize) -> &Expr {
+    &self.data[index]
+  }
+}
+
+impl From<Tuple> for Expr {
+  fn from(tuple: Tuple) -> Expr {
+    tuple.into_expr()
+  }
+}
+
+impl Prism<Expr, Tuple> for ExprToTuple {
+  fn narrow_type(&self, input: Expr) -> Result<Tuple, Expr> {
+    Tuple::parse(input).map_err(|err| err.original_expr)
+  }
+  fn widen_type(&self, input: Tuple) -> Expr {
+    input.into_expr()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse() {
+    let tuple = Tuple::parse(Expr::call("tuple", vec![Expr::from(2), Expr::from(1)])).unwrap();
+    assert_eq!(tuple.as_slice(), &[Expr::from(2), Expr::from(1)]);
+    let err = Tuple::parse(Expr::call("vector", vec![Expr::from(2)])).unwrap_err();
+    assert_eq!(err.original_expr, Expr::call("vector", vec![Expr::from(2)]));
+  }
+
+  #[test]
+  fn test_prism_round_trip() {
+    let expr = Expr::call("tuple", vec![Expr::from(1), Expr::string("a")]);
+    let tuple = ExprToTuple.narrow_type(expr.clone()).unwrap();
+    assert_eq!(tuple.len(), 2);
+    assert_eq!(tuple[1], Expr::string("a"));
+    assert_eq!(ExprToTuple.widen_type(tuple), expr);
+  }
+}
@@ -20,4 +20,4 @@ pub use output::{tagged_into_expr, tagged_into_term, unit_into_term,
                  tagged_into_expr_lossy, tagged_into_term_lossy, unit_into_term_lossy};
 pub use parser::{parse_composite_unit_term, parse_composite_unit_expr, try_parse_unit};
 pub use prisms::{ParsedCompositeUnit, UnitPrism};
-pub use simplifier::{UnitTermSimplifier, UnitPolynomialSimplifier};
+pub use simplifier::{UnitTermSimplifier, UnitPolynomialSimplifier, unit_aware_simplifier};
@@ -1,5 +1,6 @@
 
 use crate::expr::simplifier::{Simplifier, SimplifierContext};
+use crate::expr::simplifier::chained::ChainedSimplifier;
 use crate::expr::Expr;
 use crate::expr::algebra::term::{Term, Sign, SignedTerm};
 use crate::expr::algebra::polynomial::{Polynomial, parse_polynomial};
@@ -19,6 +20,7 @@ use itertools::Itertools;
 #[derive(Debug)]
 pub struct UnitTermSimplifier<'a, P: ?Sized> {
   unit_parser: &'a P,
+  prefer_derived_units: bool,
 }
 
 /// Simplifier which combines terms of the same dimension in a
@@ -31,7 +33,26 @@ pub struct UnitPolynomialSimplifier<'a, P: ?Sized> {
 impl<'a, P> UnitTermSimplifier<'a, P>
 where P: UnitParser<Number> + ?Sized {
   pub fn new(unit_parser: &'a P) -> Self {
-    Self { unit_parser }
+    Self { unit_parser, prefer_derived_units: false }
+  }
+
+  /// If true, a product of several units is replaced by a single unit
+  /// from the parser's [`UnitParser::derived_units`] of the same
+  /// dimension, if there is one.
+  pub fn with_derived_units(mut self, prefer_derived_units: bool) -> Self {
+    self.prefer_derived_units = prefer_derived_units;
+    self
+  }
+
+  fn to_derived_unit(&self, unit: CompositeUnit<Number>) -> CompositeUnit<Number> {
+    if unit.units().len() < 2 {
+      return unit;
+    }
+    let dimension = unit.dimension();
+    self.unit_parser.derived_units()
+      .into_iter()
+      .find(|derived| derived.dimension() == &dimension)
+      .map_or(unit, CompositeUnit::from)
   }
 }
 
@@ -45,7 +66,7 @@ where P: UnitParser<Number> + ?Sized {
 impl<'a, P> Clone for UnitTermSimplifier<'a, P>
 where P: ?Sized {
   fn clone(&self) -> Self {
-    Self { unit_parser: self.unit_parser }
+    Self { unit_parser: self.unit_parser, prefer_derived_units: self.prefer_derived_units }
   }
 }
 
@@ -64,7 +85,10 @@ where P: UnitParser<Number> + ?Sized {
       // No units, so nothing to simplify
       return tagged_into_expr_lossy(tagged);
     }
-    let simplified_unit = run_simplifications(tagged.unit.clone());
+    let mut simplified_unit = run_simplifications(tagged.unit.clone());
+    if self.prefer_derived_units {
+      simplified_unit = self.to_derived_unit(simplified_unit);
+    }
     // convert_or_panic: simplify_compatible_unit always retains the
     // dimension of its input.
     let tagged =
@@ -107,6 +131,24 @@ where P: UnitParser<Number> + ?Sized {
   }
 }
 
+/// Simplifier which runs `base`, then cancels compatible units and
+/// combines terms of the same dimension (see [`UnitTermSimplifier`]
+/// and [`UnitPolynomialSimplifier`]), then runs `base` once more to
+/// clean up after any unit conversions.
+pub fn unit_aware_simplifier<'a>(
+  base: &'a dyn Simplifier,
+  unit_parser: &'a dyn UnitParser<Number>,
+  prefer_derived_units: bool,
+) -> Box<dyn Simplifier + 'a> {
+  let simplifiers: [Box<dyn Simplifier + 'a>; 4] = [
+    Box::new(base),
+    Box::new(UnitTermSimplifier::new(unit_parser).with_derived_units(prefer_derived_units)),
+    Box::new(UnitPolynomialSimplifier::new(unit_parser)),
+    Box::new(base),
+  ];
+  ChainedSimplifier::several(simplifiers)
+}
+
 fn simplify_sum(terms: Vec<(Sign, Tagged<Term, Number>)>) -> Tagged<Polynomial, Number> {
   assert!(!terms.is_empty(), "simplify_sum expected non-empty vector");
   let final_unit = terms[0].1.unit.clone();
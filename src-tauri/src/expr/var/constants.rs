@@ -2,7 +2,7 @@
 //! Well-known mathematical constants that are useful to the user.
 
 use crate::expr::Expr;
-use crate::expr::number::ComplexNumber;
+use crate::expr::number::{ComplexNumber, Quaternion};
 use super::Var;
 use super::table::VarTable;
 use super::dollar_sign::DollarSignVar;
@@ -18,9 +18,9 @@ use std::f64::consts::{PI, E};
 pub static RESERVED_NAMES: Lazy<HashSet<Var>> = Lazy::new(|| {
   vec![
     // Ordinary constants
-    "pi", "gamma", "e", "i", "phi",
+    "pi", "gamma", "e", "i", "j", "k", "phi",
     // Symbolic names used by our algebra system
-    "inf", "uinf", "nan",
+    "inf", "uinf", "nan", "eps", "slot",
   ].into_iter().map(|s| Var::new(s).unwrap()).collect()
 });
 
@@ -47,6 +47,8 @@ pub fn bind_constants(table: &mut VarTable<Expr>) {
   table.insert(Var::new("gamma").unwrap(), Expr::from(GAMMA));
   table.insert(Var::new("e").unwrap(), Expr::from(E));
   table.insert(Var::new("i").unwrap(), Expr::from(ComplexNumber::ii()));
+  table.insert(Var::new("j").unwrap(), Expr::from(Quaternion::jj()));
+  table.insert(Var::new("k").unwrap(), Expr::from(Quaternion::kk()));
   table.insert(Var::new("phi").unwrap(), Expr::from(PHI));
 }
 
@@ -87,6 +89,8 @@ mod tests {
     assert!(matches!(validate_non_reserved_var_name(&var("gamma")), Err(NameIsReservedError::ReservedConstant { .. })));
     assert!(matches!(validate_non_reserved_var_name(&var("e")), Err(NameIsReservedError::ReservedConstant { .. })));
     assert!(matches!(validate_non_reserved_var_name(&var("i")), Err(NameIsReservedError::ReservedConstant { .. })));
+    assert!(matches!(validate_non_reserved_var_name(&var("j")), Err(NameIsReservedError::ReservedConstant { .. })));
+    assert!(matches!(validate_non_reserved_var_name(&var("k")), Err(NameIsReservedError::ReservedConstant { .. })));
     assert!(matches!(validate_non_reserved_var_name(&var("phi")), Err(NameIsReservedError::ReservedConstant { .. })));
   }
 
@@ -16,7 +16,9 @@ use std::fmt::{self, Display, Formatter};
 ///
 /// Variables are identified by strings. A variable's name must begin
 /// with a letter, followed by zero or more letters, digits, or
-/// apostrophes. This structure enforces these constraints.
+/// apostrophes, and optionally a single trailing underscore (which
+/// marks a pattern variable in a rewrite rule). This structure
+/// enforces these constraints.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Var(String);
@@ -31,11 +33,11 @@ pub struct TryFromStringError {
 }
 
 pub static VALID_NAME_RE: Lazy<Regex> = Lazy::new(|| {
-  Regex::new(r"^(?:[a-zA-Z$][a-zA-Z$0-9']*|∞|⧝)$").unwrap()
+  Regex::new(r"^(?:[a-zA-Z$][a-zA-Z$0-9']*_?|∞|⧝)$").unwrap()
 });
 
 pub static VALID_NAME_PREFIX_RE: Lazy<Regex> = Lazy::new(|| {
-  Regex::new(r"^(?:[a-zA-Z$][a-zA-Z$0-9']*|∞|⧝)").unwrap()
+  Regex::new(r"^(?:[a-zA-Z$][a-zA-Z$0-9']*_?|∞|⧝)").unwrap()
 });
 
 impl Var {
@@ -123,6 +125,8 @@ mod test {
     Var::new("$A$").unwrap();
     Var::new("A$").unwrap();
     Var::new("∞").unwrap();
+    Var::new("x_").unwrap();
+    Var::new("ab'_").unwrap();
   }
 
   #[test]
@@ -147,6 +151,10 @@ mod test {
     assert_eq!(Var::new("$∞"), None);
     assert_eq!(Var::new("∞x"), None);
     assert_eq!(Var::new("∞∞"), None);
+    assert_eq!(Var::new("_"), None);
+    assert_eq!(Var::new("_x"), None);
+    assert_eq!(Var::new("a_b"), None);
+    assert_eq!(Var::new("a__"), None);
   }
 
   #[test]
@@ -19,6 +19,14 @@ impl<T> VarTable<T> {
     Self { data: HashMap::with_capacity(capacity) }
   }
 
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
   pub fn get(&self, var: &Var) -> Option<&T> {
     self.data.get(var)
   }
@@ -31,6 +39,14 @@ impl<T> VarTable<T> {
     self.data.insert(var, value)
   }
 
+  pub fn keys(&self) -> impl Iterator<Item = &Var> {
+    self.data.keys()
+  }
+
+  pub fn values(&self) -> impl Iterator<Item = &T> {
+    self.data.values()
+  }
+
   pub fn remove(&mut self, var: &Var) -> Option<T> {
     self.data.remove(var)
   }
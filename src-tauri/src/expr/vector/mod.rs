@@ -1,6 +1,7 @@
 
 pub mod borrowed;
 pub mod matrix;
+pub mod packed;
 pub mod tensor;
 
 use matrix::Matrix;
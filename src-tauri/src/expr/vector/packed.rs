@@ -0,0 +1,405 @@
+
+//! Packed representation of homogeneous numeric tensors.
+//!
+//! In the expression language, a tensor is a nest of `vector` calls,
+//! and broadcasting arithmetic over a tensor produces one call per
+//! element, which the simplifier then visits and evaluates one at a
+//! time. For large vectors and matrices of real numbers, that is a lot
+//! of work to arrive at an answer we could have computed directly. A
+//! [`PackedTensor`] stores the elements of such a tensor contiguously,
+//! so that elementwise arithmetic can be done in a single pass.
+//! Tensors with any non-numeric element are not packed, and go
+//! through the usual [`Tensor`](super::tensor::Tensor) machinery.
+//!
+//! Packed tensors are not stored in [`Expr`], so a tensor is packed
+//! when an operation begins and unpacked when it ends. The real-valued
+//! cases of `+`, `-`, `*`, and `/` use them for a single operation.
+//! The `vmap` command uses [`PackedTensor::map_elementwise`] to
+//! evaluate an arithmetic subcommand over a whole vector (such as the
+//! result of `iota`), keeping every intermediate result packed, so
+//! that the vector is only packed and unpacked once.
+
+use super::{Vector, LengthError, vector_shape};
+use super::borrowed::BorrowedVector;
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::number::inexact::DivInexact;
+use crate::expr::var::Var;
+use crate::mode::calculation::CalculationMode;
+use crate::util::prism::Prism;
+
+use num::Zero;
+
+/// A tensor of real numbers, of any rank, stored in row-major order.
+/// A tensor of rank zero is a single scalar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedTensor {
+  shape: Vec<usize>,
+  data: Vec<Number>,
+}
+
+/// Prism which accepts real numbers and (possibly nested) vectors of
+/// real numbers with consistent dimensions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExprToPackedTensor;
+
+impl PackedTensor {
+  pub fn scalar(value: Number) -> Self {
+    Self { shape: vec![], data: vec![value] }
+  }
+
+  /// Packs the expression, if it is a real number or a tensor of
+  /// real numbers.
+  pub fn parse(expr: &Expr) -> Option<Self> {
+    let shape = vector_shape(expr);
+    let mut data = Vec::with_capacity(shape.iter().product());
+    collect_elements(expr, shape.len(), &mut data)?;
+    Some(Self { shape, data })
+  }
+
+  pub fn shape(&self) -> &[usize] {
+    &self.shape
+  }
+
+  pub fn rank(&self) -> usize {
+    self.shape.len()
+  }
+
+  /// The elements of the tensor, in row-major order.
+  pub fn data(&self) -> &[Number] {
+    &self.data
+  }
+
+  /// The shape which results from broadcasting the given tensors
+  /// together, or `None` if they are incompatible.
+  ///
+  /// Broadcasting follows the same rules as
+  /// [`Tensor`](super::tensor::Tensor): dimensions are matched from
+  /// the outside in, and an element of a lower-rank tensor is
+  /// repeated across the corresponding block of a higher-rank one. So
+  /// tensors are compatible if the shape of each is a prefix of the
+  /// shape of the highest-rank one.
+  pub fn broadcast_shape<'a, I>(tensors: I) -> Option<Vec<usize>>
+  where I: IntoIterator<Item = &'a PackedTensor> {
+    let mut shape: &[usize] = &[];
+    for tensor in tensors {
+      let (shorter, longer) = if tensor.shape.len() <= shape.len() {
+        (&tensor.shape[..], shape)
+      } else {
+        (shape, &tensor.shape[..])
+      };
+      if !longer.starts_with(shorter) {
+        return None;
+      }
+      shape = longer;
+    }
+    Some(shape.to_vec())
+  }
+
+  /// Applies `op` elementwise to the two tensors, broadcasting as
+  /// described in [`PackedTensor::broadcast_shape`].
+  pub fn try_broadcasted_op<F>(self, other: PackedTensor, mut op: F) -> Result<PackedTensor, LengthError>
+  where F: FnMut(&Number, &Number) -> Number {
+    if let Some((expected, actual)) = self.shape.iter().zip(&other.shape).find(|(a, b)| a != b) {
+      return Err(LengthError { expected: *expected, actual: *actual });
+    }
+    let shape = if self.rank() >= other.rank() { self.shape } else { other.shape };
+    let len: usize = shape.iter().product();
+    if len == 0 {
+      return Ok(PackedTensor { shape, data: vec![] });
+    }
+    // Each element of an operand covers a contiguous block of the
+    // result, of length 1 for the higher-rank operand.
+    let left_block = len / self.data.len();
+    let right_block = len / other.data.len();
+    let data = (0..len)
+      .map(|i| op(&self.data[i / left_block], &other.data[i / right_block]))
+      .collect();
+    Ok(PackedTensor { shape, data })
+  }
+
+  /// Evaluates `body` for every element of this vector at once, with
+  /// `var` standing for the element. Every intermediate result stays
+  /// packed.
+  ///
+  /// `body` may consist only of real numbers, `var`, and calls to
+  /// `+`, `-`, `*`, and `/`, which are evaluated as in the real-valued
+  /// cases of those functions. Returns `None` if `body` has any other
+  /// form, if it would divide by zero, or if this tensor is not a
+  /// vector (the arithmetic functions treat a row of a matrix as a
+  /// whole, not elementwise). In that case, the caller should
+  /// evaluate `body` for each element separately.
+  pub fn map_elementwise(&self, body: &Expr, var: &Var, calculation_mode: &CalculationMode) -> Option<PackedTensor> {
+    if self.rank() != 1 {
+      return None;
+    }
+    let result = self.eval_elementwise(body, var, calculation_mode)?;
+    if result.rank() == 0 {
+      // `body` does not mention `var`, so repeat the scalar result for
+      // each element.
+      result.try_broadcasted_op(self.clone(), |a, _| a.clone()).ok()
+    } else {
+      Some(result)
+    }
+  }
+
+  fn eval_elementwise(&self, body: &Expr, var: &Var, calculation_mode: &CalculationMode) -> Option<PackedTensor> {
+    match body {
+      Expr::Atom(Atom::Var(v)) if v == var => Some(self.clone()),
+      Expr::Atom(Atom::Number(n)) => Some(PackedTensor::scalar(n.clone())),
+      Expr::Atom(_) => None,
+      Expr::Call(function_name, args) => {
+        let args = args.iter()
+          .map(|arg| self.eval_elementwise(arg, var, calculation_mode))
+          .collect::<Option<Vec<_>>>()?;
+        match (function_name.as_str(), args.len()) {
+          ("+", 1..) => fold_elementwise(args, |a, b| a.clone() + b.clone()),
+          ("-", 2) => fold_elementwise(args, |a, b| a.clone() - b.clone()),
+          ("*", 1..) => fold_elementwise(args, |a, b| a.clone() * b.clone()),
+          ("/", 2) if !args[1].data.iter().any(Number::is_zero) => {
+            fold_elementwise(args, |a, b| real_quotient(a.clone(), b.clone(), calculation_mode))
+          }
+          _ => None,
+        }
+      }
+    }
+  }
+
+  /// Reifies the tensor as an `Expr` in the expression language.
+  pub fn into_expr(self) -> Expr {
+    let mut elements = self.data.into_iter().map(Expr::from);
+    build_nested(&self.shape, &mut elements)
+  }
+}
+
+/// Divides two real numbers, exactly or inexactly according to the
+/// calculation mode. Panics if `b` is zero.
+pub fn real_quotient(a: Number, b: Number, calculation_mode: &CalculationMode) -> Number {
+  if calculation_mode.has_fractional_flag() {
+    a / b
+  } else if let Some(precision) = calculation_mode.precision() {
+    a.div_inexact_to_precision(&b, precision)
+  } else {
+    a.div_inexact(&b)
+  }
+}
+
+fn fold_elementwise<F>(args: Vec<PackedTensor>, mut f: F) -> Option<PackedTensor>
+where F: FnMut(&Number, &Number) -> Number {
+  let mut args = args.into_iter();
+  let first = args.next()?;
+  args.try_fold(first, |a, b| a.try_broadcasted_op(b, &mut f).ok())
+}
+
+fn collect_elements(expr: &Expr, depth: usize, acc: &mut Vec<Number>) -> Option<()> {
+  if depth == 0 {
+    acc.push(expr.as_real_ref()?.clone());
+    return Some(());
+  }
+  let vector = BorrowedVector::parse(expr).ok()?;
+  for elem in vector {
+    collect_elements(elem, depth - 1, acc)?;
+  }
+  Some(())
+}
+
+fn build_nested(shape: &[usize], elements: &mut impl Iterator<Item = Expr>) -> Expr {
+  match shape.split_first() {
+    None => {
+      // unwrap: The shape accounts for every element.
+      elements.next().unwrap()
+    }
+    Some((len, inner_shape)) => {
+      let vector: Vector = (0..*len).map(|_| build_nested(inner_shape, elements)).collect();
+      vector.into_expr()
+    }
+  }
+}
+
+impl From<PackedTensor> for Expr {
+  fn from(tensor: PackedTensor) -> Expr {
+    tensor.into_expr()
+  }
+}
+
+impl Prism<Expr, PackedTensor> for ExprToPackedTensor {
+  fn narrow_type(&self, input: Expr) -> Result<PackedTensor, Expr> {
+    PackedTensor::parse(&input).ok_or(input)
+  }
+
+  fn widen_type(&self, input: PackedTensor) -> Expr {
+    input.into_expr()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::errorlist::ErrorList;
+  use crate::expr::function::library::build_function_table;
+  use crate::expr::simplifier::{Simplifier, SimplifierContext};
+  use crate::expr::simplifier::evaluator::FunctionEvaluator;
+  use crate::mode::calculation::CalculationMode;
+
+  fn vector(elems: Vec<Expr>) -> Expr {
+    Expr::call("vector", elems)
+  }
+
+  fn int_vector(elems: &[i64]) -> Expr {
+    vector(elems.iter().map(|x| Expr::from(*x)).collect())
+  }
+
+  fn packed(expr: &Expr) -> PackedTensor {
+    PackedTensor::parse(expr).unwrap()
+  }
+
+  #[test]
+  fn test_parse_scalar() {
+    let tensor = packed(&Expr::from(3));
+    assert_eq!(tensor.shape(), &[] as &[usize]);
+    assert_eq!(tensor.data(), &[Number::from(3)]);
+  }
+
+  #[test]
+  fn test_parse_matrix() {
+    let expr = vector(vec![int_vector(&[1, 2, 3]), int_vector(&[4, 5, 6])]);
+    let tensor = packed(&expr);
+    assert_eq!(tensor.shape(), &[2, 3]);
+    assert_eq!(tensor.data(), &[1, 2, 3, 4, 5, 6].map(Number::from));
+    assert_eq!(tensor.into_expr(), expr);
+  }
+
+  #[test]
+  fn test_parse_empty_vector() {
+    let tensor = packed(&vector(vec![]));
+    assert_eq!(tensor.shape(), &[0]);
+    assert_eq!(tensor.into_expr(), vector(vec![]));
+  }
+
+  #[test]
+  fn test_parse_failures() {
+    assert_eq!(PackedTensor::parse(&Expr::var("x").unwrap()), None);
+    assert_eq!(PackedTensor::parse(&vector(vec![Expr::from(1), Expr::var("x").unwrap()])), None);
+    // Ragged matrices are not tensors.
+    assert_eq!(PackedTensor::parse(&vector(vec![int_vector(&[1, 2]), int_vector(&[3])])), None);
+    assert_eq!(PackedTensor::parse(&vector(vec![int_vector(&[1, 2]), Expr::from(3)])), None);
+  }
+
+  #[test]
+  fn test_prism_failure_returns_input() {
+    let expr = vector(vec![Expr::from(1), Expr::var("x").unwrap()]);
+    assert_eq!(ExprToPackedTensor.narrow_type(expr.clone()), Err(expr));
+  }
+
+  #[test]
+  fn test_broadcast_shape() {
+    let scalar = packed(&Expr::from(1));
+    let vec = packed(&int_vector(&[1, 2]));
+    let mat = packed(&vector(vec![int_vector(&[1, 2, 3]), int_vector(&[4, 5, 6])]));
+    let long_vec = packed(&int_vector(&[1, 2, 3]));
+    assert_eq!(PackedTensor::broadcast_shape([&scalar, &vec]), Some(vec![2]));
+    assert_eq!(PackedTensor::broadcast_shape([&vec, &mat, &scalar]), Some(vec![2, 3]));
+    assert_eq!(PackedTensor::broadcast_shape([&long_vec, &mat]), None);
+    assert_eq!(PackedTensor::broadcast_shape([&vec, &long_vec]), None);
+  }
+
+  #[test]
+  fn test_broadcasted_op() {
+    let vec = packed(&int_vector(&[10, 20]));
+    let mat = packed(&vector(vec![int_vector(&[1, 2, 3]), int_vector(&[4, 5, 6])]));
+    let result = vec.try_broadcasted_op(mat, |a, b| a.clone() - b.clone()).unwrap();
+    assert_eq!(
+      result.into_expr(),
+      vector(vec![int_vector(&[9, 8, 7]), int_vector(&[16, 15, 14])]),
+    );
+  }
+
+  #[test]
+  fn test_broadcasted_op_with_scalar() {
+    let vec = packed(&int_vector(&[1, 2, 3]));
+    let result = vec.try_broadcasted_op(PackedTensor::scalar(Number::from(2)), |a, b| a.clone() * b.clone()).unwrap();
+    assert_eq!(result.into_expr(), int_vector(&[2, 4, 6]));
+  }
+
+  #[test]
+  fn test_broadcasted_op_length_mismatch() {
+    let left = packed(&int_vector(&[1, 2, 3]));
+    let right = packed(&int_vector(&[1, 2]));
+    assert_eq!(
+      left.try_broadcasted_op(right, |a, b| a.clone() + b.clone()),
+      Err(LengthError { expected: 3, actual: 2 }),
+    );
+  }
+
+  #[test]
+  fn test_map_elementwise() {
+    let x = Var::new("x").unwrap();
+    let vec = packed(&int_vector(&[1, 2, 3, 4]));
+    // 2x + 1
+    let body = Expr::call("+", vec![
+      Expr::call("*", vec![Expr::from(2), Expr::from(x.clone())]),
+      Expr::from(1),
+    ]);
+    let result = vec.map_elementwise(&body, &x, &CalculationMode::default()).unwrap();
+    assert_eq!(result.into_expr(), int_vector(&[3, 5, 7, 9]));
+  }
+
+  #[test]
+  fn test_map_elementwise_with_constant_body() {
+    let x = Var::new("x").unwrap();
+    let vec = packed(&int_vector(&[1, 2, 3]));
+    let body = Expr::call("-", vec![Expr::from(10), Expr::from(3)]);
+    let result = vec.map_elementwise(&body, &x, &CalculationMode::default()).unwrap();
+    assert_eq!(result.into_expr(), int_vector(&[7, 7, 7]));
+  }
+
+  #[test]
+  fn test_map_elementwise_division() {
+    let x = Var::new("x").unwrap();
+    let vec = packed(&int_vector(&[1, 2, 4]));
+    let body = Expr::call("/", vec![Expr::from(x.clone()), Expr::from(2)]);
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_fractional_flag(true);
+    let result = vec.map_elementwise(&body, &x, &calculation_mode).unwrap();
+    assert_eq!(result.data(), &[Number::ratio(1, 2), Number::from(1), Number::from(2)]);
+  }
+
+  #[test]
+  fn test_map_elementwise_failures() {
+    let x = Var::new("x").unwrap();
+    let calculation_mode = CalculationMode::default();
+    let vec = packed(&int_vector(&[0, 1, 2]));
+    // Non-arithmetic functions
+    let body = Expr::call("sin", vec![Expr::from(x.clone())]);
+    assert_eq!(vec.map_elementwise(&body, &x, &calculation_mode), None);
+    // Other variables
+    let body = Expr::call("+", vec![Expr::from(x.clone()), Expr::var("y").unwrap()]);
+    assert_eq!(vec.map_elementwise(&body, &x, &calculation_mode), None);
+    // Division by zero
+    let body = Expr::call("/", vec![Expr::from(1), Expr::from(x.clone())]);
+    assert_eq!(vec.map_elementwise(&body, &x, &calculation_mode), None);
+    // Matrices
+    let mat = packed(&vector(vec![int_vector(&[1, 2]), int_vector(&[3, 4])]));
+    let body = Expr::call("*", vec![Expr::from(x.clone()), Expr::from(x.clone())]);
+    assert_eq!(mat.map_elementwise(&body, &x, &calculation_mode), None);
+  }
+
+  #[test]
+  fn test_arithmetic_evaluates_packed_vectors_in_one_step() {
+    // Evaluating a single `+` call, without simplifying the result
+    // any further, produces the finished vector rather than one `+`
+    // call per element.
+    let function_table = build_function_table();
+    let evaluator = FunctionEvaluator::new(&function_table);
+    let mut errors = ErrorList::new();
+    let mut context = SimplifierContext {
+      base_simplifier: &evaluator,
+      calculation_mode: CalculationMode::default(),
+      errors: &mut errors,
+    };
+    let expr = Expr::call("+", vec![int_vector(&[1, 2, 3]), int_vector(&[10, 20, 30])]);
+    assert_eq!(evaluator.simplify_expr_part(expr, &mut context), int_vector(&[11, 22, 33]));
+    assert!(errors.is_empty());
+  }
+}
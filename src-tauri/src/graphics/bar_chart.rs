@@ -0,0 +1,69 @@
+
+//! Functionality for producing bar charts of labeled data.
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use super::{floatify, label_text};
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+/// A bar chart with one bar per label in each series. Whether the
+/// series are drawn side by side or stacked is determined by the
+/// directive containing this chart, not by the chart itself.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BarChartDirective {
+  pub labels: Vec<String>,
+  /// Each series has exactly one value per label.
+  pub series: Vec<Vec<f64>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("Expected {expected} values in each series to match the labels, got {actual}")]
+pub struct BarChartLengthError {
+  expected: usize,
+  actual: usize,
+}
+
+impl BarChartDirective {
+  /// Builds a bar chart whose series are the rows of `data`. Fails if
+  /// any row does not have exactly one value per label.
+  pub fn from_data(data: &[Vec<Number>], labels: &[Expr]) -> Result<BarChartDirective, BarChartLengthError> {
+    if let Some(row) = data.iter().find(|row| row.len() != labels.len()) {
+      return Err(BarChartLengthError { expected: labels.len(), actual: row.len() });
+    }
+    Ok(BarChartDirective {
+      labels: labels.iter().map(label_text).collect(),
+      series: data.iter().map(floatify).collect(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn numbers(values: &[i64]) -> Vec<Number> {
+    values.iter().copied().map(Number::from).collect()
+  }
+
+  #[test]
+  fn test_from_data() {
+    let data = vec![numbers(&[1, 2, 3]), numbers(&[4, 5, 6])];
+    let labels = vec![Expr::from("a"), Expr::from("b"), Expr::from(2024)];
+    let chart = BarChartDirective::from_data(&data, &labels).unwrap();
+    assert_eq!(chart, BarChartDirective {
+      labels: vec![String::from("a"), String::from("b"), String::from("2024")],
+      series: vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]],
+    });
+  }
+
+  #[test]
+  fn test_from_data_with_length_mismatch() {
+    let data = vec![numbers(&[1, 2, 3]), numbers(&[4, 5])];
+    let labels = vec![Expr::from("a"), Expr::from("b"), Expr::from("c")];
+    let err = BarChartDirective::from_data(&data, &labels).unwrap_err();
+    assert_eq!(err, BarChartLengthError { expected: 3, actual: 2 });
+  }
+}
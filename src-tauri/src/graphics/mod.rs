@@ -1,12 +1,18 @@
 
 //! Support for plotting and graphical output.
 
+pub mod bar_chart;
 pub mod contour_plot;
 pub mod dataset;
 pub mod payload;
+pub mod pie_chart;
 pub mod plot;
 pub mod response;
+pub mod scatter_plot;
+pub mod time_plot;
 
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
 use crate::expr::number::Number;
 
 use serde::{Serialize, Deserialize};
@@ -58,6 +64,15 @@ where I: IntoIterator<Item = T>,
   iter.into_iter().map(|n| n.borrow().to_f64_or_nan()).collect()
 }
 
+/// The text of a chart label. Strings are shown without quotation
+/// marks, and any other expression is shown as-is.
+pub fn label_text(label: &Expr) -> String {
+  match label {
+    Expr::Atom(Atom::String(s)) => s.clone(),
+    expr => expr.to_string(),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
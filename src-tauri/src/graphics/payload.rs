@@ -98,7 +98,7 @@ impl GraphicsPayload {
       })?;
       let directive = function.call_for_graphics(args, errors, simplifier, function_table, calculation_mode.clone())
         .map_err(|_| CompileGraphicsError::GraphicsDirectiveError)?;
-      response.directives.push(directive);
+      response.push_directive(directive);
     }
     Ok(response)
   }
@@ -0,0 +1,85 @@
+
+//! Functionality for producing pie charts of labeled data.
+
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::prisms::NonNegativeNumber;
+use super::label_text;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+/// A pie chart with one wedge per value. The size of each wedge is
+/// its value's share of the total.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PieChartDirective {
+  pub labels: Vec<String>,
+  pub values: Vec<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum PieChartError {
+  #[error("Expected {expected} labels to match the values, got {actual}")]
+  LengthMismatch { expected: usize, actual: usize },
+  #[error("Expected at least one positive value")]
+  NoPositiveValues,
+}
+
+impl PieChartDirective {
+  /// Builds a pie chart of the given values. If no labels are given,
+  /// the wedges are numbered from one.
+  pub fn from_data(
+    values: Vec<NonNegativeNumber>,
+    labels: Option<&[Expr]>,
+  ) -> Result<PieChartDirective, PieChartError> {
+    let values: Vec<f64> = values.into_iter().map(|value| Number::from(value).to_f64_or_nan()).collect();
+    if !values.iter().any(|value| *value > 0.0) {
+      return Err(PieChartError::NoPositiveValues);
+    }
+    let labels = match labels {
+      None => (1..=values.len()).map(|i| i.to_string()).collect(),
+      Some(labels) if labels.len() == values.len() => labels.iter().map(label_text).collect(),
+      Some(labels) => {
+        return Err(PieChartError::LengthMismatch { expected: values.len(), actual: labels.len() });
+      }
+    };
+    Ok(PieChartDirective { labels, values })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn values(values: &[i64]) -> Vec<NonNegativeNumber> {
+    values.iter().map(|v| NonNegativeNumber::new(Number::from(*v)).unwrap()).collect()
+  }
+
+  #[test]
+  fn test_from_data() {
+    let labels = vec![Expr::from("a"), Expr::from(7)];
+    let chart = PieChartDirective::from_data(values(&[1, 3]), Some(&labels)).unwrap();
+    assert_eq!(chart, PieChartDirective {
+      labels: vec![String::from("a"), String::from("7")],
+      values: vec![1.0, 3.0],
+    });
+  }
+
+  #[test]
+  fn test_from_data_without_labels() {
+    let chart = PieChartDirective::from_data(values(&[0, 2, 5]), None).unwrap();
+    assert_eq!(chart.labels, vec![String::from("1"), String::from("2"), String::from("3")]);
+  }
+
+  #[test]
+  fn test_from_data_errors() {
+    assert_eq!(
+      PieChartDirective::from_data(values(&[1, 2]), Some(&[Expr::from("a")])),
+      Err(PieChartError::LengthMismatch { expected: 2, actual: 1 }),
+    );
+    assert_eq!(PieChartDirective::from_data(values(&[0, 0]), None), Err(PieChartError::NoPositiveValues));
+    assert_eq!(PieChartDirective::from_data(vec![], None), Err(PieChartError::NoPositiveValues));
+  }
+}
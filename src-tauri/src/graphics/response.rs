@@ -4,12 +4,48 @@
 use super::GraphicsType;
 use super::plot::PlotDirective;
 use super::contour_plot::ContourPlotDirective;
+use super::time_plot::TimePlotDirective;
+use super::bar_chart::BarChartDirective;
+use super::pie_chart::PieChartDirective;
+use super::scatter_plot::ScatterPlotDirective;
 
 use serde::{Serialize, Deserialize};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct GraphicsResponse {
   pub directives: Vec<GraphicsDirective>,
+  pub x_axis: AxisType,
+  pub bar_mode: BarMode,
+}
+
+/// The kind of scale used by an axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AxisType {
+  /// An ordinary numerical axis.
+  #[default]
+  #[serde(rename = "linear")]
+  Linear,
+  /// An axis of UTC datetimes, whose coordinates are milliseconds
+  /// since the Unix epoch and whose tick labels are dates.
+  #[serde(rename = "date")]
+  Date,
+  /// An axis of discrete labels, in the order they are first given.
+  #[serde(rename = "category")]
+  Category,
+}
+
+/// How bars from different series sharing a label are arranged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum BarMode {
+  /// Bars are drawn side by side.
+  #[default]
+  #[serde(rename = "group")]
+  Group,
+  /// Bars are stacked on top of one another. Negative values stack
+  /// downward from zero, separately from positive values.
+  #[serde(rename = "relative")]
+  Stack,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -19,6 +55,16 @@ pub enum GraphicsDirective {
   Plot(PlotDirective),
   #[serde(rename = "contourplot")]
   ContourPlot(ContourPlotDirective),
+  #[serde(rename = "timeplot")]
+  TimePlot(TimePlotDirective),
+  #[serde(rename = "barchart")]
+  BarChart(BarChartDirective),
+  #[serde(rename = "stackedbar")]
+  StackedBar(BarChartDirective),
+  #[serde(rename = "piechart")]
+  PieChart(PieChartDirective),
+  #[serde(rename = "scatterplot")]
+  ScatterPlot(ScatterPlotDirective),
 }
 
 impl GraphicsDirective {
@@ -26,6 +72,33 @@ impl GraphicsDirective {
     match self {
       GraphicsDirective::Plot(_) => GraphicsType::TwoDimensional,
       GraphicsDirective::ContourPlot(_) => GraphicsType::TwoDimensional,
+      GraphicsDirective::TimePlot(_) => GraphicsType::TwoDimensional,
+      GraphicsDirective::BarChart(_) => GraphicsType::TwoDimensional,
+      GraphicsDirective::StackedBar(_) => GraphicsType::TwoDimensional,
+      GraphicsDirective::PieChart(_) => GraphicsType::TwoDimensional,
+      GraphicsDirective::ScatterPlot(_) => GraphicsType::TwoDimensional,
+    }
+  }
+
+  /// The kind of X axis this directive needs. Directives which do
+  /// not draw axes at all, such as pie charts, report a linear axis.
+  pub fn x_axis_type(&self) -> AxisType {
+    match self {
+      GraphicsDirective::Plot(_) | GraphicsDirective::ContourPlot(_) | GraphicsDirective::ScatterPlot(_) |
+        GraphicsDirective::PieChart(_) => AxisType::Linear,
+      GraphicsDirective::BarChart(_) | GraphicsDirective::StackedBar(_) => AxisType::Category,
+      GraphicsDirective::TimePlot(_) => AxisType::Date,
+    }
+  }
+
+  /// The arrangement of bars this directive needs, if it draws bars
+  /// at all.
+  pub fn bar_mode(&self) -> Option<BarMode> {
+    match self {
+      GraphicsDirective::BarChart(_) => Some(BarMode::Group),
+      GraphicsDirective::StackedBar(_) => Some(BarMode::Stack),
+      GraphicsDirective::Plot(_) | GraphicsDirective::ContourPlot(_) | GraphicsDirective::TimePlot(_) |
+        GraphicsDirective::PieChart(_) | GraphicsDirective::ScatterPlot(_) => None,
     }
   }
 }
@@ -34,4 +107,19 @@ impl GraphicsResponse {
   pub fn new() -> Self {
     Self::default()
   }
+
+  /// Adds a directive to the response. If any directive needs a date
+  /// or category axis, the whole response gets one, since all
+  /// directives share the same axes. Likewise, any stacked bar chart
+  /// stacks every bar in the response.
+  pub fn push_directive(&mut self, directive: GraphicsDirective) {
+    let x_axis = directive.x_axis_type();
+    if x_axis != AxisType::Linear {
+      self.x_axis = x_axis;
+    }
+    if directive.bar_mode() == Some(BarMode::Stack) {
+      self.bar_mode = BarMode::Stack;
+    }
+    self.directives.push(directive);
+  }
 }
@@ -0,0 +1,88 @@
+
+//! Functionality for producing scatter plots of data points,
+//! optionally with a fitted curve.
+
+use crate::util::point::Point2D;
+use crate::expr::number::Number;
+use crate::expr::algebra::regression::{FitModel, FitError};
+use super::dataset::{XDataSet, GenReason};
+
+use serde::{Serialize, Deserialize};
+
+/// A plot of individual data points, drawn as markers rather than
+/// connected by lines.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScatterPlotDirective {
+  pub points: Vec<Point2D>,
+  /// The least-squares curve through the points, if one was
+  /// requested, sampled across the range of the data.
+  pub regression: Option<Vec<Point2D>>,
+}
+
+impl ScatterPlotDirective {
+  pub fn from_points(points: &[[Number; 2]]) -> ScatterPlotDirective {
+    ScatterPlotDirective {
+      points: points.iter().map(to_point).collect(),
+      regression: None,
+    }
+  }
+
+  /// Plots the points along with the least-squares curve of the given
+  /// model. Fails if the model cannot be fitted to the points.
+  pub fn with_regression(points: &[[Number; 2]], model: FitModel) -> Result<ScatterPlotDirective, FitError> {
+    let fit = model.fit(points)?;
+    // unwrap: fit succeeded, so there is at least one point.
+    let min_x = points.iter().map(|[x, _]| x).min().unwrap().clone();
+    let max_x = points.iter().map(|[x, _]| x).max().unwrap().clone();
+    let regression = XDataSet::interval(min_x, max_x).gen_points(GenReason::OneDimensional)
+      .into_iter()
+      .map(|x| {
+        let y = model.predict(&fit.coefficients, &x).map_or(f64::NAN, |y| y.to_f64_or_nan());
+        Point2D { x: x.to_f64_or_nan(), y }
+      })
+      .collect();
+    Ok(ScatterPlotDirective {
+      points: points.iter().map(to_point).collect(),
+      regression: Some(regression),
+    })
+  }
+}
+
+fn to_point([x, y]: &[Number; 2]) -> Point2D {
+  Point2D { x: x.to_f64_or_nan(), y: y.to_f64_or_nan() }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn points(points: &[(i64, i64)]) -> Vec<[Number; 2]> {
+    points.iter().map(|(x, y)| [Number::from(*x), Number::from(*y)]).collect()
+  }
+
+  #[test]
+  fn test_from_points() {
+    let plot = ScatterPlotDirective::from_points(&points(&[(0, 1), (2, 3)]));
+    assert_eq!(plot, ScatterPlotDirective {
+      points: vec![Point2D { x: 0.0, y: 1.0 }, Point2D { x: 2.0, y: 3.0 }],
+      regression: None,
+    });
+  }
+
+  #[test]
+  fn test_with_regression() {
+    let plot = ScatterPlotDirective::with_regression(&points(&[(2, 1), (0, 1), (4, 4)]), FitModel::Linear).unwrap();
+    assert_eq!(plot.points.len(), 3);
+    let regression = plot.regression.unwrap();
+    assert_eq!(regression.len(), XDataSet::INTERVAL_DATA_POINTS_ONE_DIM);
+    // y = 1/2 + 3x/4
+    assert_eq!(regression.first(), Some(&Point2D { x: 0.0, y: 0.5 }));
+    assert_eq!(regression.last(), Some(&Point2D { x: 4.0, y: 3.5 }));
+  }
+
+  #[test]
+  fn test_with_regression_failure() {
+    ScatterPlotDirective::with_regression(&points(&[(0, 1)]), FitModel::Linear).unwrap_err();
+  }
+}
@@ -0,0 +1,100 @@
+
+//! Functionality for plotting values against dates and times.
+
+use crate::util::point::Point2D;
+use crate::util::prism::{Prism, Identity};
+use crate::util::calendar::{parse_iso_datetime, ParseDateError, UNIX_EPOCH_JULIAN_DATE, SECONDS_PER_DAY};
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::vector::Vector;
+use crate::expr::prisms::expr_to_typed_array;
+
+use serde::{Serialize, Deserialize};
+
+/// A plot whose X axis is time. The X coordinate of each point is
+/// the number of milliseconds since the Unix epoch, which is the
+/// representation the frontend's date axes expect.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimePlotDirective {
+  pub points: Vec<Point2D>,
+}
+
+/// A value at a UTC datetime, given in ISO 8601 format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimePoint {
+  pub time: String,
+  pub value: Number,
+}
+
+/// Prism which parses a two-element vector of a datetime string and
+/// a real number as a [`TimePoint`].
+#[derive(Clone, Debug, Copy, Default)]
+pub struct ExprToTimePoint;
+
+impl TimePlotDirective {
+  /// Plots the given points in order of time. Fails if any datetime
+  /// cannot be parsed.
+  pub fn from_time_points(time_points: &[TimePoint]) -> Result<TimePlotDirective, ParseDateError> {
+    let mut points = time_points.iter().map(|point| {
+      let julian_date = parse_iso_datetime(&point.time)?;
+      let milliseconds = (julian_date - UNIX_EPOCH_JULIAN_DATE) * SECONDS_PER_DAY * 1000.0;
+      Ok(Point2D { x: milliseconds, y: point.value.to_f64_or_nan() })
+    }).collect::<Result<Vec<_>, ParseDateError>>()?;
+    points.sort_by(|a, b| a.x.total_cmp(&b.x));
+    Ok(TimePlotDirective { points })
+  }
+}
+
+impl Prism<Expr, TimePoint> for ExprToTimePoint {
+  fn narrow_type(&self, expr: Expr) -> Result<TimePoint, Expr> {
+    let [time, value] = expr_to_typed_array(Identity).narrow_type(expr)?;
+    match (time, value) {
+      (Expr::Atom(Atom::String(time)), Expr::Atom(Atom::Number(value))) => Ok(TimePoint { time, value }),
+      (time, value) => Err(Vector::from(vec![time, value]).into()),
+    }
+  }
+
+  fn widen_type(&self, point: TimePoint) -> Expr {
+    Vector::from(vec![Expr::from(point.time), Expr::from(point.value)]).into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn time_point(time: &str, value: i64) -> TimePoint {
+    TimePoint { time: time.to_owned(), value: Number::from(value) }
+  }
+
+  #[test]
+  fn test_from_time_points() {
+    let directive = TimePlotDirective::from_time_points(&[
+      time_point("1970-01-02", 20),
+      time_point("1970-01-01T00:00:01Z", 10),
+    ]).unwrap();
+    assert_eq!(directive.points.len(), 2);
+    assert_eq!(directive.points[0].y, 10.0);
+    assert!((directive.points[0].x - 1000.0).abs() < 1.0);
+    assert_eq!(directive.points[1].y, 20.0);
+    assert!((directive.points[1].x - 86_400_000.0).abs() < 1.0);
+  }
+
+  #[test]
+  fn test_from_time_points_bad_date() {
+    TimePlotDirective::from_time_points(&[time_point("yesterday", 1)]).unwrap_err();
+  }
+
+  #[test]
+  fn test_expr_to_time_point() {
+    let expr = Expr::from(Vector::from(vec![Expr::from("2024-03-01"), Expr::from(5)]));
+    assert_eq!(ExprToTimePoint.narrow_type(expr.clone()), Ok(time_point("2024-03-01", 5)));
+    assert_eq!(ExprToTimePoint.widen_type(time_point("2024-03-01", 5)), expr);
+    let expr = Expr::from(Vector::from(vec![Expr::from(5), Expr::from("2024-03-01")]));
+    assert_eq!(ExprToTimePoint.narrow_type(expr.clone()), Err(expr));
+    let expr = Expr::from("2024-03-01");
+    assert_eq!(ExprToTimePoint.narrow_type(expr.clone()), Err(expr));
+  }
+}
@@ -1,26 +1,138 @@
 
+use crate::util::angles::{Radians, Degrees};
+use crate::util::prism::Prism;
+use crate::expr::number::bigfloat::MAX_PRECISION;
+
 use bitflags::bitflags;
+use num::{BigInt, Integer, One};
+use serde::{Serialize, Deserialize};
+
+use std::f64::consts::PI;
+use std::fmt::{self, Display, Formatter};
 
 /// The calculator's current calculation mode includes several bitwise
 /// flags indicating how to evaluate expressions.
 ///
 /// This structure is designed to be cheap to clone, but its exact
 /// implementation is private.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CalculationMode {
   inner: CalculationModeBits,
+  modulus: Option<u64>,
+  #[serde(default)]
+  precision: Option<u32>,
+  #[serde(default)]
+  word_size: Option<WordSize>,
+  #[serde(default)]
+  rounding_mode: RoundingMode,
 }
 
 bitflags! {
-  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+  #[serde(transparent)]
   struct CalculationModeBits: u8 {
     /// See [`CalculationMode::has_infinity_flag`].
     const INFINITY = 0b0001;
     /// See [`CalculationMode::has_fractional_flag`].
     const FRACTIONAL = 0b0010;
+    /// See [`CalculationMode::branch_cut`].
+    const POSITIVE_BRANCH_CUT = 0b0100;
+    /// See [`CalculationMode::angle_mode`].
+    const DEGREES = 0b1000;
+    /// See [`CalculationMode::has_exact_matrix_flag`].
+    const EXACT_MATRICES = 0b10000;
+    /// See [`CalculationMode::has_units_flag`].
+    const UNITS = 0b100000;
+    /// See [`CalculationMode::has_derived_units_flag`].
+    const DERIVED_UNITS = 0b1000000;
   }
 }
 
+/// Prism which parses a modulus for modular arithmetic mode. The
+/// string `0` clears the modulus, and any integer of at least two
+/// sets it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToModulus;
+
+/// Prism which parses a precision for arbitrary-precision mode. The
+/// string `0` clears the precision, and any integer from one to
+/// [`MAX_PRECISION`] sets it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToPrecision;
+
+/// Prism which parses a word size for the bitwise functions, such as
+/// `u8` or `i32`. The string `0` clears the word size.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToWordSize;
+
+/// The size and signedness of the machine words used by the bitwise
+/// functions. See [`CalculationMode::word_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WordSize {
+  bits: u32,
+  signed: bool,
+}
+
+/// How a value is rounded when some of its digits must be discarded,
+/// both by the rounding functions and by the fixed, scientific, and
+/// engineering float formats. See [`CalculationMode::rounding_mode`].
+///
+/// Every mode is symmetric about zero, so a negative value rounds to
+/// the negation of what its absolute value rounds to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingMode {
+  /// Round to the nearest value, with ties rounded away from zero.
+  #[default]
+  HalfUp,
+  /// Round to the nearest value, with ties rounded to the value
+  /// whose last digit is even. Also known as banker's rounding.
+  HalfEven,
+  /// Discard the extra digits, rounding toward zero.
+  TowardZero,
+  /// Round away from zero whenever any nonzero digit is discarded.
+  AwayFromZero,
+}
+
+/// The digits discarded by rounding, relative to one half of the last
+/// kept place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Discarded {
+  Zero,
+  BelowHalf,
+  Half,
+  AboveHalf,
+}
+
+/// Prism which parses the name of a [`RoundingMode`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToRoundingMode;
+
+/// The unit in which angles are read and written by angle-aware
+/// parts of the calculator, such as polar complex literals.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AngleMode {
+  #[default]
+  Radians,
+  Degrees,
+}
+
+/// The position of the branch cut used when computing the principal
+/// value of multi-valued complex functions, such as `arg` and `ln`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BranchCut {
+  /// The branch cut lies along the negative real axis, so angles are
+  /// reported in the range `(-pi, pi]`. This is the usual
+  /// mathematical convention.
+  #[default]
+  NegativeReal,
+  /// The branch cut lies along the positive real axis, so angles are
+  /// reported in the range `[0, 2pi)`. This convention is common in
+  /// engineering fields.
+  PositiveReal,
+}
+
 impl CalculationMode {
   pub fn new() -> Self {
     Self::default()
@@ -57,6 +169,104 @@ impl CalculationMode {
     self.inner.contains(CalculationModeBits::FRACTIONAL)
   }
 
+  /// The exact matrix flag is off by default. If this flag is set,
+  /// then matrix operations are carried out in exact rational
+  /// arithmetic, regardless of the fractional flag. Matrix operations
+  /// which can only be computed numerically, such as `matexp`, will
+  /// produce an error rather than a floating-point result.
+  ///
+  /// Matrices which already contain floating-point values are not
+  /// affected.
+  pub fn has_exact_matrix_flag(&self) -> bool {
+    self.inner.contains(CalculationModeBits::EXACT_MATRICES)
+  }
+
+  /// The units flag is off by default. If this flag is set, then
+  /// every simplification also simplifies units, as the
+  /// `simplify_units` command does. Compatible units are canceled
+  /// and terms of the same dimension are combined, so that (for
+  /// instance) `10 m / 2 s` becomes `5 m / s`.
+  pub fn has_units_flag(&self) -> bool {
+    self.inner.contains(CalculationModeBits::UNITS)
+  }
+
+  /// The derived units flag is off by default. If this flag is set,
+  /// then unit simplification replaces a product of several units
+  /// with a single named unit of the same dimension where one exists,
+  /// such as `N m` with `J`. This applies whenever units are
+  /// simplified, whether by `simplify_units` or because of the units
+  /// flag.
+  pub fn has_derived_units_flag(&self) -> bool {
+    self.inner.contains(CalculationModeBits::DERIVED_UNITS)
+  }
+
+  /// The branch cut for multi-valued complex functions. Defaults to
+  /// [`BranchCut::NegativeReal`].
+  pub fn branch_cut(&self) -> BranchCut {
+    if self.inner.contains(CalculationModeBits::POSITIVE_BRANCH_CUT) {
+      BranchCut::PositiveReal
+    } else {
+      BranchCut::NegativeReal
+    }
+  }
+
+  /// The unit used for angles in polar notation. Defaults to
+  /// [`AngleMode::Radians`].
+  ///
+  /// The trigonometric functions themselves always work in radians,
+  /// regardless of this setting.
+  pub fn angle_mode(&self) -> AngleMode {
+    if self.inner.contains(CalculationModeBits::DEGREES) {
+      AngleMode::Degrees
+    } else {
+      AngleMode::Radians
+    }
+  }
+
+  /// The modulus for modular arithmetic mode, or `None` if modular
+  /// arithmetic mode is off (the default). When a modulus `n` is
+  /// set, polynomial operations such as `poly_mul` and `poly_gcd`
+  /// treat their coefficients as integers mod `n`.
+  pub fn modulus(&self) -> Option<u64> {
+    self.modulus
+  }
+
+  /// The number of significant digits used for inexact
+  /// computations, or `None` to use ordinary `f64` values (the
+  /// default). When a precision is set, inexact values are carried as
+  /// [`BigFloat`](crate::expr::number::bigfloat::BigFloat)s with that
+  /// many digits.
+  ///
+  /// Arithmetic, square roots, powers, `exp`, logarithms, and the
+  /// trigonometric and hyperbolic functions (and their inverses) of
+  /// real arguments are computed to the full precision. Everything
+  /// else, such as the gamma function or any function of a complex
+  /// argument, is still computed with `f64` values, as are the
+  /// constants `pi` and `e`. Those results are widened to `BigFloat`s
+  /// of the requested precision but are only accurate to about
+  /// [`F64_PRECISION`](crate::expr::number::bigfloat::F64_PRECISION)
+  /// significant digits.
+  pub fn precision(&self) -> Option<u32> {
+    self.precision
+  }
+
+  /// The word size for the bitwise functions, or `None` to treat
+  /// integers as unbounded (the default). Unbounded integers behave
+  /// as though they had infinitely many bits, in two's complement.
+  /// When a word size is set, the bitwise functions reduce their
+  /// arguments and results into the range of the word, wrapping
+  /// around on overflow. Ordinary arithmetic is not affected.
+  pub fn word_size(&self) -> Option<WordSize> {
+    self.word_size
+  }
+
+  /// The rounding mode, used by the rounding functions and by the
+  /// float formats which round their output. Defaults to
+  /// [`RoundingMode::HalfUp`].
+  pub fn rounding_mode(&self) -> RoundingMode {
+    self.rounding_mode
+  }
+
   /// Sets the infinity flag. See
   /// [`CalculationMode::has_infinity_flag`].
   pub fn set_infinity_flag(&mut self, mode: bool) {
@@ -68,4 +278,371 @@ impl CalculationMode {
   pub fn set_fractional_flag(&mut self, mode: bool) {
     self.inner.set(CalculationModeBits::FRACTIONAL, mode);
   }
+
+  /// Sets the exact matrix flag. See
+  /// [`CalculationMode::has_exact_matrix_flag`].
+  pub fn set_exact_matrix_flag(&mut self, mode: bool) {
+    self.inner.set(CalculationModeBits::EXACT_MATRICES, mode);
+  }
+
+  /// Sets the units flag. See [`CalculationMode::has_units_flag`].
+  pub fn set_units_flag(&mut self, mode: bool) {
+    self.inner.set(CalculationModeBits::UNITS, mode);
+  }
+
+  /// Sets the derived units flag. See
+  /// [`CalculationMode::has_derived_units_flag`].
+  pub fn set_derived_units_flag(&mut self, mode: bool) {
+    self.inner.set(CalculationModeBits::DERIVED_UNITS, mode);
+  }
+
+  /// Sets the branch cut. See [`CalculationMode::branch_cut`].
+  pub fn set_branch_cut(&mut self, branch_cut: BranchCut) {
+    self.inner.set(CalculationModeBits::POSITIVE_BRANCH_CUT, branch_cut == BranchCut::PositiveReal);
+  }
+
+  /// Sets the angle mode. See [`CalculationMode::angle_mode`].
+  pub fn set_angle_mode(&mut self, angle_mode: AngleMode) {
+    self.inner.set(CalculationModeBits::DEGREES, angle_mode == AngleMode::Degrees);
+  }
+
+  /// Sets or clears the modulus. See [`CalculationMode::modulus`].
+  ///
+  /// Panics if the modulus is less than two.
+  pub fn set_modulus(&mut self, modulus: Option<u64>) {
+    if let Some(n) = modulus {
+      assert!(n >= 2, "Modulus must be at least 2, got {n}");
+    }
+    self.modulus = modulus;
+  }
+
+  /// Sets or clears the precision. See
+  /// [`CalculationMode::precision`] for which functions are computed
+  /// to the full precision.
+  ///
+  /// Panics if the precision is zero or exceeds [`MAX_PRECISION`].
+  pub fn set_precision(&mut self, precision: Option<u32>) {
+    if let Some(n) = precision {
+      assert!((1..=MAX_PRECISION).contains(&n), "Precision must be between 1 and {MAX_PRECISION}, got {n}");
+    }
+    self.precision = precision;
+  }
+
+  /// Sets or clears the word size. See
+  /// [`CalculationMode::word_size`].
+  pub fn set_word_size(&mut self, word_size: Option<WordSize>) {
+    self.word_size = word_size;
+  }
+
+  /// Sets the rounding mode. See [`CalculationMode::rounding_mode`].
+  pub fn set_rounding_mode(&mut self, rounding_mode: RoundingMode) {
+    self.rounding_mode = rounding_mode;
+  }
+}
+
+impl RoundingMode {
+  pub const ALL: [RoundingMode; 4] = [
+    RoundingMode::HalfUp,
+    RoundingMode::HalfEven,
+    RoundingMode::TowardZero,
+    RoundingMode::AwayFromZero,
+  ];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      RoundingMode::HalfUp => "half-up",
+      RoundingMode::HalfEven => "half-even",
+      RoundingMode::TowardZero => "toward-zero",
+      RoundingMode::AwayFromZero => "away-from-zero",
+    }
+  }
+
+  /// Whether the last kept digit should be incremented (in absolute
+  /// value), given the digits being discarded and whether the last
+  /// kept digit is odd.
+  pub fn rounds_away(self, discarded: Discarded, last_kept_is_odd: bool) -> bool {
+    match self {
+      RoundingMode::HalfUp => matches!(discarded, Discarded::Half | Discarded::AboveHalf),
+      RoundingMode::HalfEven => {
+        discarded == Discarded::AboveHalf || (discarded == Discarded::Half && last_kept_is_odd)
+      }
+      RoundingMode::TowardZero => false,
+      RoundingMode::AwayFromZero => discarded != Discarded::Zero,
+    }
+  }
+}
+
+impl Display for RoundingMode {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+impl Prism<String, RoundingMode> for StringToRoundingMode {
+  fn narrow_type(&self, input: String) -> Result<RoundingMode, String> {
+    let trimmed = input.trim();
+    RoundingMode::ALL.into_iter()
+      .find(|mode| mode.name() == trimmed)
+      .ok_or(input)
+  }
+
+  fn widen_type(&self, rounding_mode: RoundingMode) -> String {
+    rounding_mode.name().to_owned()
+  }
+}
+
+impl WordSize {
+  /// The word sizes, in bits, that can be selected.
+  pub const SUPPORTED_BITS: [u32; 4] = [8, 16, 32, 64];
+
+  /// Panics if `bits` is not one of [`WordSize::SUPPORTED_BITS`].
+  pub fn new(bits: u32, signed: bool) -> Self {
+    assert!(Self::SUPPORTED_BITS.contains(&bits), "Unsupported word size {bits}");
+    Self { bits, signed }
+  }
+
+  pub fn bits(self) -> u32 {
+    self.bits
+  }
+
+  pub fn is_signed(self) -> bool {
+    self.signed
+  }
+
+  /// Reduces an integer into the range of this word, wrapping around
+  /// as two's complement hardware would.
+  pub fn wrap(self, n: &BigInt) -> BigInt {
+    let modulus = BigInt::one() << self.bits;
+    let n = n.mod_floor(&modulus);
+    if self.signed && n >= &modulus >> 1 {
+      n - modulus
+    } else {
+      n
+    }
+  }
+
+  /// The bit pattern which `n` has in this word, as an unsigned
+  /// integer, or `None` if `n` fits in the word as neither a signed
+  /// nor an unsigned value. Negative values are stored in two's
+  /// complement.
+  pub fn bit_pattern(self, n: &BigInt) -> Option<BigInt> {
+    let modulus: BigInt = BigInt::one() << self.bits;
+    let lower_bound = -(&modulus >> 1usize);
+    if n < &lower_bound || n >= &modulus {
+      return None;
+    }
+    Some(n.mod_floor(&modulus))
+  }
+}
+
+impl Display for WordSize {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    let prefix = if self.signed { 'i' } else { 'u' };
+    write!(f, "{}{}", prefix, self.bits)
+  }
+}
+
+impl Prism<String, Option<WordSize>> for StringToWordSize {
+  fn narrow_type(&self, input: String) -> Result<Option<WordSize>, String> {
+    let trimmed = input.trim();
+    if trimmed == "0" {
+      return Ok(None);
+    }
+    let signed = match trimmed.chars().next() {
+      Some('i') => true,
+      Some('u') => false,
+      _ => return Err(input),
+    };
+    match trimmed[1..].parse::<u32>() {
+      Ok(bits) if WordSize::SUPPORTED_BITS.contains(&bits) => Ok(Some(WordSize::new(bits, signed))),
+      _ => Err(input),
+    }
+  }
+
+  fn widen_type(&self, word_size: Option<WordSize>) -> String {
+    word_size.map_or_else(|| String::from("0"), |word_size| word_size.to_string())
+  }
+}
+
+impl Prism<String, Option<u64>> for StringToModulus {
+  fn narrow_type(&self, input: String) -> Result<Option<u64>, String> {
+    match input.trim().parse::<u64>() {
+      Ok(0) => Ok(None),
+      Ok(n) if n >= 2 => Ok(Some(n)),
+      _ => Err(input),
+    }
+  }
+
+  fn widen_type(&self, modulus: Option<u64>) -> String {
+    modulus.unwrap_or(0).to_string()
+  }
+}
+
+impl Prism<String, Option<u32>> for StringToPrecision {
+  fn narrow_type(&self, input: String) -> Result<Option<u32>, String> {
+    match input.trim().parse::<u32>() {
+      Ok(0) => Ok(None),
+      Ok(n) if n <= MAX_PRECISION => Ok(Some(n)),
+      _ => Err(input),
+    }
+  }
+
+  fn widen_type(&self, precision: Option<u32>) -> String {
+    precision.unwrap_or(0).to_string()
+  }
+}
+
+impl BranchCut {
+  /// Given an angle in the range `(-pi, pi]`, such as that produced
+  /// by `atan2`, returns the equivalent angle in this branch.
+  pub fn principal_angle(self, angle: Radians<f64>) -> Radians<f64> {
+    match self {
+      BranchCut::NegativeReal => angle,
+      BranchCut::PositiveReal if angle.0 < 0.0 => Radians(angle.0 + 2.0 * PI),
+      BranchCut::PositiveReal => angle,
+    }
+  }
+}
+
+impl AngleMode {
+  /// Converts an angle in this unit to radians.
+  pub fn to_radians(self, angle: f64) -> Radians<f64> {
+    match self {
+      AngleMode::Radians => Radians(angle),
+      AngleMode::Degrees => Degrees(angle).into_radians(),
+    }
+  }
+
+  /// Converts an angle in radians to this unit.
+  pub fn from_radians(self, angle: Radians<f64>) -> f64 {
+    match self {
+      AngleMode::Radians => angle.0,
+      AngleMode::Degrees => angle.into_degrees().0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_branch_cut_flag() {
+    let mut mode = CalculationMode::new();
+    assert_eq!(mode.branch_cut(), BranchCut::NegativeReal);
+    mode.set_branch_cut(BranchCut::PositiveReal);
+    assert_eq!(mode.branch_cut(), BranchCut::PositiveReal);
+    assert!(!mode.has_infinity_flag());
+    mode.set_branch_cut(BranchCut::NegativeReal);
+    assert_eq!(mode.branch_cut(), BranchCut::NegativeReal);
+  }
+
+  #[test]
+  fn test_modulus() {
+    let mut mode = CalculationMode::new();
+    assert_eq!(mode.modulus(), None);
+    mode.set_modulus(Some(7));
+    assert_eq!(mode.modulus(), Some(7));
+    mode.set_modulus(None);
+    assert_eq!(mode.modulus(), None);
+  }
+
+  #[test]
+  fn test_precision() {
+    let mut mode = CalculationMode::new();
+    assert_eq!(mode.precision(), None);
+    mode.set_precision(Some(30));
+    assert_eq!(mode.precision(), Some(30));
+    mode.set_precision(None);
+    assert_eq!(mode.precision(), None);
+  }
+
+  #[test]
+  fn test_string_to_precision() {
+    assert_eq!(StringToPrecision.narrow_type(String::from("30")), Ok(Some(30)));
+    assert_eq!(StringToPrecision.narrow_type(String::from("0")), Ok(None));
+    assert_eq!(StringToPrecision.narrow_type(String::from("100000")), Err(String::from("100000")));
+    assert_eq!(StringToPrecision.narrow_type(String::from("-1")), Err(String::from("-1")));
+    assert_eq!(StringToPrecision.widen_type(None), "0");
+    assert_eq!(StringToPrecision.widen_type(Some(30)), "30");
+  }
+
+  #[test]
+  fn test_string_to_modulus() {
+    assert_eq!(StringToModulus.narrow_type(String::from("13")), Ok(Some(13)));
+    assert_eq!(StringToModulus.narrow_type(String::from("0")), Ok(None));
+    assert_eq!(StringToModulus.narrow_type(String::from("1")), Err(String::from("1")));
+    assert_eq!(StringToModulus.narrow_type(String::from("x")), Err(String::from("x")));
+    assert_eq!(StringToModulus.widen_type(None), "0");
+  }
+
+  #[test]
+  fn test_string_to_word_size() {
+    assert_eq!(StringToWordSize.narrow_type(String::from("u8")), Ok(Some(WordSize::new(8, false))));
+    assert_eq!(StringToWordSize.narrow_type(String::from(" i64 ")), Ok(Some(WordSize::new(64, true))));
+    assert_eq!(StringToWordSize.narrow_type(String::from("0")), Ok(None));
+    assert_eq!(StringToWordSize.narrow_type(String::from("u12")), Err(String::from("u12")));
+    assert_eq!(StringToWordSize.narrow_type(String::from("32")), Err(String::from("32")));
+    assert_eq!(StringToWordSize.widen_type(Some(WordSize::new(16, true))), "i16");
+    assert_eq!(StringToWordSize.widen_type(None), "0");
+  }
+
+  #[test]
+  fn test_word_size_wrap() {
+    let u8 = WordSize::new(8, false);
+    let i8 = WordSize::new(8, true);
+    assert_eq!(u8.wrap(&BigInt::from(300)), BigInt::from(44));
+    assert_eq!(u8.wrap(&BigInt::from(-1)), BigInt::from(255));
+    assert_eq!(i8.wrap(&BigInt::from(127)), BigInt::from(127));
+    assert_eq!(i8.wrap(&BigInt::from(128)), BigInt::from(-128));
+    assert_eq!(i8.wrap(&BigInt::from(255)), BigInt::from(-1));
+    assert_eq!(WordSize::new(64, false).wrap(&BigInt::from(-1)), BigInt::from(u64::MAX));
+  }
+
+  #[test]
+  fn test_string_to_rounding_mode() {
+    assert_eq!(StringToRoundingMode.narrow_type(String::from("half-even")), Ok(RoundingMode::HalfEven));
+    assert_eq!(StringToRoundingMode.narrow_type(String::from(" toward-zero")), Ok(RoundingMode::TowardZero));
+    assert_eq!(StringToRoundingMode.narrow_type(String::from("half_up")), Err(String::from("half_up")));
+    assert_eq!(StringToRoundingMode.widen_type(RoundingMode::AwayFromZero), "away-from-zero");
+  }
+
+  #[test]
+  fn test_rounds_away() {
+    use Discarded::*;
+    assert!(!RoundingMode::HalfUp.rounds_away(BelowHalf, true));
+    assert!(RoundingMode::HalfUp.rounds_away(Half, false));
+    assert!(!RoundingMode::HalfEven.rounds_away(Half, false));
+    assert!(RoundingMode::HalfEven.rounds_away(Half, true));
+    assert!(RoundingMode::HalfEven.rounds_away(AboveHalf, false));
+    assert!(!RoundingMode::TowardZero.rounds_away(AboveHalf, true));
+    assert!(RoundingMode::AwayFromZero.rounds_away(BelowHalf, false));
+    assert!(!RoundingMode::AwayFromZero.rounds_away(Zero, true));
+  }
+
+  #[test]
+  fn test_word_size_bit_pattern() {
+    let u8 = WordSize::new(8, false);
+    let i8 = WordSize::new(8, true);
+    assert_eq!(u8.bit_pattern(&BigInt::from(-1)), Some(BigInt::from(255)));
+    assert_eq!(i8.bit_pattern(&BigInt::from(-128)), Some(BigInt::from(128)));
+    assert_eq!(i8.bit_pattern(&BigInt::from(200)), Some(BigInt::from(200)));
+    assert_eq!(u8.bit_pattern(&BigInt::from(256)), None);
+    assert_eq!(u8.bit_pattern(&BigInt::from(-129)), None);
+  }
+
+  #[test]
+  fn test_principal_angle() {
+    assert_eq!(BranchCut::NegativeReal.principal_angle(Radians(-PI / 2.0)), Radians(-PI / 2.0));
+    assert_eq!(BranchCut::PositiveReal.principal_angle(Radians(-PI / 2.0)), Radians(3.0 * PI / 2.0));
+    assert_eq!(BranchCut::PositiveReal.principal_angle(Radians(PI)), Radians(PI));
+    assert_eq!(BranchCut::PositiveReal.principal_angle(Radians(0.0)), Radians(0.0));
+  }
+
+  #[test]
+  fn test_angle_mode_conversions() {
+    assert_eq!(AngleMode::Radians.to_radians(1.5), Radians(1.5));
+    assert_eq!(AngleMode::Degrees.to_radians(180.0), Radians(PI));
+    assert_eq!(AngleMode::Degrees.from_radians(Radians(PI / 2.0)), 90.0);
+  }
 }
@@ -0,0 +1,89 @@
+
+//! Rendering of the stack as a standalone document, suitable for
+//! printing or sharing outside of the application.
+
+use super::language::{LanguageMode, LanguageSettings};
+use super::language::basic::BasicLanguageMode;
+use super::language::fancy::FancyLanguageMode;
+use crate::expr::Expr;
+
+use html_escape::encode_text;
+
+/// The stylesheet embedded into every rendered document.
+const DOCUMENT_CSS: &str = include_str!("document.css");
+
+/// The default title of a rendered stack document.
+pub const DEFAULT_DOCUMENT_TITLE: &str = "Stack";
+
+/// Renders the given stack elements (from bottom to top) as a
+/// complete HTML document.
+///
+/// The elements are always rendered using the fancy language mode,
+/// regardless of the user's current display mode, since the
+/// resulting document is meant for humans rather than for
+/// round-tripping back into the calculator. Each element is numbered
+/// in the same way as the stack view, with the top of the stack at
+/// index 1. Long vectors and matrices are written out in full.
+pub fn render_stack_document<'a, I>(title: &str, stack: I, language_settings: &LanguageSettings) -> String
+where I: IntoIterator<Item = &'a Expr>,
+      I::IntoIter: ExactSizeIterator {
+  let language_mode = FancyLanguageMode::from_common_unicode(BasicLanguageMode::from_common_operators());
+  let language_settings = language_settings.without_elision();
+  let stack = stack.into_iter();
+  let len = stack.len();
+
+  let mut out = String::new();
+  out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+  out.push_str(&format!("<title>{}</title>\n", encode_text(title)));
+  out.push_str("<style>\n");
+  out.push_str(DOCUMENT_CSS);
+  out.push_str("</style>\n</head>\n<body>\n");
+  out.push_str(&format!("<h1>{}</h1>\n", encode_text(title)));
+  if len == 0 {
+    out.push_str("<p>The stack is empty.</p>\n");
+  } else {
+    out.push_str("<table class=\"stack-document-table\">\n");
+    for (i, expr) in stack.enumerate() {
+      let index = len - i;
+      out.push_str(&format!(
+        "<tr><td class=\"stack-document-index\">{}.</td><td>{}</td></tr>\n",
+        index,
+        language_mode.to_html(expr, &language_settings),
+      ));
+    }
+    out.push_str("</table>\n");
+  }
+  out.push_str("</body>\n</html>\n");
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render_empty_stack() {
+    let doc = render_stack_document("Stack", &[], &LanguageSettings::default());
+    assert!(doc.starts_with("<!DOCTYPE html>"));
+    assert!(doc.contains("<title>Stack</title>"));
+    assert!(doc.contains("The stack is empty."));
+    assert!(doc.contains(".bracketed"));
+  }
+
+  #[test]
+  fn test_render_stack_numbers_from_top() {
+    let stack = vec![Expr::from(10), Expr::from(20), Expr::var("x").unwrap()];
+    let doc = render_stack_document("Stack", &stack, &LanguageSettings::default());
+    let first = doc.find("3.</td><td>10</td>").unwrap();
+    let second = doc.find("2.</td><td>20</td>").unwrap();
+    let third = doc.find("1.</td><td><span class=\"mathy-text\">x</span></td>").unwrap();
+    assert!(first < second);
+    assert!(second < third);
+  }
+
+  #[test]
+  fn test_render_escapes_title() {
+    let doc = render_stack_document("<b>&</b>", &[], &LanguageSettings::default());
+    assert!(doc.contains("<title>&lt;b&gt;&amp;&lt;/b&gt;</title>"));
+  }
+}
@@ -0,0 +1,272 @@
+
+//! Conversion of expressions to LaTeX math-mode source.
+
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+use crate::expr::vector::Vector;
+
+use std::fmt::Write;
+
+/// Precedence levels of LaTeX output, from loosest to tightest.
+const RELATION: u8 = 0;
+const SUM: u8 = 1;
+const PRODUCT: u8 = 2;
+const PREFIX: u8 = 3;
+const POWER: u8 = 4;
+const ATOM: u8 = 5;
+
+/// Functions which have a dedicated LaTeX operator name.
+const NAMED_FUNCTIONS: [(&str, &str); 14] = [
+  ("sin", r"\sin"), ("cos", r"\cos"), ("tan", r"\tan"),
+  ("sec", r"\sec"), ("csc", r"\csc"), ("cot", r"\cot"),
+  ("asin", r"\arcsin"), ("acos", r"\arccos"), ("atan", r"\arctan"),
+  ("sinh", r"\sinh"), ("cosh", r"\cosh"), ("tanh", r"\tanh"),
+  ("ln", r"\ln"), ("exp", r"\exp"),
+];
+
+const RELATIONS: [(&str, &str); 6] = [
+  ("=", "="), ("!=", r"\neq"), ("<", "<"), ("<=", r"\leq"), (">", ">"), (">=", r"\geq"),
+];
+
+/// Renders the expression as LaTeX, suitable for use in math mode.
+pub fn to_latex(expr: &Expr) -> String {
+  let mut out = String::new();
+  write_latex(&mut out, expr, RELATION);
+  out
+}
+
+fn write_latex(out: &mut String, expr: &Expr, prec: u8) {
+  match expr {
+    Expr::Atom(Atom::Number(n)) => write_number(out, n, prec),
+    Expr::Atom(Atom::Var(v)) => write_identifier(out, v.as_str()),
+    Expr::Atom(Atom::String(s)) => {
+      write!(out, r"\text{{{}}}", escape_text(s)).unwrap(); // unwrap: impl Write for String doesn't fail.
+    }
+    Expr::Call(f, args) => write_call(out, f, args, prec),
+  }
+}
+
+fn write_call(out: &mut String, f: &str, args: &[Expr], prec: u8) {
+  if let Some((_, relation)) = RELATIONS.iter().find(|(name, _)| *name == f) {
+    if args.len() == 2 {
+      parenthesize(out, prec > RELATION, |out| {
+        write_latex(out, &args[0], SUM);
+        write!(out, " {relation} ").unwrap(); // unwrap: impl Write for String doesn't fail.
+        write_latex(out, &args[1], SUM);
+      });
+      return;
+    }
+  }
+  match (f, args) {
+    ("+", [_, _, ..]) => write_infix(out, args, " + ", SUM, prec),
+    ("-", [a, b]) => {
+      parenthesize(out, prec > SUM, |out| {
+        write_latex(out, a, SUM);
+        out.push_str(" - ");
+        write_latex(out, b, PRODUCT);
+      });
+    }
+    ("*", [_, _, ..]) => write_infix(out, args, r" \cdot ", PRODUCT, prec),
+    ("/", [a, b]) => {
+      write_frac(out, a, b);
+    }
+    ("^", [base, exponent]) => {
+      parenthesize(out, prec > POWER, |out| {
+        write_latex(out, base, ATOM);
+        out.push_str("^{");
+        write_latex(out, exponent, RELATION);
+        out.push('}');
+      });
+    }
+    ("negate", [a]) => {
+      parenthesize(out, prec > SUM, |out| {
+        out.push('-');
+        write_latex(out, a, PREFIX);
+      });
+    }
+    ("sqrt", [a]) => {
+      out.push_str(r"\sqrt{");
+      write_latex(out, a, RELATION);
+      out.push('}');
+    }
+    ("abs", [a]) => {
+      out.push_str(r"\left|");
+      write_latex(out, a, RELATION);
+      out.push_str(r"\right|");
+    }
+    (Vector::FUNCTION_NAME, _) => write_vector(out, args),
+    _ => {
+      match NAMED_FUNCTIONS.iter().find(|(name, _)| *name == f) {
+        Some((_, latex_name)) => out.push_str(latex_name),
+        None => {
+          out.push_str(r"\operatorname{");
+          out.push_str(&escape_identifier(f));
+          out.push('}');
+        }
+      }
+      out.push_str(r"\left(");
+      write_separated(out, args, ", ", RELATION);
+      out.push_str(r"\right)");
+    }
+  }
+}
+
+fn write_infix(out: &mut String, args: &[Expr], operator: &str, op_prec: u8, prec: u8) {
+  parenthesize(out, prec > op_prec, |out| {
+    write_separated(out, args, operator, op_prec);
+  });
+}
+
+fn write_separated(out: &mut String, args: &[Expr], separator: &str, prec: u8) {
+  for (i, arg) in args.iter().enumerate() {
+    if i > 0 {
+      out.push_str(separator);
+    }
+    write_latex(out, arg, prec);
+  }
+}
+
+fn write_frac(out: &mut String, numer: &Expr, denom: &Expr) {
+  out.push_str(r"\frac{");
+  write_latex(out, numer, RELATION);
+  out.push_str("}{");
+  write_latex(out, denom, RELATION);
+  out.push('}');
+}
+
+/// Writes a vector of vectors of equal length as a matrix, and any
+/// other vector as a bracketed list.
+fn write_vector(out: &mut String, elems: &[Expr]) {
+  let rows: Option<Vec<&[Expr]>> = elems.iter()
+    .map(|elem| match elem {
+      Expr::Call(f, row) if f == Vector::FUNCTION_NAME => Some(row.as_slice()),
+      _ => None,
+    })
+    .collect();
+  match rows {
+    Some(rows) if !rows.is_empty() && rows.iter().all(|row| row.len() == rows[0].len()) => {
+      out.push_str(r"\begin{bmatrix}");
+      for (i, row) in rows.into_iter().enumerate() {
+        if i > 0 {
+          out.push_str(r" \\ ");
+        }
+        write_separated(out, row, " & ", RELATION);
+      }
+      out.push_str(r"\end{bmatrix}");
+    }
+    _ => {
+      out.push_str(r"\left[");
+      write_separated(out, elems, ", ", RELATION);
+      out.push_str(r"\right]");
+    }
+  }
+}
+
+fn write_number(out: &mut String, n: &Number, prec: u8) {
+  let s = n.to_string();
+  let (sign, magnitude) = match s.strip_prefix('-') {
+    Some(magnitude) => ("-", magnitude),
+    None => ("", s.as_str()),
+  };
+  parenthesize(out, !sign.is_empty() && prec > SUM, |out| {
+    out.push_str(sign);
+    match magnitude.split_once(':') {
+      Some((numer, denom)) => {
+        write!(out, r"\frac{{{numer}}}{{{denom}}}").unwrap(); // unwrap: impl Write for String doesn't fail.
+      }
+      None => {
+        out.push_str(magnitude);
+      }
+    }
+  });
+}
+
+fn write_identifier(out: &mut String, name: &str) {
+  if name.chars().count() == 1 {
+    out.push_str(name);
+  } else {
+    out.push_str(r"\mathit{");
+    out.push_str(&escape_identifier(name));
+    out.push('}');
+  }
+}
+
+fn parenthesize(out: &mut String, needs_parens: bool, body: impl FnOnce(&mut String)) {
+  if needs_parens {
+    out.push_str(r"\left(");
+  }
+  body(out);
+  if needs_parens {
+    out.push_str(r"\right)");
+  }
+}
+
+fn escape_identifier(name: &str) -> String {
+  name.replace('_', r"\_")
+}
+
+fn escape_text(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '\\' => result.push_str(r"\textbackslash{}"),
+      '{' | '}' | '_' | '&' | '%' | '$' | '#' => {
+        result.push('\\');
+        result.push(c);
+      }
+      '^' => result.push_str(r"\^{}"),
+      '~' => result.push_str(r"\~{}"),
+      c => result.push(c),
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn x() -> Expr {
+    Expr::var("x").unwrap()
+  }
+
+  #[test]
+  fn test_atoms() {
+    assert_eq!(to_latex(&Expr::from(3)), "3");
+    assert_eq!(to_latex(&Expr::from(Number::ratio(-1, 2))), r"-\frac{1}{2}");
+    assert_eq!(to_latex(&x()), "x");
+    assert_eq!(to_latex(&Expr::var("theta1").unwrap()), r"\mathit{theta1}");
+    assert_eq!(to_latex(&Expr::from("50% off")), r"\text{50\% off}");
+  }
+
+  #[test]
+  fn test_arithmetic() {
+    let expr = Expr::call("*", vec![
+      Expr::call("+", vec![x(), Expr::from(1)]),
+      Expr::call("^", vec![x(), Expr::from(2)]),
+    ]);
+    assert_eq!(to_latex(&expr), r"\left(x + 1\right) \cdot x^{2}");
+    let expr = Expr::call("/", vec![Expr::call("sin", vec![x()]), Expr::call("negate", vec![x()])]);
+    assert_eq!(to_latex(&expr), r"\frac{\sin\left(x\right)}{-x}");
+    let expr = Expr::call("-", vec![x(), Expr::call("-", vec![x(), Expr::from(-1)])]);
+    assert_eq!(to_latex(&expr), r"x - \left(x - \left(-1\right)\right)");
+  }
+
+  #[test]
+  fn test_relation() {
+    let expr = Expr::call("<=", vec![Expr::call("f", vec![x()]), Expr::from(0)]);
+    assert_eq!(to_latex(&expr), r"\operatorname{f}\left(x\right) \leq 0");
+  }
+
+  #[test]
+  fn test_vectors() {
+    let expr = Expr::call("vector", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(to_latex(&expr), r"\left[1, 2\right]");
+    let expr = Expr::call("vector", vec![
+      Expr::call("vector", vec![Expr::from(1), Expr::from(2)]),
+      Expr::call("vector", vec![Expr::from(3), Expr::from(4)]),
+    ]);
+    assert_eq!(to_latex(&expr), r"\begin{bmatrix}1 & 2 \\ 3 & 4\end{bmatrix}");
+  }
+}
@@ -0,0 +1,153 @@
+
+//! Rendering of expressions to standalone strings in a variety of
+//! formats, for use outside of the calculator (such as copying to the
+//! clipboard).
+
+pub mod latex;
+
+use super::DisplaySettings;
+use super::language::LanguageMode;
+use super::language::basic::BasicLanguageMode;
+use super::language::fancy::FancyLanguageMode;
+use crate::expr::Expr;
+
+use html_escape::decode_html_entities;
+use thiserror::Error;
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A format which an expression can be exported to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportFormat {
+  /// Plain text, in a form which the calculator can parse back in.
+  PlainText,
+  /// LaTeX math-mode source.
+  Latex,
+  /// HTML, in the named language mode, or in the current language
+  /// mode if no name is given.
+  Html(Option<String>),
+  /// The expression tree, serialized as JSON.
+  Json,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ExportError {
+  #[error("Unknown export format '{0}'")]
+  UnknownFormat(String),
+  #[error("Unknown language mode '{0}'")]
+  UnknownLanguageMode(String),
+  #[error("{0}")]
+  JsonError(#[from] serde_json::Error),
+}
+
+/// The names of all language modes accepted by
+/// [`named_language_mode`].
+pub const LANGUAGE_MODE_NAMES: [&str; 2] = ["basic", "fancy"];
+
+/// Constructs a language mode by name, or `None` if there is no
+/// language mode with the given name.
+pub fn named_language_mode(name: &str) -> Option<Arc<dyn LanguageMode + Send + Sync>> {
+  match name {
+    "basic" => Some(Arc::new(BasicLanguageMode::from_common_operators())),
+    "fancy" => Some(Arc::new(FancyLanguageMode::from_common_unicode(
+      BasicLanguageMode::from_common_operators().with_fancy_parens(),
+    ))),
+    _ => None,
+  }
+}
+
+/// Renders `expr` in the given format. Formats which depend on the
+/// display settings (such as the current locale) respect `settings`.
+pub fn export_expr(expr: &Expr, format: &ExportFormat, settings: &DisplaySettings) -> Result<String, ExportError> {
+  match format {
+    ExportFormat::PlainText => {
+      Ok(decode_html_entities(&settings.to_html_for_parsing(expr)).into_owned())
+    }
+    ExportFormat::Latex => {
+      Ok(latex::to_latex(expr))
+    }
+    ExportFormat::Html(None) => {
+      Ok(settings.to_full_html(expr))
+    }
+    ExportFormat::Html(Some(name)) => {
+      let base_language_mode = named_language_mode(name)
+        .ok_or_else(|| ExportError::UnknownLanguageMode(name.to_owned()))?;
+      let settings = DisplaySettings { base_language_mode, ..settings.clone() };
+      Ok(settings.to_full_html(expr))
+    }
+    ExportFormat::Json => {
+      Ok(serde_json::to_string(expr)?)
+    }
+  }
+}
+
+impl FromStr for ExportFormat {
+  type Err = ExportError;
+
+  /// Parses one of `text`, `latex`, `json`, `html`, or `html:<mode>`,
+  /// where `<mode>` is a language mode name.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(ExportFormat::PlainText),
+      "latex" => Ok(ExportFormat::Latex),
+      "json" => Ok(ExportFormat::Json),
+      "html" => Ok(ExportFormat::Html(None)),
+      _ => {
+        match s.strip_prefix("html:") {
+          Some(name) if LANGUAGE_MODE_NAMES.contains(&name) => Ok(ExportFormat::Html(Some(name.to_owned()))),
+          Some(name) => Err(ExportError::UnknownLanguageMode(name.to_owned())),
+          None => Err(ExportError::UnknownFormat(s.to_owned())),
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sample_expr() -> Expr {
+    Expr::call("+", vec![Expr::var("x").unwrap(), Expr::from("a<b")])
+  }
+
+  #[test]
+  fn test_parse_format() {
+    assert_eq!("text".parse::<ExportFormat>().unwrap(), ExportFormat::PlainText);
+    assert_eq!("html".parse::<ExportFormat>().unwrap(), ExportFormat::Html(None));
+    assert_eq!("html:fancy".parse::<ExportFormat>().unwrap(), ExportFormat::Html(Some(String::from("fancy"))));
+    assert!(matches!("html:nope".parse::<ExportFormat>(), Err(ExportError::UnknownLanguageMode(_))));
+    assert!(matches!("pdf".parse::<ExportFormat>(), Err(ExportError::UnknownFormat(_))));
+  }
+
+  #[test]
+  fn test_all_named_language_modes_exist() {
+    for name in LANGUAGE_MODE_NAMES {
+      assert!(named_language_mode(name).is_some(), "Missing language mode {name}");
+    }
+  }
+
+  #[test]
+  fn test_export_plain_text() {
+    let settings = DisplaySettings::default();
+    let text = export_expr(&sample_expr(), &ExportFormat::PlainText, &settings).unwrap();
+    assert_eq!(text, r#"x + "a<b""#);
+  }
+
+  #[test]
+  fn test_export_html_in_named_mode() {
+    let settings = DisplaySettings::default();
+    let html = export_expr(&sample_expr(), &ExportFormat::Html(Some(String::from("basic"))), &settings).unwrap();
+    assert_eq!(html, settings.to_html(&sample_expr()));
+  }
+
+  #[test]
+  fn test_export_json_round_trip() {
+    let settings = DisplaySettings::default();
+    let json = export_expr(&sample_expr(), &ExportFormat::Json, &settings).unwrap();
+    let expr: Expr = serde_json::from_str(&json).unwrap();
+    assert_eq!(expr, sample_expr());
+  }
+}
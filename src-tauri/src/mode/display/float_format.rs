@@ -0,0 +1,397 @@
+
+//! Output formats for inexact (floating-point) numbers.
+
+use crate::util::prism::Prism;
+use crate::expr::number::bigfloat::BigFloat;
+use crate::mode::calculation::{RoundingMode, Discarded};
+
+use num::{BigInt, BigRational};
+use serde::{Serialize, Deserialize};
+
+use thiserror::Error;
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The largest number of digits which can be requested by a
+/// [`FloatFormat`].
+pub const MAX_FORMAT_DIGITS: usize = 100;
+
+/// How inexact numbers are written in output. Exact numbers (integers
+/// and rationals) are never affected by the float format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FloatFormat {
+  /// The shortest representation which reads back as the same value.
+  #[default]
+  Normal,
+  /// Fixed-point notation, with the given number of digits after the
+  /// decimal point.
+  Fixed(usize),
+  /// Scientific notation, with one digit before the decimal point.
+  /// If a digit count is given, the mantissa is rounded to that many
+  /// significant digits.
+  Scientific(Option<usize>),
+  /// Engineering notation, which is like scientific notation except
+  /// that the exponent is always a multiple of three. If a digit
+  /// count is given, the mantissa is rounded to that many significant
+  /// digits.
+  Engineering(Option<usize>),
+}
+
+/// A finite decimal value, broken into its sign, its significant
+/// digits, and the power of ten of its leading digit. That is, the
+/// digits `[1, 2, 5]` with exponent `2` represent `125`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalDigits {
+  pub negative: bool,
+  pub digits: Vec<u8>,
+  pub exponent: i64,
+}
+
+/// Prism which parses a string as a [`FloatFormat`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToFloatFormat;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("Invalid float format '{input}'")]
+pub struct ParseFloatFormatError {
+  input: String,
+}
+
+impl FloatFormat {
+  /// Writes the value according to this format. Returns `None` for
+  /// [`FloatFormat::Normal`], in which case the caller should use the
+  /// value's usual representation. Digits which do not fit in the
+  /// format are rounded off according to `rounding_mode`.
+  pub fn format(&self, value: &DecimalDigits, rounding_mode: RoundingMode) -> Option<String> {
+    let body = match *self {
+      FloatFormat::Normal => return None,
+      FloatFormat::Fixed(places) => format_fixed(value, places, rounding_mode),
+      FloatFormat::Scientific(sig_digits) => format_exponential(value, sig_digits, rounding_mode, |exp| exp),
+      FloatFormat::Engineering(sig_digits) => {
+        format_exponential(value, sig_digits, rounding_mode, |exp| exp.div_euclid(3) * 3)
+      }
+    };
+    // Never write a negative zero.
+    if value.negative && body.bytes().any(|b| (b'1'..=b'9').contains(&b)) {
+      Some(format!("-{}", body))
+    } else {
+      Some(body)
+    }
+  }
+}
+
+impl DecimalDigits {
+  /// The digits of the shortest decimal representation of `value`
+  /// which reads back as the same `f64`. Returns `None` if the value
+  /// is not finite.
+  pub fn from_f64(value: f64) -> Option<Self> {
+    if !value.is_finite() {
+      return None;
+    }
+    let repr = format!("{:e}", value.abs());
+    let (mantissa, exponent) = repr.split_once('e')?;
+    let digits = mantissa.bytes().filter(u8::is_ascii_digit).map(|b| b - b'0').collect();
+    Some(DecimalDigits {
+      negative: value.is_sign_negative(),
+      digits,
+      exponent: exponent.parse().ok()?,
+    })
+  }
+
+  pub fn from_big_float(value: &BigFloat) -> Self {
+    let magnitude = value.mantissa().magnitude().to_str_radix(10);
+    let len = magnitude.len() as i64;
+    DecimalDigits {
+      negative: value.is_negative(),
+      digits: magnitude.bytes().map(|b| b - b'0').collect(),
+      exponent: value.exponent() + len - 1,
+    }
+  }
+
+  /// The exact value of these digits.
+  pub fn to_rational(&self) -> BigRational {
+    let magnitude: BigInt = self.digits.iter().fold(BigInt::from(0), |acc, d| acc * 10 + d);
+    let shift = self.exponent + 1 - self.digits.len() as i64;
+    let ten = BigInt::from(10);
+    let magnitude = if shift >= 0 {
+      BigRational::from_integer(magnitude * ten.pow(shift as u32))
+    } else {
+      BigRational::new(magnitude, ten.pow((-shift) as u32))
+    };
+    if self.negative { -magnitude } else { magnitude }
+  }
+
+  /// Rounds to the given number of significant digits, according to
+  /// `rounding_mode`. The result has exactly `count` digits, padded
+  /// with zeroes if necessary, except that rounding to zero digits
+  /// may carry over into a single leading one.
+  fn rounded(&self, count: usize, rounding_mode: RoundingMode) -> DecimalDigits {
+    let mut digits: Vec<u8> = self.digits.iter().copied().chain(std::iter::repeat(0)).take(count).collect();
+    let mut exponent = self.exponent;
+    let last_kept_is_odd = digits.last().is_some_and(|d| d % 2 == 1);
+    if rounding_mode.rounds_away(self.discarded_after(count), last_kept_is_odd) {
+      // Propagate the carry.
+      let mut carried = true;
+      for digit in digits.iter_mut().rev() {
+        if *digit == 9 {
+          *digit = 0;
+        } else {
+          *digit += 1;
+          carried = false;
+          break;
+        }
+      }
+      if carried {
+        // Every digit was a nine, so we roll over to the next power
+        // of ten.
+        digits.insert(0, 1);
+        digits.truncate(count.max(1));
+        exponent += 1;
+      }
+    }
+    DecimalDigits { negative: self.negative, digits, exponent }
+  }
+
+  /// Classifies the digits after the first `count`, as they compare
+  /// to one half of the last kept place.
+  fn discarded_after(&self, count: usize) -> Discarded {
+    let first = self.digits.get(count).copied().unwrap_or(0);
+    let rest_nonzero = self.digits.iter().skip(count + 1).any(|d| *d != 0);
+    match (first, rest_nonzero) {
+      (0, false) => Discarded::Zero,
+      (5, false) => Discarded::Half,
+      (first, _) if first < 5 => Discarded::BelowHalf,
+      _ => Discarded::AboveHalf,
+    }
+  }
+
+  /// Pads the front of the digit list with `count` zeroes, without
+  /// changing the value.
+  fn with_leading_zeroes(&self, count: usize) -> DecimalDigits {
+    let mut digits = vec![0; count];
+    digits.extend_from_slice(&self.digits);
+    DecimalDigits { negative: self.negative, digits, exponent: self.exponent + count as i64 }
+  }
+}
+
+fn digits_to_string(digits: &[u8]) -> String {
+  digits.iter().map(|d| char::from(b'0' + d)).collect()
+}
+
+fn format_fixed(value: &DecimalDigits, places: usize, rounding_mode: RoundingMode) -> String {
+  // The number of significant digits which fall at or before the
+  // last decimal place we keep. If the value is too small to reach
+  // that place at all, pad it with zeroes so that it does, since
+  // some rounding modes may still round it up.
+  let kept = value.exponent + 1 + places as i64;
+  let value = if kept < 0 { value.with_leading_zeroes(kept.unsigned_abs() as usize) } else { value.clone() };
+  let kept = kept.max(0) as usize;
+  let scaled_digits = {
+    let rounded = value.rounded(kept, rounding_mode);
+    // If rounding rolled over to a new power of ten, then there's
+    // one more digit to keep.
+    let mut digits = rounded.digits;
+    digits.resize((rounded.exponent + 1 + places as i64).max(0) as usize, 0);
+    digits
+  };
+  // `scaled_digits` is now the value times `10^places`, as an
+  // integer. Pad it so that it has at least one digit before the
+  // decimal point.
+  let mut digits = vec![0; (places + 1).saturating_sub(scaled_digits.len())];
+  digits.extend(scaled_digits);
+  let point = digits.len() - places;
+  if places == 0 {
+    digits_to_string(&digits)
+  } else {
+    format!("{}.{}", digits_to_string(&digits[..point]), digits_to_string(&digits[point..]))
+  }
+}
+
+fn format_exponential(
+  value: &DecimalDigits,
+  sig_digits: Option<usize>,
+  rounding_mode: RoundingMode,
+  choose_exponent: impl Fn(i64) -> i64,
+) -> String {
+  let value = match sig_digits {
+    Some(count) => value.rounded(count.max(1), rounding_mode),
+    None => value.clone(),
+  };
+  let is_zero = value.digits.iter().all(|d| *d == 0);
+  let exponent = if is_zero { 0 } else { choose_exponent(value.exponent) };
+  let integer_len = if is_zero { 1 } else { (value.exponent - exponent + 1) as usize };
+  let mut digits = value.digits;
+  if digits.len() < integer_len {
+    digits.resize(integer_len, 0);
+  }
+  let (integer_part, fraction) = digits.split_at(integer_len);
+  let fraction = if fraction.is_empty() && sig_digits.is_none() { &[0][..] } else { fraction };
+  if fraction.is_empty() {
+    format!("{}e{}", digits_to_string(integer_part), exponent)
+  } else {
+    format!("{}.{}e{}", digits_to_string(integer_part), digits_to_string(fraction), exponent)
+  }
+}
+
+impl FromStr for FloatFormat {
+  type Err = ParseFloatFormatError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let err = || ParseFloatFormatError { input: s.to_owned() };
+    let mut words = s.split_whitespace();
+    let name = words.next().ok_or_else(err)?;
+    let digits = words.next()
+      .map(|word| word.parse::<usize>().ok().filter(|n| *n <= MAX_FORMAT_DIGITS).ok_or_else(err))
+      .transpose()?;
+    if words.next().is_some() {
+      return Err(err());
+    }
+    match (name, digits) {
+      ("normal", None) => Ok(FloatFormat::Normal),
+      ("fix", Some(places)) => Ok(FloatFormat::Fixed(places)),
+      ("sci", digits) if digits != Some(0) => Ok(FloatFormat::Scientific(digits)),
+      ("eng", digits) if digits != Some(0) => Ok(FloatFormat::Engineering(digits)),
+      _ => Err(err()),
+    }
+  }
+}
+
+impl Display for FloatFormat {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      FloatFormat::Normal => write!(f, "normal"),
+      FloatFormat::Fixed(places) => write!(f, "fix {}", places),
+      FloatFormat::Scientific(None) => write!(f, "sci"),
+      FloatFormat::Scientific(Some(digits)) => write!(f, "sci {}", digits),
+      FloatFormat::Engineering(None) => write!(f, "eng"),
+      FloatFormat::Engineering(Some(digits)) => write!(f, "eng {}", digits),
+    }
+  }
+}
+
+impl Prism<String, FloatFormat> for StringToFloatFormat {
+  fn narrow_type(&self, input: String) -> Result<FloatFormat, String> {
+    FloatFormat::from_str(&input).map_err(|_| input)
+  }
+
+  fn widen_type(&self, format: FloatFormat) -> String {
+    format.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn format(format: FloatFormat, value: f64) -> String {
+    format_with(format, RoundingMode::default(), value)
+  }
+
+  fn format_with(format: FloatFormat, rounding_mode: RoundingMode, value: f64) -> String {
+    format.format(&DecimalDigits::from_f64(value).unwrap(), rounding_mode).unwrap()
+  }
+
+  #[test]
+  fn test_from_str() {
+    assert_eq!(FloatFormat::from_str("normal"), Ok(FloatFormat::Normal));
+    assert_eq!(FloatFormat::from_str("fix 3"), Ok(FloatFormat::Fixed(3)));
+    assert_eq!(FloatFormat::from_str("fix 0"), Ok(FloatFormat::Fixed(0)));
+    assert_eq!(FloatFormat::from_str("sci"), Ok(FloatFormat::Scientific(None)));
+    assert_eq!(FloatFormat::from_str(" sci  4 "), Ok(FloatFormat::Scientific(Some(4))));
+    assert_eq!(FloatFormat::from_str("eng 2"), Ok(FloatFormat::Engineering(Some(2))));
+    assert!(FloatFormat::from_str("fix").is_err());
+    assert!(FloatFormat::from_str("sci 0").is_err());
+    assert!(FloatFormat::from_str("normal 3").is_err());
+    assert!(FloatFormat::from_str("fix 3 4").is_err());
+    assert!(FloatFormat::from_str("fix 1000").is_err());
+    assert!(FloatFormat::from_str("hex").is_err());
+  }
+
+  #[test]
+  fn test_display_round_trip() {
+    let formats = [
+      FloatFormat::Normal,
+      FloatFormat::Fixed(2),
+      FloatFormat::Scientific(None),
+      FloatFormat::Scientific(Some(5)),
+      FloatFormat::Engineering(None),
+      FloatFormat::Engineering(Some(3)),
+    ];
+    for f in formats {
+      assert_eq!(FloatFormat::from_str(&f.to_string()), Ok(f));
+    }
+  }
+
+  #[test]
+  fn test_normal() {
+    let value = DecimalDigits::from_f64(1.5).unwrap();
+    assert_eq!(FloatFormat::Normal.format(&value, RoundingMode::default()), None);
+  }
+
+  #[test]
+  fn test_fixed() {
+    assert_eq!(format(FloatFormat::Fixed(2), 1.23456), "1.23");
+    assert_eq!(format(FloatFormat::Fixed(2), 2.675), "2.68");
+    assert_eq!(format(FloatFormat::Fixed(3), 1234.5), "1234.500");
+    assert_eq!(format(FloatFormat::Fixed(2), 9.999), "10.00");
+    assert_eq!(format(FloatFormat::Fixed(2), 0.004), "0.00");
+    assert_eq!(format(FloatFormat::Fixed(2), 0.005), "0.01");
+    assert_eq!(format(FloatFormat::Fixed(2), 0.000001), "0.00");
+    assert_eq!(format(FloatFormat::Fixed(0), 2.5), "3");
+    assert_eq!(format(FloatFormat::Fixed(1), -0.75), "-0.8");
+    assert_eq!(format(FloatFormat::Fixed(1), -0.01), "0.0");
+    assert_eq!(format(FloatFormat::Fixed(1), 0.0), "0.0");
+  }
+
+  #[test]
+  fn test_scientific() {
+    assert_eq!(format(FloatFormat::Scientific(None), 1234.5), "1.2345e3");
+    assert_eq!(format(FloatFormat::Scientific(None), 0.00012), "1.2e-4");
+    assert_eq!(format(FloatFormat::Scientific(None), 5.0), "5.0e0");
+    assert_eq!(format(FloatFormat::Scientific(Some(3)), 1234.5), "1.23e3");
+    assert_eq!(format(FloatFormat::Scientific(Some(3)), 9996.0), "1.00e4");
+    assert_eq!(format(FloatFormat::Scientific(Some(1)), -7200.0), "-7e3");
+    assert_eq!(format(FloatFormat::Scientific(Some(4)), 2.0), "2.000e0");
+    assert_eq!(format(FloatFormat::Scientific(None), 0.0), "0.0e0");
+  }
+
+  #[test]
+  fn test_engineering() {
+    assert_eq!(format(FloatFormat::Engineering(None), 1234.5), "1.2345e3");
+    assert_eq!(format(FloatFormat::Engineering(None), 12345.0), "12.345e3");
+    assert_eq!(format(FloatFormat::Engineering(None), 123456.0), "123.456e3");
+    assert_eq!(format(FloatFormat::Engineering(None), 0.00012), "120.0e-6");
+    assert_eq!(format(FloatFormat::Engineering(Some(2)), 123456.0), "120e3");
+    assert_eq!(format(FloatFormat::Engineering(Some(2)), 999.7), "1.0e3");
+    assert_eq!(format(FloatFormat::Engineering(Some(4)), -0.047), "-47.00e-3");
+  }
+
+  #[test]
+  fn test_big_float_digits() {
+    let value: BigFloat = "-123.4500".parse().unwrap();
+    let digits = DecimalDigits::from_big_float(&value);
+    assert!(digits.negative);
+    assert_eq!(digits.digits, vec![1, 2, 3, 4, 5]);
+    assert_eq!(digits.exponent, 2);
+    assert_eq!(FloatFormat::Fixed(1).format(&digits, RoundingMode::HalfUp).unwrap(), "-123.5");
+    assert_eq!(FloatFormat::Fixed(1).format(&digits, RoundingMode::HalfEven).unwrap(), "-123.4");
+    assert_eq!(digits.to_rational(), BigRational::new(BigInt::from(-2469), BigInt::from(20)));
+  }
+
+  #[test]
+  fn test_rounding_modes() {
+    assert_eq!(format_with(FloatFormat::Fixed(2), RoundingMode::HalfEven, 2.675), "2.68");
+    assert_eq!(format_with(FloatFormat::Fixed(2), RoundingMode::HalfEven, 2.665), "2.66");
+    assert_eq!(format_with(FloatFormat::Fixed(2), RoundingMode::HalfEven, 2.6651), "2.67");
+    assert_eq!(format_with(FloatFormat::Fixed(0), RoundingMode::HalfEven, 0.5), "0");
+    assert_eq!(format_with(FloatFormat::Fixed(2), RoundingMode::TowardZero, 9.999), "9.99");
+    assert_eq!(format_with(FloatFormat::Fixed(1), RoundingMode::TowardZero, -0.75), "-0.7");
+    assert_eq!(format_with(FloatFormat::Fixed(2), RoundingMode::AwayFromZero, 1.001), "1.01");
+    assert_eq!(format_with(FloatFormat::Fixed(2), RoundingMode::AwayFromZero, 0.000001), "0.01");
+    assert_eq!(format_with(FloatFormat::Fixed(2), RoundingMode::AwayFromZero, 1.0), "1.00");
+    assert_eq!(format_with(FloatFormat::Scientific(Some(2)), RoundingMode::TowardZero, 1299.0), "1.2e3");
+    assert_eq!(format_with(FloatFormat::Scientific(Some(2)), RoundingMode::HalfEven, 1250.0), "1.2e3");
+  }
+}
@@ -1,21 +1,27 @@
 
-use super::{LanguageMode, LanguageModeEngine, output_sep_by};
+use super::{LanguageMode, LanguageModeEngine, Elided, output_sep_by};
+use crate::mode::display::locale::Locale;
 use crate::mode::display::unicode::{UnicodeAliasTable, common_unicode_aliases};
 use crate::parsing::operator::{Operator, Precedence, OperatorTable};
 use crate::parsing::operator::fixity::FixityType;
 use crate::expr::Expr;
-use crate::expr::number::{Number, ComplexNumber, Quaternion};
+use crate::expr::number::{Number, ComplexNumber, Quaternion, Octonion};
 use crate::expr::atom::{Atom, write_escaped_str};
 use crate::expr::basic_parser::ExprParser;
 use crate::expr::vector::Vector;
+use crate::expr::set::Set;
+use crate::expr::tuple::Tuple;
 use crate::expr::incomplete::{IncompleteObject, ObjectType};
 use crate::util::cow_dyn::CowDyn;
-use crate::util::brackets::{BracketConstruct, fancy_parens, fancy_square_brackets};
+use crate::util::radix::Radix;
+use crate::util::brackets::{BracketConstruct, ConstBrackets, fancy_parens, fancy_square_brackets};
 
 use html_escape::encode_safe;
 
 use num::Zero;
 
+use std::io;
+
 /// The basic, and default, language mode. This language mode has
 /// minimal support for sophisticated output or pretty-printing and is
 /// designed to be mostly reversible.
@@ -85,7 +91,7 @@ impl BasicLanguageMode {
     let f = self.translate_to_unicode(engine, f);
     out.push_str(encode_safe(f).as_ref());
     fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, true, |out| {
-      output_sep_by(out, args.iter(), ", ", |out, e| engine.write_to_html(out, e, Precedence::MIN));
+      output_sep_by(out, args.iter(), &engine.argument_separator(), |out, e| engine.write_to_html(out, e, Precedence::MIN));
     });
   }
 
@@ -101,7 +107,7 @@ impl BasicLanguageMode {
   ) {
     assert!(op.fixity().is_infix(), "Expected an infix operator: {:?}", op);
     let infix_props = op.fixity().as_infix().unwrap();
-    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, prec > infix_props.precedence(), |out| {
+    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, engine.needs_parens(prec, infix_props.precedence()), |out| {
 
       engine.write_to_html(out, left_arg, infix_props.left_precedence());
       out.push(' ');
@@ -128,7 +134,7 @@ impl BasicLanguageMode {
     assert!(!args.is_empty());
     assert!(op.fixity().is_infix(), "Expected an infix operator: {:?}", op);
     let infix_props = op.fixity().as_infix().unwrap();
-    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, prec > infix_props.precedence(), |out| {
+    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, engine.needs_parens(prec, infix_props.precedence()), |out| {
       let mut first = true;
       for arg in args {
         if !first {
@@ -164,7 +170,7 @@ impl BasicLanguageMode {
     if args.len() != 1 {
       return false;
     }
-    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, prec > prefix_props.precedence(), |out| {
+    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, engine.needs_parens(prec, prefix_props.precedence()), |out| {
       let operator_name = self.translate_to_unicode(engine, op.operator_name());
       out.push_str(encode_safe(operator_name).as_ref());
       out.push(' ');
@@ -183,7 +189,7 @@ impl BasicLanguageMode {
     if args.len() != 1 {
       return false;
     }
-    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, prec > postfix_props.precedence(), |out| {
+    fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, engine.needs_parens(prec, postfix_props.precedence()), |out| {
       engine.write_to_html(out, &args[0], postfix_props.precedence());
       out.push(' ');
       let operator_name = self.translate_to_unicode(engine, op.operator_name());
@@ -220,6 +226,35 @@ impl BasicLanguageMode {
     }
   }
 
+  fn number_to_string(&self, engine: &LanguageModeEngine, number: &Number) -> String {
+    let language_settings = engine.language_settings();
+    // Float formats usually round the value, so they're never
+    // reversible either.
+    // Likewise, two's complement output reads back as an unsigned
+    // value.
+    let twos_complement_word = language_settings.word_size.filter(|_| language_settings.twos_complement);
+    let s = if self.uses_reversible_output {
+      number.to_string_radix(language_settings.preferred_radix)
+    } else if let Some(s) = twos_complement_word.and_then(|word_size| {
+      number.to_string_twos_complement(language_settings.preferred_radix, word_size)
+    }) {
+      s
+    } else {
+      number.to_string_formatted(
+        language_settings.preferred_radix,
+        language_settings.float_format,
+        language_settings.rounding_mode,
+      )
+    };
+    if language_settings.preferred_radix == Radix::DECIMAL {
+      // Digit grouping is never reversible, since the parser doesn't
+      // accept group separators.
+      language_settings.locale.localize_number(&s, !self.uses_reversible_output)
+    } else {
+      s
+    }
+  }
+
   fn number_needs_parens(&self, number: &Number, prec: Precedence) -> bool {
     let negation_precedence = self.known_operators
       .get_by_operator_name("-")
@@ -231,29 +266,65 @@ impl BasicLanguageMode {
 
   fn vector_to_html(&self, engine: &LanguageModeEngine, out: &mut String, elems: &[Expr]) {
     fancy_square_brackets(self.uses_fancy_parens).write_bracketed_if_ok(out, true, |out| {
-      output_sep_by(out, elems.iter(), ", ", |out, e| engine.write_to_html(out, e, Precedence::MIN));
+      self.elements_to_html(engine, out, elems);
+    });
+  }
+
+  fn set_to_html(&self, engine: &LanguageModeEngine, out: &mut String, elems: &[Expr]) {
+    ConstBrackets::curly().write_bracketed_if_ok(out, true, |out| {
+      self.elements_to_html(engine, out, elems);
+    });
+  }
+
+  /// Writes the elements of a vector or set, separated by commas.
+  /// Long sequences are abbreviated unless the output is meant to be
+  /// reversible.
+  fn elements_to_html(&self, engine: &LanguageModeEngine, out: &mut String, elems: &[Expr]) {
+    let separator = engine.argument_separator();
+    if self.uses_reversible_output {
+      output_sep_by(out, elems.iter(), &separator, |out, e| engine.write_to_html(out, e, Precedence::MIN));
+    } else {
+      output_sep_by(out, engine.elide(elems), &separator, |out, e| match e {
+        Elided::Item(e) => engine.write_to_html(out, e, Precedence::MIN),
+        Elided::Elision => engine.write_elision_marker(out),
+      });
+    }
+  }
+
+  fn tuple_to_html(&self, engine: &LanguageModeEngine, out: &mut String, elems: &[Expr]) {
+    let brackets = if engine.language_settings().prefers_unicode_output {
+      ConstBrackets::new("⟨", "⟩")
+    } else {
+      ConstBrackets::new("&lt;", "&gt;")
+    };
+    brackets.write_bracketed_if_ok(out, true, |out| {
+      output_sep_by(out, elems.iter(), &engine.argument_separator(), |out, e| engine.write_to_html(out, e, Precedence::MIN));
     });
   }
 
-  fn complex_to_html(&self, engine: &LanguageModeEngine, out: &mut String, args: &[Expr]) {
+  fn complex_to_html(&self, engine: &LanguageModeEngine, out: &mut String, args: &[Expr], prec: Precedence) {
     assert_eq!(args.len(), 2, "Expecting slice of two Exprs, got {:?}", args);
+    if let Some(angle_mode) = engine.language_settings().polar_angle_mode {
+      let polar_op = self.known_operators.get_by_function_name("angle", FixityType::Infix);
+      if let (Some(polar_op), [Expr::Atom(Atom::Number(re)), Expr::Atom(Atom::Number(im))]) = (polar_op, args) {
+        let (r, theta) = ComplexNumber::new(re.clone(), im.clone()).to_polar();
+        let theta = angle_mode.from_radians(theta);
+        self.bin_infix_op_to_html(engine, out, polar_op, &Expr::from(r), &Expr::from(theta), prec);
+        return;
+      }
+    }
     fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, true, |out| {
       engine.write_to_html(out, &args[0], Precedence::MIN);
-      out.push_str(", ");
+      out.push_str(&engine.argument_separator());
       engine.write_to_html(out, &args[1], Precedence::MIN);
     });
   }
 
-  fn quat_to_html(&self, engine: &LanguageModeEngine, out: &mut String, args: &[Expr]) {
-    assert_eq!(args.len(), 4, "Expecting slice of four Exprs, got {:?}", args);
+  /// Writes a quaternion or octonion as a parenthesized list of its
+  /// components.
+  fn hypercomplex_to_html(&self, engine: &LanguageModeEngine, out: &mut String, args: &[Expr]) {
     fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, true, |out| {
-      engine.write_to_html(out, &args[0], Precedence::MIN);
-      out.push_str(", ");
-      engine.write_to_html(out, &args[1], Precedence::MIN);
-      out.push_str(", ");
-      engine.write_to_html(out, &args[2], Precedence::MIN);
-      out.push_str(", ");
-      engine.write_to_html(out, &args[3], Precedence::MIN);
+      output_sep_by(out, args.iter(), &engine.argument_separator(), |out, e| engine.write_to_html(out, e, Precedence::MIN));
     });
   }
 
@@ -262,12 +333,88 @@ impl BasicLanguageMode {
     if let Expr::Atom(Atom::String(s)) = &args[0] {
       if let Ok(object_type) = ObjectType::parse(s) {
         let incomplete_object = IncompleteObject::new(object_type);
-        out.push_str(&incomplete_object.to_string());
+        out.push_str(encode_safe(&incomplete_object.to_string()).as_ref());
         return;
       }
     }
     self.fn_call_to_html(engine, out, IncompleteObject::FUNCTION_NAME, args);
   }
+
+  /// Streams a function call, vector, or infix operator application
+  /// one argument at a time. Returns `Ok(false)` if the expression
+  /// has some other shape.
+  fn try_write_call_incrementally(
+    &self,
+    engine: &LanguageModeEngine,
+    out: &mut dyn io::Write,
+    f: &str,
+    args: &[Expr],
+    prec: Precedence,
+  ) -> io::Result<bool> {
+    if f == Vector::FUNCTION_NAME {
+      out.write_all(b"[")?;
+      self.write_args_incrementally(engine, out, args, &engine.argument_separator(), Precedence::MIN)?;
+      out.write_all(b"]")?;
+      return Ok(true);
+    }
+    if f == ComplexNumber::FUNCTION_NAME || f == Quaternion::FUNCTION_NAME || f == Octonion::FUNCTION_NAME {
+      // Numerical literals are small, and have special syntax.
+      return Ok(false);
+    }
+    if let Some(op) = self.known_operators.get_by_function_name(f, FixityType::Infix) {
+      let infix_props = op.fixity().as_infix().unwrap(); // unwrap: Looked up as infix
+      let is_infix = args.len() == 2 || (args.len() > 2 && infix_props.associativity().is_fully_assoc());
+      if is_infix {
+        let needs_parens = engine.needs_parens(prec, infix_props.precedence());
+        let separator = if op.operator_name() == "*" {
+          String::from(" ")
+        } else {
+          format!(" {} ", encode_safe(op.operator_name()))
+        };
+        if needs_parens {
+          out.write_all(b"(")?;
+        }
+        if args.len() == 2 {
+          engine.write_incrementally(out, &args[0], infix_props.left_precedence())?;
+          out.write_all(separator.as_bytes())?;
+          engine.write_incrementally(out, &args[1], infix_props.right_precedence())?;
+        } else {
+          self.write_args_incrementally(engine, out, args, &separator, infix_props.precedence())?;
+        }
+        if needs_parens {
+          out.write_all(b")")?;
+        }
+        return Ok(true);
+      }
+    }
+    let is_operator =
+      self.known_operators.get_by_function_name(f, FixityType::Prefix).is_some() ||
+      self.known_operators.get_by_function_name(f, FixityType::Postfix).is_some();
+    if is_operator {
+      return Ok(false);
+    }
+    write!(out, "{}(", encode_safe(f))?;
+    self.write_args_incrementally(engine, out, args, &engine.argument_separator(), Precedence::MIN)?;
+    out.write_all(b")")?;
+    Ok(true)
+  }
+
+  fn write_args_incrementally(
+    &self,
+    engine: &LanguageModeEngine,
+    out: &mut dyn io::Write,
+    args: &[Expr],
+    separator: &str,
+    prec: Precedence,
+  ) -> io::Result<()> {
+    for (i, arg) in args.iter().enumerate() {
+      if i > 0 {
+        out.write_all(separator.as_bytes())?;
+      }
+      engine.write_incrementally(out, arg, prec)?;
+    }
+    Ok(())
+  }
 }
 
 impl LanguageMode for BasicLanguageMode {
@@ -275,7 +422,7 @@ impl LanguageMode for BasicLanguageMode {
     match expr {
       Expr::Atom(Atom::Number(n)) => {
         fancy_parens(self.uses_fancy_parens).write_bracketed_if_ok(out, self.number_needs_parens(n, prec), |out| {
-          out.push_str(&n.to_string_radix(engine.language_settings().preferred_radix));
+          out.push_str(&self.number_to_string(engine, n));
         });
       }
       Expr::Atom(Atom::Var(v)) => {
@@ -289,11 +436,17 @@ impl LanguageMode for BasicLanguageMode {
         if !self.uses_reversible_output && f == IncompleteObject::FUNCTION_NAME && args.len() == 1 {
           self.incomplete_object_to_html(engine, out, args);
         } else if f == ComplexNumber::FUNCTION_NAME && args.len() == 2 {
-          self.complex_to_html(engine, out, args);
-        } else if f == Quaternion::FUNCTION_NAME && args.len() == 4 {
-          self.quat_to_html(engine, out, args);
+          self.complex_to_html(engine, out, args, prec);
+        } else if (f == Quaternion::FUNCTION_NAME && args.len() == 4) || (f == Octonion::FUNCTION_NAME && args.len() == 8) {
+          self.hypercomplex_to_html(engine, out, args);
         } else if f == Vector::FUNCTION_NAME {
           self.vector_to_html(engine, out, args);
+        } else if !self.uses_reversible_output && f == Set::FUNCTION_NAME {
+          // The parser has no syntax for set literals, so sets are
+          // written as ordinary function calls in reversible mode.
+          self.set_to_html(engine, out, args);
+        } else if !self.uses_reversible_output && f == Tuple::FUNCTION_NAME {
+          self.tuple_to_html(engine, out, args);
         } else {
           let as_op =
             self.try_infix_op_to_html(engine, out, f, args, prec) ||
@@ -307,6 +460,29 @@ impl LanguageMode for BasicLanguageMode {
     }
   }
 
+  /// In reversible mode, function calls, vectors, and infix
+  /// operators are streamed piece by piece, so that the output for a
+  /// large expression is never held in memory all at once. Otherwise,
+  /// this falls back to the default implementation.
+  fn write_incrementally(
+    &self,
+    engine: &LanguageModeEngine,
+    out: &mut dyn io::Write,
+    expr: &Expr,
+    prec: Precedence,
+  ) -> io::Result<()> {
+    if self.uses_reversible_output {
+      if let Expr::Call(f, args) = expr {
+        if self.try_write_call_incrementally(engine, out, f, args, prec)? {
+          return Ok(());
+        }
+      }
+    }
+    let mut html = String::new();
+    self.write_to_html(engine, &mut html, expr, prec);
+    out.write_all(html.as_bytes())
+  }
+
   fn to_trait_object(&self) -> &dyn LanguageMode {
     self
   }
@@ -322,8 +498,8 @@ impl LanguageMode for BasicLanguageMode {
     }
   }
 
-  fn parse(&self, text: &str) -> anyhow::Result<Expr> {
-    let parser = ExprParser::new(&self.known_operators);
+  fn parse_localized(&self, text: &str, locale: &Locale) -> anyhow::Result<Expr> {
+    let parser = ExprParser::new(&self.known_operators).with_locale(locale.clone());
     let expr = parser.tokenize_and_parse(text)?;
     Ok(expr)
   }
@@ -336,9 +512,13 @@ impl LanguageMode for BasicLanguageMode {
 #[cfg(test)]
 mod tests {
   use super::*;
+
+  use std::str::FromStr;
   use crate::mode::display::language::LanguageSettings;
   use crate::mode::display::language::test_utils::{to_html, to_html_no_unicode};
   use crate::mode::display::unicode::{UnicodeAlias, UnicodeAliasTable};
+  use crate::mode::calculation::{AngleMode, WordSize};
+  use crate::mode::display::float_format::FloatFormat;
 
   fn sample_unicode_table() -> UnicodeAliasTable {
     UnicodeAliasTable::new(vec![
@@ -371,6 +551,90 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_complex_numbers_in_polar_mode() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let settings = LanguageSettings {
+      polar_angle_mode: Some(AngleMode::Degrees),
+      ..LanguageSettings::default()
+    };
+    let expr = Expr::from(ComplexNumber::new(0, 2));
+    assert_eq!(mode.to_html(&expr, &settings), "2.0 ∠ 90.0");
+    let expr = Expr::call("+", vec![Expr::from(1), Expr::from(ComplexNumber::new(-3, 0))]);
+    assert_eq!(mode.to_html(&expr, &settings), "1 + 3.0 ∠ 180.0");
+    let expr = Expr::from(ComplexNumber::new(0, 2));
+    assert_eq!(mode.to_reversible_language_mode().to_html(&expr, &settings), "2.0 angle 90.0");
+  }
+
+  #[test]
+  fn test_polar_mode_round_trip() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let settings = LanguageSettings {
+      polar_angle_mode: Some(AngleMode::Degrees),
+      ..LanguageSettings::default()
+    };
+    let expr = Expr::from(ComplexNumber::new(0, 2));
+    let text = mode.to_reversible_language_mode().to_html(&expr, &settings);
+    let parsed = mode.parse(&text).unwrap();
+    assert_eq!(parsed, Expr::call("angle", vec![Expr::from(2.0), Expr::from(90.0)]));
+  }
+
+  #[test]
+  fn test_float_format() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let settings = LanguageSettings {
+      float_format: FloatFormat::Scientific(Some(3)),
+      ..LanguageSettings::default()
+    };
+    let expr = Expr::call("+", vec![Expr::from(12345.0), Expr::from(7)]);
+    assert_eq!(mode.to_html(&expr, &settings), "1.23e4 + 7");
+    // Reversible output is never rounded.
+    assert_eq!(mode.to_reversible_language_mode().to_html(&expr, &settings), "12345.0 + 7");
+  }
+
+  #[test]
+  fn test_twos_complement() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let settings = LanguageSettings {
+      preferred_radix: Radix::HEXADECIMAL,
+      twos_complement: true,
+      word_size: Some(WordSize::new(16, true)),
+      ..LanguageSettings::default()
+    };
+    let expr = Expr::call("+", vec![Expr::from(-1), Expr::from(255)]);
+    assert_eq!(mode.to_html(&expr, &settings), "16#FFFF + 16#00FF");
+    // Reversible output reads back as the original value.
+    assert_eq!(mode.to_reversible_language_mode().to_html(&expr, &settings), "-16#1 + 16#FF");
+    // Without a word size, the flag has no effect.
+    let settings = LanguageSettings { word_size: None, ..settings };
+    assert_eq!(mode.to_html(&expr, &settings), "-16#1 + 16#FF");
+  }
+
+  #[test]
+  fn test_always_parenthesize() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let settings = LanguageSettings {
+      always_parenthesize: true,
+      prefers_unicode_output: false,
+      ..LanguageSettings::default()
+    };
+    let expr = Expr::call("+", vec![
+      Expr::var("a").unwrap(),
+      Expr::call("^", vec![Expr::var("b").unwrap(), Expr::var("c").unwrap()]),
+    ]);
+    assert_eq!(to_html_no_unicode(&mode, &expr), "a + b ^ c");
+    assert_eq!(mode.to_html(&expr, &settings), "a + (b ^ c)");
+    let expr = Expr::call("f", vec![
+      Expr::call("negate", vec![Expr::var("x").unwrap()]),
+      Expr::call("^", vec![Expr::var("y").unwrap(), Expr::from(2)]),
+    ]);
+    assert_eq!(mode.to_html(&expr, &settings), "f(- x, y ^ 2)");
+    let expr = Expr::call("negate", vec![
+      Expr::call("^", vec![Expr::var("y").unwrap(), Expr::from(2)]),
+    ]);
+    assert_eq!(mode.to_html(&expr, &settings), "- (y ^ 2)");
+  }
+
   #[test]
   fn test_complex_numbers_with_fancy_parens() {
     let mode = BasicLanguageMode::default().with_fancy_parens();
@@ -552,6 +816,20 @@ mod tests {
     assert_eq!(to_html(&mode, &expr), "(1 % 2) % (3 % 4)");
   }
 
+  #[test]
+  fn test_modular_int() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let expr = Expr::call(
+      "modulo",
+      vec![
+        Expr::call("+", vec![Expr::var("x").unwrap(), Expr::from(1)]),
+        Expr::from(7),
+      ],
+    );
+    assert_eq!(to_html(&mode, &expr), "x + 1 mod 7");
+    assert_eq!(mode.parse("x + 1 mod 7").unwrap(), expr);
+  }
+
   #[test]
   fn test_op_with_unicode() {
     let mode = BasicLanguageMode::from_common_operators();
@@ -580,6 +858,31 @@ mod tests {
       vec![Expr::from(1)],
     );
     assert_eq!(to_html(&mode, &expr), "+ 1");
+
+    let expr = Expr::call(
+      "not",
+      vec![Expr::call("&&", vec![Expr::var("x").unwrap(), Expr::var("y").unwrap()])],
+    );
+    assert_eq!(to_html(&mode, &expr), "! (x &amp;&amp; y)");
+  }
+
+  #[test]
+  fn test_logical_not_round_trip() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let locale = Locale::default();
+    let x = Expr::var("x").unwrap();
+    let y = Expr::var("y").unwrap();
+    assert_eq!(
+      mode.parse_localized("!x < y && !!y", &locale).unwrap(),
+      Expr::call("&&", vec![
+        Expr::call("not", vec![Expr::call("<", vec![x.clone(), y.clone()])]),
+        Expr::call("not", vec![Expr::call("not", vec![y.clone()])]),
+      ]),
+    );
+    assert_eq!(
+      mode.parse_localized("x != y", &locale).unwrap(),
+      Expr::call("!=", vec![x, y]),
+    );
   }
 
   #[test]
@@ -625,6 +928,28 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_long_vector_is_elided() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let settings = LanguageSettings { max_display_elements: Some(4), ..LanguageSettings::default() };
+    let expr = Expr::call("vector", (1..=10).map(Expr::from).collect());
+    assert_eq!(
+      mode.to_html(&expr, &settings),
+      r#"[1, 2, 3, <span class="elision">…</span>, 10]"#,
+    );
+    let expr = Expr::call("vector", (1..=4).map(Expr::from).collect());
+    assert_eq!(mode.to_html(&expr, &settings), "[1, 2, 3, 4]");
+  }
+
+  #[test]
+  fn test_long_vector_in_reversible_mode() {
+    let mode = BasicLanguageMode::from_common_operators().with_fancy_parens();
+    let mode = mode.to_reversible_language_mode();
+    let settings = LanguageSettings { max_display_elements: Some(4), ..LanguageSettings::default() };
+    let expr = Expr::call("vector", (1..=10).map(Expr::from).collect());
+    assert_eq!(mode.to_html(&expr, &settings), "[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]");
+  }
+
   #[test]
   fn test_incomplete_object() {
     let mode = BasicLanguageMode::from_common_operators();
@@ -633,6 +958,11 @@ mod tests {
       vec![Expr::string("[")],
     );
     assert_eq!(to_html(&mode, &expr), "[ ...");
+    let expr = Expr::call(
+      "incomplete",
+      vec![Expr::string("<")],
+    );
+    assert_eq!(to_html(&mode, &expr), "&lt; ...");
   }
 
   #[test]
@@ -663,6 +993,83 @@ mod tests {
     assert_eq!(mode.to_html(&expr, &LanguageSettings::default()), r#"incomplete("[")"#);
   }
 
+  #[test]
+  fn test_set() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let expr = Expr::call("set", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(to_html(&mode, &expr), "{1, 2}");
+    let expr = Expr::call("set", vec![]);
+    assert_eq!(to_html(&mode, &expr), "{}");
+  }
+
+  #[test]
+  fn test_set_in_reversible_mode() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let mode = mode.to_reversible_language_mode();
+    let expr = Expr::call("set", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(mode.to_html(&expr, &LanguageSettings::default()), "set(1, 2)");
+  }
+
+  #[test]
+  fn test_tuple() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let expr = Expr::call("tuple", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(to_html(&mode, &expr), "⟨1, 2⟩");
+    assert_eq!(to_html_no_unicode(&mode, &expr), "&lt;1, 2&gt;");
+  }
+
+  #[test]
+  fn test_tuple_in_reversible_mode() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let mode = mode.to_reversible_language_mode();
+    let expr = Expr::call("tuple", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(mode.to_html(&expr, &LanguageSettings::default()), "tuple(1, 2)");
+  }
+
+  #[test]
+  fn test_localized_output() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let settings = LanguageSettings {
+      locale: Locale::from_str("de").unwrap(),
+      ..LanguageSettings::default()
+    };
+    let expr = Expr::call("vector", vec![Expr::from(Number::from(1.5)), Expr::from(12345)]);
+    assert_eq!(mode.to_html(&expr, &settings), "[1,5; 12.345]");
+    let mode = mode.to_reversible_language_mode();
+    assert_eq!(mode.to_html(&expr, &settings), "[1,5; 12345]");
+  }
+
+  #[test]
+  fn test_localized_round_trip() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let locale = Locale::from_str("de").unwrap();
+    let settings = LanguageSettings { locale: locale.clone(), ..LanguageSettings::default() };
+    let expr = Expr::call("f", vec![Expr::from(Number::from(0.25)), Expr::from(1000)]);
+    let text = mode.to_reversible_language_mode().to_html(&expr, &settings);
+    assert_eq!(mode.parse_localized(&text, &locale).unwrap(), expr);
+  }
+
+  #[test]
+  fn test_incremental_output_matches_html() {
+    let mode = BasicLanguageMode::from_common_operators();
+    let mode = mode.to_reversible_language_mode();
+    let settings = LanguageSettings::default();
+    let x = Expr::var("x").unwrap();
+    let exprs = vec![
+      Expr::call("+", vec![Expr::from(1), Expr::from(-2), x.clone()]),
+      Expr::call("*", vec![Expr::call("+", vec![Expr::from(1), x.clone()]), Expr::from(3)]),
+      Expr::call("<=", vec![Expr::from(100), Expr::call("f", vec![x.clone(), Expr::string("a&b")])]),
+      Expr::call("vector", vec![Expr::from(ComplexNumber::new(1, 2)), Expr::call("negate", vec![x.clone()])]),
+      Expr::call("-", vec![x.clone(), Expr::call("-", vec![Expr::from(1), x])]),
+      Expr::call("set", vec![]),
+    ];
+    for expr in exprs {
+      let mut out = Vec::new();
+      mode.to_writer(&expr, &settings, &mut out).unwrap();
+      assert_eq!(String::from_utf8(out).unwrap(), mode.to_html(&expr, &settings));
+    }
+  }
+
   // TODO Common operators doesn't have any postfix ops right now,
   // test those when we get them
 }
@@ -1,23 +1,25 @@
 
-use super::{LanguageMode, LanguageModeEngine};
+use super::{LanguageMode, LanguageModeEngine, Elided, write_elision_span};
+use crate::mode::display::locale::Locale;
 use crate::parsing::operator::Precedence;
 use crate::parsing::operator::table::{EXPONENT_PRECEDENCE, INTERVAL_PRECEDENCE,
-                                      DIVISION_PRECEDENCE,
+                                      DIVISION_PRECEDENCE, ADDITION_PRECEDENCE, MODULO_PRECEDENCE,
                                       PREFIX_FUNCTION_CALL_PRECEDENCE};
 use crate::mode::display::unicode::{UnicodeAliasTable, common_unicode_aliases};
 use crate::util::cow_dyn::CowDyn;
 use crate::expr::Expr;
-use crate::expr::number::Number;
+use crate::expr::number::{Number, Quaternion, Octonion};
 use crate::expr::vector::matrix::borrowed::BorrowedMatrix;
 use crate::expr::var::Var;
 use crate::expr::atom::Atom;
 use crate::expr::interval::IntervalType;
+use crate::expr::modular::ModularInt;
 use crate::util::brackets::{BracketConstruct, fancy_parens, fancy_square_brackets,
                             HtmlBrackets, HtmlBracketsType};
 
 use once_cell::sync::Lazy;
 use html_escape::encode_safe;
-use num::Zero;
+use num::{Zero, One};
 
 use std::collections::HashSet;
 
@@ -70,6 +72,56 @@ pub fn can_prefix_promote_arg(arg: &Expr) -> bool {
   }
 }
 
+/// Names of the imaginary units of the quaternions, as HTML.
+const QUATERNION_UNITS: [&str; 4] = ["", "i", "j", "k"];
+
+/// Names of the imaginary units of the octonions, as HTML.
+const OCTONION_UNITS: [&str; 8] = [
+  "", "e<sub>1</sub>", "e<sub>2</sub>", "e<sub>3</sub>",
+  "e<sub>4</sub>", "e<sub>5</sub>", "e<sub>6</sub>", "e<sub>7</sub>",
+];
+
+/// If `f(args)` is a quaternion or octonion whose components are all
+/// real literals, returns those components, paired with the names of
+/// their units.
+fn hypercomplex_literal_parts<'a>(f: &str, args: &'a [Expr]) -> Option<Vec<(&'a Number, &'static str)>> {
+  let units: &[&'static str] = if f == Quaternion::FUNCTION_NAME && args.len() == 4 {
+    &QUATERNION_UNITS
+  } else if f == Octonion::FUNCTION_NAME && args.len() == 8 {
+    &OCTONION_UNITS
+  } else {
+    return None;
+  };
+  args.iter().zip(units)
+    .map(|(arg, unit)| match arg {
+      Expr::Atom(Atom::Number(n)) => Some((n, *unit)),
+      _ => None,
+    })
+    .collect()
+}
+
+/// If the function call is an `if` or `piecewise` call of the right
+/// shape, returns its branches, as expected by
+/// [`FancyLanguageMode::write_piecewise`].
+fn piecewise_branches<'a>(f: &str, args: &'a [Expr]) -> Option<Vec<(&'a Expr, Option<&'a Expr>)>> {
+  match f {
+    "if" if args.len() == 3 => {
+      Some(vec![(&args[1], Some(&args[0])), (&args[2], None)])
+    }
+    "piecewise" if args.len() >= 2 => {
+      let branches = args.chunks(2)
+        .map(|chunk| match chunk {
+          [condition, value] => (value, Some(condition)),
+          [default] => (default, None),
+          _ => unreachable!(),
+        })
+        .collect();
+      Some(branches)
+    }
+    _ => None,
+  }
+}
+
 impl<L: LanguageMode> FancyLanguageMode<L> {
   pub fn new(inner_mode: L) -> Self {
     Self {
@@ -108,7 +160,7 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
     let [base, exp] = args else { unreachable!() };
 
     out.push_str("<span class=\"grouping-span\">");
-    fancy_parens(true).write_bracketed_if_ok(out, prec > EXPONENT_PRECEDENCE, |out| {
+    fancy_parens(true).write_bracketed_if_ok(out, engine.needs_parens(prec, EXPONENT_PRECEDENCE), |out| {
       out.push_str("<span class=\"grouping-span\">");
       engine.write_to_html(out, base, EXPONENT_PRECEDENCE.incremented());
       out.push_str("</span>");
@@ -124,7 +176,7 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
     let [exp] = args else { unreachable!() };
 
     out.push_str("<span class=\"grouping-span\">");
-    fancy_parens(true).write_bracketed_if_ok(out, prec > EXPONENT_PRECEDENCE, |out| {
+    fancy_parens(true).write_bracketed_if_ok(out, engine.needs_parens(prec, EXPONENT_PRECEDENCE), |out| {
       out.push_str("<span>𝕖</span>");
       out.push_str("<sup>");
       engine.write_to_html(out, exp, Precedence::MIN);
@@ -170,6 +222,22 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
     out.push_str("</span>");
   }
 
+  /// Writes a modular integer in the conventional `a (mod m)`
+  /// notation.
+  fn write_modular(&self, engine: &LanguageModeEngine, out: &mut String, args: &[Expr], prec: Precedence) {
+    assert!(args.len() == 2);
+    let [value, modulus] = args else { unreachable!() };
+
+    fancy_parens(true).write_bracketed_if_ok(out, engine.needs_parens(prec, MODULO_PRECEDENCE), |out| {
+      engine.write_to_html(out, value, MODULO_PRECEDENCE.incremented());
+      out.push(' ');
+      fancy_parens(true).write_bracketed_if_ok(out, true, |out| {
+        out.push_str("mod ");
+        engine.write_to_html(out, modulus, Precedence::MIN);
+      });
+    });
+  }
+
   fn write_with_prefix_promotion(
     &self,
     engine: &LanguageModeEngine,
@@ -178,7 +246,7 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
     arg: &Expr,
     prec: Precedence,
   ) {
-    fancy_parens(true).write_bracketed_if_ok(out, prec > PREFIX_FUNCTION_CALL_PRECEDENCE, |out| {
+    fancy_parens(true).write_bracketed_if_ok(out, engine.needs_parens(prec, PREFIX_FUNCTION_CALL_PRECEDENCE), |out| {
       out.push_str(function);
       out.push(' ');
       engine.write_to_html(out, arg, PREFIX_FUNCTION_CALL_PRECEDENCE);
@@ -193,7 +261,7 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
     base: &Expr,
     prec: Precedence,
   ) {
-    fancy_parens(true).write_bracketed_if_ok(out, prec > PREFIX_FUNCTION_CALL_PRECEDENCE, |out| {
+    fancy_parens(true).write_bracketed_if_ok(out, engine.needs_parens(prec, PREFIX_FUNCTION_CALL_PRECEDENCE), |out| {
       out.push_str("log<sub>");
       engine.write_to_html(out, base, Precedence::MIN);
       out.push_str("</sub> ");
@@ -204,13 +272,31 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
   fn write_matrix(&self, engine: &LanguageModeEngine, out: &mut String, matrix: &BorrowedMatrix) {
     out.push_str("<span class=\"grouping-span\">");
     fancy_square_brackets(true).write_bracketed_if_ok(out, true, |out| {
+      // Every row is elided in the same way, so the first row tells
+      // us which column (if any) holds the elision marker.
+      let columns = engine.elide(matrix.row(0).unwrap_or(&[]));
       out.push_str(r#"<table class="matrix-table">"#);
-      for row in matrix.rows() {
+      for row in engine.elide(matrix.as_row_major()) {
         out.push_str("<tr>");
-        for elem in row {
-          out.push_str("<td>");
-          engine.write_to_html(out, elem, Precedence::MIN);
-          out.push_str("</td>");
+        match row {
+          Elided::Item(row) => {
+            for elem in engine.elide(row) {
+              out.push_str("<td>");
+              match elem {
+                Elided::Item(elem) => engine.write_to_html(out, elem, Precedence::MIN),
+                Elided::Elision => engine.write_elision_marker(out),
+              }
+              out.push_str("</td>");
+            }
+          }
+          Elided::Elision => {
+            for column in &columns {
+              out.push_str("<td>");
+              let marker = if matches!(column, Elided::Elision) { "⋱" } else { "⋮" };
+              write_elision_span(out, marker);
+              out.push_str("</td>");
+            }
+          }
         }
         out.push_str("</tr>");
       }
@@ -231,7 +317,7 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
     assert!(args.len() == 2);
     let [numer, denom] = args else { unreachable!() };
 
-    fancy_parens(true).write_bracketed_if_ok(out, prec > DIVISION_PRECEDENCE, |out| {
+    fancy_parens(true).write_bracketed_if_ok(out, engine.needs_parens(prec, DIVISION_PRECEDENCE), |out| {
       out.push_str(r#"<table class="fraction-table">"#);
       out.push_str("<tr>");
       out.push_str(r#"<td class="fraction-table-numerator">"#);
@@ -246,6 +332,70 @@ impl<L: LanguageMode> FancyLanguageMode<L> {
       out.push_str("</table>");
     });
   }
+
+  /// Writes the branches of an `if` or `piecewise` call as a table
+  /// with a brace on the left. Each branch is a value together with
+  /// its condition, or `None` for the default value.
+  fn write_piecewise(&self, engine: &LanguageModeEngine, out: &mut String, branches: &[(&Expr, Option<&Expr>)]) {
+    out.push_str("<span class=\"grouping-span\">");
+    out.push_str(&format!(
+      r#"<span class="{} {}">"#,
+      HtmlBracketsType::BASE_CSS_CLASS,
+      HtmlBracketsType::CurlyBraces.left_css_class(),
+    ));
+    out.push_str(r#"<table class="piecewise-table">"#);
+    for (value, condition) in branches {
+      out.push_str("<tr><td>");
+      engine.write_to_html(out, value, Precedence::MIN);
+      out.push_str("</td><td>");
+      match condition {
+        Some(condition) => {
+          out.push_str("if ");
+          engine.write_to_html(out, condition, Precedence::MIN);
+        }
+        None => {
+          out.push_str("otherwise");
+        }
+      }
+      out.push_str("</td></tr>");
+    }
+    out.push_str("</table>");
+    out.push_str("</span>");
+    out.push_str("</span>");
+  }
+
+  /// Writes a quaternion or octonion literal as a sum, such as `a +
+  /// bi + cj + dk`, omitting any zero components.
+  fn write_hypercomplex(&self, engine: &LanguageModeEngine, out: &mut String, parts: Vec<(&Number, &str)>, prec: Precedence) {
+    let terms: Vec<_> = parts.into_iter()
+      .filter(|(coeff, _)| !coeff.is_zero())
+      .collect();
+    if terms.is_empty() {
+      self.inner_mode.write_to_html(engine, out, &Expr::zero(), prec);
+      return;
+    }
+    let needs_parens = (terms.len() > 1 || *terms[0].0 < Number::zero()) && engine.needs_parens(prec, ADDITION_PRECEDENCE);
+    fancy_parens(true).write_bracketed_if_ok(out, needs_parens, |out| {
+      for (index, (coeff, unit)) in terms.into_iter().enumerate() {
+        let is_negative = *coeff < Number::zero();
+        match (index, is_negative) {
+          (0, false) => {}
+          (0, true) => out.push('-'),
+          (_, false) => out.push_str(" + "),
+          (_, true) => out.push_str(" - "),
+        }
+        let magnitude = coeff.abs();
+        if unit.is_empty() || !magnitude.is_one() {
+          self.inner_mode.write_to_html(engine, out, &Expr::from(magnitude), Precedence::MIN);
+        }
+        if !unit.is_empty() {
+          out.push_str(r#"<span class="mathy-text">"#);
+          out.push_str(unit);
+          out.push_str("</span>");
+        }
+      }
+    });
+  }
 }
 
 impl<L: LanguageMode + Default> Default for FancyLanguageMode<L> {
@@ -275,6 +425,8 @@ impl<L: LanguageMode> LanguageMode for FancyLanguageMode<L> {
             self.write_exponent(engine, out, args, prec)
           } else if f == "/" && args.len() == 2 {
             self.write_fraction(engine, out, args, prec)
+          } else if let Some(parts) = hypercomplex_literal_parts(f, args) {
+            self.write_hypercomplex(engine, out, parts, prec)
           } else if f == "exp" && args.len() == 1 {
             self.write_e_to_exponent(engine, out, args, prec)
           } else if f == "abs" && args.len() == 1 {
@@ -285,6 +437,10 @@ impl<L: LanguageMode> LanguageMode for FancyLanguageMode<L> {
             self.write_abs_value_bars(engine, out, arg, Some(k))
           } else if IntervalType::is_interval_type(f) && args.len() == 2 {
             self.write_interval(engine, out, f, args)
+          } else if let Some(branches) = piecewise_branches(f, args) {
+            self.write_piecewise(engine, out, &branches)
+          } else if f == ModularInt::FUNCTION_NAME && args.len() == 2 {
+            self.write_modular(engine, out, args, prec)
           } else if PREFIX_PROMOTION_FUNCTIONS.contains(f) && args.len() == 1 && can_prefix_promote_arg(&args[0]) {
             self.write_with_prefix_promotion(engine, out, f, &args[0], prec)
           } else if f == "log" && args.len() == 2 && can_prefix_promote_arg(&args[0]) {
@@ -305,8 +461,8 @@ impl<L: LanguageMode> LanguageMode for FancyLanguageMode<L> {
     self.inner_mode.to_reversible_language_mode()
   }
 
-  fn parse(&self, text: &str) -> anyhow::Result<Expr> {
-    self.inner_mode.parse(text)
+  fn parse_localized(&self, text: &str, locale: &Locale) -> anyhow::Result<Expr> {
+    self.inner_mode.parse_localized(text, locale)
   }
 
   fn language_mode_name(&self) -> String {
@@ -317,6 +473,7 @@ impl<L: LanguageMode> LanguageMode for FancyLanguageMode<L> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::mode::display::language::LanguageSettings;
   use crate::mode::display::language::test_utils::to_html;
   use crate::mode::display::language::basic::BasicLanguageMode;
   use crate::expr::number::ComplexNumber;
@@ -372,6 +529,50 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_quaternions() {
+    let mode = sample_language_mode();
+    assert_eq!(
+      to_html(&mode, &Expr::from(Quaternion::new(1, 2, -3, 1))),
+      r#"1 + 2<span class="mathy-text">i</span> - 3<span class="mathy-text">j</span> + <span class="mathy-text">k</span>"#,
+    );
+    assert_eq!(
+      to_html(&mode, &Expr::from(Quaternion::new(0, 0, -1, 0))),
+      r#"-<span class="mathy-text">j</span>"#,
+    );
+    assert_eq!(to_html(&mode, &Expr::from(Quaternion::new(0, 0, 0, 0))), "0");
+  }
+
+  #[test]
+  fn test_octonions() {
+    let mode = sample_language_mode();
+    let expr = Expr::from(Octonion::from_components([2, 0, 0, 0, 0, -1, 0, 3].map(Number::from)));
+    assert_eq!(
+      to_html(&mode, &expr),
+      r#"2 - <span class="mathy-text">e<sub>5</sub></span> + 3<span class="mathy-text">e<sub>7</sub></span>"#,
+    );
+    let reversible_mode = mode.to_reversible_language_mode();
+    assert_eq!(reversible_mode.to_html(&expr, &LanguageSettings::default()), "(2, 0, 0, 0, 0, -1, 0, 3)");
+  }
+
+  #[test]
+  fn test_quaternion_in_product() {
+    let mode = sample_language_mode();
+    let expr = Expr::call("*", vec![Expr::var("x").unwrap(), Expr::from(Quaternion::new(0, 1, 0, 2))]);
+    assert_eq!(
+      to_html(&mode, &expr),
+      r#"<span class="mathy-text">x</span> <span class="bracketed bracketed--parens"><span class="mathy-text">i</span> + 2<span class="mathy-text">k</span></span>"#,
+    );
+  }
+
+  #[test]
+  fn test_quaternion_in_reversible_mode() {
+    let mode = sample_language_mode();
+    let expr = Expr::from(Quaternion::new(1, 2, 3, 4));
+    let reversible_mode = mode.to_reversible_language_mode();
+    assert_eq!(reversible_mode.to_html(&expr, &LanguageSettings::default()), "(1, 2, 3, 4)");
+  }
+
   #[test]
   fn test_simple_function_call() {
     let mode = sample_language_mode();
@@ -513,6 +714,59 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_modular() {
+    let mode = sample_language_mode();
+    let expr = Expr::call("modulo", vec![Expr::from(3), Expr::from(7)]);
+    assert_eq!(
+      to_html(&mode, &expr),
+      r#"3 <span class="bracketed bracketed--parens">mod 7</span>"#,
+    );
+    let expr = Expr::call("+", vec![expr, Expr::var("x").unwrap()]);
+    assert_eq!(
+      to_html(&mode, &expr),
+      r#"<span class="bracketed bracketed--parens">3 <span class="bracketed bracketed--parens">mod 7</span></span> + <span class="mathy-text">x</span>"#,
+    );
+  }
+
+  #[test]
+  fn test_if() {
+    let mode = sample_language_mode();
+    let expr = Expr::call("if", vec![
+      Expr::call("<", vec![Expr::var("x").unwrap(), Expr::from(0)]),
+      Expr::from(1),
+      Expr::from(2),
+    ]);
+    assert_eq!(to_html(&mode, &expr), concat!{
+      r#"<span class="grouping-span">"#,
+        r#"<span class="bracketed bracketed--curly-left">"#,
+          r#"<table class="piecewise-table">"#,
+            r#"<tr><td>1</td><td>if <span class="mathy-text">x</span> &lt; 0</td></tr>"#,
+            "<tr><td>2</td><td>otherwise</td></tr>",
+          "</table>",
+        "</span>",
+      "</span>",
+    });
+  }
+
+  #[test]
+  fn test_piecewise() {
+    let mode = sample_language_mode();
+    let expr = Expr::call("piecewise", vec![Expr::from(0), Expr::from(10), Expr::from(1), Expr::from(20)]);
+    assert_eq!(to_html(&mode, &expr), concat!{
+      r#"<span class="grouping-span">"#,
+        r#"<span class="bracketed bracketed--curly-left">"#,
+          r#"<table class="piecewise-table">"#,
+            "<tr><td>10</td><td>if 0</td></tr>",
+            "<tr><td>20</td><td>if 1</td></tr>",
+          "</table>",
+        "</span>",
+      "</span>",
+    });
+    let expr = Expr::call("piecewise", vec![Expr::from(0)]);
+    assert_eq!(to_html(&mode, &expr), r#"piecewise<span class="bracketed bracketed--parens">0</span>"#);
+  }
+
   #[test]
   fn test_interval_wrong_arity() {
     let mode = sample_language_mode();
@@ -573,6 +827,16 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_tuple() {
+    let mode = sample_language_mode();
+    let expr = Expr::call("tuple", vec![Expr::from(10), Expr::var("x").unwrap()]);
+    assert_eq!(
+      to_html(&mode, &expr),
+      r#"⟨10, <span class="mathy-text">x</span>⟩"#,
+    );
+  }
+
   #[test]
   fn test_jagged_vector() {
     let mode = sample_language_mode();
@@ -613,6 +877,37 @@ mod tests {
     });
   }
 
+  #[test]
+  fn test_large_matrix_is_elided() {
+    let mode = sample_language_mode();
+    let settings = LanguageSettings { max_display_elements: Some(2), ..LanguageSettings::default() };
+    let row = |n: i64| Expr::call("vector", vec![Expr::from(n), Expr::from(n + 1), Expr::from(n + 2)]);
+    let expr = Expr::call("vector", vec![row(10), row(20), row(30)]);
+    assert_eq!(mode.to_html(&expr, &settings), concat!{
+      r#"<span class="grouping-span">"#,
+        "<span class=\"bracketed bracketed--square\">",
+          "<table class=\"matrix-table\">",
+            "<tr>",
+              "<td>10</td>",
+              "<td><span class=\"elision\">…</span></td>",
+              "<td>12</td>",
+            "</tr>",
+            "<tr>",
+              "<td><span class=\"elision\">⋮</span></td>",
+              "<td><span class=\"elision\">⋱</span></td>",
+              "<td><span class=\"elision\">⋮</span></td>",
+            "</tr>",
+            "<tr>",
+              "<td>30</td>",
+              "<td><span class=\"elision\">…</span></td>",
+              "<td>32</td>",
+            "</tr>",
+          "</table>",
+        "</span>",
+      "</span>",
+    });
+  }
+
   #[test]
   fn test_empty_matrix() {
     let mode = sample_language_mode();
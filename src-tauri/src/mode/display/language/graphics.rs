@@ -1,5 +1,6 @@
 
 use super::{LanguageMode, LanguageModeEngine};
+use crate::mode::display::locale::Locale;
 use crate::expr::Expr;
 use crate::parsing::operator::Precedence;
 use crate::graphics::payload::{GraphicsPayload, SerializedGraphicsPayload};
@@ -51,8 +52,8 @@ impl<'a, L: LanguageMode + ?Sized> LanguageMode for GraphicsLanguageMode<'a, L>
     }
   }
 
-  fn parse(&self, text: &str) -> anyhow::Result<Expr> {
-    self.inner.parse(text)
+  fn parse_localized(&self, text: &str, locale: &Locale) -> anyhow::Result<Expr> {
+    self.inner.parse_localized(text, locale)
   }
 
   fn to_trait_object(&self) -> &dyn LanguageMode {
@@ -0,0 +1,87 @@
+
+use super::{LanguageMode, LanguageModeEngine};
+use crate::mode::display::locale::Locale;
+use crate::expr::Expr;
+use crate::parsing::operator::Precedence;
+use crate::util::cow_dyn::CowDyn;
+
+/// A language mode which parses input using a fixed [`Locale`] and is
+/// otherwise identical to the inner language mode.
+///
+/// Output is unaffected by this wrapper, since rendering already
+/// takes the locale from the
+/// [`LanguageSettings`](super::LanguageSettings).
+#[derive(Debug, Clone)]
+pub struct LocalizedLanguageMode<L> {
+  inner: L,
+  locale: Locale,
+}
+
+impl<L: LanguageMode> LocalizedLanguageMode<L> {
+  pub fn new(inner: L, locale: Locale) -> Self {
+    Self { inner, locale }
+  }
+
+  pub fn locale(&self) -> &Locale {
+    &self.locale
+  }
+}
+
+impl<L: LanguageMode> LanguageMode for LocalizedLanguageMode<L> {
+  fn write_to_html(&self, engine: &LanguageModeEngine, out: &mut String, expr: &Expr, prec: Precedence) {
+    self.inner.write_to_html(engine, out, expr, prec);
+  }
+
+  fn parse_localized(&self, text: &str, locale: &Locale) -> anyhow::Result<Expr> {
+    self.inner.parse_localized(text, locale)
+  }
+
+  fn parse(&self, text: &str) -> anyhow::Result<Expr> {
+    self.inner.parse_localized(text, &self.locale)
+  }
+
+  fn to_trait_object(&self) -> &dyn LanguageMode {
+    self
+  }
+
+  fn to_reversible_language_mode(&self) -> CowDyn<dyn LanguageMode> {
+    self.inner.to_reversible_language_mode()
+  }
+
+  fn language_mode_name(&self) -> String {
+    self.inner.language_mode_name()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::mode::display::language::basic::BasicLanguageMode;
+  use crate::expr::number::Number;
+
+  use std::str::FromStr;
+
+  #[test]
+  fn test_parse_uses_locale() {
+    let mode = LocalizedLanguageMode::new(
+      BasicLanguageMode::from_common_operators(),
+      Locale::from_str("de").unwrap(),
+    );
+    assert_eq!(
+      mode.parse("f(1,5; 2)").unwrap(),
+      Expr::call("f", vec![Expr::from(Number::from(1.5)), Expr::from(2)]),
+    );
+  }
+
+  #[test]
+  fn test_parse_localized_overrides_locale() {
+    let mode = LocalizedLanguageMode::new(
+      BasicLanguageMode::from_common_operators(),
+      Locale::from_str("de").unwrap(),
+    );
+    assert_eq!(
+      mode.parse_localized("f(1, 2)", &Locale::default()).unwrap(),
+      Expr::call("f", vec![Expr::from(1), Expr::from(2)]),
+    );
+  }
+}
@@ -2,11 +2,20 @@
 pub mod basic;
 pub mod fancy;
 pub mod graphics;
+pub mod localized;
 
+use super::locale::Locale;
+use super::float_format::FloatFormat;
 use crate::util::cow_dyn::CowDyn;
 use crate::util::radix::Radix;
+use crate::mode::calculation::{AngleMode, RoundingMode, WordSize};
 use crate::expr::Expr;
 use crate::parsing::operator::Precedence;
+use crate::util::prism::Prism;
+
+use serde::{Serialize, Deserialize};
+
+use std::io;
 
 /// A language mode provides a mechanism to convert Exprs into HTML
 /// code for display within the frontend.
@@ -15,7 +24,16 @@ use crate::parsing::operator::Precedence;
 /// into Exprs.
 pub trait LanguageMode {
   fn write_to_html(&self, engine: &LanguageModeEngine, out: &mut String, expr: &Expr, prec: Precedence);
-  fn parse(&self, text: &str) -> anyhow::Result<Expr>;
+
+  /// Parses the text according to the conventions of the given
+  /// locale.
+  fn parse_localized(&self, text: &str, locale: &Locale) -> anyhow::Result<Expr>;
+
+  /// Parses the text according to the conventions of the default
+  /// locale.
+  fn parse(&self, text: &str) -> anyhow::Result<Expr> {
+    self.parse_localized(text, &Locale::default())
+  }
 
   /// Converts `self` into a `dyn LanguageMode`. The implementation of
   /// this method should _always_ be
@@ -41,6 +59,26 @@ pub trait LanguageMode {
   /// it might be simpler than `self`.
   fn to_reversible_language_mode(&self) -> CowDyn<dyn LanguageMode>;
 
+  /// As [`LanguageMode::write_to_html`], but writes the output to an
+  /// I/O stream as it is produced. Every individual write to `out` is
+  /// a complete HTML fragment, never splitting a tag or an entity.
+  ///
+  /// The default implementation renders the whole expression with
+  /// `write_to_html` and writes it in one piece. Language modes which
+  /// expect to be used on very large expressions should override this
+  /// to avoid holding the entire output in memory.
+  fn write_incrementally(
+    &self,
+    engine: &LanguageModeEngine,
+    out: &mut dyn io::Write,
+    expr: &Expr,
+    prec: Precedence,
+  ) -> io::Result<()> {
+    let mut html = String::new();
+    self.write_to_html(engine, &mut html, expr, prec);
+    out.write_all(html.as_bytes())
+  }
+
   fn to_html(&self, expr: &Expr, language_settings: &LanguageSettings) -> String {
     let engine = LanguageModeEngine { data: self.to_trait_object(), language_settings };
 
@@ -49,6 +87,12 @@ pub trait LanguageMode {
     out
   }
 
+  /// As [`LanguageMode::to_html`], but streams the output to `out`.
+  fn to_writer(&self, expr: &Expr, language_settings: &LanguageSettings, out: &mut dyn io::Write) -> io::Result<()> {
+    let engine = LanguageModeEngine { data: self.to_trait_object(), language_settings };
+    self.write_incrementally(&engine, out, expr, Precedence::MIN)
+  }
+
   /// A short, user-friendly name for this language mode.
   fn language_mode_name(&self) -> String;
 }
@@ -64,7 +108,8 @@ pub struct LanguageModeEngine<'a, 'b> {
   language_settings: &'b LanguageSettings,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LanguageSettings {
   /// The preferred radix for outputting real numbers.
   pub preferred_radix: Radix,
@@ -74,6 +119,57 @@ pub struct LanguageSettings {
   /// an ASCII name or its Unicode equivalent(s), regardless of this
   /// flag.
   pub prefers_unicode_output: bool,
+  /// The locale used to write numbers and argument separators.
+  pub locale: Locale,
+  /// If present, complex number literals are written in polar
+  /// notation (`r ∠ θ`) rather than as rectangular pairs, with the
+  /// angle written in the given unit.
+  pub polar_angle_mode: Option<AngleMode>,
+  /// If true, every operator expression nested inside another is
+  /// wrapped in parentheses, even when operator precedence would make
+  /// the parentheses redundant.
+  pub always_parenthesize: bool,
+  /// The notation used to write inexact numbers in decimal.
+  pub float_format: FloatFormat,
+  /// The rounding mode used when the float format discards digits.
+  /// This mirrors [`CalculationMode::rounding_mode`] and is kept in
+  /// sync with it by the `set_rounding_mode` command.
+  ///
+  /// [`CalculationMode::rounding_mode`]: crate::mode::calculation::CalculationMode::rounding_mode
+  pub rounding_mode: RoundingMode,
+  /// If true, integers written in a radix which is a power of two
+  /// (such as binary or hexadecimal) are written as their
+  /// fixed-width two's complement bit patterns in [`word_size`],
+  /// when there is one.
+  ///
+  /// [`word_size`]: LanguageSettings::word_size
+  pub twos_complement: bool,
+  /// The word size for two's complement output. This mirrors
+  /// [`CalculationMode::word_size`] and is kept in sync with it by
+  /// the `set_word_size` command.
+  ///
+  /// [`CalculationMode::word_size`]: crate::mode::calculation::CalculationMode::word_size
+  pub word_size: Option<WordSize>,
+  /// If present, vectors, sets, and matrices with more than this many
+  /// elements (or rows or columns, in the case of matrices) are
+  /// abbreviated in the output, with an elision marker standing in
+  /// for the elements in the middle. Reversible output is never
+  /// abbreviated, since the parser has no way to read it back.
+  pub max_display_elements: Option<usize>,
+}
+
+/// Prism which parses the maximum number of elements to display in a
+/// composite value. The string `0` removes the limit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToDisplayLimit;
+
+/// A single item of a sequence abbreviated by
+/// [`LanguageModeEngine::elide`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Elided<T> {
+  Item(T),
+  /// Stands in for one or more omitted items.
+  Elision,
 }
 
 impl<'a, 'b> LanguageModeEngine<'a, 'b> {
@@ -81,9 +177,60 @@ impl<'a, 'b> LanguageModeEngine<'a, 'b> {
     self.data.write_to_html(self, out, expr, prec);
   }
 
+  pub fn write_incrementally(&self, out: &mut dyn io::Write, expr: &Expr, prec: Precedence) -> io::Result<()> {
+    self.data.write_incrementally(self, out, expr, prec)
+  }
+
   pub fn language_settings(&self) -> &LanguageSettings {
     self.language_settings
   }
+
+  /// Whether an operator of precedence `op_prec`, written in a
+  /// context of precedence `prec`, must be wrapped in parentheses.
+  /// This respects the [`LanguageSettings::always_parenthesize`]
+  /// flag, so language modes should prefer this over comparing
+  /// precedences directly.
+  pub fn needs_parens(&self, prec: Precedence, op_prec: Precedence) -> bool {
+    prec > op_prec || (self.language_settings.always_parenthesize && prec > Precedence::MIN)
+  }
+
+  /// The separator, including trailing whitespace, which should be
+  /// written between arguments to functions and elements of vectors.
+  pub fn argument_separator(&self) -> String {
+    format!("{} ", self.language_settings.locale.argument_separator())
+  }
+
+  /// Abbreviates the sequence according to
+  /// [`LanguageSettings::max_display_elements`]. If the sequence is
+  /// too long, the result keeps as many leading elements as will fit,
+  /// followed by an elision and the final element.
+  pub fn elide<'c, T>(&self, elems: &'c [T]) -> Vec<Elided<&'c T>> {
+    match self.language_settings.max_display_elements {
+      Some(max) if elems.len() > max => {
+        let mut result: Vec<_> = elems[..max.saturating_sub(1)].iter().map(Elided::Item).collect();
+        result.push(Elided::Elision);
+        result.push(Elided::Item(&elems[elems.len() - 1]));
+        result
+      }
+      _ => elems.iter().map(Elided::Item).collect(),
+    }
+  }
+
+  /// Writes the marker which stands in for omitted elements of a
+  /// sequence.
+  pub fn write_elision_marker(&self, out: &mut String) {
+    let marker = if self.language_settings.prefers_unicode_output { "…" } else { "..." };
+    write_elision_span(out, marker);
+  }
+}
+
+impl LanguageSettings {
+  pub const DEFAULT_MAX_DISPLAY_ELEMENTS: usize = 100;
+
+  /// A copy of these settings which never abbreviates output.
+  pub fn without_elision(&self) -> Self {
+    LanguageSettings { max_display_elements: None, ..self.clone() }
+  }
 }
 
 impl Default for LanguageSettings {
@@ -91,6 +238,14 @@ impl Default for LanguageSettings {
     LanguageSettings {
       preferred_radix: Radix::DECIMAL,
       prefers_unicode_output: true,
+      locale: Locale::default(),
+      polar_angle_mode: None,
+      always_parenthesize: false,
+      float_format: FloatFormat::default(),
+      rounding_mode: RoundingMode::default(),
+      twos_complement: false,
+      word_size: None,
+      max_display_elements: Some(LanguageSettings::DEFAULT_MAX_DISPLAY_ELEMENTS),
     }
   }
 }
@@ -101,6 +256,20 @@ impl<'a, T: LanguageMode + ?Sized> LanguageMode for &'a T {
     (**self).write_to_html(engine, out, expr, prec);
   }
 
+  fn write_incrementally(
+    &self,
+    engine: &LanguageModeEngine,
+    out: &mut dyn io::Write,
+    expr: &Expr,
+    prec: Precedence,
+  ) -> io::Result<()> {
+    (**self).write_incrementally(engine, out, expr, prec)
+  }
+
+  fn parse_localized(&self, text: &str, locale: &Locale) -> anyhow::Result<Expr> {
+    (**self).parse_localized(text, locale)
+  }
+
   fn parse(&self, text: &str) -> anyhow::Result<Expr> {
     (**self).parse(text)
   }
@@ -123,6 +292,20 @@ impl<T: LanguageMode + ?Sized> LanguageMode for Box<T> {
     (**self).write_to_html(engine, out, expr, prec);
   }
 
+  fn write_incrementally(
+    &self,
+    engine: &LanguageModeEngine,
+    out: &mut dyn io::Write,
+    expr: &Expr,
+    prec: Precedence,
+  ) -> io::Result<()> {
+    (**self).write_incrementally(engine, out, expr, prec)
+  }
+
+  fn parse_localized(&self, text: &str, locale: &Locale) -> anyhow::Result<Expr> {
+    (**self).parse_localized(text, locale)
+  }
+
   fn parse(&self, text: &str) -> anyhow::Result<Expr> {
     (**self).parse(text)
   }
@@ -140,6 +323,28 @@ impl<T: LanguageMode + ?Sized> LanguageMode for Box<T> {
   }
 }
 
+impl Prism<String, Option<usize>> for StringToDisplayLimit {
+  fn narrow_type(&self, input: String) -> Result<Option<usize>, String> {
+    match input.trim().parse::<usize>() {
+      Ok(0) => Ok(None),
+      Ok(n) => Ok(Some(n)),
+      Err(_) => Err(input),
+    }
+  }
+
+  fn widen_type(&self, limit: Option<usize>) -> String {
+    limit.unwrap_or(0).to_string()
+  }
+}
+
+/// Writes the given elision marker, wrapped in a span which the
+/// frontend recognizes as a request to show the full value.
+pub fn write_elision_span(out: &mut String, marker: &str) {
+  out.push_str(r#"<span class="elision">"#);
+  out.push_str(marker);
+  out.push_str("</span>");
+}
+
 /// Helper function to output a list of values, separated by a chosen
 /// delimiter.
 pub fn output_sep_by<T, I, F>(
@@ -0,0 +1,226 @@
+
+//! Locale-specific conventions for reading and writing numbers.
+
+use crate::util::prism::Prism;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A set of conventions for reading and writing numerical literals.
+///
+/// Locales only affect the decimal separator and the grouping of
+/// digits in the integer part of a number. When the decimal separator
+/// is a comma, the argument separator (used in function calls and
+/// vectors) becomes a semicolon, so that the two are never ambiguous.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Locale {
+  name: &'static str,
+  decimal_separator: char,
+  group_separator: Option<char>,
+}
+
+/// Prism which parses a string as a [`Locale`] name.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringToLocale;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("Unknown locale '{name}'")]
+pub struct UnknownLocaleError {
+  name: String,
+}
+
+/// The locales known to the engine, in the order they should be
+/// presented to the user.
+pub const KNOWN_LOCALES: [Locale; 4] = [
+  Locale::DEFAULT,
+  Locale { name: "en", decimal_separator: '.', group_separator: Some(',') },
+  Locale { name: "de", decimal_separator: ',', group_separator: Some('.') },
+  Locale { name: "fr", decimal_separator: ',', group_separator: Some('\u{202F}') },
+];
+
+impl Locale {
+  /// The default locale. Uses a decimal point and never groups
+  /// digits.
+  pub const DEFAULT: Locale = Locale { name: "default", decimal_separator: '.', group_separator: None };
+
+  pub fn name(&self) -> &'static str {
+    self.name
+  }
+
+  pub fn decimal_separator(&self) -> char {
+    self.decimal_separator
+  }
+
+  pub fn group_separator(&self) -> Option<char> {
+    self.group_separator
+  }
+
+  /// The character used to separate arguments to functions and
+  /// elements of vectors.
+  pub fn argument_separator(&self) -> char {
+    if self.decimal_separator == ',' { ';' } else { ',' }
+  }
+
+  /// Rewrites a number, written in the canonical (default locale)
+  /// form, according to this locale's conventions. If `use_grouping`
+  /// is false, digit groups are never separated, which guarantees
+  /// that the output can be read back in by the parser.
+  ///
+  /// Only runs of digits which begin a number (i.e. which are not
+  /// preceded by a decimal point or exponent marker) are grouped.
+  pub fn localize_number(&self, canonical: &str, use_grouping: bool) -> String {
+    let group_separator = self.group_separator.filter(|_| use_grouping);
+    let chars: Vec<char> = canonical.chars().collect();
+    let mut out = String::with_capacity(canonical.len());
+    let mut i = 0;
+    while i < chars.len() {
+      let ch = chars[i];
+      if ch.is_ascii_digit() {
+        let run_end = chars[i..].iter().position(|c| !c.is_ascii_digit()).map_or(chars.len(), |n| i + n);
+        let is_integer_part = !matches!(preceding_marker(&chars[..i]), Some('.' | 'e' | 'E'));
+        for (j, digit) in chars[i..run_end].iter().enumerate() {
+          let remaining = run_end - i - j;
+          if j > 0 && remaining % 3 == 0 && is_integer_part {
+            if let Some(sep) = group_separator {
+              out.push(sep);
+            }
+          }
+          out.push(*digit);
+        }
+        i = run_end;
+      } else {
+        out.push(if ch == '.' { self.decimal_separator } else { ch });
+        i += 1;
+      }
+    }
+    out
+  }
+
+  /// Rewrites a number literal, written according to this locale's
+  /// conventions without digit grouping, into the canonical form
+  /// understood by [`Number::from_str`](crate::expr::number::Number).
+  pub fn delocalize_number(&self, localized: &str) -> String {
+    localized.chars().map(|ch| if ch == self.decimal_separator { '.' } else { ch }).collect()
+  }
+}
+
+/// Returns the non-digit character immediately before the final run
+/// of digits in `chars`, skipping over an exponent sign if present.
+fn preceding_marker(chars: &[char]) -> Option<char> {
+  match chars.last() {
+    Some('+' | '-') if chars.len() >= 2 && matches!(chars[chars.len() - 2], 'e' | 'E') => Some('e'),
+    other => other.copied(),
+  }
+}
+
+impl Default for Locale {
+  fn default() -> Self {
+    Locale::DEFAULT
+  }
+}
+
+impl FromStr for Locale {
+  type Err = UnknownLocaleError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    KNOWN_LOCALES.iter()
+      .find(|locale| locale.name == s)
+      .cloned()
+      .ok_or_else(|| UnknownLocaleError { name: s.to_owned() })
+  }
+}
+
+impl TryFrom<String> for Locale {
+  type Error = UnknownLocaleError;
+
+  fn try_from(name: String) -> Result<Self, Self::Error> {
+    name.parse()
+  }
+}
+
+impl From<Locale> for String {
+  fn from(locale: Locale) -> Self {
+    locale.name.to_owned()
+  }
+}
+
+impl Display for Locale {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name)
+  }
+}
+
+impl Prism<String, Locale> for StringToLocale {
+  fn narrow_type(&self, input: String) -> Result<Locale, String> {
+    Locale::from_str(&input).map_err(|_| input)
+  }
+
+  fn widen_type(&self, locale: Locale) -> String {
+    locale.name.to_owned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn locale(name: &str) -> Locale {
+    Locale::from_str(name).unwrap()
+  }
+
+  #[test]
+  fn test_from_str() {
+    assert_eq!(locale("default"), Locale::DEFAULT);
+    assert_eq!(locale("de").decimal_separator(), ',');
+    assert_eq!(Locale::from_str("xx"), Err(UnknownLocaleError { name: String::from("xx") }));
+  }
+
+  #[test]
+  fn test_argument_separator() {
+    assert_eq!(locale("default").argument_separator(), ',');
+    assert_eq!(locale("en").argument_separator(), ',');
+    assert_eq!(locale("de").argument_separator(), ';');
+  }
+
+  #[test]
+  fn test_localize_number_default() {
+    assert_eq!(Locale::DEFAULT.localize_number("-1234567.125", true), "-1234567.125");
+  }
+
+  #[test]
+  fn test_localize_number_with_grouping() {
+    assert_eq!(locale("en").localize_number("1234567.12345", true), "1,234,567.12345");
+    assert_eq!(locale("de").localize_number("-1234567.5", true), "-1.234.567,5");
+    assert_eq!(locale("de").localize_number("123", true), "123");
+    assert_eq!(locale("de").localize_number("1000:1001", true), "1.000:1.001");
+    assert_eq!(locale("fr").localize_number("1234.5", true), "1\u{202F}234,5");
+  }
+
+  #[test]
+  fn test_localize_number_does_not_group_exponent() {
+    assert_eq!(locale("en").localize_number("1.5e-12345", true), "1.5e-12345");
+    assert_eq!(locale("de").localize_number("1234.5e1234", true), "1.234,5e1234");
+  }
+
+  #[test]
+  fn test_localize_number_without_grouping() {
+    assert_eq!(locale("de").localize_number("-1234567.5", false), "-1234567,5");
+  }
+
+  #[test]
+  fn test_delocalize_number() {
+    assert_eq!(locale("de").delocalize_number("1234,5"), "1234.5");
+    assert_eq!(locale("en").delocalize_number("1234.5"), "1234.5");
+  }
+
+  #[test]
+  fn test_string_to_locale_prism() {
+    assert_eq!(StringToLocale.narrow_type(String::from("fr")), Ok(locale("fr")));
+    assert_eq!(StringToLocale.narrow_type(String::from("zz")), Err(String::from("zz")));
+    assert_eq!(StringToLocale.widen_type(locale("en")), "en");
+  }
+}
@@ -1,20 +1,30 @@
 
+pub mod document;
+pub mod export;
+pub mod float_format;
 pub mod language;
+pub mod locale;
+pub mod spoken;
 pub mod unicode;
 
 use crate::expr::Expr;
 use language::{LanguageMode, LanguageSettings};
 use language::basic::BasicLanguageMode;
 use language::graphics::GraphicsLanguageMode;
+use language::localized::LocalizedLanguageMode;
 
 use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct DisplaySettings {
   /// The current language mode. We store this as an [`Arc`] rather
   /// than a simple [`Box`] so that the language mode can be cheaply
   /// copied onto the undo stack.
   pub base_language_mode: Arc<dyn LanguageMode + Send + Sync>,
   pub is_graphics_enabled: bool,
+  /// Whether or not to send an English rendering of each stack
+  /// element (for screen readers) alongside the HTML.
+  pub is_spoken_text_enabled: bool,
   pub language_settings: LanguageSettings,
 }
 
@@ -23,16 +33,20 @@ impl DisplaySettings {
     DisplaySettings {
       base_language_mode: Arc::new(language_mode),
       is_graphics_enabled: true,
+      is_spoken_text_enabled: false,
       language_settings,
     }
   }
 
+  /// The language mode used for displaying and parsing values.
+  /// Input is parsed according to the current locale.
   pub fn language_mode(&self) -> Box<dyn LanguageMode + '_> {
     let base_language_mode = self.base_language_mode.as_ref();
+    let locale = self.language_settings.locale.clone();
     if self.is_graphics_enabled {
-      Box::new(GraphicsLanguageMode::new(base_language_mode))
+      Box::new(LocalizedLanguageMode::new(GraphicsLanguageMode::new(base_language_mode), locale))
     } else {
-      Box::new(base_language_mode)
+      Box::new(LocalizedLanguageMode::new(base_language_mode, locale))
     }
   }
 }
@@ -42,6 +56,13 @@ impl DisplaySettings {
     let language_mode = self.language_mode();
     language_mode.to_html(expr, &self.language_settings)
   }
+  /// As [`DisplaySettings::to_html`], but never abbreviates long
+  /// vectors or matrices, regardless of
+  /// [`LanguageSettings::max_display_elements`].
+  pub fn to_full_html(&self, expr: &Expr) -> String {
+    let language_mode = self.language_mode();
+    language_mode.to_html(expr, &self.language_settings.without_elision())
+  }
   pub fn to_html_for_parsing(&self, expr: &Expr) -> String {
     let language_mode = self.language_mode();
     let language_mode = language_mode.to_reversible_language_mode();
@@ -0,0 +1,348 @@
+
+//! Rendering of expressions as unambiguous English text, for use by
+//! screen readers.
+//!
+//! Subexpressions which would require parentheses in written form
+//! are introduced with "the quantity" and terminated with a comma,
+//! so that "(x + 1) ^ 2 / 2" is read as "the quantity x plus one,
+//! squared, over two".
+
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::{Number, ComplexNumber};
+use crate::expr::vector::Vector;
+use crate::expr::set::Set;
+use crate::expr::tuple::Tuple;
+use crate::parsing::operator::{OperatorTable, Precedence};
+use crate::parsing::operator::fixity::FixityType;
+
+use once_cell::sync::Lazy;
+
+static OPERATORS: Lazy<OperatorTable> = Lazy::new(OperatorTable::common_operators);
+
+const SMALL_NUMBER_WORDS: [&str; 21] = [
+  "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+  "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen",
+  "nineteen", "twenty",
+];
+
+/// Renders the expression as English text.
+pub fn to_spoken_text(expr: &Expr) -> String {
+  let text = speak(expr, Precedence::MIN);
+  text.strip_suffix(',').map(str::to_owned).unwrap_or(text)
+}
+
+fn speak(expr: &Expr, prec: Precedence) -> String {
+  match expr {
+    Expr::Atom(Atom::Number(n)) => speak_number(n),
+    Expr::Atom(Atom::Var(v)) => v.as_str().to_owned(),
+    Expr::Atom(Atom::String(s)) => format!("the string \"{s}\""),
+    Expr::Call(f, args) => speak_call(f, args, prec),
+  }
+}
+
+fn speak_number(number: &Number) -> String {
+  let text = number.to_string();
+  let (sign, magnitude) = match text.strip_prefix('-') {
+    Some(magnitude) => ("negative ", magnitude),
+    None => ("", text.as_str()),
+  };
+  let magnitude = match magnitude.parse::<usize>() {
+    Ok(n) if n < SMALL_NUMBER_WORDS.len() => SMALL_NUMBER_WORDS[n].to_owned(),
+    _ => {
+      magnitude
+        .replace('.', " point ")
+        .replace(':', " over ")
+        .replace(['e', 'E'], " times ten to the power ")
+    }
+  };
+  format!("{sign}{magnitude}")
+}
+
+fn speak_call(f: &str, args: &[Expr], prec: Precedence) -> String {
+  if f == Vector::FUNCTION_NAME {
+    return speak_vector(args);
+  }
+  if f == Set::FUNCTION_NAME {
+    return speak_set(args);
+  }
+  if f == Tuple::FUNCTION_NAME {
+    return speak_tuple(args);
+  }
+  if f == ComplexNumber::FUNCTION_NAME && args.len() == 2 {
+    let real = speak(&args[0], Precedence::MAX);
+    let imag = speak(&args[1], Precedence::MAX);
+    return format!("the complex number {real} plus {imag} i");
+  }
+  match args {
+    [base, exponent] if f == "^" => {
+      if let Some(power_word) = power_word(exponent) {
+        let base = speak_operand(base, Precedence::MAX);
+        return format!("{base} {power_word}");
+      }
+      speak_infix(f, args, prec).unwrap_or_else(|| speak_function(f, args))
+    }
+    [arg] => speak_prefix(f, arg, prec).unwrap_or_else(|| speak_function(f, args)),
+    _ => speak_infix(f, args, prec).unwrap_or_else(|| speak_function(f, args)),
+  }
+}
+
+fn speak_infix(f: &str, args: &[Expr], prec: Precedence) -> Option<String> {
+  let op = OPERATORS.get_by_function_name(f, FixityType::Infix)?;
+  let infix_props = op.fixity().as_infix()?;
+  if args.len() < 2 || (args.len() > 2 && !infix_props.associativity().is_fully_assoc()) {
+    return None;
+  }
+  let word = infix_word(f);
+  let mut text = String::new();
+  for (i, arg) in args.iter().enumerate() {
+    let arg_prec = if args.len() > 2 {
+      infix_props.precedence()
+    } else if i == 0 {
+      infix_props.left_precedence()
+    } else {
+      infix_props.right_precedence()
+    };
+    if i > 0 {
+      text.push(' ');
+      text.push_str(word);
+      text.push(' ');
+    }
+    let arg_text = speak(arg, arg_prec);
+    let is_last = i == args.len() - 1;
+    if !is_last && is_compound(arg) && !arg_text.ends_with(',') {
+      text.push_str(&arg_text);
+      text.push(',');
+    } else {
+      text.push_str(&arg_text);
+    }
+  }
+  Some(group_if(text, infix_props.precedence() < prec))
+}
+
+fn speak_prefix(f: &str, arg: &Expr, prec: Precedence) -> Option<String> {
+  let op = OPERATORS.get_by_function_name(f, FixityType::Prefix)?;
+  let prefix_props = op.fixity().as_prefix()?;
+  let word = match f {
+    "negate" => "negative",
+    "identity" => "plus",
+    _ => op.operator_name(),
+  };
+  let text = format!("{word} {}", speak(arg, prefix_props.precedence()));
+  Some(group_if(text, prefix_props.precedence() < prec))
+}
+
+fn speak_function(f: &str, args: &[Expr]) -> String {
+  let name = function_phrase(f);
+  let args: Vec<_> = args.iter().map(|arg| speak_operand(arg, Precedence::MAX)).collect();
+  match args.as_slice() {
+    [] => format!("{name} of nothing"),
+    [arg] => format!("{name} of {arg}"),
+    args => format!("{name} of {}", join_with_and(args)),
+  }
+}
+
+fn speak_vector(args: &[Expr]) -> String {
+  if args.is_empty() {
+    return String::from("the empty vector");
+  }
+  let args: Vec<_> = args.iter().map(|arg| speak_operand(arg, Precedence::MAX)).collect();
+  format!("the vector {}, end vector", join_with_and(&args))
+}
+
+fn speak_set(args: &[Expr]) -> String {
+  if args.is_empty() {
+    return String::from("the empty set");
+  }
+  let args: Vec<_> = args.iter().map(|arg| speak_operand(arg, Precedence::MAX)).collect();
+  format!("the set of {}, end set", join_with_and(&args))
+}
+
+fn speak_tuple(args: &[Expr]) -> String {
+  if args.is_empty() {
+    return String::from("the empty tuple");
+  }
+  let args: Vec<_> = args.iter().map(|arg| speak_operand(arg, Precedence::MAX)).collect();
+  format!("the tuple {}, end tuple", join_with_and(&args))
+}
+
+/// Speaks the expression, grouping it with "the quantity" if it is
+/// anything more complicated than a single atom or function call.
+fn speak_operand(expr: &Expr, prec: Precedence) -> String {
+  let text = speak(expr, prec);
+  if is_compound(expr) && !text.starts_with("the quantity ") && !is_function_phrase(expr) {
+    format!("the quantity {text},")
+  } else {
+    text
+  }
+}
+
+fn group_if(text: String, cond: bool) -> String {
+  if cond {
+    format!("the quantity {text},")
+  } else {
+    text
+  }
+}
+
+fn is_compound(expr: &Expr) -> bool {
+  matches!(expr, Expr::Call(_, _)) && !matches!(expr, Expr::Call(f, _) if f == Vector::FUNCTION_NAME || f == Set::FUNCTION_NAME || f == Tuple::FUNCTION_NAME)
+}
+
+fn is_function_phrase(expr: &Expr) -> bool {
+  let Expr::Call(f, args) = expr else {
+    return false;
+  };
+  let is_operator = match args.len() {
+    1 => OPERATORS.get_by_function_name(f, FixityType::Prefix).is_some(),
+    _ => OPERATORS.get_by_function_name(f, FixityType::Infix).is_some(),
+  };
+  !is_operator && f != ComplexNumber::FUNCTION_NAME
+}
+
+fn power_word(exponent: &Expr) -> Option<&'static str> {
+  match exponent {
+    Expr::Atom(Atom::Number(n)) if *n == Number::from(2) => Some("squared"),
+    Expr::Atom(Atom::Number(n)) if *n == Number::from(3) => Some("cubed"),
+    _ => None,
+  }
+}
+
+fn infix_word(f: &str) -> &str {
+  match f {
+    "+" => "plus",
+    "-" => "minus",
+    "*" => "times",
+    "/" => "over",
+    "%" => "mod",
+    "^" => "to the power",
+    "@" => "matrix times",
+    "<" => "is less than",
+    ">" => "is greater than",
+    "=" => "equals",
+    "!=" => "is not equal to",
+    "<=" => "is at most",
+    ">=" => "is at least",
+    "&&" => "and",
+    "||" => "or",
+    ".." => "to",
+    _ => f,
+  }
+}
+
+fn function_phrase(f: &str) -> &str {
+  match f {
+    "sqrt" => "the square root",
+    "abs" => "the absolute value",
+    "ln" => "the natural log",
+    "log" => "the log",
+    "exp" => "the exponential",
+    "not" => "the negation",
+    "det" => "the determinant",
+    _ => f,
+  }
+}
+
+fn join_with_and(items: &[String]) -> String {
+  match items {
+    [] => String::new(),
+    [a] => a.to_owned(),
+    [a, b] => format!("{a} and {b}"),
+    [init @ .., last] => format!("{}, and {last}", init.join(", ")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> Expr {
+    Expr::var(name).unwrap()
+  }
+
+  #[test]
+  fn test_atoms() {
+    assert_eq!(to_spoken_text(&Expr::from(7)), "seven");
+    assert_eq!(to_spoken_text(&Expr::from(-3)), "negative three");
+    assert_eq!(to_spoken_text(&Expr::from(1234)), "1234");
+    assert_eq!(to_spoken_text(&Expr::from(Number::from(1.5))), "1 point 5");
+    assert_eq!(to_spoken_text(&var("x")), "x");
+    assert_eq!(to_spoken_text(&Expr::string("hi")), "the string \"hi\"");
+  }
+
+  #[test]
+  fn test_squared_quantity_over_two() {
+    let expr = Expr::call("/", vec![
+      Expr::call("^", vec![
+        Expr::call("+", vec![var("x"), Expr::from(1)]),
+        Expr::from(2),
+      ]),
+      Expr::from(2),
+    ]);
+    assert_eq!(to_spoken_text(&expr), "the quantity x plus one, squared, over two");
+  }
+
+  #[test]
+  fn test_grouping_on_right() {
+    let expr = Expr::call("*", vec![
+      var("a"),
+      Expr::call("-", vec![var("b"), var("c")]),
+    ]);
+    assert_eq!(to_spoken_text(&expr), "a times the quantity b minus c");
+  }
+
+  #[test]
+  fn test_no_grouping_for_higher_precedence() {
+    let expr = Expr::call("+", vec![
+      Expr::call("*", vec![var("a"), var("b")]),
+      var("c"),
+    ]);
+    assert_eq!(to_spoken_text(&expr), "a times b, plus c");
+  }
+
+  #[test]
+  fn test_general_power() {
+    let expr = Expr::call("^", vec![var("x"), var("n")]);
+    assert_eq!(to_spoken_text(&expr), "x to the power n");
+  }
+
+  #[test]
+  fn test_negation() {
+    let expr = Expr::call("negate", vec![var("x")]);
+    assert_eq!(to_spoken_text(&expr), "negative x");
+  }
+
+  #[test]
+  fn test_function_calls() {
+    let expr = Expr::call("sqrt", vec![Expr::call("+", vec![var("x"), Expr::from(1)])]);
+    assert_eq!(to_spoken_text(&expr), "the square root of the quantity x plus one");
+    let expr = Expr::call("f", vec![var("a"), var("b"), Expr::call("sin", vec![var("c")])]);
+    assert_eq!(to_spoken_text(&expr), "f of a, b, and sin of c");
+  }
+
+  #[test]
+  fn test_vectors() {
+    assert_eq!(to_spoken_text(&Expr::call("vector", vec![])), "the empty vector");
+    let expr = Expr::call("vector", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(to_spoken_text(&expr), "the vector one and two, end vector");
+  }
+
+  #[test]
+  fn test_sets() {
+    assert_eq!(to_spoken_text(&Expr::call("set", vec![])), "the empty set");
+    let expr = Expr::call("set", vec![var("x"), var("y")]);
+    assert_eq!(to_spoken_text(&expr), "the set of x and y, end set");
+  }
+
+  #[test]
+  fn test_tuples() {
+    let expr = Expr::call("tuple", vec![Expr::from(3), Expr::from(1)]);
+    assert_eq!(to_spoken_text(&expr), "the tuple three and one, end tuple");
+  }
+
+  #[test]
+  fn test_complex_number() {
+    let expr = Expr::call("complex", vec![Expr::from(1), Expr::from(2)]);
+    assert_eq!(to_spoken_text(&expr), "the complex number one plus two i");
+  }
+}
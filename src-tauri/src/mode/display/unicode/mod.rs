@@ -12,6 +12,9 @@ pub fn common_unicode_aliases() -> UnicodeAliasTable {
     UnicodeAlias::simple("!=", "≠"),
     UnicodeAlias::simple("inf", "∞"),
     UnicodeAlias::simple("uinf", "⧝"),
+    UnicodeAlias::simple("eps", "ε"),
+    UnicodeAlias::simple("slot", "□"),
+    UnicodeAlias::simple("angle", "∠"),
   ]).unwrap()
 }
 
@@ -36,6 +36,22 @@ pub const INTERVAL_PRECEDENCE: Precedence = Precedence::new(197);
 /// some language modes.
 pub const DIVISION_PRECEDENCE: Precedence = Precedence::new(190);
 
+/// Precedence used for the addition and subtraction infix operators.
+/// Like [`EXPONENT_PRECEDENCE`], these are needed by language modes
+/// which write certain literals (such as quaternions) as sums.
+pub const ADDITION_PRECEDENCE: Precedence = Precedence::new(180);
+
+/// Precedence used for the `mod` infix operator, which constructs a
+/// modular integer. This binds more loosely than arithmetic, so that
+/// `a + b mod m` reduces the whole sum, and is rendered specially by
+/// some language modes.
+pub const MODULO_PRECEDENCE: Precedence = Precedence::new(170);
+
+/// Precedence used for the polar complex literal infix operator
+/// (`r ∠ θ`). This binds more tightly than multiplication and
+/// negation, so that `-2 ∠ 30` negates the whole complex number.
+pub const POLAR_PRECEDENCE: Precedence = Precedence::new(198);
+
 /// Precedence level of prefix function calls. If a language mode
 /// decides to write a function call such as `sin(x)` as though it was
 /// a prefix operator (i.e. `sin x`), this is the precedence of that
@@ -100,24 +116,29 @@ impl OperatorTable {
       Operator::new("/", Fixity::new().with_infix("/", Associativity::LEFT, DIVISION_PRECEDENCE)),
       Operator::new("%", Fixity::new().with_infix("%", Associativity::NONE, Precedence::new(190))),
       Operator::new("+", Fixity::new()
-                           .with_infix("+", Associativity::FULL, Precedence::new(180))
+                           .with_infix("+", Associativity::FULL, ADDITION_PRECEDENCE)
                            .with_prefix("identity", Precedence::new(197))),
       Operator::new("-", Fixity::new()
-                           .with_infix("-", Associativity::LEFT, Precedence::new(180))
+                           .with_infix("-", Associativity::LEFT, ADDITION_PRECEDENCE)
                            .with_prefix("negate", Precedence::new(197))),
+      Operator::new("mod", Fixity::new().with_infix("modulo", Associativity::NONE, MODULO_PRECEDENCE)),
       Operator::new("<", Fixity::new().with_infix("<", Associativity::NONE, Precedence::new(160))),
       Operator::new(">", Fixity::new().with_infix(">", Associativity::NONE, Precedence::new(160))),
       Operator::new("=", Fixity::new().with_infix("=", Associativity::NONE, Precedence::new(160))),
       Operator::new("!=", Fixity::new().with_infix("!=", Associativity::NONE, Precedence::new(160))),
       Operator::new("<=", Fixity::new().with_infix("<=", Associativity::NONE, Precedence::new(160))),
       Operator::new(">=", Fixity::new().with_infix(">=", Associativity::NONE, Precedence::new(160))),
+      Operator::new("angle", Fixity::new().with_infix("angle", Associativity::NONE, POLAR_PRECEDENCE)),
+      Operator::new("!", Fixity::new().with_prefix("not", Precedence::new(120))),
       Operator::new("&&", Fixity::new().with_infix("&&", Associativity::FULL, Precedence::new(110))),
       Operator::new("||", Fixity::new().with_infix("||", Associativity::FULL, Precedence::new(100))),
+      Operator::new(":=", Fixity::new().with_infix(":=", Associativity::NONE, Precedence::new(90))), // Rewrite rule
 
       // Also admit Unicode equivalents to several operators.
       Operator::new("≠", Fixity::new().with_infix("≠", Associativity::NONE, Precedence::new(160))),
       Operator::new("≤", Fixity::new().with_infix("≤", Associativity::NONE, Precedence::new(160))),
       Operator::new("≥", Fixity::new().with_infix("≥", Associativity::NONE, Precedence::new(160))),
+      Operator::new("∠", Fixity::new().with_infix("∠", Associativity::NONE, POLAR_PRECEDENCE)),
     ].into_iter().collect()
   }
 
@@ -7,13 +7,21 @@ mod mobile;
 use crate::command::CommandContext;
 use crate::command::options::CommandOptions;
 use crate::state::query::{Query, QueryContext};
-use crate::state::tauri_command::{self, handle_non_tauri_errors};
+use crate::state::stats::EngineStats;
+use crate::state::bookmarks::BookmarkPreview;
+use crate::state::tauri_command::{self, handle_non_tauri_errors, handle_non_tauri_errors_or};
 use crate::state::validation::{Validator, ValidationContext};
 use crate::state::{TauriApplicationState, UndoDirection};
+use crate::state::jobs;
 use crate::state::events::show_error;
+use crate::state::windows::WindowKind;
 use crate::graphics::payload::SerializedGraphicsPayload;
 use crate::graphics::response::GraphicsResponse;
-use crate::expr::simplifier::default_simplifier;
+use crate::expr::simplifier::profiled_default_simplifier;
+use crate::expr::simplifier::profile::PassTiming;
+use crate::units::currency::CurrencyUnitParser;
+
+use tauri::Manager;
 
 /// Main entry-point, called from the `fifi` binary crate on desktop
 /// platforms.
@@ -26,13 +34,31 @@ pub fn run_application() {
       run_math_command,
       render_graphics,
       get_editable_stack_elem,
+      show_full_stack_elem,
+      export_stack_elem,
       perform_undo_action,
+      cancel_computation,
       validate_stack_size,
       validate_value,
       query_stack,
+      get_engine_stats,
+      simplifier_profile,
+      reset_simplifier_profile,
+      open_window,
+      render_stack_document,
+      list_bookmarks,
     ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app_handle, event| {
+      if let tauri::RunEvent::Exit = event {
+        // The debounced writer may not have caught up with the last
+        // command, so write the final state before the process ends.
+        let app_state = app_handle.state::<TauriApplicationState>();
+        let state = app_state.lock_state();
+        app_state.save_session_now(&state);
+      }
+    });
 }
 
 #[tauri::command]
@@ -43,15 +69,34 @@ fn run_math_command(
   args: Vec<String>,
   opts: CommandOptions,
 ) -> Result<(), tauri::Error> {
-  let mut state = app_state.state.lock().expect("poisoned mutex");
+  let mut state = app_state.lock_state();
   let command_table = &app_state.command_table;
+  if command_table.get(command_name).is_ok_and(|command| command.runs_in_background()) {
+    return handle_non_tauri_errors(
+      &app_handle,
+      jobs::start_background_command(&state, &app_handle, &app_state.jobs, command_name, args, opts),
+    );
+  }
+  if let Err(err) = app_state.jobs.ensure_idle() {
+    return handle_non_tauri_errors(&app_handle, Err(err.into()));
+  }
+  let units_parser = CurrencyUnitParser::new(app_state.units_parser.as_ref(), state.currency_rates().clone());
   let command_context = CommandContext {
     opts,
-    simplifier: default_simplifier(&app_state.function_table),
-    units_parser: app_state.units_parser.as_ref(),
+    simplifier: profiled_default_simplifier(
+      &app_state.function_table,
+      state.user_functions().clone(),
+      state.rewrite_rules().clone(),
+      state.assumptions().clone(),
+      state.derivative_rules().clone(),
+      &app_state.simplifier_profile,
+    ),
+    units_parser: &units_parser,
     dispatch_table: command_table,
+    function_table: &app_state.function_table,
+    nesting_depth: 0,
   };
-  handle_non_tauri_errors(
+  let result = handle_non_tauri_errors(
     &app_handle,
     tauri_command::run_math_command(
       &mut state,
@@ -61,7 +106,9 @@ fn run_math_command(
       command_name,
       args,
     ),
-  )
+  );
+  app_state.save_session(&state);
+  result
 }
 
 #[tauri::command]
@@ -70,7 +117,7 @@ fn render_graphics(
   app_handle: tauri::AppHandle,
   payload: SerializedGraphicsPayload,
 ) -> Result<Option<GraphicsResponse>, tauri::Error> {
-  let mut state = app_state.state.lock().expect("poisoned mutex");
+  let mut state = app_state.lock_state();
   let function_table = &app_state.function_table;
   handle_non_tauri_errors(
     &app_handle,
@@ -89,7 +136,7 @@ fn get_editable_stack_elem(
   app_handle: tauri::AppHandle,
   stack_index: usize,
 ) -> Result<String, tauri::Error> {
-  let mut state = app_state.state.lock().expect("poisoned mutex");
+  let mut state = app_state.lock_state();
   match tauri_command::get_editable_stack_elem(&mut state, stack_index) {
     Ok(s) => Ok(s),
     Err(err) => {
@@ -99,17 +146,63 @@ fn get_editable_stack_elem(
   }
 }
 
+#[tauri::command]
+fn show_full_stack_elem(
+  app_state: tauri::State<TauriApplicationState>,
+  app_handle: tauri::AppHandle,
+  stack_index: usize,
+) -> Result<String, tauri::Error> {
+  let state = app_state.lock_state();
+  match tauri_command::show_full_stack_elem(&state, stack_index) {
+    Ok(s) => Ok(s),
+    Err(err) => {
+      show_error(&app_handle, format!("Error: {}", err))?;
+      Ok(String::from(""))
+    }
+  }
+}
+
+#[tauri::command]
+fn export_stack_elem(
+  app_state: tauri::State<TauriApplicationState>,
+  app_handle: tauri::AppHandle,
+  stack_index: usize,
+  format: String,
+) -> Result<String, tauri::Error> {
+  let mut state = app_state.lock_state();
+  match tauri_command::export_stack_elem(&mut state, stack_index, &format) {
+    Ok(s) => Ok(s),
+    Err(err) => {
+      show_error(&app_handle, format!("Error: {}", err))?;
+      Ok(String::from(""))
+    }
+  }
+}
+
 #[tauri::command]
 fn perform_undo_action(
   app_state: tauri::State<TauriApplicationState>,
   app_handle: tauri::AppHandle,
   direction: UndoDirection,
 ) -> Result<(), tauri::Error> {
-  let mut state = app_state.state.lock().expect("poisoned mutex");
-  handle_non_tauri_errors(
+  let mut state = app_state.lock_state();
+  if let Err(err) = app_state.jobs.ensure_idle() {
+    return handle_non_tauri_errors(&app_handle, Err(err.into()));
+  }
+  let result = handle_non_tauri_errors(
     &app_handle,
     tauri_command::perform_undo_action(&mut state, &app_handle, direction),
-  )
+  );
+  app_state.save_session(&state);
+  result
+}
+
+#[tauri::command]
+fn cancel_computation(
+  app_state: tauri::State<TauriApplicationState>,
+  app_handle: tauri::AppHandle,
+) -> Result<(), tauri::Error> {
+  jobs::cancel_background_command(&app_handle, &app_state.jobs)
 }
 
 #[tauri::command]
@@ -118,7 +211,7 @@ fn validate_stack_size(
   app_handle: tauri::AppHandle,
   expected: usize,
 ) -> Result<bool, tauri::Error> {
-  let state = app_state.state.lock().expect("poisoned mutex");
+  let state = app_state.lock_state();
   tauri_command::validate_stack_size(&state, &app_handle, expected)
 }
 
@@ -129,10 +222,11 @@ fn validate_value(
   value: &str,
   validator: Validator,
 ) -> Result<bool, tauri::Error> {
-  let state = app_state.state.lock().expect("poisoned mutex");
+  let state = app_state.lock_state();
   let language_mode = state.display_settings().language_mode();
+  let units_parser = CurrencyUnitParser::new(app_state.units_parser.as_ref(), state.currency_rates());
   let validation_context = ValidationContext {
-    units_parser: app_state.units_parser.as_ref(),
+    units_parser: &units_parser,
     language_mode: language_mode.as_ref(),
   };
   tauri_command::validate_value(&validation_context, &app_handle, value.to_owned(), validator)
@@ -144,9 +238,60 @@ fn query_stack(
   app_handle: tauri::AppHandle,
   query: Query,
 ) -> Result<bool, tauri::Error> {
-  let state = app_state.state.lock().expect("poisoned mutex");
+  let state = app_state.lock_state();
+  let units_parser = CurrencyUnitParser::new(app_state.units_parser.as_ref(), state.currency_rates());
   let query_context = QueryContext {
-    units_parser: app_state.units_parser.as_ref(),
+    units_parser: &units_parser,
   };
   tauri_command::query_stack(&query_context, &app_handle, &state, &query)
 }
+
+#[tauri::command]
+fn get_engine_stats(
+  app_state: tauri::State<TauriApplicationState>,
+) -> EngineStats {
+  let state = app_state.lock_state();
+  tauri_command::get_engine_stats(&state)
+}
+
+#[tauri::command]
+fn simplifier_profile(
+  app_state: tauri::State<TauriApplicationState>,
+) -> Vec<PassTiming> {
+  app_state.simplifier_profile.timings()
+}
+
+#[tauri::command]
+fn reset_simplifier_profile(
+  app_state: tauri::State<TauriApplicationState>,
+) {
+  app_state.simplifier_profile.reset();
+}
+
+// Tauri runs synchronous commands on the main thread, where creating
+// a window deadlocks on Windows, so this command is async.
+#[tauri::command]
+async fn open_window(
+  app_state: tauri::State<'_, TauriApplicationState>,
+  app_handle: tauri::AppHandle,
+  kind: WindowKind,
+) -> Result<Option<String>, tauri::Error> {
+  let label = tauri_command::open_auxiliary_window(&app_state, &app_handle, kind);
+  handle_non_tauri_errors_or(&app_handle, label.map(Some), None)
+}
+
+#[tauri::command]
+fn render_stack_document(
+  app_state: tauri::State<TauriApplicationState>,
+) -> String {
+  let state = app_state.lock_state();
+  tauri_command::render_stack_document(&state)
+}
+
+#[tauri::command]
+fn list_bookmarks(
+  app_state: tauri::State<TauriApplicationState>,
+) -> Vec<BookmarkPreview> {
+  let state = app_state.lock_state();
+  tauri_command::list_bookmarks(&state)
+}
@@ -60,6 +60,21 @@ pub trait StackLike {
     self.pop_several(self.len()).unwrap()
   }
 
+  /// Puts back values that were just popped, after the operation
+  /// that popped them has failed. `values` should be given in the
+  /// order that `pop_several` returned them, and nothing else should
+  /// have been pushed or popped in the meantime.
+  ///
+  /// The default implementation pushes the values. Stacks whose pops
+  /// can leave values in place (such as a [`KeepableStack`] with
+  /// "keep" semantics) override this so that those values are not
+  /// duplicated.
+  ///
+  /// [`KeepableStack`]: super::keepable::KeepableStack
+  fn restore_popped(&mut self, values: impl IntoIterator<Item = Self::Elem>) {
+    self.push_several(values);
+  }
+
   /// Returns true if the stack is empty.
   fn is_empty(&self) -> bool {
     self.len() == 0
@@ -26,6 +26,20 @@ pub trait StackDelegate<T> {
 
   /// Called after a value on the stack is modified in-place.
   fn on_mutate(&mut self, index: i64, old_value: &T, new_value: &T);
+
+  /// Whether the value at the given position (counting from the top
+  /// of the stack) is pinned. A pinned value is returned by `pop`,
+  /// `pop_several`, and `pop_all` as normal, but it is read in place
+  /// rather than removed from the stack, and
+  /// [`on_pop`](StackDelegate::on_pop) is not called for it. Later
+  /// pops from the same [`DelegatingStack`] skip over it, so each
+  /// pinned value is read at most once. Random-access removal with
+  /// `pop_nth` ignores pins.
+  ///
+  /// By default, no values are pinned.
+  fn is_pinned(&self, _index: usize) -> bool {
+    false
+  }
 }
 
 /// Null Object implementation of [`StackDelegate`]. Never performs
@@ -37,6 +51,23 @@ pub struct NullStackDelegate;
 pub struct DelegatingStack<'a, S, D> {
   stack: &'a mut S,
   delegate: D,
+  read_through: ReadThrough,
+}
+
+/// The pinned values which have been returned by pops from a
+/// [`DelegatingStack`] but left on the underlying stack. Since pops
+/// proceed from the top down, these values always form a contiguous
+/// run of the stack, which later pops skip over.
+#[derive(Debug, Clone, Default)]
+struct ReadThrough {
+  /// The position of the lowest such value, counting from the bottom
+  /// of the stack. Meaningless if `count` is zero.
+  start: usize,
+  /// The number of such values.
+  count: usize,
+  /// For each pop so far, whether its value was read in place. The
+  /// most recent pop is at the end.
+  pops: Vec<bool>,
 }
 
 pub struct RefMut<'a, T, R, D>
@@ -52,7 +83,26 @@ impl<'a, S, D> DelegatingStack<'a, S, D>
 where S: StackLike,
       D: StackDelegate<S::Elem> {
   pub fn new(stack: &'a mut S, delegate: D) -> Self {
-    DelegatingStack { stack, delegate }
+    DelegatingStack { stack, delegate, read_through: ReadThrough::default() }
+  }
+
+  /// The position (counting from the top) of the value that the next
+  /// pop will return. This is the top of the stack, unless the top of
+  /// the stack consists of pinned values which have already been
+  /// read, in which case it is the value just beneath them.
+  fn next_pop_index(&self) -> usize {
+    let len = self.stack.len();
+    let ReadThrough { start, count, .. } = self.read_through;
+    if count == 0 || len > start + count {
+      0
+    } else {
+      len - start
+    }
+  }
+
+  /// The number of values which have not yet been read by a pop.
+  fn unread_len(&self) -> usize {
+    self.stack.len() - self.read_through.count
   }
 }
 
@@ -68,7 +118,7 @@ where D: StackDelegate<T> {
   pub fn foreach_mut<F>(&mut self, mut f: F)
   where F: FnMut(&mut T),
         T: Clone {
-    let len = self.len();
+    let len = self.stack.len();
     for (i, elem) in self.stack.iter_mut().enumerate() {
       let original_elem = elem.clone();
       f(elem);
@@ -77,9 +127,18 @@ where D: StackDelegate<T> {
   }
 }
 
+/// Popping from a `DelegatingStack` requires random access to the
+/// underlying stack and `T: Clone`, so that pinned values can be read
+/// in place.
+///
+/// The length of a `DelegatingStack` is always the length of the
+/// underlying stack, but pinned values which have already been read
+/// can't be popped again, so `pop`, `pop_several`, and `pop_all` may
+/// find fewer values available than `len` suggests.
 impl<'a, S, D> StackLike for DelegatingStack<'a, S, D>
-where S: StackLike,
-      D: StackDelegate<S::Elem> {
+where S: RandomAccessStackLike,
+      D: StackDelegate<S::Elem>,
+      S::Elem: Clone {
   type Elem = S::Elem;
 
   fn len(&self) -> usize {
@@ -92,25 +151,65 @@ where S: StackLike,
   }
 
   fn pop(&mut self) -> Result<S::Elem, StackError> {
-    let value = self.stack.pop()?;
-    self.delegate.on_pop(0, &value);
-    Ok(value)
+    if self.unread_len() == 0 {
+      return Err(StackError::NotEnoughElements { expected: 1, actual: 0 });
+    }
+    let index = self.next_pop_index();
+    let position = self.stack.len() - index - 1;
+    if self.delegate.is_pinned(index) {
+      let value = self.stack.get(index as i64).map(|elem| elem.clone())?;
+      self.read_through.start = position;
+      self.read_through.count += 1;
+      self.read_through.pops.push(true);
+      Ok(value)
+    } else {
+      let value = self.stack.pop_nth(index)?;
+      self.delegate.on_pop(index, &value);
+      if position < self.read_through.start {
+        self.read_through.start -= 1;
+      }
+      self.read_through.pops.push(false);
+      Ok(value)
+    }
   }
 
   fn pop_several(&mut self, count: usize) -> Result<Vec<S::Elem>, StackError> {
-    let values = self.stack.pop_several(count)?;
-    for value in values.iter().rev() {
-      self.delegate.on_pop(0, value);
+    let actual = self.unread_len();
+    if actual < count {
+      return Err(StackError::NotEnoughElements { expected: count, actual });
     }
+    let mut values = (0..count)
+      .map(|_| self.pop())
+      .collect::<Result<Vec<_>, _>>()?;
+    values.reverse();
     Ok(values)
   }
 
   fn pop_all(&mut self) -> Vec<S::Elem> {
-    let values = self.stack.pop_all();
-    for value in values.iter().rev() {
-      self.delegate.on_pop(0, value);
+    // unwrap: We're popping exactly as many values as are available.
+    self.pop_several(self.unread_len()).unwrap()
+  }
+
+  fn restore_popped(&mut self, values: impl IntoIterator<Item = S::Elem>) {
+    // The bottommost value was popped most recently, so undo the pops
+    // in reverse.
+    for value in values {
+      match self.read_through.pops.pop() {
+        Some(true) => {
+          // The value is still on the stack. Just stop skipping it.
+          self.read_through.start += 1;
+          self.read_through.count -= 1;
+        }
+        Some(false) => {
+          let index = self.next_pop_index();
+          // unwrap: The index is at most the length of the stack.
+          self.insert(index, value).unwrap();
+        }
+        None => {
+          self.push(value);
+        }
+      }
     }
-    values
   }
 }
 
@@ -143,12 +242,25 @@ where S: RandomAccessStackLike,
     // only sees valid pushes.
     self.check_stack_size(index)?;
     self.delegate.on_push(index, &element);
+    // Keep track of the pinned values that pops have skipped, if the
+    // new value goes beneath them.
+    let position = self.stack.len() - index;
+    if position <= self.read_through.start {
+      self.read_through.start += 1;
+    }
     self.stack.insert(index, element)
   }
 
   fn pop_nth(&mut self, index: usize) -> Result<S::Elem, StackError> {
     let value = self.stack.pop_nth(index)?;
     self.delegate.on_pop(index, &value);
+    let position = self.stack.len() - index;
+    let ReadThrough { start, count, .. } = self.read_through;
+    if position < start {
+      self.read_through.start -= 1;
+    } else if position < start + count {
+      self.read_through.count -= 1;
+    }
     Ok(value)
   }
 }
@@ -206,6 +318,15 @@ mod tests {
     mutations: Vec<(i64, i32, i32)>,
   }
 
+  /// Delegate which pins the given values, wherever they are on the
+  /// stack, and records the pops.
+  #[derive(Default, Debug, PartialEq, Eq)]
+  struct PinningDelegate {
+    stack: Vec<i32>,
+    pinned_values: Vec<i32>,
+    pops: Vec<(usize, i32)>,
+  }
+
   impl StackDelegate<i32> for TestDelegate {
     fn on_push(&mut self, index: usize, value: &i32) {
       self.pushes.push((index, *value));
@@ -220,6 +341,29 @@ mod tests {
     }
   }
 
+  impl StackDelegate<i32> for PinningDelegate {
+    fn on_push(&mut self, index: usize, value: &i32) {
+      self.stack.insert(self.stack.len() - index, *value);
+    }
+
+    fn on_pop(&mut self, index: usize, value: &i32) {
+      let removed = self.stack.remove(self.stack.len() - index - 1);
+      assert_eq!(removed, *value);
+      self.pops.push((index, *value));
+    }
+
+    fn on_mutate(&mut self, _: i64, _: &i32, _: &i32) {}
+
+    fn is_pinned(&self, index: usize) -> bool {
+      let value = self.stack[self.stack.len() - index - 1];
+      self.pinned_values.contains(&value)
+    }
+  }
+
+  fn pinning_delegate(stack: &[i32], pinned_values: &[i32]) -> PinningDelegate {
+    PinningDelegate { stack: stack.to_vec(), pinned_values: pinned_values.to_vec(), pops: vec![] }
+  }
+
   #[test]
   fn test_push() {
     let mut stack = Stack::new();
@@ -425,4 +569,79 @@ mod tests {
     });
   }
 
+  #[test]
+  fn test_pop_pinned() {
+    let mut stack = Stack::from(vec![10, 20, 30]);
+    {
+      let mut stack = DelegatingStack::new(&mut stack, pinning_delegate(&[10, 20, 30], &[30]));
+      assert_eq!(stack.pop(), Ok(30));
+      assert_eq!(stack.pop(), Ok(20));
+      assert_eq!(stack.len(), 2);
+      assert_eq!(stack.delegate.pops, vec![(1, 20)]);
+      assert_eq!(stack.pop(), Ok(10));
+      assert_eq!(stack.pop(), Err(StackError::NotEnoughElements { expected: 1, actual: 0 }));
+    }
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![30]);
+  }
+
+  #[test]
+  fn test_push_after_pop_pinned() {
+    let mut stack = Stack::from(vec![10, 20]);
+    {
+      let mut stack = DelegatingStack::new(&mut stack, pinning_delegate(&[10, 20], &[20]));
+      assert_eq!(stack.pop_several(2), Ok(vec![10, 20]));
+      stack.push(30);
+      assert_eq!(stack.pop(), Ok(30));
+    }
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![20]);
+  }
+
+  #[test]
+  fn test_pop_several_with_pins() {
+    let mut stack = Stack::from(vec![10, 20, 30, 40, 50]);
+    {
+      let mut stack = DelegatingStack::new(&mut stack, pinning_delegate(&[10, 20, 30, 40, 50], &[20, 40]));
+      assert_eq!(stack.pop_several(4), Ok(vec![20, 30, 40, 50]));
+      assert_eq!(stack.delegate.pops, vec![(0, 50), (1, 30)]);
+      assert_eq!(stack.delegate.stack, vec![10, 20, 40]);
+    }
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![10, 20, 40]);
+  }
+
+  #[test]
+  fn test_pop_all_with_pins() {
+    let mut stack = Stack::from(vec![10, 20, 30]);
+    {
+      let mut stack = DelegatingStack::new(&mut stack, pinning_delegate(&[10, 20, 30], &[10]));
+      assert_eq!(stack.pop_all(), vec![10, 20, 30]);
+      assert_eq!(stack.delegate.pops, vec![(0, 30), (0, 20)]);
+    }
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![10]);
+  }
+
+  #[test]
+  fn test_restore_popped_with_pins() {
+    let mut stack = Stack::from(vec![10, 20, 30, 40, 50]);
+    {
+      let mut stack = DelegatingStack::new(&mut stack, pinning_delegate(&[10, 20, 30, 40, 50], &[20, 40]));
+      let values = stack.pop_several(4).unwrap();
+      assert_eq!(values, vec![20, 30, 40, 50]);
+      stack.restore_popped(values);
+      assert_eq!(stack.delegate.stack, vec![10, 20, 30, 40, 50]);
+      // The restored values can be popped again.
+      assert_eq!(stack.pop_several(2), Ok(vec![40, 50]));
+    }
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![10, 20, 30, 40]);
+  }
+
+  #[test]
+  fn test_restore_popped_without_pops() {
+    let mut stack = Stack::from(vec![10]);
+    {
+      let mut stack = DelegatingStack::new(&mut stack, TestDelegate::default());
+      stack.restore_popped([20, 30]);
+      assert_eq!(stack.delegate.pushes, vec![(0, 20), (0, 30)]);
+    }
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![10, 20, 30]);
+  }
 }
@@ -10,11 +10,13 @@ use super::error::StackError;
 /// its underlying stack with no modifications. With "keep" semantics
 /// on, the `KeepableStack` will perform "push" operations normally
 /// but, when a "pop" operation is requested, the desired elements
-/// will be returned but will also be pushed back onto the stack.
+/// will be returned but will also be left on the stack.
 ///
 /// To state the obvious, `T: Clone` must be true in order for the
 /// keep semantics to work, as it involves cloning the underlying
-/// value in order to both keep and return it.
+/// value in order to both keep and return it. The underlying stack
+/// must also support random access, since kept values are read in
+/// place rather than popped.
 ///
 /// Note that some care must be taken when using a [`KeepableStack`].
 /// Notably, since the `pop`, `pop_several`, and `pop_all` methods
@@ -91,7 +93,7 @@ impl<S> KeepableStack<S> where S: StackLike {
 }
 
 impl<S> StackLike for KeepableStack<S>
-where S: StackLike,
+where S: RandomAccessStackLike,
       S::Elem: Clone {
   type Elem = S::Elem;
 
@@ -108,19 +110,31 @@ where S: StackLike,
   }
 
   fn pop(&mut self) -> Result<S::Elem, StackError> {
-    let value = self.stack.pop()?;
     if self.keep_semantics {
-      self.stack.push(value.clone());
+      self.stack.get(0).map(|elem| elem.clone())
+    } else {
+      self.stack.pop()
     }
-    Ok(value)
   }
 
   fn pop_several(&mut self, count: usize) -> Result<Vec<S::Elem>, StackError> {
-    let values = self.stack.pop_several(count)?;
     if self.keep_semantics {
-      self.stack.push_several(values.clone());
+      // Read the values in place, rather than popping and re-pushing
+      // them, so that the underlying stack sees no changes at all.
+      self.check_stack_size(count)?;
+      (0..count).rev()
+        .map(|index| self.stack.get(index as i64).map(|elem| elem.clone()))
+        .collect()
+    } else {
+      self.stack.pop_several(count)
+    }
+  }
+
+  fn restore_popped(&mut self, values: impl IntoIterator<Item = S::Elem>) {
+    // With "keep" semantics, nothing was removed in the first place.
+    if !self.keep_semantics {
+      self.stack.restore_popped(values);
     }
-    Ok(values)
   }
 }
 
@@ -0,0 +1,103 @@
+
+//! Numbered bookmarks, which remember stack values independently of
+//! their position on the stack.
+
+use crate::expr::Expr;
+use crate::mode::display::DisplaySettings;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::collections::BTreeMap;
+
+/// A table of bookmarked expressions, keyed by bookmark number.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookmarkTable {
+  entries: BTreeMap<usize, Expr>,
+}
+
+/// A bookmark as presented to the frontend's bookmark palette.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkPreview {
+  pub index: usize,
+  /// The bookmarked value, rendered as HTML in the current display
+  /// mode.
+  pub preview_html: String,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("No such bookmark: {index}")]
+pub struct UnknownBookmarkError {
+  pub index: usize,
+}
+
+impl BookmarkTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<&Expr> {
+    self.entries.get(&index)
+  }
+
+  /// Looks up the bookmark, producing an appropriate error if it
+  /// does not exist.
+  pub fn try_get(&self, index: usize) -> Result<&Expr, UnknownBookmarkError> {
+    self.get(index).ok_or(UnknownBookmarkError { index })
+  }
+
+  /// Sets the bookmark, returning its previous value (if any).
+  pub fn insert(&mut self, index: usize, expr: Expr) -> Option<Expr> {
+    self.entries.insert(index, expr)
+  }
+
+  pub fn remove(&mut self, index: usize) -> Option<Expr> {
+    self.entries.remove(&index)
+  }
+
+  /// Iterates over the bookmarks in increasing order of index.
+  pub fn iter(&self) -> impl Iterator<Item = (usize, &Expr)> {
+    self.entries.iter().map(|(index, expr)| (*index, expr))
+  }
+
+  pub fn previews(&self, display_settings: &DisplaySettings) -> Vec<BookmarkPreview> {
+    self.iter()
+      .map(|(index, expr)| BookmarkPreview { index, preview_html: display_settings.to_html(expr) })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_insert_and_get() {
+    let mut table = BookmarkTable::new();
+    assert_eq!(table.insert(3, Expr::from(10)), None);
+    assert_eq!(table.insert(3, Expr::from(20)), Some(Expr::from(10)));
+    assert_eq!(table.get(3), Some(&Expr::from(20)));
+    assert_eq!(table.try_get(4), Err(UnknownBookmarkError { index: 4 }));
+  }
+
+  #[test]
+  fn test_previews_in_order() {
+    let mut table = BookmarkTable::new();
+    table.insert(5, Expr::from(50));
+    table.insert(1, Expr::from(10));
+    let previews = table.previews(&DisplaySettings::default());
+    assert_eq!(previews, vec![
+      BookmarkPreview { index: 1, preview_html: String::from("10") },
+      BookmarkPreview { index: 5, preview_html: String::from("50") },
+    ]);
+  }
+}
@@ -3,7 +3,10 @@
 //! `UndoStack<UndoableState>`.
 
 use super::UndoableState;
-use super::undo::{PushExprChange, PopExprChange, ReplaceExprChange};
+use super::labels::StackLabels;
+use super::pins::StackPins;
+use super::groups::StackGroups;
+use super::undo::{PushExprChange, PopExprChange, ReplaceExprChange, AltStackChange};
 use crate::undo::UndoStack;
 use crate::stack::StackDelegate;
 use crate::expr::Expr;
@@ -11,24 +14,73 @@ use crate::expr::Expr;
 #[derive(Debug)]
 pub struct UndoingDelegate<'a> {
   undo_stack: &'a mut UndoStack<UndoableState>,
+  labels: &'a mut StackLabels,
+  pins: &'a mut StackPins,
+  groups: &'a mut StackGroups,
+}
+
+/// A delegate recording changes to the alternate stack in an
+/// `UndoStack<UndoableState>`.
+#[derive(Debug)]
+pub struct AltStackUndoingDelegate<'a> {
+  undo_stack: &'a mut UndoStack<UndoableState>,
 }
 
 impl<'a> UndoingDelegate<'a> {
+  /// A delegate recording changes to `undo_stack`. The stack labels,
+  /// pin flags, and groups are shifted along with the stack, so that
+  /// each stays with its entry. Entries flagged in `pins` are reported as
+  /// pinned.
+  pub fn new(
+    undo_stack: &'a mut UndoStack<UndoableState>,
+    labels: &'a mut StackLabels,
+    pins: &'a mut StackPins,
+    groups: &'a mut StackGroups,
+  ) -> Self {
+    Self { undo_stack, labels, pins, groups }
+  }
+}
+
+impl<'a> StackDelegate<Expr> for UndoingDelegate<'a> {
+  fn on_push(&mut self, index: usize, _new_value: &Expr) {
+    self.labels.insert(index, None);
+    self.pins.insert(index, false);
+    self.groups.insert(index, None);
+    self.undo_stack.push_change(PushExprChange::new(index));
+  }
+
+  fn on_pop(&mut self, index: usize, old_value: &Expr) {
+    let label = self.labels.remove(index);
+    let pinned = self.pins.remove(index);
+    let group = self.groups.remove(index);
+    self.undo_stack.push_change(PopExprChange::new(index, old_value.clone(), label, pinned, group));
+  }
+
+  fn on_mutate(&mut self, index: i64, old_value: &Expr, new_value: &Expr) {
+    self.undo_stack.push_change(ReplaceExprChange::new(index, old_value, new_value));
+  }
+
+  fn is_pinned(&self, index: usize) -> bool {
+    self.pins.is_pinned(index)
+  }
+}
+
+impl<'a> AltStackUndoingDelegate<'a> {
   pub fn new(undo_stack: &'a mut UndoStack<UndoableState>) -> Self {
     Self { undo_stack }
   }
 }
 
-impl<'a> StackDelegate<Expr> for UndoingDelegate<'a> {
-  fn on_push(&mut self, index: usize, new_value: &Expr) {
-    self.undo_stack.push_change(PushExprChange::new(index, new_value.clone()));
+impl<'a> StackDelegate<Expr> for AltStackUndoingDelegate<'a> {
+  fn on_push(&mut self, index: usize, _new_value: &Expr) {
+    self.undo_stack.push_change(AltStackChange::push(index));
   }
 
   fn on_pop(&mut self, index: usize, old_value: &Expr) {
-    self.undo_stack.push_change(PopExprChange::new(index, old_value.clone()));
+    self.undo_stack.push_change(AltStackChange::pop(index, old_value.clone()));
   }
 
   fn on_mutate(&mut self, index: i64, old_value: &Expr, new_value: &Expr) {
-    self.undo_stack.push_change(ReplaceExprChange::new(index, old_value.clone(), new_value.clone()));
+    self.undo_stack.push_change(AltStackChange::replace(index, old_value, new_value));
   }
 }
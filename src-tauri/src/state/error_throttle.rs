@@ -0,0 +1,185 @@
+
+//! Rate limiting of the error messages shown to the user.
+//!
+//! A single command can produce a flood of errors (for instance, one
+//! per element when mapping over a large vector). Such a batch is
+//! already reported as a single message with a count (see
+//! [`crate::state::tauri_command`]), but a program or a rapidly
+//! repeated command can still produce many batches in quick
+//! succession. The [`ErrorThrottle`] caps the number of errors shown
+//! per time window. Errors beyond the cap are not shown, but they are
+//! counted, and once the window closes, a single summary error
+//! reports how many were suppressed.
+//!
+//! Every error which is under the cap is shown, even if it repeats
+//! the previous one, since the user may well have triggered it again
+//! on purpose.
+
+use std::time::{Duration, Instant};
+
+/// The length of the window over which errors are rate-limited.
+pub const ERROR_WINDOW: Duration = Duration::from_secs(1);
+
+/// The maximum number of errors shown to the user per
+/// [`ERROR_WINDOW`].
+pub const MAX_ERRORS_PER_WINDOW: usize = 5;
+
+#[derive(Debug, Clone, Default)]
+pub struct ErrorThrottle {
+  window_start: Option<Instant>,
+  shown_in_window: usize,
+  /// The number of errors suppressed in the current window.
+  suppressed: usize,
+}
+
+/// An error which has passed through the [`ErrorThrottle`] and should
+/// be shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrottledError {
+  pub message: String,
+  /// The number of errors this message stands in for. Always at
+  /// least one.
+  pub count: usize,
+}
+
+/// The result of [`ErrorThrottle::admit`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Admission {
+  /// The errors to show to the user, in order. This is empty if the
+  /// new error was suppressed.
+  pub shown: Vec<ThrottledError>,
+  /// If the new error is the first to be suppressed in the current
+  /// window, the time at which the window closes. The caller should
+  /// call [`ErrorThrottle::flush`] at that time, to report the
+  /// suppressed errors.
+  pub flush_at: Option<Instant>,
+}
+
+impl ErrorThrottle {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Records an error message, standing in for `count` errors,
+  /// received at the given time.
+  pub fn admit(&mut self, message: String, count: usize, now: Instant) -> Admission {
+    let count = count.max(1);
+    let mut admission = Admission::default();
+    if self.window_start.is_none_or(|start| now.saturating_duration_since(start) >= ERROR_WINDOW) {
+      // Report anything suppressed in the old window before moving on
+      // to the new one, in case the flush hasn't happened yet.
+      admission.shown.extend(self.take_summary());
+      self.window_start = Some(now);
+      self.shown_in_window = 0;
+    }
+    if self.shown_in_window >= MAX_ERRORS_PER_WINDOW {
+      if self.suppressed == 0 {
+        // unwrap: The window was started above.
+        admission.flush_at = Some(self.window_start.unwrap() + ERROR_WINDOW);
+      }
+      self.suppressed += count;
+    } else {
+      self.shown_in_window += 1;
+      admission.shown.push(ThrottledError { message, count });
+    }
+    admission
+  }
+
+  /// Returns a summary of the errors suppressed in the most recent
+  /// window, if that window has closed and any errors were
+  /// suppressed.
+  pub fn flush(&mut self, now: Instant) -> Option<ThrottledError> {
+    let window_start = self.window_start?;
+    if now.saturating_duration_since(window_start) < ERROR_WINDOW {
+      return None;
+    }
+    self.take_summary()
+  }
+
+  fn take_summary(&mut self) -> Option<ThrottledError> {
+    match self.suppressed {
+      0 => None,
+      suppressed => {
+        self.suppressed = 0;
+        Some(ThrottledError { message: suppressed_message(suppressed), count: 1 })
+      }
+    }
+  }
+}
+
+fn suppressed_message(suppressed: usize) -> String {
+  if suppressed == 1 {
+    String::from("1 more error was not shown")
+  } else {
+    format!("{suppressed} more errors were not shown")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn error(message: &str, count: usize) -> ThrottledError {
+    ThrottledError { message: message.to_owned(), count }
+  }
+
+  fn shown(errors: Vec<ThrottledError>) -> Admission {
+    Admission { shown: errors, flush_at: None }
+  }
+
+  #[test]
+  fn test_errors_under_the_limit_are_shown() {
+    let mut throttle = ErrorThrottle::new();
+    let now = Instant::now();
+    assert_eq!(throttle.admit("a".to_owned(), 1, now), shown(vec![error("a", 1)]));
+    assert_eq!(throttle.admit("b".to_owned(), 1, now), shown(vec![error("b", 1)]));
+    assert_eq!(throttle.admit("a".to_owned(), 3, now), shown(vec![error("a", 3)]));
+  }
+
+  #[test]
+  fn test_repeated_errors_are_shown() {
+    let mut throttle = ErrorThrottle::new();
+    let now = Instant::now();
+    assert_eq!(throttle.admit("a".to_owned(), 1, now), shown(vec![error("a", 1)]));
+    assert_eq!(throttle.admit("a".to_owned(), 1, now), shown(vec![error("a", 1)]));
+    assert_eq!(throttle.admit("a".to_owned(), 2, now + Duration::from_millis(500)), shown(vec![error("a", 2)]));
+  }
+
+  #[test]
+  fn test_rate_limit() {
+    let mut throttle = ErrorThrottle::new();
+    let now = Instant::now();
+    for i in 0..MAX_ERRORS_PER_WINDOW {
+      assert_eq!(throttle.admit(i.to_string(), 1, now), shown(vec![error(&i.to_string(), 1)]));
+    }
+    assert_eq!(throttle.admit("x".to_owned(), 1, now), Admission { shown: vec![], flush_at: Some(now + ERROR_WINDOW) });
+    assert_eq!(throttle.admit("y".to_owned(), 2, now + Duration::from_millis(999)), Admission::default());
+    assert_eq!(throttle.flush(now + Duration::from_millis(999)), None);
+    assert_eq!(throttle.flush(now + ERROR_WINDOW), Some(error("3 more errors were not shown", 1)));
+    assert_eq!(throttle.flush(now + ERROR_WINDOW), None);
+  }
+
+  #[test]
+  fn test_summary_is_not_merged_into_next_error() {
+    let mut throttle = ErrorThrottle::new();
+    let now = Instant::now();
+    for i in 0..=MAX_ERRORS_PER_WINDOW {
+      throttle.admit(i.to_string(), 1, now);
+    }
+    // The window closes before the flush happens.
+    assert_eq!(
+      throttle.admit("z".to_owned(), 1, now + ERROR_WINDOW),
+      shown(vec![error("1 more error was not shown", 1), error("z", 1)]),
+    );
+    assert_eq!(throttle.flush(now + ERROR_WINDOW), None);
+  }
+
+  #[test]
+  fn test_flush_without_suppressed_errors() {
+    let mut throttle = ErrorThrottle::new();
+    let now = Instant::now();
+    assert_eq!(throttle.flush(now), None);
+    throttle.admit("a".to_owned(), 1, now);
+    assert_eq!(throttle.flush(now + ERROR_WINDOW), None);
+  }
+}
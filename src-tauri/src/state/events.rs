@@ -1,10 +1,32 @@
 
 //! Serializable events that the Rust backend can send (via Tauri) to
 //! the frontend.
+//!
+//! Events are routed per window: each event type declares which
+//! [`WindowKind`]s are interested in it, and [`emit_to_windows`]
+//! sends it to every open window of those kinds.
+
+use super::TauriApplicationState;
+use super::error_throttle::ThrottledError;
+use super::hints::ThemeHints;
+use super::modeline::ModelineSegment;
+use super::windows::WindowKind;
+use crate::command::options::CommandOptions;
 
 use serde::Serialize;
 use tauri::Manager;
 
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// An event payload that can be sent to the frontend.
+pub trait WindowEvent: Serialize + Clone {
+  const EVENT_NAME: &'static str;
+
+  /// Whether windows of the given kind should receive this event.
+  fn is_routed_to(kind: WindowKind) -> bool;
+}
+
 /// Instructs the frontend to re-render the stack elements with the
 /// given values.
 #[derive(Serialize, Clone, PartialEq, Eq)]
@@ -12,6 +34,18 @@ use tauri::Manager;
 pub struct RefreshStackPayload {
   /// The stack elements, starting from the top.
   pub stack: Vec<String>,
+  /// The label attached to each stack element, in the same order as
+  /// `stack`.
+  pub labels: Vec<Option<String>>,
+  /// Whether each stack element is pinned, in the same order as
+  /// `stack`.
+  pub pinned: Vec<bool>,
+  /// Groups of adjacent stack elements, which the frontend displays
+  /// as a single collapsible row each.
+  pub groups: Vec<StackGroupPayload>,
+  /// English renderings of the stack elements, in the same order as
+  /// `stack`, or `None` if spoken text is disabled.
+  pub spoken_stack: Option<Vec<String>>,
   /// Whether or not to force the stack view to scroll down to the
   /// bottom immediately.
   ///
@@ -19,6 +53,30 @@ pub struct RefreshStackPayload {
   /// specific value somewhere on the stack is being modified, in
   /// which case it may be best to leave the view where it is.
   pub force_scroll_down: bool,
+  /// The command whose execution triggered this refresh, or `None`
+  /// if the refresh was caused by something else, such as an undo.
+  pub command_timing: Option<CommandTimingPayload>,
+}
+
+/// The name of a command and how long it took to run, in a
+/// [`RefreshStackPayload`].
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTimingPayload {
+  pub command_name: String,
+  pub elapsed_millis: u64,
+}
+
+/// A titled group of stack elements in a [`RefreshStackPayload`].
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StackGroupPayload {
+  pub title: String,
+  /// The position in the payload's `stack` of the group's first
+  /// element.
+  pub start: usize,
+  /// The number of elements in the group.
+  pub length: usize,
 }
 
 /// Instructs the frontend to update the states of the "Undo" and
@@ -34,7 +92,32 @@ pub struct UndoAvailabilityPayload {
 #[derive(Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelinePayload {
+  /// The whole modeline as a single line of text. The concatenation
+  /// of the indicators' values.
   pub modeline_text: String,
+  pub indicators: Vec<ModeIndicatorPayload>,
+}
+
+/// One segment of the modeline.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModeIndicatorPayload {
+  pub key: Option<String>,
+  pub value: String,
+  pub tooltip: Option<String>,
+  pub active: bool,
+  /// A nullary command which toggles this mode when clicked.
+  pub toggle_command: Option<String>,
+}
+
+/// Informs the frontend of semantic hints about the engine state,
+/// such as whether the last command failed. Unlike the modeline, this
+/// is structured data, meant for coloring or badging the UI.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeHintsPayload {
+  #[serde(flatten)]
+  pub hints: ThemeHints,
 }
 
 /// Instructs the frontend to render an error message to the user.
@@ -43,24 +126,321 @@ pub struct ModelinePayload {
 pub struct ShowErrorPayload {
   /// The error message to display.
   pub error_message: String,
+  /// The number of errors this message summarizes. If greater than
+  /// one, then other errors were suppressed and only this one is
+  /// shown.
+  pub count: usize,
+}
+
+/// Informs the frontend of the progress of a program being stepped
+/// through one instruction at a time.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramStepPayload {
+  /// The name of the instruction that will run on the next step, or
+  /// `None` if the program has finished.
+  pub upcoming_command: Option<String>,
+  /// The (zero-based) index of the upcoming instruction.
+  pub step_index: usize,
+  /// The total number of instructions in the program.
+  pub step_count: usize,
+  /// The stack elements, starting from the top.
+  pub stack: Vec<String>,
+}
+
+/// Informs the frontend that a command failed due to an internal
+/// error. The application state has been rolled back to what it was
+/// before the command ran.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalErrorPayload {
+  /// The name of the command that failed.
+  pub command_name: String,
+  /// A description of the failure.
+  pub message: String,
+}
+
+/// Asks the frontend to confirm a destructive command. The command
+/// has not run. If the user agrees, the frontend should re-run it
+/// with the same arguments and with the `confirmed` option set.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmationRequestPayload {
+  pub command_name: String,
+  pub args: Vec<String>,
+  pub options: CommandOptions,
+  /// A description of what the command is about to do.
+  pub message: String,
 }
 
-impl RefreshStackPayload {
-  pub const EVENT_NAME: &'static str = "refresh-stack";
+/// Informs the frontend that a timer started by the `timer` command
+/// has gone off.
+#[derive(Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerExpiredPayload {
+  /// The length of the timer, in seconds.
+  pub seconds: f64,
 }
 
-impl UndoAvailabilityPayload {
-  pub const EVENT_NAME: &'static str = "refresh-undo-availability";
+/// Reports on a command running on the background job runner. Sent
+/// once when the job starts, periodically while it runs, and once
+/// more when it finishes or is cancelled.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputationProgressPayload {
+  pub job_id: u64,
+  pub command_name: String,
+  pub status: ComputationStatus,
+  /// The number of expression nodes the job has simplified so far.
+  pub steps: u64,
+  pub elapsed_millis: u64,
 }
 
-impl ModelinePayload {
-  pub const EVENT_NAME: &'static str = "refresh-modeline";
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComputationStatus {
+  Running,
+  Finished,
+  Cancelled,
 }
 
-impl ShowErrorPayload {
-  pub const EVENT_NAME: &'static str = "show-error";
+impl From<&ModelineSegment> for ModeIndicatorPayload {
+  fn from(segment: &ModelineSegment) -> Self {
+    ModeIndicatorPayload {
+      key: segment.key.map(str::to_owned),
+      value: segment.text.clone(),
+      tooltip: segment.tooltip.map(str::to_owned),
+      active: segment.active,
+      toggle_command: segment.toggle_command.map(str::to_owned),
+    }
+  }
 }
 
+impl CommandTimingPayload {
+  pub fn new(command_name: impl Into<String>, elapsed: Duration) -> Self {
+    CommandTimingPayload {
+      command_name: command_name.into(),
+      elapsed_millis: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+    }
+  }
+}
+
+impl WindowEvent for RefreshStackPayload {
+  const EVENT_NAME: &'static str = "refresh-stack";
+
+  fn is_routed_to(_kind: WindowKind) -> bool {
+    true
+  }
+}
+
+impl WindowEvent for UndoAvailabilityPayload {
+  const EVENT_NAME: &'static str = "refresh-undo-availability";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind == WindowKind::Main
+  }
+}
+
+impl WindowEvent for ModelinePayload {
+  const EVENT_NAME: &'static str = "refresh-modeline";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind != WindowKind::Graph
+  }
+}
+
+impl WindowEvent for ThemeHintsPayload {
+  const EVENT_NAME: &'static str = "refresh-theme-hints";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind != WindowKind::Graph
+  }
+}
+
+impl WindowEvent for ProgramStepPayload {
+  const EVENT_NAME: &'static str = "program-step";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind == WindowKind::Main
+  }
+}
+
+impl WindowEvent for InternalErrorPayload {
+  const EVENT_NAME: &'static str = "internal-error";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind == WindowKind::Main
+  }
+}
+
+impl WindowEvent for ConfirmationRequestPayload {
+  const EVENT_NAME: &'static str = "request-confirmation";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind == WindowKind::Main
+  }
+}
+
+impl WindowEvent for ShowErrorPayload {
+  const EVENT_NAME: &'static str = "show-error";
+
+  fn is_routed_to(_kind: WindowKind) -> bool {
+    true
+  }
+}
+
+impl WindowEvent for TimerExpiredPayload {
+  const EVENT_NAME: &'static str = "timer-expired";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind == WindowKind::Main
+  }
+}
+
+impl WindowEvent for ComputationProgressPayload {
+  const EVENT_NAME: &'static str = "computation-progress";
+
+  fn is_routed_to(kind: WindowKind) -> bool {
+    kind == WindowKind::Main
+  }
+}
+
+/// Sends the event to every open window whose kind is interested in
+/// it. Windows not managed by [`super::windows`] receive nothing.
+pub fn emit_to_windows<E: WindowEvent>(app_handle: &tauri::AppHandle, payload: E) -> tauri::Result<()> {
+  for label in app_handle.webview_windows().keys() {
+    if WindowKind::from_label(label).is_some_and(E::is_routed_to) {
+      app_handle.emit_to(label.as_str(), E::EVENT_NAME, payload.clone())?;
+    }
+  }
+  Ok(())
+}
+
+/// Shows an error message to the user, unless too many errors have
+/// been shown recently. See [`ErrorThrottle`] for details.
+///
+/// [`ErrorThrottle`]: super::error_throttle::ErrorThrottle
 pub fn show_error(app_handle: &tauri::AppHandle, error_message: String) -> tauri::Result<()> {
-  app_handle.emit(ShowErrorPayload::EVENT_NAME, ShowErrorPayload { error_message })
+  show_error_summary(app_handle, error_message, 1)
+}
+
+/// As [`show_error`], but `error_message` stands in for `count`
+/// errors, only the first of which is being shown.
+pub fn show_error_summary(app_handle: &tauri::AppHandle, error_message: String, count: usize) -> tauri::Result<()> {
+  let app_state = app_handle.state::<TauriApplicationState>();
+  let admission = {
+    // A poisoned throttle still holds valid counts, so keep using it.
+    let mut throttle = app_state.error_throttle.lock().unwrap_or_else(|err| err.into_inner());
+    throttle.admit(error_message, count, Instant::now())
+  };
+  if let Some(flush_at) = admission.flush_at {
+    schedule_error_flush(app_handle, flush_at);
+  }
+  for error in admission.shown {
+    emit_to_windows(app_handle, ShowErrorPayload::from(error))?;
+  }
+  Ok(())
+}
+
+/// Reports the errors suppressed by the throttle once its current
+/// window closes. The wait happens on a separate thread, so this
+/// returns immediately.
+fn schedule_error_flush(app_handle: &tauri::AppHandle, flush_at: Instant) {
+  let app_handle = app_handle.clone();
+  thread::spawn(move || {
+    thread::sleep(flush_at.saturating_duration_since(Instant::now()));
+    let app_state = app_handle.state::<TauriApplicationState>();
+    let summary = {
+      let mut throttle = app_state.error_throttle.lock().unwrap_or_else(|err| err.into_inner());
+      throttle.flush(Instant::now())
+    };
+    if let Some(summary) = summary {
+      // If the window has closed in the meantime, there's nobody to
+      // notify, so errors are ignored.
+      let _ = emit_to_windows(&app_handle, ShowErrorPayload::from(summary));
+    }
+  });
+}
+
+impl From<ThrottledError> for ShowErrorPayload {
+  fn from(error: ThrottledError) -> Self {
+    ShowErrorPayload { error_message: error.message, count: error.count }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::state::modeline::ModelineBuilder;
+
+  #[test]
+  fn test_confirmation_request_only_goes_to_main_window() {
+    assert!(ConfirmationRequestPayload::is_routed_to(WindowKind::Main));
+    assert!(!ConfirmationRequestPayload::is_routed_to(WindowKind::Stack));
+  }
+
+  #[test]
+  fn test_stack_refresh_goes_to_all_windows() {
+    assert!(RefreshStackPayload::is_routed_to(WindowKind::Main));
+    assert!(RefreshStackPayload::is_routed_to(WindowKind::Stack));
+    assert!(RefreshStackPayload::is_routed_to(WindowKind::Graph));
+  }
+
+  #[test]
+  fn test_modeline_skips_graph_windows() {
+    assert!(ModelinePayload::is_routed_to(WindowKind::Main));
+    assert!(ModelinePayload::is_routed_to(WindowKind::Stack));
+    assert!(!ModelinePayload::is_routed_to(WindowKind::Graph));
+  }
+
+  #[test]
+  fn test_mode_indicator_from_segment() {
+    let modeline = ModelineBuilder::new()
+      .append_flag("Deg", false)
+      .with_tooltip("Degrees mode")
+      .toggled_by("toggle_degrees")
+      .build();
+    let indicator = ModeIndicatorPayload::from(&modeline.segments()[0]);
+    assert_eq!(serde_json::to_value(indicator).unwrap(), serde_json::json!({
+      "key": "Deg",
+      "value": "-  ",
+      "tooltip": "Degrees mode",
+      "active": false,
+      "toggleCommand": "toggle_degrees",
+    }));
+  }
+
+  #[test]
+  fn test_command_timing_payload() {
+    let payload = CommandTimingPayload::new("fit", Duration::from_secs_f64(1.2345));
+    assert_eq!(serde_json::to_value(payload).unwrap(), serde_json::json!({
+      "commandName": "fit",
+      "elapsedMillis": 1234,
+    }));
+  }
+
+  #[test]
+  fn test_computation_progress_payload() {
+    let payload = ComputationProgressPayload {
+      job_id: 3,
+      command_name: String::from("det"),
+      status: ComputationStatus::Running,
+      steps: 120,
+      elapsed_millis: 2500,
+    };
+    assert_eq!(serde_json::to_value(payload).unwrap(), serde_json::json!({
+      "jobId": 3,
+      "commandName": "det",
+      "status": "running",
+      "steps": 120,
+      "elapsedMillis": 2500,
+    }));
+  }
+
+  #[test]
+  fn test_undo_availability_only_goes_to_main_window() {
+    assert!(UndoAvailabilityPayload::is_routed_to(WindowKind::Main));
+    assert!(!UndoAvailabilityPayload::is_routed_to(WindowKind::Stack));
+    assert!(!UndoAvailabilityPayload::is_routed_to(WindowKind::Graph));
+  }
 }
@@ -0,0 +1,222 @@
+
+//! Collapsible groups of entries on the main stack.
+
+use serde::{Serialize, Deserialize};
+
+use std::ops::Range;
+
+/// A titled group of contiguous stack entries. Each entry in the
+/// group carries a copy of this value, and the `id` distinguishes two
+/// adjacent groups that happen to share a title.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackGroup {
+  id: u64,
+  title: String,
+}
+
+/// Group membership for the entries of the main stack, stored in
+/// parallel to the stack itself in the same way as
+/// [`StackLabels`](super::labels::StackLabels). All indices here are
+/// counted from the top of the stack.
+///
+/// A group is a maximal run of adjacent entries with the same
+/// [`StackGroup`]. Pushing a new value into the middle of a group
+/// leaves the new value ungrouped, which splits the group into two
+/// runs.
+///
+/// As with labels, the store may temporarily be out of sync with the
+/// length of the stack. Missing positions are treated as ungrouped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StackGroups {
+  /// Groups from the bottom of the stack to the top.
+  groups: Vec<Option<StackGroup>>,
+}
+
+impl StackGroup {
+  pub fn title(&self) -> &str {
+    &self.title
+  }
+}
+
+impl StackGroups {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// True if no entry belongs to a group.
+  pub fn is_empty(&self) -> bool {
+    self.groups.iter().all(Option::is_none)
+  }
+
+  pub fn get(&self, index: usize) -> Option<&StackGroup> {
+    let position = self.groups.len().checked_sub(index + 1)?;
+    self.groups[position].as_ref()
+  }
+
+  /// Sets or clears the group at the given position, returning the
+  /// old group.
+  pub fn set(&mut self, index: usize, group: Option<StackGroup>) -> Option<StackGroup> {
+    if self.groups.len() <= index {
+      // Clearing a group that doesn't exist is a no-op.
+      group.as_ref()?;
+      self.pad_to(index + 1);
+    }
+    let position = self.groups.len() - index - 1;
+    std::mem::replace(&mut self.groups[position], group)
+  }
+
+  /// A new group with the given title, distinct from every group
+  /// currently in use.
+  pub fn new_group(&self, title: String) -> StackGroup {
+    let id = self.groups.iter().flatten().map(|group| group.id + 1).max().unwrap_or(0);
+    StackGroup { id, title }
+  }
+
+  /// The positions of the group containing the entry at `index`, or
+  /// `None` if that entry is not in a group. As with all other
+  /// indices here, the range is counted from the top of the stack.
+  pub fn group_range(&self, index: usize) -> Option<Range<usize>> {
+    let group = self.get(index)?;
+    let mut start = index;
+    while start > 0 && self.get(start - 1) == Some(group) {
+      start -= 1;
+    }
+    let mut end = index + 1;
+    while self.get(end) == Some(group) {
+      end += 1;
+    }
+    Some(start..end)
+  }
+
+  /// Makes room for a new stack entry at the given position.
+  pub fn insert(&mut self, index: usize, group: Option<StackGroup>) {
+    self.pad_to(index);
+    let position = self.groups.len() - index;
+    self.groups.insert(position, group);
+  }
+
+  /// Removes the group slot for a stack entry that has been popped,
+  /// returning its group.
+  pub fn remove(&mut self, index: usize) -> Option<StackGroup> {
+    let position = self.groups.len().checked_sub(index + 1)?;
+    self.groups.remove(position)
+  }
+
+  /// The groups for a stack of the given length, from the bottom of
+  /// the stack to the top.
+  pub fn for_stack_len(&self, len: usize) -> Vec<Option<StackGroup>> {
+    let mut result = vec![None; len.saturating_sub(self.groups.len())];
+    let skip = self.groups.len().saturating_sub(len);
+    result.extend(self.groups[skip..].iter().cloned());
+    result
+  }
+
+  /// The groups for a stack of the given length, as runs of adjacent
+  /// entries. Unlike the other methods here, the ranges are positions
+  /// counted from the bottom of the stack, matching
+  /// [`for_stack_len`](StackGroups::for_stack_len).
+  pub fn runs_for_stack_len(&self, len: usize) -> Vec<(Range<usize>, StackGroup)> {
+    let mut runs: Vec<(Range<usize>, StackGroup)> = Vec::new();
+    for (position, group) in self.for_stack_len(len).into_iter().enumerate() {
+      let Some(group) = group else { continue };
+      match runs.last_mut() {
+        Some((range, last)) if range.end == position && *last == group => {
+          range.end += 1;
+        }
+        _ => {
+          runs.push((position..position + 1, group));
+        }
+      }
+    }
+    runs
+  }
+
+  fn pad_to(&mut self, len: usize) {
+    if self.groups.len() < len {
+      let padding = len - self.groups.len();
+      self.groups.splice(0..0, std::iter::repeat_n(None, padding));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_set_and_get() {
+    let mut groups = StackGroups::new();
+    assert!(groups.is_empty());
+    let group = groups.new_group("data".to_owned());
+    assert_eq!(groups.set(2, Some(group.clone())), None);
+    assert_eq!(groups.get(2), Some(&group));
+    assert_eq!(groups.get(0), None);
+    assert_eq!(groups.get(3), None);
+    assert_eq!(groups.set(2, None), Some(group));
+    assert!(groups.is_empty());
+    assert_eq!(groups.set(9, None), None);
+  }
+
+  #[test]
+  fn test_new_group_is_distinct() {
+    let mut groups = StackGroups::new();
+    let first = groups.new_group("data".to_owned());
+    groups.set(0, Some(first.clone()));
+    let second = groups.new_group("data".to_owned());
+    assert_ne!(first, second);
+    assert_eq!(second.title(), "data");
+  }
+
+  #[test]
+  fn test_group_range() {
+    let mut groups = StackGroups::new();
+    let first = groups.new_group("a".to_owned());
+    groups.set(1, Some(first.clone()));
+    groups.set(2, Some(first.clone()));
+    let second = groups.new_group("a".to_owned());
+    groups.set(3, Some(second));
+    assert_eq!(groups.group_range(0), None);
+    assert_eq!(groups.group_range(1), Some(1..3));
+    assert_eq!(groups.group_range(2), Some(1..3));
+    assert_eq!(groups.group_range(3), Some(3..4));
+    assert_eq!(groups.group_range(4), None);
+  }
+
+  #[test]
+  fn test_insert_splits_group() {
+    let mut groups = StackGroups::new();
+    let group = groups.new_group("a".to_owned());
+    groups.set(0, Some(group.clone()));
+    groups.set(1, Some(group.clone()));
+    groups.insert(1, None);
+    assert_eq!(groups.group_range(0), Some(0..1));
+    assert_eq!(groups.group_range(2), Some(2..3));
+    assert_eq!(groups.remove(1), None);
+    assert_eq!(groups.group_range(0), Some(0..2));
+  }
+
+  #[test]
+  fn test_runs_for_stack_len() {
+    let mut groups = StackGroups::new();
+    let first = groups.new_group("a".to_owned());
+    groups.set(0, Some(first.clone()));
+    groups.set(1, Some(first.clone()));
+    let second = groups.new_group("b".to_owned());
+    groups.set(2, Some(second.clone()));
+    groups.set(4, Some(second.clone()));
+    assert_eq!(
+      groups.runs_for_stack_len(6),
+      vec![(1..2, second.clone()), (3..4, second), (4..6, first)],
+    );
+  }
+
+  #[test]
+  fn test_for_stack_len_with_mismatched_length() {
+    let mut groups = StackGroups::new();
+    let group = groups.new_group("a".to_owned());
+    groups.set(0, Some(group.clone()));
+    assert_eq!(groups.for_stack_len(2), vec![None, Some(group.clone())]);
+    groups.set(3, Some(group.clone()));
+    assert_eq!(groups.for_stack_len(1), vec![Some(group)]);
+  }
+}
@@ -0,0 +1,137 @@
+
+//! Semantic hints about the engine state, which the frontend can use
+//! to color or badge the UI.
+
+use super::modeline::Modeline;
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::mode::calculation::AngleMode;
+
+use serde::Serialize;
+
+/// How the most recent command went.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorLevel {
+  /// The command succeeded without complaint, or no command has run
+  /// yet.
+  #[default]
+  Ok,
+  /// The command ran, but some subexpression could not be simplified
+  /// and produced an error message.
+  Warning,
+  /// The command failed, leaving the stack unchanged.
+  Error,
+}
+
+/// Whether the numbers on the stack are exact (integers and
+/// rationals) or approximate (floating-point values).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumericKind {
+  /// No stack entry contains a number.
+  None,
+  /// Every number on the stack is exact.
+  Exact,
+  /// Every number on the stack is approximate.
+  Float,
+  /// The stack contains both exact and approximate numbers.
+  Mixed,
+}
+
+/// Structured hints about the engine state, sent alongside (but
+/// independently of) the modeline text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeHints {
+  pub error_level: ErrorLevel,
+  pub numeric_kind: NumericKind,
+  pub degrees_mode: bool,
+  pub precision: Option<u32>,
+  /// The keys of every active modeline segment. See
+  /// [`Modeline::active_keys`].
+  pub active_modes: Vec<String>,
+}
+
+impl NumericKind {
+  /// Classifies all of the numbers appearing anywhere in the given
+  /// expressions.
+  pub fn of_exprs<'a>(exprs: impl IntoIterator<Item = &'a Expr>) -> Self {
+    let mut has_exact = false;
+    let mut has_float = false;
+    for expr in exprs {
+      scan_numbers(expr, &mut has_exact, &mut has_float);
+    }
+    match (has_exact, has_float) {
+      (false, false) => NumericKind::None,
+      (true, false) => NumericKind::Exact,
+      (false, true) => NumericKind::Float,
+      (true, true) => NumericKind::Mixed,
+    }
+  }
+}
+
+impl ThemeHints {
+  pub fn new(
+    error_level: ErrorLevel,
+    numeric_kind: NumericKind,
+    angle_mode: AngleMode,
+    precision: Option<u32>,
+    modeline: &Modeline,
+  ) -> Self {
+    ThemeHints {
+      error_level,
+      numeric_kind,
+      degrees_mode: angle_mode == AngleMode::Degrees,
+      precision,
+      active_modes: modeline.active_keys().map(str::to_owned).collect(),
+    }
+  }
+}
+
+fn scan_numbers(expr: &Expr, has_exact: &mut bool, has_float: &mut bool) {
+  match expr {
+    Expr::Atom(Atom::Number(n)) => {
+      if n.repr().is_exact() {
+        *has_exact = true;
+      } else {
+        *has_float = true;
+      }
+    }
+    Expr::Atom(_) => {}
+    Expr::Call(_, args) => {
+      for arg in args {
+        scan_numbers(arg, has_exact, has_float);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::state::modeline::ModelineBuilder;
+
+  #[test]
+  fn test_numeric_kind() {
+    assert_eq!(NumericKind::of_exprs(&[]), NumericKind::None);
+    assert_eq!(NumericKind::of_exprs(&[Expr::var("x").unwrap()]), NumericKind::None);
+    assert_eq!(NumericKind::of_exprs(&[Expr::from(1), Expr::from(2)]), NumericKind::Exact);
+    assert_eq!(NumericKind::of_exprs(&[Expr::from(1.5)]), NumericKind::Float);
+    let nested = Expr::call("+", vec![Expr::from(1), Expr::call("sin", vec![Expr::from(0.5)])]);
+    assert_eq!(NumericKind::of_exprs(&[nested]), NumericKind::Mixed);
+  }
+
+  #[test]
+  fn test_theme_hints() {
+    let modeline = ModelineBuilder::new()
+      .append("|")
+      .append_flag("Inf", true)
+      .append_flag("Deg", false)
+      .build();
+    let hints = ThemeHints::new(ErrorLevel::Warning, NumericKind::Exact, AngleMode::Degrees, Some(30), &modeline);
+    assert_eq!(hints.active_modes, vec!["Inf".to_owned()]);
+    assert!(hints.degrees_mode);
+    assert_eq!(hints.precision, Some(30));
+  }
+}
@@ -0,0 +1,151 @@
+
+//! A record of the commands the user has run during this session.
+
+use crate::command::options::CommandOptions;
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+/// The maximum number of entries kept in a [`CommandHistory`]. Once
+/// this is exceeded, the oldest entries are discarded.
+pub const MAX_HISTORY_LENGTH: usize = 10_000;
+
+/// The history of commands run in the current session, oldest first.
+/// Unlike the undo stack, the history is never rewound by an undo.
+#[derive(Debug, Clone, Default)]
+pub struct CommandHistory {
+  entries: VecDeque<HistoryEntry>,
+}
+
+/// A single command run by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+  pub command_name: String,
+  pub args: Vec<String>,
+  pub options: CommandOptions,
+  /// The top of the stack after the command ran, rendered in a
+  /// reversible language mode, or `None` if the stack was empty.
+  pub result: Option<String>,
+}
+
+impl CommandHistory {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &HistoryEntry> {
+    self.entries.iter()
+  }
+
+  pub fn clear(&mut self) {
+    self.entries.clear();
+  }
+
+  pub fn push(&mut self, entry: HistoryEntry) {
+    if self.entries.len() >= MAX_HISTORY_LENGTH {
+      self.entries.pop_front();
+    }
+    self.entries.push_back(entry);
+  }
+
+  /// Renders the history as a human-readable Markdown document.
+  pub fn to_markdown(&self) -> String {
+    let mut out = String::from("# Session transcript\n\n");
+    if self.entries.is_empty() {
+      out.push_str("_No commands were run._\n");
+      return out;
+    }
+    for (index, entry) in self.entries.iter().enumerate() {
+      // unwrap: impl Write for String doesn't fail.
+      write!(out, "{}. `{}`", index + 1, entry.describe_command()).unwrap();
+      match &entry.result {
+        Some(result) => writeln!(out, " → `{}`", result).unwrap(),
+        None => writeln!(out, " → (empty stack)").unwrap(),
+      }
+    }
+    out
+  }
+}
+
+impl HistoryEntry {
+  /// A short description of the command, including its arguments
+  /// and any modifiers.
+  pub fn describe_command(&self) -> String {
+    let mut out = String::new();
+    if let Some(argument) = self.options.argument {
+      write!(out, "[{argument}] ").unwrap();
+    }
+    let modifiers = [
+      (self.options.keep_modifier, "keep"),
+      (self.options.hyperbolic_modifier, "hyper"),
+      (self.options.inverse_modifier, "inv"),
+    ];
+    for (flag, name) in modifiers {
+      if flag {
+        write!(out, "{name} ").unwrap();
+      }
+    }
+    out.push_str(&self.command_name);
+    for arg in &self.args {
+      write!(out, " {arg}").unwrap();
+    }
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(name: &str, args: Vec<&str>, result: Option<&str>) -> HistoryEntry {
+    HistoryEntry {
+      command_name: name.to_owned(),
+      args: args.into_iter().map(String::from).collect(),
+      options: CommandOptions::default(),
+      result: result.map(String::from),
+    }
+  }
+
+  #[test]
+  fn test_describe_command() {
+    assert_eq!(entry("+", vec![], None).describe_command(), "+");
+    assert_eq!(entry("push_number", vec!["10"], None).describe_command(), "push_number 10");
+    let mut modified = entry("sin", vec![], None);
+    modified.options = CommandOptions::numerical(2).with_inverse_modifier().with_keep_modifier();
+    assert_eq!(modified.describe_command(), "[2] keep inv sin");
+  }
+
+  #[test]
+  fn test_to_markdown() {
+    let mut history = CommandHistory::new();
+    history.push(entry("push_number", vec!["2"], Some("2")));
+    history.push(entry("pop", vec![], None));
+    assert_eq!(
+      history.to_markdown(),
+      "# Session transcript\n\n1. `push_number 2` → `2`\n2. `pop` → (empty stack)\n",
+    );
+  }
+
+  #[test]
+  fn test_to_markdown_empty() {
+    assert_eq!(CommandHistory::new().to_markdown(), "# Session transcript\n\n_No commands were run._\n");
+  }
+
+  #[test]
+  fn test_history_is_capped() {
+    let mut history = CommandHistory::new();
+    for i in 0..MAX_HISTORY_LENGTH + 5 {
+      history.push(entry(&i.to_string(), vec![], None));
+    }
+    assert_eq!(history.len(), MAX_HISTORY_LENGTH);
+    assert_eq!(history.iter().next().unwrap().command_name, "5");
+  }
+}
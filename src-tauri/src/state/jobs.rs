@@ -0,0 +1,317 @@
+
+//! Background execution of expensive commands.
+//!
+//! Ordinary commands run while holding the application state mutex,
+//! which keeps the frontend from doing anything else until they
+//! finish. Commands which report [`Command::runs_in_background`]
+//! instead run on a worker thread, against a
+//! [`detached_copy`](ApplicationState::detached_copy) of the state.
+//! While the job runs, the frontend receives periodic
+//! [`ComputationProgressPayload`] events and may cancel the job. When
+//! the job finishes (and has not been cancelled), its result and undo
+//! history are merged back into the real state.
+//!
+//! Only one job runs at a time. Commands which would modify the state
+//! are refused until the job is done, since the job's result is
+//! computed from the state as it was when the job started. A
+//! cancelled job still counts as running until its worker thread
+//! notices the cancellation and stops, so that two workers never run
+//! at once.
+//!
+//! When both the state mutex and the job runner's lock are needed,
+//! the state mutex is always acquired first.
+//!
+//! [`Command::runs_in_background`]: crate::command::Command::runs_in_background
+
+use super::{ApplicationState, TauriApplicationState};
+use super::events::{ComputationProgressPayload, ComputationStatus, CommandTimingPayload, emit_to_windows};
+use super::history::HistoryEntry;
+use super::recovery::run_recoverably;
+use super::tauri_command::{report_command_result, handle_non_tauri_errors};
+use crate::command::CommandContext;
+use crate::command::options::CommandOptions;
+use crate::expr::simplifier::profiled_cancellable_default_simplifier;
+use crate::expr::simplifier::cancellable::CancellationToken;
+use crate::units::currency::CurrencyUnitParser;
+
+use tauri::Manager;
+use thiserror::Error;
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a running job reports its progress to the frontend.
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Tracks the job currently running in the background, if any.
+#[derive(Debug, Default)]
+pub struct JobRunner {
+  current: Mutex<Option<Job>>,
+  next_id: AtomicU64,
+}
+
+/// A command running in the background.
+#[derive(Debug, Clone)]
+pub struct Job {
+  id: u64,
+  command_name: String,
+  started_at: Instant,
+  token: Arc<CancellationToken>,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("A computation ({command_name}) is already in progress")]
+pub struct JobBusyError {
+  pub command_name: String,
+}
+
+impl JobRunner {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn lock_current(&self) -> MutexGuard<'_, Option<Job>> {
+    // The lock is never held across anything that can panic, but
+    // there is no harm in recovering if it is poisoned anyway.
+    self.current.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Registers a new job for the given command. Fails if a job is
+  /// already running, even if that job has been cancelled.
+  pub fn begin(&self, command_name: &str) -> Result<Job, JobBusyError> {
+    let mut current = self.lock_current();
+    if let Some(job) = current.as_ref() {
+      return Err(JobBusyError { command_name: job.command_name.clone() });
+    }
+    let job = Job {
+      id: self.next_id.fetch_add(1, Ordering::Relaxed),
+      command_name: command_name.to_owned(),
+      started_at: Instant::now(),
+      token: Arc::new(CancellationToken::new()),
+    };
+    *current = Some(job.clone());
+    Ok(job)
+  }
+
+  /// Fails if a job is running, even if that job has been
+  /// cancelled.
+  pub fn ensure_idle(&self) -> Result<(), JobBusyError> {
+    match self.lock_current().as_ref() {
+      None => Ok(()),
+      Some(job) => Err(JobBusyError { command_name: job.command_name.clone() }),
+    }
+  }
+
+  /// Whether `job` is still the running job, that is, it has neither
+  /// finished nor been cancelled.
+  pub fn is_current(&self, job: &Job) -> bool {
+    self.lock_current().as_ref().is_some_and(|current| current.id == job.id && !current.token.is_cancelled())
+  }
+
+  /// Cancels the running job, returning it. Returns `None` if no job
+  /// is running or the running job has already been cancelled.
+  ///
+  /// The job remains registered until its worker calls
+  /// [`JobRunner::finish`].
+  pub fn cancel(&self) -> Option<Job> {
+    let current = self.lock_current();
+    let job = current.as_ref().filter(|job| !job.token.is_cancelled())?;
+    job.token.cancel();
+    Some(job.clone())
+  }
+
+  /// Marks `job` as finished. Returns false if the job had been
+  /// cancelled, in which case its result should be discarded.
+  pub fn finish(&self, job: &Job) -> bool {
+    let mut current = self.lock_current();
+    if current.as_ref().is_some_and(|current| current.id == job.id) {
+      *current = None;
+      !job.token.is_cancelled()
+    } else {
+      false
+    }
+  }
+}
+
+impl Job {
+  pub fn id(&self) -> u64 {
+    self.id
+  }
+
+  pub fn command_name(&self) -> &str {
+    &self.command_name
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    self.started_at.elapsed()
+  }
+
+  pub fn progress(&self, status: ComputationStatus) -> ComputationProgressPayload {
+    ComputationProgressPayload {
+      job_id: self.id,
+      command_name: self.command_name.clone(),
+      status,
+      steps: self.token.steps(),
+      elapsed_millis: u64::try_from(self.elapsed().as_millis()).unwrap_or(u64::MAX),
+    }
+  }
+}
+
+/// Starts running the given command on a worker thread, against a
+/// detached copy of `state`. Returns as soon as the job has started.
+pub fn start_background_command(
+  state: &ApplicationState,
+  app_handle: &tauri::AppHandle,
+  jobs: &JobRunner,
+  command_name: &str,
+  args: Vec<String>,
+  opts: CommandOptions,
+) -> anyhow::Result<()> {
+  let job = jobs.begin(command_name)?;
+  let copy = state.detached_copy();
+  let worker_handle = app_handle.clone();
+  let worker_job = job.clone();
+  thread::spawn(move || {
+    run_job(&worker_handle, worker_job, copy, args, opts);
+  });
+  let reporter_handle = app_handle.clone();
+  let reporter_job = job.clone();
+  thread::spawn(move || {
+    report_progress(&reporter_handle, reporter_job);
+  });
+  emit_to_windows(app_handle, job.progress(ComputationStatus::Running))?;
+  Ok(())
+}
+
+/// Cancels the running job, if there is one, and informs the
+/// frontend. The state is left exactly as it was when the job
+/// started.
+pub fn cancel_background_command(app_handle: &tauri::AppHandle, jobs: &JobRunner) -> tauri::Result<()> {
+  if let Some(job) = jobs.cancel() {
+    emit_to_windows(app_handle, job.progress(ComputationStatus::Cancelled))?;
+  }
+  Ok(())
+}
+
+/// The body of a job's worker thread.
+fn run_job(app_handle: &tauri::AppHandle, job: Job, mut copy: ApplicationState, args: Vec<String>, opts: CommandOptions) {
+  let app_state = app_handle.state::<TauriApplicationState>();
+  let history_entry = HistoryEntry {
+    command_name: job.command_name.clone(),
+    args: args.clone(),
+    options: opts.clone(),
+    result: None,
+  };
+  let units_parser = CurrencyUnitParser::new(app_state.units_parser.as_ref(), copy.currency_rates().clone());
+  let simplifier = profiled_cancellable_default_simplifier(
+    &app_state.function_table,
+    copy.user_functions().clone(),
+    copy.rewrite_rules().clone(),
+    copy.assumptions().clone(),
+    copy.derivative_rules().clone(),
+    &app_state.simplifier_profile,
+    Arc::clone(&job.token),
+  );
+  let command_context = CommandContext {
+    opts,
+    simplifier,
+    units_parser: &units_parser,
+    dispatch_table: &app_state.command_table,
+    function_table: &app_state.function_table,
+    nesting_depth: 0,
+  };
+  let result = run_recoverably(&mut copy, |copy| {
+    let command = app_state.command_table.get(&job.command_name)?;
+    command.run_command(copy, args, &command_context)
+  });
+  let command_timing = CommandTimingPayload::new(&job.command_name, job.elapsed());
+
+  let mut state = app_state.lock_state();
+  if !app_state.jobs.finish(&job) {
+    // Cancelled. The frontend was notified when that happened.
+    return;
+  }
+  state.merge_detached_copy(copy);
+  let result = emit_to_windows(app_handle, job.progress(ComputationStatus::Finished))
+    .map_err(anyhow::Error::from)
+    .and_then(|_| report_command_result(&mut state, app_handle, history_entry, result, command_timing));
+  if let Err(err) = handle_non_tauri_errors(app_handle, result) {
+    // There is no Tauri command waiting on this thread, so there is
+    // nobody to pass the error along to.
+    eprintln!("Could not report the result of {}: {err}", job.command_name);
+  }
+  app_state.save_session(&state);
+}
+
+/// Sends progress events for `job` until it is no longer running.
+fn report_progress(app_handle: &tauri::AppHandle, job: Job) {
+  let app_state = app_handle.state::<TauriApplicationState>();
+  loop {
+    thread::sleep(PROGRESS_INTERVAL);
+    if !app_state.jobs.is_current(&job) {
+      break;
+    }
+    // If the window has closed in the meantime, there's nobody to
+    // notify, so errors are ignored.
+    let _ = emit_to_windows(app_handle, job.progress(ComputationStatus::Running));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::Expr;
+  use crate::stack::base::StackLike;
+  use crate::stack::test_utils::stack_of;
+  use crate::state::test_utils::state_for_stack;
+
+  #[test]
+  fn test_one_job_at_a_time() {
+    let jobs = JobRunner::new();
+    assert!(jobs.ensure_idle().is_ok());
+    let job = jobs.begin("det").unwrap();
+    assert!(jobs.is_current(&job));
+    assert_eq!(jobs.begin("integrate").unwrap_err(), JobBusyError { command_name: String::from("det") });
+    assert_eq!(jobs.ensure_idle().unwrap_err().to_string(), "A computation (det) is already in progress");
+
+    assert!(jobs.finish(&job));
+    assert!(!jobs.is_current(&job));
+    assert!(jobs.ensure_idle().is_ok());
+    let next_job = jobs.begin("integrate").unwrap();
+    assert_ne!(next_job.id(), job.id());
+  }
+
+  #[test]
+  fn test_cancel() {
+    let jobs = JobRunner::new();
+    assert!(jobs.cancel().is_none());
+    let job = jobs.begin("det").unwrap();
+    let cancelled = jobs.cancel().unwrap();
+    assert_eq!(cancelled.id(), job.id());
+    assert!(job.token.is_cancelled());
+    assert!(!jobs.is_current(&job));
+    assert!(jobs.cancel().is_none());
+    // No new job may start until the cancelled job's worker stops.
+    assert!(jobs.ensure_idle().is_err());
+    assert!(jobs.begin("integrate").is_err());
+    // The worker must not apply the result of a cancelled job.
+    assert!(!jobs.finish(&job));
+    assert!(jobs.ensure_idle().is_ok());
+    assert!(!jobs.finish(&job));
+  }
+
+  #[test]
+  fn test_merge_detached_copy() {
+    let mut state = state_for_stack(vec![1, 2]);
+    let mut copy = state.detached_copy();
+    copy.undo_stack_mut().push_cut();
+    copy.main_stack_mut().push(Expr::from(3));
+    state.merge_detached_copy(copy);
+    assert_eq!(state.main_stack(), &stack_of(vec![1, 2, 3]));
+
+    state.undo().unwrap();
+    assert_eq!(state.main_stack(), &stack_of(vec![1, 2]));
+  }
+}
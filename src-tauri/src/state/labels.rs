@@ -0,0 +1,135 @@
+
+//! Human-readable labels attached to entries of the main stack.
+
+use serde::{Serialize, Deserialize};
+
+/// Labels for the entries of the main stack, stored in parallel to
+/// the stack itself. Like the stack's own
+/// [`RandomAccessStackLike`](crate::stack::base::RandomAccessStackLike)
+/// methods, all indices here are counted from the top of the stack.
+///
+/// The labels are kept aligned with the stack by
+/// [`UndoingDelegate`](super::delegate::UndoingDelegate), so a label
+/// follows its entry as other values are pushed and popped around
+/// it. If the stack is modified without going through the delegate,
+/// the label store may temporarily be shorter or longer than the
+/// stack. Every method here tolerates that, treating missing
+/// positions as unlabeled.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StackLabels {
+  /// Labels from the bottom of the stack to the top.
+  labels: Vec<Option<String>>,
+}
+
+impl StackLabels {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// True if no entry has a label.
+  pub fn is_empty(&self) -> bool {
+    self.labels.iter().all(Option::is_none)
+  }
+
+  pub fn get(&self, index: usize) -> Option<&str> {
+    let position = self.labels.len().checked_sub(index + 1)?;
+    self.labels[position].as_deref()
+  }
+
+  /// Sets or clears the label at the given position, returning the
+  /// old label.
+  pub fn set(&mut self, index: usize, label: Option<String>) -> Option<String> {
+    if self.labels.len() <= index {
+      // Clearing a label that doesn't exist is a no-op.
+      label.as_ref()?;
+      self.pad_to(index + 1);
+    }
+    let position = self.labels.len() - index - 1;
+    std::mem::replace(&mut self.labels[position], label)
+  }
+
+  /// Makes room for a new stack entry at the given position.
+  pub fn insert(&mut self, index: usize, label: Option<String>) {
+    self.pad_to(index);
+    let position = self.labels.len() - index;
+    self.labels.insert(position, label);
+  }
+
+  /// Removes the label slot for a stack entry that has been popped,
+  /// returning its label.
+  pub fn remove(&mut self, index: usize) -> Option<String> {
+    let position = self.labels.len().checked_sub(index + 1)?;
+    self.labels.remove(position)
+  }
+
+  /// The labels for a stack of the given length, from the bottom of
+  /// the stack to the top.
+  pub fn for_stack_len(&self, len: usize) -> Vec<Option<String>> {
+    let mut result = vec![None; len.saturating_sub(self.labels.len())];
+    let skip = self.labels.len().saturating_sub(len);
+    result.extend(self.labels[skip..].iter().cloned());
+    result
+  }
+
+  fn pad_to(&mut self, len: usize) {
+    if self.labels.len() < len {
+      let padding = len - self.labels.len();
+      self.labels.splice(0..0, std::iter::repeat_n(None, padding));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn label(s: &str) -> Option<String> {
+    Some(s.to_owned())
+  }
+
+  #[test]
+  fn test_set_and_get() {
+    let mut labels = StackLabels::new();
+    assert!(labels.is_empty());
+    assert_eq!(labels.set(2, label("a")), None);
+    assert_eq!(labels.get(2), Some("a"));
+    assert_eq!(labels.get(0), None);
+    assert_eq!(labels.get(3), None);
+    assert_eq!(labels.set(2, None), label("a"));
+    assert!(labels.is_empty());
+  }
+
+  #[test]
+  fn test_labels_follow_entries() {
+    let mut labels = StackLabels::new();
+    labels.insert(0, None);
+    labels.insert(0, label("a"));
+    labels.insert(0, None);
+    assert_eq!(labels.for_stack_len(3), vec![None, label("a"), None]);
+    assert_eq!(labels.remove(0), None);
+    assert_eq!(labels.get(0), Some("a"));
+    labels.insert(1, label("b"));
+    assert_eq!(labels.for_stack_len(3), vec![None, label("b"), label("a")]);
+    assert_eq!(labels.remove(0), label("a"));
+  }
+
+  #[test]
+  fn test_for_stack_len_with_mismatched_length() {
+    let mut labels = StackLabels::new();
+    labels.set(0, label("top"));
+    assert_eq!(labels.for_stack_len(3), vec![None, None, label("top")]);
+    labels.set(3, label("deep"));
+    assert_eq!(labels.for_stack_len(2), vec![None, label("top")]);
+    assert_eq!(labels.for_stack_len(0), Vec::<Option<String>>::new());
+  }
+
+  #[test]
+  fn test_remove_out_of_bounds() {
+    let mut labels = StackLabels::new();
+    assert_eq!(labels.remove(0), None);
+    labels.insert(3, label("a"));
+    assert_eq!(labels.get(3), Some("a"));
+    assert_eq!(labels.remove(3), label("a"));
+  }
+}
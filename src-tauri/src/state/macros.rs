@@ -0,0 +1,108 @@
+
+//! Keyboard macros, which record a sequence of commands as the user
+//! runs them so that the sequence can be replayed later.
+
+use crate::command::program::ProgramStep;
+
+use thiserror::Error;
+
+/// The names of the commands which control macro recording. These
+/// are never recorded as part of a macro themselves.
+pub const MACRO_COMMAND_NAMES: [&str; 3] = ["start_macro_record", "end_macro_record", "play_macro"];
+
+/// The macro currently being recorded, if any, and the most recently
+/// recorded macro.
+#[derive(Debug, Clone, Default)]
+pub struct MacroRecorder {
+  in_progress: Option<Vec<ProgramStep>>,
+  last_macro: Option<Vec<ProgramStep>>,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MacroError {
+  #[error("Already recording a macro")]
+  AlreadyRecording,
+  #[error("Not recording a macro")]
+  NotRecording,
+  #[error("Cannot play a macro while recording one")]
+  PlayWhileRecording,
+  #[error("No macro has been recorded")]
+  NoMacro,
+}
+
+impl MacroRecorder {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn is_recording(&self) -> bool {
+    self.in_progress.is_some()
+  }
+
+  /// The most recently completed macro, if any.
+  pub fn last_macro(&self) -> Option<&[ProgramStep]> {
+    self.last_macro.as_deref()
+  }
+
+  pub fn start_recording(&mut self) -> Result<(), MacroError> {
+    if self.is_recording() {
+      return Err(MacroError::AlreadyRecording);
+    }
+    self.in_progress = Some(Vec::new());
+    Ok(())
+  }
+
+  /// Finishes the macro being recorded, which replaces the previous
+  /// macro. Returns the number of steps recorded.
+  pub fn end_recording(&mut self) -> Result<usize, MacroError> {
+    let steps = self.in_progress.take().ok_or(MacroError::NotRecording)?;
+    let len = steps.len();
+    self.last_macro = Some(steps);
+    Ok(len)
+  }
+
+  /// Appends a step to the macro being recorded. Does nothing if no
+  /// macro is being recorded, or if the step is itself one of the
+  /// macro commands.
+  pub fn record(&mut self, step: ProgramStep) {
+    if let Some(steps) = &mut self.in_progress {
+      if !MACRO_COMMAND_NAMES.contains(&step.name.as_str()) {
+        steps.push(step);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_record_macro() {
+    let mut recorder = MacroRecorder::new();
+    recorder.record(ProgramStep::new("dup"));
+    recorder.start_recording().unwrap();
+    assert_eq!(recorder.start_recording(), Err(MacroError::AlreadyRecording));
+    recorder.record(ProgramStep::new("+"));
+    recorder.record(ProgramStep::new("play_macro"));
+    recorder.record(ProgramStep::new("negate"));
+    assert_eq!(recorder.last_macro(), None);
+    assert_eq!(recorder.end_recording(), Ok(2));
+    assert_eq!(recorder.last_macro(), Some(&[ProgramStep::new("+"), ProgramStep::new("negate")][..]));
+    assert_eq!(recorder.end_recording(), Err(MacroError::NotRecording));
+  }
+
+  #[test]
+  fn test_new_recording_keeps_last_macro_until_finished() {
+    let mut recorder = MacroRecorder::new();
+    recorder.start_recording().unwrap();
+    recorder.record(ProgramStep::new("+"));
+    recorder.end_recording().unwrap();
+    recorder.start_recording().unwrap();
+    recorder.record(ProgramStep::new("*"));
+    assert_eq!(recorder.last_macro(), Some(&[ProgramStep::new("+")][..]));
+    recorder.end_recording().unwrap();
+    assert_eq!(recorder.last_macro(), Some(&[ProgramStep::new("*")][..]));
+  }
+}
@@ -1,56 +1,118 @@
 
 //! Backend application state manager.
 
+pub mod bookmarks;
 pub mod delegate;
+pub mod error_throttle;
 pub mod events;
+pub mod groups;
+pub mod hints;
+pub mod history;
+pub mod jobs;
+pub mod labels;
+pub mod macros;
 pub mod modeline;
+pub mod persistence;
+pub mod pins;
 pub mod query;
+pub mod recovery;
+pub mod stats;
+pub mod stopwatch;
 pub mod tauri_command;
 pub mod undo;
 pub mod validation;
-
-use events::{RefreshStackPayload, UndoAvailabilityPayload, ModelinePayload};
-use delegate::UndoingDelegate;
-use modeline::{ModelineBuilder, LanguageModeValue, boolean_flag};
+pub mod windows;
+
+use events::{RefreshStackPayload, StackGroupPayload, CommandTimingPayload, UndoAvailabilityPayload, ModelinePayload, ModeIndicatorPayload, ThemeHintsPayload, ProgramStepPayload, emit_to_windows};
+use bookmarks::BookmarkTable;
+use error_throttle::ErrorThrottle;
+use delegate::{UndoingDelegate, AltStackUndoingDelegate};
+use groups::StackGroups;
+use hints::{ErrorLevel, NumericKind, ThemeHints};
+use history::CommandHistory;
+use jobs::JobRunner;
+use labels::StackLabels;
+use macros::MacroRecorder;
+use persistence::{SessionStore, SessionSnapshot};
+use pins::StackPins;
+use stopwatch::Stopwatch;
+use modeline::{Modeline, ModelineBuilder, LanguageModeValue, PrecisionValue, WordSizeValue};
 use crate::stack::{Stack, DelegatingStack};
+use crate::stack::base::StackLike;
 use crate::expr::Expr;
 use crate::expr::function::table::FunctionTable;
+use crate::expr::function::user::UserFunctionTable;
+use crate::expr::calculus::DerivativeRuleTable;
+use crate::expr::rewrite::RewriteRuleTable;
+use crate::expr::assumptions::AssumptionTable;
+use crate::expr::simplifier::profile::SimplifierProfile;
 use crate::expr::function::library::build_function_table;
 use crate::expr::var::table::VarTable;
 use crate::expr::var::constants::bind_constants;
 use crate::expr::number::Number;
 use crate::command::default_dispatch_table;
 use crate::command::dispatch::CommandDispatchTable;
+use crate::command::program::ProgramSession;
 use crate::mode::display::DisplaySettings;
-use crate::mode::calculation::CalculationMode;
-use crate::undo::{UndoStack, UndoError};
+use crate::mode::display::spoken::to_spoken_text;
+use crate::mode::calculation::{CalculationMode, BranchCut, AngleMode};
+use crate::undo::{UndoStack, UndoError, UndoMark, UndoLimits};
 use crate::units::parsing::{UnitParser, default_parser};
+use crate::units::currency::CurrencyRateTable;
+use crate::units::context::ConversionContextTable;
 
 use serde::{Serialize, Deserialize};
 
-use tauri::Manager;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
-use std::sync::Mutex;
+/// The limits on the undo history on first launch. These keep the
+/// history from growing without bound over a long session.
+pub const DEFAULT_UNDO_LIMITS: UndoLimits = UndoLimits {
+  max_depth: Some(1000),
+  max_bytes: Some(256 * 1024 * 1024),
+};
 
 pub struct TauriApplicationState {
   pub state: Mutex<ApplicationState>,
   pub command_table: CommandDispatchTable,
   pub function_table: FunctionTable,
   pub units_parser: Box<dyn UnitParser<Number> + Send + Sync>,
+  pub session_store: SessionStore,
+  pub simplifier_profile: SimplifierProfile,
+  pub jobs: JobRunner,
+  /// The throttle through which all errors shown to the user pass.
+  /// See [`events::show_error`].
+  pub error_throttle: Mutex<ErrorThrottle>,
 }
 
 #[derive(Default)]
 pub struct ApplicationState {
   undoable_state: UndoableState,
   undo_stack: UndoStack<UndoableState>,
+  program_session: Option<ProgramSession>,
+  history: CommandHistory,
+  macro_recorder: MacroRecorder,
+  stopwatch: Stopwatch,
+  currency_rates: CurrencyRateTable<Number>,
+  last_error_level: ErrorLevel,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct UndoableState {
   main_stack: Stack<Expr>,
+  stack_labels: StackLabels,
+  stack_pins: StackPins,
+  stack_groups: StackGroups,
+  alt_stack: Stack<Expr>,
   display_settings: DisplaySettings,
   calculation_mode: CalculationMode,
   variables: VarTable<Expr>,
+  bookmarks: BookmarkTable,
+  user_functions: UserFunctionTable,
+  derivative_rules: DerivativeRuleTable,
+  rewrite_rules: RewriteRuleTable,
+  assumptions: AssumptionTable,
+  conversion_contexts: ConversionContextTable,
 }
 
 /// Direction of an undo command issued to Tauri.
@@ -61,17 +123,60 @@ pub enum UndoDirection {
 }
 
 impl TauriApplicationState {
+  /// Builds the application state, restoring the previous session
+  /// from the default [`SessionStore`] if there is one.
   pub fn with_default_tables() -> Self {
-    let state = {
-      let mut state = ApplicationState::default();
-      bind_constants(state.variable_table_mut());
-      state
+    let session_store = SessionStore::at_default_location();
+    let state = match session_store.load() {
+      Ok(Some(snapshot)) => snapshot.restore(),
+      Ok(None) => ApplicationState::with_constants(),
+      Err(err) => {
+        eprintln!("Could not restore previous session: {err}");
+        // Keep the unreadable file around rather than overwriting it
+        // with the next save.
+        match session_store.back_up_session_file() {
+          Ok(Some(backup_path)) => eprintln!("Moved unreadable session file to {}", backup_path.display()),
+          Ok(None) => {}
+          Err(err) => eprintln!("Could not back up unreadable session file: {err}"),
+        }
+        ApplicationState::with_constants()
+      }
     };
     Self {
       state: Mutex::new(state),
       command_table: default_dispatch_table(),
       function_table: build_function_table(),
       units_parser: Box::new(default_parser()),
+      session_store,
+      simplifier_profile: SimplifierProfile::new(),
+      jobs: JobRunner::new(),
+      error_throttle: Mutex::new(ErrorThrottle::new()),
+    }
+  }
+}
+
+impl TauriApplicationState {
+  /// Locks the application state. Commands are run with
+  /// [`recovery::run_recoverably`], so the mutex should never be
+  /// poisoned. If it is anyway, we recover the state and clear the
+  /// poison rather than taking down the whole application.
+  pub fn lock_state(&self) -> MutexGuard<'_, ApplicationState> {
+    self.state.lock().unwrap_or_else(|err: PoisonError<_>| {
+      self.state.clear_poison();
+      err.into_inner()
+    })
+  }
+
+  /// Schedules the given state to be saved as the current session.
+  pub fn save_session(&self, state: &ApplicationState) {
+    self.session_store.save_debounced(SessionSnapshot::capture(state));
+  }
+
+  /// Saves the given state as the current session immediately. See
+  /// [`SessionStore::save_now`].
+  pub fn save_session_now(&self, state: &ApplicationState) {
+    if let Err(err) = self.session_store.save_now(&SessionSnapshot::capture(state)) {
+      eprintln!("Could not save session: {err}");
     }
   }
 }
@@ -81,16 +186,43 @@ impl ApplicationState {
     Self::default()
   }
 
+  /// A new state with the built-in constants bound and the default
+  /// undo limits, as on first launch.
+  pub fn with_constants() -> Self {
+    let mut state = Self::default();
+    bind_constants(state.variable_table_mut());
+    state.undo_stack.set_limits(DEFAULT_UNDO_LIMITS);
+    state
+  }
+
   pub fn send_refresh_stack_event(
     &self,
     app_handle: &tauri::AppHandle,
     force_scroll_down: bool,
+    command_timing: Option<CommandTimingPayload>,
   ) -> tauri::Result<()> {
     let state = &self.undoable_state;
     let displayed_stack: Vec<String> =
       state.main_stack.iter().map(|expr| state.display_settings.to_html(expr)).collect();
-    let payload = RefreshStackPayload { stack: displayed_stack, force_scroll_down };
-    app_handle.emit(RefreshStackPayload::EVENT_NAME, payload)
+    let spoken_stack = state.display_settings.is_spoken_text_enabled.then(|| {
+      state.main_stack.iter().map(to_spoken_text).collect()
+    });
+    let labels = state.stack_labels.for_stack_len(state.main_stack.len());
+    let pinned = state.stack_pins.for_stack_len(state.main_stack.len());
+    let groups = state.stack_groups.runs_for_stack_len(state.main_stack.len())
+      .into_iter()
+      .map(|(range, group)| StackGroupPayload { title: group.title().to_owned(), start: range.start, length: range.len() })
+      .collect();
+    let payload = RefreshStackPayload {
+      stack: displayed_stack,
+      labels,
+      pinned,
+      groups,
+      spoken_stack,
+      force_scroll_down,
+      command_timing,
+    };
+    emit_to_windows(app_handle, payload)
   }
 
   pub fn send_undo_buttons_event(&self, app_handle: &tauri::AppHandle) -> tauri::Result<()> {
@@ -98,37 +230,138 @@ impl ApplicationState {
       has_undos: self.undo_stack.has_undos(),
       has_redos: self.undo_stack.has_redos(),
     };
-    app_handle.emit(UndoAvailabilityPayload::EVENT_NAME, payload)
+    emit_to_windows(app_handle, payload)
   }
 
   pub fn send_modeline_event(&self, app_handle: &tauri::AppHandle) -> tauri::Result<()> {
+    let modeline = self.modeline();
     let payload = ModelinePayload {
-      modeline_text: self.modeline(),
+      modeline_text: modeline.render(),
+      indicators: modeline.segments().iter().map(ModeIndicatorPayload::from).collect(),
     };
-    app_handle.emit(ModelinePayload::EVENT_NAME, payload)
+    emit_to_windows(app_handle, payload)
   }
 
-  pub fn send_all_updates(&self, app_handle: &tauri::AppHandle, force_scroll_down: bool) -> tauri::Result<()> {
-    self.send_refresh_stack_event(app_handle, force_scroll_down)?;
+  pub fn send_theme_hints_event(&self, app_handle: &tauri::AppHandle) -> tauri::Result<()> {
+    let payload = ThemeHintsPayload {
+      hints: self.theme_hints(),
+    };
+    emit_to_windows(app_handle, payload)
+  }
+
+  /// Sends the state of the current program stepping session, if
+  /// there is one. Does nothing if no program is being stepped
+  /// through.
+  pub fn send_program_step_event(&self, app_handle: &tauri::AppHandle) -> tauri::Result<()> {
+    let Some(session) = &self.program_session else {
+      return Ok(());
+    };
+    let state = &self.undoable_state;
+    let payload = ProgramStepPayload {
+      upcoming_command: session.upcoming().map(|instruction| instruction.name().to_owned()),
+      step_index: session.next_index(),
+      step_count: session.program().len(),
+      stack: state.main_stack.iter().map(|expr| state.display_settings.to_html(expr)).collect(),
+    };
+    emit_to_windows(app_handle, payload)
+  }
+
+  /// Sends every state update event to the frontend. If the updates
+  /// are the result of running a command, `command_timing` reports
+  /// how long that command took.
+  pub fn send_all_updates(
+    &self,
+    app_handle: &tauri::AppHandle,
+    force_scroll_down: bool,
+    command_timing: Option<CommandTimingPayload>,
+  ) -> tauri::Result<()> {
+    self.send_refresh_stack_event(app_handle, force_scroll_down, command_timing)?;
     self.send_undo_buttons_event(app_handle)?;
     self.send_modeline_event(app_handle)?;
+    self.send_theme_hints_event(app_handle)?;
+    self.send_program_step_event(app_handle)?;
     Ok(())
   }
 
   /// The modeline, which appears in teletype font at the bottom of
   /// the screen and indicates the current values of various
   /// user-specified flags.
-  pub fn modeline(&self) -> String {
+  pub fn modeline(&self) -> Modeline {
     ModelineBuilder::new()
-      .append(self.display_settings().language_settings.preferred_radix)
-      .append(boolean_flag("Inf", self.calculation_mode().has_infinity_flag()))
-      .append(boolean_flag("Fr", self.calculation_mode().has_fractional_flag()))
-      .append(LanguageModeValue::new(self.display_settings().base_language_mode.as_ref()))
-      .append(boolean_flag("U", self.display_settings().language_settings.prefers_unicode_output))
-      .append(boolean_flag("Gr", self.display_settings().is_graphics_enabled))
+      .append_keyed("radix", self.display_settings().language_settings.preferred_radix)
+      .with_tooltip("Display radix")
+      .append_flag("Inf", self.calculation_mode().has_infinity_flag())
+      .with_tooltip("Infinity mode")
+      .toggled_by("toggle_infinity")
+      .append_flag("Fr", self.calculation_mode().has_fractional_flag())
+      .with_tooltip("Fraction mode")
+      .toggled_by("toggle_fractional")
+      .append_flag("Ex", self.calculation_mode().has_exact_matrix_flag())
+      .with_tooltip("Exact matrix mode")
+      .toggled_by("toggle_exact_matrix")
+      .append_flag("Un", self.calculation_mode().has_units_flag())
+      .with_tooltip("Units simplification mode")
+      .toggled_by("toggle_units")
+      .append_flag("Dr", self.calculation_mode().has_derived_units_flag())
+      .with_tooltip("Derived units mode")
+      .toggled_by("toggle_derived_units")
+      .append_flag("Br", self.calculation_mode().branch_cut() == BranchCut::PositiveReal)
+      .with_tooltip("Branch cut along the positive real axis")
+      .toggled_by("toggle_branch_cut")
+      .append_flag("Deg", self.calculation_mode().angle_mode() == AngleMode::Degrees)
+      .with_tooltip("Degrees mode")
+      .toggled_by("toggle_degrees")
+      .append_flag("Mod", self.calculation_mode().modulus().is_some())
+      .with_tooltip("Modular arithmetic mode")
+      .append_keyed("precision", PrecisionValue::new(self.calculation_mode().precision()))
+      .with_tooltip("Arbitrary-precision mode")
+      .append_keyed("word_size", WordSizeValue::new(self.calculation_mode().word_size()))
+      .with_tooltip("Word size for bitwise operations")
+      .append_flag("2C", self.display_settings().language_settings.twos_complement)
+      .with_tooltip("Two's complement display")
+      .toggled_by("toggle_twos_complement")
+      .append_keyed("rounding", self.calculation_mode().rounding_mode())
+      .with_tooltip("Rounding mode")
+      .append_flag("Pol", self.display_settings().language_settings.polar_angle_mode.is_some())
+      .with_tooltip("Polar display mode")
+      .toggled_by("toggle_polar_display")
+      .append_keyed("language", LanguageModeValue::new(self.display_settings().base_language_mode.as_ref()))
+      .with_tooltip("Language mode")
+      .append_flag("U", self.display_settings().language_settings.prefers_unicode_output)
+      .with_tooltip("Unicode output")
+      .toggled_by("toggle_unicode")
+      .append_flag("()", self.display_settings().language_settings.always_parenthesize)
+      .with_tooltip("Always parenthesize")
+      .toggled_by("toggle_always_parenthesize")
+      .append_flag("Gr", self.display_settings().is_graphics_enabled)
+      .with_tooltip("Graphics mode")
+      .toggled_by("toggle_graphics")
+      .append_flag("Def", self.macro_recorder().is_recording())
+      .with_tooltip("Recording a macro")
       .build()
   }
 
+  /// Semantic hints about the current state, for theming the
+  /// frontend.
+  pub fn theme_hints(&self) -> ThemeHints {
+    ThemeHints::new(
+      self.last_error_level,
+      NumericKind::of_exprs(self.main_stack().iter()),
+      self.calculation_mode().angle_mode(),
+      self.calculation_mode().precision(),
+      &self.modeline(),
+    )
+  }
+
+  /// How the most recently run command went.
+  pub fn last_error_level(&self) -> ErrorLevel {
+    self.last_error_level
+  }
+
+  pub fn set_last_error_level(&mut self, level: ErrorLevel) {
+    self.last_error_level = level;
+  }
+
   pub fn display_settings(&self) -> &DisplaySettings {
     &self.undoable_state.display_settings
   }
@@ -153,6 +386,57 @@ impl ApplicationState {
     &mut self.undoable_state.variables
   }
 
+  pub fn bookmarks(&self) -> &BookmarkTable {
+    &self.undoable_state.bookmarks
+  }
+
+  pub fn bookmarks_mut(&mut self) -> &mut BookmarkTable {
+    &mut self.undoable_state.bookmarks
+  }
+
+  pub fn user_functions(&self) -> &UserFunctionTable {
+    &self.undoable_state.user_functions
+  }
+
+  pub fn user_functions_mut(&mut self) -> &mut UserFunctionTable {
+    &mut self.undoable_state.user_functions
+  }
+
+  pub fn derivative_rules(&self) -> &DerivativeRuleTable {
+    &self.undoable_state.derivative_rules
+  }
+
+  pub fn derivative_rules_mut(&mut self) -> &mut DerivativeRuleTable {
+    &mut self.undoable_state.derivative_rules
+  }
+
+  pub fn rewrite_rules(&self) -> &RewriteRuleTable {
+    &self.undoable_state.rewrite_rules
+  }
+
+  pub fn rewrite_rules_mut(&mut self) -> &mut RewriteRuleTable {
+    &mut self.undoable_state.rewrite_rules
+  }
+
+  /// The properties the user has declared for variables.
+  pub fn assumptions(&self) -> &AssumptionTable {
+    &self.undoable_state.assumptions
+  }
+
+  pub fn assumptions_mut(&mut self) -> &mut AssumptionTable {
+    &mut self.undoable_state.assumptions
+  }
+
+  /// The named conversion contexts the user has defined for
+  /// `convert_units_with_context`.
+  pub fn conversion_contexts(&self) -> &ConversionContextTable {
+    &self.undoable_state.conversion_contexts
+  }
+
+  pub fn conversion_contexts_mut(&mut self) -> &mut ConversionContextTable {
+    &mut self.undoable_state.conversion_contexts
+  }
+
   pub fn main_stack(&self) -> &Stack<Expr> {
     &self.undoable_state.main_stack
   }
@@ -170,11 +454,138 @@ impl ApplicationState {
 
   pub fn main_stack_mut(&mut self) -> DelegatingStack<'_, Stack<Expr>, UndoingDelegate<'_>> {
     DelegatingStack::new(
-      self.undoable_state.main_stack_mut(),
-      UndoingDelegate::new(&mut self.undo_stack),
+      &mut self.undoable_state.main_stack,
+      UndoingDelegate::new(
+        &mut self.undo_stack,
+        &mut self.undoable_state.stack_labels,
+        &mut self.undoable_state.stack_pins,
+        &mut self.undoable_state.stack_groups,
+      ),
+    )
+  }
+
+  /// The alternate stack, a secondary stack for stashing values out
+  /// of the way of the main stack, similar to Forth's return stack.
+  pub fn alt_stack(&self) -> &Stack<Expr> {
+    &self.undoable_state.alt_stack
+  }
+
+  /// Returns the alternate stack as a mutable reference, without any
+  /// undo semantics. As with
+  /// [`main_stack_mut_raw`](ApplicationState::main_stack_mut_raw),
+  /// the caller is responsible for the undo stack.
+  pub fn alt_stack_mut_raw(&mut self) -> &mut Stack<Expr> {
+    &mut self.undoable_state.alt_stack
+  }
+
+  pub fn alt_stack_mut(&mut self) -> DelegatingStack<'_, Stack<Expr>, AltStackUndoingDelegate<'_>> {
+    DelegatingStack::new(
+      &mut self.undoable_state.alt_stack,
+      AltStackUndoingDelegate::new(&mut self.undo_stack),
     )
   }
 
+  /// Labels attached to entries of the main stack.
+  pub fn stack_labels(&self) -> &StackLabels {
+    &self.undoable_state.stack_labels
+  }
+
+  pub fn stack_labels_mut(&mut self) -> &mut StackLabels {
+    &mut self.undoable_state.stack_labels
+  }
+
+  /// Pin flags on entries of the main stack.
+  pub fn stack_pins(&self) -> &StackPins {
+    &self.undoable_state.stack_pins
+  }
+
+  pub fn stack_pins_mut(&mut self) -> &mut StackPins {
+    &mut self.undoable_state.stack_pins
+  }
+
+  /// Collapsible groups of entries on the main stack.
+  pub fn stack_groups(&self) -> &StackGroups {
+    &self.undoable_state.stack_groups
+  }
+
+  pub fn stack_groups_mut(&mut self) -> &mut StackGroups {
+    &mut self.undoable_state.stack_groups
+  }
+
+  /// The commands the user has run this session.
+  pub fn history(&self) -> &CommandHistory {
+    &self.history
+  }
+
+  pub fn history_mut(&mut self) -> &mut CommandHistory {
+    &mut self.history
+  }
+
+  pub fn macro_recorder(&self) -> &MacroRecorder {
+    &self.macro_recorder
+  }
+
+  pub fn macro_recorder_mut(&mut self) -> &mut MacroRecorder {
+    &mut self.macro_recorder
+  }
+
+  pub fn stopwatch(&self) -> &Stopwatch {
+    &self.stopwatch
+  }
+
+  pub fn stopwatch_mut(&mut self) -> &mut Stopwatch {
+    &mut self.stopwatch
+  }
+
+  /// The exchange rates used for currency units. These are not part
+  /// of the undoable state, since they describe the outside world
+  /// rather than the user's calculation.
+  pub fn currency_rates(&self) -> &CurrencyRateTable<Number> {
+    &self.currency_rates
+  }
+
+  pub fn currency_rates_mut(&mut self) -> &mut CurrencyRateTable<Number> {
+    &mut self.currency_rates
+  }
+
+  /// The program currently being stepped through with the
+  /// `step_program` command, if any.
+  pub fn program_session(&self) -> Option<&ProgramSession> {
+    self.program_session.as_ref()
+  }
+
+  pub fn program_session_mut(&mut self) -> &mut Option<ProgramSession> {
+    &mut self.program_session
+  }
+
+  /// A copy of this state with an empty undo history, no program
+  /// session, no macros, and a stopped stopwatch. Useful for running commands whose effects should not
+  /// be visible to the user.
+  pub fn detached_copy(&self) -> ApplicationState {
+    ApplicationState {
+      undoable_state: self.undoable_state.clone(),
+      undo_stack: UndoStack::new(),
+      program_session: None,
+      history: CommandHistory::new(),
+      macro_recorder: MacroRecorder::new(),
+      stopwatch: Stopwatch::new(),
+      currency_rates: self.currency_rates.clone(),
+      last_error_level: ErrorLevel::Ok,
+    }
+  }
+
+  /// Adopts the undoable state of a [`detached_copy`] of this state,
+  /// together with every change recorded on the copy's undo stack,
+  /// so that the work done on the copy can be undone here. The
+  /// caller must ensure that this state has not changed since the
+  /// copy was made.
+  ///
+  /// [`detached_copy`]: ApplicationState::detached_copy
+  pub fn merge_detached_copy(&mut self, copy: ApplicationState) {
+    self.undoable_state = copy.undoable_state;
+    self.undo_stack.append(copy.undo_stack);
+  }
+
   pub fn into_main_stack(self) -> Stack<Expr> {
     self.undoable_state.main_stack
   }
@@ -187,6 +598,12 @@ impl ApplicationState {
     &mut self.undo_stack
   }
 
+  /// Reverts all undoable changes made since `mark` was taken. See
+  /// [`UndoStack::revert_to_mark`].
+  pub fn revert_to_undo_mark(&mut self, mark: UndoMark) {
+    self.undo_stack.revert_to_mark(mark, &mut self.undoable_state)
+  }
+
   pub fn undo(&mut self) -> Result<(), UndoError> {
     self.undo_stack.undo(&mut self.undoable_state)
   }
@@ -205,6 +622,38 @@ impl UndoableState {
     &mut self.main_stack
   }
 
+  pub fn alt_stack(&self) -> &Stack<Expr> {
+    &self.alt_stack
+  }
+
+  pub fn alt_stack_mut(&mut self) -> &mut Stack<Expr> {
+    &mut self.alt_stack
+  }
+
+  pub fn stack_labels(&self) -> &StackLabels {
+    &self.stack_labels
+  }
+
+  pub fn stack_labels_mut(&mut self) -> &mut StackLabels {
+    &mut self.stack_labels
+  }
+
+  pub fn stack_pins(&self) -> &StackPins {
+    &self.stack_pins
+  }
+
+  pub fn stack_pins_mut(&mut self) -> &mut StackPins {
+    &mut self.stack_pins
+  }
+
+  pub fn stack_groups(&self) -> &StackGroups {
+    &self.stack_groups
+  }
+
+  pub fn stack_groups_mut(&mut self) -> &mut StackGroups {
+    &mut self.stack_groups
+  }
+
   pub fn display_settings(&self) -> &DisplaySettings {
     &self.display_settings
   }
@@ -228,6 +677,54 @@ impl UndoableState {
   pub fn variable_table_mut(&mut self) -> &mut VarTable<Expr> {
     &mut self.variables
   }
+
+  pub fn bookmarks(&self) -> &BookmarkTable {
+    &self.bookmarks
+  }
+
+  pub fn bookmarks_mut(&mut self) -> &mut BookmarkTable {
+    &mut self.bookmarks
+  }
+
+  pub fn user_functions(&self) -> &UserFunctionTable {
+    &self.user_functions
+  }
+
+  pub fn user_functions_mut(&mut self) -> &mut UserFunctionTable {
+    &mut self.user_functions
+  }
+
+  pub fn derivative_rules(&self) -> &DerivativeRuleTable {
+    &self.derivative_rules
+  }
+
+  pub fn derivative_rules_mut(&mut self) -> &mut DerivativeRuleTable {
+    &mut self.derivative_rules
+  }
+
+  pub fn rewrite_rules(&self) -> &RewriteRuleTable {
+    &self.rewrite_rules
+  }
+
+  pub fn rewrite_rules_mut(&mut self) -> &mut RewriteRuleTable {
+    &mut self.rewrite_rules
+  }
+
+  pub fn assumptions(&self) -> &AssumptionTable {
+    &self.assumptions
+  }
+
+  pub fn assumptions_mut(&mut self) -> &mut AssumptionTable {
+    &mut self.assumptions
+  }
+
+  pub fn conversion_contexts(&self) -> &ConversionContextTable {
+    &self.conversion_contexts
+  }
+
+  pub fn conversion_contexts_mut(&mut self) -> &mut ConversionContextTable {
+    &mut self.conversion_contexts
+  }
 }
 
 #[cfg(test)]
@@ -6,6 +6,7 @@
 use crate::util::pad_or_trunc_str;
 use crate::util::radix::Radix;
 use crate::mode::display::language::LanguageMode;
+use crate::mode::calculation::{WordSize, RoundingMode};
 
 use std::fmt::Write;
 use std::borrow::Cow;
@@ -17,10 +18,35 @@ pub trait ModelineValue {
   fn contribute(&self, buf: &mut String);
 }
 
-/// Builder structure for modeline text.
+/// A modeline, as a sequence of segments. The segments can be
+/// rendered to a single line of text with [`Modeline::render`], or
+/// inspected individually.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Modeline {
+  segments: Vec<ModelineSegment>,
+}
+
+/// One piece of a [`Modeline`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelineSegment {
+  /// A stable name for the mode this segment indicates, or `None`
+  /// for decorative segments.
+  pub key: Option<&'static str>,
+  /// The text contributed to the rendered modeline.
+  pub text: String,
+  /// Whether the mode is active. Segments which do not represent an
+  /// on/off flag are always active.
+  pub active: bool,
+  /// A human-readable description of the mode, if any.
+  pub tooltip: Option<&'static str>,
+  /// The name of a nullary command which toggles the mode, if any.
+  pub toggle_command: Option<&'static str>,
+}
+
+/// Builder structure for a [`Modeline`].
 #[derive(Debug)]
 pub struct ModelineBuilder {
-  result_str: String,
+  segments: Vec<ModelineSegment>,
 }
 
 /// A [`ModelineValue`] which prints the user-friendly name of a
@@ -30,20 +56,94 @@ pub struct LanguageModeValue<'a> {
   desired_length: usize,
 }
 
+/// A [`ModelineValue`] which shows the precision of
+/// arbitrary-precision mode, as a fixed-width indicator. If the mode
+/// is off, this prints a dash, similar to [`boolean_flag`].
+#[derive(Debug, Clone, Copy)]
+pub struct PrecisionValue {
+  precision: Option<u32>,
+}
+
+/// A [`ModelineValue`] which shows the word size used by the bitwise
+/// functions, as a fixed-width indicator. If no word size is set,
+/// this prints a dash, similar to [`boolean_flag`].
+#[derive(Debug, Clone, Copy)]
+pub struct WordSizeValue {
+  word_size: Option<WordSize>,
+}
+
+impl Modeline {
+  pub fn segments(&self) -> &[ModelineSegment] {
+    &self.segments
+  }
+
+  /// Whether the segment with the given key is present and active.
+  pub fn is_active(&self, key: &str) -> bool {
+    self.segments.iter().any(|segment| segment.key == Some(key) && segment.active)
+  }
+
+  /// The keys of all active segments, in order.
+  pub fn active_keys(&self) -> impl Iterator<Item = &'static str> + '_ {
+    self.segments.iter()
+      .filter(|segment| segment.active)
+      .filter_map(|segment| segment.key)
+  }
+
+  /// Renders the modeline as a single line of text.
+  pub fn render(&self) -> String {
+    self.segments.iter().map(|segment| segment.text.as_str()).collect()
+  }
+}
+
 impl ModelineBuilder {
   pub fn new() -> Self {
-    ModelineBuilder { result_str: String::new() }
+    ModelineBuilder { segments: Vec::new() }
   }
 
-  /// Appends a value to the builder. Returns `self` after
+  /// Appends an unnamed value to the builder. Returns `self` after
   /// modifications.
-  pub fn append<V: ModelineValue>(mut self, value: V) -> Self {
-    value.contribute(&mut self.result_str);
+  pub fn append<V: ModelineValue>(self, value: V) -> Self {
+    self.push_segment(None, value, true)
+  }
+
+  /// Appends a value indicating the mode called `key`.
+  pub fn append_keyed<V: ModelineValue>(self, key: &'static str, value: V) -> Self {
+    self.push_segment(Some(key), value, true)
+  }
+
+  /// Appends an on/off flag, rendered as per [`boolean_flag`]. The
+  /// flag's text doubles as its key.
+  pub fn append_flag(self, key: &'static str, flag: bool) -> Self {
+    self.push_segment(Some(key), boolean_flag(key, flag), flag)
+  }
+
+  /// Sets the tooltip of the most recently appended segment. Does
+  /// nothing if no segments have been appended.
+  pub fn with_tooltip(mut self, tooltip: &'static str) -> Self {
+    if let Some(segment) = self.segments.last_mut() {
+      segment.tooltip = Some(tooltip);
+    }
+    self
+  }
+
+  /// Sets the toggle command of the most recently appended segment.
+  /// Does nothing if no segments have been appended.
+  pub fn toggled_by(mut self, command: &'static str) -> Self {
+    if let Some(segment) = self.segments.last_mut() {
+      segment.toggle_command = Some(command);
+    }
     self
   }
 
-  pub fn build(self) -> String {
-    self.result_str
+  pub fn build(self) -> Modeline {
+    Modeline { segments: self.segments }
+  }
+
+  fn push_segment<V: ModelineValue>(mut self, key: Option<&'static str>, value: V, active: bool) -> Self {
+    let mut text = String::new();
+    value.contribute(&mut text);
+    self.segments.push(ModelineSegment { key, text, active, tooltip: None, toggle_command: None });
+    self
   }
 }
 
@@ -71,6 +171,26 @@ impl<'a> AsRef<dyn LanguageMode + 'a> for LanguageModeValue<'a> {
   }
 }
 
+impl PrecisionValue {
+  /// Width of the indicator, which is wide enough to show any
+  /// precision up to [`MAX_PRECISION`](crate::expr::number::bigfloat::MAX_PRECISION).
+  pub const WIDTH: usize = 5;
+
+  pub fn new(precision: Option<u32>) -> Self {
+    PrecisionValue { precision }
+  }
+}
+
+impl WordSizeValue {
+  /// Width of the indicator, which is wide enough to show any
+  /// supported word size.
+  pub const WIDTH: usize = 3;
+
+  pub fn new(word_size: Option<WordSize>) -> Self {
+    WordSizeValue { word_size }
+  }
+}
+
 impl Default for ModelineBuilder {
   fn default() -> Self {
     ModelineBuilder::new()
@@ -117,6 +237,18 @@ impl ModelineValue for Radix {
   }
 }
 
+impl ModelineValue for RoundingMode {
+  fn contribute(&self, buf: &mut String) {
+    let abbreviation = match self {
+      RoundingMode::HalfUp => "HU",
+      RoundingMode::HalfEven => "HE",
+      RoundingMode::TowardZero => "TZ",
+      RoundingMode::AwayFromZero => "AZ",
+    };
+    buf.push_str(abbreviation);
+  }
+}
+
 impl<'a> ModelineValue for LanguageModeValue<'a> {
   fn contribute(&self, buf: &mut String) {
     let name = self.language_mode.language_mode_name();
@@ -125,6 +257,24 @@ impl<'a> ModelineValue for LanguageModeValue<'a> {
   }
 }
 
+impl ModelineValue for PrecisionValue {
+  fn contribute(&self, buf: &mut String) {
+    match self.precision {
+      Some(precision) => write!(buf, "{: <width$}", format!("P{}", precision), width = Self::WIDTH).unwrap(),
+      None => write!(buf, "-{: >width$}", "", width = Self::WIDTH - 1).unwrap(),
+    }
+  }
+}
+
+impl ModelineValue for WordSizeValue {
+  fn contribute(&self, buf: &mut String) {
+    match self.word_size {
+      Some(word_size) => write!(buf, "{: <width$}", word_size.to_string(), width = Self::WIDTH).unwrap(),
+      None => write!(buf, "-{: >width$}", "", width = Self::WIDTH - 1).unwrap(),
+    }
+  }
+}
+
 /// If the flag is true, then this function returns the given string.
 /// Otherwise, returns a single dash, with spaces padding to the
 /// length of the given string.
@@ -151,7 +301,38 @@ mod tests {
     let builder = ModelineBuilder::new()
       .append("ABC")
       .append("DEF");
-    assert_eq!(builder.build(), "ABCDEF");
+    assert_eq!(builder.build().render(), "ABCDEF");
+  }
+
+  #[test]
+  fn test_modeline_segments() {
+    let modeline = ModelineBuilder::new()
+      .append_keyed("radix", Radix::new(16))
+      .append_flag("Inf", true)
+      .append_flag("Deg", false)
+      .build();
+    assert_eq!(modeline.render(), "HexInf-  ");
+    assert!(modeline.is_active("Inf"));
+    assert!(!modeline.is_active("Deg"));
+    assert!(!modeline.is_active("Xyz"));
+    assert_eq!(modeline.active_keys().collect::<Vec<_>>(), vec!["radix", "Inf"]);
+    assert_eq!(modeline.segments()[2].text, "-  ");
+  }
+
+  #[test]
+  fn test_modeline_segment_annotations() {
+    let modeline = ModelineBuilder::new()
+      .with_tooltip("Ignored")
+      .append_flag("Inf", false)
+      .with_tooltip("Infinity mode")
+      .toggled_by("toggle_infinity")
+      .append_keyed("radix", Radix::new(10))
+      .build();
+    let segments = modeline.segments();
+    assert_eq!(segments[0].tooltip, Some("Infinity mode"));
+    assert_eq!(segments[0].toggle_command, Some("toggle_infinity"));
+    assert_eq!(segments[1].tooltip, None);
+    assert_eq!(segments[1].toggle_command, None);
   }
 
   #[test]
@@ -172,11 +353,46 @@ mod tests {
       .append(Radix::new(9))
       .append(Radix::new(11));
     assert_eq!(
-      builder.build(),
+      builder.build().render(),
       "DecHexBinOctR=9R11",
     );
   }
 
+  #[test]
+  fn test_contribute_precision() {
+    let builder = ModelineBuilder::new()
+      .append(PrecisionValue::new(Some(30)))
+      .append("|")
+      .append(PrecisionValue::new(None))
+      .append("|")
+      .append(PrecisionValue::new(Some(1000)));
+    assert_eq!(builder.build().render(), "P30  |-    |P1000");
+  }
+
+  #[test]
+  fn test_contribute_word_size() {
+    let builder = ModelineBuilder::new()
+      .append(WordSizeValue::new(Some(WordSize::new(8, false))))
+      .append("|")
+      .append(WordSizeValue::new(None))
+      .append("|")
+      .append(WordSizeValue::new(Some(WordSize::new(64, true))));
+    assert_eq!(builder.build().render(), "u8 |-  |i64");
+  }
+
+  #[test]
+  fn test_contribute_rounding_mode() {
+    let builder = ModelineBuilder::new()
+      .append(RoundingMode::HalfUp)
+      .append("|")
+      .append(RoundingMode::HalfEven)
+      .append("|")
+      .append(RoundingMode::TowardZero)
+      .append("|")
+      .append(RoundingMode::AwayFromZero);
+    assert_eq!(builder.build().render(), "HU|HE|TZ|AZ");
+  }
+
   #[test]
   fn test_boolean_flag() {
     assert_eq!(boolean_flag("", true), "");
@@ -0,0 +1,557 @@
+
+//! Automatic saving and restoring of the user's session across
+//! application restarts.
+//!
+//! After every command, a [`SessionSnapshot`] of the state is handed
+//! to the [`SessionStore`], which writes it to disk once commands
+//! have stopped arriving for [`SAVE_DEBOUNCE`]. At startup, the most
+//! recent snapshot is loaded back.
+//!
+//! Snapshots are stored as JSON, tagged with a schema version. When
+//! the schema changes, bump the version by adding an entry to
+//! [`MIGRATIONS`] which upgrades the previous version's JSON, so that
+//! older session files continue to load.
+
+use super::ApplicationState;
+use super::bookmarks::BookmarkTable;
+use super::groups::StackGroup;
+use crate::expr::Expr;
+use crate::expr::number::Number;
+use crate::expr::function::user::UserFunctionTable;
+use crate::expr::calculus::DerivativeRuleTable;
+use crate::expr::rewrite::RewriteRuleTable;
+use crate::expr::assumptions::AssumptionTable;
+use crate::expr::var::Var;
+use crate::expr::var::constants::RESERVED_NAMES;
+use crate::mode::calculation::CalculationMode;
+use crate::mode::display::DisplaySettings;
+use crate::mode::display::export::named_language_mode;
+use crate::mode::display::language::{LanguageMode, LanguageSettings};
+use crate::stack::Stack;
+use crate::stack::base::StackLike;
+use crate::units::currency::CurrencyRateTable;
+use crate::units::context::ConversionContextTable;
+
+use serde::{Serialize, Deserialize};
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Sender, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// How long the store waits for commands to stop arriving before
+/// writing the session to disk.
+pub const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The subdirectory of the platform's configuration directory in
+/// which the session is stored. Matches the Tauri bundle identifier.
+const APP_DIRECTORY_NAME: &str = "com.mercerenies.fifi";
+
+const SESSION_FILE_NAME: &str = "session.json";
+
+/// A function upgrading the JSON of one schema version to the next.
+type Migration = fn(Value) -> Result<Value, PersistenceError>;
+
+/// Schema migrations, in order. `MIGRATIONS[i]` upgrades a snapshot
+/// from version `i + 1` to version `i + 2`.
+const MIGRATIONS: &[Migration] = &[add_display_settings];
+
+/// The schema version written by this version of the application.
+pub const SESSION_SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PersistenceError {
+  #[error("I/O error: {0}")]
+  IoError(#[from] io::Error),
+  #[error("Malformed session file: {0}")]
+  JsonError(#[from] serde_json::Error),
+  #[error("Session file has no schema version")]
+  MissingVersion,
+  #[error("Session file has schema version {0}, but this version of the application only supports up to {SESSION_SCHEMA_VERSION}")]
+  UnsupportedVersion(u64),
+}
+
+/// The persisted portion of the application state. Built-in
+/// constants are not saved, since they are rebound at startup.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+  /// The main stack, from bottom to top.
+  stack: Vec<Expr>,
+  /// Labels on the stack entries, in the same order as `stack`.
+  #[serde(default)]
+  stack_labels: Vec<Option<String>>,
+  /// Pin flags on the stack entries, in the same order as `stack`.
+  #[serde(default)]
+  stack_pins: Vec<bool>,
+  /// Groups of the stack entries, in the same order as `stack`.
+  #[serde(default)]
+  stack_groups: Vec<Option<StackGroup>>,
+  /// The alternate stack, from bottom to top.
+  #[serde(default)]
+  alt_stack: Vec<Expr>,
+  variables: Vec<(Var, Expr)>,
+  bookmarks: Vec<(usize, Expr)>,
+  user_functions: UserFunctionTable,
+  #[serde(default)]
+  derivative_rules: DerivativeRuleTable,
+  #[serde(default)]
+  rewrite_rules: RewriteRuleTable,
+  #[serde(default)]
+  assumptions: AssumptionTable,
+  #[serde(default)]
+  conversion_contexts: ConversionContextTable,
+  calculation_mode: CalculationMode,
+  display_settings: DisplaySettingsSnapshot,
+  #[serde(default)]
+  currency_rates: CurrencyRateTable<Number>,
+}
+
+/// The persisted form of the [`DisplaySettings`]. The language mode
+/// is saved by name, since the language mode itself is a trait
+/// object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct DisplaySettingsSnapshot {
+  language_mode: String,
+  is_graphics_enabled: bool,
+  is_spoken_text_enabled: bool,
+  language_settings: LanguageSettings,
+}
+
+#[derive(Serialize)]
+struct VersionedSnapshot<'a> {
+  version: u64,
+  #[serde(flatten)]
+  snapshot: &'a SessionSnapshot,
+}
+
+/// Persists sessions to a file, writing on a background thread.
+#[derive(Debug)]
+pub struct SessionStore {
+  path: Option<PathBuf>,
+  writer: OnceLock<Sender<SessionSnapshot>>,
+}
+
+impl SessionSnapshot {
+  pub fn capture(state: &ApplicationState) -> Self {
+    let variables = state.variable_table().keys()
+      .filter(|var| !RESERVED_NAMES.contains(*var))
+      .filter_map(|var| state.variable_table().get(var).map(|value| (var.clone(), value.clone())))
+      .collect();
+    Self {
+      stack: state.main_stack().iter().cloned().collect(),
+      stack_labels: state.stack_labels().for_stack_len(state.main_stack().len()),
+      stack_pins: state.stack_pins().for_stack_len(state.main_stack().len()),
+      stack_groups: state.stack_groups().for_stack_len(state.main_stack().len()),
+      alt_stack: state.alt_stack().iter().cloned().collect(),
+      variables,
+      bookmarks: state.bookmarks().iter().map(|(index, expr)| (index, expr.clone())).collect(),
+      user_functions: state.user_functions().clone(),
+      derivative_rules: state.derivative_rules().clone(),
+      rewrite_rules: state.rewrite_rules().clone(),
+      assumptions: state.assumptions().clone(),
+      conversion_contexts: state.conversion_contexts().clone(),
+      calculation_mode: state.calculation_mode().clone(),
+      display_settings: DisplaySettingsSnapshot::capture(state.display_settings()),
+      currency_rates: state.currency_rates().clone(),
+    }
+  }
+
+  /// Builds a fresh application state, with built-in constants
+  /// bound, containing the contents of this snapshot. The undo
+  /// history starts out empty.
+  pub fn restore(self) -> ApplicationState {
+    let mut state = ApplicationState::with_constants();
+    *state.main_stack_mut_raw() = Stack::from(self.stack);
+    for (index, label) in self.stack_labels.into_iter().rev().enumerate() {
+      state.stack_labels_mut().set(index, label);
+    }
+    for (index, pinned) in self.stack_pins.into_iter().rev().enumerate() {
+      state.stack_pins_mut().set(index, pinned);
+    }
+    for (index, group) in self.stack_groups.into_iter().rev().enumerate() {
+      state.stack_groups_mut().set(index, group);
+    }
+    *state.alt_stack_mut_raw() = Stack::from(self.alt_stack);
+    for (var, value) in self.variables {
+      state.variable_table_mut().insert(var, value);
+    }
+    let mut bookmarks = BookmarkTable::new();
+    for (index, expr) in self.bookmarks {
+      bookmarks.insert(index, expr);
+    }
+    *state.bookmarks_mut() = bookmarks;
+    *state.user_functions_mut() = self.user_functions;
+    *state.derivative_rules_mut() = self.derivative_rules;
+    *state.rewrite_rules_mut() = self.rewrite_rules;
+    *state.assumptions_mut() = self.assumptions;
+    *state.conversion_contexts_mut() = self.conversion_contexts;
+    *state.calculation_mode_mut() = self.calculation_mode;
+    *state.display_settings_mut() = self.display_settings.restore();
+    *state.currency_rates_mut() = self.currency_rates;
+    state
+  }
+
+  pub fn to_json(&self) -> Result<String, PersistenceError> {
+    let versioned = VersionedSnapshot { version: SESSION_SCHEMA_VERSION, snapshot: self };
+    Ok(serde_json::to_string(&versioned)?)
+  }
+
+  /// Parses a snapshot of any supported schema version, migrating it
+  /// to the current version.
+  pub fn from_json(json: &str) -> Result<Self, PersistenceError> {
+    let value: Value = serde_json::from_str(json)?;
+    let value = migrate(value, MIGRATIONS)?;
+    Ok(serde_json::from_value(value)?)
+  }
+}
+
+impl DisplaySettingsSnapshot {
+  fn capture(settings: &DisplaySettings) -> Self {
+    Self {
+      language_mode: settings.base_language_mode.language_mode_name().to_lowercase(),
+      is_graphics_enabled: settings.is_graphics_enabled,
+      is_spoken_text_enabled: settings.is_spoken_text_enabled,
+      language_settings: settings.language_settings.clone(),
+    }
+  }
+
+  /// Builds the display settings. A language mode which is no longer
+  /// known is replaced by the default one.
+  fn restore(self) -> DisplaySettings {
+    let mut settings = DisplaySettings::default();
+    if let Some(language_mode) = named_language_mode(&self.language_mode) {
+      settings.base_language_mode = language_mode;
+    }
+    settings.is_graphics_enabled = self.is_graphics_enabled;
+    settings.is_spoken_text_enabled = self.is_spoken_text_enabled;
+    settings.language_settings = self.language_settings;
+    settings
+  }
+}
+
+impl Default for DisplaySettingsSnapshot {
+  fn default() -> Self {
+    Self::capture(&DisplaySettings::default())
+  }
+}
+
+/// Version 1 saved only the calculation mode, from which the rounding
+/// mode and word size of the display settings were recovered. Version
+/// 2 saves the display settings in full.
+fn add_display_settings(mut value: Value) -> Result<Value, PersistenceError> {
+  let calculation_mode: CalculationMode = serde_json::from_value(value["calculation_mode"].clone())?;
+  let mut display_settings = DisplaySettingsSnapshot::default();
+  display_settings.language_settings.rounding_mode = calculation_mode.rounding_mode();
+  display_settings.language_settings.word_size = calculation_mode.word_size();
+  value["display_settings"] = serde_json::to_value(display_settings)?;
+  value["version"] = json!(2);
+  Ok(value)
+}
+
+/// Applies `migrations` to bring the versioned JSON `value` up to
+/// the latest version, stripping the version tag from the result.
+fn migrate(mut value: Value, migrations: &[Migration]) -> Result<Value, PersistenceError> {
+  let latest_version = migrations.len() as u64 + 1;
+  let version = value.get("version").and_then(Value::as_u64).ok_or(PersistenceError::MissingVersion)?;
+  if version == 0 || version > latest_version {
+    return Err(PersistenceError::UnsupportedVersion(version));
+  }
+  for migration in &migrations[(version - 1) as usize..] {
+    value = migration(value)?;
+  }
+  if let Value::Object(fields) = &mut value {
+    fields.remove("version");
+  }
+  Ok(value)
+}
+
+impl SessionStore {
+  /// A store which saves to the given file.
+  pub fn new(path: PathBuf) -> Self {
+    Self { path: Some(path), writer: OnceLock::new() }
+  }
+
+  /// A store which never saves or loads anything.
+  pub fn disabled() -> Self {
+    Self { path: None, writer: OnceLock::new() }
+  }
+
+  /// A store in the platform's configuration directory, or a
+  /// disabled store if the platform has no such directory.
+  pub fn at_default_location() -> Self {
+    match dirs::config_dir() {
+      Some(dir) => Self::new(dir.join(APP_DIRECTORY_NAME).join(SESSION_FILE_NAME)),
+      None => Self::disabled(),
+    }
+  }
+
+  pub fn path(&self) -> Option<&Path> {
+    self.path.as_deref()
+  }
+
+  /// Loads the saved session, if there is one.
+  pub fn load(&self) -> Result<Option<SessionSnapshot>, PersistenceError> {
+    let Some(path) = &self.path else {
+      return Ok(None);
+    };
+    match fs::read_to_string(path) {
+      Ok(json) => Ok(Some(SessionSnapshot::from_json(&json)?)),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  /// Schedules the snapshot to be saved. If another snapshot arrives
+  /// within [`SAVE_DEBOUNCE`], only the later one is written.
+  pub fn save_debounced(&self, snapshot: SessionSnapshot) {
+    let Some(path) = &self.path else {
+      return;
+    };
+    let writer = self.writer.get_or_init(|| {
+      let (sender, receiver) = mpsc::channel();
+      let path = path.clone();
+      thread::spawn(move || run_writer(&path, receiver));
+      sender
+    });
+    // The writer thread only exits once the sender is dropped, so
+    // this cannot fail.
+    let _ = writer.send(snapshot);
+  }
+
+  /// Saves the snapshot immediately, on the calling thread. Used when
+  /// the application exits, since a debounced save which has not yet
+  /// been written would otherwise be lost.
+  pub fn save_now(&self, snapshot: &SessionSnapshot) -> Result<(), PersistenceError> {
+    match &self.path {
+      Some(path) => write_snapshot(path, snapshot),
+      None => Ok(()),
+    }
+  }
+
+  /// Moves the session file aside to a backup file, so that a session
+  /// which could not be loaded is not overwritten by the next save.
+  /// Returns the path of the backup, or `None` if there was no
+  /// session file.
+  pub fn back_up_session_file(&self) -> io::Result<Option<PathBuf>> {
+    let Some(path) = &self.path else {
+      return Ok(None);
+    };
+    let backup_path = path.with_extension("json.bak");
+    match fs::rename(path, &backup_path) {
+      Ok(()) => Ok(Some(backup_path)),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+      Err(err) => Err(err),
+    }
+  }
+}
+
+/// Body of the background writer thread.
+fn run_writer(path: &Path, receiver: Receiver<SessionSnapshot>) {
+  while let Ok(mut latest) = receiver.recv() {
+    let disconnected = loop {
+      match receiver.recv_timeout(SAVE_DEBOUNCE) {
+        Ok(snapshot) => latest = snapshot,
+        Err(RecvTimeoutError::Timeout) => break false,
+        Err(RecvTimeoutError::Disconnected) => break true,
+      }
+    };
+    if let Err(err) = write_snapshot(path, &latest) {
+      eprintln!("Could not save session to {}: {}", path.display(), err);
+    }
+    if disconnected {
+      return;
+    }
+  }
+}
+
+/// Writes the snapshot to a temporary file and then moves it into
+/// place, so that a crash mid-write cannot corrupt the saved session.
+fn write_snapshot(path: &Path, snapshot: &SessionSnapshot) -> Result<(), PersistenceError> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  let temp_path = path.with_extension("json.tmp");
+  fs::write(&temp_path, snapshot.to_json()?)?;
+  fs::rename(&temp_path, path)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::function::user::UserFunction;
+  use crate::expr::rewrite::RewriteRule;
+  use crate::expr::assumptions::Assumption;
+  use crate::units::context::ConversionContext;
+  use crate::mode::calculation::{AngleMode, RoundingMode};
+  use crate::mode::display::float_format::FloatFormat;
+  use crate::mode::display::locale::Locale;
+  use crate::util::radix::Radix;
+  use crate::state::test_utils::state_for_stack;
+
+  use std::collections::BTreeSet;
+
+  fn sample_state() -> ApplicationState {
+    let mut state = state_for_stack(vec![1, 2, 3]);
+    state.variable_table_mut().insert(Var::new("x").unwrap(), Expr::from(10));
+    state.bookmarks_mut().insert(4, Expr::from(20));
+    state.stack_labels_mut().set(1, Some("two".to_owned()));
+    state.stack_pins_mut().set(2, true);
+    let group = state.stack_groups().new_group("data".to_owned());
+    state.stack_groups_mut().set(0, Some(group.clone()));
+    state.stack_groups_mut().set(1, Some(group));
+    state.alt_stack_mut_raw().push(Expr::from(4));
+    let function = UserFunction::new(vec![Var::new("y").unwrap()], Expr::var("y").unwrap()).unwrap();
+    state.user_functions_mut().insert("id".to_owned(), function);
+    let rule = RewriteRule::new(Expr::call("f", vec![Expr::var("x_").unwrap()]), Expr::var("x_").unwrap()).unwrap();
+    state.rewrite_rules_mut().push(rule);
+    state.assumptions_mut().set(Var::new("x").unwrap(), BTreeSet::from([Assumption::Positive]));
+    let context = ConversionContext::from_json(r#"{"links": [{"from": "L/km", "to": "mi/gal", "reciprocal": 2.5}]}"#).unwrap();
+    state.conversion_contexts_mut().insert("fuel".to_owned(), context);
+    state.calculation_mode_mut().set_angle_mode(AngleMode::Degrees);
+    *state.currency_rates_mut() = CurrencyRateTable::from_json(r#"{"rates": {"EUR": 0.5}}"#).unwrap();
+    let display_settings = state.display_settings_mut();
+    display_settings.base_language_mode = named_language_mode("fancy").unwrap();
+    display_settings.is_graphics_enabled = false;
+    display_settings.language_settings.preferred_radix = Radix::HEXADECIMAL;
+    display_settings.language_settings.float_format = FloatFormat::Scientific(Some(4));
+    display_settings.language_settings.locale = "de".parse::<Locale>().unwrap();
+    state
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let snapshot = SessionSnapshot::capture(&sample_state());
+    let json = snapshot.to_json().unwrap();
+    assert_eq!(SessionSnapshot::from_json(&json).unwrap(), snapshot);
+
+    let restored = snapshot.restore();
+    assert_eq!(restored.main_stack(), sample_state().main_stack());
+    assert_eq!(restored.stack_labels(), sample_state().stack_labels());
+    assert_eq!(restored.stack_pins(), sample_state().stack_pins());
+    assert_eq!(restored.stack_groups(), sample_state().stack_groups());
+    assert_eq!(restored.alt_stack(), sample_state().alt_stack());
+    assert_eq!(restored.variable_table().get(&Var::new("x").unwrap()), Some(&Expr::from(10)));
+    assert_eq!(restored.bookmarks().get(4), Some(&Expr::from(20)));
+    assert!(restored.user_functions().get("id").is_some());
+    assert_eq!(restored.rewrite_rules(), sample_state().rewrite_rules());
+    assert_eq!(restored.assumptions(), sample_state().assumptions());
+    assert_eq!(restored.conversion_contexts(), sample_state().conversion_contexts());
+    assert_eq!(restored.calculation_mode().angle_mode(), AngleMode::Degrees);
+    assert_eq!(restored.currency_rates(), sample_state().currency_rates());
+    let display_settings = restored.display_settings();
+    assert_eq!(display_settings.base_language_mode.language_mode_name(), "Fancy");
+    assert!(!display_settings.is_graphics_enabled);
+    assert_eq!(display_settings.language_settings, sample_state().display_settings().language_settings);
+  }
+
+  #[test]
+  fn test_migrate_from_version_1() {
+    let mut calculation_mode = CalculationMode::default();
+    calculation_mode.set_rounding_mode(RoundingMode::HalfEven);
+    let json = json!({
+      "version": 1,
+      "stack": [],
+      "variables": [],
+      "bookmarks": [],
+      "user_functions": serde_json::to_value(UserFunctionTable::new()).unwrap(),
+      "calculation_mode": serde_json::to_value(&calculation_mode).unwrap(),
+    }).to_string();
+    let restored = SessionSnapshot::from_json(&json).unwrap().restore();
+    assert_eq!(restored.calculation_mode().rounding_mode(), RoundingMode::HalfEven);
+    let display_settings = restored.display_settings();
+    assert_eq!(display_settings.language_settings.rounding_mode, RoundingMode::HalfEven);
+    assert_eq!(display_settings.base_language_mode.language_mode_name(), "Basic");
+    assert!(display_settings.is_graphics_enabled);
+  }
+
+  #[test]
+  fn test_constants_are_not_saved() {
+    let snapshot = SessionSnapshot::capture(&ApplicationState::with_constants());
+    assert_eq!(snapshot, SessionSnapshot::default());
+    // But they are rebound on restore.
+    let restored = snapshot.restore();
+    assert!(restored.variable_table().get(&Var::new("pi").unwrap()).is_some());
+  }
+
+  #[test]
+  fn test_version_errors() {
+    assert!(matches!(SessionSnapshot::from_json("{}"), Err(PersistenceError::MissingVersion)));
+    let json = json!({ "version": SESSION_SCHEMA_VERSION + 1 }).to_string();
+    assert!(matches!(SessionSnapshot::from_json(&json), Err(PersistenceError::UnsupportedVersion(_))));
+  }
+
+  #[test]
+  fn test_migrations_run_in_order() {
+    fn v1_to_v2(mut value: Value) -> Result<Value, PersistenceError> {
+      value["b"] = value["a"].clone();
+      value["version"] = json!(2);
+      Ok(value)
+    }
+    fn v2_to_v3(mut value: Value) -> Result<Value, PersistenceError> {
+      value["c"] = json!(value["b"].as_u64().unwrap() + 1);
+      value["version"] = json!(3);
+      Ok(value)
+    }
+    let migrations: &[Migration] = &[v1_to_v2, v2_to_v3];
+    let migrated = migrate(json!({ "version": 1, "a": 10 }), migrations).unwrap();
+    assert_eq!(migrated, json!({ "a": 10, "b": 10, "c": 11 }));
+    let migrated = migrate(json!({ "version": 2, "b": 5 }), migrations).unwrap();
+    assert_eq!(migrated, json!({ "b": 5, "c": 6 }));
+    let migrated = migrate(json!({ "version": 3, "c": 0 }), migrations).unwrap();
+    assert_eq!(migrated, json!({ "c": 0 }));
+  }
+
+  #[test]
+  fn test_store_load_missing_file() {
+    let path = std::env::temp_dir().join("fifi-test-missing-session.json");
+    let _ = fs::remove_file(&path);
+    assert!(SessionStore::new(path).load().unwrap().is_none());
+    assert!(SessionStore::disabled().load().unwrap().is_none());
+  }
+
+  #[test]
+  fn test_write_then_load() {
+    let dir = std::env::temp_dir().join(format!("fifi-test-session-{}", std::process::id()));
+    let path = dir.join(SESSION_FILE_NAME);
+    let snapshot = SessionSnapshot::capture(&sample_state());
+    write_snapshot(&path, &snapshot).unwrap();
+    assert_eq!(SessionStore::new(path).load().unwrap(), Some(snapshot));
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_save_now() {
+    let dir = std::env::temp_dir().join(format!("fifi-test-save-now-{}", std::process::id()));
+    let store = SessionStore::new(dir.join(SESSION_FILE_NAME));
+    let snapshot = SessionSnapshot::capture(&sample_state());
+    store.save_now(&snapshot).unwrap();
+    assert_eq!(store.load().unwrap(), Some(snapshot));
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_back_up_session_file() {
+    let dir = std::env::temp_dir().join(format!("fifi-test-backup-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(SESSION_FILE_NAME);
+    fs::write(&path, "not json").unwrap();
+    let store = SessionStore::new(path.clone());
+    assert!(store.load().is_err());
+
+    let backup_path = store.back_up_session_file().unwrap().unwrap();
+    assert_eq!(backup_path, dir.join("session.json.bak"));
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), "not json");
+    assert!(store.load().unwrap().is_none());
+    // Nothing left to back up.
+    assert!(store.back_up_session_file().unwrap().is_none());
+    assert!(SessionStore::disabled().back_up_session_file().unwrap().is_none());
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,126 @@
+
+//! Pin flags attached to entries of the main stack.
+
+/// Pin flags for the entries of the main stack, stored in parallel to
+/// the stack itself in the same way as
+/// [`StackLabels`](super::labels::StackLabels). All indices here are
+/// counted from the top of the stack.
+///
+/// A pinned entry is read by commands that pop it, but it is left in
+/// place on the stack rather than consumed. See
+/// [`StackDelegate::is_pinned`](crate::stack::StackDelegate::is_pinned).
+///
+/// As with labels, the flags may temporarily be out of sync with the
+/// length of the stack. Missing positions are treated as unpinned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StackPins {
+  /// Flags from the bottom of the stack to the top.
+  pinned: Vec<bool>,
+}
+
+impl StackPins {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// True if no entry is pinned.
+  pub fn is_empty(&self) -> bool {
+    !self.pinned.iter().any(|pinned| *pinned)
+  }
+
+  pub fn is_pinned(&self, index: usize) -> bool {
+    match self.pinned.len().checked_sub(index + 1) {
+      None => false,
+      Some(position) => self.pinned[position],
+    }
+  }
+
+  /// Pins or unpins the entry at the given position, returning the
+  /// old flag.
+  pub fn set(&mut self, index: usize, pinned: bool) -> bool {
+    if self.pinned.len() <= index {
+      if !pinned {
+        // Unpinning an entry that doesn't exist is a no-op.
+        return false;
+      }
+      self.pad_to(index + 1);
+    }
+    let position = self.pinned.len() - index - 1;
+    std::mem::replace(&mut self.pinned[position], pinned)
+  }
+
+  /// Makes room for a new stack entry at the given position.
+  pub fn insert(&mut self, index: usize, pinned: bool) {
+    self.pad_to(index);
+    let position = self.pinned.len() - index;
+    self.pinned.insert(position, pinned);
+  }
+
+  /// Removes the flag for a stack entry that has been popped,
+  /// returning whether it was pinned.
+  pub fn remove(&mut self, index: usize) -> bool {
+    match self.pinned.len().checked_sub(index + 1) {
+      None => false,
+      Some(position) => self.pinned.remove(position),
+    }
+  }
+
+  /// The flags for a stack of the given length, from the bottom of
+  /// the stack to the top.
+  pub fn for_stack_len(&self, len: usize) -> Vec<bool> {
+    let mut result = vec![false; len.saturating_sub(self.pinned.len())];
+    let skip = self.pinned.len().saturating_sub(len);
+    result.extend_from_slice(&self.pinned[skip..]);
+    result
+  }
+
+  fn pad_to(&mut self, len: usize) {
+    if self.pinned.len() < len {
+      let padding = len - self.pinned.len();
+      self.pinned.splice(0..0, std::iter::repeat_n(false, padding));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_set_and_query() {
+    let mut pins = StackPins::new();
+    assert!(pins.is_empty());
+    assert!(!pins.set(2, true));
+    assert!(pins.is_pinned(2));
+    assert!(!pins.is_pinned(0));
+    assert!(!pins.is_pinned(3));
+    assert!(pins.set(2, false));
+    assert!(pins.is_empty());
+    assert!(!pins.set(9, false));
+  }
+
+  #[test]
+  fn test_pins_follow_entries() {
+    let mut pins = StackPins::new();
+    pins.insert(0, false);
+    pins.insert(0, true);
+    pins.insert(0, false);
+    assert_eq!(pins.for_stack_len(3), vec![false, true, false]);
+    assert!(!pins.remove(0));
+    assert!(pins.is_pinned(0));
+    pins.insert(1, false);
+    assert_eq!(pins.for_stack_len(3), vec![false, false, true]);
+    assert!(pins.remove(0));
+    assert!(pins.is_empty());
+  }
+
+  #[test]
+  fn test_for_stack_len_with_mismatched_length() {
+    let mut pins = StackPins::new();
+    pins.set(0, true);
+    assert_eq!(pins.for_stack_len(3), vec![false, false, true]);
+    pins.set(3, true);
+    assert_eq!(pins.for_stack_len(2), vec![false, true]);
+    assert_eq!(pins.for_stack_len(0), Vec::<bool>::new());
+  }
+}
@@ -0,0 +1,87 @@
+
+//! Recovery from panics that occur while running commands.
+//!
+//! A panic in the middle of a command would otherwise poison the
+//! state mutex and leave the application unusable. Instead, we catch
+//! the panic, roll the state back to where it was before the command
+//! started, and report the failure to the user.
+
+use super::ApplicationState;
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A panic caught by [`run_recoverably`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaughtPanic {
+  /// The panic message, if one could be extracted from the payload.
+  pub message: String,
+}
+
+/// Runs `f` against the state, catching any panic that occurs. If `f`
+/// panics, every undoable change it made to the state is reverted
+/// before returning.
+pub fn run_recoverably<T, F>(state: &mut ApplicationState, f: F) -> Result<T, CaughtPanic>
+where F: FnOnce(&mut ApplicationState) -> T {
+  let mark = state.undo_stack().mark();
+  // If `f` panics, the only state it could have left
+  // inconsistent is `state`, which we immediately roll back.
+  let result = panic::catch_unwind(AssertUnwindSafe(|| f(state)));
+  result.map_err(|payload| {
+    state.revert_to_undo_mark(mark);
+    CaughtPanic { message: panic_message(payload.as_ref()) }
+  })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+  if let Some(s) = payload.downcast_ref::<&str>() {
+    (*s).to_owned()
+  } else if let Some(s) = payload.downcast_ref::<String>() {
+    s.clone()
+  } else {
+    String::from("Unknown internal error")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::Expr;
+  use crate::stack::base::StackLike;
+  use crate::state::test_utils::state_for_stack;
+  use crate::stack::test_utils::stack_of;
+
+  #[test]
+  fn test_run_recoverably_success() {
+    let mut state = state_for_stack(vec![1, 2]);
+    let result = run_recoverably(&mut state, |state| {
+      state.undo_stack_mut().push_cut();
+      state.main_stack_mut().pop().unwrap()
+    });
+    assert_eq!(result, Ok(Expr::from(2)));
+    assert_eq!(state.into_main_stack(), stack_of(vec![1]));
+  }
+
+  #[test]
+  fn test_run_recoverably_reverts_on_panic() {
+    let mut state = state_for_stack(vec![1, 2]);
+    let result: Result<(), _> = run_recoverably(&mut state, |state| {
+      state.undo_stack_mut().push_cut();
+      state.main_stack_mut().pop().unwrap();
+      state.main_stack_mut().push(Expr::from(99));
+      panic!("something went wrong");
+    });
+    assert_eq!(result, Err(CaughtPanic { message: String::from("something went wrong") }));
+    assert!(!state.undo_stack().has_undos());
+    assert_eq!(state.into_main_stack(), stack_of(vec![1, 2]));
+  }
+
+  #[test]
+  fn test_run_recoverably_with_formatted_panic() {
+    let mut state = ApplicationState::new();
+    let result: Result<(), _> = run_recoverably(&mut state, |_| {
+      panic!("value was {}", 10);
+    });
+    assert_eq!(result, Err(CaughtPanic { message: String::from("value was 10") }));
+  }
+}
@@ -0,0 +1,79 @@
+
+//! Summary statistics about the size of the application state.
+
+use super::ApplicationState;
+use crate::stack::base::StackLike;
+
+use serde::{Serialize, Deserialize};
+
+/// A snapshot of how much data the engine is currently holding. The
+/// frontend uses this to warn the user before the stack or variable
+/// table grow unwieldy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineStats {
+  /// The number of elements on the main stack.
+  pub stack_depth: usize,
+  /// The total number of expression nodes across the stack and all
+  /// bound variables.
+  pub node_count: usize,
+  /// The number of bound variables, including built-in constants.
+  pub variable_count: usize,
+  /// The number of actions available to undo.
+  pub undo_depth: usize,
+  /// A rough estimate, in bytes, of the memory occupied by the stack
+  /// and variable bindings. This does not account for the undo
+  /// history.
+  pub approx_memory_bytes: usize,
+}
+
+impl EngineStats {
+  pub fn from_state(state: &ApplicationState) -> Self {
+    let exprs = || state.main_stack().iter().chain(state.variable_table().values());
+    EngineStats {
+      stack_depth: state.main_stack().len(),
+      node_count: exprs().map(|expr| expr.node_count()).sum(),
+      variable_count: state.variable_table().len(),
+      undo_depth: state.undo_stack().undo_depth(),
+      approx_memory_bytes: exprs().map(|expr| expr.approx_size_in_bytes()).sum(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::Expr;
+  use crate::expr::var::Var;
+  use crate::state::test_utils::state_for_stack;
+
+  #[test]
+  fn test_stats_of_empty_state() {
+    let stats = EngineStats::from_state(&ApplicationState::new());
+    assert_eq!(stats, EngineStats {
+      stack_depth: 0,
+      node_count: 0,
+      variable_count: 0,
+      undo_depth: 0,
+      approx_memory_bytes: 0,
+    });
+  }
+
+  #[test]
+  fn test_stats_of_state() {
+    let mut state = state_for_stack(vec![
+      Expr::from(1),
+      Expr::call("+", vec![Expr::from(2), Expr::from(3)]),
+    ]);
+    state.variable_table_mut().insert(Var::new("x").unwrap(), Expr::from(10));
+    state.undo_stack_mut().push_cut();
+    state.main_stack_mut().push(Expr::from(4));
+
+    let stats = EngineStats::from_state(&state);
+    assert_eq!(stats.stack_depth, 3);
+    assert_eq!(stats.node_count, 6);
+    assert_eq!(stats.variable_count, 1);
+    assert_eq!(stats.undo_depth, 1);
+    assert!(stats.approx_memory_bytes > 0);
+  }
+}
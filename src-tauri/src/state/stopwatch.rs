@@ -0,0 +1,86 @@
+
+//! A stopwatch for timing things in the real world. The stopwatch is
+//! not part of the undoable state, since undoing a command should not
+//! turn back the clock.
+
+use thiserror::Error;
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Default)]
+pub struct Stopwatch {
+  running: Option<RunningStopwatch>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RunningStopwatch {
+  started_at: Instant,
+  last_lap_at: Instant,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("The stopwatch is not running")]
+pub struct StopwatchNotRunningError;
+
+impl Stopwatch {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.running.is_some()
+  }
+
+  /// Starts the stopwatch at the given time. If the stopwatch is
+  /// already running, it is restarted.
+  pub fn start(&mut self, now: Instant) {
+    self.running = Some(RunningStopwatch { started_at: now, last_lap_at: now });
+  }
+
+  /// Returns the time since the previous lap (or since the stopwatch
+  /// started, if this is the first lap), and begins a new lap.
+  pub fn lap(&mut self, now: Instant) -> Result<Duration, StopwatchNotRunningError> {
+    let running = self.running.as_mut().ok_or(StopwatchNotRunningError)?;
+    let elapsed = now.saturating_duration_since(running.last_lap_at);
+    running.last_lap_at = now;
+    Ok(elapsed)
+  }
+
+  /// Stops the stopwatch, returning the total time since it started.
+  pub fn stop(&mut self, now: Instant) -> Result<Duration, StopwatchNotRunningError> {
+    let running = self.running.take().ok_or(StopwatchNotRunningError)?;
+    Ok(now.saturating_duration_since(running.started_at))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_laps_and_stop() {
+    let t0 = Instant::now();
+    let mut stopwatch = Stopwatch::new();
+    stopwatch.start(t0);
+    assert_eq!(stopwatch.lap(t0 + Duration::from_secs(3)), Ok(Duration::from_secs(3)));
+    assert_eq!(stopwatch.lap(t0 + Duration::from_secs(5)), Ok(Duration::from_secs(2)));
+    assert_eq!(stopwatch.stop(t0 + Duration::from_secs(6)), Ok(Duration::from_secs(6)));
+    assert!(!stopwatch.is_running());
+  }
+
+  #[test]
+  fn test_restart() {
+    let t0 = Instant::now();
+    let mut stopwatch = Stopwatch::new();
+    stopwatch.start(t0);
+    stopwatch.start(t0 + Duration::from_secs(10));
+    assert_eq!(stopwatch.stop(t0 + Duration::from_secs(11)), Ok(Duration::from_secs(1)));
+  }
+
+  #[test]
+  fn test_not_running() {
+    let mut stopwatch = Stopwatch::new();
+    assert_eq!(stopwatch.lap(Instant::now()), Err(StopwatchNotRunningError));
+    assert_eq!(stopwatch.stop(Instant::now()), Err(StopwatchNotRunningError));
+  }
+}
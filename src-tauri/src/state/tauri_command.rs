@@ -1,12 +1,19 @@
 
 //! Tauri command-like functions.
 
-use super::{ApplicationState, UndoDirection};
+use super::{ApplicationState, TauriApplicationState, UndoDirection};
 use super::validation::{Validator, ValidationContext, validate};
 use super::query::{Query, QueryContext, run_query};
-use super::events::show_error;
+use super::stats::EngineStats;
+use super::history::HistoryEntry;
+use super::hints::ErrorLevel;
+use super::bookmarks::BookmarkPreview;
+use super::events::{InternalErrorPayload, CommandTimingPayload, ConfirmationRequestPayload, TimerExpiredPayload, show_error, show_error_summary, emit_to_windows};
+use super::windows::{WindowKind, open_window};
+use super::recovery::{CaughtPanic, run_recoverably};
 use crate::command::{CommandContext, CommandOutput};
 use crate::command::dispatch::CommandDispatchTable;
+use crate::command::program::ProgramStep;
 use crate::errorlist::ErrorList;
 use crate::expr::simplifier::default_simplifier;
 use crate::expr::function::table::FunctionTable;
@@ -14,11 +21,22 @@ use crate::stack::StackError;
 use crate::stack::base::{StackLike, RandomAccessStackLike};
 use crate::graphics::payload::SerializedGraphicsPayload;
 use crate::graphics::response::GraphicsResponse;
+use crate::mode::display::document;
+use crate::mode::display::export::{ExportFormat, export_expr};
+
+use html_escape::decode_html_entities;
 
 use std::fmt::Display;
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Runs the given (nullary) math command from the command dispatch
 /// table.
+///
+/// If the command panics, its changes to the state are rolled back
+/// and an [`InternalErrorPayload`] event is sent, in addition to the
+/// usual error notification. If the command asks for confirmation, a
+/// [`ConfirmationRequestPayload`] is sent and nothing else happens.
 pub fn run_math_command(
   state: &mut ApplicationState,
   command_context: CommandContext,
@@ -28,13 +46,93 @@ pub fn run_math_command(
   args: Vec<String>,
 ) -> anyhow::Result<()> {
   let command = command_table.get(command_name)?;
-  let output = command.run_command(state, args, &command_context)?;
+  let history_entry = HistoryEntry {
+    command_name: command_name.to_owned(),
+    args: args.clone(),
+    options: command_context.opts.clone(),
+    result: None,
+  };
+  let start_time = Instant::now();
+  let result = run_recoverably(state, |state| command.run_command(state, args, &command_context));
+  let command_timing = CommandTimingPayload::new(command_name, start_time.elapsed());
+  report_command_result(state, app_handle, history_entry, result, command_timing)
+}
+
+/// Records the result of a command which has just run against
+/// `state`, updating the history and any macro being recorded, and
+/// reports the outcome to the frontend. Shared by
+/// [`run_math_command`] and the background job runner.
+pub fn report_command_result(
+  state: &mut ApplicationState,
+  app_handle: &tauri::AppHandle,
+  mut history_entry: HistoryEntry,
+  result: Result<anyhow::Result<CommandOutput>, CaughtPanic>,
+  command_timing: CommandTimingPayload,
+) -> anyhow::Result<()> {
+  let command_name = history_entry.command_name.clone();
+  let output = match result {
+    Ok(Ok(output)) => output,
+    Ok(Err(err)) => {
+      state.set_last_error_level(ErrorLevel::Error);
+      state.send_theme_hints_event(app_handle)?;
+      return Err(err);
+    }
+    Err(caught_panic) => {
+      state.set_last_error_level(ErrorLevel::Error);
+      let payload = InternalErrorPayload {
+        command_name: command_name.clone(),
+        message: caught_panic.message,
+      };
+      show_error(app_handle, format!("Internal error in {}: {}", payload.command_name, payload.message))?;
+      emit_to_windows(app_handle, payload)?;
+      state.send_all_updates(app_handle, false, None)?;
+      return Ok(());
+    }
+  };
+  if let Some(message) = output.confirmation_request() {
+    // The command did not run, so there is nothing to record.
+    let payload = ConfirmationRequestPayload {
+      command_name: command_name.clone(),
+      args: history_entry.args,
+      options: history_entry.options,
+      message: message.to_owned(),
+    };
+    emit_to_windows(app_handle, payload)?;
+    return Ok(());
+  }
+  state.macro_recorder_mut().record(ProgramStep {
+    name: history_entry.command_name.clone(),
+    args: history_entry.args.clone(),
+    options: history_entry.options.clone(),
+  });
+  if command_name != "nop" {
+    history_entry.result = state.main_stack().get(0).ok()
+      .map(|expr| decode_html_entities(&state.display_settings().to_html_for_parsing(expr)).into_owned());
+    state.history_mut().push(history_entry);
+  }
   handle_command_output(app_handle, &output)?;
+  let error_level = if output.errors().is_empty() { ErrorLevel::Ok } else { ErrorLevel::Warning };
+  state.set_last_error_level(error_level);
+  if let Some(duration) = output.timer_request() {
+    start_timer(app_handle, duration);
+  }
 
-  state.send_all_updates(app_handle, output.force_scroll_down())?;
+  state.send_all_updates(app_handle, output.force_scroll_down(), Some(command_timing))?;
   Ok(())
 }
 
+/// Sends a [`TimerExpiredPayload`] once `duration` has passed. The
+/// wait happens on a separate thread, so this returns immediately.
+fn start_timer(app_handle: &tauri::AppHandle, duration: Duration) {
+  let app_handle = app_handle.clone();
+  thread::spawn(move || {
+    thread::sleep(duration);
+    // If the window has closed in the meantime, there's nobody to
+    // notify, so errors are ignored.
+    let _ = emit_to_windows(&app_handle, TimerExpiredPayload { seconds: duration.as_secs_f64() });
+  });
+}
+
 /// Renders a `graphics` command in the expression language into a set
 /// of directives for the frontend to follow.
 pub fn render_graphics(
@@ -72,6 +170,30 @@ pub fn get_editable_stack_elem(
   Ok(state.display_settings().to_html_for_parsing(elem))
 }
 
+/// Returns the HTML representation of the given element on the stack,
+/// using the current language mode but without abbreviating long
+/// vectors or matrices. Produces a [`StackError`] if the index is out
+/// of bounds.
+pub fn show_full_stack_elem(
+  state: &ApplicationState,
+  stack_index: usize,
+) -> Result<String, StackError> {
+  let elem = state.main_stack().get(stack_index as i64)?;
+  Ok(state.display_settings().to_full_html(elem))
+}
+
+/// Renders the stack element at the given index in the named export
+/// format, for copying to the clipboard.
+pub fn export_stack_elem(
+  state: &mut ApplicationState,
+  stack_index: usize,
+  format: &str,
+) -> anyhow::Result<String> {
+  let format = format.parse::<ExportFormat>()?;
+  let elem = state.main_stack().get(stack_index as i64)?;
+  Ok(export_expr(elem, &format, state.display_settings())?)
+}
+
 /// Runs the given undo action.
 pub fn perform_undo_action(
   state: &mut ApplicationState,
@@ -88,7 +210,7 @@ pub fn perform_undo_action(
     UndoDirection::Redo => state.redo(),
   };
 
-  state.send_all_updates(app_handle, true)?;
+  state.send_all_updates(app_handle, true, None)?;
   Ok(())
 }
 
@@ -151,14 +273,48 @@ pub fn query_stack(
   Ok(query_result)
 }
 
+/// Opens a new auxiliary window and sends it the current state, so it
+/// does not start out blank. Returns the new window's label.
+///
+/// Building a window while holding the state mutex can deadlock on
+/// Windows, so the state is only locked once the window exists.
+pub fn open_auxiliary_window(
+  app_state: &TauriApplicationState,
+  app_handle: &tauri::AppHandle,
+  kind: WindowKind,
+) -> anyhow::Result<String> {
+  let label = open_window(app_handle, kind)?;
+  let state = app_state.lock_state();
+  state.send_all_updates(app_handle, true, None)?;
+  Ok(label)
+}
+
+/// Reports summary statistics about the size of the current state.
+pub fn get_engine_stats(state: &ApplicationState) -> EngineStats {
+  EngineStats::from_state(state)
+}
+
+/// Renders the current stack as a standalone HTML document.
+pub fn render_stack_document(state: &ApplicationState) -> String {
+  let language_settings = &state.display_settings().language_settings;
+  document::render_stack_document(document::DEFAULT_DOCUMENT_TITLE, state.main_stack().iter(), language_settings)
+}
+
+/// Lists the current bookmarks, in order, with a rendered preview of
+/// each.
+pub fn list_bookmarks(state: &ApplicationState) -> Vec<BookmarkPreview> {
+  state.bookmarks().previews(state.display_settings())
+}
+
 /// Handles errors from the referenced [`ErrorList`] by communicating
 /// them to the user.
 ///
-/// Currently, this function only displays the *first* error to the
-/// user, for brevity's sake. This behavior may change in the future.
+/// Only the *first* error is displayed to the user, for brevity's
+/// sake, together with the total number of errors.
 pub fn handle_error_list<E: Display>(app_handle: &tauri::AppHandle, error_list: ErrorList<E>) -> tauri::Result<()> {
   if !error_list.is_empty() {
-    show_error(app_handle, format!("Error: {}", error_list.into_vec()[0]))
+    let count = error_list.len();
+    show_error_summary(app_handle, format!("Error: {}", error_list.into_vec()[0]), count)
   } else {
     Ok(())
   }
@@ -167,11 +323,12 @@ pub fn handle_error_list<E: Display>(app_handle: &tauri::AppHandle, error_list:
 /// Handles errors from the referenced [`CommandOutput`] by
 /// communicating them to the user.
 ///
-/// Currently, this function only displays the *first* error to the
-/// user, for brevity's sake. This behavior may change in the future.
+/// Only the *first* error is displayed to the user, for brevity's
+/// sake, together with the total number of errors.
 pub fn handle_command_output(app_handle: &tauri::AppHandle, command_output: &CommandOutput) -> tauri::Result<()> {
-  if !command_output.errors().is_empty() {
-    show_error(app_handle, format!("Error: {}", command_output.get_error(0)))
+  let errors = command_output.errors();
+  if !errors.is_empty() {
+    show_error_summary(app_handle, format!("Error: {}", errors[0]), errors.len())
   } else {
     Ok(())
   }
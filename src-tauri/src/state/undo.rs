@@ -1,14 +1,29 @@
 
 //! [`UndoableChange`] implementations for the application's
 //! [`UndoableState`].
+//!
+//! Changes which push or pop stack entries hold the entry only while
+//! it is off the stack, moving it between the stack and the change as
+//! they are played. So the history never keeps a second copy of a
+//! value which is still on the stack. Changes which replace an entry
+//! store an [`ExprDelta`] of the entry instead.
 
 use crate::undo::UndoableChange;
 use crate::util::Ellipsis;
 use crate::expr::Expr;
+use crate::expr::delta::ExprDelta;
 use crate::expr::var::Var;
-use crate::stack::base::RandomAccessStackLike;
+use crate::expr::function::user::UserFunction;
+use crate::expr::calculus::DerivativeRule;
+use crate::expr::rewrite::RewriteRule;
+use crate::expr::assumptions::Assumption;
+use crate::units::context::ConversionContext;
+use crate::stack::Stack;
+use crate::stack::base::{StackLike, RandomAccessStackLike};
 use super::UndoableState;
+use super::groups::StackGroup;
 
+use std::collections::BTreeSet;
 use std::fmt::{self, Debug, Formatter};
 
 /// `UndoableChange` that pushes a single value onto the stack at the
@@ -16,24 +31,68 @@ use std::fmt::{self, Debug, Formatter};
 #[derive(Clone, Debug)]
 pub struct PushExprChange {
   index: usize,
-  expr: Expr,
+  /// The pushed value, while the change is undone. `None` while the
+  /// value is on the stack.
+  expr: Option<Expr>,
 }
 
 /// `UndoableChange` that pops a single value off the stack, not
-/// necessarily the top one.
+/// necessarily the top one. The value's label, pin flag, and group
+/// are restored when the change is undone.
 #[derive(Clone, Debug)]
 pub struct PopExprChange {
   index: usize,
-  expr: Expr,
+  /// The popped value, while it is off the stack. `None` while the
+  /// change is undone.
+  expr: Option<Expr>,
+  label: Option<String>,
+  pinned: bool,
+  group: Option<StackGroup>,
 }
 
 /// `UndoableChange` that replaces a single value on the stack with
-/// another value.
+/// another value. Only the parts of the value which actually changed
+/// are stored.
 #[derive(Clone, Debug)]
 pub struct ReplaceExprChange {
   index: i64,
-  old_expr: Expr,
-  new_expr: Expr,
+  delta: ExprDelta,
+}
+
+/// `UndoableChange` that sets or clears the label on a stack entry.
+#[derive(Clone, Debug)]
+pub struct UpdateLabelChange {
+  index: usize,
+  old_label: Option<String>,
+  new_label: Option<String>,
+}
+
+/// `UndoableChange` that moves a stack entry into or out of a group.
+#[derive(Clone, Debug)]
+pub struct UpdateGroupChange {
+  index: usize,
+  old_group: Option<StackGroup>,
+  new_group: Option<StackGroup>,
+}
+
+/// `UndoableChange` that pins or unpins a stack entry.
+#[derive(Clone, Debug)]
+pub struct UpdatePinChange {
+  index: usize,
+  old_pinned: bool,
+  new_pinned: bool,
+}
+
+/// `UndoableChange` on the alternate stack. Entries on the alternate
+/// stack carry no labels or pins, so a single change type covers all
+/// of the ways it can be modified. As with [`PushExprChange`] and
+/// [`PopExprChange`], a pushed or popped value is only held while it
+/// is off the stack.
+#[derive(Clone, Debug)]
+pub enum AltStackChange {
+  Push { index: usize, expr: Option<Expr> },
+  Pop { index: usize, expr: Option<Expr> },
+  Replace { index: i64, delta: ExprDelta },
 }
 
 /// `UndoableChange` that replaces a variable binding's presence in
@@ -46,6 +105,58 @@ pub struct UpdateVarChange {
   new_value: Option<Expr>,
 }
 
+/// `UndoableChange` that sets or clears a numbered bookmark in the
+/// state's bookmark table.
+#[derive(Clone, Debug)]
+pub struct UpdateBookmarkChange {
+  index: usize,
+  old_value: Option<Expr>,
+  new_value: Option<Expr>,
+}
+
+/// `UndoableChange` that defines, redefines, or removes a
+/// user-defined function.
+#[derive(Clone, Debug)]
+pub struct UpdateUserFunctionChange {
+  name: String,
+  old_value: Option<UserFunction>,
+  new_value: Option<UserFunction>,
+}
+
+/// `UndoableChange` that registers, replaces, or removes the
+/// derivative rule for a function.
+#[derive(Clone, Debug)]
+pub struct UpdateDerivativeRuleChange {
+  name: String,
+  old_value: Option<DerivativeRule>,
+  new_value: Option<DerivativeRule>,
+}
+
+/// `UndoableChange` that adds a rewrite rule at the end of the
+/// state's rewrite rule table.
+#[derive(Clone, Debug)]
+pub struct AddRewriteRuleChange {
+  rule: RewriteRule,
+}
+
+/// `UndoableChange` that replaces the set of assumptions declared on
+/// a variable.
+#[derive(Clone, Debug)]
+pub struct UpdateAssumptionsChange {
+  var: Var,
+  old_assumptions: BTreeSet<Assumption>,
+  new_assumptions: BTreeSet<Assumption>,
+}
+
+/// `UndoableChange` that defines, redefines, or removes a named
+/// unit conversion context.
+#[derive(Clone, Debug)]
+pub struct UpdateConversionContextChange {
+  name: String,
+  old_value: Option<ConversionContext>,
+  new_value: Option<ConversionContext>,
+}
+
 /// `UndoableChange` that toggles the value of the given Boolean flag
 /// on the state object. A `ToggleFlagChange` shall be its own
 /// inverse. That is, since such flags are simply toggling a Boolean
@@ -57,20 +168,54 @@ pub struct ToggleFlagChange {
 }
 
 impl PushExprChange {
-  pub fn new(index: usize, expr: Expr) -> Self {
-    Self { index, expr }
+  /// A change recording that a value was pushed at the given
+  /// position. The value itself is on the stack, so it isn't needed.
+  pub fn new(index: usize) -> Self {
+    Self { index, expr: None }
   }
 }
 
 impl PopExprChange {
-  pub fn new(index: usize, expr: Expr) -> Self {
-    Self { index, expr }
+  pub fn new(index: usize, expr: Expr, label: Option<String>, pinned: bool, group: Option<StackGroup>) -> Self {
+    Self { index, expr: Some(expr), label, pinned, group }
   }
 }
 
 impl ReplaceExprChange {
-  pub fn new(index: i64, old_expr: Expr, new_expr: Expr) -> Self {
-    Self { index, old_expr, new_expr }
+  pub fn new(index: i64, old_expr: &Expr, new_expr: &Expr) -> Self {
+    Self { index, delta: ExprDelta::between(old_expr, new_expr) }
+  }
+}
+
+impl AltStackChange {
+  pub fn push(index: usize) -> Self {
+    AltStackChange::Push { index, expr: None }
+  }
+
+  pub fn pop(index: usize, expr: Expr) -> Self {
+    AltStackChange::Pop { index, expr: Some(expr) }
+  }
+
+  pub fn replace(index: i64, old_expr: &Expr, new_expr: &Expr) -> Self {
+    AltStackChange::Replace { index, delta: ExprDelta::between(old_expr, new_expr) }
+  }
+}
+
+impl UpdateLabelChange {
+  pub fn new(index: usize, old_label: Option<String>, new_label: Option<String>) -> Self {
+    Self { index, old_label, new_label }
+  }
+}
+
+impl UpdateGroupChange {
+  pub fn new(index: usize, old_group: Option<StackGroup>, new_group: Option<StackGroup>) -> Self {
+    Self { index, old_group, new_group }
+  }
+}
+
+impl UpdatePinChange {
+  pub fn new(index: usize, old_pinned: bool, new_pinned: bool) -> Self {
+    Self { index, old_pinned, new_pinned }
   }
 }
 
@@ -92,6 +237,42 @@ impl UpdateVarChange {
   }
 }
 
+impl UpdateBookmarkChange {
+  pub fn new(index: usize, old_value: Option<Expr>, new_value: Option<Expr>) -> Self {
+    Self { index, old_value, new_value }
+  }
+}
+
+impl UpdateUserFunctionChange {
+  pub fn new(name: String, old_value: Option<UserFunction>, new_value: Option<UserFunction>) -> Self {
+    Self { name, old_value, new_value }
+  }
+}
+
+impl UpdateDerivativeRuleChange {
+  pub fn new(name: String, old_value: Option<DerivativeRule>, new_value: Option<DerivativeRule>) -> Self {
+    Self { name, old_value, new_value }
+  }
+}
+
+impl AddRewriteRuleChange {
+  pub fn new(rule: RewriteRule) -> Self {
+    Self { rule }
+  }
+}
+
+impl UpdateAssumptionsChange {
+  pub fn new(var: Var, old_assumptions: BTreeSet<Assumption>, new_assumptions: BTreeSet<Assumption>) -> Self {
+    Self { var, old_assumptions, new_assumptions }
+  }
+}
+
+impl UpdateConversionContextChange {
+  pub fn new(name: String, old_value: Option<ConversionContext>, new_value: Option<ConversionContext>) -> Self {
+    Self { name, old_value, new_value }
+  }
+}
+
 impl ToggleFlagChange {
   pub fn new<F>(flag_name: impl Into<String>, toggle_function: F) -> Self
   where F: Fn(&mut UndoableState) + Send + Sync + 'static {
@@ -130,57 +311,156 @@ impl ToggleFlagChange {
 }
 
 impl UndoableChange<UndoableState> for PushExprChange {
-  fn play_forward(&self, state: &mut UndoableState) {
-    let _ = state.main_stack_mut().insert(self.index, self.expr.clone());
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    move_onto_stack(state.main_stack_mut(), self.index, &mut self.expr);
+    state.stack_labels_mut().insert(self.index, None);
+    state.stack_pins_mut().insert(self.index, false);
+    state.stack_groups_mut().insert(self.index, None);
   }
 
-  fn play_backward(&self, state: &mut UndoableState) {
-    let _ = state.main_stack_mut().pop_nth(self.index);
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    move_off_stack(state.main_stack_mut(), self.index, &mut self.expr);
+    state.stack_labels_mut().remove(self.index);
+    state.stack_pins_mut().remove(self.index);
+    state.stack_groups_mut().remove(self.index);
   }
 
   fn undo_summary(&self) -> String {
     format!("{:?}", self)
   }
+
+  fn approx_size_in_bytes(&self) -> usize {
+    optional_expr_size(&self.expr)
+  }
 }
 
 impl UndoableChange<UndoableState> for PopExprChange {
-  fn play_forward(&self, state: &mut UndoableState) {
-    let _ = state.main_stack_mut().pop_nth(self.index);
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    move_off_stack(state.main_stack_mut(), self.index, &mut self.expr);
+    state.stack_labels_mut().remove(self.index);
+    state.stack_pins_mut().remove(self.index);
+    state.stack_groups_mut().remove(self.index);
   }
 
-  fn play_backward(&self, state: &mut UndoableState) {
-    let _ = state.main_stack_mut().insert(self.index, self.expr.clone());
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    move_onto_stack(state.main_stack_mut(), self.index, &mut self.expr);
+    state.stack_labels_mut().insert(self.index, self.label.clone());
+    state.stack_pins_mut().insert(self.index, self.pinned);
+    state.stack_groups_mut().insert(self.index, self.group.clone());
   }
 
   fn undo_summary(&self) -> String {
     format!("{:?}", self)
   }
+
+  fn approx_size_in_bytes(&self) -> usize {
+    optional_expr_size(&self.expr)
+  }
+}
+
+impl UndoableChange<UndoableState> for AltStackChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    // There should be no errors if we're undoing the right state, but
+    // ignore any that occur, per UndoableChange's contract.
+    let stack = state.alt_stack_mut();
+    match self {
+      AltStackChange::Push { index, expr } => move_onto_stack(stack, *index, expr),
+      AltStackChange::Pop { index, expr } => move_off_stack(stack, *index, expr),
+      AltStackChange::Replace { index, delta } => { let _ = stack.mutate(*index, |e| delta.play_forward(e)); }
+    }
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let stack = state.alt_stack_mut();
+    match self {
+      AltStackChange::Push { index, expr } => move_off_stack(stack, *index, expr),
+      AltStackChange::Pop { index, expr } => move_onto_stack(stack, *index, expr),
+      AltStackChange::Replace { index, delta } => { let _ = stack.mutate(*index, |e| delta.play_backward(e)); }
+    }
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+
+  fn approx_size_in_bytes(&self) -> usize {
+    match self {
+      AltStackChange::Push { expr, .. } | AltStackChange::Pop { expr, .. } => optional_expr_size(expr),
+      AltStackChange::Replace { delta, .. } => delta.approx_size_in_bytes(),
+    }
+  }
 }
 
 impl UndoableChange<UndoableState> for ReplaceExprChange {
-  fn play_forward(&self, state: &mut UndoableState) {
+  fn play_forward(&mut self, state: &mut UndoableState) {
     // There should be no errors if we're undoing the right state, but
     // ignore any that occur, per UndoableChange's contract.
     let _ = state.main_stack_mut().mutate(self.index, |e| {
-      *e = self.new_expr.clone();
+      self.delta.play_forward(e);
     });
   }
 
-  fn play_backward(&self, state: &mut UndoableState) {
+  fn play_backward(&mut self, state: &mut UndoableState) {
     // There should be no errors if we're undoing the right state, but
     // ignore any that occur, per UndoableChange's contract.
     let _ = state.main_stack_mut().mutate(self.index, |e| {
-      *e = self.old_expr.clone()
+      self.delta.play_backward(e);
     });
   }
 
   fn undo_summary(&self) -> String {
     format!("{:?}", self)
   }
+
+  fn approx_size_in_bytes(&self) -> usize {
+    self.delta.approx_size_in_bytes()
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdateLabelChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.stack_labels_mut().set(self.index, self.new_label.clone());
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.stack_labels_mut().set(self.index, self.old_label.clone());
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdateGroupChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.stack_groups_mut().set(self.index, self.new_group.clone());
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.stack_groups_mut().set(self.index, self.old_group.clone());
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdatePinChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.stack_pins_mut().set(self.index, self.new_pinned);
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.stack_pins_mut().set(self.index, self.old_pinned);
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
 }
 
 impl UndoableChange<UndoableState> for UpdateVarChange {
-  fn play_forward(&self, state: &mut UndoableState) {
+  fn play_forward(&mut self, state: &mut UndoableState) {
     let table = state.variable_table_mut();
     match self.new_value.clone() {
       Some(new_value) => table.insert(self.var.clone(), new_value),
@@ -188,7 +468,7 @@ impl UndoableChange<UndoableState> for UpdateVarChange {
     };
   }
 
-  fn play_backward(&self, state: &mut UndoableState) {
+  fn play_backward(&mut self, state: &mut UndoableState) {
     let table = state.variable_table_mut();
     match self.old_value.clone() {
       Some(old_value) => table.insert(self.var.clone(), old_value),
@@ -199,6 +479,130 @@ impl UndoableChange<UndoableState> for UpdateVarChange {
   fn undo_summary(&self) -> String {
     format!("{:?}", self)
   }
+
+  fn approx_size_in_bytes(&self) -> usize {
+    optional_expr_size(&self.old_value) + optional_expr_size(&self.new_value)
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdateBookmarkChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    let table = state.bookmarks_mut();
+    match self.new_value.clone() {
+      Some(new_value) => table.insert(self.index, new_value),
+      None => table.remove(self.index),
+    };
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let table = state.bookmarks_mut();
+    match self.old_value.clone() {
+      Some(old_value) => table.insert(self.index, old_value),
+      None => table.remove(self.index),
+    };
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+
+  fn approx_size_in_bytes(&self) -> usize {
+    optional_expr_size(&self.old_value) + optional_expr_size(&self.new_value)
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdateUserFunctionChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    let table = state.user_functions_mut();
+    match self.new_value.clone() {
+      Some(new_value) => table.insert(self.name.clone(), new_value),
+      None => table.remove(&self.name),
+    };
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let table = state.user_functions_mut();
+    match self.old_value.clone() {
+      Some(old_value) => table.insert(self.name.clone(), old_value),
+      None => table.remove(&self.name),
+    };
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdateDerivativeRuleChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    let table = state.derivative_rules_mut();
+    match self.new_value.clone() {
+      Some(new_value) => table.insert(self.name.clone(), new_value),
+      None => table.remove(&self.name),
+    };
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let table = state.derivative_rules_mut();
+    match self.old_value.clone() {
+      Some(old_value) => table.insert(self.name.clone(), old_value),
+      None => table.remove(&self.name),
+    };
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdateAssumptionsChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.assumptions_mut().set(self.var.clone(), self.new_assumptions.clone());
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.assumptions_mut().set(self.var.clone(), self.old_assumptions.clone());
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for UpdateConversionContextChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    let table = state.conversion_contexts_mut();
+    match self.new_value.clone() {
+      Some(new_value) => table.insert(self.name.clone(), new_value),
+      None => table.remove(&self.name),
+    };
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    let table = state.conversion_contexts_mut();
+    match self.old_value.clone() {
+      Some(old_value) => table.insert(self.name.clone(), old_value),
+      None => table.remove(&self.name),
+    };
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl UndoableChange<UndoableState> for AddRewriteRuleChange {
+  fn play_forward(&mut self, state: &mut UndoableState) {
+    state.rewrite_rules_mut().push(self.rule.clone());
+  }
+
+  fn play_backward(&mut self, state: &mut UndoableState) {
+    state.rewrite_rules_mut().pop();
+  }
+
+  fn undo_summary(&self) -> String {
+    format!("{:?}", self)
+  }
 }
 
 impl Debug for ToggleFlagChange {
@@ -211,11 +615,11 @@ impl Debug for ToggleFlagChange {
 }
 
 impl UndoableChange<UndoableState> for ToggleFlagChange {
-  fn play_forward(&self, state: &mut UndoableState) {
+  fn play_forward(&mut self, state: &mut UndoableState) {
     (self.toggle_function)(state)
   }
 
-  fn play_backward(&self, state: &mut UndoableState) {
+  fn play_backward(&mut self, state: &mut UndoableState) {
     (self.toggle_function)(state)
   }
 
@@ -223,3 +627,97 @@ impl UndoableChange<UndoableState> for ToggleFlagChange {
     format!("{:?}", self)
   }
 }
+
+fn optional_expr_size(expr: &Option<Expr>) -> usize {
+  expr.as_ref().map_or(0, Expr::approx_size_in_bytes)
+}
+
+/// Moves the value at the given position off of the stack and into
+/// `slot`.
+fn move_off_stack(stack: &mut Stack<Expr>, index: usize, slot: &mut Option<Expr>) {
+  if let Ok(expr) = stack.pop_nth(index) {
+    *slot = Some(expr);
+  }
+}
+
+/// Moves the value in `slot` onto the stack at the given position.
+/// Does nothing if the position is out of bounds, in which case the
+/// value stays in `slot`.
+fn move_onto_stack(stack: &mut Stack<Expr>, index: usize, slot: &mut Option<Expr>) {
+  if index <= stack.len() {
+    if let Some(expr) = slot.take() {
+      // unwrap: We just checked the bounds.
+      stack.insert(index, expr).unwrap();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stack::test_utils::stack_of;
+
+  fn state_with_stack(values: Vec<i64>) -> UndoableState {
+    let mut state = UndoableState::default();
+    for value in values {
+      state.main_stack_mut().push(Expr::from(value));
+    }
+    state
+  }
+
+  #[test]
+  fn test_push_change_holds_value_only_while_undone() {
+    let mut state = state_with_stack(vec![10, 20]);
+    let mut change = PushExprChange::new(0);
+    assert_eq!(change.approx_size_in_bytes(), 0);
+
+    change.play_backward(&mut state);
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    assert_eq!(change.approx_size_in_bytes(), Expr::from(20).approx_size_in_bytes());
+
+    change.play_forward(&mut state);
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20]));
+    assert_eq!(change.approx_size_in_bytes(), 0);
+  }
+
+  #[test]
+  fn test_pop_change_holds_value_only_while_off_stack() {
+    let mut state = state_with_stack(vec![10, 30]);
+    let mut change = PopExprChange::new(1, Expr::from(20), None, false, None);
+
+    change.play_backward(&mut state);
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 20, 30]));
+    assert_eq!(change.approx_size_in_bytes(), 0);
+
+    change.play_forward(&mut state);
+    assert_eq!(state.main_stack(), &stack_of(vec![10, 30]));
+    assert_eq!(change.approx_size_in_bytes(), Expr::from(20).approx_size_in_bytes());
+  }
+
+  #[test]
+  fn test_alt_stack_push_and_pop() {
+    let mut state = UndoableState::default();
+    state.alt_stack_mut().push(Expr::from(10));
+    let mut push = AltStackChange::push(0);
+    push.play_backward(&mut state);
+    assert!(state.alt_stack().is_empty());
+    push.play_forward(&mut state);
+    assert_eq!(state.alt_stack(), &stack_of(vec![10]));
+
+    let mut pop = AltStackChange::pop(0, Expr::from(20));
+    pop.play_backward(&mut state);
+    assert_eq!(state.alt_stack(), &stack_of(vec![10, 20]));
+    pop.play_forward(&mut state);
+    assert_eq!(state.alt_stack(), &stack_of(vec![10]));
+  }
+
+  #[test]
+  fn test_push_change_out_of_bounds_does_nothing() {
+    let mut state = state_with_stack(vec![10]);
+    let mut change = PushExprChange { index: 3, expr: Some(Expr::from(20)) };
+    change.play_forward(&mut state);
+    assert_eq!(state.main_stack(), &stack_of(vec![10]));
+    // The value is kept, rather than lost.
+    assert_eq!(change.expr, Some(Expr::from(20)));
+  }
+}
@@ -0,0 +1,107 @@
+
+//! The kinds of windows the application can display.
+//!
+//! Every window shares the same [`TauriApplicationState`](super::TauriApplicationState).
+//! A window's kind is determined by its Tauri label: the main window
+//! is labeled `main`, and auxiliary windows are labeled with their
+//! kind's prefix followed by a unique number, such as `stack-1`.
+
+use serde::{Serialize, Deserialize};
+use tauri::{WebviewUrl, WebviewWindowBuilder};
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowKind {
+  /// The main calculator window, with the stack and button grid.
+  Main,
+  /// A secondary, read-only view of the stack.
+  Stack,
+  /// A dedicated window for rendering graphs.
+  Graph,
+}
+
+/// Source of unique numbers for auxiliary window labels.
+static NEXT_WINDOW_ID: AtomicUsize = AtomicUsize::new(1);
+
+impl WindowKind {
+  pub const MAIN_LABEL: &'static str = "main";
+
+  pub fn label_prefix(self) -> &'static str {
+    match self {
+      WindowKind::Main => WindowKind::MAIN_LABEL,
+      WindowKind::Stack => "stack",
+      WindowKind::Graph => "graph",
+    }
+  }
+
+  pub fn title(self) -> &'static str {
+    match self {
+      WindowKind::Main => "fifi",
+      WindowKind::Stack => "fifi - Stack",
+      WindowKind::Graph => "fifi - Graph",
+    }
+  }
+
+  /// Determines the kind of window from its Tauri label. Returns
+  /// `None` for labels not created by this module.
+  pub fn from_label(label: &str) -> Option<WindowKind> {
+    if label == WindowKind::MAIN_LABEL {
+      return Some(WindowKind::Main);
+    }
+    let (prefix, id) = label.split_once('-')?;
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+      return None;
+    }
+    [WindowKind::Stack, WindowKind::Graph].into_iter()
+      .find(|kind| kind.label_prefix() == prefix)
+  }
+
+  /// Produces a fresh label for a new window of this kind.
+  fn fresh_label(self) -> String {
+    let id = NEXT_WINDOW_ID.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{}", self.label_prefix(), id)
+  }
+}
+
+/// Opens a new auxiliary window of the given kind and returns its
+/// label. The frontend reads the `window` query parameter to decide
+/// which view to render. There is only ever one main window, so it is
+/// an error to request another.
+pub fn open_window(app_handle: &tauri::AppHandle, kind: WindowKind) -> anyhow::Result<String> {
+  anyhow::ensure!(kind != WindowKind::Main, "Cannot open a second main window");
+  let label = kind.fresh_label();
+  let url = WebviewUrl::App(PathBuf::from(format!("index.html?window={}", kind.label_prefix())));
+  WebviewWindowBuilder::new(app_handle, &label, url)
+    .title(kind.title())
+    .inner_size(480.0, 600.0)
+    .build()?;
+  Ok(label)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_from_label() {
+    assert_eq!(WindowKind::from_label("main"), Some(WindowKind::Main));
+    assert_eq!(WindowKind::from_label("stack-1"), Some(WindowKind::Stack));
+    assert_eq!(WindowKind::from_label("graph-20"), Some(WindowKind::Graph));
+    assert_eq!(WindowKind::from_label("main-1"), None);
+    assert_eq!(WindowKind::from_label("stack-"), None);
+    assert_eq!(WindowKind::from_label("stack-x"), None);
+    assert_eq!(WindowKind::from_label("other-1"), None);
+  }
+
+  #[test]
+  fn test_fresh_labels_roundtrip() {
+    let label1 = WindowKind::Stack.fresh_label();
+    let label2 = WindowKind::Stack.fresh_label();
+    assert_ne!(label1, label2);
+    assert_eq!(WindowKind::from_label(&label1), Some(WindowKind::Stack));
+    assert_eq!(WindowKind::from_label(&WindowKind::Graph.fresh_label()), Some(WindowKind::Graph));
+  }
+}
@@ -13,7 +13,7 @@
 /// ```
 /// # use fifi::undo::{UndoableChange, NoChange};
 /// # let mut state: i32 = 0;
-/// # let change = NoChange;
+/// # let mut change = NoChange;
 /// change.play_forward(&mut state);
 /// change.play_backward(&mut state);
 /// # assert_eq!(state, 0);
@@ -25,11 +25,15 @@
 /// ```
 /// # use fifi::undo::{UndoableChange, NoChange};
 /// # let mut state: i32 = 0;
-/// # let change = NoChange;
+/// # let mut change = NoChange;
 /// change.play_backward(&mut state);
 /// change.play_forward(&mut state);
 /// # assert_eq!(state, 0);
 /// ```
+///
+/// Playing a change may move data between the change and the state,
+/// so that a value which is present in the state need not also be
+/// stored in the change.
 pub trait UndoableChange<S> {
 
   /// Plays the action in the forward direction.
@@ -38,10 +42,10 @@ pub trait UndoableChange<S> {
   /// action is explicitly requested. Notably, `play_forward` is NOT
   /// called when the action is initially performed as part of the
   /// normal (non-undo-stack) flow of the program.
-  fn play_forward(&self, state: &mut S);
+  fn play_forward(&mut self, state: &mut S);
 
   /// Plays the action in the backward direction.
-  fn play_backward(&self, state: &mut S);
+  fn play_backward(&mut self, state: &mut S);
 
   /// Debug-friendly summary of the undo action. This method is
   /// optional and is only used in `Debug` impls. The output of this
@@ -50,6 +54,17 @@ pub trait UndoableChange<S> {
   fn undo_summary(&self) -> String {
     "UndoableChange".to_string()
   }
+
+  /// An estimate of the number of bytes of memory held by this
+  /// change, used to enforce the memory budget of an
+  /// [`UndoStack`](super::UndoStack). Changes which hold only small,
+  /// fixed-size data can use the default of zero.
+  ///
+  /// The estimate may change when the change is played, but it must
+  /// not change otherwise.
+  fn approx_size_in_bytes(&self) -> usize {
+    0
+  }
 }
 
 /// Empty `UndoableChange` that performs no action.
@@ -57,8 +72,8 @@ pub trait UndoableChange<S> {
 pub struct NoChange;
 
 impl<S> UndoableChange<S> for NoChange {
-  fn play_forward(&self, _: &mut S) {}
-  fn play_backward(&self, _: &mut S) {}
+  fn play_forward(&mut self, _: &mut S) {}
+  fn play_backward(&mut self, _: &mut S) {}
 
   fn undo_summary(&self) -> String {
     "NoChange".to_string()
@@ -7,4 +7,4 @@ mod stack;
 
 pub use change::{UndoableChange, NoChange};
 pub use error::UndoError;
-pub use stack::UndoStack;
+pub use stack::{UndoStack, UndoMark, UndoLimits};
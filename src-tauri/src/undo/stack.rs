@@ -2,6 +2,8 @@
 use super::change::UndoableChange;
 use super::error::UndoError;
 
+use serde::{Serialize, Deserialize};
+
 use std::fmt::{self, Formatter, Debug};
 
 /// A stack of undo-able actions, which can be played backward and
@@ -10,9 +12,43 @@ use std::fmt::{self, Formatter, Debug};
 /// The type `S` represents the state of the system. Undo and redo
 /// actions require a mutable reference to a value of type `S`, but no
 /// other restrictions are placed on what this type must be.
+///
+/// The stack may be given [`UndoLimits`], in which case the oldest
+/// undo actions are discarded as new ones are added, so that the
+/// history stays within the limits.
 pub struct UndoStack<S> {
   past: Vec<UndoStackValue<S>>,
   future: Vec<UndoStackValue<S>>,
+  limits: UndoLimits,
+  /// The number of values which have been discarded from the bottom
+  /// of the past stack to enforce the limits.
+  evicted_count: usize,
+  /// The total [`UndoableChange::approx_size_in_bytes`] of the
+  /// changes on the past stack. A change's size can only change when
+  /// it is played, so this is kept up to date as changes move on and
+  /// off the past stack, rather than recomputed.
+  past_bytes: usize,
+}
+
+/// Bounds on the size of an [`UndoStack`]'s history. The most recent
+/// undo action is always kept, even if it alone exceeds the limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoLimits {
+  /// The maximum number of undo actions, or `None` for no limit.
+  pub max_depth: Option<usize>,
+  /// The maximum total of [`UndoableChange::approx_size_in_bytes`]
+  /// across the history, or `None` for no limit.
+  pub max_bytes: Option<usize>,
+}
+
+/// A position in the history of an [`UndoStack`], which can later be
+/// returned to with [`UndoStack::revert_to_mark`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoMark {
+  /// The length the past stack would have if nothing had been
+  /// evicted from it.
+  position: usize,
 }
 
 enum UndoStackValue<S> {
@@ -23,16 +59,36 @@ enum UndoStackValue<S> {
 impl<S> UndoStack<S> {
   /// A new undo stack, with no actions available to undo or redo.
   pub fn new() -> Self {
+    Self::with_limits(UndoLimits::default())
+  }
+
+  /// A new, empty undo stack with the given limits.
+  pub fn with_limits(limits: UndoLimits) -> Self {
     UndoStack {
       past: Vec::new(),
       future: Vec::new(),
+      limits,
+      evicted_count: 0,
+      past_bytes: 0,
     }
   }
 
+  pub fn limits(&self) -> UndoLimits {
+    self.limits
+  }
+
+  /// Changes the limits on the history, immediately discarding the
+  /// oldest undo actions if the history exceeds the new limits.
+  pub fn set_limits(&mut self, limits: UndoLimits) {
+    self.limits = limits;
+    self.enforce_limits();
+  }
+
   /// Removes all actions from the past, so that `self.has_undos()` is
   /// false.
   pub fn clear_past(&mut self) {
     self.past.clear();
+    self.past_bytes = 0;
   }
 
   /// Removes all actions from the future, so that `self.has_redos()`
@@ -52,9 +108,11 @@ impl<S> UndoStack<S> {
   /// undoing and redoing when an action is requested.
   ///
   /// This also clears the future stack, since previously-available
-  /// redos are no longer relevant.
+  /// redos are no longer relevant. Since a cut begins a new undo
+  /// action, this is when the stack's limits are enforced.
   pub fn push_cut(&mut self) {
     self.future.clear();
+    self.enforce_limits();
     self.past.push(UndoStackValue::Cut);
   }
 
@@ -64,6 +122,7 @@ impl<S> UndoStack<S> {
   /// redos are no longer relevant.
   pub fn push_change(&mut self, change: impl UndoableChange<S> + Send + Sync + 'static) {
     self.future.clear();
+    self.past_bytes += change.approx_size_in_bytes();
     self.past.push(UndoStackValue::Change(Box::new(change)));
   }
 
@@ -81,9 +140,12 @@ impl<S> UndoStack<S> {
   /// moved to the redo stack, but no `UndoableChange` methods were
   /// called.
   pub fn undo(&mut self, state: &mut S) -> Result<(), UndoError> {
+    let mut moved_bytes = 0;
     let is_successful = play_actions(&mut self.past, &mut self.future, |action| {
-      action.play_backward(state)
+      moved_bytes += action.approx_size_in_bytes();
+      action.play_backward(state);
     });
+    self.past_bytes -= moved_bytes;
     if is_successful {
       Ok(())
     } else {
@@ -105,9 +167,12 @@ impl<S> UndoStack<S> {
   /// moved to the undo stack, but no `UndoableChange` methods were
   /// called.
   pub fn redo(&mut self, state: &mut S) -> Result<(), UndoError> {
+    let mut moved_bytes = 0;
     let is_successful = play_actions(&mut self.future, &mut self.past, |action| {
-      action.play_forward(state)
+      action.play_forward(state);
+      moved_bytes += action.approx_size_in_bytes();
     });
+    self.past_bytes += moved_bytes;
     if is_successful {
       Ok(())
     } else {
@@ -115,16 +180,136 @@ impl<S> UndoStack<S> {
     }
   }
 
+  /// Records the current position in the undo history.
+  pub fn mark(&self) -> UndoMark {
+    UndoMark { position: self.evicted_count + self.past.len() }
+  }
+
+  /// Plays backward every change pushed since `mark` was taken, and
+  /// discards those changes (and any cuts) from the history. Unlike
+  /// [`UndoStack::undo`], the discarded changes are not made
+  /// available to redo.
+  ///
+  /// This is intended for abandoning a partially-completed action,
+  /// so it is only meaningful if no undo or redo has happened since
+  /// the mark was taken. If the past stack is already at or below the
+  /// mark, this method does nothing. Changes which have been evicted
+  /// to enforce the stack's limits cannot be reverted.
+  pub fn revert_to_mark(&mut self, mark: UndoMark, state: &mut S) {
+    let past_len = self.past_len_at(mark);
+    while self.past.len() > past_len {
+      if let Some(UndoStackValue::Change(mut change)) = self.past.pop() {
+        self.past_bytes -= change.approx_size_in_bytes();
+        change.play_backward(state);
+      }
+    }
+  }
+
+  /// Removes every cut pushed since `mark` was taken, so that all
+  /// changes made since then are undone together as a single action.
+  pub fn merge_since_mark(&mut self, mark: UndoMark) {
+    let past_len = self.past_len_at(mark);
+    if self.past.len() > past_len {
+      let mut recent = self.past.split_off(past_len);
+      recent.retain(|value| !value.is_cut());
+      self.past.extend(recent);
+    }
+  }
+
+  /// Moves every action from the past stack of `other` onto this
+  /// one, as though they had been pushed here in the same order.
+  /// `other`'s future stack is discarded, as is this stack's. The
+  /// combined history is then trimmed to this stack's limits.
+  pub fn append(&mut self, other: UndoStack<S>) {
+    self.future.clear();
+    self.past_bytes += other.past_bytes;
+    self.past.extend(other.past);
+    self.enforce_limits();
+  }
+
   /// Returns true if there are any changes on the past stack to undo.
   pub fn has_undos(&self) -> bool {
     !self.past.iter().all(UndoStackValue::is_cut)
   }
 
+  /// The number of undo actions currently available. Each undo
+  /// action consists of all changes between two cuts.
+  pub fn undo_depth(&self) -> usize {
+    count_action_groups(&self.past)
+  }
+
+  /// The number of redo actions currently available.
+  pub fn redo_depth(&self) -> usize {
+    count_action_groups(&self.future)
+  }
+
   /// Returns true if there are any changes on the future stack to
   /// redo.
   pub fn has_redos(&self) -> bool {
     !self.future.iter().all(UndoStackValue::is_cut)
   }
+
+  /// An estimate of the number of bytes of memory held by the
+  /// changes on the past stack.
+  pub fn approx_size_in_bytes(&self) -> usize {
+    self.past_bytes
+  }
+
+  /// The length of the past stack at the time the mark was taken,
+  /// accounting for values evicted since then.
+  fn past_len_at(&self, mark: UndoMark) -> usize {
+    mark.position.saturating_sub(self.evicted_count)
+  }
+
+  /// Discards the oldest undo actions until the past stack is within
+  /// the limits, or until only one undo action remains.
+  fn enforce_limits(&mut self) {
+    let mut depth = self.undo_depth();
+    let mut evict_until = 0;
+    while depth > 1 && (
+      self.limits.max_depth.is_some_and(|max_depth| depth > max_depth) ||
+      self.limits.max_bytes.is_some_and(|max_bytes| self.past_bytes > max_bytes)
+    ) {
+      // Skip to the end of the oldest remaining action.
+      while self.past[evict_until].is_cut() {
+        evict_until += 1;
+      }
+      while evict_until < self.past.len() && !self.past[evict_until].is_cut() {
+        self.past_bytes -= self.past[evict_until].approx_size_in_bytes();
+        evict_until += 1;
+      }
+      depth -= 1;
+    }
+    self.past.drain(..evict_until);
+    self.evicted_count += evict_until;
+  }
+}
+
+impl UndoLimits {
+  /// Limits which never discard any history.
+  pub fn unlimited() -> Self {
+    Self::default()
+  }
+}
+
+/// Counts the maximal runs of consecutive changes in the stack.
+fn count_action_groups<S>(stack: &[UndoStackValue<S>]) -> usize {
+  let mut count = 0;
+  let mut in_group = false;
+  for value in stack {
+    match value {
+      UndoStackValue::Cut => {
+        in_group = false;
+      }
+      UndoStackValue::Change(_) => {
+        if !in_group {
+          count += 1;
+        }
+        in_group = true;
+      }
+    }
+  }
+  count
 }
 
 /// Plays actions from a stack, pushing onto another stack. Returns
@@ -135,7 +320,7 @@ fn play_actions<S, F>(
   dest: &mut Vec<UndoStackValue<S>>,
   mut play_function: F,
 ) -> bool
-where F: FnMut(&dyn UndoableChange<S>) {
+where F: FnMut(&mut dyn UndoableChange<S>) {
   // Pop zero or more cuts before any actions.
   while matches!(source.last(), Some(UndoStackValue::Cut)) {
     let top_cut = source.pop().expect("stack should be nonempty");
@@ -149,10 +334,10 @@ where F: FnMut(&dyn UndoableChange<S>) {
 
   // Now play any actions we encounter up to the next cut.
   while matches!(source.last(), Some(UndoStackValue::Change(_))) {
-    let Some(UndoStackValue::Change(action)) = source.pop() else {
+    let Some(UndoStackValue::Change(mut action)) = source.pop() else {
       panic!("top of stack must be an UndoStackValue::Change");
     };
-    play_function(action.as_ref());
+    play_function(action.as_mut());
     dest.push(UndoStackValue::Change(action));
   }
   true
@@ -162,6 +347,13 @@ impl<S> UndoStackValue<S> {
   pub fn is_cut(&self) -> bool {
     matches!(self, UndoStackValue::Cut)
   }
+
+  fn approx_size_in_bytes(&self) -> usize {
+    match self {
+      UndoStackValue::Cut => 0,
+      UndoStackValue::Change(change) => change.approx_size_in_bytes(),
+    }
+  }
 }
 
 impl<S> Default for UndoStack<S> {
@@ -184,6 +376,8 @@ impl<S> Debug for UndoStack<S> {
     f.debug_struct("UndoStack")
       .field("past", &self.past)
       .field("future", &self.future)
+      .field("limits", &self.limits)
+      .field("past_bytes", &self.past_bytes)
       .finish()
   }
 }
@@ -199,23 +393,43 @@ mod tests {
   struct MulTwoAction;
 
   impl UndoableChange<i32> for AddOneAction {
-    fn play_forward(&self, state: &mut i32) {
+    fn play_forward(&mut self, state: &mut i32) {
       *state += 1;
     }
-    fn play_backward(&self, state: &mut i32) {
+    fn play_backward(&mut self, state: &mut i32) {
       *state -= 1;
     }
   }
 
   impl UndoableChange<i32> for MulTwoAction {
-    fn play_forward(&self, state: &mut i32) {
+    fn play_forward(&mut self, state: &mut i32) {
       *state *= 2;
     }
-    fn play_backward(&self, state: &mut i32) {
+    fn play_backward(&mut self, state: &mut i32) {
       *state /= 2;
     }
   }
 
+  /// A change that does nothing but claims to hold the given number
+  /// of bytes.
+  #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+  struct SizedAction(usize);
+
+  impl UndoableChange<i32> for SizedAction {
+    fn play_forward(&mut self, _: &mut i32) {}
+    fn play_backward(&mut self, _: &mut i32) {}
+    fn approx_size_in_bytes(&self) -> usize {
+      self.0
+    }
+  }
+
+  /// Checks the running byte total against a recount of the past
+  /// stack.
+  fn assert_byte_total<S>(stack: &UndoStack<S>) {
+    let recount: usize = stack.past.iter().map(UndoStackValue::approx_size_in_bytes).sum();
+    assert_eq!(stack.approx_size_in_bytes(), recount);
+  }
+
   #[test]
   fn test_empty_stack() {
     let mut stack = UndoStack::<i32>::new();
@@ -225,6 +439,97 @@ mod tests {
     assert_eq!(stack.redo(&mut 0), Err(UndoError::NothingToRedo));
   }
 
+  #[test]
+  fn test_undo_depth() {
+    let mut stack = UndoStack::<i32>::new();
+    assert_eq!(stack.undo_depth(), 0);
+    stack.push_cut();
+    assert_eq!(stack.undo_depth(), 0);
+    stack.push_change(MulTwoAction);
+    stack.push_change(AddOneAction);
+    assert_eq!(stack.undo_depth(), 1);
+    stack.push_cut();
+    stack.push_cut();
+    stack.push_change(AddOneAction);
+    assert_eq!(stack.undo_depth(), 2);
+    assert_eq!(stack.redo_depth(), 0);
+
+    stack.undo(&mut 0).unwrap();
+    assert_eq!(stack.undo_depth(), 1);
+    assert_eq!(stack.redo_depth(), 1);
+  }
+
+  #[test]
+  fn test_revert_to_mark() {
+    let mut stack = UndoStack::<i32>::new();
+    let mut state = 3;
+    stack.push_change(AddOneAction);
+    state += 1;
+    let mark = stack.mark();
+    stack.push_cut();
+    stack.push_change(MulTwoAction);
+    state *= 2;
+    stack.push_change(AddOneAction);
+    state += 1;
+
+    stack.revert_to_mark(mark, &mut state);
+    assert_eq!(state, 4);
+    assert_eq!(stack.mark(), mark);
+    assert!(!stack.has_redos());
+
+    // Reverting again is a no-op.
+    stack.revert_to_mark(mark, &mut state);
+    assert_eq!(state, 4);
+    stack.undo(&mut state).unwrap();
+    assert_eq!(state, 3);
+  }
+
+  #[test]
+  fn test_merge_since_mark() {
+    let mut stack = UndoStack::<i32>::new();
+    let mut state = 3;
+    stack.push_cut();
+    stack.push_change(AddOneAction);
+    state += 1;
+    stack.push_cut();
+    let mark = stack.mark();
+    stack.push_change(MulTwoAction);
+    state *= 2;
+    stack.push_cut();
+    stack.push_change(AddOneAction);
+    state += 1;
+    assert_eq!(stack.undo_depth(), 3);
+
+    stack.merge_since_mark(mark);
+    assert_eq!(stack.undo_depth(), 2);
+    stack.undo(&mut state).unwrap();
+    assert_eq!(state, 4);
+    stack.redo(&mut state).unwrap();
+    assert_eq!(state, 9);
+  }
+
+  #[test]
+  fn test_append() {
+    let mut stack = UndoStack::<i32>::new();
+    let mut state = 3;
+    stack.push_cut();
+    stack.push_change(AddOneAction);
+    state += 1;
+
+    let mut other = UndoStack::<i32>::new();
+    other.push_cut();
+    other.push_change(MulTwoAction);
+    state *= 2;
+    stack.append(other);
+    assert_eq!(stack.undo_depth(), 2);
+    assert!(!stack.has_redos());
+
+    stack.undo(&mut state).unwrap();
+    assert_eq!(state, 4);
+    stack.undo(&mut state).unwrap();
+    assert_eq!(state, 3);
+  }
+
   #[test]
   fn test_single_undo_and_redo() {
     let mut stack = UndoStack::<i32>::new();
@@ -419,4 +724,130 @@ mod tests {
     assert!(!stack.has_redos());
   }
 
+  #[test]
+  fn test_max_depth_evicts_oldest_actions() {
+    let mut stack = UndoStack::<i32>::with_limits(UndoLimits { max_depth: Some(2), max_bytes: None });
+    let mut state = 1;
+    for _ in 0..4 {
+      stack.push_cut();
+      stack.push_change(AddOneAction);
+      state += 1;
+    }
+    // The limit is enforced when the next action begins.
+    assert_eq!(stack.undo_depth(), 3);
+    stack.push_cut();
+    assert_eq!(stack.undo_depth(), 2);
+
+    stack.undo(&mut state).unwrap();
+    stack.undo(&mut state).unwrap();
+    assert_eq!(stack.undo(&mut state), Err(UndoError::NothingToUndo));
+    assert_eq!(state, 3);
+  }
+
+  #[test]
+  fn test_append_enforces_limits() {
+    let mut stack = UndoStack::<i32>::with_limits(UndoLimits { max_depth: Some(2), max_bytes: None });
+    stack.push_cut();
+    stack.push_change(SizedAction(1));
+    let mut other = UndoStack::<i32>::new();
+    for size in [10, 20, 30] {
+      other.push_cut();
+      other.push_change(SizedAction(size));
+    }
+    stack.append(other);
+    assert_eq!(stack.undo_depth(), 2);
+    assert_eq!(stack.approx_size_in_bytes(), 50);
+    assert_byte_total(&stack);
+  }
+
+  #[test]
+  fn test_max_bytes_evicts_oldest_actions() {
+    let mut stack = UndoStack::<i32>::with_limits(UndoLimits { max_depth: None, max_bytes: Some(100) });
+    stack.push_cut();
+    stack.push_change(SizedAction(40));
+    stack.push_change(SizedAction(20));
+    stack.push_cut();
+    stack.push_change(SizedAction(50));
+    stack.push_cut();
+    assert_eq!(stack.undo_depth(), 1);
+    assert_eq!(stack.approx_size_in_bytes(), 50);
+
+    // The most recent action is kept even if it is too large.
+    stack.push_change(SizedAction(500));
+    stack.push_cut();
+    assert_eq!(stack.undo_depth(), 1);
+    assert_eq!(stack.approx_size_in_bytes(), 500);
+    assert_byte_total(&stack);
+  }
+
+  #[test]
+  fn test_byte_total_follows_undo_and_redo() {
+    let mut stack = UndoStack::<i32>::new();
+    let mut state = 0;
+    stack.push_cut();
+    stack.push_change(SizedAction(10));
+    stack.push_cut();
+    stack.push_change(SizedAction(20));
+    stack.push_change(SizedAction(30));
+    assert_eq!(stack.approx_size_in_bytes(), 60);
+    assert_byte_total(&stack);
+
+    stack.undo(&mut state).unwrap();
+    assert_eq!(stack.approx_size_in_bytes(), 10);
+    assert_byte_total(&stack);
+    stack.redo(&mut state).unwrap();
+    assert_eq!(stack.approx_size_in_bytes(), 60);
+    assert_byte_total(&stack);
+
+    let mark = stack.mark();
+    stack.push_cut();
+    stack.push_change(SizedAction(40));
+    stack.revert_to_mark(mark, &mut state);
+    assert_eq!(stack.approx_size_in_bytes(), 60);
+    assert_byte_total(&stack);
+
+    let mut other = UndoStack::<i32>::new();
+    other.push_cut();
+    other.push_change(SizedAction(5));
+    stack.append(other);
+    assert_eq!(stack.approx_size_in_bytes(), 65);
+    assert_byte_total(&stack);
+
+    stack.clear();
+    assert_eq!(stack.approx_size_in_bytes(), 0);
+    assert_byte_total(&stack);
+  }
+
+  #[test]
+  fn test_set_limits_evicts_immediately() {
+    let mut stack = UndoStack::<i32>::new();
+    for _ in 0..5 {
+      stack.push_cut();
+      stack.push_change(AddOneAction);
+    }
+    assert_eq!(stack.undo_depth(), 5);
+    stack.set_limits(UndoLimits { max_depth: Some(3), max_bytes: None });
+    assert_eq!(stack.undo_depth(), 3);
+    stack.set_limits(UndoLimits::unlimited());
+    assert_eq!(stack.undo_depth(), 3);
+  }
+
+  #[test]
+  fn test_mark_survives_eviction() {
+    let mut stack = UndoStack::<i32>::with_limits(UndoLimits { max_depth: Some(1), max_bytes: None });
+    let mut state = 0;
+    stack.push_cut();
+    stack.push_change(AddOneAction);
+    state += 1;
+    let mark = stack.mark();
+    stack.push_cut();
+    stack.push_change(MulTwoAction);
+    state *= 2;
+    stack.push_cut();
+    assert_eq!(stack.undo_depth(), 1);
+
+    stack.revert_to_mark(mark, &mut state);
+    assert_eq!(state, 1);
+    assert!(!stack.has_undos());
+  }
 }
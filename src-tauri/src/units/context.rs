@@ -0,0 +1,311 @@
+
+//! User-defined conversion contexts, which relate quantities that
+//! are not simply proportional to one another.
+//!
+//! A conversion context is a named collection of links. Each link
+//! relates a source unit to a target unit by some
+//! [`ConversionRelation`], such as the reciprocal relationship
+//! between fuel consumption (`L/km`) and fuel economy (`mi/gal`), or a
+//! lookup table like the American wire gauge.
+
+use crate::expr::Expr;
+use crate::expr::atom::Atom;
+use crate::expr::number::Number;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::collections::BTreeMap;
+
+/// The conversion contexts defined by the user, indexed by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConversionContextTable {
+  contexts: BTreeMap<String, ConversionContext>,
+}
+
+/// A collection of links between units. When converting within a
+/// context, the first link whose units match the source and target
+/// dimensions is used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionContext {
+  links: Vec<ConversionLink>,
+}
+
+/// A relationship between a source unit and a target unit. The units
+/// are stored as they were written, and are parsed again whenever the
+/// link is used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionLink {
+  pub source: String,
+  pub target: String,
+  pub relation: ConversionRelation,
+}
+
+/// How the value of a quantity in the target unit of a
+/// [`ConversionLink`] is computed from its value in the source unit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversionRelation {
+  /// The target value is the given constant divided by the source
+  /// value. This relation is its own inverse.
+  Reciprocal(Number),
+  /// The target value is linearly interpolated between the two
+  /// nearest points of the table. The points are sorted by source
+  /// value, and both columns are strictly monotonic, so the table can
+  /// be read in either direction. Values outside the table cannot be
+  /// converted.
+  Table(Vec<(Number, Number)>),
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ConversionContextError {
+  #[error("Malformed conversion context: {0}")]
+  JsonError(#[from] serde_json::Error),
+  #[error("Conversion context must contain at least one link")]
+  NoLinks,
+  #[error("Each link must have exactly one of 'reciprocal' or 'table'")]
+  AmbiguousRelation,
+  #[error("Reciprocal constant must be finite and nonzero")]
+  InvalidConstant,
+  #[error("Conversion table must contain finite values")]
+  InvalidTableValue,
+  #[error("Conversion table must have at least two points")]
+  TooFewPoints,
+  #[error("Conversion table must be strictly monotonic in both columns")]
+  NonMonotonicTable,
+  #[error("Conversion table requires a real number, got {0}")]
+  NonNumericValue(String),
+  #[error("Value {0} is outside the range of the conversion table")]
+  OutOfRange(Number),
+}
+
+/// The JSON shape accepted by [`ConversionContext::from_json`].
+#[derive(Debug, Deserialize)]
+struct RawContext {
+  links: Vec<RawLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLink {
+  from: String,
+  to: String,
+  reciprocal: Option<f64>,
+  table: Option<Vec<(f64, f64)>>,
+}
+
+impl ConversionContextTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.contexts.is_empty()
+  }
+
+  pub fn get(&self, name: &str) -> Option<&ConversionContext> {
+    self.contexts.get(name)
+  }
+
+  /// Defines or redefines a context, returning the old definition.
+  pub fn insert(&mut self, name: String, context: ConversionContext) -> Option<ConversionContext> {
+    self.contexts.insert(name, context)
+  }
+
+  pub fn remove(&mut self, name: &str) -> Option<ConversionContext> {
+    self.contexts.remove(name)
+  }
+
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.contexts.keys().map(String::as_str)
+  }
+}
+
+impl ConversionContext {
+  /// Parses a context of the form `{"links": [{"from": "L/km", "to":
+  /// "mi/gal", "reciprocal": 2.352}, {"from": "1", "to": "mm",
+  /// "table": [[0, 8.25], [10, 2.59]]}]}`. Each link must specify
+  /// exactly one of `reciprocal` and `table`. The unit names are not
+  /// validated here, since that requires a unit parser.
+  pub fn from_json(json: &str) -> Result<Self, ConversionContextError> {
+    let raw: RawContext = serde_json::from_str(json)?;
+    if raw.links.is_empty() {
+      return Err(ConversionContextError::NoLinks);
+    }
+    let links = raw.links.into_iter()
+      .map(ConversionLink::from_raw)
+      .collect::<Result<_, _>>()?;
+    Ok(Self { links })
+  }
+
+  pub fn links(&self) -> &[ConversionLink] {
+    &self.links
+  }
+}
+
+impl ConversionLink {
+  fn from_raw(raw: RawLink) -> Result<Self, ConversionContextError> {
+    let relation = match (raw.reciprocal, raw.table) {
+      (Some(constant), None) => {
+        if !constant.is_finite() || constant == 0.0 {
+          return Err(ConversionContextError::InvalidConstant);
+        }
+        ConversionRelation::Reciprocal(Number::from(constant))
+      }
+      (None, Some(table)) => {
+        if table.iter().any(|(x, y)| !x.is_finite() || !y.is_finite()) {
+          return Err(ConversionContextError::InvalidTableValue);
+        }
+        let points = table.into_iter().map(|(x, y)| (Number::from(x), Number::from(y))).collect();
+        ConversionRelation::table(points)?
+      }
+      _ => return Err(ConversionContextError::AmbiguousRelation),
+    };
+    Ok(Self { source: raw.from, target: raw.to, relation })
+  }
+}
+
+impl ConversionRelation {
+  /// Constructs a [`ConversionRelation::Table`], sorting the points
+  /// and checking that both columns are strictly monotonic.
+  pub fn table(mut points: Vec<(Number, Number)>) -> Result<Self, ConversionContextError> {
+    if points.len() < 2 {
+      return Err(ConversionContextError::TooFewPoints);
+    }
+    points.sort_by(|(x1, _), (x2, _)| x1.cmp(x2));
+    let sources_increasing = points.windows(2).all(|w| w[0].0 < w[1].0);
+    let targets_increasing = points.windows(2).all(|w| w[0].1 < w[1].1);
+    let targets_decreasing = points.windows(2).all(|w| w[0].1 > w[1].1);
+    if !sources_increasing || !(targets_increasing || targets_decreasing) {
+      return Err(ConversionContextError::NonMonotonicTable);
+    }
+    Ok(ConversionRelation::Table(points))
+  }
+
+  /// Converts a value in the source unit into a value in the target
+  /// unit. Tables require `value` to be a real number literal.
+  pub fn apply(&self, value: Expr) -> Result<Expr, ConversionContextError> {
+    match self {
+      ConversionRelation::Reciprocal(constant) => Ok(reciprocal(constant, value)),
+      ConversionRelation::Table(points) => {
+        let x = expect_number(value)?;
+        interpolate(points.iter().map(|(x, y)| (x, y)), &x).map(Expr::from)
+      }
+    }
+  }
+
+  /// Converts a value in the target unit back into a value in the
+  /// source unit.
+  pub fn apply_inverse(&self, value: Expr) -> Result<Expr, ConversionContextError> {
+    match self {
+      ConversionRelation::Reciprocal(constant) => Ok(reciprocal(constant, value)),
+      ConversionRelation::Table(points) => {
+        let y = expect_number(value)?;
+        let mut inverse_points: Vec<_> = points.iter().map(|(x, y)| (y, x)).collect();
+        inverse_points.sort_by_key(|(y, _)| *y);
+        interpolate(inverse_points.into_iter(), &y).map(Expr::from)
+      }
+    }
+  }
+}
+
+fn reciprocal(constant: &Number, value: Expr) -> Expr {
+  Expr::call("/", vec![Expr::from(constant.clone()), value])
+}
+
+fn expect_number(value: Expr) -> Result<Number, ConversionContextError> {
+  match value {
+    Expr::Atom(Atom::Number(n)) => Ok(n),
+    value => Err(ConversionContextError::NonNumericValue(value.to_string())),
+  }
+}
+
+/// Linearly interpolates `x` between the points, which must be sorted
+/// by their first coordinate.
+fn interpolate<'a>(
+  points: impl Iterator<Item = (&'a Number, &'a Number)>,
+  x: &Number,
+) -> Result<Number, ConversionContextError> {
+  let mut previous: Option<(&Number, &Number)> = None;
+  for (x1, y1) in points {
+    if x1 == x {
+      return Ok(y1.clone());
+    }
+    if let Some((x0, y0)) = previous {
+      if x0 < x && x < x1 {
+        let slope = (y1 - y0) / (x1 - x0);
+        return Ok(y0 + slope * (x - x0));
+      }
+    }
+    previous = Some((x1, y1));
+  }
+  Err(ConversionContextError::OutOfRange(x.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn table(points: &[(i64, i64)]) -> Result<ConversionRelation, ConversionContextError> {
+    ConversionRelation::table(points.iter().map(|(x, y)| (Number::from(*x), Number::from(*y))).collect())
+  }
+
+  #[test]
+  fn test_from_json() {
+    let json = r#"{"links": [
+      {"from": "L/km", "to": "mi/gal", "reciprocal": 2.5},
+      {"from": "1", "to": "mm", "table": [[10, 2.5], [0, 8.0]]}
+    ]}"#;
+    let context = ConversionContext::from_json(json).unwrap();
+    assert_eq!(context.links().len(), 2);
+    assert_eq!(context.links()[0].relation, ConversionRelation::Reciprocal(Number::from(2.5)));
+    assert_eq!(
+      context.links()[1].relation,
+      ConversionRelation::Table(vec![(Number::from(0.0), Number::from(8.0)), (Number::from(10.0), Number::from(2.5))]),
+    );
+  }
+
+  #[test]
+  fn test_from_json_invalid() {
+    let err = ConversionContext::from_json(r#"{"links": []}"#).unwrap_err();
+    assert!(matches!(err, ConversionContextError::NoLinks));
+    let err = ConversionContext::from_json(r#"{"links": [{"from": "m", "to": "s"}]}"#).unwrap_err();
+    assert!(matches!(err, ConversionContextError::AmbiguousRelation));
+    let err = ConversionContext::from_json(r#"{"links": [{"from": "m", "to": "s", "reciprocal": 0}]}"#).unwrap_err();
+    assert!(matches!(err, ConversionContextError::InvalidConstant));
+    let err = ConversionContext::from_json(r#"{"links": [{"from": "m", "to": "s", "table": [[1, 1]]}]}"#).unwrap_err();
+    assert!(matches!(err, ConversionContextError::TooFewPoints));
+    let err = ConversionContext::from_json("not json").unwrap_err();
+    assert!(matches!(err, ConversionContextError::JsonError(_)));
+  }
+
+  #[test]
+  fn test_table_must_be_monotonic() {
+    assert!(table(&[(0, 0), (1, 10), (2, 20)]).is_ok());
+    assert!(table(&[(0, 20), (1, 10), (2, 0)]).is_ok());
+    assert!(matches!(table(&[(0, 0), (1, 10), (2, 5)]), Err(ConversionContextError::NonMonotonicTable)));
+    assert!(matches!(table(&[(0, 0), (0, 10)]), Err(ConversionContextError::NonMonotonicTable)));
+  }
+
+  #[test]
+  fn test_table_interpolation() {
+    let relation = table(&[(0, 100), (10, 50), (20, 0)]).unwrap();
+    assert_eq!(relation.apply(Expr::from(10)).unwrap(), Expr::from(50));
+    assert_eq!(relation.apply(Expr::from(5)).unwrap(), Expr::from(75));
+    assert_eq!(relation.apply(Expr::from(15)).unwrap(), Expr::from(25));
+    assert_eq!(relation.apply_inverse(Expr::from(75)).unwrap(), Expr::from(5));
+    assert_eq!(relation.apply_inverse(Expr::from(0)).unwrap(), Expr::from(20));
+    assert!(matches!(relation.apply(Expr::from(21)), Err(ConversionContextError::OutOfRange(_))));
+    assert!(matches!(relation.apply(Expr::var("x").unwrap()), Err(ConversionContextError::NonNumericValue(_))));
+  }
+
+  #[test]
+  fn test_reciprocal() {
+    let relation = ConversionRelation::Reciprocal(Number::from(2));
+    let x = Expr::var("x").unwrap();
+    let expected = Expr::call("/", vec![Expr::from(2), x.clone()]);
+    assert_eq!(relation.apply(x.clone()).unwrap(), expected);
+    assert_eq!(relation.apply_inverse(x).unwrap(), expected);
+  }
+}
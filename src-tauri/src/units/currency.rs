@@ -0,0 +1,269 @@
+
+//! Currency units, whose conversion factors come from a table of
+//! exchange rates rather than from a fixed definition.
+//!
+//! All currencies live in the [`BaseDimension::Currency`] dimension
+//! and convert through [`REFERENCE_CURRENCY`]. Rates are always
+//! expressed as the amount of a currency which is worth one unit of
+//! the reference currency.
+
+use super::unit::Unit;
+use super::dimension::BaseDimension;
+use super::parsing::{UnitParser, UnitParserError};
+
+use num::One;
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Div;
+
+/// The currency that all other currencies convert through. This is
+/// the base unit of the currency dimension.
+pub const REFERENCE_CURRENCY: &str = "USD";
+
+/// Approximate exchange rates, in ten-thousandths of a unit of each
+/// currency per US dollar. These are only a reasonable offline
+/// default, and users who need current rates should load them with
+/// [`CurrencyRateTable::from_json`].
+const STATIC_RATES: &[(&str, i64)] = &[
+  ("USD", 10_000),
+  ("EUR", 9_200),
+  ("GBP", 7_900),
+  ("JPY", 1_500_000),
+  ("CHF", 8_800),
+  ("CAD", 13_600),
+  ("AUD", 15_200),
+  ("NZD", 16_400),
+  ("CNY", 72_000),
+  ("HKD", 78_000),
+  ("SGD", 13_500),
+  ("INR", 830_000),
+  ("KRW", 13_500_000),
+  ("MXN", 170_000),
+  ("BRL", 50_000),
+  ("ZAR", 185_000),
+  ("SEK", 105_000),
+  ("NOK", 106_000),
+];
+
+/// A source of exchange rates.
+pub trait CurrencyRateProvider<T> {
+  /// The amount of the given currency which is worth one unit of
+  /// [`REFERENCE_CURRENCY`], or `None` if the currency is unknown.
+  fn rate(&self, code: &str) -> Option<T>;
+
+  /// All of the currency codes known to this provider.
+  fn currency_codes(&self) -> Vec<String>;
+}
+
+/// The built-in offline exchange rate table.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StaticCurrencyRates;
+
+/// An exchange rate table, normalized to [`REFERENCE_CURRENCY`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurrencyRateTable<T> {
+  rates: BTreeMap<String, T>,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CurrencyRateError {
+  #[error("Malformed currency rate table: {0}")]
+  JsonError(#[from] serde_json::Error),
+  #[error("Currency rate table must include a rate for {REFERENCE_CURRENCY}")]
+  MissingReferenceCurrency,
+  #[error("Invalid rate for currency {0}")]
+  InvalidRate(String),
+}
+
+/// The JSON shape accepted by [`CurrencyRateTable::from_json`].
+#[derive(Debug, Deserialize)]
+struct RawRateTable {
+  #[serde(default = "default_base_currency")]
+  base: String,
+  rates: HashMap<String, f64>,
+}
+
+/// A [`UnitParser`] which recognizes currency codes as units, falling
+/// back to currencies only when the inner parser fails.
+#[derive(Debug, Clone)]
+pub struct CurrencyUnitParser<P, R> {
+  inner: P,
+  rates: R,
+}
+
+fn default_base_currency() -> String {
+  REFERENCE_CURRENCY.to_owned()
+}
+
+impl<T> CurrencyRateProvider<T> for StaticCurrencyRates
+where T: From<i64> + Div<Output = T> {
+  fn rate(&self, code: &str) -> Option<T> {
+    STATIC_RATES.iter()
+      .find(|(name, _)| *name == code)
+      .map(|(_, rate)| T::from(*rate) / T::from(10_000))
+  }
+
+  fn currency_codes(&self) -> Vec<String> {
+    STATIC_RATES.iter().map(|(name, _)| (*name).to_owned()).collect()
+  }
+}
+
+impl<T> CurrencyRateTable<T> {
+  /// Copies every rate out of the given provider.
+  pub fn from_provider<P>(provider: &P) -> Self
+  where P: CurrencyRateProvider<T> + ?Sized {
+    let rates = provider.currency_codes().into_iter()
+      .filter_map(|code| provider.rate(&code).map(|rate| (code, rate)))
+      .collect();
+    Self { rates }
+  }
+
+  /// Parses a table of the form `{"base": "EUR", "rates": {"USD":
+  /// 1.09, ...}}`, where each rate is the amount of that currency
+  /// worth one unit of the base currency. The base defaults to
+  /// [`REFERENCE_CURRENCY`]. If the base is some other currency, the
+  /// table must include a rate for the reference currency, so that
+  /// the rates can be normalized.
+  pub fn from_json(json: &str) -> Result<Self, CurrencyRateError>
+  where T: From<f64> {
+    let raw: RawRateTable = serde_json::from_str(json)?;
+    if let Some((code, _)) = raw.rates.iter().find(|(_, rate)| !(rate.is_finite() && **rate > 0.0)) {
+      return Err(CurrencyRateError::InvalidRate(code.to_owned()));
+    }
+    let reference_rate = if raw.base == REFERENCE_CURRENCY {
+      1.0
+    } else {
+      *raw.rates.get(REFERENCE_CURRENCY).ok_or(CurrencyRateError::MissingReferenceCurrency)?
+    };
+    let mut rates: BTreeMap<String, T> = raw.rates.into_iter()
+      .map(|(code, rate)| (code, T::from(rate / reference_rate)))
+      .collect();
+    rates.insert(raw.base, T::from(1.0 / reference_rate));
+    Ok(Self { rates })
+  }
+
+  pub fn len(&self) -> usize {
+    self.rates.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rates.is_empty()
+  }
+}
+
+impl<T> Default for CurrencyRateTable<T>
+where T: From<i64> + Div<Output = T> {
+  fn default() -> Self {
+    Self::from_provider(&StaticCurrencyRates)
+  }
+}
+
+impl<T: Clone> CurrencyRateProvider<T> for CurrencyRateTable<T> {
+  fn rate(&self, code: &str) -> Option<T> {
+    self.rates.get(code).cloned()
+  }
+
+  fn currency_codes(&self) -> Vec<String> {
+    self.rates.keys().cloned().collect()
+  }
+}
+
+impl<T, P> CurrencyRateProvider<T> for &P
+where P: CurrencyRateProvider<T> + ?Sized {
+  fn rate(&self, code: &str) -> Option<T> {
+    (**self).rate(code)
+  }
+
+  fn currency_codes(&self) -> Vec<String> {
+    (**self).currency_codes()
+  }
+}
+
+impl<P, R> CurrencyUnitParser<P, R> {
+  pub fn new(inner: P, rates: R) -> Self {
+    Self { inner, rates }
+  }
+}
+
+impl<T, P, R> UnitParser<T> for CurrencyUnitParser<P, R>
+where T: One + Div<Output = T>,
+      P: UnitParser<T>,
+      R: CurrencyRateProvider<T> {
+  fn parse_unit(&self, input: &str) -> Result<Unit<T>, UnitParserError> {
+    self.inner.parse_unit(input).or_else(|err| {
+      let rate = self.rates.rate(input).ok_or(err)?;
+      Ok(Unit::new(input, BaseDimension::Currency, T::one() / rate))
+    })
+  }
+
+  fn base_unit(&self, dimension: BaseDimension) -> Unit<T> {
+    self.inner.base_unit(dimension)
+  }
+
+  fn derived_units(&self) -> Vec<Unit<T>> {
+    self.inner.derived_units()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::units::parsing::NullaryUnitParser;
+  use crate::expr::number::Number;
+
+  #[test]
+  fn test_static_rates() {
+    let rate: Option<Number> = StaticCurrencyRates.rate("EUR");
+    assert_eq!(rate, Some(Number::ratio(23, 25)));
+    let rate: Option<Number> = StaticCurrencyRates.rate(REFERENCE_CURRENCY);
+    assert_eq!(rate, Some(Number::one()));
+    let rate: Option<Number> = StaticCurrencyRates.rate("XYZ");
+    assert_eq!(rate, None);
+  }
+
+  #[test]
+  fn test_from_json_with_reference_base() {
+    let table = CurrencyRateTable::<f64>::from_json(r#"{"rates": {"EUR": 0.5, "GBP": 0.25}}"#).unwrap();
+    assert_eq!(table.rate("EUR"), Some(0.5));
+    assert_eq!(table.rate("GBP"), Some(0.25));
+    assert_eq!(table.rate("USD"), Some(1.0));
+    assert_eq!(table.rate("JPY"), None);
+    assert_eq!(table.len(), 3);
+  }
+
+  #[test]
+  fn test_from_json_with_other_base() {
+    let table = CurrencyRateTable::<f64>::from_json(r#"{"base": "EUR", "rates": {"USD": 2.0, "GBP": 0.5}}"#).unwrap();
+    assert_eq!(table.rate("USD"), Some(1.0));
+    assert_eq!(table.rate("EUR"), Some(0.5));
+    assert_eq!(table.rate("GBP"), Some(0.25));
+  }
+
+  #[test]
+  fn test_from_json_errors() {
+    assert!(matches!(
+      CurrencyRateTable::<f64>::from_json(r#"{"base": "EUR", "rates": {"GBP": 0.5}}"#),
+      Err(CurrencyRateError::MissingReferenceCurrency),
+    ));
+    assert!(matches!(
+      CurrencyRateTable::<f64>::from_json(r#"{"rates": {"GBP": -1.0}}"#),
+      Err(CurrencyRateError::InvalidRate(code)) if code == "GBP",
+    ));
+    assert!(matches!(
+      CurrencyRateTable::<f64>::from_json(r#"{"rates": "GBP"}"#),
+      Err(CurrencyRateError::JsonError(_)),
+    ));
+  }
+
+  #[test]
+  fn test_currency_unit_parser() {
+    let table = CurrencyRateTable::<f64>::from_json(r#"{"rates": {"EUR": 0.5}}"#).unwrap();
+    let parser = CurrencyUnitParser::new(NullaryUnitParser, table);
+    assert_eq!(parser.parse_unit("EUR"), Ok(Unit::new("EUR", BaseDimension::Currency, 2.0)));
+    assert_eq!(parser.parse_unit("USD"), Ok(Unit::new("USD", BaseDimension::Currency, 1.0)));
+    assert_eq!(parser.parse_unit("GBP"), Err(UnitParserError::new("GBP")));
+  }
+}
@@ -18,7 +18,7 @@ pub struct Dimension {
 /// Dimensions available for units to represent. Every unit represents
 /// a formal product or quotient of zero or more dimensions.
 ///
-/// These are simply the seven base SI units.
+/// These are the seven base SI units, together with currency.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BaseDimension {
   Length,
@@ -28,9 +28,10 @@ pub enum BaseDimension {
   Current,
   LuminousIntensity,
   AmountOfSubstance,
+  Currency,
 }
 
-pub const NDIMS: usize = 7;
+pub const NDIMS: usize = 8;
 
 impl Dimension {
   pub fn singleton(base: BaseDimension) -> Self {
@@ -148,6 +149,7 @@ impl BaseDimension {
     BaseDimension::Current,
     BaseDimension::LuminousIntensity,
     BaseDimension::AmountOfSubstance,
+    BaseDimension::Currency,
   ];
 
   fn dimension_index(self) -> usize {
@@ -159,6 +161,7 @@ impl BaseDimension {
       BaseDimension::Current => 4,
       BaseDimension::LuminousIntensity => 5,
       BaseDimension::AmountOfSubstance => 6,
+      BaseDimension::Currency => 7,
     }
   }
 }
@@ -296,6 +299,7 @@ impl Display for BaseDimension {
       BaseDimension::Current => write!(f, "current"),
       BaseDimension::LuminousIntensity => write!(f, "intensity"),
       BaseDimension::AmountOfSubstance => write!(f, "amount"),
+      BaseDimension::Currency => write!(f, "currency"),
     }
   }
 }
@@ -345,82 +349,82 @@ mod tests {
   #[test]
   fn test_singleton() {
     let value = Dimension::singleton(BaseDimension::Time);
-    assert_eq!(value.dims, [0, 1, 0, 0, 0, 0, 0]);
+    assert_eq!(value.dims, [0, 1, 0, 0, 0, 0, 0, 0]);
   }
 
   #[test]
   fn test_pow() {
-    let value = Dimension { dims: [1, 2, 3, 4, 5, 6, 7] }.pow(2);
-    assert_eq!(value.dims, [2, 4, 6, 8, 10, 12, 14]);
-    let value = Dimension { dims: [1, -1, 2, 2, -3, 3, 10] }.pow(-2);
-    assert_eq!(value.dims, [-2, 2, -4, -4, 6, -6, -20]);
-    let value = Dimension { dims: [1, -1, 2, 2, -3, 3, 10] }.pow(0);
+    let value = Dimension { dims: [1, 2, 3, 4, 5, 6, 7, 0] }.pow(2);
+    assert_eq!(value.dims, [2, 4, 6, 8, 10, 12, 14, 0]);
+    let value = Dimension { dims: [1, -1, 2, 2, -3, 3, 10, 0] }.pow(-2);
+    assert_eq!(value.dims, [-2, 2, -4, -4, 6, -6, -20, 0]);
+    let value = Dimension { dims: [1, -1, 2, 2, -3, 3, 10, 0] }.pow(0);
     assert_eq!(value, Dimension::one());
   }
 
   #[test]
   fn test_get() {
-    let value = Dimension { dims: [1, 2, 3, 4, 5, 6, 7] };
+    let value = Dimension { dims: [1, 2, 3, 4, 5, 6, 7, 0] };
     assert_eq!(value.get(BaseDimension::LuminousIntensity), 6);
     assert_eq!(value.get(BaseDimension::Mass), 3);
   }
 
   #[test]
   fn test_get_mut() {
-    let mut value = Dimension { dims: [1, 2, 3, 4, 5, 6, 7] };
+    let mut value = Dimension { dims: [1, 2, 3, 4, 5, 6, 7, 0] };
     *value.get_mut(BaseDimension::LuminousIntensity) = 99;
-    assert_eq!(value.dims, [1, 2, 3, 4, 5, 99, 7]);
+    assert_eq!(value.dims, [1, 2, 3, 4, 5, 99, 7, 0]);
   }
 
   #[test]
   fn test_min_dimension() {
-    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7] };
-    let b = Dimension { dims: [7, 6, 5, 4, 3, 2, 1] };
+    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7, 0] };
+    let b = Dimension { dims: [7, 6, 5, 4, 3, 2, 1, 0] };
     let result = a.min(&b);
-    assert_eq!(result.dims, [1, 2, 3, 4, 3, 2, 1]);
+    assert_eq!(result.dims, [1, 2, 3, 4, 3, 2, 1, 0]);
   }
 
   #[test]
   fn test_max_dimension() {
-    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7] };
-    let b = Dimension { dims: [7, 6, 5, 4, 3, 2, 1] };
+    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7, 0] };
+    let b = Dimension { dims: [7, 6, 5, 4, 3, 2, 1, 0] };
     let result = a.max(&b);
-    assert_eq!(result.dims, [7, 6, 5, 4, 5, 6, 7]);
+    assert_eq!(result.dims, [7, 6, 5, 4, 5, 6, 7, 0]);
   }
 
   #[test]
   fn test_mul() {
-    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7] };
-    let b = Dimension { dims: [-1, 2, 2, 2, 10, 10, 10] };
+    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7, 0] };
+    let b = Dimension { dims: [-1, 2, 2, 2, 10, 10, 10, 0] };
     assert_eq!(
       a * b,
-      Dimension { dims: [0, 4, 5, 6, 15, 16, 17] },
+      Dimension { dims: [0, 4, 5, 6, 15, 16, 17, 0] },
     );
   }
 
   #[test]
   fn test_div() {
-    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7] };
-    let b = Dimension { dims: [-1, 2, 2, 2, 10, 10, 10] };
+    let a = Dimension { dims: [1, 2, 3, 4, 5, 6, 7, 0] };
+    let b = Dimension { dims: [-1, 2, 2, 2, 10, 10, 10, 0] };
     assert_eq!(
       a / b,
-      Dimension { dims: [2, 0, 1, 2, -5, -4, -3] },
+      Dimension { dims: [2, 0, 1, 2, -5, -4, -3, 0] },
     );
   }
 
   #[test]
   fn test_display_on_singleton() {
-    let dim = Dimension { dims: [0, 0, 1, 0, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 1, 0, 0, 0, 0, 0] };
     assert_eq!(dim.to_string(), "mass");
   }
 
   #[test]
   fn test_display_on_power() {
-    let dim = Dimension { dims: [0, 0, 3, 0, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 3, 0, 0, 0, 0, 0] };
     assert_eq!(dim.to_string(), "mass^3");
-    let dim = Dimension { dims: [0, 0, 0, -3, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 0, -3, 0, 0, 0, 0] };
     assert_eq!(dim.to_string(), "1 / temperature^3");
-    let dim = Dimension { dims: [0, 0, 0, -1, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 0, -1, 0, 0, 0, 0] };
     assert_eq!(dim.to_string(), "1 / temperature");
   }
 
@@ -431,91 +435,91 @@ mod tests {
 
   #[test]
   fn test_display_on_composite() {
-    let dim = Dimension { dims: [0, 1, 3, 0, -1, 1, -2] };
+    let dim = Dimension { dims: [0, 1, 3, 0, -1, 1, -2, 0] };
     assert_eq!(dim.to_string(), "time mass^3 intensity / current amount^2");
   }
 
   #[test]
   fn test_partial_ord_on_dimension() {
-    assert!(Dimension { dims: [0, 0, 0, 0, 0, 0, 0] } < Dimension { dims: [0, 0, 0, 0, 0, 0, 1] });
-    assert!(Dimension { dims: [0, 0, 0, 0, 0, 0, 0] } < Dimension { dims: [0, 0, 0, 1, 0, 0, 0] });
-    assert!(Dimension { dims: [0, 0, 0, 0, 0, 0, 0] } <= Dimension { dims: [0, 0, 0, 0, 0, 0, 0] });
-    assert!(Dimension { dims: [0, 0, 0, 0, 0, 2, 2] } <= Dimension { dims: [0, 0, 0, 0, 0, 3, 4] });
-    assert!(Dimension { dims: [0, 0, 0, 0, 0, 2, 2] } >= Dimension { dims: [0, 0, 0, 0, 0, 1, 1] });
-    assert!(!(Dimension { dims: [1, 0, 0, 0, 0, 0, 0] } <= Dimension { dims: [0, 1, 0, 0, 0, 0, 0] }));
-    assert!(!(Dimension { dims: [1, 0, 0, 0, 0, 0, 0] } >= Dimension { dims: [0, 1, 0, 0, 0, 0, 0] }));
+    assert!(Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] } < Dimension { dims: [0, 0, 0, 0, 0, 0, 1, 0] });
+    assert!(Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] } < Dimension { dims: [0, 0, 0, 1, 0, 0, 0, 0] });
+    assert!(Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] } <= Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] });
+    assert!(Dimension { dims: [0, 0, 0, 0, 0, 2, 2, 0] } <= Dimension { dims: [0, 0, 0, 0, 0, 3, 4, 0] });
+    assert!(Dimension { dims: [0, 0, 0, 0, 0, 2, 2, 0] } >= Dimension { dims: [0, 0, 0, 0, 0, 1, 1, 0] });
+    assert!(!(Dimension { dims: [1, 0, 0, 0, 0, 0, 0, 0] } <= Dimension { dims: [0, 1, 0, 0, 0, 0, 0, 0] }));
+    assert!(!(Dimension { dims: [1, 0, 0, 0, 0, 0, 0, 0] } >= Dimension { dims: [0, 1, 0, 0, 0, 0, 0, 0] }));
   }
 
   #[test]
   fn test_is_simple() {
-    let dim = Dimension { dims: [0, 0, 1, 0, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 1, 0, 0, 0, 0, 0] };
     assert!(dim.is_simple());
-    let dim = Dimension { dims: [0, 0, 0, 0, 0, 1, 0] };
+    let dim = Dimension { dims: [0, 0, 0, 0, 0, 1, 0, 0] };
     assert!(dim.is_simple());
-    let dim = Dimension { dims: [0, 0, 0, 0, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] };
     assert!(!dim.is_simple());
-    let dim = Dimension { dims: [0, 0, 0, -1, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 0, -1, 0, 0, 0, 0] };
     assert!(!dim.is_simple());
-    let dim = Dimension { dims: [0, 0, 0, 1, 0, 0, 1] };
+    let dim = Dimension { dims: [0, 0, 0, 1, 0, 0, 1, 0] };
     assert!(!dim.is_simple());
-    let dim = Dimension { dims: [0, 0, 0, 2, 0, 0, 0] };
+    let dim = Dimension { dims: [0, 0, 0, 2, 0, 0, 0, 0] };
     assert!(!dim.is_simple());
-    let dim = Dimension { dims: [1, 1, 1, 2, 1, 1, 1] };
+    let dim = Dimension { dims: [1, 1, 1, 2, 1, 1, 1, 0] };
     assert!(!dim.is_simple());
   }
 
   #[test]
   fn test_ilog() {
     assert_eq!(
-      Dimension { dims: [0, 0, 1, 0, 0, 2, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0] }),
+      Dimension { dims: [0, 0, 1, 0, 0, 2, 0, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0, 0] }),
       Some(1),
     );
     assert_eq!(
-      Dimension { dims: [0, 0, 3, 0, 0, 6, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0] }),
+      Dimension { dims: [0, 0, 3, 0, 0, 6, 0, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0, 0] }),
       Some(3),
     );
     assert_eq!(
-      Dimension { dims: [0, 0, -3, 0, 0, 6, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, -2, 0] }),
+      Dimension { dims: [0, 0, -3, 0, 0, 6, 0, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, -2, 0, 0] }),
       Some(-3),
     );
     assert_eq!(
-      Dimension { dims: [0, 0, -3, 0, 0, -6, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0] }),
+      Dimension { dims: [0, 0, -3, 0, 0, -6, 0, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0, 0] }),
       Some(-3),
     );
     assert_eq!(
-      Dimension { dims: [0, 0, -3, 0, 0, 6, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0] }),
+      Dimension { dims: [0, 0, -3, 0, 0, 6, 0, 0] }.ilog(&Dimension { dims: [0, 0, 1, 0, 0, 2, 0, 0] }),
       None,
     );
     assert_eq!(
-      Dimension { dims: [1, 1, 1, 1, 1, 1, 1] }.ilog(&Dimension { dims: [1, 1, 1, 1, 1, 1, 1] }),
+      Dimension { dims: [1, 1, 1, 1, 1, 1, 1, 0] }.ilog(&Dimension { dims: [1, 1, 1, 1, 1, 1, 1, 0] }),
       Some(1),
     );
     assert_eq!(
-      Dimension { dims: [1, 2, 1, 1, 1, 1, 1] }.ilog(&Dimension { dims: [1, 1, 1, 1, 1, 1, 1] }),
+      Dimension { dims: [1, 2, 1, 1, 1, 1, 1, 0] }.ilog(&Dimension { dims: [1, 1, 1, 1, 1, 1, 1, 0] }),
       None,
     );
     assert_eq!(
-      Dimension { dims: [1, 1, 1, 1, 1, 1, 1] }.ilog(&Dimension { dims: [1, 1, 1, 2, 1, 1, 1] }),
+      Dimension { dims: [1, 1, 1, 1, 1, 1, 1, 0] }.ilog(&Dimension { dims: [1, 1, 1, 2, 1, 1, 1, 0] }),
       None,
     );
     assert_eq!(
-      Dimension { dims: [0, 0, 0, 0, 0, 0, 0] }.ilog(&Dimension { dims: [1, 2, 1, 2, 1, 3, 9] }),
+      Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] }.ilog(&Dimension { dims: [1, 2, 1, 2, 1, 3, 9, 0] }),
       Some(0),
     );
     assert_eq!(
-      Dimension { dims: [0, 1, 0, 0, 0, 1, 0] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 0, 0] }),
+      Dimension { dims: [0, 1, 0, 0, 0, 1, 0, 0] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] }),
       None,
     );
     assert_eq!(
-      Dimension { dims: [0, 0, 0, 0, 0, 0, 0] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 0, 0] }),
+      Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 0, 0, 0] }),
       None,
     );
     assert_eq!(
-      Dimension { dims: [0, 0, 0, 0, 0, 0, 1] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 1, 0] }),
+      Dimension { dims: [0, 0, 0, 0, 0, 0, 1, 0] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 1, 0, 0] }),
       None,
     );
     assert_eq!(
-      Dimension { dims: [0, 0, 0, 0, 0, 1, 0] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 0, 1] }),
+      Dimension { dims: [0, 0, 0, 0, 0, 1, 0, 0] }.ilog(&Dimension { dims: [0, 0, 0, 0, 0, 0, 1, 0] }),
       None,
     );
   }
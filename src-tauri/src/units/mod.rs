@@ -3,6 +3,8 @@
 //! which contain units.
 
 pub mod convertible;
+pub mod context;
+pub mod currency;
 pub mod dimension;
 pub mod parsing;
 pub mod prefix;
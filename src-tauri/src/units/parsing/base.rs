@@ -17,6 +17,14 @@ pub trait UnitParser<T> {
   /// that all other units implicitly convert through.
   fn base_unit(&self, dimension: BaseDimension) -> Unit<T>;
 
+  /// Named units, such as newtons or watts, which stand for a product
+  /// of other units and may replace that product when derived units
+  /// are preferred. Listed in order of preference. The default
+  /// implementation returns no units.
+  fn derived_units(&self) -> Vec<Unit<T>> {
+    Vec::new()
+  }
+
   /// Produces the base unit for this dimension, built up using
   /// [`UnitParser::base_unit`].
   fn base_composite_unit(&self, dimension: &Dimension) -> CompositeUnit<T> {
@@ -65,4 +73,8 @@ where P: UnitParser<T> + ?Sized {
   fn base_unit(&self, dimension: BaseDimension) -> Unit<T> {
     (**self).base_unit(dimension)
   }
+
+  fn derived_units(&self) -> Vec<Unit<T>> {
+    (**self).derived_units()
+  }
 }
@@ -3,6 +3,7 @@ use super::table::TableBasedParser;
 use super::prefix::PrefixParser;
 use crate::units::dimension::{Dimension, BaseDimension};
 use crate::units::unit::Unit;
+use crate::units::currency::REFERENCE_CURRENCY;
 
 use num::One;
 use num::pow::Pow;
@@ -129,12 +130,8 @@ where S: ScalarLike + 'static {
       .with_composed(nautical_miles() / hours()),
     Unit::new("ga", Length / Time.pow(2), fraction(980_665, 100_000)) // "g" acceleration
       .with_composed(meters() / seconds().pow(2)),
-    Unit::new("N", Mass * Length / Time.pow(2), fraction(1, 1)) // Newton
-      .with_composed(grams() * meters() / seconds().pow(2)),
     Unit::new("dyn", Mass * Length / Time.pow(2), fraction(1, 100_000)) // Dyne
       .with_composed(grams() * meters() / seconds().pow(2)),
-    Unit::new("J", Mass * Length.pow(2) / Time.pow(2), fraction(1, 1)) // Joule
-      .with_composed(grams() * meters().pow(2) / seconds().pow(2)),
     Unit::new("cal", Mass * Length.pow(2) / Time.pow(2), fraction(41_868, 10_000)) // Calorie
       .with_composed(grams() * meters().pow(2) / seconds().pow(2)),
     Unit::new("calth", Mass * Length.pow(2) / Time.pow(2), fraction(4_184, 1_000)) // Thermochemical Calorie
@@ -142,8 +139,49 @@ where S: ScalarLike + 'static {
     Unit::new("Cal", Mass * Length.pow(2) / Time.pow(2), fraction(41_868, 10)) // Large Calorie
       .with_composed(grams() * meters().pow(2) / seconds().pow(2)),
   ];
-  let units_table: HashMap<_, _> = units.into_iter().map(|unit| (unit.name().to_string(), unit)).collect();
+  let derived_unit_names: Vec<_> = si_derived_units::<S>().iter().map(|unit| unit.name().to_owned()).collect();
+  let units_table: HashMap<_, _> = units.into_iter()
+    .chain(si_derived_units())
+    .map(|unit| (unit.name().to_string(), unit))
+    .collect();
   TableBasedParser::new(units_table, si_base_unit)
+    .with_derived_units(derived_unit_names)
+}
+
+/// The named SI derived units, which may replace a product of other
+/// units when derived units are preferred. In order of preference.
+///
+/// Hertz is deliberately not listed here, since it would also replace
+/// angular velocities such as `rad / s`.
+fn si_derived_units<S>() -> Vec<Unit<S>>
+where S: ScalarLike + 'static {
+  use BaseDimension::*;
+  vec![
+    Unit::new("N", Mass * Length / Time.pow(2), fraction(1, 1)) // Newton
+      .with_composed(grams() * meters() / seconds().pow(2)),
+    Unit::new("J", Mass * Length.pow(2) / Time.pow(2), fraction(1, 1)) // Joule
+      .with_composed(grams() * meters().pow(2) / seconds().pow(2)),
+    Unit::new("W", Mass * Length.pow(2) / Time.pow(3), fraction(1, 1)) // Watt
+      .with_composed(grams() * meters().pow(2) / seconds().pow(3)),
+    Unit::new("Pa", Mass / (Length * Time.pow(2)), fraction(1, 1)) // Pascal
+      .with_composed(grams() / (meters() * seconds().pow(2))),
+    Unit::new("C", Current * Time, fraction(1, 1)) // Coulomb
+      .with_composed(amperes() * seconds()),
+    Unit::new("V", Mass * Length.pow(2) / (Time.pow(3) * Current), fraction(1, 1)) // Volt
+      .with_composed(grams() * meters().pow(2) / (seconds().pow(3) * amperes())),
+    Unit::new("ohm", Mass * Length.pow(2) / (Time.pow(3) * Current.pow(2)), fraction(1, 1))
+      .with_composed(grams() * meters().pow(2) / (seconds().pow(3) * amperes().pow(2))),
+    Unit::new("S", Time.pow(3) * Current.pow(2) / (Mass * Length.pow(2)), fraction(1, 1)) // Siemens
+      .with_composed(seconds().pow(3) * amperes().pow(2) / (grams() * meters().pow(2))),
+    Unit::new("F", Time.pow(4) * Current.pow(2) / (Mass * Length.pow(2)), fraction(1, 1)) // Farad
+      .with_composed(seconds().pow(4) * amperes().pow(2) / (grams() * meters().pow(2))),
+    Unit::new("Wb", Mass * Length.pow(2) / (Time.pow(2) * Current), fraction(1, 1)) // Weber
+      .with_composed(grams() * meters().pow(2) / (seconds().pow(2) * amperes())),
+    Unit::new("T", Mass / (Time.pow(2) * Current), fraction(1, 1)) // Tesla
+      .with_composed(grams() / (seconds().pow(2) * amperes())),
+    Unit::new("H", Mass * Length.pow(2) / (Time.pow(2) * Current.pow(2)), fraction(1, 1)) // Henry
+      .with_composed(grams() * meters().pow(2) / (seconds().pow(2) * amperes().pow(2))),
+  ]
 }
 
 pub fn si_base_unit<S: One + From<i64>>(dimension: BaseDimension) -> Unit<S> {
@@ -156,6 +194,7 @@ pub fn si_base_unit<S: One + From<i64>>(dimension: BaseDimension) -> Unit<S> {
     Current => amperes(),
     LuminousIntensity => candela(),
     AmountOfSubstance => moles(),
+    Currency => Unit::new(REFERENCE_CURRENCY, Currency, S::one()),
   }
 }
 
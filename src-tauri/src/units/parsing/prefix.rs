@@ -52,6 +52,10 @@ where P: UnitParser<T>,
   fn base_unit(&self, dimension: BaseDimension) -> Unit<T> {
     self.inner.base_unit(dimension)
   }
+
+  fn derived_units(&self) -> Vec<Unit<T>> {
+    self.inner.derived_units()
+  }
 }
 
 #[cfg(test)]
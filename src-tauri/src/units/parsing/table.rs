@@ -10,6 +10,9 @@ use std::collections::HashMap;
 pub struct TableBasedParser<T> {
   pub table: HashMap<String, Unit<T>>,
   pub base_units: Box<dyn Fn(BaseDimension) -> Unit<T> + Send + Sync>,
+  /// Names of the units in `table` which are reported by
+  /// [`UnitParser::derived_units`], in order of preference.
+  pub derived_unit_names: Vec<String>,
 }
 
 impl<T> TableBasedParser<T> {
@@ -18,8 +21,18 @@ impl<T> TableBasedParser<T> {
     Self {
       table,
       base_units: Box::new(base_units),
+      derived_unit_names: Vec::new(),
     }
   }
+
+  /// Marks the named units as derived units. Names which are not in
+  /// the table are ignored.
+  pub fn with_derived_units<I>(mut self, names: I) -> Self
+  where I: IntoIterator,
+        I::Item: Into<String> {
+    self.derived_unit_names = names.into_iter().map(Into::into).collect();
+    self
+  }
 }
 
 impl<T: Clone> UnitParser<T> for TableBasedParser<T> {
@@ -32,6 +45,12 @@ impl<T: Clone> UnitParser<T> for TableBasedParser<T> {
   fn base_unit(&self, dimension: BaseDimension) -> Unit<T> {
     (self.base_units)(dimension)
   }
+
+  fn derived_units(&self) -> Vec<Unit<T>> {
+    self.derived_unit_names.iter()
+      .filter_map(|name| self.table.get(name).cloned())
+      .collect()
+  }
 }
 
 #[cfg(test)]
@@ -64,4 +83,14 @@ mod tests {
     assert_eq!(table.parse_unit("xyz"), Err(UnitParserError::new("xyz")));
     assert_eq!(table.parse_unit("M"), Err(UnitParserError::new("M"))); // Note: Case sensitive
   }
+
+  #[test]
+  fn test_derived_units() {
+    assert_eq!(sample_table().derived_units(), vec![]);
+    let table = sample_table().with_derived_units(["min", "nonexistent", "m"]);
+    assert_eq!(
+      table.derived_units(),
+      vec![Unit::new("min", BaseDimension::Time, 60.0), Unit::new("m", BaseDimension::Length, 1.0)],
+    );
+  }
 }
@@ -0,0 +1,139 @@
+
+//! Low-precision positions of the sun and moon, good to within a
+//! minute or two for dates in the modern era.
+//!
+//! Sunrise and sunset use the sunrise equation, as given in the
+//! Astronomical Almanac's low-precision formulas for the sun. The
+//! moon phase is computed from the mean synodic month, measured from
+//! a known new moon.
+
+use super::angles::{Degrees, Radians};
+
+use thiserror::Error;
+
+/// The Julian date of the J2000.0 epoch, 2000-01-01T12:00:00 TT.
+const J2000: f64 = 2_451_545.0;
+
+/// The Julian date of the new moon of 2000-01-06T18:14Z.
+const REFERENCE_NEW_MOON: f64 = 2_451_550.1;
+
+/// The mean length of a lunar cycle, in days.
+pub const SYNODIC_MONTH: f64 = 29.530_588_853;
+
+/// The altitude of the sun's center at sunrise and sunset, which
+/// accounts for atmospheric refraction and the radius of the solar
+/// disc.
+const SUNRISE_ALTITUDE: Degrees<f64> = Degrees(-0.833);
+
+/// The obliquity of the ecliptic.
+const OBLIQUITY: Degrees<f64> = Degrees(23.4397);
+
+/// The times of sunrise and sunset on a given day, as Julian dates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunEvents {
+  pub sunrise: f64,
+  pub sunset: f64,
+}
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SunEventError {
+  #[error("The sun does not rise on this day at this latitude")]
+  PolarNight,
+  #[error("The sun does not set on this day at this latitude")]
+  MidnightSun,
+}
+
+/// Computes sunrise and sunset at the given position, for the solar
+/// day whose noon is nearest the given Julian date. Far from
+/// Greenwich, the results may fall on an adjacent UTC day. Longitude
+/// is positive to the east of Greenwich.
+pub fn sun_events(julian_date: f64, latitude: Radians<f64>, longitude: Radians<f64>) -> Result<SunEvents, SunEventError> {
+  let longitude = longitude.into_degrees().0;
+  // Days since J2000, at the nearest local solar noon.
+  let day = (julian_date - J2000 + 0.0008).round();
+  let mean_solar_noon = day - longitude / 360.0;
+
+  let mean_anomaly = Degrees((357.5291 + 0.985_600_28 * mean_solar_noon).rem_euclid(360.0)).into_radians();
+  let center = 1.9148 * mean_anomaly.sin()
+    + 0.0200 * (mean_anomaly * 2.0).sin()
+    + 0.0003 * (mean_anomaly * 3.0).sin();
+  let ecliptic_longitude =
+    Degrees((mean_anomaly.into_degrees().0 + center + 180.0 + 102.9372).rem_euclid(360.0)).into_radians();
+  let solar_transit = J2000 + mean_solar_noon + 0.0053 * mean_anomaly.sin() - 0.0069 * (ecliptic_longitude * 2.0).sin();
+
+  let declination_sin = ecliptic_longitude.sin() * OBLIQUITY.into_radians().sin();
+  let declination_cos = (1.0 - declination_sin * declination_sin).sqrt();
+  let hour_angle_cos = (SUNRISE_ALTITUDE.into_radians().sin() - latitude.sin() * declination_sin)
+    / (latitude.cos() * declination_cos);
+  if hour_angle_cos > 1.0 {
+    return Err(SunEventError::PolarNight);
+  }
+  if hour_angle_cos < -1.0 {
+    return Err(SunEventError::MidnightSun);
+  }
+  let hour_angle = Radians(hour_angle_cos.acos()).into_degrees().0;
+  Ok(SunEvents {
+    sunrise: solar_transit - hour_angle / 360.0,
+    sunset: solar_transit + hour_angle / 360.0,
+  })
+}
+
+/// The fraction of the lunar cycle that has elapsed at the given
+/// Julian date: 0 at new moon, 0.5 at full moon, approaching 1 just
+/// before the next new moon.
+pub fn moon_phase(julian_date: f64) -> f64 {
+  ((julian_date - REFERENCE_NEW_MOON) / SYNODIC_MONTH).rem_euclid(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::calendar::parse_iso_datetime;
+
+  const ONE_MINUTE: f64 = 1.0 / 1440.0;
+
+  fn degrees(x: f64) -> Radians<f64> {
+    Degrees(x).into_radians()
+  }
+
+  fn assert_near(actual: f64, expected: &str, tolerance: f64) {
+    let expected = parse_iso_datetime(expected).unwrap();
+    assert!((actual - expected).abs() < tolerance, "Expected {expected}, got {actual}");
+  }
+
+  #[test]
+  fn test_sun_events_in_london() {
+    // Summer solstice in London: sunrise 04:43 BST, sunset 21:21 BST.
+    let date = parse_iso_datetime("2024-06-21").unwrap();
+    let events = sun_events(date, degrees(51.5074), degrees(-0.1278)).unwrap();
+    assert_near(events.sunrise, "2024-06-21T03:43", 3.0 * ONE_MINUTE);
+    assert_near(events.sunset, "2024-06-21T20:21", 3.0 * ONE_MINUTE);
+  }
+
+  #[test]
+  fn test_sun_events_east_of_greenwich() {
+    // Tokyo, spring equinox: sunrise 05:45 JST, sunset 17:53 JST.
+    let date = parse_iso_datetime("2024-03-20").unwrap();
+    let events = sun_events(date, degrees(35.6762), degrees(139.6503)).unwrap();
+    assert_near(events.sunrise, "2024-03-19T20:45", 3.0 * ONE_MINUTE);
+    assert_near(events.sunset, "2024-03-20T08:53", 3.0 * ONE_MINUTE);
+  }
+
+  #[test]
+  fn test_polar_day_and_night() {
+    let midsummer = parse_iso_datetime("2024-06-21").unwrap();
+    let midwinter = parse_iso_datetime("2024-12-21").unwrap();
+    assert_eq!(sun_events(midsummer, degrees(78.2), degrees(15.6)), Err(SunEventError::MidnightSun));
+    assert_eq!(sun_events(midwinter, degrees(78.2), degrees(15.6)), Err(SunEventError::PolarNight));
+  }
+
+  #[test]
+  fn test_moon_phase() {
+    // New moon on 2024-04-08T18:21Z, full moon on 2024-04-23T23:49Z.
+    let phase = moon_phase(parse_iso_datetime("2024-04-08T18:21").unwrap());
+    assert!(!(0.02..=0.98).contains(&phase), "Got phase {phase}");
+    let phase = moon_phase(parse_iso_datetime("2024-04-23T23:49").unwrap());
+    assert!((phase - 0.5).abs() < 0.03, "Got phase {phase}");
+  }
+}
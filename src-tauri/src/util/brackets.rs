@@ -26,6 +26,7 @@ pub enum HtmlBracketsType {
   SquareBrackets,
   Parentheses,
   VerticalBars,
+  CurlyBraces,
 }
 
 /// Bracketing construct which chooses from two constituent bracketing
@@ -132,6 +133,7 @@ impl HtmlBracketsType {
       HtmlBracketsType::SquareBrackets => "bracketed--square",
       HtmlBracketsType::Parentheses => "bracketed--parens",
       HtmlBracketsType::VerticalBars => "bracketed--vert",
+      HtmlBracketsType::CurlyBraces => "bracketed--curly",
     }
   }
 
@@ -141,6 +143,7 @@ impl HtmlBracketsType {
       HtmlBracketsType::SquareBrackets => "bracketed--square-left",
       HtmlBracketsType::Parentheses => "bracketed--parens-left",
       HtmlBracketsType::VerticalBars => "bracketed--vert-left",
+      HtmlBracketsType::CurlyBraces => "bracketed--curly-left",
     }
   }
 
@@ -150,6 +153,7 @@ impl HtmlBracketsType {
       HtmlBracketsType::SquareBrackets => "bracketed--square-right",
       HtmlBracketsType::Parentheses => "bracketed--parens-right",
       HtmlBracketsType::VerticalBars => "bracketed--vert-right",
+      HtmlBracketsType::CurlyBraces => "bracketed--curly-right",
     }
   }
 }
@@ -0,0 +1,132 @@
+
+//! Conversions between ISO 8601 date strings and Julian dates.
+//!
+//! Dates are always in UTC, using the proleptic Gregorian calendar.
+//! A Julian date counts days (and fractions thereof) since noon UTC
+//! on 1 January 4713 BC in the proleptic Julian calendar, which is
+//! the standard time scale for astronomical calculations.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use thiserror::Error;
+
+/// The Julian date of the Unix epoch, 1970-01-01T00:00:00Z.
+pub const UNIX_EPOCH_JULIAN_DATE: f64 = 2_440_587.5;
+
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+
+static ISO_DATETIME_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"^(-?\d{4,})-(\d{2})-(\d{2})(?:[T ](\d{2}):(\d{2})(?::(\d{2}(?:\.\d+)?))?Z?)?$").unwrap()
+});
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseDateError {
+  #[error("Expected a date of the form YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS, got '{0}'")]
+  BadFormat(String),
+  #[error("Date or time out of range: '{0}'")]
+  OutOfRange(String),
+}
+
+/// Parses a UTC date, with an optional time of day, into a Julian
+/// date. A date with no time is taken to be at midnight.
+pub fn parse_iso_datetime(s: &str) -> Result<f64, ParseDateError> {
+  let s = s.trim();
+  let caps = ISO_DATETIME_RE.captures(s).ok_or_else(|| ParseDateError::BadFormat(s.to_owned()))?;
+  let out_of_range = || ParseDateError::OutOfRange(s.to_owned());
+  let field = |i: usize| caps.get(i).map_or("0", |m| m.as_str());
+
+  let year: i64 = field(1).parse().map_err(|_| out_of_range())?;
+  let month: u32 = field(2).parse().map_err(|_| out_of_range())?;
+  let day: u32 = field(3).parse().map_err(|_| out_of_range())?;
+  let hour: u32 = field(4).parse().map_err(|_| out_of_range())?;
+  let minute: u32 = field(5).parse().map_err(|_| out_of_range())?;
+  let second: f64 = field(6).parse().map_err(|_| out_of_range())?;
+  if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) ||
+     hour > 23 || minute > 59 || second >= 60.0 {
+    return Err(out_of_range());
+  }
+
+  let days = days_from_civil(year, month, day) as f64;
+  let seconds = f64::from(hour * 3600 + minute * 60) + second;
+  Ok(UNIX_EPOCH_JULIAN_DATE + days + seconds / SECONDS_PER_DAY)
+}
+
+/// Formats a Julian date as a UTC datetime string of the form
+/// `YYYY-MM-DDTHH:MM:SSZ`, rounded to the nearest second.
+pub fn format_iso_datetime(julian_date: f64) -> String {
+  let total_seconds = ((julian_date - UNIX_EPOCH_JULIAN_DATE) * SECONDS_PER_DAY).round() as i64;
+  let days = total_seconds.div_euclid(86_400);
+  let seconds_of_day = total_seconds.rem_euclid(86_400);
+  let (year, month, day) = civil_from_days(days);
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+    year, month, day, seconds_of_day / 3600, (seconds_of_day / 60) % 60, seconds_of_day % 60,
+  )
+}
+
+fn is_leap_year(year: i64) -> bool {
+  (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+  match month {
+    2 if is_leap_year(year) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+/// The number of days from 1970-01-01 to the given date. This is
+/// Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year.rem_euclid(400);
+  let month = i64::from(month);
+  let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+  let days = days + 719_468;
+  let era = days.div_euclid(146_097);
+  let day_of_era = days.rem_euclid(146_097);
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+  let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+  (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_known_dates() {
+    assert_eq!(parse_iso_datetime("1970-01-01"), Ok(UNIX_EPOCH_JULIAN_DATE));
+    assert_eq!(parse_iso_datetime("2000-01-01T12:00:00Z"), Ok(2_451_545.0));
+    assert_eq!(parse_iso_datetime("2000-01-01 18:00"), Ok(2_451_545.25));
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    assert!(matches!(parse_iso_datetime("yesterday"), Err(ParseDateError::BadFormat(_))));
+    assert!(matches!(parse_iso_datetime("2023-02-29"), Err(ParseDateError::OutOfRange(_))));
+    assert!(matches!(parse_iso_datetime("2024-01-01T24:00"), Err(ParseDateError::OutOfRange(_))));
+    assert!(parse_iso_datetime("2024-02-29").is_ok());
+  }
+
+  #[test]
+  fn test_round_trip() {
+    for s in ["1970-01-01T00:00:00Z", "2000-02-29T23:59:59Z", "1600-03-01T06:30:00Z", "2038-01-19T03:14:08Z"] {
+      assert_eq!(format_iso_datetime(parse_iso_datetime(s).unwrap()), s);
+    }
+  }
+}
@@ -0,0 +1,200 @@
+
+//! Distances and directions between points on the Earth's surface.
+//!
+//! Positions are given as latitude and longitude, with latitude
+//! positive to the north and longitude positive to the east.
+//! Distances are in meters.
+//!
+//! The great-circle functions treat the Earth as a sphere, which is
+//! accurate to within about half a percent. [`vincenty_distance`]
+//! uses the WGS-84 ellipsoid and is accurate to within a millimeter,
+//! at the cost of an iterative solution which can fail to converge
+//! for nearly antipodal points.
+
+use super::angles::Radians;
+
+use thiserror::Error;
+
+use std::f64::consts::PI;
+
+/// The mean radius of the Earth, as defined by the IUGG.
+pub const EARTH_MEAN_RADIUS: f64 = 6_371_008.8;
+
+/// The semi-major axis of the WGS-84 ellipsoid.
+const WGS84_SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+
+/// The flattening of the WGS-84 ellipsoid.
+const WGS84_FLATTENING: f64 = 1.0 / 298.257_223_563;
+
+const VINCENTY_MAX_ITERATIONS: usize = 200;
+
+const VINCENTY_TOLERANCE: f64 = 1e-12;
+
+/// A position on the Earth's surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+  pub latitude: Radians<f64>,
+  pub longitude: Radians<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+#[error("Vincenty's formula failed to converge; the points may be nearly antipodal")]
+pub struct VincentyConvergenceError {
+  _priv: (),
+}
+
+impl GeoPoint {
+  pub fn new(latitude: Radians<f64>, longitude: Radians<f64>) -> Self {
+    Self { latitude, longitude }
+  }
+}
+
+/// The great-circle distance between two points, computed with the
+/// haversine formula.
+pub fn haversine_distance(p1: GeoPoint, p2: GeoPoint) -> f64 {
+  let dlat = p2.latitude.0 - p1.latitude.0;
+  let dlon = p2.longitude.0 - p1.longitude.0;
+  let a = (dlat / 2.0).sin().powi(2) + p1.latitude.cos() * p2.latitude.cos() * (dlon / 2.0).sin().powi(2);
+  2.0 * EARTH_MEAN_RADIUS * a.sqrt().min(1.0).asin()
+}
+
+/// The distance between two points along the WGS-84 ellipsoid,
+/// computed with Vincenty's inverse formula.
+pub fn vincenty_distance(p1: GeoPoint, p2: GeoPoint) -> Result<f64, VincentyConvergenceError> {
+  let a = WGS84_SEMI_MAJOR_AXIS;
+  let f = WGS84_FLATTENING;
+  let b = (1.0 - f) * a;
+
+  let lon_diff = p2.longitude.0 - p1.longitude.0;
+  let reduced_lat1 = ((1.0 - f) * p1.latitude.tan()).atan();
+  let reduced_lat2 = ((1.0 - f) * p2.latitude.tan()).atan();
+  let (sin_u1, cos_u1) = reduced_lat1.sin_cos();
+  let (sin_u2, cos_u2) = reduced_lat2.sin_cos();
+
+  let mut lambda = lon_diff;
+  for _ in 0..VINCENTY_MAX_ITERATIONS {
+    let (sin_lambda, cos_lambda) = lambda.sin_cos();
+    let sin_sigma = ((cos_u2 * sin_lambda).powi(2) +
+                     (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2)).sqrt();
+    if sin_sigma == 0.0 {
+      // Coincident points
+      return Ok(0.0);
+    }
+    let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+    let sigma = sin_sigma.atan2(cos_sigma);
+    let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+    let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+    // On the equator, cos_sq_alpha is zero and the midpoint term
+    // vanishes.
+    let cos_2sigma_m = if cos_sq_alpha == 0.0 { 0.0 } else { cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha };
+    let c = f / 16.0 * cos_sq_alpha * (4.0 + f * (4.0 - 3.0 * cos_sq_alpha));
+    let prev_lambda = lambda;
+    lambda = lon_diff + (1.0 - c) * f * sin_alpha *
+      (sigma + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+    if (lambda - prev_lambda).abs() < VINCENTY_TOLERANCE {
+      let u_sq = cos_sq_alpha * (a * a - b * b) / (b * b);
+      let big_a = 1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+      let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+      let delta_sigma = big_b * sin_sigma * (cos_2sigma_m + big_b / 4.0 * (
+        cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m) -
+          big_b / 6.0 * cos_2sigma_m * (-3.0 + 4.0 * sin_sigma * sin_sigma) * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)
+      ));
+      return Ok(b * big_a * (sigma - delta_sigma));
+    }
+  }
+  Err(VincentyConvergenceError { _priv: () })
+}
+
+/// The initial bearing of the great circle path from `p1` to `p2`,
+/// measured clockwise from north, in the range `[0, 2pi)`.
+pub fn initial_bearing(p1: GeoPoint, p2: GeoPoint) -> Radians<f64> {
+  let dlon = p2.longitude.0 - p1.longitude.0;
+  let y = dlon.sin() * p2.latitude.cos();
+  let x = p1.latitude.cos() * p2.latitude.sin() - p1.latitude.sin() * p2.latitude.cos() * dlon.cos();
+  Radians(y.atan2(x).rem_euclid(2.0 * PI))
+}
+
+/// The point reached by travelling `distance` meters along a great
+/// circle from `start`, with the given initial bearing. The resulting
+/// longitude is normalized to `(-pi, pi]`.
+pub fn destination_point(start: GeoPoint, bearing: Radians<f64>, distance: f64) -> GeoPoint {
+  let angular_distance = distance / EARTH_MEAN_RADIUS;
+  let (sin_lat1, cos_lat1) = start.latitude.0.sin_cos();
+  let (sin_dist, cos_dist) = angular_distance.sin_cos();
+  let sin_lat2 = sin_lat1 * cos_dist + cos_lat1 * sin_dist * bearing.cos();
+  let lat2 = sin_lat2.clamp(-1.0, 1.0).asin();
+  let lon2 = start.longitude.0 + (bearing.sin() * sin_dist * cos_lat1).atan2(cos_dist - sin_lat1 * sin_lat2);
+  let lon2 = PI - (PI - lon2).rem_euclid(2.0 * PI);
+  GeoPoint::new(Radians(lat2), Radians(lon2))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::angles::Degrees;
+
+  fn point(lat: f64, lon: f64) -> GeoPoint {
+    GeoPoint::new(Degrees(lat).into_radians(), Degrees(lon).into_radians())
+  }
+
+  fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+    degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3600.0)
+  }
+
+  #[test]
+  fn test_haversine_quarter_meridian() {
+    let distance = haversine_distance(point(0.0, 0.0), point(90.0, 0.0));
+    assert!((distance - EARTH_MEAN_RADIUS * PI / 2.0).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_haversine_london_to_paris() {
+    let distance = haversine_distance(point(51.5074, -0.1278), point(48.8566, 2.3522));
+    assert!((distance - 343_560.0).abs() < 500.0, "Got {distance}");
+  }
+
+  #[test]
+  fn test_vincenty_flinders_peak_to_buninyong() {
+    // The worked example from Vincenty's original paper.
+    let flinders_peak = point(dms(-37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440));
+    let buninyong = point(dms(-37.0, 39.0, 10.15610), dms(143.0, 55.0, 35.38390));
+    let distance = vincenty_distance(flinders_peak, buninyong).unwrap();
+    assert!((distance - 54_972.271).abs() < 0.001, "Got {distance}");
+  }
+
+  #[test]
+  fn test_vincenty_coincident_points() {
+    assert_eq!(vincenty_distance(point(10.0, 20.0), point(10.0, 20.0)), Ok(0.0));
+  }
+
+  #[test]
+  fn test_vincenty_antipodal_points() {
+    assert!(vincenty_distance(point(0.0, 0.0), point(0.5, 179.7)).is_err());
+  }
+
+  #[test]
+  fn test_initial_bearing() {
+    let bearing = initial_bearing(point(0.0, 0.0), point(0.0, 1.0)).into_degrees().0;
+    assert!((bearing - 90.0).abs() < 1e-9);
+    let bearing = initial_bearing(point(0.0, 0.0), point(-1.0, 0.0)).into_degrees().0;
+    assert!((bearing - 180.0).abs() < 1e-9);
+    let bearing = initial_bearing(point(0.0, 0.0), point(0.0, -1.0)).into_degrees().0;
+    assert!((bearing - 270.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_destination_point_round_trip() {
+    let start = point(51.5074, -0.1278);
+    let end = point(48.8566, 2.3522);
+    let reached = destination_point(start, initial_bearing(start, end), haversine_distance(start, end));
+    assert!((reached.latitude.0 - end.latitude.0).abs() < 1e-9);
+    assert!((reached.longitude.0 - end.longitude.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_destination_point_across_antimeridian() {
+    let reached = destination_point(point(0.0, 179.0), Degrees(90.0).into_radians(), 2.0 * 111_195.0);
+    let longitude = reached.longitude.into_degrees().0;
+    assert!((longitude - -179.0).abs() < 0.01, "Got {longitude}");
+  }
+}
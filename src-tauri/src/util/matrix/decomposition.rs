@@ -0,0 +1,429 @@
+
+//! Floating-point factorizations of real matrices: LU, QR, Cholesky,
+//! and singular value decompositions.
+//!
+//! Like [`numeric`](super::numeric), the functions here are only
+//! approximate. Each decomposition is returned as a list of factors
+//! whose product is (up to rounding error) the original matrix.
+
+use super::{Matrix, MatrixIndex};
+
+use thiserror::Error;
+
+pub type RealMatrix = Matrix<f64>;
+
+/// Maximum number of Jacobi sweeps performed by [`svd`] before giving
+/// up.
+const MAX_JACOBI_SWEEPS: usize = 100;
+
+/// Relative tolerance within which a matrix is considered symmetric
+/// by [`cholesky`].
+const SYMMETRY_TOLERANCE: f64 = 1e-10;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum DecompositionError {
+  #[error("Matrix is not symmetric")]
+  NotSymmetric,
+  #[error("Matrix is not positive definite")]
+  NotPositiveDefinite,
+  #[error("Singular value decomposition did not converge")]
+  NoConvergence,
+}
+
+/// An LU decomposition `A = P L U` with partial pivoting. `P` is a
+/// permutation matrix, `L` is unit lower triangular, and `U` is upper
+/// triangular.
+#[derive(Clone, Debug)]
+pub struct LuDecomposition {
+  pub permutation: RealMatrix,
+  pub lower: RealMatrix,
+  pub upper: RealMatrix,
+}
+
+/// A QR decomposition `A = Q R` of an `m x n` matrix. `Q` is an `m x
+/// m` orthogonal matrix and `R` is an `m x n` upper triangular matrix
+/// with a non-negative diagonal.
+#[derive(Clone, Debug)]
+pub struct QrDecomposition {
+  pub orthogonal: RealMatrix,
+  pub upper: RealMatrix,
+}
+
+/// A thin singular value decomposition `A = U S V^T` of an `m x n`
+/// matrix, where `k = min(m, n)`. `U` is `m x k` and `V` is `n x k`,
+/// both with orthonormal columns, and `S` is the `k x k` diagonal
+/// matrix of singular values, in descending order.
+#[derive(Clone, Debug)]
+pub struct SingularValueDecomposition {
+  pub left: RealMatrix,
+  pub singular_values: Vec<f64>,
+  pub right: RealMatrix,
+}
+
+impl SingularValueDecomposition {
+  /// The singular values, as a diagonal matrix.
+  pub fn singular_value_matrix(&self) -> RealMatrix {
+    let size = self.singular_values.len();
+    Matrix::from_generator(size, size, |index| {
+      if index.x == index.y { self.singular_values[index.x] } else { 0.0 }
+    })
+  }
+}
+
+/// Computes the LU decomposition of a square matrix, by Gaussian
+/// elimination with partial pivoting. Singular matrices are
+/// permitted and produce a zero on the diagonal of `U`. Panics if
+/// `matrix` is not square.
+pub fn lu(matrix: &RealMatrix) -> LuDecomposition {
+  assert!(matrix.width() == matrix.height(), "Can only LU-decompose square matrices");
+  let size = matrix.width();
+  let mut work = matrix.clone();
+  // `rows[i]` is the row of the original matrix which ended up in
+  // row `i` after pivoting.
+  let mut rows: Vec<usize> = (0..size).collect();
+  for k in 0..size {
+    let pivot_y = (k..size)
+      .max_by(|i, j| work[MatrixIndex { y: *i, x: k }].abs().total_cmp(&work[MatrixIndex { y: *j, x: k }].abs()))
+      .unwrap(); // unwrap: k < size
+    work.body.swap(k, pivot_y);
+    rows.swap(k, pivot_y);
+    let pivot = work[MatrixIndex { y: k, x: k }];
+    if pivot == 0.0 {
+      // The column is already zero below the diagonal.
+      continue;
+    }
+    for y in (k + 1)..size {
+      let factor = work[MatrixIndex { y, x: k }] / pivot;
+      // The multipliers of `L` are stored below the diagonal.
+      work[MatrixIndex { y, x: k }] = factor;
+      for x in (k + 1)..size {
+        let delta = factor * work[MatrixIndex { y: k, x }];
+        work[MatrixIndex { y, x }] -= delta;
+      }
+    }
+  }
+  LuDecomposition {
+    permutation: Matrix::from_generator(size, size, |index| {
+      if rows[index.x] == index.y { 1.0 } else { 0.0 }
+    }),
+    lower: Matrix::from_generator(size, size, |index| {
+      if index.x < index.y {
+        work[index]
+      } else if index.x == index.y {
+        1.0
+      } else {
+        0.0
+      }
+    }),
+    upper: Matrix::from_generator(size, size, |index| {
+      if index.x >= index.y { work[index] } else { 0.0 }
+    }),
+  }
+}
+
+/// Computes the QR decomposition of a matrix of any shape, by
+/// Householder reflections.
+pub fn qr(matrix: &RealMatrix) -> QrDecomposition {
+  let height = matrix.height();
+  let width = matrix.width();
+  let mut r = matrix.clone();
+  let mut q = Matrix::<f64>::identity(height);
+  for k in 0..width.min(height.saturating_sub(1)) {
+    let mut v: Vec<f64> = (k..height).map(|y| r[MatrixIndex { y, x: k }]).collect();
+    let norm_x = vector_norm(&v);
+    if norm_x == 0.0 {
+      continue;
+    }
+    v[0] += v[0].signum() * norm_x;
+    let norm_v = vector_norm(&v);
+    v.iter_mut().for_each(|x| *x /= norm_v);
+
+    for x in 0..width {
+      let dot: f64 = v.iter().enumerate().map(|(i, vi)| vi * r[MatrixIndex { y: k + i, x }]).sum();
+      for (i, vi) in v.iter().enumerate() {
+        r[MatrixIndex { y: k + i, x }] -= 2.0 * vi * dot;
+      }
+    }
+    for y in 0..height {
+      let dot: f64 = v.iter().enumerate().map(|(i, vi)| q[MatrixIndex { y, x: k + i }] * vi).sum();
+      for (i, vi) in v.iter().enumerate() {
+        q[MatrixIndex { y, x: k + i }] -= 2.0 * dot * vi;
+      }
+    }
+    // Clear the rounding errors left below the diagonal.
+    for y in (k + 1)..height {
+      r[MatrixIndex { y, x: k }] = 0.0;
+    }
+  }
+
+  // Normalize signs so that the diagonal of `R` is non-negative,
+  // which makes the decomposition unique for full-rank matrices.
+  for k in 0..width.min(height) {
+    if r[MatrixIndex { y: k, x: k }] < 0.0 {
+      for x in 0..width {
+        r[MatrixIndex { y: k, x }] = - r[MatrixIndex { y: k, x }];
+      }
+      for y in 0..height {
+        q[MatrixIndex { y, x: k }] = - q[MatrixIndex { y, x: k }];
+      }
+    }
+  }
+  QrDecomposition { orthogonal: q, upper: r }
+}
+
+/// Computes the Cholesky decomposition `A = L L^T` of a symmetric
+/// positive definite matrix, returning the lower triangular factor
+/// `L`. Panics if `matrix` is not square.
+pub fn cholesky(matrix: &RealMatrix) -> Result<RealMatrix, DecompositionError> {
+  assert!(matrix.width() == matrix.height(), "Can only Cholesky-decompose square matrices");
+  let size = matrix.width();
+  let scale = matrix.items().fold(0.0, |acc: f64, x| acc.max(x.abs()));
+  for y in 0..size {
+    for x in 0..y {
+      let difference = matrix[MatrixIndex { y, x }] - matrix[MatrixIndex { y: x, x: y }];
+      if difference.abs() > SYMMETRY_TOLERANCE * scale {
+        return Err(DecompositionError::NotSymmetric);
+      }
+    }
+  }
+
+  let mut lower = Matrix::of_value(size, size, 0.0f64);
+  for j in 0..size {
+    let diagonal = matrix[MatrixIndex { y: j, x: j }] -
+      (0..j).map(|k| lower[MatrixIndex { y: j, x: k }].powi(2)).sum::<f64>();
+    if diagonal <= 0.0 {
+      return Err(DecompositionError::NotPositiveDefinite);
+    }
+    let diagonal = diagonal.sqrt();
+    lower[MatrixIndex { y: j, x: j }] = diagonal;
+    for i in (j + 1)..size {
+      let dot: f64 = (0..j).map(|k| lower[MatrixIndex { y: i, x: k }] * lower[MatrixIndex { y: j, x: k }]).sum();
+      lower[MatrixIndex { y: i, x: j }] = (matrix[MatrixIndex { y: i, x: j }] - dot) / diagonal;
+    }
+  }
+  Ok(lower)
+}
+
+/// Computes the thin singular value decomposition of a matrix of any
+/// shape, by one-sided Jacobi rotations.
+pub fn svd(matrix: &RealMatrix) -> Result<SingularValueDecomposition, DecompositionError> {
+  if matrix.height() < matrix.width() {
+    // Decompose the transpose, which is taller than it is wide, and
+    // swap the roles of `U` and `V`.
+    let SingularValueDecomposition { left, singular_values, right } = svd(&matrix.clone().transpose())?;
+    return Ok(SingularValueDecomposition { left: right, singular_values, right: left });
+  }
+  let height = matrix.height();
+  let width = matrix.width();
+  let mut u = matrix.clone();
+  let mut v = Matrix::<f64>::identity(width);
+
+  let mut converged = false;
+  for _ in 0..MAX_JACOBI_SWEEPS {
+    converged = true;
+    for p in 0..width {
+      for q in (p + 1)..width {
+        let alpha: f64 = (0..height).map(|y| u[MatrixIndex { y, x: p }].powi(2)).sum();
+        let beta: f64 = (0..height).map(|y| u[MatrixIndex { y, x: q }].powi(2)).sum();
+        let gamma: f64 = (0..height).map(|y| u[MatrixIndex { y, x: p }] * u[MatrixIndex { y, x: q }]).sum();
+        if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+          // Columns `p` and `q` are already orthogonal.
+          continue;
+        }
+        converged = false;
+        let zeta = (beta - alpha) / (2.0 * gamma);
+        let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+        let c = 1.0 / (1.0 + t * t).sqrt();
+        let s = c * t;
+        rotate_columns(&mut u, p, q, c, s);
+        rotate_columns(&mut v, p, q, c, s);
+      }
+    }
+    if converged {
+      break;
+    }
+  }
+  if !converged {
+    return Err(DecompositionError::NoConvergence);
+  }
+
+  // The columns of `U` are now orthogonal, and their norms are the
+  // singular values.
+  let mut order: Vec<usize> = (0..width).collect();
+  let norms: Vec<f64> = (0..width).map(|x| vector_norm(&u.column(x).unwrap().to_owned())).collect();
+  order.sort_by(|a, b| norms[*b].total_cmp(&norms[*a]));
+  let largest = order.first().map_or(0.0, |x| norms[*x]);
+  let tolerance = f64::EPSILON * largest * height as f64;
+
+  let singular_values: Vec<f64> = order.iter().map(|x| norms[*x]).collect();
+  let mut left = Matrix::from_generator(height, width, |index| {
+    let norm = norms[order[index.x]];
+    if norm > tolerance { u[MatrixIndex { y: index.y, x: order[index.x] }] / norm } else { 0.0 }
+  });
+  let right = Matrix::from_generator(width, width, |index| v[MatrixIndex { y: index.y, x: order[index.x] }]);
+  complete_orthonormal_columns(&mut left, singular_values.iter().take_while(|s| **s > tolerance).count());
+  let singular_values = singular_values.into_iter().map(|s| if s > tolerance { s } else { 0.0 }).collect();
+  Ok(SingularValueDecomposition { left, singular_values, right })
+}
+
+/// Applies the Jacobi rotation with cosine `c` and sine `s` to
+/// columns `p` and `q` of `matrix`.
+fn rotate_columns(matrix: &mut RealMatrix, p: usize, q: usize, c: f64, s: f64) {
+  for row in &mut matrix.body {
+    let (a, b) = (row[p], row[q]);
+    row[p] = c * a - s * b;
+    row[q] = s * a + c * b;
+  }
+}
+
+/// Replaces every column of `matrix` after the first `known` columns
+/// (which must already be orthonormal) with unit vectors orthogonal
+/// to all columns before it, chosen by Gram-Schmidt from the standard
+/// basis.
+fn complete_orthonormal_columns(matrix: &mut RealMatrix, known: usize) {
+  let height = matrix.height();
+  let mut candidates = 0..height;
+  for x in known..matrix.width() {
+    for candidate in candidates.by_ref() {
+      let mut vector = vec![0.0; height];
+      vector[candidate] = 1.0;
+      for previous in 0..x {
+        let column = matrix.column(previous).unwrap().to_owned();
+        let dot: f64 = column.iter().zip(&vector).map(|(a, b)| a * b).sum();
+        vector.iter_mut().zip(&column).for_each(|(v, c)| *v -= dot * c);
+      }
+      let norm = vector_norm(&vector);
+      // Each standard basis vector has unit norm, so a residual of at
+      // least 1/2 is comfortably independent of the columns so far.
+      if norm >= 0.5 {
+        for (y, value) in vector.into_iter().enumerate() {
+          matrix[MatrixIndex { y, x }] = value / norm;
+        }
+        break;
+      }
+    }
+  }
+}
+
+fn vector_norm(v: &[f64]) -> f64 {
+  v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use try_traits::ops::TryMul;
+
+  fn matrix(body: Vec<Vec<f64>>) -> RealMatrix {
+    Matrix::new(body).unwrap()
+  }
+
+  fn product(factors: &[&RealMatrix]) -> RealMatrix {
+    factors[1..].iter().fold(factors[0].clone(), |acc, m| acc.try_mul(*m).unwrap())
+  }
+
+  fn assert_close(actual: &RealMatrix, expected: &RealMatrix) {
+    assert_eq!(actual.height(), expected.height());
+    assert_eq!(actual.width(), expected.width());
+    for (a, e) in actual.items().zip(expected.items()) {
+      assert!((a - e).abs() < 1e-9, "Expected {expected:?}, got {actual:?}");
+    }
+  }
+
+  fn assert_orthonormal_columns(matrix: &RealMatrix) {
+    let gram = matrix.clone().transpose().try_mul(matrix).unwrap();
+    assert_close(&gram, &Matrix::identity(matrix.width()));
+  }
+
+  #[test]
+  fn test_lu() {
+    let a = matrix(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0], vec![7.0, 8.0, 10.0]]);
+    let LuDecomposition { permutation, lower, upper } = lu(&a);
+    assert_close(&product(&[&permutation, &lower, &upper]), &a);
+    // Partial pivoting puts the largest entry of the first column on
+    // top.
+    assert_eq!(upper[MatrixIndex { y: 0, x: 0 }], 7.0);
+    for y in 0..3 {
+      assert_eq!(lower[MatrixIndex { y, x: y }], 1.0);
+      for x in 0..y {
+        assert_eq!(upper[MatrixIndex { y, x }], 0.0);
+      }
+    }
+  }
+
+  #[test]
+  fn test_lu_of_singular_matrix() {
+    let a = matrix(vec![vec![1.0, 2.0], vec![2.0, 4.0]]);
+    let LuDecomposition { permutation, lower, upper } = lu(&a);
+    assert_close(&product(&[&permutation, &lower, &upper]), &a);
+    assert_eq!(upper[MatrixIndex { y: 1, x: 1 }], 0.0);
+  }
+
+  #[test]
+  fn test_qr() {
+    let a = matrix(vec![vec![12.0, -51.0, 4.0], vec![6.0, 167.0, -68.0], vec![-4.0, 24.0, -41.0]]);
+    let QrDecomposition { orthogonal, upper } = qr(&a);
+    assert_close(&product(&[&orthogonal, &upper]), &a);
+    assert_orthonormal_columns(&orthogonal);
+    assert_close(&upper, &matrix(vec![vec![14.0, 21.0, -14.0], vec![0.0, 175.0, -70.0], vec![0.0, 0.0, 35.0]]));
+  }
+
+  #[test]
+  fn test_qr_of_rectangular_matrices() {
+    let tall = matrix(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]);
+    let QrDecomposition { orthogonal, upper } = qr(&tall);
+    assert_eq!((orthogonal.height(), orthogonal.width()), (3, 3));
+    assert_eq!((upper.height(), upper.width()), (3, 2));
+    assert_close(&product(&[&orthogonal, &upper]), &tall);
+    assert_orthonormal_columns(&orthogonal);
+
+    let wide = tall.transpose();
+    let QrDecomposition { orthogonal, upper } = qr(&wide);
+    assert_close(&product(&[&orthogonal, &upper]), &wide);
+    assert_eq!(upper[MatrixIndex { y: 1, x: 0 }], 0.0);
+  }
+
+  #[test]
+  fn test_cholesky() {
+    let a = matrix(vec![vec![4.0, 12.0, -16.0], vec![12.0, 37.0, -43.0], vec![-16.0, -43.0, 98.0]]);
+    let lower = cholesky(&a).unwrap();
+    assert_close(&lower, &matrix(vec![vec![2.0, 0.0, 0.0], vec![6.0, 1.0, 0.0], vec![-8.0, 5.0, 3.0]]));
+    assert_close(&product(&[&lower, &lower.clone().transpose()]), &a);
+  }
+
+  #[test]
+  fn test_cholesky_errors() {
+    let asymmetric = matrix(vec![vec![1.0, 2.0], vec![0.0, 1.0]]);
+    assert_eq!(cholesky(&asymmetric).unwrap_err(), DecompositionError::NotSymmetric);
+    let indefinite = matrix(vec![vec![1.0, 2.0], vec![2.0, 1.0]]);
+    assert_eq!(cholesky(&indefinite).unwrap_err(), DecompositionError::NotPositiveDefinite);
+  }
+
+  #[test]
+  fn test_svd() {
+    let a = matrix(vec![vec![3.0, 2.0, 2.0], vec![2.0, 3.0, -2.0]]);
+    let decomposition = svd(&a).unwrap();
+    assert_eq!((decomposition.left.height(), decomposition.left.width()), (2, 2));
+    assert_eq!((decomposition.right.height(), decomposition.right.width()), (3, 2));
+    assert!((decomposition.singular_values[0] - 5.0).abs() < 1e-9);
+    assert!((decomposition.singular_values[1] - 3.0).abs() < 1e-9);
+    let s = decomposition.singular_value_matrix();
+    let right_transpose = decomposition.right.clone().transpose();
+    assert_close(&product(&[&decomposition.left, &s, &right_transpose]), &a);
+    assert_orthonormal_columns(&decomposition.left);
+    assert_orthonormal_columns(&decomposition.right);
+  }
+
+  #[test]
+  fn test_svd_of_rank_deficient_matrix() {
+    let a = matrix(vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]]);
+    let decomposition = svd(&a).unwrap();
+    assert!((decomposition.singular_values[0] - 70f64.sqrt()).abs() < 1e-9);
+    assert_eq!(decomposition.singular_values[1], 0.0);
+    let s = decomposition.singular_value_matrix();
+    let right_transpose = decomposition.right.clone().transpose();
+    assert_close(&product(&[&decomposition.left, &s, &right_transpose]), &a);
+    assert_orthonormal_columns(&decomposition.left);
+  }
+}
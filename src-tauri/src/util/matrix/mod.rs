@@ -4,6 +4,8 @@
 
 mod base;
 mod column;
+pub mod decomposition;
+pub mod numeric;
 pub mod row_reduction;
 
 pub use base::{MatrixElement, MatrixFieldElement};
@@ -240,15 +242,44 @@ impl<T> Matrix<T> {
   }
 }
 
+impl<T: MatrixElement> Matrix<T> {
+  /// Raises a square matrix to a nonnegative integer power, by
+  /// repeated squaring. Panics if `self` is not a square matrix.
+  pub fn powu(&self, mut exponent: u64) -> Matrix<T> {
+    assert!(self.width() == self.height(), "Can only raise square matrices to a power");
+    let mut result = Matrix::identity(self.width());
+    let mut base = self.clone();
+    while exponent > 0 {
+      if exponent % 2 == 1 {
+        result = result.try_mul(&base).unwrap(); // unwrap: Both matrices are square of the same size
+      }
+      exponent /= 2;
+      if exponent > 0 {
+        base = base.try_mul(&base).unwrap();
+      }
+    }
+    result
+  }
+}
+
 impl<T: MatrixFieldElement> Matrix<T> {
   /// The determinant of `self`. Panics if `self` is not a square
   /// matrix.
-  pub fn determinant(mut self) -> T {
+  pub fn determinant(self) -> T {
+    // unwrap: The computation is never cancelled.
+    self.determinant_with_cancellation(|| false).unwrap()
+  }
+
+  /// As [`Matrix::determinant`], but periodically calls
+  /// `is_cancelled` and returns `None` if it ever returns true.
+  pub fn determinant_with_cancellation(mut self, is_cancelled: impl FnMut() -> bool) -> Option<T> {
     assert!(self.width() == self.height(), "Can only calculate the determinant of square matrices");
     let mut red_matrix = ReducibleMatrix::new(&mut self);
-    red_matrix.reduce_to_row_form();
+    if !red_matrix.reduce_to_row_form_with_cancellation(is_cancelled) {
+      return None;
+    }
     let diag_product = red_matrix.as_ref().diag().fold(T::one(), |acc, item| acc * item);
-    diag_product / red_matrix.determinant_multiplier()
+    Some(diag_product / red_matrix.determinant_multiplier())
   }
 
   /// The inverse of a square matrix. Panics if `self` is not a square
@@ -284,6 +315,23 @@ impl<T: MatrixFieldElement> Matrix<T> {
       .collect::<Vec<_>>();
     Ok(Matrix::new(final_vec_of_vecs).unwrap())
   }
+
+  /// The least-squares solution `b` to the overdetermined system `self
+  /// * b = observations`, found by solving the normal equations. Each
+  /// column of `self` is one regressor, and each row is one
+  /// observation. Panics if the number of observations does not match
+  /// the height of `self`. Returns an error object if the columns of
+  /// `self` are linearly dependent.
+  pub fn least_squares(&self, observations: &[T]) -> Result<Vec<T>, SingularMatrixError> {
+    assert!(self.height() == observations.len(), "Expected one observation per row");
+    let transposed = self.clone().transpose();
+    let observations = Matrix::from_generator(observations.len(), 1, |index| observations[index.y].clone());
+    // unwrap: The dimensions agree by construction.
+    let normal_matrix = transposed.try_mul(self).unwrap();
+    let normal_rhs = transposed.try_mul(&observations).unwrap();
+    let solution = normal_matrix.inverse_matrix()?.try_mul(&normal_rhs).unwrap();
+    Ok(solution.into_items().collect())
+  }
 }
 
 impl MatrixIndex {
@@ -368,6 +416,8 @@ impl<T> ErrorWithPayload<Vec<Vec<T>>> for MatrixDimsError<T> {
 mod tests {
   use super::*;
 
+  use approx::assert_abs_diff_eq;
+
   #[test]
   fn test_roundtrip_serialize() {
     let matrix = Matrix::from_generator(5, 5, |idx| idx.y + idx.x);
@@ -376,6 +426,32 @@ mod tests {
     assert_eq!(deserialized_matrix, matrix);
   }
 
+  #[test]
+  fn test_least_squares() {
+    // Exact fit of y = 1 + 2x
+    let design = Matrix::new(vec![
+      vec![1.0, 0.0],
+      vec![1.0, 1.0],
+      vec![1.0, 2.0],
+    ]).unwrap();
+    let [a, b] = design.least_squares(&[1.0, 3.0, 5.0]).unwrap().try_into().unwrap();
+    assert_abs_diff_eq!(a, 1.0, epsilon = 1e-9);
+    assert_abs_diff_eq!(b, 2.0, epsilon = 1e-9);
+    // Best fit of a constant is the mean
+    let design = Matrix::new(vec![vec![1.0], vec![1.0], vec![1.0], vec![1.0]]).unwrap();
+    let [mean] = design.least_squares(&[1.0, 2.0, 3.0, 6.0]).unwrap().try_into().unwrap();
+    assert_abs_diff_eq!(mean, 3.0, epsilon = 1e-9);
+  }
+
+  #[test]
+  fn test_least_squares_singular() {
+    let design = Matrix::new(vec![
+      vec![1.0, 2.0],
+      vec![2.0, 4.0],
+    ]).unwrap();
+    design.least_squares(&[1.0, 2.0]).unwrap_err();
+  }
+
   #[test]
   fn test_row_accessor() {
     let matrix = Matrix::new(vec![
@@ -473,6 +549,20 @@ mod tests {
     assert_eq!(matrix.determinant(), 0.0);
   }
 
+  #[test]
+  fn test_determinant_with_cancellation() {
+    let matrix = Matrix::new(vec![
+      vec![1.0, 2.0, 3.0],
+      vec![4.0, 5.0, 6.0],
+      vec![7.0, -8.0, 9.0],
+    ]).unwrap();
+    assert_eq!(matrix.clone().determinant_with_cancellation(|| false), Some(-96.0));
+    // Cancelled after the first column has been reduced.
+    let mut checks = 0;
+    assert_eq!(matrix.determinant_with_cancellation(|| { checks += 1; checks > 1 }), None);
+    assert_eq!(checks, 2);
+  }
+
   #[test]
   fn test_inverse_matrix() {
     let matrix = Matrix::new(vec![
@@ -532,6 +622,20 @@ mod tests {
     ]).unwrap());
   }
 
+  #[test]
+  fn test_matrix_powu() {
+    let a = Matrix::new(vec![
+      vec![1, 1],
+      vec![1, 0],
+    ]).unwrap();
+    assert_eq!(a.powu(0), Matrix::identity(2));
+    assert_eq!(a.powu(1), a);
+    assert_eq!(a.powu(10), Matrix::new(vec![
+      vec![89, 55],
+      vec![55, 34],
+    ]).unwrap());
+  }
+
   #[test]
   fn test_matrix_multiplication_with_bad_dims() {
     let a = Matrix::new(vec![
@@ -0,0 +1,452 @@
+
+//! Floating-point algorithms on complex matrices, such as the matrix
+//! exponential and eigendecomposition.
+//!
+//! Unlike the rest of this module, the functions here are only
+//! approximate and work exclusively with [`Complex64`] elements.
+
+use super::{Matrix, MatrixIndex, SingularMatrixError};
+
+use num::complex::Complex64;
+use num::{Zero, One};
+use thiserror::Error;
+use try_traits::ops::TryMul;
+
+pub type ComplexMatrix = Matrix<Complex64>;
+
+/// Degree of the Padé approximant used by [`expm`].
+const PADE_DEGREE: usize = 6;
+
+/// Maximum number of QR iterations spent trying to isolate any one
+/// eigenvalue before giving up.
+const MAX_QR_ITERATIONS: usize = 200;
+
+/// Relative tolerance below which a pivot is considered zero when
+/// computing eigenspaces.
+const RANK_TOLERANCE: f64 = 1e-8;
+
+/// Relative distance within which two computed eigenvalues are
+/// considered to be the same (repeated) eigenvalue.
+const CLUSTER_TOLERANCE: f64 = 1e-6;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum MatrixFunctionError {
+  #[error("Matrix is singular")]
+  Singular,
+  #[error("Matrix is not diagonalizable")]
+  NotDiagonalizable,
+  #[error("Eigenvalue computation did not converge")]
+  NoConvergence,
+}
+
+/// A diagonalization `A = V D V^-1` of a square matrix.
+#[derive(Clone, Debug)]
+pub struct Eigendecomposition {
+  /// The diagonal entries of `D`.
+  pub eigenvalues: Vec<Complex64>,
+  /// The matrix `V`, whose columns are eigenvectors corresponding
+  /// (in order) to the eigenvalues.
+  pub eigenvectors: ComplexMatrix,
+}
+
+impl From<SingularMatrixError> for MatrixFunctionError {
+  fn from(_: SingularMatrixError) -> Self {
+    MatrixFunctionError::Singular
+  }
+}
+
+impl Eigendecomposition {
+  /// Computes `V f(D) V^-1`, applying the scalar function `f` to each
+  /// eigenvalue.
+  pub fn map_eigenvalues<F>(&self, mut f: F) -> Result<ComplexMatrix, MatrixFunctionError>
+  where F: FnMut(Complex64) -> Result<Complex64, MatrixFunctionError> {
+    let size = self.eigenvalues.len();
+    let mapped = self.eigenvalues.iter().map(|x| f(*x)).collect::<Result<Vec<_>, _>>()?;
+    let scaled = Matrix::from_generator(size, size, |index| {
+      self.eigenvectors[index] * mapped[index.x]
+    });
+    let inverse = solve(&self.eigenvectors, &Matrix::identity(size))?;
+    Ok(mul(&scaled, &inverse))
+  }
+}
+
+/// The infinity norm (maximum absolute row sum) of a matrix.
+pub fn inf_norm(matrix: &ComplexMatrix) -> f64 {
+  matrix.rows()
+    .map(|row| row.iter().map(|x| x.norm()).sum::<f64>())
+    .fold(0.0, f64::max)
+}
+
+/// If every entry of `matrix` has a negligible imaginary part
+/// (relative to the size of the matrix), discards the imaginary
+/// parts. Otherwise, returns `matrix` unmodified. This is useful for
+/// cleaning up rounding errors after computing a function of a real
+/// matrix via complex eigenvalues.
+pub fn chop_imaginary_parts(matrix: ComplexMatrix) -> ComplexMatrix {
+  let tolerance = RANK_TOLERANCE * inf_norm(&matrix).max(1.0);
+  if matrix.items().all(|x| x.im.abs() <= tolerance) {
+    matrix.map(|x| Complex64::from(x.re))
+  } else {
+    matrix
+  }
+}
+
+/// The matrix exponential, computed by scaling and squaring with a
+/// diagonal Padé approximant. Panics if `matrix` is not square.
+pub fn expm(matrix: &ComplexMatrix) -> Result<ComplexMatrix, SingularMatrixError> {
+  assert!(matrix.width() == matrix.height(), "Can only exponentiate square matrices");
+  let size = matrix.width();
+
+  // Scale the matrix down until its norm is at most 1/2, where the
+  // Padé approximant is accurate to machine precision.
+  let norm = inf_norm(matrix);
+  let squarings = if norm > 0.5 { (norm / 0.5).log2().ceil() as i32 } else { 0 };
+  let scale = Complex64::from(0.5f64.powi(squarings));
+  let scaled = matrix.clone().map(|x| x * scale);
+
+  let mut numerator = Matrix::identity(size);
+  let mut denominator = Matrix::identity(size);
+  let mut power = Matrix::identity(size);
+  let mut coefficient = 1.0;
+  for k in 1..=PADE_DEGREE {
+    coefficient *= (PADE_DEGREE - k + 1) as f64 / (k * (2 * PADE_DEGREE - k + 1)) as f64;
+    power = mul(&power, &scaled);
+    let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+    numerator = add(&numerator, &power, Complex64::from(coefficient));
+    denominator = add(&denominator, &power, Complex64::from(sign * coefficient));
+  }
+
+  let mut result = solve(&denominator, &numerator)?;
+  for _ in 0..squarings {
+    result = mul(&result, &result);
+  }
+  Ok(result)
+}
+
+/// Diagonalizes a square matrix. Returns an error if the matrix is
+/// not diagonalizable (to within floating-point tolerance). Panics if
+/// `matrix` is not square.
+pub fn diagonalize(matrix: &ComplexMatrix) -> Result<Eigendecomposition, MatrixFunctionError> {
+  assert!(matrix.width() == matrix.height(), "Can only diagonalize square matrices");
+  let size = matrix.width();
+  let scale = inf_norm(matrix).max(1.0);
+
+  // Group numerically-equal eigenvalues together, so that repeated
+  // eigenvalues get a full eigenspace rather than one eigenvector
+  // each.
+  let mut clusters: Vec<(Complex64, usize)> = Vec::new();
+  for value in eigenvalues(matrix)? {
+    match clusters.iter_mut().find(|(c, _)| (*c - value).norm() <= CLUSTER_TOLERANCE * scale) {
+      Some((c, count)) => {
+        *c = (*c * (*count as f64) + value) / ((*count + 1) as f64);
+        *count += 1;
+      }
+      None => clusters.push((value, 1)),
+    }
+  }
+
+  let mut eigenvalues = Vec::with_capacity(size);
+  let mut columns = Vec::with_capacity(size);
+  for (value, multiplicity) in clusters {
+    let shifted = Matrix::from_generator(size, size, |index| {
+      if index.x == index.y { matrix[index] - value } else { matrix[index] }
+    });
+    let basis = null_space(shifted, RANK_TOLERANCE * scale);
+    if basis.len() < multiplicity {
+      return Err(MatrixFunctionError::NotDiagonalizable);
+    }
+    for vector in basis.into_iter().take(multiplicity) {
+      eigenvalues.push(value);
+      columns.push(vector);
+    }
+  }
+
+  let eigenvectors = Matrix::new(columns).unwrap().transpose(); // unwrap: All columns have length `size`.
+  Ok(Eigendecomposition { eigenvalues, eigenvectors })
+}
+
+/// The eigenvalues of a square matrix, computed by the shifted QR
+/// algorithm. Eigenvalues are returned with multiplicity, in no
+/// particular order. Panics if `matrix` is not square.
+pub fn eigenvalues(matrix: &ComplexMatrix) -> Result<Vec<Complex64>, MatrixFunctionError> {
+  assert!(matrix.width() == matrix.height(), "Can only compute eigenvalues of square matrices");
+  let scale = inf_norm(matrix).max(f64::MIN_POSITIVE);
+  let mut work = matrix.clone();
+  let mut eigenvalues = Vec::with_capacity(matrix.width());
+  let mut size = matrix.width();
+  let mut iterations = 0;
+  while size > 0 {
+    let last = size - 1;
+    let off_diagonal: f64 = (0..last).map(|x| work[MatrixIndex { y: last, x }].norm()).sum();
+    if off_diagonal <= f64::EPSILON * scale {
+      // The last row of the active block is (numerically) zero, so
+      // its diagonal entry is an eigenvalue. Deflate and continue
+      // with the leading block.
+      eigenvalues.push(work[MatrixIndex { y: last, x: last }]);
+      size -= 1;
+      iterations = 0;
+      continue;
+    }
+    iterations += 1;
+    if iterations > MAX_QR_ITERATIONS {
+      return Err(MatrixFunctionError::NoConvergence);
+    }
+    let shift = if iterations % 11 == 0 {
+      // Occasional exceptional shift, to break cycles.
+      work[MatrixIndex { y: last, x: last }] + off_diagonal
+    } else {
+      wilkinson_shift(&work, size)
+    };
+    qr_step(&mut work, size, shift);
+  }
+  Ok(eigenvalues)
+}
+
+/// The eigenvalue of the trailing 2x2 block of the active submatrix
+/// which is closest to its bottom-right entry.
+fn wilkinson_shift(matrix: &ComplexMatrix, size: usize) -> Complex64 {
+  let a = matrix[MatrixIndex { y: size - 2, x: size - 2 }];
+  let b = matrix[MatrixIndex { y: size - 2, x: size - 1 }];
+  let c = matrix[MatrixIndex { y: size - 1, x: size - 2 }];
+  let d = matrix[MatrixIndex { y: size - 1, x: size - 1 }];
+  let half_trace = (a + d) / 2.0;
+  let discriminant = ((a - d) * (a - d) / 4.0 + b * c).sqrt();
+  let lambda1 = half_trace + discriminant;
+  let lambda2 = half_trace - discriminant;
+  if (lambda1 - d).norm() <= (lambda2 - d).norm() { lambda1 } else { lambda2 }
+}
+
+/// Performs one shifted QR step on the leading `size x size` block of
+/// `matrix`, replacing it with `RQ + shift`, where `QR = M - shift`.
+fn qr_step(matrix: &mut ComplexMatrix, size: usize, shift: Complex64) {
+  let mut r = Matrix::from_generator(size, size, |index| {
+    if index.x == index.y { matrix[index] - shift } else { matrix[index] }
+  });
+  let mut q = Matrix::<Complex64>::identity(size);
+
+  // Householder reflections, accumulated into `q`.
+  for k in 0..size.saturating_sub(1) {
+    let mut v: Vec<Complex64> = (k..size).map(|y| r[MatrixIndex { y, x: k }]).collect();
+    let norm_x = vector_norm(&v);
+    if norm_x == 0.0 {
+      continue;
+    }
+    let phase = if v[0].is_zero() { Complex64::one() } else { v[0] / v[0].norm() };
+    v[0] += phase * norm_x;
+    let norm_v = vector_norm(&v);
+    if norm_v == 0.0 {
+      continue;
+    }
+    v.iter_mut().for_each(|x| *x /= norm_v);
+
+    for x in 0..size {
+      let dot: Complex64 = v.iter().enumerate().map(|(i, vi)| vi.conj() * r[MatrixIndex { y: k + i, x }]).sum();
+      for (i, vi) in v.iter().enumerate() {
+        r[MatrixIndex { y: k + i, x }] -= vi * dot * 2.0;
+      }
+    }
+    for y in 0..size {
+      let dot: Complex64 = v.iter().enumerate().map(|(i, vi)| q[MatrixIndex { y, x: k + i }] * vi).sum();
+      for (i, vi) in v.iter().enumerate() {
+        q[MatrixIndex { y, x: k + i }] -= dot * vi.conj() * 2.0;
+      }
+    }
+  }
+
+  let product = mul(&r, &q);
+  for y in 0..size {
+    for x in 0..size {
+      let shift = if x == y { shift } else { Complex64::zero() };
+      matrix[MatrixIndex { y, x }] = product[MatrixIndex { y, x }] + shift;
+    }
+  }
+}
+
+/// A basis of unit vectors for the null space of `matrix`, computed
+/// by Gaussian elimination with full pivoting. Pivots smaller than
+/// `tolerance` are treated as zero.
+fn null_space(mut matrix: ComplexMatrix, tolerance: f64) -> Vec<Vec<Complex64>> {
+  let height = matrix.height();
+  let width = matrix.width();
+  let mut permutation: Vec<usize> = (0..width).collect();
+  let mut rank = 0;
+  while rank < height.min(width) {
+    let mut best = (0.0, rank, rank);
+    for y in rank..height {
+      for x in rank..width {
+        let value = matrix[MatrixIndex { y, x }].norm();
+        if value > best.0 {
+          best = (value, y, x);
+        }
+      }
+    }
+    let (value, pivot_y, pivot_x) = best;
+    if value < tolerance {
+      break;
+    }
+    swap_rows(&mut matrix, rank, pivot_y);
+    swap_columns(&mut matrix, rank, pivot_x);
+    permutation.swap(rank, pivot_x);
+
+    let pivot = matrix[MatrixIndex { y: rank, x: rank }];
+    for x in 0..width {
+      matrix[MatrixIndex { y: rank, x }] /= pivot;
+    }
+    for y in (0..height).filter(|y| *y != rank) {
+      let factor = matrix[MatrixIndex { y, x: rank }];
+      if !factor.is_zero() {
+        for x in 0..width {
+          let delta = factor * matrix[MatrixIndex { y: rank, x }];
+          matrix[MatrixIndex { y, x }] -= delta;
+        }
+      }
+    }
+    rank += 1;
+  }
+
+  (rank..width).map(|free| {
+    let mut vector = vec![Complex64::zero(); width];
+    vector[permutation[free]] = Complex64::one();
+    for y in 0..rank {
+      vector[permutation[y]] = - matrix[MatrixIndex { y, x: free }];
+    }
+    let norm = vector_norm(&vector);
+    vector.into_iter().map(|x| x / norm).collect()
+  }).collect()
+}
+
+/// Solves `AX = B` for `X`, by Gaussian elimination with partial
+/// pivoting.
+pub fn solve(a: &ComplexMatrix, b: &ComplexMatrix) -> Result<ComplexMatrix, SingularMatrixError> {
+  assert!(a.width() == a.height(), "Can only solve square systems");
+  assert!(a.height() == b.height(), "Dimension mismatch in linear system");
+  let size = a.width();
+  let norm = inf_norm(a);
+  let mut full = a.clone().hcat(b.clone());
+  let width = full.width();
+  for k in 0..size {
+    let pivot_y = (k..size)
+      .max_by(|i, j| full[MatrixIndex { y: *i, x: k }].norm().total_cmp(&full[MatrixIndex { y: *j, x: k }].norm()))
+      .unwrap(); // unwrap: k < size
+    if full[MatrixIndex { y: pivot_y, x: k }].norm() <= f64::EPSILON * norm {
+      return Err(SingularMatrixError { _priv: () });
+    }
+    swap_rows(&mut full, k, pivot_y);
+    let pivot = full[MatrixIndex { y: k, x: k }];
+    for x in k..width {
+      full[MatrixIndex { y: k, x }] /= pivot;
+    }
+    for y in (0..size).filter(|y| *y != k) {
+      let factor = full[MatrixIndex { y, x: k }];
+      if !factor.is_zero() {
+        for x in k..width {
+          let delta = factor * full[MatrixIndex { y: k, x }];
+          full[MatrixIndex { y, x }] -= delta;
+        }
+      }
+    }
+  }
+  Ok(Matrix::from_generator(size, b.width(), |index| {
+    full[MatrixIndex { y: index.y, x: index.x + size }]
+  }))
+}
+
+fn mul(a: &ComplexMatrix, b: &ComplexMatrix) -> ComplexMatrix {
+  a.try_mul(b).expect("Dimension mismatch in matrix multiplication")
+}
+
+/// Computes `a + coefficient * b`.
+fn add(a: &ComplexMatrix, b: &ComplexMatrix, coefficient: Complex64) -> ComplexMatrix {
+  Matrix::from_generator(a.height(), a.width(), |index| a[index] + coefficient * b[index])
+}
+
+fn vector_norm(v: &[Complex64]) -> f64 {
+  v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt()
+}
+
+fn swap_rows(matrix: &mut ComplexMatrix, a: usize, b: usize) {
+  matrix.body.swap(a, b);
+}
+
+fn swap_columns(matrix: &mut ComplexMatrix, a: usize, b: usize) {
+  for row in &mut matrix.body {
+    row.swap(a, b);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn real_matrix(body: Vec<Vec<f64>>) -> ComplexMatrix {
+    Matrix::new(body).unwrap().map(Complex64::from)
+  }
+
+  fn assert_close(actual: &ComplexMatrix, expected: &ComplexMatrix) {
+    assert_eq!(actual.height(), expected.height());
+    assert_eq!(actual.width(), expected.width());
+    for (a, e) in actual.items().zip(expected.items()) {
+      assert!((a - e).norm() < 1e-9, "Expected {expected:?}, got {actual:?}");
+    }
+  }
+
+  #[test]
+  fn test_expm_of_diagonal_matrix() {
+    let matrix = real_matrix(vec![vec![1.0, 0.0], vec![0.0, -2.0]]);
+    let expected = real_matrix(vec![vec![1f64.exp(), 0.0], vec![0.0, (-2f64).exp()]]);
+    assert_close(&expm(&matrix).unwrap(), &expected);
+  }
+
+  #[test]
+  fn test_expm_of_nilpotent_matrix() {
+    let matrix = real_matrix(vec![vec![0.0, 3.0], vec![0.0, 0.0]]);
+    let expected = real_matrix(vec![vec![1.0, 3.0], vec![0.0, 1.0]]);
+    assert_close(&expm(&matrix).unwrap(), &expected);
+  }
+
+  #[test]
+  fn test_expm_of_rotation_generator() {
+    let theta = 10.0f64;
+    let matrix = real_matrix(vec![vec![0.0, -theta], vec![theta, 0.0]]);
+    let expected = real_matrix(vec![vec![theta.cos(), -theta.sin()], vec![theta.sin(), theta.cos()]]);
+    assert_close(&expm(&matrix).unwrap(), &expected);
+  }
+
+  #[test]
+  fn test_eigenvalues_of_rotation() {
+    let matrix = real_matrix(vec![vec![0.0, -1.0], vec![1.0, 0.0]]);
+    let mut values = eigenvalues(&matrix).unwrap();
+    values.sort_by(|a, b| a.im.total_cmp(&b.im));
+    assert!((values[0] - Complex64::new(0.0, -1.0)).norm() < 1e-12);
+    assert!((values[1] - Complex64::new(0.0, 1.0)).norm() < 1e-12);
+  }
+
+  #[test]
+  fn test_diagonalize_round_trip() {
+    let matrix = real_matrix(vec![vec![2.0, 1.0, 0.0], vec![1.0, 3.0, 1.0], vec![0.0, 1.0, 4.0]]);
+    let decomposition = diagonalize(&matrix).unwrap();
+    assert_close(&decomposition.map_eigenvalues(Ok).unwrap(), &matrix);
+  }
+
+  #[test]
+  fn test_diagonalize_with_repeated_eigenvalue() {
+    let matrix = real_matrix(vec![vec![3.0, 0.0], vec![0.0, 3.0]]);
+    let decomposition = diagonalize(&matrix).unwrap();
+    assert_close(&decomposition.map_eigenvalues(|x| Ok(x * x)).unwrap(), &real_matrix(vec![vec![9.0, 0.0], vec![0.0, 9.0]]));
+  }
+
+  #[test]
+  fn test_diagonalize_defective_matrix() {
+    let matrix = real_matrix(vec![vec![1.0, 1.0], vec![0.0, 1.0]]);
+    assert_eq!(diagonalize(&matrix).unwrap_err(), MatrixFunctionError::NotDiagonalizable);
+  }
+
+  #[test]
+  fn test_solve() {
+    let a = real_matrix(vec![vec![0.0, 2.0], vec![1.0, 1.0]]);
+    let b = real_matrix(vec![vec![4.0], vec![3.0]]);
+    assert_close(&solve(&a, &b).unwrap(), &real_matrix(vec![vec![1.0], vec![2.0]]));
+    let singular = real_matrix(vec![vec![1.0, 2.0], vec![2.0, 4.0]]);
+    solve(&singular, &b).unwrap_err();
+  }
+}
@@ -76,11 +76,22 @@ impl<'a, T: MatrixFieldElement> ReducibleMatrix<'a, T> {
   /// * the pivot of each row is strictly to the right of the pivot of
   /// the row above it.
   pub fn reduce_to_row_form(&mut self) {
+    self.reduce_to_row_form_with_cancellation(|| false);
+  }
+
+  /// As [`ReducibleMatrix::reduce_to_row_form`], but calls
+  /// `is_cancelled` before reducing each column and gives up if it
+  /// returns true. Returns false if the reduction was abandoned, in
+  /// which case the matrix is left partially reduced.
+  pub fn reduce_to_row_form_with_cancellation(&mut self, mut is_cancelled: impl FnMut() -> bool) -> bool {
     fn find_pivot<T: MatrixFieldElement>(matrix: &Matrix<T>, column_index: usize) -> Option<usize> {
       (column_index..matrix.height()).find(|i| !matrix[MatrixIndex { y: column_index, x: *i }].is_zero())
     }
 
     for i in 0..self.height().min(self.width()) {
+      if is_cancelled() {
+        return false;
+      }
       // Find the pivot for column i and swap it into row i.
       let Some(pivot_index) = find_pivot(self.matrix, i) else { continue; };
       self.swap_rows(i, pivot_index);
@@ -93,6 +104,7 @@ impl<'a, T: MatrixFieldElement> ReducibleMatrix<'a, T> {
         }
       }
     }
+    true
   }
 }
 
@@ -2,13 +2,21 @@
 //! Various utility functions.
 
 pub mod angles;
+pub mod astronomy;
 pub mod brackets;
+pub mod calendar;
 pub mod cow_dyn;
+pub mod geodesy;
 pub mod matrix;
+pub mod modular;
 pub mod point;
+pub mod primes;
 pub mod prism;
 pub mod radix;
+pub mod rsa;
+pub mod special;
 pub mod stricteq;
+pub mod triangle;
 pub mod write;
 
 use regex::{Regex, escape};
@@ -0,0 +1,190 @@
+
+//! Modular arithmetic on integers: inverses, the Chinese remainder
+//! theorem, and discrete logarithms.
+
+use num::{BigInt, Zero, One, Integer, Signed};
+
+use std::collections::HashMap;
+
+/// The inverse of `a` modulo `m`, as an integer in `[0, m)`. Returns
+/// `None` if `m` is not positive or if `a` and `m` are not coprime.
+pub fn modinv(a: &BigInt, m: &BigInt) -> Option<BigInt> {
+  if !m.is_positive() {
+    return None;
+  }
+  let gcd = a.mod_floor(m).extended_gcd(m);
+  if !gcd.gcd.is_one() {
+    return None;
+  }
+  Some(gcd.x.mod_floor(m))
+}
+
+/// Combines the congruences `x = residues[i] (mod moduli[i])` into a
+/// single congruence `x = r (mod l)`, returning `(r, l)` with `r` in
+/// `[0, l)`. The moduli need not be pairwise coprime, in which case
+/// `l` is their least common multiple.
+///
+/// Returns `None` if the congruences are incompatible, if the slices
+/// have different lengths, or if any modulus is not positive.
+pub fn crt(residues: &[BigInt], moduli: &[BigInt]) -> Option<(BigInt, BigInt)> {
+  if residues.len() != moduli.len() {
+    return None;
+  }
+  let mut result = (BigInt::zero(), BigInt::one());
+  for (a, n) in residues.iter().zip(moduli) {
+    if !n.is_positive() {
+      return None;
+    }
+    let (r, m) = result;
+    // Solve r + m k = a (mod n) for k.
+    let gcd = m.extended_gcd(n);
+    let difference = a - &r;
+    if !(&difference % &gcd.gcd).is_zero() {
+      return None;
+    }
+    let step = n / &gcd.gcd;
+    let k = ((difference / &gcd.gcd) * gcd.x).mod_floor(&step);
+    let lcm = &m * &step;
+    result = ((r + m * k).mod_floor(&lcm), lcm);
+  }
+  Some(result)
+}
+
+/// The smallest `x >= 0` such that `g^x = h (mod m)`, found by the
+/// baby-step giant-step algorithm in `O(sqrt(m))` time and space.
+/// `g` need not be coprime to `m`. Returns `None` if there is no such
+/// `x` or if `m` is zero.
+pub fn discrete_log(g: u64, h: u64, m: u64) -> Option<u64> {
+  if m == 0 {
+    return None;
+  }
+  let mulmod = |a: u64, b: u64, m: u64| ((a as u128 * b as u128) % m as u128) as u64;
+  let (mut g, mut h, mut m) = (g % m, h % m, m);
+  // We solve coefficient * g^x = h (mod m). While g shares a factor
+  // with m, divide that factor out of the whole congruence. Each
+  // division accounts for one power of g, which we track in offset.
+  let mut coefficient = 1 % m;
+  let mut offset = 0;
+  loop {
+    if h == coefficient {
+      return Some(offset);
+    }
+    let d = g.gcd(&m);
+    if d == 1 {
+      break;
+    }
+    if h % d != 0 {
+      return None;
+    }
+    h /= d;
+    m /= d;
+    offset += 1;
+    coefficient = mulmod(coefficient, g / d, m);
+    g %= m;
+  }
+
+  // Now g is invertible mod m, and x > 0. Write x = i n - j with
+  // 1 <= i <= n and 0 <= j < n. Then coefficient * g^(i n) = h g^j.
+  let n = m.isqrt() + 1;
+  let mut baby_steps = HashMap::with_capacity(n as usize);
+  let mut value = h;
+  for j in 0..n {
+    // Later insertions overwrite earlier ones, so we keep the
+    // largest j, which gives the smallest x.
+    baby_steps.insert(value, j);
+    value = mulmod(value, g, m);
+  }
+  let giant_step = (0..n).fold(1 % m, |acc, _| mulmod(acc, g, m));
+  let mut value = coefficient;
+  for i in 1..=n {
+    value = mulmod(value, giant_step, m);
+    if let Some(&j) = baby_steps.get(&value) {
+      return Some(offset + i * n - j);
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn big(n: i64) -> BigInt {
+    BigInt::from(n)
+  }
+
+  fn bigs(ns: &[i64]) -> Vec<BigInt> {
+    ns.iter().copied().map(big).collect()
+  }
+
+  fn naive_discrete_log(g: u64, h: u64, m: u64) -> Option<u64> {
+    let mut value = 1 % m;
+    for x in 0..=m {
+      if value == h % m {
+        return Some(x);
+      }
+      value = value * g % m;
+    }
+    None
+  }
+
+  #[test]
+  fn test_modinv() {
+    assert_eq!(modinv(&big(3), &big(11)), Some(big(4)));
+    assert_eq!(modinv(&big(-3), &big(11)), Some(big(7)));
+    assert_eq!(modinv(&big(10), &big(17)), Some(big(12)));
+    assert_eq!(modinv(&big(6), &big(9)), None);
+    assert_eq!(modinv(&big(1), &big(1)), Some(big(0)));
+    assert_eq!(modinv(&big(3), &big(0)), None);
+    assert_eq!(modinv(&big(3), &big(-11)), None);
+  }
+
+  #[test]
+  fn test_crt_coprime() {
+    assert_eq!(crt(&bigs(&[2, 3, 2]), &bigs(&[3, 5, 7])), Some((big(23), big(105))));
+    assert_eq!(crt(&bigs(&[-1]), &bigs(&[5])), Some((big(4), big(5))));
+    assert_eq!(crt(&[], &[]), Some((big(0), big(1))));
+  }
+
+  #[test]
+  fn test_crt_non_coprime() {
+    assert_eq!(crt(&bigs(&[2, 4]), &bigs(&[4, 6])), Some((big(10), big(12))));
+    assert_eq!(crt(&bigs(&[1, 2]), &bigs(&[4, 6])), None);
+  }
+
+  #[test]
+  fn test_crt_invalid() {
+    assert_eq!(crt(&bigs(&[1, 2]), &bigs(&[3])), None);
+    assert_eq!(crt(&bigs(&[1]), &bigs(&[0])), None);
+  }
+
+  #[test]
+  fn test_discrete_log() {
+    assert_eq!(discrete_log(3, 13, 17), Some(4));
+    assert_eq!(discrete_log(2, 1, 7), Some(0));
+    assert_eq!(discrete_log(2, 3, 7), None);
+    assert_eq!(discrete_log(5, 0, 1), Some(0));
+    assert_eq!(discrete_log(2, 0, 0), None);
+  }
+
+  #[test]
+  fn test_discrete_log_agrees_with_naive_search() {
+    for m in 1..40 {
+      for g in 0..m {
+        for h in 0..m {
+          assert_eq!(discrete_log(g, h, m), naive_discrete_log(g, h, m), "discrete_log({g}, {h}, {m})");
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_discrete_log_large_modulus() {
+    let m = 1_000_000_007;
+    let h = BigInt::from(5).modpow(&big(123_456_789), &big(m as i64));
+    let h = u64::try_from(h).unwrap();
+    let x = discrete_log(5, h, m).unwrap();
+    assert!(x <= 123_456_789);
+    assert_eq!(BigInt::from(5).modpow(&BigInt::from(x), &big(m as i64)), BigInt::from(h));
+  }
+}
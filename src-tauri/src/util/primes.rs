@@ -0,0 +1,257 @@
+
+//! Counting and enumerating prime numbers.
+
+use rand::Rng;
+
+use std::iter;
+
+/// The width of each block of numbers sieved at once by
+/// [`nth_prime`].
+const SEGMENT_SIZE: u64 = 1 << 18;
+
+/// The primes less than or equal to `limit`, by the sieve of
+/// Eratosthenes.
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+  if limit < 2 {
+    return Vec::new();
+  }
+  let limit = limit as usize;
+  let mut is_composite = vec![false; limit + 1];
+  let mut primes = Vec::new();
+  for n in 2..=limit {
+    if !is_composite[n] {
+      primes.push(n as u64);
+      for multiple in (n * n..=limit).step_by(n) {
+        is_composite[multiple] = true;
+      }
+    }
+  }
+  primes
+}
+
+/// The primes in the inclusive range `[low, high]`, by a segmented
+/// sieve. Uses memory proportional to `high - low` and to the square
+/// root of `high`.
+pub fn primes_in_range(low: u64, high: u64) -> Vec<u64> {
+  let low = low.max(2);
+  if high < low {
+    return Vec::new();
+  }
+  let mut is_composite = vec![false; (high - low + 1) as usize];
+  for p in primes_up_to(high.isqrt()) {
+    let first_multiple = (p * p).max(low.div_ceil(p) * p);
+    let mut multiple = first_multiple;
+    while multiple <= high {
+      is_composite[(multiple - low) as usize] = true;
+      multiple += p;
+    }
+  }
+  is_composite.into_iter()
+    .zip(low..)
+    .filter_map(|(is_composite, n)| (!is_composite).then_some(n))
+    .collect()
+}
+
+/// The number of primes less than or equal to `n`.
+///
+/// This is Legendre's formula, evaluated with the Lucy_Hedgehog
+/// dynamic programming scheme. It only tracks the counts at the
+/// `O(sqrt(n))` distinct values of `n / i`, so it runs in
+/// `O(n^(3/4))` time and `O(sqrt(n))` space.
+pub fn prime_pi(n: u64) -> u64 {
+  if n < 2 {
+    return 0;
+  }
+  let r = n.isqrt() as usize;
+  // small[v] counts the survivors in 2..=v, for v <= r, and large[i]
+  // counts the survivors in 2..=(n / i). Initially, everything
+  // survives. Sieving by each prime p in turn removes the numbers
+  // whose smallest prime factor is p.
+  let mut small: Vec<u64> = (0..=r as u64).map(|v| v.saturating_sub(1)).collect();
+  let mut large: Vec<u64> = iter::once(0).chain((1..=r as u64).map(|i| n / i - 1)).collect();
+  for p in 2..=r {
+    if small[p] == small[p - 1] {
+      // Not prime
+      continue;
+    }
+    let primes_below_p = small[p - 1];
+    let p_squared = (p * p) as u64;
+    for i in 1..=r {
+      if n / (i as u64) < p_squared {
+        break;
+      }
+      let quotient_count = if i * p <= r {
+        large[i * p]
+      } else {
+        small[(n / (i * p) as u64) as usize]
+      };
+      large[i] -= quotient_count - primes_below_p;
+    }
+    for v in (p * p..=r).rev() {
+      small[v] -= small[v / p] - primes_below_p;
+    }
+  }
+  large[1]
+}
+
+/// The `k`th prime, counting from `nth_prime(1) = 2`. Returns `None`
+/// if `k` is zero.
+pub fn nth_prime(k: u64) -> Option<u64> {
+  if k == 0 {
+    return None;
+  }
+  if k < 6 {
+    return Some([2, 3, 5, 7, 11][(k - 1) as usize]);
+  }
+  // Dusart's lower bound: p_k > k (ln k + ln ln k - 1) for k >= 2.
+  // We count the primes up to that bound, then sieve forward.
+  let kf = k as f64;
+  let mut start = (kf * (kf.ln() + kf.ln().ln() - 1.0)).floor() as u64;
+  let mut count = prime_pi(start);
+  loop {
+    let segment = primes_in_range(start + 1, start + SEGMENT_SIZE);
+    if count + segment.len() as u64 >= k {
+      return Some(segment[(k - count - 1) as usize]);
+    }
+    count += segment.len() as u64;
+    start += SEGMENT_SIZE;
+  }
+}
+
+/// Deterministic Miller-Rabin primality test. These witnesses
+/// suffice for every 64-bit integer.
+pub fn is_prime(n: u64) -> bool {
+  const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+  if n < 2 {
+    return false;
+  }
+  if let Some(&p) = WITNESSES.iter().find(|&&p| n.is_multiple_of(p)) {
+    return n == p;
+  }
+  let mulmod = |a: u64, b: u64| ((a as u128 * b as u128) % n as u128) as u64;
+  let powmod = |mut base: u64, mut exp: u64| {
+    let mut result = 1;
+    while exp > 0 {
+      if exp & 1 == 1 {
+        result = mulmod(result, base);
+      }
+      base = mulmod(base, base);
+      exp >>= 1;
+    }
+    result
+  };
+  let s = (n - 1).trailing_zeros();
+  let d = (n - 1) >> s;
+  WITNESSES.iter().all(|&a| {
+    let mut x = powmod(a, d);
+    if x == 1 || x == n - 1 {
+      return true;
+    }
+    for _ in 1..s {
+      x = mulmod(x, x);
+      if x == n - 1 {
+        return true;
+      }
+    }
+    false
+  })
+}
+
+/// A uniformly random prime with exactly `bits` bits and the top two
+/// bits set, so that the product of two such primes has exactly the
+/// sum of their bit lengths. `bits` must be between 2 and 64.
+pub fn random_prime<R: Rng + ?Sized>(rng: &mut R, bits: u32) -> u64 {
+  assert!((2..=64).contains(&bits), "random_prime expects between 2 and 64 bits, got {bits}");
+  let top_bits = 0b11 << (bits - 2);
+  let mask = if bits == 64 { u64::MAX } else { (1 << bits) - 1 };
+  loop {
+    let candidate = (rng.gen::<u64>() & mask) | top_bits;
+    if is_prime(candidate) {
+      return candidate;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use rand::SeedableRng;
+  use rand::rngs::StdRng;
+
+  #[test]
+  fn test_primes_up_to() {
+    assert_eq!(primes_up_to(1), Vec::<u64>::new());
+    assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+  }
+
+  #[test]
+  fn test_primes_in_range() {
+    assert_eq!(primes_in_range(0, 10), vec![2, 3, 5, 7]);
+    assert_eq!(primes_in_range(90, 110), vec![97, 101, 103, 107, 109]);
+    assert_eq!(primes_in_range(24, 28), Vec::<u64>::new());
+    assert_eq!(primes_in_range(10, 5), Vec::<u64>::new());
+    assert_eq!(primes_in_range(1_000_000_000, 1_000_000_100), vec![1_000_000_007, 1_000_000_009, 1_000_000_021, 1_000_000_033, 1_000_000_087, 1_000_000_093, 1_000_000_097]);
+  }
+
+  #[test]
+  fn test_prime_pi_agrees_with_sieve() {
+    let primes = primes_up_to(2000);
+    for n in 0..2000 {
+      let expected = primes.iter().filter(|&&p| p <= n).count() as u64;
+      assert_eq!(prime_pi(n), expected, "prime_pi({n})");
+    }
+  }
+
+  #[test]
+  fn test_prime_pi_large() {
+    assert_eq!(prime_pi(1_000_000), 78_498);
+    assert_eq!(prime_pi(1_000_000_000), 50_847_534);
+  }
+
+  #[test]
+  fn test_nth_prime() {
+    assert_eq!(nth_prime(0), None);
+    assert_eq!(nth_prime(1), Some(2));
+    assert_eq!(nth_prime(5), Some(11));
+    assert_eq!(nth_prime(6), Some(13));
+    assert_eq!(nth_prime(1000), Some(7919));
+    assert_eq!(nth_prime(1_000_000), Some(15_485_863));
+  }
+
+  #[test]
+  fn test_is_prime_agrees_with_sieve() {
+    let primes = primes_up_to(10_000);
+    for n in 0..10_000 {
+      assert_eq!(is_prime(n), primes.binary_search(&n).is_ok(), "is_prime({n})");
+    }
+  }
+
+  #[test]
+  fn test_is_prime_large() {
+    assert!(is_prime(1_000_000_007));
+    assert!(is_prime(18_446_744_073_709_551_557)); // Largest 64-bit prime
+    assert!(!is_prime(18_446_744_073_709_551_615));
+    assert!(!is_prime(3_215_031_751)); // Strong pseudoprime to bases 2, 3, 5, 7
+    assert!(!is_prime(1_000_000_007 * 998_244_353));
+  }
+
+  #[test]
+  fn test_random_prime() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for bits in [2, 3, 8, 17, 32, 64] {
+      let p = random_prime(&mut rng, bits);
+      assert!(is_prime(p));
+      assert_eq!(64 - p.leading_zeros(), bits);
+    }
+  }
+
+  #[test]
+  fn test_nth_prime_inverts_prime_pi() {
+    for k in 1..300 {
+      let p = nth_prime(k).unwrap();
+      assert_eq!(prime_pi(p), k);
+      assert_eq!(prime_pi(p - 1), k - 1);
+    }
+  }
+}
@@ -6,6 +6,7 @@ use crate::util::remove_suffix;
 use crate::util::prism::Prism;
 
 use num::{BigInt, Zero, Signed, ToPrimitive};
+use serde::{Serialize, Deserialize};
 use thiserror::Error;
 
 use std::str::FromStr;
@@ -14,7 +15,8 @@ use std::fmt::{self, Display, Formatter};
 /// A numerical radix. Supported radixes are from 2 up to 36 inclusive
 /// and will use decimal digits first, followed by the uppercase Latin
 /// alphabet A-Z.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
 pub struct Radix {
   value: u8,
 }
@@ -264,6 +266,14 @@ impl From<Radix> for u8 {
   }
 }
 
+impl TryFrom<u8> for Radix {
+  type Error = RadixFromStrError;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    Radix::try_new(value).ok_or_else(|| RadixFromStrError { input: value.to_string() })
+  }
+}
+
 impl Default for ToDigitsOptions {
   fn default() -> Self {
     Self {
@@ -0,0 +1,116 @@
+
+//! Textbook RSA key generation, at sizes suitable for working through
+//! examples by hand. These keys are far too small to be secure, and
+//! no padding scheme is involved.
+
+use super::modular::modinv;
+use super::primes::random_prime;
+
+use num::{BigInt, Integer, One};
+use rand::Rng;
+use thiserror::Error;
+
+/// The smallest supported modulus size, in bits.
+pub const MIN_RSA_BITS: u32 = 16;
+
+/// The largest supported modulus size, in bits.
+pub const MAX_RSA_BITS: u32 = 128;
+
+/// The conventional public exponent, used whenever it is small enough
+/// for the key.
+const PREFERRED_PUBLIC_EXPONENT: u64 = 65537;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaKey {
+  /// The public modulus `n = p q`.
+  pub modulus: BigInt,
+  /// The public exponent `e`.
+  pub public_exponent: BigInt,
+  /// The private exponent `d`, the inverse of `e` modulo
+  /// `(p - 1)(q - 1)`.
+  pub private_exponent: BigInt,
+  pub p: u64,
+  pub q: u64,
+}
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+#[error("RSA modulus must have between {MIN_RSA_BITS} and {MAX_RSA_BITS} bits, got {0}")]
+pub struct RsaKeySizeError(pub u32);
+
+/// Generates an RSA key whose modulus has exactly `bits` bits, from
+/// two distinct random primes of (nearly) equal size.
+pub fn rsa_keygen<R: Rng + ?Sized>(rng: &mut R, bits: u32) -> Result<RsaKey, RsaKeySizeError> {
+  if !(MIN_RSA_BITS..=MAX_RSA_BITS).contains(&bits) {
+    return Err(RsaKeySizeError(bits));
+  }
+  let p = random_prime(rng, bits.div_ceil(2));
+  let q = loop {
+    let q = random_prime(rng, bits / 2);
+    if q != p {
+      break q;
+    }
+  };
+  let totient = BigInt::from(p - 1) * BigInt::from(q - 1);
+  let public_exponent = choose_public_exponent(&totient);
+  let private_exponent = modinv(&public_exponent, &totient)
+    .expect("public exponent should be coprime to the totient");
+  Ok(RsaKey {
+    modulus: BigInt::from(p) * BigInt::from(q),
+    public_exponent,
+    private_exponent,
+    p,
+    q,
+  })
+}
+
+/// 65537 if possible, otherwise the smallest valid exponent.
+fn choose_public_exponent(totient: &BigInt) -> BigInt {
+  let preferred = BigInt::from(PREFERRED_PUBLIC_EXPONENT);
+  if &preferred < totient && preferred.gcd(totient).is_one() {
+    return preferred;
+  }
+  // The totient is even, so only odd exponents can work. Some odd
+  // exponent below the totient always does, since the totient is
+  // coprime to totient - 1.
+  let mut e = BigInt::from(3);
+  while !e.gcd(totient).is_one() {
+    e += 2;
+  }
+  e
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::primes::is_prime;
+
+  use rand::SeedableRng;
+  use rand::rngs::StdRng;
+
+  #[test]
+  fn test_keygen_round_trip() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for bits in [MIN_RSA_BITS, 17, 32, 63, 64, 100, MAX_RSA_BITS] {
+      let key = rsa_keygen(&mut rng, bits).unwrap();
+      assert!(is_prime(key.p) && is_prime(key.q));
+      assert_ne!(key.p, key.q);
+      assert_eq!(key.modulus.bits(), u64::from(bits));
+      let message = BigInt::from(12345);
+      let ciphertext = message.modpow(&key.public_exponent, &key.modulus);
+      assert_eq!(ciphertext.modpow(&key.private_exponent, &key.modulus), message);
+    }
+  }
+
+  #[test]
+  fn test_small_totient_public_exponent() {
+    assert_eq!(choose_public_exponent(&BigInt::from(120)), BigInt::from(7));
+    assert_eq!(choose_public_exponent(&BigInt::from(65536 * 3)), BigInt::from(65537));
+  }
+
+  #[test]
+  fn test_invalid_key_size() {
+    let mut rng = StdRng::seed_from_u64(0);
+    assert_eq!(rsa_keygen(&mut rng, 8), Err(RsaKeySizeError(8)));
+    assert_eq!(rsa_keygen(&mut rng, 256), Err(RsaKeySizeError(256)));
+  }
+}
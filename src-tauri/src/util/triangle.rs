@@ -0,0 +1,218 @@
+
+//! Solving triangles from a partial list of sides and angles.
+//!
+//! Sides are indexed so that side `i` is opposite angle `i`. Angles
+//! are in radians.
+
+use thiserror::Error;
+
+use std::f64::consts::PI;
+
+/// Relative tolerance used when checking that redundant information
+/// (such as all three angles) is consistent.
+const TOLERANCE: f64 = 1e-9;
+
+/// A fully solved triangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+  pub sides: [f64; 3],
+  pub angles: [f64; 3],
+}
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TriangleError {
+  #[error("Sides must be positive")]
+  InvalidSide,
+  #[error("Angles must be strictly between zero and a straight angle")]
+  InvalidAngle,
+  #[error("At least three values, including one side, are needed to solve a triangle")]
+  Underdetermined,
+  #[error("No triangle has the given sides and angles")]
+  NoSolution,
+  #[error("The given sides and angles are inconsistent")]
+  Inconsistent,
+}
+
+impl Triangle {
+  pub fn area(&self) -> f64 {
+    0.5 * self.sides[0] * self.sides[1] * self.angles[2].sin()
+  }
+}
+
+/// Solves the triangle with the given known sides and angles. At
+/// least three values must be known, at least one of which is a side.
+///
+/// When two sides and an angle not between them are given, there may
+/// be two triangles satisfying the constraints. In that case, the
+/// triangle in which the unknown angle opposite a given side is
+/// acute is returned.
+///
+/// If more values are given than are necessary, they are checked for
+/// consistency with one another.
+pub fn solve_triangle(sides: [Option<f64>; 3], angles: [Option<f64>; 3]) -> Result<Triangle, TriangleError> {
+  if sides.iter().flatten().any(|s| !(*s > 0.0 && s.is_finite())) {
+    return Err(TriangleError::InvalidSide);
+  }
+  if angles.iter().flatten().any(|a| !(*a > 0.0 && *a < PI)) {
+    return Err(TriangleError::InvalidAngle);
+  }
+  let side_count = sides.iter().flatten().count();
+  let angle_count = angles.iter().flatten().count();
+  if side_count == 0 || side_count + angle_count < 3 {
+    return Err(TriangleError::Underdetermined);
+  }
+
+  let triangle = if angle_count >= 2 {
+    solve_from_two_angles(sides, angles)?
+  } else if side_count == 3 {
+    solve_from_three_sides(sides.map(Option::unwrap))?
+  } else {
+    // Exactly two sides and one angle.
+    let angle_index = angles.iter().position(Option::is_some).unwrap();
+    match sides[angle_index] {
+      None => solve_side_angle_side(sides, angle_index, angles[angle_index].unwrap())?,
+      Some(opposite_side) => solve_side_side_angle(sides, angle_index, opposite_side, angles[angle_index].unwrap())?,
+    }
+  };
+
+  // Verify anything we didn't use.
+  let consistent = |given: &[Option<f64>; 3], solved: &[f64; 3]| {
+    given.iter().zip(solved).all(|(given, solved)| {
+      given.is_none_or(|given| (given - solved).abs() <= TOLERANCE * given.abs().max(1.0))
+    })
+  };
+  if !consistent(&sides, &triangle.sides) || !consistent(&angles, &triangle.angles) {
+    return Err(TriangleError::Inconsistent);
+  }
+  Ok(triangle)
+}
+
+fn solve_from_two_angles(sides: [Option<f64>; 3], angles: [Option<f64>; 3]) -> Result<Triangle, TriangleError> {
+  let known_sum: f64 = angles.iter().flatten().sum();
+  let angles = match angles.iter().position(Option::is_none) {
+    None => {
+      if (known_sum - PI).abs() > TOLERANCE * PI {
+        return Err(TriangleError::Inconsistent);
+      }
+      angles.map(Option::unwrap)
+    }
+    Some(missing) => {
+      if known_sum >= PI {
+        return Err(TriangleError::NoSolution);
+      }
+      let mut result = angles.map(|a| a.unwrap_or(0.0));
+      result[missing] = PI - known_sum;
+      result
+    }
+  };
+  let known_index = sides.iter().position(Option::is_some).unwrap();
+  let ratio = sides[known_index].unwrap() / angles[known_index].sin();
+  Ok(Triangle { sides: angles.map(|a| ratio * a.sin()), angles })
+}
+
+fn solve_from_three_sides(sides: [f64; 3]) -> Result<Triangle, TriangleError> {
+  let [a, b, c] = sides;
+  if a >= b + c || b >= a + c || c >= a + b {
+    return Err(TriangleError::NoSolution);
+  }
+  let angle_opposite = |opposite: f64, x: f64, y: f64| {
+    ((x * x + y * y - opposite * opposite) / (2.0 * x * y)).clamp(-1.0, 1.0).acos()
+  };
+  let angles = [angle_opposite(a, b, c), angle_opposite(b, a, c), angle_opposite(c, a, b)];
+  Ok(Triangle { sides, angles })
+}
+
+/// Two sides and the angle between them, which is opposite the
+/// unknown side `angle_index`.
+fn solve_side_angle_side(sides: [Option<f64>; 3], angle_index: usize, angle: f64) -> Result<Triangle, TriangleError> {
+  let (j, k) = ((angle_index + 1) % 3, (angle_index + 2) % 3);
+  let (x, y) = (sides[j].unwrap(), sides[k].unwrap());
+  let mut all_sides = [0.0; 3];
+  all_sides[angle_index] = (x * x + y * y - 2.0 * x * y * angle.cos()).sqrt();
+  all_sides[j] = x;
+  all_sides[k] = y;
+  solve_from_three_sides(all_sides)
+}
+
+/// Two sides and an angle opposite one of them (the ambiguous case).
+fn solve_side_side_angle(
+  sides: [Option<f64>; 3],
+  angle_index: usize,
+  opposite_side: f64,
+  angle: f64,
+) -> Result<Triangle, TriangleError> {
+  let other_index = (0..3).find(|&i| i != angle_index && sides[i].is_some()).unwrap();
+  let sine = sides[other_index].unwrap() * angle.sin() / opposite_side;
+  if sine > 1.0 {
+    return Err(TriangleError::NoSolution);
+  }
+  let mut angles = [None; 3];
+  angles[angle_index] = Some(angle);
+  angles[other_index] = Some(sine.asin());
+  let mut known_sides = [None; 3];
+  known_sides[angle_index] = Some(opposite_side);
+  solve_from_two_angles(known_sides, angles)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use approx::assert_abs_diff_eq;
+
+  fn degrees(x: f64) -> f64 {
+    x.to_radians()
+  }
+
+  fn assert_triangle(triangle: Triangle, sides: [f64; 3], angles_in_degrees: [f64; 3]) {
+    for i in 0..3 {
+      assert_abs_diff_eq!(triangle.sides[i], sides[i], epsilon = 1e-9);
+      assert_abs_diff_eq!(triangle.angles[i].to_degrees(), angles_in_degrees[i], epsilon = 1e-9);
+    }
+  }
+
+  #[test]
+  fn test_three_sides() {
+    let triangle = solve_triangle([Some(3.0), Some(4.0), Some(5.0)], [None; 3]).unwrap();
+    assert_triangle(triangle, [3.0, 4.0, 5.0], [36.86989764584402, 53.13010235415598, 90.0]);
+    assert_abs_diff_eq!(triangle.area(), 6.0, epsilon = 1e-9);
+  }
+
+  #[test]
+  fn test_side_angle_side() {
+    let triangle = solve_triangle([Some(3.0), Some(4.0), None], [None, None, Some(degrees(90.0))]).unwrap();
+    assert_triangle(triangle, [3.0, 4.0, 5.0], [36.86989764584402, 53.13010235415598, 90.0]);
+  }
+
+  #[test]
+  fn test_angle_side_angle() {
+    let triangle = solve_triangle([None, None, Some(1.0)], [Some(degrees(60.0)), Some(degrees(60.0)), None]).unwrap();
+    assert_triangle(triangle, [1.0, 1.0, 1.0], [60.0, 60.0, 60.0]);
+  }
+
+  #[test]
+  fn test_side_side_angle_picks_acute_solution() {
+    let triangle = solve_triangle([Some(1.0), Some(2.0f64.sqrt()), None], [Some(degrees(30.0)), None, None]).unwrap();
+    assert_abs_diff_eq!(triangle.angles[1].to_degrees(), 45.0, epsilon = 1e-9);
+    assert_abs_diff_eq!(triangle.angles[2].to_degrees(), 105.0, epsilon = 1e-9);
+  }
+
+  #[test]
+  fn test_errors() {
+    assert_eq!(solve_triangle([Some(1.0), Some(1.0), Some(3.0)], [None; 3]), Err(TriangleError::NoSolution));
+    assert_eq!(solve_triangle([Some(1.0), Some(2.0), None], [Some(degrees(60.0)), None, None]), Err(TriangleError::NoSolution));
+    assert_eq!(solve_triangle([Some(1.0), Some(1.0), None], [None; 3]), Err(TriangleError::Underdetermined));
+    assert_eq!(solve_triangle([None; 3], [Some(1.0), Some(1.0), Some(PI - 2.0)]), Err(TriangleError::Underdetermined));
+    assert_eq!(solve_triangle([Some(-1.0), Some(1.0), Some(1.0)], [None; 3]), Err(TriangleError::InvalidSide));
+    assert_eq!(solve_triangle([Some(1.0), None, None], [Some(PI), Some(1.0), None]), Err(TriangleError::InvalidAngle));
+    assert_eq!(
+      solve_triangle([Some(3.0), Some(4.0), Some(5.0)], [None, None, Some(degrees(80.0))]),
+      Err(TriangleError::Inconsistent),
+    );
+    assert_eq!(
+      solve_triangle([Some(1.0), None, None], [Some(degrees(60.0)), Some(degrees(60.0)), Some(degrees(70.0))]),
+      Err(TriangleError::Inconsistent),
+    );
+  }
+}
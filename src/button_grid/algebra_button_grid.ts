@@ -1,7 +1,7 @@
 
 import { AbstractButtonManager, ButtonGrid, GridCell } from "../button_grid.js";
 import { SubcommandBehavior } from './subcommand.js';
-import { backButton, Button } from './button.js';
+import { backButton, Button, DispatchButton } from './button.js';
 import { variableNameInput } from '../input_box/algebraic_input.js';
 import { FreeformInputMethod } from '../input_box/freeform_input.js';
 import { TAURI } from '../tauri_api.js';
@@ -21,14 +21,36 @@ export class AlgebraButtonGrid extends ButtonGrid {
     return [
       [
         new VariableSubstituteButton(),
+        new VariableCommandButton("x=", "S", "isolate", 1),
+        new VariableCommandButton("x&lt;", "L", "solve_inequality", 1),
+        new VariableCommandButton("dom", "o", "domain", 1),
+        new DispatchButton("~", "match_expr", "m"),
+        new DeclareButton(),
       ],
       [
         new FindRootButton(),
         new DerivativeButton(),
+        new NumericalDerivativeButton(),
+        new IntegralButton(),
+        new VariableCommandButton("<span class='mathy-text'>∫≈</span>", "I", "num_integrate", 2),
+        new GlobalMinButton(),
+      ],
+      [
+        new VariableCommandButton("lim", "l", "limit", 2),
+        new VariableCommandButton("lim<sup>-</sup>", "<", "limit_left", 2),
+        new VariableCommandButton("lim<sup>+</sup>", ">", "limit_right", 2),
+        new VariableCommandButton("<span class='mathy-text'>Σ</span>", "s", "sum", 3),
+        new VariableCommandButton("<span class='mathy-text'>Π</span>", "p", "prod", 3),
+        new DispatchButton("<span class='mathy-text'>∂σ</span>", "sensitivity", "z"),
+      ],
+      [
+        new RandomGrammarButton("rand", "r", "random_expr"),
+        new RandomGrammarButton("chk", "k", "check_simplifier"),
+      ],
+      [
+        new InsertTemplateButton(),
+        new DispatchButton("&#x25A1;", "fill_slot", "f"),
       ],
-      [],
-      [],
-      [],
       [
         backButton(this.rootGrid),
       ],
@@ -72,6 +94,111 @@ export class VariableSubstituteButton extends Button {
   }
 }
 
+// Button which reads a variable name and an assumption from the
+// user, then declares that the variable has that property.
+export class DeclareButton extends Button {
+  constructor() {
+    super("decl", "a");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndDeclare(manager);
+  }
+
+  private async readAndDeclare(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const variableName = await variableNameInput(manager.inputManager);
+      if (!variableName) {
+        return;
+      }
+      const assumption = await manager.inputManager.show(new FreeformInputMethod("Assume:"), "real");
+      if (!assumption) {
+        return;
+      }
+      await manager.invokeMathCommand('declare', [variableName, assumption]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
+
+// Button which reads a template name, such as "integral", from the
+// user and pushes that template's skeleton expression. The slots are
+// then filled in one at a time with the "fill_slot" command.
+export class InsertTemplateButton extends Button {
+  constructor() {
+    super("tmpl", "t");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndInsert(manager);
+  }
+
+  private async readAndInsert(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const templateName = await manager.inputManager.show(new FreeformInputMethod("Template:"));
+      if (!templateName) {
+        return;
+      }
+      await manager.invokeMathCommand('insert_template', [templateName]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
+
+// Button which reads a list of operators and a list of variables
+// from the user, then invokes a command which builds random
+// expressions from them.
+export class RandomGrammarButton extends Button {
+  private commandName: string;
+
+  constructor(label: string, keyboardShortcut: string, commandName: string) {
+    super(label, keyboardShortcut);
+    this.commandName = commandName;
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndGenerate(manager);
+  }
+
+  private async readAndGenerate(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const operators = await manager.inputManager.show(new FreeformInputMethod("Ops:"), "+ - * /");
+      if (operators === undefined) {
+        return;
+      }
+      // An empty variable list is allowed, so only abort if the
+      // input was cancelled.
+      const variables = await manager.inputManager.show(new FreeformInputMethod("Vars:"), "x");
+      if (variables === undefined) {
+        return;
+      }
+      await manager.invokeMathCommand(this.commandName, [operators, variables]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
+
 // TODO: Common superclass for buttons which expect one variable as
 // input and call a command with it.
 export class FindRootButton extends Button {
@@ -107,6 +234,39 @@ export class FindRootButton extends Button {
   }
 }
 
+export class GlobalMinButton extends Button {
+
+  constructor() {
+    super("min", "M");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndApply(manager);
+  }
+
+  private async readAndApply(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const isValid = await TAURI.validateStackSize(2);
+      if (!isValid) {
+        return;
+      }
+      const variableName = await variableNameInput(manager.inputManager);
+      if (!variableName) {
+        return;
+      }
+      await manager.invokeMathCommand('global_min', [variableName]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
+
 export class DerivativeButton extends Button {
 
   constructor() {
@@ -139,3 +299,108 @@ export class DerivativeButton extends Button {
     return "invalid";
   }
 }
+
+export class NumericalDerivativeButton extends Button {
+
+  constructor() {
+    super("<span class='mathy-text'>dx≈</span>", "D");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndApply(manager);
+  }
+
+  private async readAndApply(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const isValid = await TAURI.validateStackSize(2);
+      if (!isValid) {
+        return;
+      }
+      const variableName = await variableNameInput(manager.inputManager);
+      if (!variableName) {
+        return;
+      }
+      await manager.invokeMathCommand('num_deriv', [variableName]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
+
+export class IntegralButton extends Button {
+
+  constructor() {
+    super("<span class='mathy-text'>∫dx</span>", "i");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndApply(manager);
+  }
+
+  private async readAndApply(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const isValid = await TAURI.validateStackSize(1);
+      if (!isValid) {
+        return;
+      }
+      const variableName = await variableNameInput(manager.inputManager);
+      if (!variableName) {
+        return;
+      }
+      await manager.invokeMathCommand('integrate', [variableName]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
+
+// Button which reads a variable name from the user and then invokes
+// a command with that variable as its sole argument.
+export class VariableCommandButton extends Button {
+  private commandName: string;
+  private requiredStackSize: number;
+
+  constructor(label: string, keyboardShortcut: string, commandName: string, requiredStackSize: number) {
+    super(label, keyboardShortcut);
+    this.commandName = commandName;
+    this.requiredStackSize = requiredStackSize;
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndApply(manager);
+  }
+
+  private async readAndApply(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const isValid = await TAURI.validateStackSize(this.requiredStackSize);
+      if (!isValid) {
+        return;
+      }
+      const variableName = await variableNameInput(manager.inputManager);
+      if (!variableName) {
+        return;
+      }
+      await manager.invokeMathCommand(this.commandName, [variableName]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
@@ -0,0 +1,35 @@
+import { ButtonGrid, GridCell } from "../button_grid.js";
+import { backButton, DispatchButton } from './button.js';
+
+export class BitwiseButtonGrid extends ButtonGrid {
+  readonly rows: readonly (readonly GridCell[])[];
+
+  private rootGrid: ButtonGrid;
+
+  constructor(rootGrid: ButtonGrid) {
+    super();
+    this.rootGrid = rootGrid;
+    this.rows = this.initRows();
+  }
+
+  private initRows(): GridCell[][] {
+    return [
+      [
+        new DispatchButton("and", "band", "a"),
+        new DispatchButton("or", "bor", "o"),
+        new DispatchButton("xor", "bxor", "x"),
+        new DispatchButton("not", "bnot", "n"),
+      ],
+      [
+        new DispatchButton("&lt;&lt;", "lsh", "l"),
+        new DispatchButton("&gt;&gt;", "rsh", "r"),
+      ],
+      [],
+      [],
+      [],
+      [
+        backButton(this.rootGrid),
+      ],
+    ];
+  }
+}
@@ -34,12 +34,21 @@ export class DisplayButtonGrid extends ButtonGrid {
         new SetDisplayRadixButton("0b", "2", 2),
         new SetDisplayRadixButton("0o", "8", 8),
         new SetDisplayRadixToInputButton(),
+        new DispatchButton("2C", "toggle_twos_complement", "t"),
+      ],
+      [
+        new SetLocaleToInputButton(),
+        new SetFloatFormatToInputButton(),
+        new SetMaxDisplayElementsToInputButton(),
+      ],
+      [
+        new DispatchButton("&#x27F2;", "reset_modes", "R"),
       ],
-      [],
-      [],
       [
         new DispatchButton(imageSvg(), "toggle_graphics", "G"),
         new DispatchButton("¶", "toggle_unicode", "u"),
+        new DispatchButton("(a)", "toggle_always_parenthesize", "("),
+        new DispatchButton("Aa", "toggle_spoken_text", "s"),
       ],
       [
         backButton(this.rootGrid),
@@ -99,6 +108,92 @@ export class SetDisplayRadixToInputButton extends Button {
   }
 }
 
+// Button to set the number locale to a value given by user input.
+export class SetLocaleToInputButton extends Button {
+  readonly commandName: string = "set_locale";
+
+  constructor() {
+    super("1,5", "l");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const userInput = await manager.inputManager.show(new FreeformInputMethod("Locale:"));
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+// Button to set the float format to a user-specified value, such as
+// "fix 4" or "normal".
+export class SetFloatFormatToInputButton extends Button {
+  readonly commandName: string = "set_float_format";
+
+  constructor() {
+    super("1.0", "F");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const userInput = await manager.inputManager.show(new FreeformInputMethod("Float format:"));
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+// Button to set the maximum number of elements displayed in a vector
+// or matrix to a value given by user input. Zero removes the limit.
+export class SetMaxDisplayElementsToInputButton extends Button {
+  readonly commandName: string = "set_max_display_elements";
+
+  constructor() {
+    super("[…]", "e");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const userInput = await manager.inputManager.show(new FreeformInputMethod("Max elements:", "number"));
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
 function isValidRadix(n: number): boolean {
   return (n >= 2 && n <= 36 && Number.isInteger(n));
 }
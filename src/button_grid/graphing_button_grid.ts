@@ -18,6 +18,11 @@ export class GraphingButtonGrid extends ButtonGrid {
       [
         new DispatchButton("y=", "plot", "f"),
         new DispatchButton("con", "contourplot", "c"),
+        new DispatchButton("t", "timeplot", "t"),
+        new DispatchButton("bar", "barchart", "b"),
+        new DispatchButton("stk", "stackedbar", "B"),
+        new DispatchButton("pie", "piechart", "p"),
+        new DispatchButton("sct", "scatterplot", "s"),
       ],
       [
         new DispatchButton("xy", "xy", null),
@@ -28,8 +28,14 @@ export class InputButtonGrid extends ButtonGrid {
         new DispatchButton("(", "incomplete(", "("),
         new DispatchButton(")", "incomplete)", ")"),
       ],
-      [],
-      [],
+      [
+        new DispatchButton("{", "incomplete{", "{"),
+        new DispatchButton("}", "incomplete}", "}"),
+      ],
+      [
+        new DispatchButton("<", "incomplete<", "<"),
+        new DispatchButton(">", "incomplete>", ">"),
+      ],
       [
         backButton(this.rootGrid),
       ],
@@ -1,6 +1,7 @@
 
 import { AbstractButtonManager, ButtonGrid, GridCell } from "../button_grid.js";
 import { AlgebraButtonGrid } from "./algebra_button_grid.js";
+import { BitwiseButtonGrid } from "./bitwise_button_grid.js";
 import { StorageButtonGrid } from "./storage_button_grid.js";
 import { VectorButtonGrid } from "./vector_button_grid.js";
 import { VectorStatsButtonGrid } from "./vector_stats_button_grid.js";
@@ -88,6 +89,7 @@ export class MainButtonGrid extends ButtonGrid {
         new DispatchButton(swapSvg(), "swap", "Tab"),
         new DispatchButton(dupSvg(), "dup", "Enter"),
         new GotoButton("<math><mi>ξ</mi></math>", "f", this.subgrids.transcendental),
+        new GotoButton("bit", "b", this.subgrids.bitwise),
       ],
       [
         new GotoButton("<math><mi>x</mi></math>", "a", this.subgrids.algebra),
@@ -136,6 +138,7 @@ export class MainButtonGrid extends ButtonGrid {
 
 class Subgrids {
   readonly algebra: AlgebraButtonGrid;
+  readonly bitwise: BitwiseButtonGrid;
   readonly display: DisplayButtonGrid;
   readonly formula: FormulaButtonGrid;
   readonly graphing: GraphingButtonGrid;
@@ -155,6 +158,7 @@ class Subgrids {
 
     // Primary button grids
     this.algebra = new AlgebraButtonGrid(mainGrid);
+    this.bitwise = new BitwiseButtonGrid(mainGrid);
     this.display = new DisplayButtonGrid(mainGrid);
     this.formula = new FormulaButtonGrid(mainGrid);
     this.graphing = new GraphingButtonGrid(mainGrid);
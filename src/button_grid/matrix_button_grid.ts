@@ -1,5 +1,5 @@
 
-import { ButtonGrid, GridCell } from "../button_grid.js";
+import { ButtonGrid, GridCell, Spacer } from "../button_grid.js";
 import { backButton, DispatchButton } from './button.js';
 import { SignedNumberedButton, UnsignedNumberedButton } from './button/numbered.js';
 
@@ -30,12 +30,21 @@ export class MatrixButtonGrid extends ButtonGrid {
         new DispatchButton("<math><msup><mi>A</mi><mn>-1</mn></msup></math>", "recip", "&"),
         new DispatchButton("det", "det", "D"),
         new DispatchButton("tr", "trace", "T"),
+        new DispatchButton("LU", "lu", "u"),
+        new DispatchButton("QR", "qr", "q"),
       ],
       [
         new DispatchButton("<math><mo>&times;</mo></math>", "@", "@"),
         new DispatchButton("<math><mo>&otimes;</mo></math>", "kron", "K"),
+        new Spacer(),
+        new DispatchButton("chol", "cholesky", "C"),
+        new DispatchButton("SVD", "svd", "s"),
+      ],
+      [
+        new DispatchButton("<math><msup><mi>e</mi><mi>A</mi></msup></math>", "matexp", "E"),
+        new DispatchButton("<math><msup><mi>A</mi><mi>n</mi></msup></math>", "matpow", "^"),
+        new DispatchButton("log", "matlog", "L"),
       ],
-      [],
       [
         backButton(this.rootGrid),
       ],
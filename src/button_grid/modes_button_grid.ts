@@ -1,6 +1,8 @@
 
-import { ButtonGrid, GridCell } from "../button_grid.js";
-import { backButton, DispatchButton } from './button.js';
+import { AbstractButtonManager, ButtonGrid, GridCell } from "../button_grid.js";
+import { SubcommandBehavior } from './subcommand.js';
+import { backButton, Button, DispatchButton } from './button.js';
+import { FreeformInputMethod } from '../input_box/freeform_input.js';
 
 export class ModesButtonGrid extends ButtonGrid {
   readonly rows: readonly (readonly GridCell[])[];
@@ -18,9 +20,22 @@ export class ModesButtonGrid extends ButtonGrid {
       [
         new DispatchButton("∞", "toggle_infinity", "i"),
         new DispatchButton("&divide;", "toggle_fractional", "f"),
+        new DispatchButton("[ℚ]", "toggle_exact_matrix", "x"),
+        new DispatchButton("arg", "toggle_branch_cut", "b"),
+        new DispatchButton("deg", "toggle_degrees", "d"),
+        new DispatchButton("∠", "toggle_polar_display", "p"),
+      ],
+      [
+        new SetModulusButton(),
+        new DispatchButton("m/s", "toggle_units", "u"),
+        new DispatchButton("N·m=J", "toggle_derived_units", "U"),
+        new SetPrecisionButton(),
+        new SetWordSizeButton(),
+        new SetRoundingModeButton(),
+      ],
+      [
+        new SetUndoLimitButton(),
       ],
-      [],
-      [],
       [],
       [],
       [
@@ -29,3 +44,153 @@ export class ModesButtonGrid extends ButtonGrid {
     ];
   }
 }
+
+// Button to set (or, with an input of zero, clear) the modulus for
+// modular arithmetic mode.
+export class SetModulusButton extends Button {
+  readonly commandName: string = "set_modulus";
+
+  constructor() {
+    super("mod", "m");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const userInput = await manager.inputManager.show(new FreeformInputMethod("Modulus:", "number"));
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+// Button to set (or, with an input of zero, clear) the number of
+// significant digits for arbitrary-precision mode.
+export class SetPrecisionButton extends Button {
+  readonly commandName: string = "set_precision";
+
+  constructor() {
+    super("prec", "P");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const userInput = await manager.inputManager.show(new FreeformInputMethod("Precision:", "number"));
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+// Button to set (or, with an input of zero, clear) the word size
+// used by the bitwise functions.
+export class SetWordSizeButton extends Button {
+  readonly commandName: string = "set_word_size";
+
+  constructor() {
+    super("word", "w");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const userInput = await manager.inputManager.show(new FreeformInputMethod("Word size (e.g. u8, i32):"));
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+// Button to set the rounding mode used by the rounding functions and
+// by the fixed, scientific, and engineering float formats.
+export class SetRoundingModeButton extends Button {
+  readonly commandName: string = "set_rounding_mode";
+
+  constructor() {
+    super("rnd", "r");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const userInput = await manager.inputManager.show(
+        new FreeformInputMethod("Rounding mode (half-up, half-even, toward-zero, away-from-zero):"),
+      );
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+// Button to set (or, with an input of zero, clear) the maximum
+// number of undo steps kept in history. With the hyperbolic
+// modifier, sets the memory limit of the undo history, in megabytes,
+// instead.
+export class SetUndoLimitButton extends Button {
+  readonly commandName: string = "set_undo_limit";
+
+  constructor() {
+    super("undo", "z");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    this.getInputAndSet(manager); // Fire-and-forget
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async getInputAndSet(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const prompt = manager.getModifiers().hyperbolicModifier ? "Undo memory limit (MB):" : "Undo depth limit:";
+      const userInput = await manager.inputManager.show(new FreeformInputMethod(prompt, "number"));
+      if (userInput) {
+        await manager.invokeMathCommand(this.commandName, [userInput]);
+      }
+    } finally {
+      manager.resetState();
+    }
+  }
+}
@@ -2,8 +2,11 @@
 import { AbstractButtonManager, ButtonGrid, GridCell } from "../button_grid.js";
 import { SubcommandBehavior } from './subcommand.js';
 import { ButtonModifiers } from './modifier_delegate.js';
-import { backButton, Button } from './button.js';
+import { backButton, Button, DispatchButton } from './button.js';
+import { UnsignedNumberedButton } from './button/numbered.js';
+import { VariableCommandButton } from './algebra_button_grid.js';
 import { variableNameInput } from '../input_box/algebraic_input.js';
+import { FreeformInputMethod } from '../input_box/freeform_input.js';
 import { TAURI } from '../tauri_api.js';
 
 export class StorageButtonGrid extends ButtonGrid {
@@ -25,10 +28,38 @@ export class StorageButtonGrid extends ButtonGrid {
       ],
       [
         new VariableUnbindButton(),
+        new DispatchButton("<math><mo>&empty;</mo></math>", "clear_vars", "U"),
+        new DispatchButton("<math><mo>⌫</mo></math>", "clear_stack", "C"),
+        new DispatchButton("&#x27F2;", "reset_session", "X"),
+        new DispatchButton("<math><mi>f</mi><mo>:=</mo></math>", "define_function", "f"),
+        new VariableCommandButton("<math><mi>f</mi><mo>'</mo><mo>:=</mo></math>", "F", "define_derivative", 1),
+        new DispatchButton("<math><mo>:=</mo></math>", "add_rewrite_rule", "R"),
+      ],
+      [
+        new UnsignedNumberedButton("&#x1F516;", "bookmark", "b", "Bookmark:"),
+        new UnsignedNumberedButton("&#x1F516;&#x2193;", "recall_bookmark", "r", "Bookmark:"),
+        new LabelStackElemButton(),
+        new DispatchButton("&#x1F4CC;", "pin_stack_elem", "p"),
+        new DispatchButton("&#x1F4CC;&#x2715;", "unpin_stack_elem", "P"),
+        new GroupStackElemsButton(),
+        new DispatchButton("&#x1F5C2;&#x2715;", "ungroup", "G"),
+      ],
+      [
+        new DispatchButton("&#x2935;", "to_alt_stack", "a"),
+        new DispatchButton("&#x2934;", "from_alt_stack", "A"),
+        new DispatchButton("&#x21C4;", "swap_stacks", "x"),
+      ],
+      [
+        new DispatchButton("&#x23FA;", "start_macro_record", "("),
+        new DispatchButton("&#x23F9;", "end_macro_record", ")"),
+        new DispatchButton("&#x25B6;", "play_macro", "e"),
+      ],
+      [
+        new DispatchButton("&#x23F1;", "stopwatch_start", "w"),
+        new DispatchButton("&#x23F1;&#x21BB;", "stopwatch_lap", "l"),
+        new DispatchButton("&#x23F1;&#x23F9;", "stopwatch_stop", "W"),
+        new UnsignedNumberedButton("&#x23F2;", "timer", "T", "Seconds:"),
       ],
-      [],
-      [],
-      [],
       [
         backButton(this.rootGrid),
       ],
@@ -106,3 +137,74 @@ export class VariableUnbindButton extends Button {
     }
   }
 }
+
+export const LABEL_INPUT_PROMPT = "Label:";
+
+export class LabelStackElemButton extends Button {
+  constructor() {
+    super("&#x1F3F7;", "L");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndLabel(manager);
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async readAndLabel(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const stackIndex = Math.max(manager.getModifiers().prefixArgument ?? 1, 1);
+      const isValid = await TAURI.validateStackSize(stackIndex);
+      if (!isValid) {
+        return;
+      }
+      const label = await manager.inputManager.show(new FreeformInputMethod(LABEL_INPUT_PROMPT));
+      if (label === undefined) {
+        return;
+      }
+      // An empty label clears the existing one.
+      await manager.invokeMathCommand('label_stack_elem', [label]);
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+export const GROUP_TITLE_INPUT_PROMPT = "Group title:";
+
+export class GroupStackElemsButton extends Button {
+  constructor() {
+    super("&#x1F5C2;", "g");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndGroup(manager);
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async readAndGroup(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const count = Math.max(manager.getModifiers().prefixArgument ?? 1, 1);
+      const isValid = await TAURI.validateStackSize(count);
+      if (!isValid) {
+        return;
+      }
+      const title = await manager.inputManager.show(new FreeformInputMethod(GROUP_TITLE_INPUT_PROMPT));
+      if (!title) {
+        return;
+      }
+      await manager.invokeMathCommand('group', [title]);
+    } finally {
+      manager.resetState();
+    }
+  }
+}
@@ -19,7 +19,12 @@ export class StringButtonGrid extends ButtonGrid {
         new DispatchButton("AZ", "uppercase", "M-u"),
         new DispatchButton("az", "lowercase", "M-l"),
       ],
-      [],
+      [
+        new DispatchButton("%", "url_encode", "u"),
+        new DispatchButton("%⁻¹", "url_decode", "U"),
+        new DispatchButton("&amp;", "html_escape", "h"),
+        new DispatchButton("&amp;⁻¹", "html_unescape", "H"),
+      ],
       [],
       [],
       [],
@@ -36,8 +36,11 @@ export class UnitsButtonGrid extends ButtonGrid {
       [
         new DispatchButton("1", "remove_units", "r"),
         new DispatchButton("cm", "extract_units", "x"),
+        new CurrencyConversionButton(),
+      ],
+      [
+        new ContextConversionButton(),
       ],
-      [],
       [],
       [],
       [
@@ -142,6 +145,82 @@ export class TemperatureConversionButton extends Button {
   }
 }
 
+export class CurrencyConversionButton extends Button {
+  constructor() {
+    super("$", "$");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndSubstitute(manager);
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async readAndSubstitute(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const isValid = await TAURI.validateStackSize(1);
+      if (!isValid) {
+        return;
+      }
+      // Currency codes are checked against the exchange rate table
+      // by the command itself.
+      const sourceCurrency = await manager.inputManager.show(new FreeformInputMethod("Old currency:"), "USD");
+      if (!sourceCurrency) {
+        return;
+      }
+      const destCurrency = await manager.inputManager.show(new FreeformInputMethod("New currency:"), "");
+      if (!destCurrency) {
+        return;
+      }
+      await manager.invokeMathCommand('convert_currency', [sourceCurrency, destCurrency]);
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
+export class ContextConversionButton extends Button {
+  constructor() {
+    super("<span class='mathy-text'>ctx</span>", "k");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndSubstitute(manager);
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+
+  private async readAndSubstitute(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const isValid = await TAURI.validateStackSize(1);
+      if (!isValid) {
+        return;
+      }
+      // The context name is checked against the user's defined
+      // conversion contexts by the command itself.
+      const contextName = await manager.inputManager.show(new FreeformInputMethod("Context:"), "");
+      if (!contextName) {
+        return;
+      }
+      const destUnits = await unitInput(manager.inputManager, "New units:");
+      if (!destUnits) {
+        return;
+      }
+      await manager.invokeMathCommand('convert_units_with_context', [destUnits, contextName]);
+    } finally {
+      manager.resetState();
+    }
+  }
+}
+
 // Freeform input that validates as a valid unit.
 export async function unitInput(manager: InputBoxManager, prompt: string, initialInput: string = ""): Promise<string | undefined> {
   const text = await manager.show(new FreeformInputMethod(prompt), initialInput);
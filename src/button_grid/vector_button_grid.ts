@@ -58,6 +58,14 @@ export class VectorButtonGrid extends ButtonGrid {
         new SubcommandDispatchButton("<math><mo>&otimes;</mo></math>", "outerprod", "O"),
         innerProductButton(),
       ],
+      [
+        new DispatchButton("<math><mo>&cup;</mo></math>", "union", "+"),
+        new DispatchButton("<math><mo>&cap;</mo></math>", "intersection", "&"),
+        new DispatchButton("<math><mo>&setminus;</mo></math>", "difference", "-"),
+        new DispatchButton("<math><mi>&weierp;</mi></math>", "powerset", "P"),
+        new DispatchButton("#", "cardinality", "#"),
+        new DispatchButton("<math><mo>&isin;</mo></math>", "member", "e"),
+      ],
       [
         backButton(this.rootGrid),
         new GotoButton(barGraphSvg(), "V", subgrids.vectorStats),
@@ -1,6 +1,8 @@
 
-import { ButtonGrid, GridCell } from "../button_grid.js";
-import { backButton, DispatchButton } from './button.js';
+import { AbstractButtonManager, ButtonGrid, GridCell } from "../button_grid.js";
+import { SubcommandBehavior } from './subcommand.js';
+import { backButton, Button, DispatchButton } from './button.js';
+import { FreeformInputMethod } from '../input_box/freeform_input.js';
 
 export class VectorStatsButtonGrid extends ButtonGrid {
   readonly rows: readonly (readonly GridCell[])[];
@@ -22,7 +24,11 @@ export class VectorStatsButtonGrid extends ButtonGrid {
         new DispatchButton("std", "stddev", "S"),
         new DispatchButton("cov", "covariance", "C"),
       ],
-      [],
+      [
+        new FitButton(),
+        new DispatchButton("hist", "histogram", "h"),
+        new DispatchButton("sum", "summary", "s"),
+      ],
       [],
       [],
       [],
@@ -32,3 +38,34 @@ export class VectorStatsButtonGrid extends ButtonGrid {
     ];
   }
 }
+
+// Button which reads a model name, such as "linear" or "poly 2", from
+// the user and fits that model to the data matrix on top of the
+// stack.
+export class FitButton extends Button {
+  constructor() {
+    super("fit", "F");
+  }
+
+  async fire(manager: AbstractButtonManager): Promise<void> {
+    // Fire-and-forget a new promise that gets user input, so we don't
+    // hold up the existing input.
+    this.readAndFit(manager);
+  }
+
+  private async readAndFit(manager: AbstractButtonManager): Promise<void> {
+    try {
+      const model = await manager.inputManager.show(new FreeformInputMethod("Model:"), "linear");
+      if (!model) {
+        return;
+      }
+      await manager.invokeMathCommand('fit', [model]);
+    } finally {
+      manager.resetState();
+    }
+  }
+
+  asSubcommand(): SubcommandBehavior {
+    return "invalid";
+  }
+}
@@ -1,7 +1,10 @@
 
 import { StackUpdatedDelegate } from './stack_view.js';
 import { TAURI } from './tauri_api.js';
-import { GraphicsDirective, PlotDirective, ContourPlotDirective } from './tauri_api/graphics.js';
+import {
+  GraphicsDirective, GraphicsResponse, PlotDirective, ContourPlotDirective, TimePlotDirective, BarChartDirective,
+  PieChartDirective, ScatterPlotDirective,
+} from './tauri_api/graphics.js';
 import { GLOBAL_IMAGE_CACHE } from './graphics/image_cache.js';
 
 import Plotly from 'plotly.js-dist-min';
@@ -120,8 +123,8 @@ export async function renderPlotTo(
     // isn't hurting.
     throw "Failed to render graphics";
   }
-  const data = response.directives.map(directiveToTrace);
-  const finalLayout = Object.assign(defaultPlotLayout(), layout);
+  const data = response.directives.flatMap(directiveToTraces);
+  const finalLayout = Object.assign(defaultPlotLayout(), responseLayout(response), layout);
   const finalConfig = Object.assign(defaultPlotConfig(), config);
 
   const plot = await Plotly.newPlot(renderTarget.getHtmlRenderTarget(), data, finalLayout, finalConfig);
@@ -137,16 +140,42 @@ function defaultPlotLayout(): Partial<Plotly.Layout> {
   };
 }
 
+// Layout settings which depend on the directives being rendered.
+function responseLayout(response: GraphicsResponse): Partial<Plotly.Layout> {
+  return {
+    xaxis: { type: response.xAxis },
+    barmode: response.barMode,
+  };
+}
+
 function defaultPlotConfig(): Partial<Plotly.Config> {
   return {};
 }
 
-function directiveToTrace(directive: GraphicsDirective): Plotly.Data {
+function directiveToTraces(directive: GraphicsDirective): Plotly.Data[] {
+  switch (directive.type) {
+  case "barchart":
+  case "stackedbar":
+    return barChartToTraces(directive);
+  case "scatterplot":
+    return scatterPlotToTraces(directive);
+  default:
+    return [directiveToTrace(directive)];
+  }
+}
+
+function directiveToTrace(
+  directive: Exclude<GraphicsDirective, BarChartDirective | ScatterPlotDirective>,
+): Plotly.Data {
   switch (directive.type) {
   case "plot":
     return plotToTrace(directive);
   case "contourplot":
     return contourPlotToTrace(directive);
+  case "timeplot":
+    return timePlotToTrace(directive);
+  case "piechart":
+    return pieChartToTrace(directive);
   }
 }
 
@@ -158,6 +187,55 @@ function plotToTrace(plot: PlotDirective): Partial<Plotly.PlotData> {
   };
 }
 
+function timePlotToTrace(timePlot: TimePlotDirective): Partial<Plotly.PlotData> {
+  return {
+    x: timePlot.points.map((p) => p.x),
+    y: timePlot.points.map((p) => p.y),
+    type: 'scatter',
+  };
+}
+
+// The points as markers, plus the regression curve as a line if
+// there is one.
+function scatterPlotToTraces(scatterPlot: ScatterPlotDirective): Partial<Plotly.PlotData>[] {
+  const traces: Partial<Plotly.PlotData>[] = [{
+    x: scatterPlot.points.map((p) => p.x),
+    y: scatterPlot.points.map((p) => p.y),
+    mode: 'markers',
+    type: 'scatter',
+  }];
+  if (scatterPlot.regression != null) {
+    traces.push({
+      x: scatterPlot.regression.map((p) => p.x),
+      y: scatterPlot.regression.map((p) => p.y),
+      mode: 'lines',
+      type: 'scatter',
+    });
+  }
+  return traces;
+}
+
+// One trace per series, with each bar labeled by its value.
+function barChartToTraces(barChart: BarChartDirective): Partial<Plotly.PlotData>[] {
+  return barChart.series.map((values) => ({
+    x: barChart.labels,
+    y: values,
+    text: values.map(String),
+    textposition: 'auto',
+    type: 'bar',
+  }));
+}
+
+// Each wedge is labeled by its percentage of the total.
+function pieChartToTrace(pieChart: PieChartDirective): Partial<Plotly.PlotData> {
+  return {
+    labels: pieChart.labels,
+    values: pieChart.values,
+    textinfo: 'label+percent',
+    type: 'pie',
+  };
+}
+
 function contourPlotToTrace(contourPlot: ContourPlotDirective): Partial<Plotly.PlotData> {
   return {
     x: contourPlot.xValues,
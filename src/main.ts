@@ -1,16 +1,76 @@
 
 import * as Page from './page.js';
 import { UiManager } from './ui_manager.js';
-import { TAURI, RefreshStackPayload, UndoAvailabilityPayload,
-         ModelinePayload, defaultCommandOptions } from './tauri_api.js';
+import { TAURI, RefreshStackPayload, UndoAvailabilityPayload, CommandTiming,
+         ModelinePayload, ModeIndicator, ThemeHintsPayload, ConfirmationRequestPayload, ShowErrorPayload,
+         ComputationProgressPayload, defaultCommandOptions } from './tauri_api.js';
 import { StackView, StackUpdatedDelegate } from './stack_view.js';
 import { GRAPHICS_DELEGATE } from './graphics.js';
 
 async function refreshStack(stackView: StackView, payload: RefreshStackPayload): Promise<void> {
-  await stackView.refreshStack(payload.stack);
+  await stackView.refreshStack(payload.stack, payload.spokenStack, payload.labels, payload.pinned, payload.groups);
   if (payload.forceScrollDown) {
     stackView.scrollToBottom();
   }
+  refreshCommandTiming(payload.commandTiming);
+}
+
+// Commands faster than this are not worth remarking on.
+const COMMAND_TIMING_THRESHOLD_MILLIS = 100;
+
+// Shows how long the last command took, if it was slow enough to
+// notice.
+function refreshCommandTiming(timing: CommandTiming | null) {
+  const indicator = Page.getCommandTiming();
+  if (timing === null || timing.elapsedMillis < COMMAND_TIMING_THRESHOLD_MILLIS) {
+    indicator.textContent = "";
+    indicator.title = "";
+  } else {
+    const seconds = (timing.elapsedMillis / 1000).toFixed(1);
+    indicator.textContent = `computed in ${seconds} s`;
+    indicator.title = `${timing.commandName} took ${timing.elapsedMillis} ms`;
+  }
+}
+
+// Progress events are sent from a separate thread, so one can arrive
+// after its job has already finished. Those are ignored.
+let lastCompletedJobId = -1;
+
+// Shows the progress of a command running in the background. While
+// the command runs, clicking the indicator cancels it. Once it
+// finishes, the stack refresh replaces the progress with the usual
+// timing.
+function refreshComputationProgress(uiManager: UiManager, payload: ComputationProgressPayload) {
+  const indicator = Page.getCommandTiming();
+  if (payload.jobId <= lastCompletedJobId) {
+    return;
+  }
+  switch (payload.status) {
+  case "running": {
+    const seconds = (payload.elapsedMillis / 1000).toFixed(1);
+    indicator.textContent = `computing ${payload.commandName}… ${seconds} s`;
+    indicator.title = `${payload.steps} simplifications so far (click to cancel)`;
+    indicator.classList.add("command-timing-running");
+    break;
+  }
+  case "finished":
+    lastCompletedJobId = payload.jobId;
+    indicator.classList.remove("command-timing-running");
+    break;
+  case "cancelled":
+    lastCompletedJobId = payload.jobId;
+    indicator.classList.remove("command-timing-running");
+    indicator.textContent = "";
+    indicator.title = "";
+    uiManager.notificationManager.show(`Cancelled ${payload.commandName}`);
+    break;
+  }
+}
+
+function cancelRunningComputation() {
+  if (Page.getCommandTiming().classList.contains("command-timing-running")) {
+    TAURI.cancelComputation();
+  }
 }
 
 function refreshUndoButtons(uiManager: UiManager, state: UndoAvailabilityPayload) {
@@ -19,15 +79,83 @@ function refreshUndoButtons(uiManager: UiManager, state: UndoAvailabilityPayload
   undoManager.setRedoButtonEnabled(state.hasRedos);
 }
 
+// Notes how many errors were folded into this one, if any.
+function errorText(payload: ShowErrorPayload): string {
+  if (payload.count > 1) {
+    return `${payload.errorMessage} (${payload.count} errors in total)`;
+  } else {
+    return payload.errorMessage;
+  }
+}
+
+// Renders each modeline indicator as its own span, so that toggleable
+// modes can be clicked. Falls back to the plain modeline text if the
+// payload has no indicators.
 function refreshModeline(payload: ModelinePayload) {
   const modeline = Page.getModelineBar();
   const pre = document.createElement("pre");
-  pre.innerHTML = payload.modelineText;
+  if (payload.indicators.length === 0) {
+    pre.textContent = payload.modelineText;
+  } else {
+    for (const indicator of payload.indicators) {
+      pre.appendChild(modeIndicatorElement(indicator));
+    }
+  }
   modeline.innerHTML = "";
   modeline.appendChild(pre);
 }
 
+function modeIndicatorElement(indicator: ModeIndicator): HTMLElement {
+  const span = document.createElement("span");
+  span.textContent = indicator.value;
+  span.classList.add("mode-indicator");
+  if (indicator.key !== null) {
+    span.dataset.modeKey = indicator.key;
+  }
+  if (indicator.active) {
+    span.classList.add("mode-indicator-active");
+  }
+  if (indicator.tooltip !== null) {
+    span.title = indicator.tooltip;
+  }
+  const toggleCommand = indicator.toggleCommand;
+  if (toggleCommand !== null) {
+    span.classList.add("mode-indicator-toggle");
+    span.addEventListener("click", () => TAURI.runMathCommand(toggleCommand, [], defaultCommandOptions()));
+  }
+  return span;
+}
+
+// Exposes the engine's theme hints as data attributes on the body,
+// so that stylesheets can respond to them.
+function refreshThemeHints(payload: ThemeHintsPayload) {
+  const dataset = document.body.dataset;
+  dataset.errorLevel = payload.errorLevel;
+  dataset.numericKind = payload.numericKind;
+  dataset.angleMode = payload.degreesMode ? "degrees" : "radians";
+  dataset.precision = payload.precision === null ? "" : String(payload.precision);
+  dataset.activeModes = payload.activeModes.join(" ");
+}
+
+// Asks the user to confirm a destructive command, re-running it if
+// they agree.
+async function handleConfirmationRequest(payload: ConfirmationRequestPayload): Promise<void> {
+  if (window.confirm(payload.message)) {
+    await TAURI.runMathCommand(payload.commandName, payload.args, { ...payload.options, confirmed: true });
+  }
+}
+
+// Auxiliary windows (opened via TAURI.openWindow) share the backend
+// state but only display the stack.
+function isAuxiliaryWindow(): boolean {
+  const windowKind = new URLSearchParams(window.location.search).get("window");
+  return windowKind !== null && windowKind !== "main";
+}
+
 window.addEventListener("DOMContentLoaded", async function() {
+  if (isAuxiliaryWindow()) {
+    document.body.classList.add("auxiliary-window");
+  }
   const uiManager = await UiManager.create();
   const stackView = new StackView(
     Page.getValueStack(),
@@ -39,9 +167,14 @@ window.addEventListener("DOMContentLoaded", async function() {
 
   uiManager.initListeners();
   await TAURI.listen("refresh-stack", (event) => refreshStack(stackView, event.payload));
-  await TAURI.listen("show-error", (event) => uiManager.notificationManager.show(event.payload.errorMessage));
+  await TAURI.listen("show-error", (event) => uiManager.notificationManager.show(errorText(event.payload)));
   await TAURI.listen("refresh-undo-availability", (event) => refreshUndoButtons(uiManager, event.payload));
   await TAURI.listen("refresh-modeline", (event) => refreshModeline(event.payload));
+  await TAURI.listen("refresh-theme-hints", (event) => refreshThemeHints(event.payload));
+  await TAURI.listen("request-confirmation", (event) => handleConfirmationRequest(event.payload));
+  await TAURI.listen("timer-expired", (event) => uiManager.notificationManager.show(`Timer finished (${event.payload.seconds} s)`));
+  await TAURI.listen("computation-progress", (event) => refreshComputationProgress(uiManager, event.payload));
+  Page.getCommandTiming().addEventListener("click", cancelRunningComputation);
 
   // Send a nop command, just to flush the stack and undo buttons in
   // case we were resumed from a paused state.
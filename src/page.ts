@@ -18,6 +18,7 @@ export const ElementIds = {
   UNDO_BUTTON: 'undo-button',
   REDO_BUTTON: 'redo-button',
   MODELINE_BAR: 'modeline-bar',
+  COMMAND_TIMING: 'command-timing',
 };
 
 function getElement(id: string): HTMLElement {
@@ -92,3 +93,7 @@ export function getRedoButton(): HTMLButtonElement {
 export function getModelineBar(): HTMLElement {
   return getElement(ElementIds.MODELINE_BAR);
 }
+
+export function getCommandTiming(): HTMLElement {
+  return getElement(ElementIds.COMMAND_TIMING);
+}
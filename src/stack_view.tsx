@@ -1,5 +1,6 @@
 
 import { jsx, HtmlText, toNodes } from './jsx.js';
+import { TAURI, StackGroup } from './tauri_api.js';
 
 // Manager class for displaying the current value stack.
 export class StackView {
@@ -11,26 +12,50 @@ export class StackView {
     this.delegate = delegate ?? NULL_STACK_UPDATED_DELEGATE;
   }
 
-  async refreshStack(newStackHtml: string[]): Promise<void> {
+  async refreshStack(
+    newStackHtml: string[],
+    spokenStack: string[] | null = null,
+    labels: (string | null)[] = [],
+    pinned: boolean[] = [],
+    groups: StackGroup[] = [],
+  ): Promise<void> {
     this.valueStackDiv.dataset.stackLength = String(newStackHtml.length);
     const listItems = [];
     for (let i = 0; i < newStackHtml.length; i++) {
       const elem = newStackHtml[i];
       const index = newStackHtml.length - i;
+      const label = labels[i] ?? null;
+      const labelSpan = (label === null) ? [] : [
+        <span class='value-stack-element-label'>{label}</span>
+      ];
+      const isPinned = pinned[i] ?? false;
+      const pinSpan = isPinned ? [
+        <span class='value-stack-element-pin' title='Pinned'>&#x1F4CC;</span>
+      ] : [];
       const li = (
-        <li class='value-stack-element' data-stack-index={index - 1} value={index}>
+        <li class='value-stack-element' data-stack-index={index - 1} data-pinned={String(isPinned)} value={index}>
           <span class='value-stack-element-ordinal'>
             {index}.&nbsp;
           </span>
           <span class='value-stack-element-value'>
             <HtmlText content={elem} />
           </span>
+          {labelSpan}
+          {pinSpan}
         </li>
       );
+      for (const marker of li.querySelectorAll('.elision')) {
+        marker.addEventListener('click', () => this.showFullElement(li, index - 1));
+      }
+      if (spokenStack !== null) {
+        const spokenLabel = (label === null) ? "" : ` (${label})`;
+        const spokenPin = isPinned ? " (pinned)" : "";
+        li.setAttribute("aria-label", `${index}. ${spokenStack[i]}${spokenLabel}${spokenPin}`);
+      }
       listItems.push(li);
     }
     const ol = (
-      <ol>{listItems}</ol>
+      <ol>{collapseGroups(listItems, groups)}</ol>
     );
     const stack = this.valueStackDiv;
     stack.innerHTML = "";
@@ -38,11 +63,43 @@ export class StackView {
     await this.delegate.onStackUpdated(stack);
   }
 
+  // Replaces an abbreviated stack element with its full rendering.
+  private async showFullElement(li: HTMLElement, stackIndex: number): Promise<void> {
+    const html = await TAURI.showFullStackElem(stackIndex);
+    const valueSpan = li.querySelector('.value-stack-element-value');
+    if (valueSpan !== null && html !== "") {
+      valueSpan.innerHTML = html;
+      await this.delegate.onStackUpdated(this.valueStackDiv);
+    }
+  }
+
   scrollToBottom(): void {
     this.valueStackDiv.scrollTo({ top: this.valueStackDiv.scrollHeight });
   }
 }
 
+// Wraps each group of stack elements in a single collapsible list
+// item. The groups must be sorted and must not overlap.
+function collapseGroups(listItems: HTMLElement[], groups: StackGroup[]): HTMLElement[] {
+  const result = [];
+  let i = 0;
+  for (const group of groups) {
+    result.push(...listItems.slice(i, group.start));
+    const members = listItems.slice(group.start, group.start + group.length);
+    result.push(
+      <li class='value-stack-group'>
+        <details>
+          <summary class='value-stack-group-title'>{group.title} ({members.length})</summary>
+          <ol>{members}</ol>
+        </details>
+      </li>
+    );
+    i = group.start + group.length;
+  }
+  result.push(...listItems.slice(i));
+  return result;
+}
+
 export interface StackUpdatedDelegate {
   onStackUpdated(stackDiv: HTMLElement): Promise<void>;
 }
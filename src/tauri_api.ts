@@ -23,10 +23,22 @@ class TauriApi {
     return invoke('get_editable_stack_elem', { stackIndex });
   }
 
+  showFullStackElem(stackIndex: number): Promise<string> {
+    return invoke('show_full_stack_elem', { stackIndex });
+  }
+
+  exportStackElem(stackIndex: number, format: string): Promise<string> {
+    return invoke('export_stack_elem', { stackIndex, format });
+  }
+
   performUndoAction(direction: UndoDirection): Promise<void> {
     return invoke('perform_undo_action', { direction });
   }
 
+  cancelComputation(): Promise<void> {
+    return invoke('cancel_computation');
+  }
+
   validateStackSize(expected: number): Promise<boolean> {
     return invoke('validate_stack_size', { expected });
   }
@@ -39,15 +51,45 @@ class TauriApi {
     return invoke('query_stack', { query });
   }
 
+  openWindow(kind: WindowKind): Promise<string | null> {
+    return invoke('open_window', { kind });
+  }
+
+  getEngineStats(): Promise<EngineStats> {
+    return invoke('get_engine_stats');
+  }
+
+  simplifierProfile(): Promise<PassTiming[]> {
+    return invoke('simplifier_profile');
+  }
+
+  resetSimplifierProfile(): Promise<void> {
+    return invoke('reset_simplifier_profile');
+  }
+
+  renderStackDocument(): Promise<string> {
+    return invoke('render_stack_document');
+  }
+
+  listBookmarks(): Promise<BookmarkPreview[]> {
+    return invoke('list_bookmarks');
+  }
+
   showError(errorMessage: string): Promise<void> {
-    const payload: ShowErrorPayload = { errorMessage: "Error: " + errorMessage };
+    const payload: ShowErrorPayload = { errorMessage: "Error: " + errorMessage, count: 1 };
     return emit('show-error', payload);
   }
 
   listen(event: 'refresh-stack', callback: EventCallback<RefreshStackPayload>): Promise<UnlistenFn>;
   listen(event: 'refresh-undo-availability', callback: EventCallback<UndoAvailabilityPayload>): Promise<UnlistenFn>;
   listen(event: 'refresh-modeline', callback: EventCallback<ModelinePayload>): Promise<UnlistenFn>;
+  listen(event: 'refresh-theme-hints', callback: EventCallback<ThemeHintsPayload>): Promise<UnlistenFn>;
   listen(event: 'show-error', callback: EventCallback<ShowErrorPayload>): Promise<UnlistenFn>;
+  listen(event: 'program-step', callback: EventCallback<ProgramStepPayload>): Promise<UnlistenFn>;
+  listen(event: 'internal-error', callback: EventCallback<InternalErrorPayload>): Promise<UnlistenFn>;
+  listen(event: 'request-confirmation', callback: EventCallback<ConfirmationRequestPayload>): Promise<UnlistenFn>;
+  listen(event: 'timer-expired', callback: EventCallback<TimerExpiredPayload>): Promise<UnlistenFn>;
+  listen(event: 'computation-progress', callback: EventCallback<ComputationProgressPayload>): Promise<UnlistenFn>;
   /* eslint-disable-next-line @typescript-eslint/no-explicit-any */
   listen(event: string, callback: EventCallback<any>): Promise<UnlistenFn> {
     return listen(event, callback);
@@ -61,6 +103,12 @@ export enum UndoDirection {
   REDO = "redo",
 }
 
+export enum WindowKind {
+  MAIN = "main",
+  STACK = "stack",
+  GRAPH = "graph",
+}
+
 export enum Validator {
   VARIABLE = "variable",
   RADIX = "radix",
@@ -82,11 +130,28 @@ export interface CommandOptions {
   keepModifier: boolean,
   hyperbolicModifier: boolean,
   inverseModifier: boolean,
+  confirmed?: boolean,
 }
 
 export interface RefreshStackPayload {
   stack: string[];
+  labels: (string | null)[];
+  pinned: boolean[];
+  groups: StackGroup[];
+  spokenStack: string[] | null;
   forceScrollDown: boolean;
+  commandTiming: CommandTiming | null;
+}
+
+export interface CommandTiming {
+  commandName: string;
+  elapsedMillis: number;
+}
+
+export interface StackGroup {
+  title: string;
+  start: number;
+  length: number;
 }
 
 export interface UndoAvailabilityPayload {
@@ -96,10 +161,81 @@ export interface UndoAvailabilityPayload {
 
 export interface ModelinePayload {
   modelineText: string;
+  indicators: ModeIndicator[];
+}
+
+export interface ModeIndicator {
+  key: string | null;
+  value: string;
+  tooltip: string | null;
+  active: boolean;
+  toggleCommand: string | null;
+}
+
+export interface ThemeHintsPayload {
+  errorLevel: "ok" | "warning" | "error";
+  numericKind: "none" | "exact" | "float" | "mixed";
+  degreesMode: boolean;
+  precision: number | null;
+  activeModes: string[];
 }
 
 export interface ShowErrorPayload {
   errorMessage: string;
+  // The number of errors summarized by this message, including
+  // itself.
+  count: number;
+}
+
+export interface TimerExpiredPayload {
+  seconds: number;
+}
+
+export interface ComputationProgressPayload {
+  jobId: number;
+  commandName: string;
+  status: "running" | "finished" | "cancelled";
+  // The number of simplifications the job has performed so far.
+  steps: number;
+  elapsedMillis: number;
+}
+
+export interface ConfirmationRequestPayload {
+  commandName: string;
+  args: string[];
+  options: CommandOptions;
+  message: string;
+}
+
+export interface InternalErrorPayload {
+  commandName: string;
+  message: string;
+}
+
+export interface ProgramStepPayload {
+  upcomingCommand: string | null;
+  stepIndex: number;
+  stepCount: number;
+  stack: string[];
+}
+
+export interface EngineStats {
+  stackDepth: number;
+  nodeCount: number;
+  variableCount: number;
+  undoDepth: number;
+  approxMemoryBytes: number;
+}
+
+export interface PassTiming {
+  pass: string;
+  calls: number;
+  totalMicros: number;
+}
+
+export interface BookmarkPreview {
+  index: number;
+  previewHtml: string;
 }
 
 export interface StackQuery {
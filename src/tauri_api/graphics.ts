@@ -5,9 +5,17 @@ export type SerializedGraphicsPayload = string;
 
 export interface GraphicsResponse {
   directives: GraphicsDirective[];
+  xAxis: AxisType;
+  barMode: BarMode;
 }
 
-export type GraphicsDirective = PlotDirective | ContourPlotDirective;
+export type AxisType = "linear" | "date" | "category";
+
+export type BarMode = "group" | "relative";
+
+export type GraphicsDirective =
+  PlotDirective | ContourPlotDirective | TimePlotDirective | BarChartDirective | PieChartDirective |
+  ScatterPlotDirective;
 
 export interface PlotDirective {
   type: "plot";
@@ -21,6 +29,33 @@ export interface ContourPlotDirective {
   zValues: number[][];
 }
 
+// The x coordinates of a time plot are milliseconds since the Unix
+// epoch.
+export interface TimePlotDirective {
+  type: "timeplot";
+  points: Point2D[];
+}
+
+// Each series has one value per label.
+export interface BarChartDirective {
+  type: "barchart" | "stackedbar";
+  labels: string[];
+  series: number[][];
+}
+
+export interface PieChartDirective {
+  type: "piechart";
+  labels: string[];
+  values: number[];
+}
+
+// The regression curve, if present, is drawn through the points.
+export interface ScatterPlotDirective {
+  type: "scatterplot";
+  points: Point2D[];
+  regression: Point2D[] | null;
+}
+
 export interface Range<T> {
   start: T;
   end: T;
@@ -3,6 +3,7 @@ import { StackUpdatedDelegate } from './stack_view.js';
 import { DragTouchMode } from './touch_mode/drag.js';
 import { EditTouchMode } from './touch_mode/edit.js';
 import { ViewTouchMode } from './touch_mode/view.js';
+import { SelectTouchMode } from './touch_mode/select.js';
 import { UiManager } from './ui_manager.js';
 
 // Manager for the "Touch Mode" radiobuttons which control what
@@ -67,6 +68,7 @@ export const TouchModeFactories: Record<string, (ctx: TouchModeFactoryContext) =
   DRAG: (ctx) => new DragTouchMode(ctx),
   VIEW: (ctx) => new ViewTouchMode(ctx),
   EDIT: (ctx) => new EditTouchMode(ctx),
+  SELECT: (ctx) => new SelectTouchMode(ctx),
 };
 
 export interface TouchMode {
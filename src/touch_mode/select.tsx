@@ -0,0 +1,104 @@
+
+import { UiManager } from '../ui_manager.js';
+import { TouchModeFactoryContext } from '../touch_mode.js';
+import { ClickableTouchMode } from './clickable.js';
+import { TAURI, defaultCommandOptions } from '../tauri_api.js';
+import { jsx, Fragment } from '../jsx.js';
+
+const SELECTED_CLASS = "value-stack-element-selected";
+const BACK_BUTTON_ID = "range-button-bar-back-button";
+
+const RANGE_ACTIONS: readonly RangeAction[] = [
+  { label: "Delete", command: "delete_range" },
+  { label: "Duplicate", command: "duplicate_range" },
+  { label: "Rotate", command: "rotate_range" },
+  { label: "To Vector", command: "collect_range_to_vector" },
+];
+
+interface RangeAction {
+  label: string;
+  command: string;
+}
+
+// Touch mode for selecting a range of stack elements. The first
+// click marks one end of the range and the second click marks the
+// other, after which the user picks what to do with the range.
+export class SelectTouchMode extends ClickableTouchMode {
+  private uiManager: UiManager;
+  private valueStack: HTMLElement;
+  private anchorIndex: number | null = null;
+
+  constructor(context: TouchModeFactoryContext) {
+    super(context);
+    this.uiManager = context.uiManager;
+    this.valueStack = context.valueStackDiv;
+  }
+
+  initTouchMode(): void {
+    // Stack indices are invalidated whenever the stack changes.
+    this.anchorIndex = null;
+    super.initTouchMode();
+  }
+
+  onClick(elem: HTMLElement): void {
+    const index = Number(elem.dataset.stackIndex);
+    if (this.anchorIndex === null) {
+      this.anchorIndex = index;
+      this.highlightRange(index, index);
+      return;
+    }
+    const anchorIndex = this.anchorIndex;
+    this.anchorIndex = null;
+    this.highlightRange(anchorIndex, index);
+    this.showRangeActions(anchorIndex, index);
+  }
+
+  private highlightRange(a: number, b: number): void {
+    const [top, bottom] = [Math.min(a, b), Math.max(a, b)];
+    for (const elem of this.valueStack.querySelectorAll('li.value-stack-element')) {
+      const index = Number((elem as HTMLElement).dataset.stackIndex);
+      elem.classList.toggle(SELECTED_CLASS, top <= index && index <= bottom);
+    }
+  }
+
+  private clearHighlight(): void {
+    for (const elem of this.valueStack.querySelectorAll(`.${SELECTED_CLASS}`)) {
+      elem.classList.remove(SELECTED_CLASS);
+    }
+  }
+
+  private showRangeActions(a: number, b: number): void {
+    const [top, bottom] = [Math.min(a, b), Math.max(a, b)];
+    // Stack indices are zero-based, but the user sees them starting
+    // at one.
+    const description = (top === bottom) ?
+      `Stack element ${top + 1}` :
+      `Stack elements ${top + 1} through ${bottom + 1}`;
+    const html = <>
+      <header>
+        <div class="viewable-button-bar">
+          <button id={BACK_BUTTON_ID}>Back</button>
+          {RANGE_ACTIONS.map((action) => <button data-range-command={action.command}>{action.label}</button>)}
+        </div>
+      </header>
+      <main class="viewable-display-main">
+        <span class="viewable-display-content-area">{description}</span>
+      </main>
+    </>;
+    this.uiManager.showPopup(html, `#${BACK_BUTTON_ID}`);
+
+    const backButton = document.getElementById(BACK_BUTTON_ID);
+    backButton?.addEventListener('click', () => this.clearHighlight());
+    for (const button of document.querySelectorAll('button[data-range-command]')) {
+      const command = (button as HTMLElement).dataset.rangeCommand;
+      button.addEventListener('click', () => {
+        // Close the popup first, so the stack view is back in place
+        // when the results come in.
+        backButton?.click();
+        if (command) {
+          TAURI.runMathCommand(command, [String(top), String(bottom)], defaultCommandOptions());
+        }
+      });
+    }
+  }
+}